@@ -1,7 +1,7 @@
 //! PPTX CLI - Command-line tool for creating PowerPoint presentations
 
 use clap::Parser;
-use ppt_rs::cli::{Cli, Commands, CreateCommand, FromMarkdownCommand, InfoCommand, ValidateCommand};
+use ppt_rs::cli::{Cli, Commands, CreateCommand, FromMarkdownCommand, InfoCommand, ToMarkdownCommand, ValidateCommand};
 
 fn main() {
     let cli = Cli::parse();
@@ -26,7 +26,7 @@ fn main() {
                 }
             }
         }
-        Commands::Md2Ppt { input, output, title } => {
+        Commands::Md2Ppt { input, output, title, strict } => {
             // Auto-generate output if not provided
             let output_path = output.unwrap_or_else(|| {
                 use std::path::Path;
@@ -50,12 +50,31 @@ fn main() {
                 &input,
                 &output_path,
                 title.as_deref(),
+                strict,
             ) {
                 Ok(_) => {
                     println!("✓ Created presentation: {output_path}");
                     println!("  Input: {input}");
-                    let title = title.as_deref().unwrap_or("Presentation from Markdown");
-                    println!("  Title: {title}");
+                    if let Some(title) = title {
+                        println!("  Title: {title}");
+                    }
+                }
+                Err(e) => {
+                    eprintln!("✗ Error: {e}");
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::Ppt2Md { input, output, images } => {
+            let output_path = output.unwrap_or_else(|| {
+                std::path::Path::new(&input).with_extension("md").to_string_lossy().into_owned()
+            });
+
+            match ToMarkdownCommand::execute(&input, &output_path, images.as_deref()) {
+                Ok(slides) => {
+                    println!("✓ Created Markdown: {output_path}");
+                    println!("  Input: {input}");
+                    println!("  Slides: {slides}");
                 }
                 Err(e) => {
                     eprintln!("✗ Error: {e}");
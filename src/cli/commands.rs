@@ -1,11 +1,12 @@
 //! CLI commands implementation
 
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use crate::generator;
 
 pub struct CreateCommand;
 pub struct FromMarkdownCommand;
+pub struct ToMarkdownCommand;
 pub struct InfoCommand;
 pub struct ValidateCommand;
 
@@ -39,22 +40,48 @@ impl CreateCommand {
 }
 
 impl FromMarkdownCommand {
+    /// Convert `input` to `output`; with `strict`, Mermaid diagram problems
+    /// are errors instead of warnings
     pub fn execute(
         input: &str,
         output: &str,
         title: Option<&str>,
+        strict: bool,
     ) -> Result<(), String> {
         // Read markdown file
         let md_content = fs::read_to_string(input)
             .map_err(|e| format!("Failed to read markdown file: {e}"))?;
 
-        // Parse markdown into slides using enhanced parser
-        let slides = super::markdown::parse_markdown(&md_content)?;
+        // Parse markdown into slides, with the front matter's deck settings;
+        // image paths are relative to the Markdown file
+        let base_dir = Path::new(input).parent().unwrap_or(Path::new(""));
+        let document = super::markdown::parse_document_in(&md_content, base_dir)?;
+        let slides = document.slides;
+        let diagnostics: Vec<String> = document.diagnostics
+            .iter()
+            .map(|d| format!("{input}:{}:{}: {}", d.line, d.column, d.message))
+            .collect();
+        if strict && !diagnostics.is_empty() {
+            return Err(format!("Mermaid diagram problems (--strict):\n  {}", diagnostics.join("\n  ")));
+        }
+        for warning in &diagnostics {
+            eprintln!("⚠ Warning: {warning}");
+        }
+        for (line, message) in &document.warnings {
+            eprintln!("⚠ Warning: {input}:{line}: {message}");
+        }
 
         if slides.is_empty() {
             return Err("No slides found in markdown file".to_string());
         }
 
+        // Move overflowing bullets and table rows onto continuation slides
+        let measurer = generator::TextMeasurer::new();
+        let slides = generator::paginate_slides(slides, &measurer);
+        for warning in generator::check_overflow(&slides, &measurer) {
+            eprintln!("⚠ Warning: {warning}");
+        }
+
         // Create output directory if needed
         if let Some(parent) = PathBuf::from(output).parent() {
             if !parent.as_os_str().is_empty() {
@@ -63,10 +90,14 @@ impl FromMarkdownCommand {
             }
         }
 
-        let title = title.unwrap_or("Presentation from Markdown");
+        // The --title flag wins over the front matter
+        let title = title
+            .or(document.front_matter.title.as_deref())
+            .unwrap_or("Presentation from Markdown");
+        let options = document.front_matter.presentation_options();
 
         // Generate PPTX with content
-        let pptx_data = generator::create_pptx_with_content(title, slides)
+        let pptx_data = generator::create_pptx_with_options(title, slides, &options)
             .map_err(|e| format!("Failed to generate PPTX: {e}"))?;
 
         // Write to file
@@ -77,6 +108,40 @@ impl FromMarkdownCommand {
     }
 }
 
+impl ToMarkdownCommand {
+    /// Export `input` to the Markdown file `output`, writing its pictures to
+    /// `images_dir` (relative to `output`, default `OUTPUT_images`); returns
+    /// the number of slides
+    pub fn execute(input: &str, output: &str, images_dir: Option<&str>) -> Result<usize, String> {
+        let reader = crate::oxml::PresentationReader::open(input)
+            .map_err(|e| format!("Failed to read PPTX file: {e}"))?;
+        let default_dir = Path::new(output).file_stem()
+            .map(|stem| format!("{}_images", stem.to_string_lossy()))
+            .unwrap_or_else(|| "images".to_string());
+        let export = super::markdown::export_markdown(&reader, images_dir.unwrap_or(&default_dir))
+            .map_err(|e| format!("Failed to export slides: {e}"))?;
+
+        let base_dir = Path::new(output).parent().unwrap_or(Path::new(""));
+        for (link, data) in &export.images {
+            let path = base_dir.join(link);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|e| format!("Failed to create directory: {e}"))?;
+            }
+            fs::write(&path, data)
+                .map_err(|e| format!("Failed to write image {}: {e}", path.display()))?;
+        }
+        if !base_dir.as_os_str().is_empty() {
+            fs::create_dir_all(base_dir)
+                .map_err(|e| format!("Failed to create directory: {e}"))?;
+        }
+        fs::write(output, &export.markdown)
+            .map_err(|e| format!("Failed to write file: {e}"))?;
+
+        Ok(reader.slide_count())
+    }
+}
+
 
 impl InfoCommand {
     pub fn execute(file: &str) -> Result<(), String> {
@@ -0,0 +1,470 @@
+//! Front matter and slide directives
+//!
+//! Deck settings come from YAML (`---`) or TOML (`+++`) front matter at the
+//! top of the file; only flat `key: value` / `key = value` pairs are read.
+//! HTML comments such as `<!-- layout: two-column -->` change the slide they
+//! appear in. `:::columns` / `:::column` containers and `<!-- split -->`
+//! comments split a slide into two columns.
+
+use crate::generator::{Background, HeaderFooter, PresentationOptions, SlideContent, SlideLayout};
+use crate::parts::animation::{SlideTransition, TransitionEffect};
+use crate::prelude::themes::{self, Theme};
+
+/// A problem in the front matter or a slide directive, with its 1-based line
+pub type DirectiveWarning = (usize, String);
+
+/// Deck-level settings from the front matter
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FrontMatter {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    /// Name of a `prelude::themes` preset
+    pub theme: Option<String>,
+    /// `16:9`, `16:10` or `4:3`
+    pub aspect: Option<String>,
+    pub footer: Option<String>,
+    pub slide_numbers: Option<bool>,
+    /// Transition for every slide (`fade`, `push`, ...)
+    pub transition: Option<String>,
+    /// Code block highlighting theme: a built-in name or a `.tmTheme` file
+    pub code_theme: Option<String>,
+    /// `.pptx` or `.potx` file whose slide masters and layouts the deck uses
+    pub template: Option<String>,
+}
+
+impl FrontMatter {
+    /// Theme preset named by `theme`
+    pub fn theme(&self) -> Option<Theme> {
+        self.theme.as_deref().and_then(theme_named)
+    }
+
+    /// Presentation options for the deck settings; invalid values are skipped
+    pub fn presentation_options(&self) -> PresentationOptions {
+        let mut options = self.theme().map_or_else(PresentationOptions::new, |theme| PresentationOptions::from_theme(&theme));
+        if let Some(author) = &self.author {
+            options = options.author(author);
+        }
+        if let Some((width, height)) = self.aspect.as_deref().and_then(slide_size) {
+            options = options.slide_size(width, height);
+        }
+        if let Some(transition) = self.transition.as_deref().and_then(transition_named) {
+            options = options.transition(transition);
+        }
+        if let Some(data) = self.template.as_ref().and_then(|path| std::fs::read(path).ok()) {
+            options = options.template(data);
+        }
+        let slide_numbers = self.slide_numbers == Some(true);
+        if self.footer.is_some() || slide_numbers {
+            let mut header_footer = HeaderFooter::new();
+            if let Some(footer) = &self.footer {
+                header_footer = header_footer.footer(footer);
+            }
+            if slide_numbers {
+                header_footer = header_footer.slide_numbers();
+            }
+            options = options.header_footer(header_footer);
+        }
+        options
+    }
+
+    /// Read one `key`/`value` pair, returning a warning for bad ones
+    fn set(&mut self, key: &str, value: &str) -> Option<String> {
+        let mut warning = None;
+        match key.to_lowercase().replace('-', "_").as_str() {
+            "title" => self.title = Some(value.to_string()),
+            "author" => self.author = Some(value.to_string()),
+            "footer" => self.footer = Some(value.to_string()),
+            "theme" => {
+                if theme_named(value).is_none() {
+                    let names: Vec<&str> = themes::all().iter().map(|t| t.name).collect();
+                    warning = Some(format!("unknown theme `{value}`, expected one of {}", names.join(", ")));
+                }
+                self.theme = Some(value.to_string());
+            }
+            "aspect" => {
+                if slide_size(value).is_none() {
+                    warning = Some(format!("unknown aspect `{value}`, expected 16:9, 16:10 or 4:3"));
+                }
+                self.aspect = Some(value.to_string());
+            }
+            "slide_numbers" => match value.to_lowercase().as_str() {
+                "true" | "yes" | "on" => self.slide_numbers = Some(true),
+                "false" | "no" | "off" => self.slide_numbers = Some(false),
+                _ => warning = Some(format!("`slide_numbers` needs true or false, found `{value}`")),
+            },
+            "transition" => {
+                if transition_named(value).is_none() {
+                    warning = Some(format!("unknown transition `{value}`"));
+                }
+                self.transition = Some(value.to_string());
+            }
+            "code_theme" => {
+                // `.tmTheme` files are loaded relative to the Markdown file later
+                if !value.to_lowercase().ends_with(".tmtheme")
+                    && let Err(e) = crate::cli::syntax::load_theme(value)
+                {
+                    warning = Some(e.to_string());
+                }
+                self.code_theme = Some(value.to_string());
+            }
+            // Read relative to the Markdown file later
+            "template" => self.template = Some(value.to_string()),
+            _ => warning = Some(format!("unknown front matter key `{key}`")),
+        }
+        warning
+    }
+}
+
+/// Split the front matter off `content`
+///
+/// Returns the settings, their problems, the body and the number of lines
+/// before the body. Without a closed front matter block the body is `content`.
+pub fn split_front_matter(content: &str) -> (FrontMatter, Vec<DirectiveWarning>, &str, usize) {
+    let mut lines = content.split_inclusive('\n');
+    let first = lines.next().unwrap_or("");
+    let fence = match first.trim_end() {
+        fence @ ("---" | "+++") => fence,
+        _ => return (FrontMatter::default(), Vec::new(), content, 0),
+    };
+    let toml = fence == "+++";
+    let mut consumed = first.len();
+    let mut front_matter = FrontMatter::default();
+    let mut warnings = Vec::new();
+
+    for (index, line) in lines.enumerate() {
+        let line_number = index + 2;
+        consumed += line.len();
+        let trimmed = line.trim();
+        if trimmed == fence || (!toml && trimmed == "...") {
+            return (front_matter, warnings, &content[consumed..], line_number);
+        }
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        let separator = if toml { '=' } else { ':' };
+        let pair = line.split_once(separator).map(|(key, value)| (key.trim(), scalar(value)));
+        match pair {
+            _ if line.starts_with(char::is_whitespace) || trimmed.starts_with('-') || trimmed.starts_with('[') => {
+                warnings.push((line_number, format!("nested values are not supported: `{trimmed}`")));
+            }
+            Some((key, value)) if !key.is_empty() && !value.is_empty() => {
+                if let Some(warning) = front_matter.set(key.trim_matches('"'), value) {
+                    warnings.push((line_number, warning));
+                }
+            }
+            _ => warnings.push((line_number, format!("expected `key{}value`, found `{trimmed}`", if toml { " = " } else { ": " }))),
+        }
+    }
+
+    // Never closed: not front matter after all
+    (FrontMatter::default(), Vec::new(), content, 0)
+}
+
+/// Value of a pair: a quoted string, or the text before a `#` comment
+fn scalar(value: &str) -> &str {
+    let value = value.trim();
+    for quote in ['"', '\''] {
+        if let Some(quoted) = value.strip_prefix(quote) {
+            return quoted.split_once(quote).map_or(quoted, |(inner, _)| inner);
+        }
+    }
+    value.split_once(" #").map_or(value, |(value, _)| value).trim()
+}
+
+/// A slide setting from an HTML comment
+#[derive(Debug, Clone)]
+pub enum SlideDirective {
+    Layout(SlideLayout),
+    Background(Background),
+    Transition(SlideTransition),
+}
+
+impl SlideDirective {
+    pub fn apply(self, slide: &mut SlideContent) {
+        match self {
+            SlideDirective::Layout(layout) => slide.layout = layout,
+            SlideDirective::Background(background) => slide.background = Some(background),
+            SlideDirective::Transition(transition) => slide.transition = Some(transition),
+        }
+    }
+}
+
+/// Directives in the `<!-- key: value -->` comments of `html`, which starts
+/// on line `line`; other comments are ignored
+pub fn parse_slide_directives(html: &str, line: usize) -> (Vec<SlideDirective>, Vec<DirectiveWarning>) {
+    let mut directives = Vec::new();
+    let mut warnings = Vec::new();
+    let mut rest = html;
+    let mut line = line;
+
+    while let Some(start) = rest.find("<!--") {
+        line += rest[..start].matches('\n').count();
+        let comment = &rest[start + 4..];
+        let (inner, after) = comment.split_once("-->").unwrap_or((comment, ""));
+        for (offset, text) in inner.lines().enumerate() {
+            let Some((key, value)) = text.split_once(':') else { continue };
+            let value = value.trim();
+            let directive = match key.trim().to_lowercase().as_str() {
+                "layout" => layout_named(value)
+                    .map(SlideDirective::Layout)
+                    .ok_or_else(|| format!("unknown layout `{value}`")),
+                "background" => hex_color(value)
+                    .map(|color| SlideDirective::Background(Background::solid(&color)))
+                    .ok_or_else(|| format!("background needs a hex colour like `#1E1E1E`, found `{value}`")),
+                "transition" => transition_named(value)
+                    .map(SlideDirective::Transition)
+                    .ok_or_else(|| format!("unknown transition `{value}`")),
+                _ => continue,
+            };
+            match directive {
+                Ok(directive) => directives.push(directive),
+                Err(warning) => warnings.push((line + offset, warning)),
+            }
+        }
+        line += inner.matches('\n').count();
+        rest = after;
+    }
+    (directives, warnings)
+}
+
+/// A column boundary on a slide
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnMarker {
+    /// `:::columns` opens a column container
+    Columns,
+    /// `:::column` opens a column
+    Column,
+    /// `:::` closes the open column or container
+    End,
+    /// `<!-- split -->` starts the right column
+    Split,
+}
+
+/// Column marker of an HTML block holding a single comment: `<!-- split -->`
+/// or a `:::` fence rewritten by [`container_fences_to_comments`]
+pub fn column_marker(html: &str) -> Option<ColumnMarker> {
+    let inner = html.trim().strip_prefix("<!--")?.strip_suffix("-->")?.trim();
+    if inner.eq_ignore_ascii_case("split") {
+        return Some(ColumnMarker::Split);
+    }
+    // Pandoc writes the class in braces: `::: {.columns}`
+    let name = inner.strip_prefix(":::")?.trim().trim_start_matches('{').trim_end_matches('}');
+    match normalize(name.trim_start_matches('.')).as_str() {
+        "columns" => Some(ColumnMarker::Columns),
+        "column" => Some(ColumnMarker::Column),
+        "" => Some(ColumnMarker::End),
+        _ => None,
+    }
+}
+
+/// Turn the `:::columns`, `:::column` and `:::` fence lines outside code
+/// blocks into one-line HTML comments, which end paragraphs and lists the way
+/// the fences should; line numbers are kept
+pub fn container_fences_to_comments(body: &str) -> String {
+    let mut out = String::with_capacity(body.len());
+    let mut fences = CodeFences::default();
+    for line in body.split_inclusive('\n') {
+        let text = line.trim_end();
+        let trimmed = text.trim_start();
+        if !fences.in_code(text) {
+            let comment = format!("<!-- {trimmed} -->");
+            if trimmed.starts_with(":::") && column_marker(&comment).is_some() {
+                out.push_str(&comment);
+                out.push_str(&line[text.len()..]);
+                continue;
+            }
+        }
+        out.push_str(line);
+    }
+    out
+}
+
+/// Fenced code blocks seen line by line, for rewrites that leave code alone
+#[derive(Debug, Default)]
+pub struct CodeFences {
+    open: Option<(char, usize)>,
+}
+
+impl CodeFences {
+    /// Whether `line` is a code fence or inside a fenced code block
+    pub fn in_code(&mut self, line: &str) -> bool {
+        let trimmed = line.trim();
+        let fence = ['`', '~'].into_iter().find_map(|c| {
+            let len = trimmed.chars().take_while(|&t| t == c).count();
+            (len >= 3).then_some((c, len))
+        });
+        match (self.open, fence) {
+            (None, Some(open)) => self.open = Some(open),
+            (Some((c, len)), Some((close, close_len)))
+                if c == close && close_len >= len && trimmed.trim_start_matches(c).is_empty() =>
+            {
+                self.open = None
+            }
+            (None, None) => return false,
+            _ => {}
+        }
+        true
+    }
+}
+
+/// Theme preset by name, ignoring case
+fn theme_named(name: &str) -> Option<Theme> {
+    themes::all().into_iter().find(|theme| theme.name.eq_ignore_ascii_case(name.trim()))
+}
+
+/// Slide width and height in EMU for an aspect ratio
+fn slide_size(aspect: &str) -> Option<(u32, u32)> {
+    match aspect.replace(' ', "").to_lowercase().as_str() {
+        "16:9" | "widescreen" => Some((12_192_000, 6_858_000)),
+        "16:10" => Some((10_972_800, 6_858_000)),
+        "4:3" | "standard" => Some((9_144_000, 6_858_000)),
+        _ => None,
+    }
+}
+
+/// Normalized name: lowercase without `-`, `_` and spaces
+fn normalize(name: &str) -> String {
+    name.chars().filter(|c| !matches!(c, '-' | '_' | ' ')).collect::<String>().to_lowercase()
+}
+
+/// Slide layout by name (`two-column`, `title-only`, `blank`, ...)
+fn layout_named(name: &str) -> Option<SlideLayout> {
+    match normalize(name).as_str() {
+        "titleandcontent" | "content" => Some(SlideLayout::TitleAndContent),
+        "titleonly" => Some(SlideLayout::TitleOnly),
+        "titleandbigcontent" | "bigcontent" => Some(SlideLayout::TitleAndBigContent),
+        "blank" => Some(SlideLayout::Blank),
+        "centeredtitle" | "title" => Some(SlideLayout::CenteredTitle),
+        "twocolumn" | "twocolumns" => Some(SlideLayout::TwoColumn),
+        "comparison" => Some(SlideLayout::Comparison),
+        _ => None,
+    }
+}
+
+/// Transition by effect name (`fade`, `push`, `random-bars`, ...)
+fn transition_named(name: &str) -> Option<SlideTransition> {
+    let effect = match normalize(name).as_str() {
+        "none" => TransitionEffect::None,
+        "fade" => TransitionEffect::Fade,
+        "push" => TransitionEffect::Push,
+        "wipe" => TransitionEffect::Wipe,
+        "split" => TransitionEffect::Split,
+        "reveal" => TransitionEffect::Reveal,
+        "randombars" => TransitionEffect::RandomBars,
+        "shape" | "circle" => TransitionEffect::Shape,
+        "uncover" => TransitionEffect::Uncover,
+        "cover" => TransitionEffect::Cover,
+        "flash" => TransitionEffect::Flash,
+        "strips" => TransitionEffect::Strips,
+        "blinds" => TransitionEffect::Blinds,
+        "clock" => TransitionEffect::Clock,
+        "ripple" => TransitionEffect::Ripple,
+        "honeycomb" => TransitionEffect::Honeycomb,
+        "glitter" => TransitionEffect::Glitter,
+        "vortex" => TransitionEffect::Vortex,
+        "shred" => TransitionEffect::Shred,
+        "switch" => TransitionEffect::Switch,
+        "flip" => TransitionEffect::Flip,
+        "gallery" => TransitionEffect::Gallery,
+        "cube" => TransitionEffect::Cube,
+        "doors" => TransitionEffect::Doors,
+        "box" => TransitionEffect::Box,
+        "zoom" => TransitionEffect::Zoom,
+        "random" => TransitionEffect::Random,
+        _ => return None,
+    };
+    Some(SlideTransition::new(effect))
+}
+
+/// `#RGB` or `#RRGGBB` (the `#` is optional) as `RRGGBB`
+fn hex_color(value: &str) -> Option<String> {
+    let hex = value.trim().trim_start_matches('#');
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    match hex.len() {
+        3 => Some(hex.chars().flat_map(|c| [c, c]).collect()),
+        6 => Some(hex.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_yaml_front_matter() {
+        let md = "---\ntitle: \"Q3 Review\"\nauthor: Jane Doe # presenter\ntheme: dark\naspect: 16:9\nslide_numbers: true\n---\n# First";
+        let (front_matter, warnings, body, lines) = split_front_matter(md);
+        assert!(warnings.is_empty());
+        assert_eq!(front_matter.title.as_deref(), Some("Q3 Review"));
+        assert_eq!(front_matter.author.as_deref(), Some("Jane Doe"));
+        assert_eq!(body, "# First");
+        assert_eq!(lines, 7);
+
+        let options = front_matter.presentation_options();
+        assert_eq!(options.slide_size, Some((12_192_000, 6_858_000)));
+        assert_eq!(options.author.as_deref(), Some("Jane Doe"));
+        assert!(options.header_footer.is_some());
+        assert!(options.background.is_some());
+    }
+
+    #[test]
+    fn test_toml_front_matter() {
+        let md = "+++\ntitle = 'Deck'\ntransition = \"push\"\nfooter = \"ACME\"\n+++\n# One";
+        let (front_matter, warnings, body, _) = split_front_matter(md);
+        assert!(warnings.is_empty());
+        assert_eq!(front_matter.title.as_deref(), Some("Deck"));
+        assert_eq!(front_matter.footer.as_deref(), Some("ACME"));
+        assert_eq!(body, "# One");
+        assert_eq!(front_matter.presentation_options().transition.unwrap().effect, TransitionEffect::Push);
+    }
+
+    #[test]
+    fn test_front_matter_warnings() {
+        let md = "---\ntheme: neon\ncolour: red\nslide_numbers: maybe\ntags:\n  - a\ntemplate: corp.pptx\ncode_theme: vivid\n---\n# One";
+        let (front_matter, warnings, _, _) = split_front_matter(md);
+        let lines: Vec<usize> = warnings.iter().map(|(line, _)| *line).collect();
+        assert_eq!(lines, [2, 3, 4, 5, 6, 8]);
+        assert!(warnings[0].1.contains("Corporate"));
+        assert!(warnings[5].1.contains("InspiredGitHub"));
+        assert!(front_matter.theme().is_none());
+        assert_eq!(front_matter.template.as_deref(), Some("corp.pptx"));
+
+        // An unclosed block is ordinary Markdown
+        let (_, warnings, body, lines) = split_front_matter("---\ntitle: x\n# One");
+        assert!(warnings.is_empty());
+        assert_eq!((body, lines), ("---\ntitle: x\n# One", 0));
+    }
+
+    #[test]
+    fn test_slide_directives() {
+        let html = "<!-- layout: two-column -->\n<!-- a plain comment -->\n<!--\nbackground: #000\ntransition: spin\n-->";
+        let (directives, warnings) = parse_slide_directives(html, 10);
+        assert_eq!(directives.len(), 2);
+        assert_eq!(warnings, [(14, "unknown transition `spin`".to_string())]);
+
+        let mut slide = SlideContent::new("Slide");
+        for directive in directives {
+            directive.apply(&mut slide);
+        }
+        assert_eq!(slide.layout, SlideLayout::TwoColumn);
+        assert!(matches!(slide.background, Some(Background::Solid(ref color)) if color == "000000"));
+    }
+
+    #[test]
+    fn test_column_markers() {
+        let md = ":::columns\n::: column\nLeft\n:::\n::: {.column}\n```\n:::\n```\n:::\n:::\n:::note\n";
+        let body = container_fences_to_comments(md);
+        assert_eq!(body.lines().count(), md.lines().count());
+        let markers: Vec<_> = body.lines().filter_map(column_marker).collect();
+        use ColumnMarker::*;
+        assert_eq!(markers, [Columns, Column, End, Column, End, End]);
+        assert!(body.contains("```\n:::\n```"));
+        assert!(body.ends_with(":::note\n"));
+        assert_eq!(column_marker("<!-- Split -->"), Some(Split));
+        assert_eq!(column_marker("<!-- layout: comparison -->"), None);
+    }
+}
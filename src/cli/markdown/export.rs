@@ -0,0 +1,412 @@
+//! PowerPoint to Markdown export
+//!
+//! Writes the Markdown that md2ppt reads: one `# title` section per slide with
+//! its bullets, columns, tables, code, equations, pictures and speaker notes, so decks
+//! made from Markdown come back close to their source.
+
+use crate::exc::PptxError;
+use crate::generator::constants::{SLIDE_HEIGHT, SLIDE_WIDTH};
+use crate::generator::omml::MATH_FONT;
+use crate::oxml::{Paragraph, ParsedEquation, ParsedNotes, ParsedPicture, ParsedShape, ParsedSlide, ParsedTable, PresentationInfo, PresentationReader, ShapeRole, TextRun};
+
+/// Typefaces that mark text as code
+const MONOSPACE_FONTS: &[&str] = &[
+    "consolas", "courier", "courier new", "menlo", "monaco", "lucida console",
+    "cascadia code", "cascadia mono", "fira code", "jetbrains mono", "source code pro",
+    "dejavu sans mono", "roboto mono", "sf mono",
+];
+
+/// A presentation exported to Markdown
+#[derive(Debug, Clone, Default)]
+pub struct MarkdownExport {
+    pub markdown: String,
+    /// Pictures the Markdown links to, by path relative to the Markdown file
+    pub images: Vec<(String, Vec<u8>)>,
+}
+
+/// Export a presentation to Markdown, linking its pictures under `image_dir`
+/// (relative to the Markdown file)
+pub fn export_markdown(reader: &PresentationReader, image_dir: &str) -> Result<MarkdownExport, PptxError> {
+    let mut export = MarkdownExport { markdown: front_matter(reader.info()), images: Vec::new() };
+    let size = reader.info().slide_size.unwrap_or((SLIDE_WIDTH, SLIDE_HEIGHT));
+
+    for index in 0..reader.slide_count() {
+        let slide = reader.get_slide(index)?;
+        let mut links = Vec::new();
+        for picture in &slide.pictures {
+            let Some(path) = picture.target.as_deref() else { continue };
+            let name = path.rsplit('/').next().unwrap_or(path);
+            let link = match image_dir.trim_end_matches('/') {
+                "" => name.to_string(),
+                dir => format!("{dir}/{name}"),
+            };
+            if !export.images.iter().any(|(existing, _)| *existing == link)
+                && let Some(data) = reader.part_data(path)
+            {
+                export.images.push((link.clone(), data.to_vec()));
+            }
+            links.push((picture, link));
+        }
+
+        if !export.markdown.is_empty() {
+            export.markdown.push('\n');
+        }
+        let notes = reader.notes(index)?;
+        export.markdown.push_str(&slide_markdown(index + 1, &slide, &links, notes.as_ref(), size));
+    }
+    Ok(export)
+}
+
+/// `title` and `author` front matter, if the deck has them
+fn front_matter(info: &PresentationInfo) -> String {
+    let lines: String = [("title", &info.title), ("author", &info.creator)]
+        .into_iter()
+        .filter_map(|(key, value)| value.as_deref().map(|value| format!("{key}: {}\n", quote(value))))
+        .collect();
+    if lines.is_empty() { lines } else { format!("---\n{lines}---\n") }
+}
+
+/// Quoted front matter value
+fn quote(value: &str) -> String {
+    if value.contains('"') { format!("'{value}'") } else { format!("\"{value}\"") }
+}
+
+/// Markdown section of one slide, numbered from 1, on a `size` slide
+fn slide_markdown(number: usize, slide: &ParsedSlide, pictures: &[(&ParsedPicture, String)], notes: Option<&ParsedNotes>, size: (u32, u32)) -> String {
+    let title = slide.title.as_deref()
+        .map(|title| title.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|title| !title.is_empty())
+        .unwrap_or_else(|| format!("Slide {number}"));
+    let mut blocks = Vec::new();
+
+    let bodies: Vec<usize> = (0..slide.shapes.len())
+        .filter(|&i| slide.shapes[i].role == ShapeRole::Body && !is_code(&slide.shapes[i]))
+        .collect();
+    let mut headings = Vec::new();
+    match bodies[..] {
+        [a, b] if side_by_side(&slide.shapes[a], &slide.shapes[b]) => {
+            let (left, right) = if slide.shapes[a].x <= slide.shapes[b].x { (a, b) } else { (b, a) };
+            let mut columns = String::from(":::columns\n");
+            for column in [&slide.shapes[left], &slide.shapes[right]] {
+                columns.push_str(":::column\n");
+                if let Some(heading) = (0..slide.shapes.len()).find(|&i| is_heading_of(&slide.shapes[i], column)) {
+                    // Headings are bold already
+                    let mut paragraph = slide.shapes[heading].paragraphs[0].clone();
+                    paragraph.runs.iter_mut().for_each(|run| run.bold = false);
+                    columns.push_str(&format!("## {}\n\n", paragraph_markdown(&paragraph)));
+                    headings.push(heading);
+                }
+                columns.push_str(&bullets(column));
+                columns.push_str(":::\n");
+            }
+            columns.push_str(":::");
+            blocks.push(columns);
+        }
+        _ => blocks.extend(bodies.iter().map(|&i| bullets(&slide.shapes[i])).filter(|b| !b.is_empty())),
+    }
+
+    // Other text boxes become paragraphs
+    for (i, shape) in slide.shapes.iter().enumerate() {
+        if shape.role == ShapeRole::Other && shape.text_box && !is_code(shape) && !headings.contains(&i) {
+            blocks.extend(shape.paragraphs.iter().map(paragraph_markdown).filter(|p| !p.is_empty()));
+        }
+    }
+    blocks.extend(slide.equations.iter().map(equation_markdown));
+    blocks.extend(slide.tables.iter().map(table_markdown));
+    blocks.extend(slide.shapes.iter().filter(|shape| shape.role != ShapeRole::Title && is_code(shape)).map(code_markdown));
+    blocks.extend(pictures.iter().map(|(picture, link)| picture_markdown(picture, link, size)));
+    if let Some(notes) = notes.filter(|notes| !notes.is_empty()) {
+        let quoted: Vec<String> = notes.paragraphs.iter()
+            .map(|p| format!("> {}", paragraph_markdown(p)).trim_end().to_string())
+            .collect();
+        blocks.push(quoted.join("\n"));
+    }
+
+    let mut markdown = format!("# {}\n", escape(&title));
+    for block in blocks {
+        markdown.push('\n');
+        markdown.push_str(block.trim_end());
+        markdown.push('\n');
+    }
+    markdown
+}
+
+/// Fenced code block, with the info string md2ppt keeps in the shape name and
+/// without the line numbers it adds
+fn code_markdown(shape: &ParsedShape) -> String {
+    let info = shape.name.strip_prefix("Code Block: ").unwrap_or_default();
+    let code: Vec<String> = shape.paragraphs.iter()
+        .map(|p| {
+            let numbered = p.runs.first().is_some_and(|run| run.no_proof && run.text.trim().parse::<usize>().is_ok());
+            let runs = if numbered { &p.runs[1..] } else { &p.runs[..] };
+            runs.iter().map(|run| run.text.as_str()).collect::<String>().trim_end().to_string()
+        })
+        .collect();
+    let fence = if code.iter().any(|line| line.contains("```")) { "````" } else { "```" };
+    format!("{fence}{info}\n{}\n{fence}", code.join("\n"))
+}
+
+/// `$$...$$` display or `$...$` inline math; equations without their TeX
+/// become a comment with their linear text
+fn equation_markdown(equation: &ParsedEquation) -> String {
+    match equation.latex.as_deref() {
+        Some(latex) if equation.display => format!("$$\n{}\n$$", latex.trim()),
+        Some(latex) => format!("${}$", latex.trim()),
+        None => format!("<!-- equation: {} -->", equation.text.replace("--", "- -").replace('\n', " ")),
+    }
+}
+
+/// Bullet list of a body shape, indented by paragraph level
+fn bullets(shape: &ParsedShape) -> String {
+    let mut markdown = String::new();
+    for p in &shape.paragraphs {
+        match display_math(p) {
+            Some(tex) => {
+                // Display equations are blocks of their own between the bullets
+                if !markdown.is_empty() && !markdown.ends_with("\n\n") {
+                    markdown.push('\n');
+                }
+                markdown.push_str(&format!("$$\n{tex}\n$$\n\n"));
+            }
+            None => markdown.push_str(&format!("{}- {}\n", "  ".repeat(p.level as usize), paragraph_markdown(p))),
+        }
+    }
+    markdown
+}
+
+/// TeX of a paragraph that is a display equation, from the fallback md2ppt writes
+fn display_math(paragraph: &Paragraph) -> Option<&str> {
+    let [run] = &paragraph.runs[..] else {
+        return None;
+    };
+    let tex = run.text.trim().strip_prefix("$$")?.strip_suffix("$$")?;
+    is_math(run).then_some(tex)
+}
+
+/// GFM table with inline formatting in its cells; the first row is the header,
+/// and the first body row sets the column alignments
+fn table_markdown(table: &ParsedTable) -> String {
+    let columns = table.rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    let row_markdown = |cells: Vec<String>| format!("| {} |", cells.join(" | "));
+    let mut lines: Vec<String> = table.rows.iter().enumerate()
+        .map(|(i, row)| {
+            let mut cells: Vec<String> = row.iter()
+                .map(|cell| {
+                    let lines: Vec<String> = cell.paragraphs.iter()
+                        .map(|paragraph| {
+                            // Headers are bold already
+                            let mut paragraph = paragraph.clone();
+                            paragraph.runs.iter_mut().for_each(|run| run.bold &= i > 0);
+                            paragraph_markdown(&paragraph)
+                        })
+                        .collect();
+                    lines.join("<br>").replace('|', "\\|")
+                })
+                .collect();
+            cells.resize(columns, String::new());
+            row_markdown(cells)
+        })
+        .collect();
+    let aligned = table.rows.get(1).or(table.rows.first());
+    let separator = (0..columns)
+        .map(|column| match aligned.and_then(|row| row.get(column)?.align.as_deref()) {
+            Some("l") => ":---",
+            Some("ctr") => ":---:",
+            Some("r") => "---:",
+            _ => "---",
+        }.to_string())
+        .collect();
+    lines.insert(1.min(lines.len()), row_markdown(separator));
+    lines.join("\n")
+}
+
+/// Image link; pictures filling the `size` slide or one half of it use the
+/// `![bg](...)` forms
+fn picture_markdown(picture: &ParsedPicture, link: &str, (width, height): (u32, u32)) -> String {
+    let (width, height) = (width as i64, height as i64);
+    let full_height = picture.y == 0 && picture.height >= height;
+    let alt = match (picture.x, picture.width) {
+        (0, w) if full_height && w >= width => "bg".to_string(),
+        (0, w) if full_height && w == width / 2 => "bg left".to_string(),
+        (x, w) if full_height && x == width / 2 && w == width / 2 => "bg right".to_string(),
+        _ => escape(picture.description.as_deref().unwrap_or_default()),
+    };
+    format!("![{alt}]({link})")
+}
+
+/// Inline Markdown of a paragraph: emphasis, code spans and links
+fn paragraph_markdown(paragraph: &Paragraph) -> String {
+    // Merge neighbouring runs with the same formatting
+    let mut spans: Vec<(&TextRun, String)> = Vec::new();
+    for run in &paragraph.runs {
+        match spans.last_mut() {
+            Some((last, text)) if same_format(last, run) => text.push_str(&run.text),
+            _ => spans.push((run, run.text.clone())),
+        }
+    }
+
+    let mut markdown = String::new();
+    for (run, text) in spans {
+        let core = text.trim();
+        if core.is_empty() {
+            markdown.push_str(&text);
+            continue;
+        }
+        // Spaces stay outside the emphasis markers
+        let (lead, trail) = (&text[..text.len() - text.trim_start().len()], &text[text.trim_end().len()..]);
+        let mut span = if is_math(run) {
+            core.to_string()
+        } else if is_monospace(run) {
+            format!("`{core}`")
+        } else {
+            escape(core)
+        };
+        if let Some(url) = run.hyperlink.as_deref().filter(|url| url.contains(':')) {
+            span = format!("[{span}]({url})");
+        }
+        let marker = match (run.bold, run.italic) {
+            (true, true) => "***",
+            (true, false) => "**",
+            (false, true) => "*",
+            (false, false) => "",
+        };
+        markdown.push_str(&format!("{lead}{marker}{span}{marker}{trail}"));
+    }
+    markdown.trim().to_string()
+}
+
+fn same_format(a: &TextRun, b: &TextRun) -> bool {
+    a.bold == b.bold && a.italic == b.italic && a.hyperlink == b.hyperlink && is_monospace(a) == is_monospace(b)
+}
+
+fn is_monospace(run: &TextRun) -> bool {
+    run.font.as_deref().is_some_and(|font| MONOSPACE_FONTS.contains(&font.to_lowercase().as_str()))
+}
+
+/// TeX between `$`, in the math font, as md2ppt writes for readers without Office Math
+fn is_math(run: &TextRun) -> bool {
+    let text = run.text.trim();
+    run.font.as_deref() == Some(MATH_FONT) && text.len() > 1 && text.starts_with('$') && text.ends_with('$')
+}
+
+/// A text box whose text is all in a monospace font
+fn is_code(shape: &ParsedShape) -> bool {
+    let mut runs = shape.paragraphs.iter().flat_map(|p| &p.runs).peekable();
+    runs.peek().is_some() && runs.all(is_monospace)
+}
+
+/// Two shapes next to each other on the same band of the slide
+fn side_by_side(a: &ParsedShape, b: &ParsedShape) -> bool {
+    let apart = a.x + a.width <= b.x || b.x + b.width <= a.x;
+    let level = a.y < b.y + b.height && b.y < a.y + a.height;
+    apart && level
+}
+
+/// A one-line text box just above a column, aligned with it
+fn is_heading_of(shape: &ParsedShape, column: &ParsedShape) -> bool {
+    shape.role == ShapeRole::Other
+        && shape.text_box
+        && shape.paragraphs.len() == 1
+        && shape.y < column.y
+        && (shape.x - column.x).abs() < column.width / 4
+}
+
+/// Escape characters that Markdown would read as formatting
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '*' | '_' | '`' | '[' | ']') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    if escaped.starts_with('#') || escaped.starts_with('>') {
+        escaped.insert(0, '\\');
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oxml::ParsedTableCell;
+
+    fn run(text: &str) -> TextRun {
+        TextRun::new(text)
+    }
+
+    #[test]
+    fn test_code_markdown() {
+        let code = |text: &str| TextRun { font: Some("Consolas".to_string()), ..run(text) };
+        let number = |text: &str| TextRun { no_proof: true, ..code(text) };
+        let mut shape = ParsedShape::new("Code Block: rust {2} showLineNumbers");
+        shape.paragraphs = vec![
+            Paragraph { runs: vec![number(" 9 "), code("fn main() {")], level: 0, bullet: false },
+            Paragraph { runs: vec![number("10 "), code("    42")], level: 0, bullet: false },
+            Paragraph { runs: vec![code("}")], level: 0, bullet: false },
+        ];
+        assert!(is_code(&shape));
+        assert_eq!(code_markdown(&shape), "```rust {2} showLineNumbers\nfn main() {\n    42\n}\n```");
+
+        shape.name = "Code Block".to_string();
+        assert!(code_markdown(&shape).starts_with("```\nfn main"));
+    }
+
+    #[test]
+    fn test_paragraph_markdown() {
+        let mut bold = run("bold ");
+        bold.bold = true;
+        let mut code = run("cargo");
+        code.font = Some("Consolas".to_string());
+        let mut link = run("docs");
+        link.hyperlink = Some("https://docs.rs".to_string());
+        let paragraph = Paragraph {
+            runs: vec![run("A "), bold, run(" "), code, run(", "), link, run(" for snake_case")],
+            level: 0,
+            bullet: true,
+        };
+        assert_eq!(paragraph_markdown(&paragraph), "A **bold**  `cargo`, [docs](https://docs.rs) for snake\\_case");
+        assert_eq!(escape("# not a heading"), "\\# not a heading");
+    }
+
+    #[test]
+    fn test_table_markdown() {
+        let cell = |runs: Vec<TextRun>| ParsedTableCell {
+            text: String::new(),
+            paragraphs: runs.into_iter().map(|run| Paragraph { runs: vec![run], level: 0, bullet: false }).collect(),
+            row_span: 1,
+            col_span: 1,
+            align: None,
+        };
+        let bold = TextRun { bold: true, ..run("Value") };
+        let link = TextRun { hyperlink: Some("https://docs.rs".to_string()), ..run("docs") };
+        let table = ParsedTable { rows: vec![
+            vec![cell(vec![run("Name")]), cell(vec![bold])],
+            vec![cell(vec![run("a|b")]), cell(vec![TextRun { bold: true, ..run("1") }, link])],
+            vec![cell(vec![run("c")])],
+        ] };
+        assert_eq!(table_markdown(&table), "| Name | Value |\n| --- | --- |\n| a\\|b | **1**<br>[docs](https://docs.rs) |\n| c |  |");
+
+        // Body cell alignments become the separator row
+        let mut table = table;
+        for (cell, align) in table.rows[1].iter_mut().zip(["r", "l"]) {
+            cell.align = Some(align.to_string());
+        }
+        table.rows[0][0].align = Some("ctr".to_string());
+        assert!(table_markdown(&table).contains("| Name | Value |\n| ---: | :--- |\n"));
+    }
+
+    #[test]
+    fn test_equation_markdown() {
+        let equation = |latex: Option<&str>, display| ParsedEquation {
+            latex: latex.map(str::to_string),
+            text: "x^2 -- 1".to_string(),
+            display,
+            x: 0,
+            y: 0,
+        };
+        assert_eq!(equation_markdown(&equation(Some("x^2 - 1"), true)), "$$\nx^2 - 1\n$$");
+        assert_eq!(equation_markdown(&equation(Some("x^2 - 1"), false)), "$x^2 - 1$");
+        assert_eq!(equation_markdown(&equation(None, true)), "<!-- equation: x^2 - - 1 -->");
+    }
+}
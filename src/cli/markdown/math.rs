@@ -0,0 +1,153 @@
+//! TeX math in Markdown: `$...$` inline and `$$...$$` display
+//!
+//! Markdown would read the backslashes, underscores and asterisks of TeX as
+//! escapes and emphasis, so math is rewritten before parsing, keeping line
+//! numbers: `$$` lines around a block become a ```` ```math ```` fence, and
+//! math within a line becomes a code span tagged with a private-use character.
+
+use super::directives::CodeFences;
+
+/// First character of a code span holding inline math
+const INLINE_MATH: char = '\u{E000}';
+/// First character of a code span holding display math
+const DISPLAY_MATH: char = '\u{E001}';
+
+/// TeX of a code span written by [`math_to_code`], and whether it is display math
+pub fn code_math(code: &str) -> Option<(&str, bool)> {
+    let mut chars = code.chars();
+    match chars.next()? {
+        INLINE_MATH => Some((chars.as_str(), false)),
+        DISPLAY_MATH => Some((chars.as_str(), true)),
+        _ => None,
+    }
+}
+
+/// Rewrite the math outside code into math fences and tagged code spans
+pub fn math_to_code(body: &str) -> String {
+    let mut out = String::with_capacity(body.len());
+    let mut fences = CodeFences::default();
+    let mut in_block = false;
+    for line in body.split_inclusive('\n') {
+        let text = line.trim_end();
+        let ending = &line[text.len()..];
+        if in_block {
+            if text.trim() == "$$" {
+                out.push_str(&text.replace("$$", "```"));
+                in_block = false;
+            } else {
+                out.push_str(text);
+            }
+        } else if fences.in_code(text) {
+            out.push_str(text);
+        } else if text.trim() == "$$" {
+            out.push_str(&text.replace("$$", "```math"));
+            in_block = true;
+        } else {
+            out.push_str(&spans_to_code(text));
+        }
+        out.push_str(ending);
+    }
+    out
+}
+
+/// Rewrite `$...$` and `$$...$$` within a line, skipping code spans and `\$`
+///
+/// Like Pandoc, an opening `$` must be followed by a non-space and a closing
+/// one preceded by a non-space and not followed by a digit, so prices such
+/// as `$5 and $10` stay text.
+fn spans_to_code(line: &str) -> String {
+    let chars: Vec<char> = line.chars().collect();
+    let mut out = String::with_capacity(line.len());
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => {
+                out.extend(&chars[i..(i + 2).min(chars.len())]);
+                i += 2;
+            }
+            '`' => {
+                // Code spans are copied as they are; they close with a run
+                // of as many backticks
+                let run = |at: usize| chars[at..].iter().take_while(|&&c| c == '`').count();
+                let ticks = run(i);
+                let mut end = i + ticks;
+                let mut j = end;
+                while j < chars.len() {
+                    if chars[j] != '`' {
+                        j += 1;
+                    } else if run(j) == ticks {
+                        end = j + ticks;
+                        break;
+                    } else {
+                        j += run(j);
+                    }
+                }
+                out.extend(&chars[i..end]);
+                i = end;
+            }
+            '$' => match math_span(&chars, i) {
+                Some((tex, display, end)) => {
+                    let tex: String = tex.iter().collect();
+                    let longest = tex.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+                    let ticks = "`".repeat(longest + 1);
+                    let mark = if display { DISPLAY_MATH } else { INLINE_MATH };
+                    out.push_str(&format!("{ticks}{mark}{tex}{ticks}"));
+                    i = end;
+                }
+                None => {
+                    out.push('$');
+                    i += 1;
+                }
+            },
+            c => {
+                out.push(c);
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+/// Math starting at the `$` at `start`: its TeX, whether it is display math,
+/// and the index after the closing delimiter
+fn math_span(chars: &[char], start: usize) -> Option<(&[char], bool, usize)> {
+    let display = chars.get(start + 1) == Some(&'$');
+    let open = if display { start + 2 } else { start + 1 };
+    if chars.get(open).is_none_or(|c| c.is_whitespace()) {
+        return None;
+    }
+    let mut j = open;
+    while j < chars.len() {
+        match chars[j] {
+            '\\' => j += 1,
+            '$' if display && chars.get(j + 1) == Some(&'$') => return Some((&chars[open..j], true, j + 2)),
+            '$' if display => {}
+            '$' if !chars[j - 1].is_whitespace() && !chars.get(j + 1).is_some_and(char::is_ascii_digit) => {
+                return Some((&chars[open..j], false, j + 1));
+            }
+            _ => {}
+        }
+        j += 1;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_math_to_code() {
+        let md = "Euler: $e^{i\\pi} + 1 = 0$ costs $5 and $10\n\n$$\n\\frac{a}{b}\n$$\n\n```\n$x$\n```\n`$y$` and \\$z$ and $$x_1$$\n";
+        let body = math_to_code(md);
+        assert_eq!(body.lines().count(), md.lines().count());
+        assert!(body.starts_with("Euler: `\u{E000}e^{i\\pi} + 1 = 0` costs $5 and $10\n"));
+        assert!(body.contains("\n```math\n\\frac{a}{b}\n```\n"));
+        assert!(body.contains("```\n$x$\n```"));
+        assert!(body.ends_with("`$y$` and \\$z$ and `\u{E001}x_1`\n"));
+
+        assert_eq!(code_math("\u{E000}x^2"), Some(("x^2", false)));
+        assert_eq!(code_math("\u{E001}x^2"), Some(("x^2", true)));
+        assert_eq!(code_math("x^2"), None);
+    }
+}
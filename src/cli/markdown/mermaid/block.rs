@@ -0,0 +1,431 @@
+//! Block diagram (`block-beta`) parsing and rendering
+//!
+//! Blocks flow left to right through a grid of `columns`, wrapping onto new
+//! rows; `block ... end` nests a group with its own grid.
+
+use std::collections::HashMap;
+use crate::generator::{Shape, ShapeType, ShapeFill, ShapeLine};
+use crate::generator::connectors::{Connector, ConnectorType, ConnectorLine, ArrowType, ConnectionSite};
+use super::flowchart::{create_node_shape, parse_node_def};
+use super::layout::{site_point, DIAGRAM_AREA};
+use super::style::{split_class_shorthand, DiagramTheme, Styles};
+use super::types::{DiagramBounds, DiagramElements, FlowNode, MermaidDiagnostic, MermaidResult};
+
+/// Height of a group's padding, in rows
+const GROUP_PADDING: f64 = 0.3;
+
+/// An entry of a block grid
+#[derive(Debug, Clone)]
+pub enum BlockItem {
+    Node { node: FlowNode, span: usize },
+    /// Empty cells
+    Space(usize),
+    Group { id: String, span: usize, columns: Option<usize>, items: Vec<BlockItem> },
+}
+
+impl BlockItem {
+    fn span(&self) -> usize {
+        match self {
+            BlockItem::Node { span, .. } | BlockItem::Space(span) | BlockItem::Group { span, .. } => *span,
+        }
+    }
+
+    /// Height in rows
+    fn rows(&self) -> f64 {
+        match self {
+            BlockItem::Group { columns, items, .. } => grid_rows(items, *columns).iter().map(|row| row.height).sum::<f64>() + GROUP_PADDING,
+            _ => 1.0,
+        }
+    }
+}
+
+/// A link between two blocks
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockLink {
+    pub from: String,
+    pub to: String,
+    pub label: Option<String>,
+    pub arrow: bool,
+}
+
+/// Parsed block diagram
+#[derive(Debug, Clone)]
+pub struct BlockDiagram {
+    pub columns: Option<usize>,
+    pub items: Vec<BlockItem>,
+    pub links: Vec<BlockLink>,
+    pub styles: Styles,
+    /// Lines that were dropped or only partly understood
+    pub warnings: Vec<MermaidDiagnostic>,
+}
+
+/// A group being parsed: ID, span, columns, items and the line it opened on
+type OpenGroup = (String, usize, Option<usize>, Vec<BlockItem>, usize);
+
+/// Parse a block diagram
+pub fn parse(code: &str) -> MermaidResult<BlockDiagram> {
+    let mut styles = Styles::default();
+    let mut warnings = Vec::new();
+    let mut links = Vec::new();
+    let mut stack: Vec<OpenGroup> = vec![(String::new(), 1, None, Vec::new(), 0)];
+    let mut group_count = 0;
+
+    for (index, source) in code.lines().enumerate().skip(1) {
+        let line = source.trim();
+        if line.is_empty() || line.starts_with("%%") || styles.parse_line(index, source) {
+            continue;
+        }
+        let Some(top) = stack.last_mut() else { break };
+
+        if let Some(columns) = line.strip_prefix("columns ") {
+            match columns.trim() {
+                "auto" => top.2 = None,
+                columns => match columns.parse::<usize>() {
+                    Ok(n) if n > 0 => top.2 = Some(n),
+                    _ => warnings.push(MermaidDiagnostic::at(index, source, columns, format!("`{columns}` is not a column count"))),
+                },
+            }
+        } else if line == "end" {
+            if stack.len() > 1 {
+                let (id, span, columns, items, _) = stack.pop().unwrap_or_default();
+                if let Some(parent) = stack.last_mut() {
+                    parent.3.push(BlockItem::Group { id, span, columns, items });
+                }
+            } else {
+                warnings.push(MermaidDiagnostic::at(index, source, "end", "`end` without `block`"));
+            }
+        } else if line == "block" || line.starts_with("block:") {
+            group_count += 1;
+            let mut parts = line.split(':').skip(1);
+            let id = parts.next().filter(|id| !id.is_empty()).map_or_else(|| format!("block{group_count}"), str::to_string);
+            let span = parts.next().and_then(|span| span.parse().ok()).unwrap_or(1);
+            stack.push((id, span, None, Vec::new(), index));
+        } else if let Some(link) = parse_link(line) {
+            links.push((link, index));
+        } else {
+            for token in tokenize(line) {
+                match parse_item(token, &mut styles, index, source) {
+                    Some(item) => top.3.push(item),
+                    None => warnings.push(MermaidDiagnostic::at(index, source, token, format!("invalid block `{token}`"))),
+                }
+            }
+        }
+    }
+
+    while stack.len() > 1 {
+        let (id, span, columns, items, line) = stack.pop().unwrap_or_default();
+        let source = code.lines().nth(line).unwrap_or("");
+        warnings.push(MermaidDiagnostic::at(line, source, "block", format!("block `{id}` is never closed with `end`")));
+        if let Some(parent) = stack.last_mut() {
+            parent.3.push(BlockItem::Group { id, span, columns, items });
+        }
+    }
+    let (_, _, columns, items, _) = stack.pop().unwrap_or_default();
+
+    let mut ids = Vec::new();
+    collect_ids(&items, &mut ids);
+    if ids.is_empty() {
+        return Err(MermaidDiagnostic::empty("block diagram"));
+    }
+    let links = links.into_iter()
+        .filter_map(|(link, index)| {
+            let missing = [&link.from, &link.to].into_iter().find(|id| !ids.contains(id)).cloned();
+            match missing {
+                Some(id) => {
+                    let source = code.lines().nth(index).unwrap_or("");
+                    warnings.push(MermaidDiagnostic::at(index, source, &id, format!("link to undeclared block `{id}`")));
+                    None
+                }
+                None => Some(link),
+            }
+        })
+        .collect::<Vec<_>>();
+    warnings.extend(styles.diagnostics(links.len()));
+
+    Ok(BlockDiagram { columns, items, links, styles, warnings })
+}
+
+/// Split a line into block definitions, keeping bracketed labels together
+fn tokenize(line: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+    let mut depth = 0usize;
+    let mut quoted = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '[' | '(' | '{' if !quoted => depth += 1,
+            ']' | ')' | '}' if !quoted => depth = depth.saturating_sub(1),
+            c if c.is_whitespace() && !quoted && depth == 0 => {
+                if let Some(s) = start.take() {
+                    tokens.push(&line[s..i]);
+                }
+                continue;
+            }
+            _ => {}
+        }
+        start.get_or_insert(i);
+    }
+    if let Some(s) = start {
+        tokens.push(&line[s..]);
+    }
+    tokens
+}
+
+/// Parse `id`, `id["label"]`, `id:2` or `space:2` on the source line with
+/// 0-based index `index`
+fn parse_item(token: &str, styles: &mut Styles, index: usize, source: &str) -> Option<BlockItem> {
+    // The span follows the shape, so only look after the closing bracket
+    let shape_end = token.rfind([']', ')', '}']).map_or(0, |i| i + 1);
+    let (def, span) = match token[shape_end..].rsplit_once(':') {
+        Some((_, span)) if span.parse::<usize>().is_ok() => {
+            (&token[..token.len() - span.len() - 1], span.parse().unwrap_or(1))
+        }
+        _ => (token, 1),
+    };
+    if def == "space" {
+        return Some(BlockItem::Space(span));
+    }
+
+    let (def, class) = split_class_shorthand(def);
+    let (_, node) = parse_node_def(def);
+    let mut node = node?;
+    node.label = node.label.trim().trim_matches('"').to_string();
+    if let Some(class) = class {
+        styles.add_class(&node.id, class, index, source);
+    }
+    Some(BlockItem::Node { node, span: span.max(1) })
+}
+
+/// Parse `a --> b`, `a --- b` or `a -- "label" --> b`
+fn parse_link(line: &str) -> Option<BlockLink> {
+    let (left, to, arrow) = match line.split_once("-->") {
+        Some((left, to)) => (left, to, true),
+        None => line.split_once("---").map(|(left, to)| (left, to, false))?,
+    };
+    let (from, label) = match left.split_once("--") {
+        Some((from, label)) => (from, Some(label.trim().trim_matches('"').to_string())),
+        None => (left, None),
+    };
+    let (from, to) = (from.trim(), to.trim());
+    let is_id = |id: &str| !id.is_empty() && id.chars().all(|c| c.is_alphanumeric() || c == '_');
+    (is_id(from) && is_id(to)).then(|| BlockLink { from: from.to_string(), to: to.to_string(), label, arrow })
+}
+
+fn collect_ids(items: &[BlockItem], ids: &mut Vec<String>) {
+    for item in items {
+        match item {
+            BlockItem::Node { node, .. } => ids.push(node.id.clone()),
+            BlockItem::Group { id, items, .. } => {
+                ids.push(id.clone());
+                collect_ids(items, ids);
+            }
+            BlockItem::Space(_) => {}
+        }
+    }
+}
+
+/// A grid row: items with their column, and its height in rows
+struct GridRow {
+    cells: Vec<(usize, usize)>,
+    height: f64,
+}
+
+/// Number of columns: `columns N`, or everything on one row
+fn column_count(items: &[BlockItem], columns: Option<usize>) -> usize {
+    columns.unwrap_or_else(|| items.iter().map(BlockItem::span).sum()).max(1)
+}
+
+/// Flow items into rows, wrapping when a span does not fit
+fn grid_rows(items: &[BlockItem], columns: Option<usize>) -> Vec<GridRow> {
+    let columns = column_count(items, columns);
+    let mut rows: Vec<GridRow> = Vec::new();
+    let mut column = columns;
+    for (i, item) in items.iter().enumerate() {
+        let span = item.span().min(columns);
+        if column + span > columns {
+            rows.push(GridRow { cells: Vec::new(), height: 1.0 });
+            column = 0;
+        }
+        if let Some(row) = rows.last_mut() {
+            row.cells.push((i, column));
+            row.height = row.height.max(item.rows());
+        }
+        column += span;
+    }
+    rows
+}
+
+/// Generate shapes and connectors for a block diagram
+pub fn generate_elements(code: &str, theme: Option<&DiagramTheme>) -> MermaidResult<DiagramElements> {
+    let diagram = parse(code)?;
+    let theme = theme.cloned().unwrap_or_else(default_theme);
+
+    // Cells are capped so small grids do not fill the whole slide
+    let columns = column_count(&diagram.items, diagram.columns);
+    let total_rows: f64 = grid_rows(&diagram.items, diagram.columns).iter().map(|row| row.height).sum();
+    let column_width = (DIAGRAM_AREA.width / columns as u32).min(2_000_000);
+    let row_height = ((DIAGRAM_AREA.height as f64 / total_rows) as u32).min(900_000);
+    let width = column_width * columns as u32;
+    let height = (row_height as f64 * total_rows) as u32;
+    let area = DiagramBounds {
+        x: DIAGRAM_AREA.x + (DIAGRAM_AREA.width - width) / 2,
+        y: DIAGRAM_AREA.y + DIAGRAM_AREA.height.saturating_sub(height) / 2,
+        width,
+        height,
+    };
+
+    let mut grid = Grid { theme: &theme, styles: &diagram.styles, row_height, shapes: Vec::new(), blocks: HashMap::new(), shape_id: 10 };
+    grid.place(&diagram.items, diagram.columns, area);
+    let Grid { mut shapes, blocks, mut shape_id, .. } = grid;
+
+    let mut connectors = Vec::new();
+    for (link_index, link) in diagram.links.iter().enumerate() {
+        let (Some(&(from, from_id)), Some(&(to, to_id))) = (blocks.get(&link.from), blocks.get(&link.to)) else { continue };
+        let dx = (to.x + to.width / 2) as i64 - (from.x + from.width / 2) as i64;
+        let dy = (to.y + to.height / 2) as i64 - (from.y + from.height / 2) as i64;
+        let (start_site, end_site) = match (dx.abs() >= dy.abs(), dx >= 0, dy >= 0) {
+            (true, true, _) => (ConnectionSite::Right, ConnectionSite::Left),
+            (true, false, _) => (ConnectionSite::Left, ConnectionSite::Right),
+            (false, _, true) => (ConnectionSite::Bottom, ConnectionSite::Top),
+            (false, _, false) => (ConnectionSite::Top, ConnectionSite::Bottom),
+        };
+        let (start_x, start_y) = site_point(&from, start_site);
+        let (end_x, end_y) = site_point(&to, end_site);
+
+        let mut connector = Connector::new(ConnectorType::Straight, start_x, start_y, end_x, end_y)
+            .with_line(diagram.styles.link_style(link_index).apply_to_line(ConnectorLine::new(&theme.line, 19050)))
+            .connect_start(from_id, start_site)
+            .connect_end(to_id, end_site);
+        if link.arrow {
+            connector = connector.with_end_arrow(ArrowType::Triangle);
+        }
+        connectors.push(connector);
+
+        if let Some(label) = &link.label {
+            let (label_width, label_height) = (800_000u32, 250_000u32);
+            let (mid_x, mid_y) = ((start_x + end_x) / 2, (start_y + end_y) / 2);
+            shapes.push(
+                Shape::new(ShapeType::Rectangle, mid_x.saturating_sub(label_width / 2), mid_y.saturating_sub(label_height / 2), label_width, label_height)
+                    .with_id(shape_id)
+                    .with_fill(ShapeFill::new(&theme.node_fill))
+                    .with_line(ShapeLine::new(&theme.muted, 1))
+                    .with_text(label)
+            );
+            shape_id += 1;
+        }
+    }
+
+    Ok(DiagramElements::from_shapes_and_connectors(shapes, connectors).with_warnings(diagram.warnings))
+}
+
+/// Default block diagram colors
+fn default_theme() -> DiagramTheme {
+    DiagramTheme {
+        node_fill: "FFFFFF".to_string(),
+        primary_fill: "FFF3E0".to_string(),
+        secondary_fill: "EDE7F6".to_string(),
+        stroke: "5E35B1".to_string(),
+        line: "5E35B1".to_string(),
+        text: None,
+        accent: "E65100".to_string(),
+        muted: "9575CD".to_string(),
+    }
+}
+
+/// Shapes placed so far, with the box and shape ID of every block
+struct Grid<'a> {
+    theme: &'a DiagramTheme,
+    styles: &'a Styles,
+    row_height: u32,
+    shapes: Vec<Shape>,
+    blocks: HashMap<String, (DiagramBounds, u32)>,
+    shape_id: u32,
+}
+
+impl Grid<'_> {
+    /// Place items in the grid filling `area`; groups recurse into their box
+    fn place(&mut self, items: &[BlockItem], columns: Option<usize>, area: DiagramBounds) {
+        let column_width = area.width / column_count(items, columns) as u32;
+        let gap = (self.row_height / 8).min(column_width / 8);
+        let mut y = area.y;
+        for row in grid_rows(items, columns) {
+            for &(i, column) in &row.cells {
+                let item = &items[i];
+                let cell = DiagramBounds {
+                    x: area.x + column_width * column as u32 + gap,
+                    y: y + gap,
+                    width: (column_width * item.span() as u32).saturating_sub(2 * gap),
+                    height: ((self.row_height as f64 * item.rows()) as u32).saturating_sub(2 * gap),
+                };
+                match item {
+                    BlockItem::Node { node, .. } => {
+                        let shape = create_node_shape(node, cell.x, cell.y, cell.width, cell.height, self.shape_id, self.theme);
+                        self.shapes.push(self.styles.node_style(&node.id).apply_to_shape(shape));
+                        self.blocks.insert(node.id.clone(), (cell, self.shape_id));
+                        self.shape_id += 1;
+                    }
+                    BlockItem::Group { id, columns, items, .. } => {
+                        let shape = Shape::new(ShapeType::RoundedRectangle, cell.x, cell.y, cell.width, cell.height)
+                            .with_id(self.shape_id)
+                            .with_fill(ShapeFill::new(&self.theme.secondary_fill))
+                            .with_line(ShapeLine::new(&self.theme.muted, 1));
+                        self.shapes.push(self.styles.node_style(id).apply_to_shape(shape));
+                        self.blocks.insert(id.clone(), (cell, self.shape_id));
+                        self.shape_id += 1;
+
+                        let padding = ((self.row_height as f64 * GROUP_PADDING / 2.0) as u32).saturating_sub(gap);
+                        let inner = DiagramBounds {
+                            x: cell.x + padding,
+                            y: cell.y + padding,
+                            width: cell.width.saturating_sub(2 * padding),
+                            height: cell.height.saturating_sub(2 * padding),
+                        };
+                        self.place(items, *columns, inner);
+                    }
+                    BlockItem::Space(_) => {}
+                }
+            }
+            y += (self.row_height as f64 * row.height) as u32;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_block_diagram() {
+        let code = "block-beta\n    columns 3\n    a[\"Front end\"] b:2\n    space c(\"API\")\n    block:db:2\n        d[(\"Store\")] e\n    end\n    a --> c\n    c -- \"reads\" --> d";
+        let diagram = parse(code).unwrap();
+        assert_eq!(diagram.columns, Some(3));
+        assert_eq!(diagram.items.len(), 5);
+        assert!(matches!(&diagram.items[0], BlockItem::Node { node, span: 1 } if node.label == "Front end"));
+        assert!(matches!(&diagram.items[1], BlockItem::Node { span: 2, .. }));
+        assert!(matches!(&diagram.items[2], BlockItem::Space(1)));
+        assert!(matches!(&diagram.items[4], BlockItem::Group { id, span: 2, items, .. } if id == "db" && items.len() == 2));
+        assert_eq!(diagram.links[1], BlockLink { from: "c".into(), to: "d".into(), label: Some("reads".into()), arrow: true });
+        assert!(diagram.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_block_grid_wraps() {
+        let code = "block-beta\n    columns 2\n    a b c:2\n    a --> c";
+        let elements = generate_elements(code, None).unwrap();
+        let boxes: Vec<(u32, u32, u32)> = elements.shapes.iter().map(|s| (s.x, s.y, s.width)).collect();
+        assert_eq!(boxes[0].1, boxes[1].1);
+        assert!(boxes[1].0 > boxes[0].0);
+        assert!(boxes[2].1 > boxes[0].1 && boxes[2].2 > boxes[0].2 * 2);
+        assert_eq!(elements.connectors.len(), 1);
+    }
+
+    #[test]
+    fn test_block_diagnostics() {
+        let code = "block-beta\n    columns x\n    a b\n    a --> z\n    block:g\n        c";
+        let diagram = parse(code).unwrap();
+        let warnings: Vec<(usize, usize)> = diagram.warnings.iter().map(|w| (w.line, w.column)).collect();
+        assert_eq!(warnings, [(2, 13), (5, 5), (4, 11)]);
+        assert!(parse("block-beta\n    columns 2").is_err());
+    }
+}
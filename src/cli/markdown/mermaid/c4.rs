@@ -0,0 +1,461 @@
+//! C4 diagram (`C4Context`, `C4Container`, ...) parsing and rendering
+//!
+//! Elements become colored boxes, boundaries become dashed groups around
+//! them, and relationships become labeled connectors.
+
+use crate::generator::{Shape, ShapeType, ShapeFill, ShapeLine};
+use crate::generator::connectors::{Connector, ConnectorType, ConnectorLine, ArrowType, LineDash};
+use super::layout::{LayoutGraph, DIAGRAM_AREA};
+use super::types::{DiagramBounds, DiagramElements, FlowDirection, MermaidDiagnostic, MermaidResult};
+
+/// Level of a C4 element
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum C4Kind {
+    Person,
+    System,
+    Container,
+    Component,
+}
+
+impl C4Kind {
+    fn name(&self) -> &'static str {
+        match self {
+            C4Kind::Person => "Person",
+            C4Kind::System => "Software System",
+            C4Kind::Container => "Container",
+            C4Kind::Component => "Component",
+        }
+    }
+
+    /// Standard C4 colors, internal and external
+    fn fill(&self, external: bool) -> &'static str {
+        match (self, external) {
+            (C4Kind::Person, false) => "08427B",
+            (C4Kind::System, false) => "1168BD",
+            (C4Kind::Container, false) => "438DD5",
+            (C4Kind::Component, false) => "85BBF0",
+            (C4Kind::Person, true) => "686868",
+            (_, true) => "999999",
+        }
+    }
+}
+
+/// Storage variant of an element (`SystemDb`, `ContainerQueue`, ...)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum C4Shape {
+    Box,
+    Database,
+    Queue,
+}
+
+/// A person, system, container or component
+#[derive(Debug, Clone, PartialEq)]
+pub struct C4Element {
+    pub alias: String,
+    pub label: String,
+    pub kind: C4Kind,
+    pub shape: C4Shape,
+    pub external: bool,
+    pub technology: Option<String>,
+    pub description: Option<String>,
+    pub boundary: Option<usize>,
+}
+
+/// A boundary grouping elements and nested boundaries
+#[derive(Debug, Clone, PartialEq)]
+pub struct C4Boundary {
+    pub alias: String,
+    pub label: String,
+    /// Kind shown under the label, e.g. `System` or `Enterprise`
+    pub kind: String,
+    pub parent: Option<usize>,
+}
+
+/// Where a relationship draws its arrowheads and which way it is laid out
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum C4RelKind {
+    /// `Rel`, `Rel_D`, `Rel_R`
+    Forward,
+    /// `Rel_U`, `Rel_L`: the target is laid out before the source
+    Reverse,
+    /// `Rel_Back`: the arrow points at the source
+    Back,
+    /// `BiRel`
+    Both,
+}
+
+/// A relationship between two elements
+#[derive(Debug, Clone, PartialEq)]
+pub struct C4Rel {
+    pub from: String,
+    pub to: String,
+    pub label: String,
+    pub technology: Option<String>,
+    pub kind: C4RelKind,
+}
+
+/// Parsed C4 diagram
+#[derive(Debug, Clone, PartialEq)]
+pub struct C4Diagram {
+    pub title: String,
+    pub elements: Vec<C4Element>,
+    pub boundaries: Vec<C4Boundary>,
+    pub rels: Vec<C4Rel>,
+    /// Lines that were dropped or only partly understood
+    pub warnings: Vec<MermaidDiagnostic>,
+}
+
+/// Parse a C4 diagram
+pub fn parse(code: &str) -> MermaidResult<C4Diagram> {
+    let mut diagram = C4Diagram { title: String::new(), elements: Vec::new(), boundaries: Vec::new(), rels: Vec::new(), warnings: Vec::new() };
+    // Open boundaries with the line they were opened on
+    let mut open: Vec<(usize, usize)> = Vec::new();
+    let mut rels = Vec::new();
+
+    for (index, source) in code.lines().enumerate().skip(1) {
+        let line = source.trim();
+        if line.is_empty() || line.starts_with("%%") {
+            continue;
+        }
+        if line == "}" {
+            if open.pop().is_none() {
+                diagram.warnings.push(MermaidDiagnostic::at(index, source, "}", "`}` without an open boundary"));
+            }
+            continue;
+        }
+        if let Some(title) = line.strip_prefix("title ") {
+            diagram.title = title.trim().to_string();
+            continue;
+        }
+
+        let Some((name, args, opens)) = parse_call(line) else {
+            diagram.warnings.push(MermaidDiagnostic::unsupported(index, source));
+            continue;
+        };
+        let boundary = open.last().map(|&(b, _)| b);
+        let arg = |i: usize| args.get(i).filter(|a| !a.is_empty()).cloned();
+
+        if name.starts_with("Update") {
+            // Styling and layout hints have no equivalent on a slide
+        } else if let Some(kind) = boundary_kind(name, arg(2)) {
+            let (Some(alias), label) = (arg(0), arg(1)) else {
+                diagram.warnings.push(MermaidDiagnostic::at(index, source, name, format!("`{name}` needs an alias")));
+                continue;
+            };
+            let label = label.unwrap_or_else(|| alias.clone());
+            diagram.boundaries.push(C4Boundary { alias, label, kind, parent: boundary });
+            if opens {
+                open.push((diagram.boundaries.len() - 1, index));
+            }
+        } else if let Some(kind) = rel_kind(name) {
+            // `RelIndex` puts a sequence number first
+            let args = if name == "RelIndex" { &args[args.len().min(1)..] } else { &args[..] };
+            let [from, to, rest @ ..] = args else {
+                diagram.warnings.push(MermaidDiagnostic::at(index, source, name, format!("`{name}` needs a source and a target")));
+                continue;
+            };
+            let rel = C4Rel {
+                from: from.clone(),
+                to: to.clone(),
+                label: rest.first().cloned().unwrap_or_default(),
+                technology: rest.get(1).filter(|t| !t.is_empty()).cloned(),
+                kind,
+            };
+            rels.push((rel, index));
+        } else if let Some((kind, shape, external)) = element_kind(name) {
+            let Some(alias) = arg(0) else {
+                diagram.warnings.push(MermaidDiagnostic::at(index, source, name, format!("`{name}` needs an alias")));
+                continue;
+            };
+            // Containers and components take a technology before the description
+            let (technology, description) = match kind {
+                C4Kind::Container | C4Kind::Component => (arg(2), arg(3)),
+                C4Kind::Person | C4Kind::System => (None, arg(2)),
+            };
+            let label = arg(1).unwrap_or_else(|| alias.clone());
+            diagram.elements.push(C4Element { alias, label, kind, shape, external, technology, description, boundary });
+        } else {
+            diagram.warnings.push(MermaidDiagnostic::unsupported(index, source));
+        }
+    }
+
+    for (boundary, line) in open {
+        let source = code.lines().nth(line).unwrap_or("");
+        let alias = &diagram.boundaries[boundary].alias;
+        diagram.warnings.push(MermaidDiagnostic::at(line, source, "{", format!("boundary `{alias}` is never closed with `}}`")));
+    }
+    if diagram.elements.is_empty() {
+        return Err(MermaidDiagnostic::empty("C4 diagram"));
+    }
+    for (rel, line) in rels {
+        let missing = [&rel.from, &rel.to].into_iter().find(|alias| !diagram.elements.iter().any(|e| e.alias == **alias)).cloned();
+        match missing {
+            Some(alias) => {
+                let source = code.lines().nth(line).unwrap_or("");
+                diagram.warnings.push(MermaidDiagnostic::at(line, source, &alias, format!("relationship to undeclared element `{alias}`")));
+            }
+            None => diagram.rels.push(rel),
+        }
+    }
+    Ok(diagram)
+}
+
+/// Split `Name(arg, "arg", $key=value) {` into the name, positional arguments
+/// and whether it opens a block
+fn parse_call(line: &str) -> Option<(&str, Vec<String>, bool)> {
+    let (name, rest) = line.split_once('(')?;
+    let name = name.trim();
+    if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        return None;
+    }
+    let close = rest.rfind(')')?;
+    let opens = rest[close + 1..].trim() == "{";
+
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in rest[..close].chars() {
+        match c {
+            '"' => quoted = !quoted,
+            ',' if !quoted => args.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    args.push(current);
+    let args = args.into_iter()
+        .map(|arg| arg.trim().to_string())
+        .filter(|arg| !arg.starts_with('$'))
+        .collect();
+    Some((name, args, opens))
+}
+
+fn element_kind(name: &str) -> Option<(C4Kind, C4Shape, bool)> {
+    let (name, external) = match name.strip_suffix("_Ext") {
+        Some(name) => (name, true),
+        None => (name, false),
+    };
+    let (name, shape) = if let Some(name) = name.strip_suffix("Db") {
+        (name, C4Shape::Database)
+    } else if let Some(name) = name.strip_suffix("Queue") {
+        (name, C4Shape::Queue)
+    } else {
+        (name, C4Shape::Box)
+    };
+    let kind = match name {
+        "Person" if shape == C4Shape::Box => C4Kind::Person,
+        "System" => C4Kind::System,
+        "Container" => C4Kind::Container,
+        "Component" => C4Kind::Component,
+        _ => return None,
+    };
+    Some((kind, shape, external))
+}
+
+/// Label shown for a boundary, or `None` if `name` is not a boundary
+fn boundary_kind(name: &str, boundary_type: Option<String>) -> Option<String> {
+    let kind = match name {
+        "Boundary" => return Some(boundary_type.unwrap_or_default()),
+        "Enterprise_Boundary" => "Enterprise",
+        "System_Boundary" => "System",
+        "Container_Boundary" => "Container",
+        "Node" | "Node_L" | "Node_R" | "Deployment_Node" => return Some(boundary_type.unwrap_or_else(|| "Node".to_string())),
+        _ => return None,
+    };
+    Some(kind.to_string())
+}
+
+fn rel_kind(name: &str) -> Option<C4RelKind> {
+    match name {
+        "Rel" | "RelIndex" | "Rel_D" | "Rel_Down" | "Rel_R" | "Rel_Right" => Some(C4RelKind::Forward),
+        "Rel_U" | "Rel_Up" | "Rel_L" | "Rel_Left" => Some(C4RelKind::Reverse),
+        "Rel_Back" => Some(C4RelKind::Back),
+        "BiRel" => Some(C4RelKind::Both),
+        _ => None,
+    }
+}
+
+/// Generate shapes and connectors for a C4 diagram
+pub fn generate_elements(code: &str) -> MermaidResult<DiagramElements> {
+    let diagram = parse(code)?;
+    let mut shapes = Vec::new();
+    let mut connectors = Vec::new();
+
+    let mut graph = LayoutGraph::new(FlowDirection::TopToBottom).with_spacing(500_000, 900_000);
+    for _ in &diagram.elements {
+        graph.add_node(2_000_000, 1_100_000);
+    }
+    for boundary in &diagram.boundaries {
+        graph.add_cluster(boundary.parent);
+    }
+    for (i, element) in diagram.elements.iter().enumerate() {
+        if let Some(boundary) = element.boundary {
+            graph.set_cluster(i, boundary);
+        }
+    }
+
+    // Relationships only link declared elements
+    let index = |alias: &str| diagram.elements.iter().position(|e| e.alias == alias).unwrap_or_default();
+    let rels: Vec<(&C4Rel, usize, usize)> = diagram.rels.iter().map(|rel| (rel, index(&rel.from), index(&rel.to))).collect();
+    for &(rel, from, to) in &rels {
+        if rel.kind == C4RelKind::Reverse {
+            graph.add_edge(to, from);
+        } else {
+            graph.add_edge(from, to);
+        }
+    }
+
+    // Keep room above the diagram for its title
+    let title_height = if diagram.title.is_empty() { 0 } else { 400_000 };
+    let area = DiagramBounds {
+        y: DIAGRAM_AREA.y + title_height,
+        height: DIAGRAM_AREA.height - title_height,
+        ..DIAGRAM_AREA
+    };
+    let mut layout = graph.layout();
+    layout.fit(area);
+
+    let mut shape_id = 10u32;
+    if !diagram.title.is_empty() {
+        shapes.push(
+            Shape::new(ShapeType::Rectangle, DIAGRAM_AREA.x, DIAGRAM_AREA.y, DIAGRAM_AREA.width, title_height)
+                .with_id(shape_id)
+                .with_text(&diagram.title)
+        );
+        shape_id += 1;
+    }
+
+    // Boundaries (parents come before their children) with their titles
+    for (boundary, rect) in diagram.boundaries.iter().zip(&layout.clusters) {
+        let Some(rect) = rect else { continue };
+        shapes.push(
+            Shape::new(ShapeType::RoundedRectangle, rect.x, rect.y, rect.width, rect.height)
+                .with_id(shape_id)
+                .with_line(ShapeLine::new("444444", 12700).with_dash(LineDash::Dash))
+        );
+        shape_id += 1;
+
+        let title = if boundary.kind.is_empty() { boundary.label.clone() } else { format!("{} [{}]", boundary.label, boundary.kind) };
+        shapes.push(
+            Shape::new(ShapeType::Rectangle, rect.x + 50_000, rect.y + 25_000, rect.width.saturating_sub(100_000), layout.title_height)
+                .with_id(shape_id)
+                .with_text(&title)
+        );
+        shape_id += 1;
+    }
+
+    let mut element_shape_ids = Vec::with_capacity(diagram.elements.len());
+    for (element, rect) in diagram.elements.iter().zip(&layout.nodes) {
+        let shape_type = match (element.kind, element.shape) {
+            (C4Kind::Person, _) => ShapeType::RoundedRectangle,
+            (_, C4Shape::Database) => ShapeType::Can,
+            (_, C4Shape::Queue) => ShapeType::FlowChartMagneticDrum,
+            (_, C4Shape::Box) => ShapeType::Rectangle,
+        };
+        let kind = match &element.technology {
+            Some(technology) => format!("[{}: {technology}]", element.kind.name()),
+            None => format!("[{}]", element.kind.name()),
+        };
+        let mut text = format!("{}\n{kind}", element.label);
+        if let Some(description) = &element.description {
+            text.push_str(&format!("\n\n{description}"));
+        }
+        let fill = element.kind.fill(element.external);
+
+        element_shape_ids.push(shape_id);
+        shapes.push(
+            Shape::new(shape_type, rect.x, rect.y, rect.width, rect.height)
+                .with_id(shape_id)
+                .with_fill(ShapeFill::new(fill))
+                .with_line(ShapeLine::new(fill, 1))
+                .with_text(&text)
+        );
+        shape_id += 1;
+    }
+
+    for (&(rel, from, to), route) in rels.iter().zip(&layout.edges) {
+        // The route runs from the node that was laid out first
+        let (route_from, route_to) = if rel.kind == C4RelKind::Reverse { (to, from) } else { (from, to) };
+        let (start_x, start_y) = route.start();
+        let (end_x, end_y) = route.end();
+        let connector_type = if route.has_bends() {
+            ConnectorType::Curved
+        } else if start_x.abs_diff(end_x) < 100_000 || start_y.abs_diff(end_y) < 100_000 {
+            ConnectorType::Straight
+        } else {
+            ConnectorType::Elbow
+        };
+
+        let mut connector = Connector::new(connector_type, start_x, start_y, end_x, end_y)
+            .with_line(ConnectorLine::new("707070", 12700).with_dash(LineDash::Dash))
+            .connect_start(element_shape_ids[route_from], route.start_site)
+            .connect_end(element_shape_ids[route_to], route.end_site);
+        if matches!(rel.kind, C4RelKind::Forward | C4RelKind::Both) {
+            connector = connector.with_end_arrow(ArrowType::Triangle);
+        }
+        if matches!(rel.kind, C4RelKind::Reverse | C4RelKind::Back | C4RelKind::Both) {
+            connector = connector.with_start_arrow(ArrowType::Triangle);
+        }
+        connectors.push(connector);
+
+        let label = match &rel.technology {
+            Some(technology) => format!("{}\n[{technology}]", rel.label),
+            None => rel.label.clone(),
+        };
+        if !label.is_empty() {
+            let (label_width, label_height) = (1_200_000u32, 350_000u32);
+            let (mid_x, mid_y) = route.midpoint();
+            shapes.push(
+                Shape::new(ShapeType::Rectangle, mid_x.saturating_sub(label_width / 2), mid_y.saturating_sub(label_height / 2), label_width, label_height)
+                    .with_id(shape_id)
+                    .with_fill(ShapeFill::new("FFFFFF"))
+                    .with_text(&label)
+            );
+            shape_id += 1;
+        }
+    }
+
+    Ok(DiagramElements::from_shapes_and_connectors(shapes, connectors).with_warnings(diagram.warnings.clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_c4_context() {
+        let code = "C4Context\n    title System Context\n    Enterprise_Boundary(b0, \"Bank\") {\n        Person(customer, \"Customer\", \"A customer, with accounts\")\n        System_Boundary(b1, \"Banking\") {\n            SystemDb_Ext(mainframe, \"Mainframe\")\n            Container(api, \"API\", \"Rust\", \"Serves the app\", $tags=\"v1\")\n        }\n    }\n    Rel(customer, api, \"Uses\", \"HTTPS\")\n    Rel_U(mainframe, api, \"Reports\")\n    UpdateRelStyle(customer, api, $textColor=\"blue\")";
+        let diagram = parse(code).unwrap();
+        assert_eq!(diagram.title, "System Context");
+        assert_eq!(diagram.boundaries[1].parent, Some(0));
+        assert_eq!(diagram.elements[0].description.as_deref(), Some("A customer, with accounts"));
+        assert_eq!(diagram.elements[1].boundary, Some(1));
+        assert_eq!((diagram.elements[1].shape, diagram.elements[1].external), (C4Shape::Database, true));
+        assert_eq!(diagram.elements[2].technology.as_deref(), Some("Rust"));
+        assert_eq!(diagram.rels[0].technology.as_deref(), Some("HTTPS"));
+        assert_eq!(diagram.rels[1].kind, C4RelKind::Reverse);
+        assert!(diagram.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_c4_boundaries_enclose_elements() {
+        let code = "C4Container\n    Person(user, \"User\")\n    Container_Boundary(app, \"App\") {\n        Container(web, \"Web\", \"TS\")\n        ContainerDb(db, \"DB\", \"Postgres\")\n    }\n    Rel(user, web, \"Uses\")\n    BiRel(web, db, \"Reads\")";
+        let elements = generate_elements(code).unwrap();
+        let boundary = &elements.shapes[0];
+        assert_eq!(boundary.line.as_ref().unwrap().dash, LineDash::Dash);
+        let web = elements.shapes.iter().find(|s| s.text.as_deref() == Some("Web\n[Container: TS]")).unwrap();
+        assert!(web.x >= boundary.x && web.y >= boundary.y);
+        assert!(web.x + web.width <= boundary.x + boundary.width);
+        assert_eq!(elements.connectors.len(), 2);
+        assert_eq!(elements.connectors[1].start_arrow, ArrowType::Triangle);
+    }
+
+    #[test]
+    fn test_c4_diagnostics() {
+        let code = "C4Context\n    Person(p, \"P\")\n    Boundary(b) {\n        Widget(w)\n    Rel(p, x)";
+        let diagram = parse(code).unwrap();
+        let warnings: Vec<(usize, usize)> = diagram.warnings.iter().map(|w| (w.line, w.column)).collect();
+        assert_eq!(warnings, [(4, 9), (3, 17), (5, 12)]);
+        assert!(diagram.rels.is_empty());
+        assert!(parse("C4Context\n    title Empty").is_err());
+    }
+}
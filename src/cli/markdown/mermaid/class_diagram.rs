@@ -1,12 +1,30 @@
 //! Class diagram parsing and rendering
 
-use std::collections::HashMap;
 use crate::generator::{Shape, ShapeType, ShapeFill, ShapeLine};
-use crate::generator::connectors::{Connector, ConnectorType, ConnectorLine, ArrowType, ConnectionSite};
-use super::types::DiagramElements;
+use crate::generator::connectors::{Connector, ConnectorType, ConnectorLine, ArrowType};
+use super::layout::{LayoutGraph, DIAGRAM_AREA};
+use super::style::{split_class_shorthand, DiagramTheme, Styles};
+use super::types::{DiagramElements, FlowDirection, MermaidDiagnostic, MermaidResult};
+
+/// Default class diagram colors
+fn default_theme() -> DiagramTheme {
+    DiagramTheme {
+        node_fill: "FFFFFF".to_string(),
+        primary_fill: "4472C4".to_string(),
+        secondary_fill: "D6DCE5".to_string(),
+        stroke: "2F5496".to_string(),
+        line: "2F5496".to_string(),
+        text: None,
+        accent: "2F5496".to_string(),
+        muted: "2F5496".to_string(),
+    }
+}
 
 /// Generate shapes and connectors for a class diagram
-pub fn generate_elements(code: &str) -> DiagramElements {
+pub fn generate_elements(code: &str, theme: Option<&DiagramTheme>) -> MermaidResult<DiagramElements> {
+    let theme = theme.cloned().unwrap_or_else(default_theme);
+    let mut styles = Styles::default();
+    let mut warnings = Vec::new();
     let mut shapes = Vec::new();
     let mut connectors = Vec::new();
     
@@ -15,132 +33,201 @@ pub fn generate_elements(code: &str) -> DiagramElements {
     let mut current_attrs: Vec<String> = Vec::new();
     let mut current_methods: Vec<String> = Vec::new();
     let mut in_class = false;
-    let mut relationships: Vec<(String, String, String)> = Vec::new();
+    let mut class_line = 0;
+    // (from, to, type, line index)
+    let mut relationships: Vec<(String, String, String, usize)> = Vec::new();
+    let mut direction = FlowDirection::TopToBottom;
     
-    for line in code.lines().skip(1) {
-        let line = line.trim();
+    for (index, source) in code.lines().enumerate().skip(1) {
+        let line = source.trim();
         if line.is_empty() || line.starts_with("%%") {
             continue;
         }
         
-        if line.starts_with("class ") && line.contains('{') {
-            current_class = line.strip_prefix("class ").unwrap_or("")
-                .split('{').next().unwrap_or("").trim().to_string();
-            in_class = true;
+        if !in_class && let Some(dir) = line.strip_prefix("direction") {
+            match FlowDirection::from_keyword(dir) {
+                Some(dir) => direction = dir,
+                None => warnings.push(MermaidDiagnostic::at(index, source, dir.trim(), format!("unknown direction `{}`", dir.trim()))),
+            }
+        } else if !in_class
+            && let Some(rest) = line.strip_prefix("class ")
+            && (line.contains('{') || !rest.trim().contains(char::is_whitespace))
+        {
+            let name = rest.split('{').next().unwrap_or("").trim();
+            let (name, class) = split_class_shorthand(name);
+            if let Some(class) = class {
+                styles.add_class(name, class, index, source);
+            }
             current_attrs.clear();
             current_methods.clear();
+            if line.contains('{') {
+                current_class = name.to_string();
+                class_line = index;
+                in_class = true;
+            } else {
+                // `class Name` declares a class without members
+                declare(&mut classes, name);
+            }
         } else if line == "}" && in_class {
-            classes.push((current_class.clone(), current_attrs.clone(), current_methods.clone()));
+            let class = declare(&mut classes, &current_class);
+            class.1.append(&mut current_attrs);
+            class.2.append(&mut current_methods);
             in_class = false;
+        } else if !in_class && styles.parse_line(index, source) {
+            // Styling directive
         } else if in_class {
             if line.contains('(') {
                 current_methods.push(line.to_string());
             } else if !line.is_empty() {
                 current_attrs.push(line.to_string());
             }
-        } else if line.contains("<|--") || line.contains("-->") || line.contains("--") {
-            let rel_type = if line.contains("<|--") { "extends" }
-                          else if line.contains("-->") { "uses" }
+        } else if let Some((name, member)) = line.split_once(':').filter(|(name, _)| is_class_name(name.trim())) {
+            // `Name : member` adds a member, declaring the class if needed
+            let (_, attrs, methods) = declare(&mut classes, name.trim());
+            let member = member.trim().to_string();
+            if member.contains('(') { methods.push(member) } else if !member.is_empty() { attrs.push(member) }
+        } else if line.contains("--") || line.contains("..") {
+            // A label after `:` isn't drawn
+            let link = line.split_once(':').map_or(line, |(link, _)| link);
+            let rel_type = if link.contains("<|--") { "extends" }
+                          else if link.contains("-->") { "uses" }
                           else { "associates" };
             
-            let parts: Vec<&str> = line.split(|c| c == '<' || c == '|' || c == '-' || c == '>').collect();
-            let parts: Vec<&str> = parts.into_iter().filter(|s| !s.is_empty()).collect();
-            if parts.len() >= 2 {
-                relationships.push((parts[0].trim().to_string(), parts[parts.len()-1].trim().to_string(), rel_type.to_string()));
+            let parts: Vec<&str> = link
+                .split(|c| matches!(c, '<' | '|' | '-' | '.' | '*' | '>'))
+                .map(|part| part.trim())
+                .filter(|part| !part.is_empty())
+                .collect();
+            match parts[..] {
+                [from, .., to] => {
+                    // Aggregation `o--` leaves an `o` next to a class name
+                    let from = from.strip_suffix(" o").unwrap_or(from).trim();
+                    let to = to.strip_prefix("o ").unwrap_or(to).trim();
+                    relationships.push((from.to_string(), to.to_string(), rel_type.to_string(), index));
+                }
+                _ => warnings.push(MermaidDiagnostic::at(index, source, "", "relationship needs a class on both sides")),
             }
+        } else {
+            warnings.push(MermaidDiagnostic::unsupported(index, source));
         }
     }
     
+    if in_class {
+        let source = code.lines().nth(class_line).unwrap_or("");
+        warnings.push(MermaidDiagnostic::at(class_line, source, "{", format!("class `{current_class}` is never closed with `}}`")));
+        let class = declare(&mut classes, &current_class);
+        class.1.append(&mut current_attrs);
+        class.2.append(&mut current_methods);
+    }
+    // Classes named only by a relationship are declared by it
+    for (from, to, _, _) in &relationships {
+        declare(&mut classes, from);
+        declare(&mut classes, to);
+    }
+    if classes.is_empty() {
+        return Err(MermaidDiagnostic::empty("class diagram"));
+    }
+    warnings.extend(styles.diagnostics(relationships.len()));
+    
     // Layout parameters
-    let start_x = 500_000u32;
-    let start_y = 1_600_000u32;
     let class_width = 2_000_000u32;
-    let h_spacing = 2_500_000u32;
     let header_height = 350_000u32;
     let member_height = 250_000u32;
+    let box_height = |attrs: &[String], methods: &[String]| {
+        header_height + (attrs.len().max(1) as u32 + methods.len().max(1) as u32) * member_height
+    };
     
-    let mut class_positions: HashMap<String, (u32, u32)> = HashMap::new();
-    let mut class_shape_ids: HashMap<String, u32> = HashMap::new();
+    let mut graph = LayoutGraph::new(direction).with_spacing(500_000, 700_000);
+    for (_, attrs, methods) in &classes {
+        graph.add_node(class_width, box_height(attrs, methods));
+    }
+    let index = |name: &str| classes.iter().position(|(class_name, _, _)| class_name == name);
+    let relationships: Vec<(usize, usize, usize)> = relationships.iter()
+        .enumerate()
+        .filter_map(|(i, (from, to, _rel_type, _))| Some((i, index(from)?, index(to)?)))
+        .collect();
+    for &(_, from, to) in &relationships {
+        graph.add_edge(from, to);
+    }
+    let mut layout = graph.layout();
+    layout.fit(DIAGRAM_AREA);
+    
+    let mut class_shape_ids: Vec<u32> = Vec::with_capacity(classes.len());
     let mut shape_id = 10u32;
     
-    for (i, (class_name, attrs, methods)) in classes.iter().enumerate() {
-        let x = start_x + (i as u32 % 3) * h_spacing;
-        let y = start_y + (i as u32 / 3) * 2_000_000;
-        class_positions.insert(class_name.clone(), (x, y));
-        class_shape_ids.insert(class_name.clone(), shape_id);
+    for ((class_name, attrs, methods), rect) in classes.iter().zip(&layout.nodes) {
+        let (x, y) = (rect.x, rect.y);
+        // Sections shrink with the layout when it was scaled to fit
+        let scale = rect.height as f64 / box_height(attrs, methods) as f64;
+        let header_height = (header_height as f64 * scale) as u32;
+        let member_height = (member_height as f64 * scale) as u32;
+        class_shape_ids.push(shape_id);
+        let style = styles.node_style(class_name);
         
         // Class header (this is the shape connectors attach to)
-        let header = Shape::new(ShapeType::Rectangle, x, y, class_width, header_height)
+        let header = Shape::new(ShapeType::Rectangle, x, y, rect.width, header_height)
             .with_id(shape_id)
-            .with_fill(ShapeFill::new("4472C4"))
-            .with_line(ShapeLine::new("2F5496", 2))
+            .with_fill(ShapeFill::new(&theme.primary_fill))
+            .with_line(ShapeLine::new(&theme.stroke, 2))
             .with_text(class_name);
-        shapes.push(header);
+        shapes.push(style.apply_to_shape(header));
         shape_id += 1;
         
         // Attributes section
         let attrs_text = if attrs.is_empty() { String::new() } else { attrs.join("\n") };
         let attrs_height = (attrs.len().max(1) as u32) * member_height;
-        let attrs_shape = Shape::new(ShapeType::Rectangle, x, y + header_height, class_width, attrs_height)
+        let attrs_shape = Shape::new(ShapeType::Rectangle, x, y + header_height, rect.width, attrs_height)
             .with_id(shape_id)
-            .with_fill(ShapeFill::new("D6DCE5"))
-            .with_line(ShapeLine::new("2F5496", 1))
+            .with_fill(ShapeFill::new(&theme.secondary_fill))
+            .with_line(ShapeLine::new(&theme.stroke, 1))
             .with_text(&attrs_text);
-        shapes.push(attrs_shape);
+        shapes.push(style.apply_to_shape(attrs_shape));
         shape_id += 1;
         
         // Methods section
         let methods_text = if methods.is_empty() { String::new() } else { methods.join("\n") };
         let methods_height = (methods.len().max(1) as u32) * member_height;
-        let methods_shape = Shape::new(ShapeType::Rectangle, x, y + header_height + attrs_height, class_width, methods_height)
+        let methods_shape = Shape::new(ShapeType::Rectangle, x, y + header_height + attrs_height, rect.width, methods_height)
             .with_id(shape_id)
-            .with_fill(ShapeFill::new("FFFFFF"))
-            .with_line(ShapeLine::new("2F5496", 1))
+            .with_fill(ShapeFill::new(&theme.node_fill))
+            .with_line(ShapeLine::new(&theme.stroke, 1))
             .with_text(&methods_text);
-        shapes.push(methods_shape);
+        shapes.push(style.apply_to_shape(theme.style_text(methods_shape)));
         shape_id += 1;
     }
     
     // Create connectors with shape anchoring
-    for (from, to, _rel_type) in &relationships {
-        if let (Some(&(from_x, from_y)), Some(&(to_x, to_y))) = 
-            (class_positions.get(from), class_positions.get(to)) 
-        {
-            let from_shape_id = class_shape_ids.get(from).copied();
-            let to_shape_id = class_shape_ids.get(to).copied();
-            
-            // Determine connection sites based on relative positions
-            let (start_site, end_site) = if from_y < to_y {
-                (ConnectionSite::Bottom, ConnectionSite::Top)
-            } else if from_y > to_y {
-                (ConnectionSite::Top, ConnectionSite::Bottom)
-            } else if from_x < to_x {
-                (ConnectionSite::Right, ConnectionSite::Left)
-            } else {
-                (ConnectionSite::Left, ConnectionSite::Right)
-            };
-            
-            let mut connector = Connector::new(
-                ConnectorType::Elbow,
-                from_x + class_width / 2, from_y,
-                to_x + class_width / 2, to_y + 500_000
-            )
-            .with_line(ConnectorLine::new("2F5496", 19050))
-            .with_end_arrow(ArrowType::Triangle);
-            
-            // Anchor to shapes for auto-routing
-            if let Some(id) = from_shape_id {
-                connector = connector.connect_start(id, start_site);
-            }
-            if let Some(id) = to_shape_id {
-                connector = connector.connect_end(id, end_site);
-            }
-            
-            connectors.push(connector);
-        }
+    for (&(link_index, from, to), route) in relationships.iter().zip(&layout.edges) {
+        let (start_x, start_y) = route.start();
+        let (end_x, end_y) = route.end();
+        
+        let connector = Connector::new(ConnectorType::Elbow, start_x, start_y, end_x, end_y)
+            .with_line(styles.link_style(link_index).apply_to_line(ConnectorLine::new(&theme.line, 19050)))
+            .with_end_arrow(ArrowType::Triangle)
+            .connect_start(class_shape_ids[from], route.start_site)
+            .connect_end(class_shape_ids[to], route.end_site);
+        
+        connectors.push(connector);
     }
     
-    DiagramElements::from_shapes_and_connectors(shapes, connectors)
+    Ok(DiagramElements::from_shapes_and_connectors(shapes, connectors).with_warnings(warnings))
+}
+
+/// The class named `name`, added without members if it is new
+fn declare<'a>(classes: &'a mut Vec<(String, Vec<String>, Vec<String>)>, name: &str) -> &'a mut (String, Vec<String>, Vec<String>) {
+    let position = match classes.iter().position(|(class_name, _, _)| class_name == name) {
+        Some(position) => position,
+        None => {
+            classes.push((name.to_string(), Vec::new(), Vec::new()));
+            classes.len() - 1
+        }
+    };
+    &mut classes[position]
+}
+
+/// A bare class name, optionally with a `~T~` generic
+fn is_class_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || matches!(c, '_' | '~'))
 }
 
 #[cfg(test)]
@@ -150,7 +237,40 @@ mod tests {
     #[test]
     fn test_generate_class_diagram() {
         let code = "classDiagram\n    class Animal {\n        +name: String\n        +eat()\n    }";
-        let elements = generate_elements(code);
+        let elements = generate_elements(code, None).unwrap();
         assert!(!elements.shapes.is_empty());
+        assert!(elements.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_class_diagram_layers_relationships() {
+        let code = "classDiagram\n    class Animal {\n        +name: String\n    }\n    class Dog {\n        +bark()\n    }\n    Animal <|-- Dog";
+        let elements = generate_elements(code, None).unwrap();
+        assert_eq!(elements.connectors.len(), 1);
+        // Animal's header sits above Dog's header
+        assert!(elements.shapes[0].y + elements.shapes[0].height < elements.shapes[3].y);
+    }
+
+    #[test]
+    fn test_class_diagram_implicit_classes() {
+        let code = "classDiagram\n    Animal <|-- Dog\n    Animal : +int age\n    Animal: +isMammal()\n    Dog *-- Tail : has\n    Dog o-- Collar\n    Dog ..> Food";
+        let elements = generate_elements(code, None).unwrap();
+        assert!(elements.warnings.is_empty(), "{:?}", elements.warnings);
+        assert_eq!(elements.connectors.len(), 4);
+        let headers: Vec<&str> = elements.shapes.iter().step_by(3).filter_map(|shape| shape.text.as_deref()).collect();
+        assert_eq!(headers, ["Animal", "Dog", "Tail", "Collar", "Food"]);
+        assert_eq!(elements.shapes[1].text.as_deref(), Some("+int age"));
+        assert_eq!(elements.shapes[2].text.as_deref(), Some("+isMammal()"));
+    }
+
+    #[test]
+    fn test_class_diagram_diagnostics() {
+        let code = "classDiagram\n    direction SIDEWAYS\n    class Animal\n    Animal <|-- Cat\n    Animal : +age int\n    Animal ?? Cat\n    class Dog {\n        +bark()";
+        let elements = generate_elements(code, None).unwrap();
+        let warnings: Vec<(usize, usize)> = elements.warnings.iter().map(|w| (w.line, w.column)).collect();
+        assert_eq!(warnings, [(2, 15), (6, 5), (7, 15)]);
+        assert_eq!(elements.shapes.len(), 9);
+
+        assert!(generate_elements("classDiagram", None).is_err());
     }
 }
@@ -1,160 +1,189 @@
 //! ER diagram parsing and rendering
 
-use std::collections::HashMap;
 use crate::generator::{Shape, ShapeType, ShapeFill, ShapeLine};
-use crate::generator::connectors::{Connector, ConnectorType, ConnectorLine, ArrowType, ConnectionSite};
-use super::types::DiagramElements;
+use crate::generator::connectors::{Connector, ConnectorType, ConnectorLine, ArrowType};
+use super::layout::{LayoutGraph, DIAGRAM_AREA};
+use super::style::{split_class_shorthand, DiagramTheme, Styles};
+use super::types::{DiagramElements, FlowDirection, MermaidDiagnostic, MermaidResult};
+
+/// Default ER diagram colors
+fn default_theme() -> DiagramTheme {
+    DiagramTheme {
+        node_fill: "FFFFFF".to_string(),
+        primary_fill: "C2185B".to_string(),
+        secondary_fill: "FCE4EC".to_string(),
+        stroke: "880E4F".to_string(),
+        line: "880E4F".to_string(),
+        text: None,
+        accent: "880E4F".to_string(),
+        muted: "880E4F".to_string(),
+    }
+}
 
 /// Generate shapes and connectors for an ER diagram
-pub fn generate_elements(code: &str) -> DiagramElements {
+pub fn generate_elements(code: &str, theme: Option<&DiagramTheme>) -> MermaidResult<DiagramElements> {
+    let theme = theme.cloned().unwrap_or_else(default_theme);
+    let mut styles = Styles::default();
+    let mut warnings = Vec::new();
     let mut shapes = Vec::new();
     let mut connectors = Vec::new();
     
-    let mut entities: HashMap<String, Vec<String>> = HashMap::new();
+    // Entities keep their order of appearance so the layout is deterministic
+    let mut entities: Vec<(String, Vec<String>)> = Vec::new();
     let mut relationships: Vec<(String, String, String)> = Vec::new();
     let mut current_entity = String::new();
+    let mut entity_line = 0;
+    
+    let add_entity = |entities: &mut Vec<(String, Vec<String>)>, name: &str| {
+        if !entities.iter().any(|(entity, _)| entity == name) {
+            entities.push((name.to_string(), Vec::new()));
+        }
+    };
     
-    for line in code.lines().skip(1) {
-        let line = line.trim();
-        if line.is_empty() || line.starts_with("%%") {
+    for (index, source) in code.lines().enumerate().skip(1) {
+        let line = source.trim();
+        if line.is_empty() || line.starts_with("%%") || (current_entity.is_empty() && styles.parse_line(index, source)) {
             continue;
         }
         
-        if line.contains("||") || line.contains("}|") || line.contains("|{") || line.contains("o{") {
-            // Extract relationship label after colon
-            let (rel_part, label) = if let Some(colon_pos) = line.find(':') {
-                let label_part = line[colon_pos + 1..].trim();
-                // Remove quotes if present
-                let label = label_part.trim_matches('"').trim_matches('\'').to_string();
-                (&line[..colon_pos], label)
-            } else {
-                (line, String::new())
-            };
-            
-            let parts: Vec<&str> = rel_part.split(|c: char| c == '|' || c == '{' || c == '}' || c == 'o' || c == '-').collect();
-            let parts: Vec<&str> = parts.into_iter().filter(|s| !s.is_empty() && s.chars().any(|c| c.is_alphabetic())).collect();
-            if parts.len() >= 2 {
-                let e1 = parts[0].trim().to_string();
-                let e2 = parts[parts.len()-1].trim().to_string();
-                if !entities.contains_key(&e1) { entities.insert(e1.clone(), Vec::new()); }
-                if !entities.contains_key(&e2) { entities.insert(e2.clone(), Vec::new()); }
-                relationships.push((e1, e2, label));
+        if !current_entity.is_empty() {
+            if line == "}" {
+                current_entity.clear();
+            } else if let Some((_, attrs)) = entities.iter_mut().find(|(name, _)| *name == current_entity) {
+                attrs.push(line.to_string());
             }
-        }
-        else if line.contains('{') {
-            current_entity = line.split('{').next().unwrap_or("").trim().to_string();
-            if !entities.contains_key(&current_entity) {
-                entities.insert(current_entity.clone(), Vec::new());
+        } else if let Some((e1, e2, label)) = parse_relationship(line) {
+            add_entity(&mut entities, e1);
+            add_entity(&mut entities, e2);
+            relationships.push((e1.to_string(), e2.to_string(), label));
+        } else if line.ends_with('{') {
+            let (name, class) = split_class_shorthand(line.trim_end_matches('{').trim());
+            if let Some(class) = class {
+                styles.add_class(name, class, index, source);
             }
-        } else if line == "}" {
-            current_entity.clear();
-        } else if !current_entity.is_empty() && !line.is_empty() {
-            if let Some(attrs) = entities.get_mut(&current_entity) {
-                attrs.push(line.to_string());
+            current_entity = name.to_string();
+            entity_line = index;
+            add_entity(&mut entities, name);
+        } else if is_entity_name(split_class_shorthand(line).0) {
+            let (name, class) = split_class_shorthand(line);
+            if let Some(class) = class {
+                styles.add_class(name, class, index, source);
             }
+            add_entity(&mut entities, name);
+        } else {
+            warnings.push(MermaidDiagnostic::unsupported(index, source));
         }
     }
     
+    if !current_entity.is_empty() {
+        let source = code.lines().nth(entity_line).unwrap_or("");
+        warnings.push(MermaidDiagnostic::at(entity_line, source, "{", format!("entity `{current_entity}` is never closed with `}}`")));
+    }
+    if entities.is_empty() {
+        return Err(MermaidDiagnostic::empty("ER diagram"));
+    }
+    warnings.extend(styles.diagnostics(relationships.len()));
+    
     // Layout parameters
-    let start_x = 500_000u32;
-    let start_y = 1_600_000u32;
     let entity_width = 2_200_000u32;
     let header_height = 400_000u32;
     let attr_height = 280_000u32;
-    let h_spacing = 2_800_000u32;
-    let v_spacing = 2_500_000u32;
+    let box_height = |attrs: &[String]| header_height + (attrs.len().max(1) as u32) * attr_height;
+    
+    let mut graph = LayoutGraph::new(FlowDirection::TopToBottom).with_spacing(600_000, 800_000);
+    for (_, attrs) in &entities {
+        graph.add_node(entity_width, box_height(attrs));
+    }
+    let index = |name: &str| entities.iter().position(|(entity_name, _)| entity_name == name);
+    let relationships: Vec<(usize, usize, usize, String)> = relationships.into_iter()
+        .enumerate()
+        .filter_map(|(i, (e1, e2, label))| Some((i, index(&e1)?, index(&e2)?, label)))
+        .collect();
+    for (_, e1, e2, _) in &relationships {
+        graph.add_edge(*e1, *e2);
+    }
+    let mut layout = graph.layout();
+    layout.fit(DIAGRAM_AREA);
     
-    let mut entity_positions: HashMap<String, (u32, u32)> = HashMap::new();
-    let mut entity_shape_ids: HashMap<String, u32> = HashMap::new();
+    let mut entity_shape_ids: Vec<u32> = Vec::with_capacity(entities.len());
     let mut shape_id = 10u32;
     
-    for (i, (entity_name, attrs)) in entities.iter().enumerate() {
-        let x = start_x + (i as u32 % 3) * h_spacing;
-        let y = start_y + (i as u32 / 3) * v_spacing;
-        entity_positions.insert(entity_name.clone(), (x, y));
-        entity_shape_ids.insert(entity_name.clone(), shape_id);
+    for ((entity_name, attrs), rect) in entities.iter().zip(&layout.nodes) {
+        let (x, y) = (rect.x, rect.y);
+        let header_height = (header_height as f64 * rect.height as f64 / box_height(attrs) as f64) as u32;
+        entity_shape_ids.push(shape_id);
+        let style = styles.node_style(entity_name);
         
-        let header = Shape::new(ShapeType::Rectangle, x, y, entity_width, header_height)
+        let header = Shape::new(ShapeType::Rectangle, x, y, rect.width, header_height)
             .with_id(shape_id)
-            .with_fill(ShapeFill::new("C2185B"))
-            .with_line(ShapeLine::new("880E4F", 2))
+            .with_fill(ShapeFill::new(&theme.primary_fill))
+            .with_line(ShapeLine::new(&theme.stroke, 2))
             .with_text(entity_name);
-        shapes.push(header);
+        shapes.push(style.apply_to_shape(header));
         shape_id += 1;
         
         let attrs_text = attrs.join("\n");
-        let attrs_box_height = (attrs.len().max(1) as u32) * attr_height;
-        let attrs_shape = Shape::new(ShapeType::Rectangle, x, y + header_height, entity_width, attrs_box_height)
+        let attrs_shape = Shape::new(ShapeType::Rectangle, x, y + header_height, rect.width, rect.height - header_height)
             .with_id(shape_id)
-            .with_fill(ShapeFill::new("FCE4EC"))
-            .with_line(ShapeLine::new("880E4F", 1))
+            .with_fill(ShapeFill::new(&theme.secondary_fill))
+            .with_line(ShapeLine::new(&theme.stroke, 1))
             .with_text(&attrs_text);
-        shapes.push(attrs_shape);
+        shapes.push(style.apply_to_shape(attrs_shape));
         shape_id += 1;
     }
     
-    for (e1, e2, label) in &relationships {
-        if let (Some(&(x1, y1)), Some(&(x2, y2))) = 
-            (entity_positions.get(e1), entity_positions.get(e2)) 
-        {
-            let e1_shape_id = entity_shape_ids.get(e1).copied();
-            let e2_shape_id = entity_shape_ids.get(e2).copied();
-            
-            // Smart connection site selection
-            let (start_site, end_site) = if x1 < x2 {
-                (ConnectionSite::Right, ConnectionSite::Left)
-            } else if x1 > x2 {
-                (ConnectionSite::Left, ConnectionSite::Right)
-            } else if y1 < y2 {
-                (ConnectionSite::Bottom, ConnectionSite::Top)
-            } else {
-                (ConnectionSite::Top, ConnectionSite::Bottom)
-            };
+    for ((link_index, e1, e2, label), route) in relationships.iter().zip(&layout.edges) {
+        let (start_x, start_y) = route.start();
+        let (end_x, end_y) = route.end();
+        
+        let connector = Connector::new(ConnectorType::Elbow, start_x, start_y, end_x, end_y)
+            .with_line(styles.link_style(*link_index).apply_to_line(ConnectorLine::new(&theme.line, 19050)))
+            .with_end_arrow(ArrowType::Diamond)
+            .connect_start(entity_shape_ids[*e1], route.start_site)
+            .connect_end(entity_shape_ids[*e2], route.end_site);
+        
+        // Create label shape if label exists
+        if !label.is_empty() {
+            let label_width = 1_000_000u32;
+            let label_height = 250_000u32;
+            let (mid_x, mid_y) = route.midpoint();
             
-            let mut connector = Connector::new(
-                ConnectorType::Elbow,
-                x1 + entity_width, y1 + header_height / 2,
-                x2, y2 + header_height / 2
+            let label_shape = Shape::new(
+                ShapeType::RoundedRectangle,
+                mid_x.saturating_sub(label_width / 2),
+                mid_y.saturating_sub(label_height / 2),
+                label_width,
+                label_height
             )
-            .with_line(ConnectorLine::new("880E4F", 19050))
-            .with_end_arrow(ArrowType::Diamond);
-            
-            // Anchor to shapes
-            if let Some(id) = e1_shape_id {
-                connector = connector.connect_start(id, start_site);
-            }
-            if let Some(id) = e2_shape_id {
-                connector = connector.connect_end(id, end_site);
-            }
-            
-            // Create label shape if label exists
-            if !label.is_empty() {
-                let label_width = 1_000_000u32;
-                let label_height = 250_000u32;
-                let mid_x = (x1 + entity_width + x2) / 2;
-                let mid_y = (y1 + y2 + header_height) / 2;
-                
-                let label_shape = Shape::new(
-                    ShapeType::RoundedRectangle,
-                    mid_x.saturating_sub(label_width / 2),
-                    mid_y.saturating_sub(label_height / 2),
-                    label_width,
-                    label_height
-                )
-                .with_id(shape_id)
-                .with_fill(ShapeFill::new("FFFFFF"))
-                .with_line(ShapeLine::new("880E4F", 1))
-                .with_text(label);
-                
-                shapes.push(label_shape);
-                shape_id += 1;
-            }
+            .with_id(shape_id)
+            .with_fill(ShapeFill::new(&theme.node_fill))
+            .with_line(ShapeLine::new(&theme.muted, 1))
+            .with_text(label);
             
-            connectors.push(connector);
+            shapes.push(label_shape);
+            shape_id += 1;
         }
+        
+        connectors.push(connector);
     }
     
-    DiagramElements::from_shapes_and_connectors(shapes, connectors)
+    Ok(DiagramElements::from_shapes_and_connectors(shapes, connectors).with_warnings(warnings))
+}
+
+/// Parse `ENTITY1 ||--o{ ENTITY2 : label` into both entities and the label
+fn parse_relationship(line: &str) -> Option<(&str, &str, String)> {
+    let (rel_part, label) = match line.split_once(':') {
+        Some((rel_part, label)) => (rel_part, label.trim().trim_matches('"').trim_matches('\'').to_string()),
+        None => (line, String::new()),
+    };
+    match rel_part.split_whitespace().collect::<Vec<_>>().as_slice() {
+        [e1, cardinality, e2] if cardinality.contains("--") || cardinality.contains("..") => Some((e1, e2, label)),
+        _ => None,
+    }
+}
+
+fn is_entity_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-')
 }
 
 #[cfg(test)]
@@ -165,15 +194,25 @@ mod tests {
     fn test_generate_er_diagram() {
         // Test with entity definitions
         let code = "erDiagram\n    CUSTOMER {\n        string name\n    }\n    ORDER {\n        int id\n    }\n    CUSTOMER ||--o{ ORDER : places";
-        let elements = generate_elements(code);
+        let elements = generate_elements(code, None).unwrap();
         assert!(!elements.shapes.is_empty());
+        assert!(elements.warnings.is_empty());
     }
 
     #[test]
     fn test_er_diagram_empty() {
-        // Empty ER diagram should not panic
+        // Empty ER diagram is reported rather than drawn blank
         let code = "erDiagram";
-        let elements = generate_elements(code);
-        assert!(elements.shapes.is_empty());
+        assert!(generate_elements(code, None).is_err());
+    }
+
+    #[test]
+    fn test_er_diagram_diagnostics() {
+        let code = "erDiagram\n    customer ||--o{ LINE-ITEM : \"orders\"\n    customer has order\n    PRODUCT {\n        string sku";
+        let elements = generate_elements(code, None).unwrap();
+        let labels: Vec<&str> = elements.shapes.iter().filter_map(|s| s.text.as_deref()).collect();
+        assert!(labels.contains(&"customer") && labels.contains(&"LINE-ITEM") && labels.contains(&"orders"));
+        let warnings: Vec<(usize, usize)> = elements.warnings.iter().map(|w| (w.line, w.column)).collect();
+        assert_eq!(warnings, [(3, 5), (4, 13)]);
     }
 }
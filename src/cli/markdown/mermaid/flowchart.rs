@@ -2,120 +2,155 @@
 
 use std::collections::HashMap;
 use crate::generator::{Shape, ShapeType, ShapeFill, ShapeLine};
-use crate::generator::connectors::{Connector, ConnectorType, ConnectorLine, ArrowType, LineDash, ConnectionSite};
+use crate::generator::connectors::{Connector, ConnectorType, ConnectorLine, ArrowType, LineDash};
+use super::layout::{LayoutGraph, DIAGRAM_AREA};
+use super::style::{split_class_shorthand, DiagramTheme, Styles};
 use super::types::{*, DiagramBounds};
 
-/// Parse flowchart direction from first line
-fn parse_direction(first_line: &str) -> FlowDirection {
-    let line = first_line.to_uppercase();
-    if line.contains("LR") {
-        FlowDirection::LeftToRight
-    } else if line.contains("RL") {
-        FlowDirection::RightToLeft
-    } else if line.contains("BT") {
-        FlowDirection::BottomToTop
-    } else {
-        FlowDirection::TopToBottom
+/// Parse flowchart direction from the header line (`flowchart LR`)
+fn parse_direction(first_line: &str, warnings: &mut Vec<MermaidDiagnostic>) -> FlowDirection {
+    match first_line.split_whitespace().nth(1) {
+        None => FlowDirection::TopToBottom,
+        Some(keyword) => FlowDirection::from_keyword(keyword.trim_end_matches(';')).unwrap_or_else(|| {
+            warnings.push(MermaidDiagnostic::at(0, first_line, keyword, format!("unknown direction `{keyword}`, using TB")));
+            FlowDirection::TopToBottom
+        }),
     }
 }
 
 /// Parse a flowchart from Mermaid code
-pub fn parse(code: &str) -> Flowchart {
-    let mut lines = code.lines();
-    let first_line = lines.next().unwrap_or("");
-    let direction = parse_direction(first_line);
+pub fn parse(code: &str) -> MermaidResult<Flowchart> {
+    let mut warnings = Vec::new();
+    let mut lines = code.lines().enumerate();
+    let first_line = lines.next().map_or("", |(_, line)| line);
+    let direction = parse_direction(first_line, &mut warnings);
     
-    let mut nodes: HashMap<String, FlowNode> = HashMap::new();
+    // Nodes keep their order of appearance so the layout is deterministic
+    let mut nodes: Vec<FlowNode> = Vec::new();
+    let mut node_index: HashMap<String, usize> = HashMap::new();
     let mut connections: Vec<FlowConnection> = Vec::new();
     let mut subgraphs: Vec<Subgraph> = Vec::new();
-    let mut current_subgraph: Option<Subgraph> = None;
+    let mut open_subgraphs: Vec<(usize, usize)> = Vec::new();
+    let mut styles = Styles::default();
+    
+    let mut add_node = |node: FlowNode, subgraphs: &mut Vec<Subgraph>, open_subgraphs: &[(usize, usize)]| {
+        let id = node.id.clone();
+        if !node_index.contains_key(&id) {
+            node_index.insert(id.clone(), nodes.len());
+            nodes.push(node);
+        }
+        if let Some(&(sg, _)) = open_subgraphs.last()
+            && !subgraphs[sg].nodes.contains(&id)
+        {
+            subgraphs[sg].nodes.push(id);
+        }
+    };
     
-    for line in lines {
-        let line = line.trim();
-        if line.is_empty() || line.starts_with("%%") {
+    for (index, source) in lines {
+        let line = source.trim();
+        if line.is_empty() || line.starts_with("%%") || styles.parse_line(index, source) {
             continue;
         }
         
-        // Handle subgraph start
+        // Handle subgraph start (subgraphs may nest)
         if line.starts_with("subgraph") {
             let name = line.strip_prefix("subgraph").unwrap_or("").trim().to_string();
-            current_subgraph = Some(Subgraph { name, nodes: Vec::new() });
+            subgraphs.push(Subgraph { name, nodes: Vec::new(), parent: open_subgraphs.last().map(|&(sg, _)| sg) });
+            open_subgraphs.push((subgraphs.len() - 1, index));
             continue;
         }
         
         // Handle subgraph end
         if line == "end" {
-            if let Some(sg) = current_subgraph.take() {
-                subgraphs.push(sg);
+            if open_subgraphs.pop().is_none() {
+                warnings.push(MermaidDiagnostic::at(index, source, "end", "`end` without a matching `subgraph`"));
             }
             continue;
         }
         
-        // Parse connections: A --> B, A --> B[Label], A[Text] --> B[Text]
-        if let Some((from_part, rest)) = split_connection(line) {
-            let (arrow_type, to_part) = parse_arrow_and_rest(&rest);
-            
-            // Parse from node
-            let (from_id, from_node) = parse_node_def(&from_part);
-            if let Some(node) = from_node {
-                nodes.entry(from_id.clone()).or_insert(node);
-                if let Some(ref mut sg) = current_subgraph {
-                    if !sg.nodes.contains(&from_id) {
-                        sg.nodes.push(from_id.clone());
-                    }
+        // Parse connections, which may be chained: A --> B -->|label| C[Text]
+        if let Some((from_part, mut rest)) = split_connection(line) {
+            let mut from_id = match parse_node_ref(&from_part, &mut styles, index, source) {
+                (id, Some(node)) => {
+                    add_node(node, &mut subgraphs, &open_subgraphs);
+                    Some(id)
                 }
-            }
+                (id, None) => {
+                    warnings.push(MermaidDiagnostic::at(index, source, &id, format!("invalid node `{id}`")));
+                    None
+                }
+            };
             
-            // Parse to node (may have label on arrow)
-            let (to_part_clean, arrow_label) = extract_arrow_label(&to_part);
-            let (to_id, to_node) = parse_node_def(&to_part_clean);
-            if let Some(node) = to_node {
-                nodes.entry(to_id.clone()).or_insert(node);
-                if let Some(ref mut sg) = current_subgraph {
-                    if !sg.nodes.contains(&to_id) {
-                        sg.nodes.push(to_id.clone());
+            loop {
+                let (arrow_type, after_arrow) = parse_arrow_and_rest(&rest);
+                let (after_label, arrow_label) = extract_arrow_label(&after_arrow);
+                let (to_part, next) = match split_connection(&after_label) {
+                    Some((to_part, next)) => (to_part, Some(next)),
+                    None => (after_label, None),
+                };
+                
+                let to_id = match parse_node_ref(&to_part, &mut styles, index, source) {
+                    (id, Some(node)) => {
+                        add_node(node, &mut subgraphs, &open_subgraphs);
+                        Some(id)
                     }
+                    (id, None) => {
+                        let message = if id.is_empty() { "link without a target node".to_string() } else { format!("invalid node `{id}`") };
+                        warnings.push(MermaidDiagnostic::at(index, source, &id, message));
+                        None
+                    }
+                };
+                
+                if let (Some(from), Some(to)) = (&from_id, &to_id) {
+                    connections.push(FlowConnection {
+                        from: from.clone(),
+                        to: to.clone(),
+                        label: arrow_label,
+                        arrow_type,
+                    });
+                }
+                
+                from_id = to_id;
+                match next {
+                    Some(next) => rest = next,
+                    None => break,
                 }
             }
-            
-            connections.push(FlowConnection {
-                from: from_id,
-                to: to_id,
-                label: arrow_label,
-                arrow_type,
-            });
         } else {
             // Standalone node definition
-            let (id, node) = parse_node_def(line);
-            if let Some(n) = node {
-                nodes.entry(id.clone()).or_insert(n);
-                if let Some(ref mut sg) = current_subgraph {
-                    if !sg.nodes.contains(&id) {
-                        sg.nodes.push(id);
-                    }
-                }
+            match parse_node_ref(line, &mut styles, index, source) {
+                (_, Some(node)) => add_node(node, &mut subgraphs, &open_subgraphs),
+                (_, None) => warnings.push(MermaidDiagnostic::unsupported(index, source)),
             }
         }
     }
     
-    Flowchart {
+    for &(_, index) in &open_subgraphs {
+        let source = code.lines().nth(index).unwrap_or("");
+        warnings.push(MermaidDiagnostic::at(index, source, "subgraph", "`subgraph` is never closed with `end`"));
+    }
+    if nodes.is_empty() {
+        return Err(MermaidDiagnostic::empty("flowchart"));
+    }
+    warnings.extend(styles.diagnostics(connections.len()));
+    
+    Ok(Flowchart {
         direction,
-        nodes: nodes.into_values().collect(),
+        nodes,
         connections,
         subgraphs,
-    }
+        styles,
+        warnings,
+    })
 }
 
-/// Split line at connection arrow
+/// Split line at the first connection arrow
 fn split_connection(line: &str) -> Option<(String, String)> {
-    for arrow in ["==>", "-.->", "-->", "---", "->"] {
-        if let Some(pos) = line.find(arrow) {
-            let from = line[..pos].trim().to_string();
-            let rest = line[pos..].to_string();
-            return Some((from, rest));
-        }
-    }
-    None
+    let pos = ["==>", "-.->", "-->", "---", "->"]
+        .iter()
+        .filter_map(|arrow| line.find(arrow))
+        .min()?;
+    Some((line[..pos].trim().to_string(), line[pos..].to_string()))
 }
 
 /// Parse arrow type and get the rest of the string
@@ -135,20 +170,29 @@ fn parse_arrow_and_rest(s: &str) -> (ArrowStyle, String) {
     }
 }
 
-/// Extract arrow label like |text|
+/// Extract an arrow label like |text| that directly follows the arrow
 fn extract_arrow_label(s: &str) -> (String, Option<String>) {
-    if let Some(start) = s.find('|') {
-        if let Some(end) = s[start+1..].find('|') {
-            let label = s[start+1..start+1+end].to_string();
-            let rest = s[start+2+end..].trim().to_string();
-            return (rest, Some(label));
-        }
+    if let Some(after) = s.strip_prefix('|')
+        && let Some(end) = after.find('|')
+    {
+        return (after[end + 1..].trim().to_string(), Some(after[..end].to_string()));
     }
     (s.to_string(), None)
 }
 
+/// Parse a node reference, recording an `A:::class` shorthand of the source
+/// line with 0-based index `index`
+fn parse_node_ref(s: &str, styles: &mut Styles, index: usize, source: &str) -> (String, Option<FlowNode>) {
+    let (def, class) = split_class_shorthand(s.trim());
+    let (id, node) = parse_node_def(def);
+    if let Some(class) = class {
+        styles.add_class(&id, class, index, source);
+    }
+    (id, node)
+}
+
 /// Parse a node definition like A[Text] or B(Text) or C{Text}
-fn parse_node_def(s: &str) -> (String, Option<FlowNode>) {
+pub fn parse_node_def(s: &str) -> (String, Option<FlowNode>) {
     let s = s.trim();
     
     // Try different bracket types
@@ -182,196 +226,159 @@ fn parse_node_def(s: &str) -> (String, Option<FlowNode>) {
     (s.to_string(), None)
 }
 
+/// Default flowchart colors
+fn default_theme() -> DiagramTheme {
+    DiagramTheme {
+        node_fill: "FFFFFF".to_string(),
+        primary_fill: "FFF3E0".to_string(),
+        secondary_fill: "E3F2FD".to_string(),
+        stroke: "1565C0".to_string(),
+        line: "1565C0".to_string(),
+        text: None,
+        accent: "E65100".to_string(),
+        muted: "757575".to_string(),
+    }
+}
+
 /// Generate shapes and connectors for a flowchart
-pub fn generate_elements(flowchart: &Flowchart) -> DiagramElements {
+pub fn generate_elements(flowchart: &Flowchart, theme: Option<&DiagramTheme>) -> DiagramElements {
+    let theme = theme.cloned().unwrap_or_else(default_theme);
     let mut shapes = Vec::new();
     let mut connectors = Vec::new();
-    let node_count = flowchart.nodes.len();
     
-    if node_count == 0 {
-        return DiagramElements { shapes, connectors, bounds: None, grouped: false };
-    }
-    
-    // Track element positions for bounding box calculation
-    let mut element_bounds: Vec<(u32, u32, u32, u32)> = Vec::new();
-    
-    // Layout parameters (in EMUs)
+    // Node size (in EMUs), scaled down with the layout when it does not fit
     let node_width = 1_400_000u32;
     let node_height = 500_000u32;
-    let h_spacing = 1_800_000u32;
-    let v_spacing = 900_000u32;
-    
-    let is_horizontal = matches!(flowchart.direction, FlowDirection::LeftToRight | FlowDirection::RightToLeft);
     
-    // Track node positions and their shape IDs for connector anchoring
-    let mut node_positions: HashMap<String, (u32, u32)> = HashMap::new();
-    let mut node_shape_ids: HashMap<String, u32> = HashMap::new();
-    let mut shape_id = 10u32; // Starting shape ID
+    let node_index: HashMap<&str, usize> = flowchart.nodes.iter()
+        .enumerate()
+        .map(|(i, node)| (node.id.as_str(), i))
+        .collect();
     
-    // If we have subgraphs, layout by subgraph
-    if !flowchart.subgraphs.is_empty() {
-        let mut subgraph_x = 500_000u32;
-        let subgraph_start_y = 1_600_000u32;
-        
-        for (sg_idx, subgraph) in flowchart.subgraphs.iter().enumerate() {
-            let sg_width = node_width + 400_000;
-            let title_height = 250_000u32;
-            let sg_height = (subgraph.nodes.len() as u32) * v_spacing + 400_000 + title_height;
-            let sg_x = subgraph_x;
-            let sg_y = subgraph_start_y;
-            
-            // Subgraph background shape (no text - just background)
-            let sg_shape = Shape::new(ShapeType::RoundedRectangle, sg_x, sg_y, sg_width, sg_height)
-                .with_fill(ShapeFill::new(get_subgraph_color(sg_idx)))
-                .with_line(ShapeLine::new("757575", 1));
-            shapes.push(sg_shape);
-            element_bounds.push((sg_x, sg_y, sg_width, sg_height));
-            
-            // Subgraph title at top
-            let title_shape = Shape::new(ShapeType::Rectangle, sg_x + 50_000, sg_y + 50_000, sg_width - 100_000, title_height)
-                .with_id(shape_id)
-                .with_text(&subgraph.name);
-            shapes.push(title_shape);
-            shape_id += 1;
-            
-            // Layout nodes within subgraph (offset by title height)
-            for (node_idx, node_id) in subgraph.nodes.iter().enumerate() {
-                if let Some(node) = flowchart.nodes.iter().find(|n| &n.id == node_id) {
-                    let x = sg_x + 200_000;
-                    let y = sg_y + title_height + 200_000 + (node_idx as u32) * v_spacing;
-                    
-                    node_positions.insert(node.id.clone(), (x, y));
-                    node_shape_ids.insert(node.id.clone(), shape_id);
-                    
-                    let shape = create_node_shape(node, x, y, node_width, node_height, shape_id);
-                    shapes.push(shape);
-                    element_bounds.push((x, y, node_width, node_height));
-                    shape_id += 1;
-                }
-            }
-            
-            subgraph_x += sg_width + 600_000;
+    let mut graph = LayoutGraph::new(flowchart.direction);
+    for _ in &flowchart.nodes {
+        graph.add_node(node_width, node_height);
+    }
+    for subgraph in &flowchart.subgraphs {
+        graph.add_cluster(subgraph.parent);
+    }
+    // A node listed by several subgraphs goes to the innermost one
+    let depth = |mut sg: usize| {
+        let mut depth = 0;
+        while let Some(parent) = flowchart.subgraphs[sg].parent {
+            depth += 1;
+            sg = parent;
         }
-        
-        // Layout any nodes not in subgraphs
-        let mut orphan_y = subgraph_start_y;
-        for node in &flowchart.nodes {
-            if !node_positions.contains_key(&node.id) {
-                let x = subgraph_x;
-                let y = orphan_y;
-                
-                node_positions.insert(node.id.clone(), (x, y));
-                node_shape_ids.insert(node.id.clone(), shape_id);
-                
-                let shape = create_node_shape(node, x, y, node_width, node_height, shape_id);
-                shapes.push(shape);
-                element_bounds.push((x, y, node_width, node_height));
-                shape_id += 1;
-                
-                orphan_y += v_spacing;
+        depth
+    };
+    let mut node_subgraph: Vec<Option<usize>> = vec![None; flowchart.nodes.len()];
+    for (sg_idx, subgraph) in flowchart.subgraphs.iter().enumerate() {
+        for id in &subgraph.nodes {
+            if let Some(&i) = node_index.get(id.as_str())
+                && node_subgraph[i].is_none_or(|current| depth(current) < depth(sg_idx))
+            {
+                node_subgraph[i] = Some(sg_idx);
             }
         }
-    } else {
-        // Simple grid layout without subgraphs
-        let start_x = 1_000_000u32;
-        let start_y = 1_800_000u32;
-        let cols = if is_horizontal { node_count.min(5) } else { 1 };
-        
-        for (i, node) in flowchart.nodes.iter().enumerate() {
-            let col = i % cols;
-            let row = i / cols;
-            
-            let (x, y) = if is_horizontal {
-                (start_x + (col as u32) * h_spacing, start_y + (row as u32) * v_spacing)
-            } else {
-                (start_x + (col as u32) * h_spacing, start_y + (i as u32) * v_spacing)
-            };
-            
-            node_positions.insert(node.id.clone(), (x, y));
-            node_shape_ids.insert(node.id.clone(), shape_id);
-            
-            let shape = create_node_shape(node, x, y, node_width, node_height, shape_id);
-            shapes.push(shape);
-            element_bounds.push((x, y, node_width, node_height));
-            shape_id += 1;
+    }
+    for (i, sg) in node_subgraph.iter().enumerate() {
+        if let Some(sg) = sg {
+            graph.set_cluster(i, *sg);
         }
     }
     
+    // Links keep their declaration index for `linkStyle`
+    let connections: Vec<(usize, &FlowConnection, usize, usize)> = flowchart.connections.iter()
+        .enumerate()
+        .filter_map(|(i, conn)| Some((i, conn, *node_index.get(conn.from.as_str())?, *node_index.get(conn.to.as_str())?)))
+        .collect();
+    for &(_, _, from, to) in &connections {
+        graph.add_edge(from, to);
+    }
+    
+    let mut layout = graph.layout();
+    layout.fit(DIAGRAM_AREA);
+    
+    // Track element positions for bounding box calculation
+    let mut element_bounds: Vec<(u32, u32, u32, u32)> = Vec::new();
+    let mut shape_id = 10u32; // Starting shape ID
+    
+    // Subgraph backgrounds (parents come before their children) and titles;
+    // nested subgraphs alternate fills so they stand out from their parent
+    for (sg_idx, subgraph) in flowchart.subgraphs.iter().enumerate() {
+        let Some(rect) = layout.clusters[sg_idx] else { continue };
+        let fill = if depth(sg_idx) % 2 == 0 { &theme.secondary_fill } else { &theme.node_fill };
+        let sg_shape = Shape::new(ShapeType::RoundedRectangle, rect.x, rect.y, rect.width, rect.height)
+            .with_fill(ShapeFill::new(fill))
+            .with_line(ShapeLine::new(&theme.muted, 1));
+        shapes.push(flowchart.styles.node_style(&subgraph.name).apply_to_shape(sg_shape));
+        element_bounds.push((rect.x, rect.y, rect.width, rect.height));
+        
+        let title_shape = Shape::new(ShapeType::Rectangle, rect.x + 50_000, rect.y + 25_000, rect.width.saturating_sub(100_000), layout.title_height)
+            .with_id(shape_id)
+            .with_text(&subgraph.name);
+        shapes.push(title_shape);
+        shape_id += 1;
+    }
+    
+    // Track shape IDs for connector anchoring
+    let mut node_shape_ids: Vec<u32> = Vec::with_capacity(flowchart.nodes.len());
+    for (node, rect) in flowchart.nodes.iter().zip(&layout.nodes) {
+        node_shape_ids.push(shape_id);
+        let shape = create_node_shape(node, rect.x, rect.y, rect.width, rect.height, shape_id, &theme);
+        shapes.push(flowchart.styles.node_style(&node.id).apply_to_shape(shape));
+        element_bounds.push((rect.x, rect.y, rect.width, rect.height));
+        shape_id += 1;
+    }
+    
     // Create connectors for connections with shape anchoring
-    for conn in &flowchart.connections {
-        if let (Some(&(from_x, from_y)), Some(&(to_x, to_y))) = 
-            (node_positions.get(&conn.from), node_positions.get(&conn.to)) 
-        {
-            // Get shape IDs for anchoring
-            let from_shape_id = node_shape_ids.get(&conn.from).copied();
-            let to_shape_id = node_shape_ids.get(&conn.to).copied();
-            
-            // Determine connection sites and positions based on relative positions
-            let (start_site, end_site, start_x, start_y, end_x, end_y) = if is_horizontal {
-                // Horizontal flow: connect Right -> Left
-                (ConnectionSite::Right, ConnectionSite::Left,
-                 from_x + node_width, from_y + node_height / 2,
-                 to_x, to_y + node_height / 2)
-            } else {
-                // Vertical flow: connect Bottom -> Top
-                (ConnectionSite::Bottom, ConnectionSite::Top,
-                 from_x + node_width / 2, from_y + node_height,
-                 to_x + node_width / 2, to_y)
-            };
-            
-            // Use elbow connector for better auto-routing when shapes are not aligned
-            let connector_type = if (start_x as i32 - end_x as i32).abs() < 100_000 
-                                 || (start_y as i32 - end_y as i32).abs() < 100_000 {
-                ConnectorType::Straight
-            } else {
-                ConnectorType::Elbow
-            };
-            
-            let (line_color, line_dash) = match conn.arrow_type {
-                ArrowStyle::Thick => ("E65100", LineDash::Solid),
-                ArrowStyle::Dotted => ("757575", LineDash::Dash),
-                ArrowStyle::Open => ("1565C0", LineDash::Solid),
-                ArrowStyle::Arrow => ("1565C0", LineDash::Solid),
-            };
-            
-            let mut connector = Connector::new(connector_type, start_x, start_y, end_x, end_y)
-                .with_line(ConnectorLine::new(line_color, 19050).with_dash(line_dash))
-                .with_end_arrow(ArrowType::Triangle);
-            
-            // Anchor connector to shapes for auto-routing
-            if let Some(from_id) = from_shape_id {
-                connector = connector.connect_start(from_id, start_site);
-            }
-            if let Some(to_id) = to_shape_id {
-                connector = connector.connect_end(to_id, end_site);
-            }
+    for ((link_index, conn, from, to), route) in connections.into_iter().zip(&layout.edges) {
+        let (start_x, start_y) = route.start();
+        let (end_x, end_y) = route.end();
+        
+        // Straight when aligned; elbow or curved connectors let PowerPoint route around nodes
+        let connector_type = if route.has_bends() {
+            ConnectorType::Curved
+        } else if start_x.abs_diff(end_x) < 100_000 || start_y.abs_diff(end_y) < 100_000 {
+            ConnectorType::Straight
+        } else {
+            ConnectorType::Elbow
+        };
+        
+        let (line_color, line_dash) = match conn.arrow_type {
+            ArrowStyle::Thick => (&theme.accent, LineDash::Solid),
+            ArrowStyle::Dotted => (&theme.muted, LineDash::Dash),
+            ArrowStyle::Open => (&theme.line, LineDash::Solid),
+            ArrowStyle::Arrow => (&theme.line, LineDash::Solid),
+        };
+        let line = ConnectorLine::new(line_color, 19050).with_dash(line_dash);
+        
+        let connector = Connector::new(connector_type, start_x, start_y, end_x, end_y)
+            .with_line(flowchart.styles.link_style(link_index).apply_to_line(line))
+            .with_end_arrow(ArrowType::Triangle)
+            .connect_start(node_shape_ids[from], route.start_site)
+            .connect_end(node_shape_ids[to], route.end_site);
+        
+        // Create separate label shape for better font control
+        if let Some(label) = &conn.label {
+            let label_width = 600_000u32;
+            let label_height = 250_000u32;
+            let (mid_x, mid_y) = route.midpoint();
+            let label_x = mid_x.saturating_sub(label_width / 2);
+            let label_y = mid_y.saturating_sub(label_height / 2);
             
-            // Create separate label shape for better font control
-            if let Some(label) = &conn.label {
-                let label_width = 600_000u32;
-                let label_height = 250_000u32;
-                let mid_x = (start_x + end_x) / 2;
-                let mid_y = (start_y + end_y) / 2;
-                
-                let label_shape = Shape::new(
-                    ShapeType::Rectangle,
-                    mid_x.saturating_sub(label_width / 2),
-                    mid_y.saturating_sub(label_height / 2),
-                    label_width,
-                    label_height
-                )
+            let label_shape = Shape::new(ShapeType::Rectangle, label_x, label_y, label_width, label_height)
                 .with_id(shape_id)
-                .with_fill(ShapeFill::new("FFFFFF"))
-                .with_line(ShapeLine::new("757575", 1))
+                .with_fill(ShapeFill::new(&theme.node_fill))
+                .with_line(ShapeLine::new(&theme.muted, 1))
                 .with_text(label);
-                
-                shapes.push(label_shape);
-                element_bounds.push((mid_x - label_width / 2, mid_y - label_height / 2, label_width, label_height));
-                shape_id += 1;
-            }
             
-            connectors.push(connector);
+            shapes.push(label_shape);
+            element_bounds.push((label_x, label_y, label_width, label_height));
+            shape_id += 1;
         }
+        
+        connectors.push(connector);
     }
     
     // Calculate bounding box for the entire diagram
@@ -380,17 +387,15 @@ pub fn generate_elements(flowchart: &Flowchart) -> DiagramElements {
     DiagramElements { 
         shapes, 
         connectors, 
+        charts: Vec::new(),
         bounds,
         grouped: true, // Flowcharts should be grouped
+        warnings: flowchart.warnings.clone(),
     }
 }
 
-fn get_subgraph_color(index: usize) -> &'static str {
-    const COLORS: [&str; 6] = ["E3F2FD", "F3E5F5", "E8F5E9", "FFF3E0", "E0F7FA", "FCE4EC"];
-    COLORS[index % COLORS.len()]
-}
-
-fn create_node_shape(node: &FlowNode, x: u32, y: u32, width: u32, height: u32, shape_id: u32) -> Shape {
+/// Shape for a node, filled by its kind
+pub fn create_node_shape(node: &FlowNode, x: u32, y: u32, width: u32, height: u32, shape_id: u32, theme: &DiagramTheme) -> Shape {
     let shape_type = match node.shape {
         NodeShape::Rectangle => ShapeType::Rectangle,
         NodeShape::RoundedRect => ShapeType::RoundedRectangle,
@@ -400,17 +405,15 @@ fn create_node_shape(node: &FlowNode, x: u32, y: u32, width: u32, height: u32, s
         NodeShape::Hexagon => ShapeType::Hexagon,
     };
     
-    let fill_color = match node.shape {
-        NodeShape::Diamond => "FFF3E0",
-        NodeShape::Circle => "E3F2FD",
-        _ => "FFFFFF",
-    };
-    
-    Shape::new(shape_type, x, y, width, height)
+    let shape = Shape::new(shape_type, x, y, width, height)
         .with_id(shape_id)
-        .with_fill(ShapeFill::new(fill_color))
-        .with_line(ShapeLine::new("1565C0", 2))
-        .with_text(&node.label)
+        .with_line(ShapeLine::new(&theme.stroke, 2))
+        .with_text(&node.label);
+    match node.shape {
+        NodeShape::Diamond => shape.with_fill(ShapeFill::new(&theme.primary_fill)),
+        NodeShape::Circle => shape.with_fill(ShapeFill::new(&theme.secondary_fill)),
+        _ => theme.style_text(shape.with_fill(ShapeFill::new(&theme.node_fill))),
+    }
 }
 
 #[cfg(test)]
@@ -420,10 +423,27 @@ mod tests {
     #[test]
     fn test_parse_flowchart_nodes() {
         let code = "flowchart LR\n    A[Start] --> B[Process] --> C[End]";
-        let flowchart = parse(code);
+        let flowchart = parse(code).unwrap();
         assert_eq!(flowchart.direction, FlowDirection::LeftToRight);
-        assert!(!flowchart.nodes.is_empty());
-        assert!(!flowchart.connections.is_empty());
+        assert_eq!(flowchart.nodes.len(), 3);
+        let links: Vec<(&str, &str)> = flowchart.connections.iter().map(|c| (c.from.as_str(), c.to.as_str())).collect();
+        assert_eq!(links, [("A", "B"), ("B", "C")]);
+        assert!(flowchart.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_flowchart_diagnostics() {
+        let code = "flowchart XY\n    A -->|yes| B ==> C\n    A -- maybe --> D\n    end\n    subgraph Open\n    B";
+        let flowchart = parse(code).unwrap();
+        assert_eq!(flowchart.connections.len(), 2);
+        assert_eq!(flowchart.connections[0].label.as_deref(), Some("yes"));
+        assert_eq!(flowchart.connections[1].arrow_type, ArrowStyle::Thick);
+
+        let positions: Vec<(usize, usize)> = flowchart.warnings.iter().map(|w| (w.line, w.column)).collect();
+        assert_eq!(positions, [(1, 11), (3, 5), (4, 5), (5, 5)]);
+        assert_eq!(flowchart.warnings[1].message, "invalid node `A -- maybe`");
+
+        assert_eq!(parse("flowchart LR\n    %% nothing").unwrap_err(), MermaidDiagnostic::empty("flowchart"));
     }
 
     #[test]
@@ -445,8 +465,93 @@ mod tests {
     #[test]
     fn test_generate_flowchart_shapes() {
         let code = "flowchart LR\n    A[Start] --> B[End]";
-        let flowchart = parse(code);
-        let elements = generate_elements(&flowchart);
+        let flowchart = parse(code).unwrap();
+        let elements = generate_elements(&flowchart, None);
         assert!(!elements.shapes.is_empty());
     }
+
+    #[test]
+    fn test_parse_nested_subgraphs() {
+        let code = "flowchart TB\n    subgraph Outer\n        A --> B\n        subgraph Inner\n            C\n        end\n    end\n    B --> C\n    C --> D";
+        let flowchart = parse(code).unwrap();
+        let ids: Vec<&str> = flowchart.nodes.iter().map(|n| n.id.as_str()).collect();
+        assert_eq!(ids, ["A", "B", "C", "D"]);
+        assert_eq!(flowchart.subgraphs.len(), 2);
+        assert_eq!(flowchart.subgraphs[0].parent, None);
+        assert_eq!(flowchart.subgraphs[1].parent, Some(0));
+        assert_eq!(flowchart.subgraphs[0].nodes, ["A", "B"]);
+        assert_eq!(flowchart.subgraphs[1].nodes, ["C"]);
+    }
+
+    #[test]
+    fn test_flowchart_styles() {
+        let code = "flowchart LR
+    classDef hot fill:#f96,stroke:#c00,color:#fff
+    A[Start]:::hot --> B[Work]
+    B -.-> C
+    style B fill:#eee,stroke-dasharray: 5 5
+    linkStyle 1 stroke:#0a0,stroke-width:3px";
+        let flowchart = parse(code).unwrap();
+        assert_eq!(flowchart.nodes.len(), 3);
+        assert!(flowchart.warnings.is_empty());
+        let elements = generate_elements(&flowchart, None);
+
+        let a = &elements.shapes[0];
+        assert_eq!(a.fill.as_ref().unwrap().color, "FF9966");
+        assert_eq!(a.line.as_ref().unwrap().color, "CC0000");
+        assert_eq!(a.text_color.as_deref(), Some("FFFFFF"));
+        let b = &elements.shapes[1];
+        assert_eq!(b.fill.as_ref().unwrap().color, "EEEEEE");
+        assert_eq!(b.line.as_ref().unwrap().dash, LineDash::Dash);
+
+        assert_eq!(elements.connectors[0].line.color, "1565C0");
+        assert_eq!(elements.connectors[1].line.color, "00AA00");
+        assert_eq!(elements.connectors[1].line.width, 28_575);
+        assert_eq!(elements.connectors[1].line.dash, LineDash::Dash);
+    }
+
+    #[test]
+    fn test_flowchart_style_diagnostics() {
+        let warnings = |code: &str| parse(code).unwrap().warnings;
+        assert_eq!(
+            warnings("flowchart LR\n    A --> B\n    style A fill:notacolor"),
+            [MermaidDiagnostic::new(3, 18, "`notacolor` is not a color")]
+        );
+        assert_eq!(
+            warnings("flowchart LR\n    A:::undefinedClass --> B"),
+            [MermaidDiagnostic::new(2, 9, "undefined class `undefinedClass`")]
+        );
+        assert_eq!(
+            warnings("flowchart LR\n    A --> B --> C\n    linkStyle 99 stroke:#f00"),
+            [MermaidDiagnostic::new(3, 15, "`linkStyle 99` is out of range, the diagram has 2 links")]
+        );
+    }
+
+    #[test]
+    fn test_flowchart_theme() {
+        let (theme, code) = super::super::style::split_directives("%%{init: {\"theme\": \"dark\"}}%%\ngraph TD\n    A --> B{Ok?}");
+        let elements = generate_elements(&parse(code).unwrap(), theme.as_ref());
+        let dark = crate::prelude::themes::DARK;
+        assert_eq!(elements.shapes[0].fill.as_ref().unwrap().color, dark.background);
+        assert_eq!(elements.shapes[1].fill.as_ref().unwrap().color, dark.primary);
+        assert_eq!(elements.connectors[0].line.color, dark.secondary);
+    }
+
+    #[test]
+    fn test_layered_flowchart_fits_slide() {
+        let mut code = String::from("flowchart LR\n");
+        for i in 0..12 {
+            code.push_str(&format!("    N{i} --> N{}\n", i + 1));
+        }
+        code.push_str("    N12 --> N0\n    N0 -->|skip| N6\n");
+        let elements = generate_elements(&parse(&code).unwrap(), None);
+        assert_eq!(elements.connectors.len(), 14);
+
+        let bounds = elements.bounds.unwrap();
+        assert!(bounds.x >= DIAGRAM_AREA.x && bounds.x + bounds.width <= DIAGRAM_AREA.x + DIAGRAM_AREA.width + 1);
+        assert!(bounds.y + bounds.height <= DIAGRAM_AREA.y + DIAGRAM_AREA.height + 1);
+
+        // Nodes follow the flow from left to right
+        assert!(elements.shapes[0].x < elements.shapes[1].x && elements.shapes[1].x < elements.shapes[12].x);
+    }
 }
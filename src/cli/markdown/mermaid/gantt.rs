@@ -1,94 +1,549 @@
 //! Gantt chart diagram parsing and rendering
+//!
+//! Rendered as a native stacked-bar timeline: a hidden series holds each
+//! task's start date and one visible series per section holds its duration,
+//! all as Excel date serials so the value axis reads as dates.
 
-use crate::generator::{Shape, ShapeType, ShapeFill};
-
-/// Generate shapes for a Gantt chart
-pub fn generate_shapes(code: &str) -> Vec<Shape> {
-    let mut shapes = Vec::new();
-    let mut title = String::new();
-    let mut sections: Vec<(String, Vec<(String, u32)>)> = Vec::new();
-    let mut current_section = String::new();
-    let mut current_tasks: Vec<(String, u32)> = Vec::new();
-    
-    for line in code.lines().skip(1) {
-        let line = line.trim();
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
+
+use crate::generator::charts::{Chart, ChartSeries, ChartType, ValueAxis};
+use super::layout::DIAGRAM_AREA;
+use super::types::{DiagramElements, MermaidDiagnostic, MermaidResult};
+
+const SECTION_COLORS: [&str; 6] = ["4472C4", "ED7D31", "70AD47", "FFC000", "5B9BD5", "8E7CC3"];
+const CRIT_COLOR: &str = "E53935";
+const DONE_COLOR: &str = "9E9E9E";
+const MILESTONE_COLOR: &str = "263238";
+
+/// Keywords of lines that configure the chart rather than add a task
+const KEYWORDS: [&str; 13] = [
+    "title", "dateFormat", "axisFormat", "tickInterval", "excludes", "includes", "todayMarker",
+    "weekday", "inclusiveEndDates", "topAxis", "displayMode", "accTitle", "accDescr",
+];
+
+/// A parsed Gantt task
+#[derive(Debug, Clone, PartialEq)]
+pub struct GanttTask {
+    pub name: String,
+    pub id: Option<String>,
+    /// Index into `Gantt::sections`
+    pub section: usize,
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+    pub done: bool,
+    pub active: bool,
+    pub crit: bool,
+    pub milestone: bool,
+}
+
+/// A parsed Gantt chart
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gantt {
+    pub title: String,
+    /// Mermaid (dayjs) date format of the task dates
+    pub date_format: String,
+    /// Mermaid (d3) date format of the axis labels
+    pub axis_format: Option<String>,
+    /// Axis tick interval in days
+    pub tick_interval: Option<f64>,
+    pub sections: Vec<String>,
+    pub tasks: Vec<GanttTask>,
+    /// Lines that were dropped or only partly understood
+    pub warnings: Vec<MermaidDiagnostic>,
+}
+
+/// Parse a Gantt chart
+pub fn parse(code: &str) -> MermaidResult<Gantt> {
+    let mut gantt = Gantt {
+        title: String::new(),
+        date_format: "YYYY-MM-DD".to_string(),
+        axis_format: None,
+        tick_interval: None,
+        sections: Vec::new(),
+        tasks: Vec::new(),
+        warnings: Vec::new(),
+    };
+    // Tasks with nothing to follow start at the earliest date in the chart
+    let anchor = earliest_date(code).unwrap_or_else(|| chrono::Local::now().date_naive().and_time(NaiveTime::MIN));
+
+    for (index, source) in code.lines().enumerate().skip(1) {
+        let line = source.trim();
         if line.is_empty() || line.starts_with("%%") {
             continue;
         }
-        
-        if line.starts_with("title") {
-            title = line.strip_prefix("title").unwrap_or("").trim().to_string();
-        } else if line.starts_with("section") {
-            if !current_section.is_empty() {
-                sections.push((current_section.clone(), current_tasks.clone()));
-                current_tasks.clear();
+
+        if let Some((keyword, value)) = split_keyword(line) {
+            match keyword {
+                "title" => gantt.title = value.to_string(),
+                "dateFormat" => gantt.date_format = value.to_string(),
+                "axisFormat" => gantt.axis_format = Some(value.to_string()),
+                "tickInterval" => {
+                    gantt.tick_interval = parse_interval(value);
+                    if gantt.tick_interval.is_none() {
+                        gantt.warnings.push(MermaidDiagnostic::at(index, source, value, format!("invalid tick interval `{value}`")));
+                    }
+                }
+                _ => {}
+            }
+        } else if let Some(section) = line.strip_prefix("section") {
+            gantt.sections.push(section.trim().to_string());
+        } else if let Some((name, meta)) = line.split_once(':') {
+            if gantt.sections.is_empty() {
+                gantt.sections.push(String::new());
+            }
+            let (task, problems) = parse_task(&gantt, name.trim(), meta, anchor);
+            for (token, message) in problems {
+                gantt.warnings.push(MermaidDiagnostic::at(index, source, token, message));
+            }
+            gantt.tasks.push(task);
+        } else {
+            gantt.warnings.push(MermaidDiagnostic::unsupported(index, source));
+        }
+    }
+
+    if gantt.tasks.is_empty() {
+        return Err(MermaidDiagnostic::empty("gantt chart"));
+    }
+    Ok(gantt)
+}
+
+/// Earliest task date written in the chart, in the `dateFormat` in effect
+fn earliest_date(code: &str) -> Option<NaiveDateTime> {
+    let mut date_format = "YYYY-MM-DD";
+    let mut earliest = None;
+    for line in code.lines().skip(1).map(str::trim) {
+        if let Some((keyword, value)) = split_keyword(line) {
+            if keyword == "dateFormat" {
+                date_format = value;
+            }
+        } else if let Some((_, meta)) = line.split_once(':') {
+            earliest = meta.split(',').filter_map(|item| parse_date(item, date_format)).chain(earliest).min();
+        }
+    }
+    earliest
+}
+
+/// Split a configuration line into its keyword and value
+fn split_keyword(line: &str) -> Option<(&str, &str)> {
+    KEYWORDS.iter().find_map(|&keyword| {
+        let rest = line.strip_prefix(keyword)?;
+        let value = rest.strip_prefix(':').or_else(|| rest.strip_prefix(char::is_whitespace).or(rest.is_empty().then_some("")))?;
+        Some((keyword, value.trim()))
+    })
+}
+
+/// Parse the metadata of a task: `[tags,] [id,] [start,] end`
+///
+/// Also returns the items that could not be resolved, with the reason.
+/// A task with neither a start nor a task before it starts at `anchor`.
+fn parse_task<'a>(gantt: &Gantt, name: &'a str, meta: &'a str, anchor: NaiveDateTime) -> (GanttTask, Vec<(&'a str, String)>) {
+    let mut items: Vec<&str> = meta.split(',').map(str::trim).filter(|s| !s.is_empty()).collect();
+    let (mut done, mut active, mut crit, mut milestone) = (false, false, false, false);
+    while let Some(&tag) = items.first() {
+        match tag {
+            "done" => done = true,
+            "active" => active = true,
+            "crit" => crit = true,
+            "milestone" => milestone = true,
+            _ => break,
+        }
+        items.remove(0);
+    }
+
+    let (id, start, end) = match items.as_slice() {
+        [] => (None, None, None),
+        [end] => (None, None, Some(*end)),
+        [start, end] => (None, Some(*start), Some(*end)),
+        [id, start, end, ..] => (Some(id.to_string()), Some(*start), Some(*end)),
+    };
+
+    let mut problems = Vec::new();
+    let previous_end = gantt.tasks.last().map(|t| t.end);
+    let start = start
+        .and_then(|s| {
+            let resolved = resolve_start(gantt, s);
+            if resolved.is_none() {
+                problems.push((s, format!("cannot resolve start `{s}` (dateFormat {})", gantt.date_format)));
+            }
+            resolved
+        })
+        .or(previous_end)
+        .unwrap_or(anchor);
+    let end = match end {
+        Some(e) => resolve_end(gantt, e, start).inspect(|&end| {
+            if end < start {
+                problems.push((e, format!("task `{name}` ends before it starts")));
+            }
+        }).unwrap_or_else(|| {
+            let message = if duration_parts(e).is_some() {
+                format!("duration `{e}` runs past the supported date range")
+            } else {
+                format!("cannot resolve end `{e}` (dateFormat {})", gantt.date_format)
+            };
+            problems.push((e, message));
+            next_day(start)
+        }),
+        None => {
+            problems.push((name, format!("task `{name}` has no duration or end date")));
+            next_day(start)
+        }
+    };
+
+    let task = GanttTask {
+        name: name.to_string(),
+        id,
+        section: gantt.sections.len() - 1,
+        start,
+        end: end.max(start),
+        done,
+        active,
+        crit,
+        milestone,
+    };
+    (task, problems)
+}
+
+/// Resolve a task start: a date or `after id...`
+fn resolve_start(gantt: &Gantt, value: &str) -> Option<NaiveDateTime> {
+    match value.strip_prefix("after ") {
+        Some(ids) => ids.split_whitespace().filter_map(|id| find_task(gantt, id)).map(|t| t.end).max(),
+        None => parse_date(value, &gantt.date_format),
+    }
+}
+
+/// Resolve a task end: a duration, a date or `until id...`
+fn resolve_end(gantt: &Gantt, value: &str, start: NaiveDateTime) -> Option<NaiveDateTime> {
+    if let Some(ids) = value.strip_prefix("until ") {
+        return ids.split_whitespace().filter_map(|id| find_task(gantt, id)).map(|t| t.start).min();
+    }
+    if duration_parts(value).is_some() {
+        return parse_duration(value).and_then(|duration| start.checked_add_signed(duration));
+    }
+    parse_date(value, &gantt.date_format)
+}
+
+/// Default end of a task without a usable one: a day after its start
+fn next_day(start: NaiveDateTime) -> NaiveDateTime {
+    start.checked_add_signed(Duration::days(1)).unwrap_or(start)
+}
+
+fn find_task<'a>(gantt: &'a Gantt, id: &str) -> Option<&'a GanttTask> {
+    gantt.tasks.iter().find(|t| t.id.as_deref() == Some(id))
+}
+
+/// Parse a Mermaid duration such as `3d`, `1.5h` or `2w`; `None` also for
+/// durations too long to represent
+pub fn parse_duration(value: &str) -> Option<Duration> {
+    let (amount, millis) = duration_parts(value)?;
+    let total = (amount * millis).round();
+    if total.abs() >= i64::MAX as f64 {
+        return None;
+    }
+    Duration::try_milliseconds(total as i64)
+}
+
+/// Amount and unit length in milliseconds of a duration
+fn duration_parts(value: &str) -> Option<(f64, f64)> {
+    let value = value.trim();
+    let split = value.find(|c: char| !c.is_ascii_digit() && c != '.')?;
+    let amount: f64 = value[..split].parse().ok()?;
+    let millis = match &value[split..] {
+        "ms" => 1.0,
+        "s" => 1_000.0,
+        "m" => 60_000.0,
+        "h" => 3_600_000.0,
+        "d" => 86_400_000.0,
+        "w" => 604_800_000.0,
+        _ => return None,
+    };
+    Some((amount, millis))
+}
+
+/// Parse a `tickInterval` such as `1week` into days
+fn parse_interval(value: &str) -> Option<f64> {
+    let split = value.find(|c: char| !c.is_ascii_digit())?;
+    let amount: f64 = value[..split].parse().ok()?;
+    let days = match &value[split..] {
+        "millisecond" => 1.0 / 86_400_000.0,
+        "second" => 1.0 / 86_400.0,
+        "minute" => 1.0 / 1_440.0,
+        "hour" => 1.0 / 24.0,
+        "day" => 1.0,
+        "week" => 7.0,
+        "month" => 30.0,
+        _ => return None,
+    };
+    Some(amount * days)
+}
+
+/// Parse a date written in a Mermaid (dayjs) `dateFormat`
+///
+/// A time-only format such as `HH:mm` gives a time on 1970-01-01.
+pub fn parse_date(value: &str, date_format: &str) -> Option<NaiveDateTime> {
+    let value = value.trim();
+    match date_format {
+        "X" => return chrono::DateTime::from_timestamp(value.parse().ok()?, 0).map(|dt| dt.naive_utc()),
+        "x" => return chrono::DateTime::from_timestamp_millis(value.parse().ok()?).map(|dt| dt.naive_utc()),
+        _ => {}
+    }
+    let format = chrono_format(date_format);
+    NaiveDateTime::parse_from_str(value, &format)
+        .ok()
+        .or_else(|| NaiveDate::parse_from_str(value, &format).ok().map(|d| d.and_time(NaiveTime::MIN)))
+        .or_else(|| NaiveTime::parse_from_str(value, &format).ok().map(|t| NaiveDate::default().and_time(t)))
+}
+
+/// Convert dayjs format tokens to a chrono format string
+fn chrono_format(date_format: &str) -> String {
+    const TOKENS: [(&str, &str); 14] = [
+        ("YYYY", "%Y"), ("YY", "%y"), ("MMMM", "%B"), ("MMM", "%b"), ("MM", "%m"), ("M", "%m"),
+        ("DD", "%d"), ("D", "%d"), ("HH", "%H"), ("H", "%H"), ("mm", "%M"), ("ss", "%S"),
+        ("A", "%p"), ("a", "%p"),
+    ];
+    let mut format = String::new();
+    let mut rest = date_format;
+    'outer: while let Some(c) = rest.chars().next() {
+        for (token, replacement) in TOKENS {
+            if let Some(after) = rest.strip_prefix(token) {
+                format.push_str(replacement);
+                rest = after;
+                continue 'outer;
             }
-            current_section = line.strip_prefix("section").unwrap_or("").trim().to_string();
-        } else if line.contains(':') && !line.starts_with("dateFormat") && !line.starts_with("axisFormat") {
-            if let Some((task_name, _rest)) = line.split_once(':') {
-                let duration = 3u32;
-                current_tasks.push((task_name.trim().to_string(), duration));
+        }
+        if c == '%' {
+            format.push('%');
+        }
+        format.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    format
+}
+
+/// Convert a Mermaid (d3) `axisFormat` to an Excel number format
+fn excel_number_format(axis_format: &str) -> String {
+    let mut format = String::new();
+    let mut chars = axis_format.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            if c.is_ascii_alphabetic() {
+                format.push('\\');
             }
+            format.push(c);
+            continue;
         }
+        let code = match chars.next() {
+            Some('Y') => "yyyy",
+            Some('y') => "yy",
+            Some('m') => "mm",
+            Some('d') => "dd",
+            Some('e') => "d",
+            Some('b') => "mmm",
+            Some('B') => "mmmm",
+            Some('a') => "ddd",
+            Some('A') => "dddd",
+            Some('H') => "hh",
+            Some('I') => "h",
+            Some('M') => "mm",
+            Some('S') => "ss",
+            Some('p') => "AM/PM",
+            Some('%') => "%",
+            _ => "",
+        };
+        format.push_str(code);
     }
-    
-    if !current_section.is_empty() {
-        sections.push((current_section, current_tasks));
-    }
-    
-    // Layout parameters
-    let start_x = 500_000u32;
-    let start_y = 1_600_000u32;
-    let section_height = 300_000u32;
-    let task_height = 250_000u32;
-    let task_spacing = 280_000u32;
-    let bar_width_per_unit = 600_000u32;
-    let label_width = 2_000_000u32;
-    
-    if !title.is_empty() {
-        let title_shape = Shape::new(ShapeType::Rectangle, start_x, start_y, 7_000_000, 400_000)
-            .with_text(&title);
-        shapes.push(title_shape);
-    }
-    
-    let mut y = start_y + 500_000;
-    let colors = ["4472C4", "ED7D31", "70AD47", "FFC000", "5B9BD5"];
-    
-    for (section_idx, (section_name, tasks)) in sections.iter().enumerate() {
-        let section_shape = Shape::new(ShapeType::Rectangle, start_x, y, 7_000_000, section_height)
-            .with_fill(ShapeFill::new("E0E0E0"))
-            .with_text(section_name);
-        shapes.push(section_shape);
-        y += section_height + 50_000;
-        
-        for (task_idx, (task_name, duration)) in tasks.iter().enumerate() {
-            let label_shape = Shape::new(ShapeType::Rectangle, start_x, y, label_width, task_height)
-                .with_text(task_name);
-            shapes.push(label_shape);
-            
-            let bar_x = start_x + label_width + 100_000 + (task_idx as u32) * 200_000;
-            let bar_width = duration * bar_width_per_unit;
-            let color = colors[(section_idx + task_idx) % colors.len()];
-            
-            let bar_shape = Shape::new(ShapeType::RoundedRectangle, bar_x, y, bar_width, task_height)
-                .with_fill(ShapeFill::new(color));
-            shapes.push(bar_shape);
-            
-            y += task_spacing;
+    format
+}
+
+/// Excel date serial (days since 1899-12-30)
+fn date_serial(date: NaiveDateTime) -> f64 {
+    let epoch = NaiveDate::from_ymd_opt(1899, 12, 30).unwrap_or_default().and_time(Default::default());
+    (date - epoch).num_milliseconds() as f64 / 86_400_000.0
+}
+
+/// Pick a tick interval that keeps the axis readable
+fn major_unit(span_days: f64) -> f64 {
+    match span_days {
+        s if s <= 1.0 => 1.0 / 24.0,
+        s if s <= 3.0 => 0.25,
+        s if s <= 10.0 => 1.0,
+        s if s <= 70.0 => 7.0,
+        s if s <= 140.0 => 14.0,
+        s if s <= 400.0 => 30.0,
+        s if s <= 1_500.0 => 90.0,
+        _ => 365.0,
+    }
+}
+
+/// Build the native timeline chart, filling the diagram area
+pub fn generate_chart(gantt: &Gantt) -> Chart {
+    let first = gantt.tasks.iter().map(|t| date_serial(t.start)).fold(f64::INFINITY, f64::min);
+    let last = gantt.tasks.iter().map(|t| date_serial(t.end)).fold(f64::NEG_INFINITY, f64::max);
+    let (min, max) = (first.floor(), last.ceil().max(first.floor() + 1.0));
+    // Milestones have no duration; give them a sliver centred on their date
+    let milestone_width = (max - min) / 150.0;
+
+    let categories = gantt.tasks.iter().map(|t| t.name.clone()).collect();
+    let starts = gantt.tasks
+        .iter()
+        .map(|t| if t.milestone { date_serial(t.start) - milestone_width / 2.0 } else { date_serial(t.start) })
+        .collect();
+
+    let mut chart = Chart::new(&gantt.title, ChartType::BarHorizontalStacked, categories, DIAGRAM_AREA.x, DIAGRAM_AREA.y, DIAGRAM_AREA.width, DIAGRAM_AREA.height)
+        .add_series(ChartSeries::new("Start", starts).hidden());
+
+    for (s, section) in gantt.sections.iter().enumerate() {
+        let durations = gantt.tasks
+            .iter()
+            .map(|t| match (t.section == s, t.milestone) {
+                (false, _) => 0.0,
+                (true, true) => milestone_width,
+                (true, false) => date_serial(t.end) - date_serial(t.start),
+            })
+            .collect();
+        let name = if section.is_empty() { "Tasks" } else { section };
+        let mut series = ChartSeries::new(name, durations).with_color(SECTION_COLORS[s % SECTION_COLORS.len()]);
+        for (i, task) in gantt.tasks.iter().enumerate().filter(|(_, t)| t.section == s) {
+            let color = match task {
+                t if t.milestone => MILESTONE_COLOR,
+                t if t.crit => CRIT_COLOR,
+                t if t.done => DONE_COLOR,
+                _ => continue,
+            };
+            series = series.with_point_color(i, color);
         }
+        chart = chart.add_series(series);
     }
-    
-    shapes
+
+    let number_format = gantt.axis_format.as_deref().map(excel_number_format).unwrap_or_else(|| "yyyy-mm-dd".to_string());
+    chart
+        .with_value_axis(ValueAxis {
+            min: Some(min),
+            max: Some(max),
+            major_unit: Some(gantt.tick_interval.unwrap_or_else(|| major_unit(max - min))),
+            number_format: Some(number_format),
+        })
+        .reverse_categories()
+        .legend(gantt.sections.len() > 1)
+}
+
+/// Generate the elements for a Gantt chart block
+pub fn generate_elements(code: &str) -> MermaidResult<DiagramElements> {
+    let gantt = parse(code)?;
+    Ok(DiagramElements::from_chart(generate_chart(&gantt)).with_warnings(gantt.warnings))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn date(y: i32, m: u32, d: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap().and_hms_opt(0, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_parse_dates_and_durations() {
+        assert_eq!(parse_date("2024-01-15", "YYYY-MM-DD"), Some(date(2024, 1, 15)));
+        assert_eq!(parse_date("15/01/24", "DD/MM/YY"), Some(date(2024, 1, 15)));
+        assert_eq!(parse_date("2024-01-15 13:30", "YYYY-MM-DD HH:mm"), Some(date(2024, 1, 15) + Duration::minutes(810)));
+        assert_eq!(parse_date("1705276800", "X"), Some(date(2024, 1, 15)));
+        assert_eq!(parse_date("3d", "YYYY-MM-DD"), None);
+        assert_eq!(parse_date("13:30", "HH:mm"), Some(date(1970, 1, 1) + Duration::minutes(810)));
+
+        assert_eq!(parse_duration("3d"), Some(Duration::days(3)));
+        assert_eq!(parse_duration("1.5h"), Some(Duration::minutes(90)));
+        assert_eq!(parse_duration("2w"), Some(Duration::days(14)));
+        assert_eq!(parse_duration("2024-01-15"), None);
+    }
+
+    #[test]
+    fn test_parse_tasks() {
+        let code = "gantt
+    title Release
+    dateFormat DD-MM-YYYY
+    axisFormat %d %b
+    section Design
+    Spec          :done, spec, 01-03-2024, 5d
+    Review        :crit, after spec, 2d
+    section Build
+    Code          :code, after spec, 10-03-2024
+    Ship          :milestone, after code, 0d";
+        let gantt = parse(code).unwrap();
+
+        assert_eq!(gantt.title, "Release");
+        assert_eq!(gantt.sections, vec!["Design", "Build"]);
+        let [spec, review, code, ship] = gantt.tasks.as_slice() else { panic!("expected 4 tasks") };
+        assert!(spec.done && review.crit && ship.milestone);
+        assert_eq!((spec.start, spec.end), (date(2024, 3, 1), date(2024, 3, 6)));
+        assert_eq!((review.start, review.end), (date(2024, 3, 6), date(2024, 3, 8)));
+        assert_eq!(code.id.as_deref(), Some("code"));
+        assert_eq!((code.section, code.start, code.end), (1, date(2024, 3, 6), date(2024, 3, 10)));
+        assert_eq!(ship.start, date(2024, 3, 10));
+        assert!(gantt.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_gantt_diagnostics() {
+        let code = "gantt\n    dateFormat YYYY-MM-DD\n    Spec : s1, 2024-13-40, 3d\n    Build : after nope, 2d\n    Test :\n    oops";
+        let gantt = parse(code).unwrap();
+        assert_eq!(gantt.tasks.len(), 3);
+        let warnings: Vec<(usize, usize)> = gantt.warnings.iter().map(|w| (w.line, w.column)).collect();
+        assert_eq!(warnings, [(3, 16), (4, 13), (5, 5), (6, 5)]);
+        assert_eq!(gantt.warnings[0].message, "cannot resolve start `2024-13-40` (dateFormat YYYY-MM-DD)");
+    }
+
+    #[test]
+    fn test_gantt_times_and_anchors() {
+        let code = "gantt\n    dateFormat HH:mm\n    Setup : 30m\n    Talk : talk, 09:00, 45m\n    Break : 10:00, 09:30";
+        let gantt = parse(code).unwrap();
+        let [setup, talk, pause] = gantt.tasks.as_slice() else { panic!("expected 3 tasks") };
+        let nine = date(1970, 1, 1) + Duration::hours(9);
+        assert_eq!((setup.start, setup.end), (nine, nine + Duration::minutes(30)));
+        assert_eq!((talk.start, talk.end), (nine, nine + Duration::minutes(45)));
+        assert_eq!(pause.end, pause.start);
+        let warnings: Vec<(usize, usize)> = gantt.warnings.iter().map(|w| (w.line, w.column)).collect();
+        assert_eq!(warnings, [(5, 20)]);
+        assert_eq!(gantt.warnings[0].message, "task `Break` ends before it starts");
+    }
+
+    #[test]
+    fn test_huge_durations() {
+        assert!(parse_duration("99999999999999999999999d").is_none());
+        let code = "gantt\n    dateFormat YYYY-MM-DD\n    T : a, 2024-01-01, 99999999d\n    U : 99999999999999999999999w";
+        let gantt = parse(code).unwrap();
+        assert_eq!(gantt.tasks[0].end, date(2024, 1, 2));
+        let warnings: Vec<(usize, usize)> = gantt.warnings.iter().map(|w| (w.line, w.column)).collect();
+        assert_eq!(warnings, [(3, 24), (4, 9)]);
+        assert_eq!(gantt.warnings[0].message, "duration `99999999d` runs past the supported date range");
+        assert!(generate_elements(code).is_ok());
+    }
+
+    #[test]
+    fn test_generate_timeline_chart() {
+        let code = "gantt\n    title Project\n    axisFormat %Y-%m-%d\n    section Phase 1\n    Task A : a1, 2024-01-01, 30d\n    section Phase 2\n    Task B : crit, after a1, 1w";
+        let elements = generate_elements(code).unwrap();
+        let chart = &elements.charts[0];
+
+        assert_eq!(chart.chart_type, ChartType::BarHorizontalStacked);
+        assert_eq!(chart.categories, vec!["Task A", "Task B"]);
+        assert_eq!(chart.series.len(), 3);
+        assert!(chart.series[0].hidden);
+        assert_eq!(chart.series[0].values, vec![45292.0, 45322.0]);
+        assert_eq!(chart.series[1].values, vec![30.0, 0.0]);
+        assert_eq!(chart.series[2].values, vec![0.0, 7.0]);
+        assert_eq!(chart.series[2].point_colors[1].as_deref(), Some(CRIT_COLOR));
+        assert_eq!(chart.value_axis.min, Some(45292.0));
+        assert_eq!(chart.value_axis.max, Some(45329.0));
+        assert_eq!(chart.value_axis.major_unit, Some(7.0));
+        assert_eq!(chart.value_axis.number_format.as_deref(), Some("yyyy-mm-dd"));
+        assert!(chart.reverse_categories && chart.show_legend);
+
+        assert!(generate_elements("gantt\n    title Empty").is_err());
+    }
+
     #[test]
-    fn test_generate_gantt_shapes() {
-        let code = "gantt\n    title Project\n    section Phase 1\n    Task A : a1, 2024-01-01, 30d";
-        let shapes = generate_shapes(code);
-        assert!(!shapes.is_empty());
+    fn test_axis_format() {
+        assert_eq!(excel_number_format("%d %b"), "dd mmm");
+        assert_eq!(excel_number_format("W%W"), "\\W");
+        assert_eq!(chrono_format("YYYY-MM-DD HH:mm"), "%Y-%m-%d %H:%M");
     }
 }
@@ -1,19 +1,20 @@
 //! Git graph diagram rendering
 
-use crate::generator::{Shape, ShapeType, ShapeFill, ShapeLine};
-use super::types::{create_labeled_dot, LabelPosition};
+use crate::generator::{Shape, ShapeType, ShapeFill};
+use super::types::{create_labeled_dot, DiagramElements, LabelPosition, MermaidDiagnostic, MermaidResult};
 
 /// Generate shapes for a git graph
-pub fn generate_shapes(code: &str) -> Vec<Shape> {
+pub fn generate_elements(code: &str) -> MermaidResult<DiagramElements> {
     let mut shapes = Vec::new();
+    let mut warnings = Vec::new();
     let mut commits: Vec<(String, String, u32)> = Vec::new(); // (id, branch, position)
     let mut branches: Vec<String> = vec!["main".to_string()];
     let mut current_branch = "main".to_string();
     let mut commit_count = 0u32;
     
-    for line in code.lines().skip(1) {
+    for (index, line) in code.lines().enumerate().skip(1) {
         let trimmed = line.trim();
-        if trimmed.is_empty() {
+        if trimmed.is_empty() || trimmed.starts_with("%%") {
             continue;
         }
         
@@ -32,23 +33,38 @@ pub fn generate_shapes(code: &str) -> Vec<Shape> {
             commits.push((id, current_branch.clone(), commit_count));
             commit_count += 1;
         } else if trimmed.starts_with("branch") {
+            // Creating a branch also checks it out
             let branch_name = trimmed.strip_prefix("branch").unwrap_or("").trim().to_string();
             if !branch_name.is_empty() && !branches.contains(&branch_name) {
-                branches.push(branch_name);
+                branches.push(branch_name.clone());
+                current_branch = branch_name;
+            } else {
+                warnings.push(MermaidDiagnostic::at(index, line, "", format!("branch `{branch_name}` is empty or already exists")));
             }
-        } else if trimmed.starts_with("checkout") {
-            let branch_name = trimmed.strip_prefix("checkout").unwrap_or("").trim().to_string();
-            if !branch_name.is_empty() {
+        } else if trimmed.starts_with("checkout") || trimmed.starts_with("switch") {
+            let branch_name = trimmed.trim_start_matches("checkout").trim_start_matches("switch").trim().to_string();
+            if branches.contains(&branch_name) {
                 current_branch = branch_name;
+            } else {
+                warnings.push(MermaidDiagnostic::at(index, line, &branch_name, format!("checkout of unknown branch `{branch_name}`")));
             }
         } else if trimmed.starts_with("merge") {
             // Merge creates a commit on current branch
-            let merge_from = trimmed.strip_prefix("merge").unwrap_or("").trim();
+            let merge_from = trimmed.strip_prefix("merge").unwrap_or("").split_whitespace().next().unwrap_or("");
+            if !branches.iter().any(|b| b == merge_from) {
+                warnings.push(MermaidDiagnostic::at(index, line, merge_from, format!("merge of unknown branch `{merge_from}`")));
+            }
             commits.push((format!("Merge {}", merge_from), current_branch.clone(), commit_count));
             commit_count += 1;
+        } else {
+            warnings.push(MermaidDiagnostic::unsupported(index, line));
         }
     }
     
+    if commits.is_empty() {
+        return Err(MermaidDiagnostic::empty("git graph"));
+    }
+    
     // Layout constants
     let start_x = 1_000_000u32;
     let start_y = 2_000_000u32;
@@ -89,17 +105,7 @@ pub fn generate_shapes(code: &str) -> Vec<Shape> {
         shapes.extend(create_labeled_dot(x, y, commit_size, "FFFFFF", Some(color), id, LabelPosition::Above));
     }
     
-    // If no commits, add placeholder
-    if commits.is_empty() {
-        shapes.push(
-            Shape::new(ShapeType::Rectangle, 1_000_000, 2_000_000, 7_000_000, 3_000_000)
-                .with_fill(ShapeFill::new("ECEFF1"))
-                .with_line(ShapeLine::new("607D8B", 25400))
-                .with_text("Git Graph")
-        );
-    }
-    
-    shapes
+    Ok(DiagramElements::from_shapes(shapes).with_warnings(warnings))
 }
 
 #[cfg(test)]
@@ -109,14 +115,23 @@ mod tests {
     #[test]
     fn test_generate_gitgraph() {
         let code = "gitGraph\n    commit id: \"Initial\"\n    branch feature\n    checkout feature\n    commit id: \"Feature\"\n    checkout main\n    merge feature";
-        let shapes = generate_shapes(code);
-        assert!(!shapes.is_empty());
+        let elements = generate_elements(code).unwrap();
+        assert!(!elements.shapes.is_empty());
+        assert!(elements.warnings.is_empty());
     }
 
     #[test]
     fn test_gitgraph_empty() {
+        // Reported so the caller can show its placeholder
         let code = "gitGraph";
-        let shapes = generate_shapes(code);
-        assert!(!shapes.is_empty()); // Should have placeholder
+        assert!(generate_elements(code).is_err());
+    }
+
+    #[test]
+    fn test_gitgraph_diagnostics() {
+        let code = "gitGraph\n    commit\n    checkout develop\n    cherry-pick id: \"A\"";
+        let elements = generate_elements(code).unwrap();
+        let warnings: Vec<(usize, usize)> = elements.warnings.iter().map(|w| (w.line, w.column)).collect();
+        assert_eq!(warnings, [(3, 14), (4, 5)]);
     }
 }
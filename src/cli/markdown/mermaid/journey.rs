@@ -1,23 +1,25 @@
 //! Journey diagram (user journey) rendering
 
 use crate::generator::{Shape, ShapeType, ShapeFill, ShapeLine};
+use super::types::{DiagramElements, MermaidDiagnostic, MermaidResult};
 
 /// Generate shapes for a journey diagram
-pub fn generate_shapes(code: &str) -> Vec<Shape> {
+pub fn generate_elements(code: &str) -> MermaidResult<DiagramElements> {
     let mut shapes = Vec::new();
+    let mut warnings = Vec::new();
     let mut sections: Vec<(String, Vec<(String, u8)>)> = Vec::new();
     let mut current_section = String::new();
     let mut current_items: Vec<(String, u8)> = Vec::new();
     
-    for line in code.lines().skip(1) {
+    for (index, line) in code.lines().enumerate().skip(1) {
         let trimmed = line.trim();
-        if trimmed.is_empty() || trimmed.starts_with("title") {
+        if trimmed.is_empty() || trimmed.starts_with("%%") || trimmed.starts_with("title") {
             continue;
         }
         
         if trimmed.starts_with("section") {
-            // Save previous section
-            if !current_section.is_empty() {
+            // Save previous section (tasks before the first section form an unnamed one)
+            if !current_section.is_empty() || !current_items.is_empty() {
                 sections.push((current_section.clone(), current_items.clone()));
                 current_items.clear();
             }
@@ -25,18 +27,28 @@ pub fn generate_shapes(code: &str) -> Vec<Shape> {
         } else if trimmed.contains(':') {
             // Parse item: "Task name: score: Actor"
             let parts: Vec<&str> = trimmed.split(':').collect();
-            if parts.len() >= 2 {
-                let task = parts[0].trim().to_string();
-                let score = parts[1].trim().parse::<u8>().unwrap_or(3);
-                current_items.push((task, score));
-            }
+            let task = parts[0].trim().to_string();
+            let score = parts[1].trim();
+            let score = match score.parse::<u8>() {
+                Ok(score @ 1..=5) => score,
+                _ => {
+                    warnings.push(MermaidDiagnostic::at(index, line, score, format!("score `{score}` is not between 1 and 5, using 3")));
+                    3
+                }
+            };
+            current_items.push((task, score));
+        } else {
+            warnings.push(MermaidDiagnostic::unsupported(index, line));
         }
     }
     
     // Save last section
-    if !current_section.is_empty() {
+    if !current_section.is_empty() || !current_items.is_empty() {
         sections.push((current_section, current_items));
     }
+    if sections.is_empty() {
+        return Err(MermaidDiagnostic::empty("user journey"));
+    }
     
     // Layout constants
     let start_x = 500_000u32;
@@ -81,17 +93,7 @@ pub fn generate_shapes(code: &str) -> Vec<Shape> {
         x += section_width + section_gap;
     }
     
-    // If no sections parsed, create placeholder
-    if shapes.is_empty() {
-        shapes.push(
-            Shape::new(ShapeType::Rectangle, 1_000_000, 2_000_000, 7_000_000, 3_000_000)
-                .with_fill(ShapeFill::new("F3E5F5"))
-                .with_line(ShapeLine::new("7B1FA2", 25400))
-                .with_text("User Journey Diagram")
-        );
-    }
-    
-    shapes
+    Ok(DiagramElements::from_shapes(shapes).with_warnings(warnings))
 }
 
 #[cfg(test)]
@@ -101,14 +103,25 @@ mod tests {
     #[test]
     fn test_generate_journey() {
         let code = "journey\n    title User Journey\n    section Discovery\n      Find product: 3: User\n    section Purchase\n      Add to cart: 4: User";
-        let shapes = generate_shapes(code);
-        assert!(!shapes.is_empty());
+        let elements = generate_elements(code).unwrap();
+        assert!(!elements.shapes.is_empty());
+        assert!(elements.warnings.is_empty());
     }
 
     #[test]
     fn test_journey_empty() {
+        // Reported so the caller can show its placeholder
         let code = "journey";
-        let shapes = generate_shapes(code);
-        assert!(!shapes.is_empty()); // Should have placeholder
+        assert!(generate_elements(code).is_err());
+    }
+
+    #[test]
+    fn test_journey_diagnostics() {
+        let code = "journey\n    Sign up: 9: User\n    section Use\n      Browse";
+        let elements = generate_elements(code).unwrap();
+        // The task before the first section is kept
+        assert_eq!(elements.shapes[1].text.as_deref(), Some("Sign up (3)"));
+        let warnings: Vec<(usize, usize)> = elements.warnings.iter().map(|w| (w.line, w.column)).collect();
+        assert_eq!(warnings, [(2, 14), (4, 7)]);
     }
 }
@@ -0,0 +1,793 @@
+//! Layered (Sugiyama-style) graph layout
+//!
+//! Shared by the flowchart, state, class and ER generators. The layout runs in
+//! four phases: cycle breaking, rank assignment, crossing minimisation and
+//! coordinate assignment. Edges spanning several ranks are split with dummy
+//! nodes so they get their own lane and bend points. Clusters (subgraphs) may
+//! nest and are kept contiguous within every rank.
+
+use std::collections::VecDeque;
+use crate::generator::connectors::ConnectionSite;
+use super::types::{DiagramBounds, FlowDirection};
+
+/// Slide area diagrams are scaled into (below the title, inside the margins)
+pub const DIAGRAM_AREA: DiagramBounds = DiagramBounds {
+    x: 457_200,
+    y: 1_300_000,
+    width: 8_229_600,
+    height: 5_200_000,
+};
+
+/// Number of down/up sweeps of the crossing minimisation
+const ORDER_SWEEPS: usize = 8;
+/// Number of down/up sweeps of the coordinate assignment
+const POSITION_SWEEPS: usize = 4;
+
+/// Route of an edge: start point, bend points, end point
+#[derive(Debug, Clone)]
+pub struct EdgeRoute {
+    pub points: Vec<(u32, u32)>,
+    /// Side of the source node the edge leaves from
+    pub start_site: ConnectionSite,
+    /// Side of the target node the edge enters
+    pub end_site: ConnectionSite,
+}
+
+impl EdgeRoute {
+    /// First point of the route
+    pub fn start(&self) -> (u32, u32) {
+        self.points[0]
+    }
+
+    /// Last point of the route
+    pub fn end(&self) -> (u32, u32) {
+        self.points[self.points.len() - 1]
+    }
+
+    /// Middle of the route, for edge labels
+    pub fn midpoint(&self) -> (u32, u32) {
+        let n = self.points.len();
+        if n % 2 == 1 {
+            self.points[n / 2]
+        } else {
+            let (a, b) = (self.points[n / 2 - 1], self.points[n / 2]);
+            ((a.0 + b.0) / 2, (a.1 + b.1) / 2)
+        }
+    }
+
+    /// Whether the route passes through dummy nodes
+    pub fn has_bends(&self) -> bool {
+        self.points.len() > 2
+    }
+}
+
+/// Result of a layout: boxes for nodes and clusters, routes for edges
+#[derive(Debug, Clone)]
+pub struct Layout {
+    /// Node boxes, in insertion order
+    pub nodes: Vec<DiagramBounds>,
+    /// Cluster boxes, in insertion order (`None` for empty clusters)
+    pub clusters: Vec<Option<DiagramBounds>>,
+    /// Edge routes, in insertion order
+    pub edges: Vec<EdgeRoute>,
+    /// Height reserved for cluster titles
+    pub title_height: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Layout {
+    /// Scale the layout down to fit an area (never up) and center it there
+    pub fn fit(&mut self, area: DiagramBounds) {
+        let scale = if self.width == 0 || self.height == 0 {
+            1.0
+        } else {
+            (area.width as f64 / self.width as f64)
+                .min(area.height as f64 / self.height as f64)
+                .min(1.0)
+        };
+        let offset_x = area.x as f64 + (area.width as f64 - self.width as f64 * scale).max(0.0) / 2.0;
+        let offset_y = area.y as f64 + (area.height as f64 - self.height as f64 * scale).max(0.0) / 2.0;
+        let point = |(x, y): (u32, u32)| {
+            (
+                (offset_x + x as f64 * scale).round() as u32,
+                (offset_y + y as f64 * scale).round() as u32,
+            )
+        };
+        let rect = |r: &DiagramBounds| {
+            let (x, y) = point((r.x, r.y));
+            DiagramBounds {
+                x,
+                y,
+                width: (r.width as f64 * scale).round() as u32,
+                height: (r.height as f64 * scale).round() as u32,
+            }
+        };
+
+        self.nodes = self.nodes.iter().map(rect).collect();
+        self.clusters = self.clusters.iter().map(|c| c.as_ref().map(rect)).collect();
+        for edge in &mut self.edges {
+            edge.points = edge.points.iter().map(|&p| point(p)).collect();
+        }
+        self.title_height = (self.title_height as f64 * scale).round() as u32;
+        self.width = (self.width as f64 * scale).round() as u32;
+        self.height = (self.height as f64 * scale).round() as u32;
+    }
+}
+
+/// Graph to lay out
+#[derive(Debug, Clone)]
+pub struct LayoutGraph {
+    direction: FlowDirection,
+    sizes: Vec<(u32, u32)>,
+    node_clusters: Vec<Option<usize>>,
+    cluster_parents: Vec<Option<usize>>,
+    edges: Vec<(usize, usize)>,
+    node_spacing: u32,
+    rank_spacing: u32,
+    cluster_padding: u32,
+    title_height: u32,
+}
+
+impl LayoutGraph {
+    /// Create an empty graph laid out in the given direction
+    pub fn new(direction: FlowDirection) -> Self {
+        Self {
+            direction,
+            sizes: Vec::new(),
+            node_clusters: Vec::new(),
+            cluster_parents: Vec::new(),
+            edges: Vec::new(),
+            node_spacing: 400_000,
+            rank_spacing: 600_000,
+            cluster_padding: 150_000,
+            title_height: 250_000,
+        }
+    }
+
+    /// Set the gap between nodes of a rank and the gap between ranks
+    pub fn with_spacing(mut self, node_spacing: u32, rank_spacing: u32) -> Self {
+        self.node_spacing = node_spacing;
+        self.rank_spacing = rank_spacing;
+        self
+    }
+
+    /// Add a node, returning its index
+    pub fn add_node(&mut self, width: u32, height: u32) -> usize {
+        self.sizes.push((width, height));
+        self.node_clusters.push(None);
+        self.sizes.len() - 1
+    }
+
+    /// Add a cluster, optionally nested in another, returning its index
+    pub fn add_cluster(&mut self, parent: Option<usize>) -> usize {
+        self.cluster_parents.push(parent);
+        self.cluster_parents.len() - 1
+    }
+
+    /// Put a node in a cluster
+    pub fn set_cluster(&mut self, node: usize, cluster: usize) {
+        self.node_clusters[node] = Some(cluster);
+    }
+
+    /// Add a directed edge between two nodes
+    pub fn add_edge(&mut self, from: usize, to: usize) {
+        self.edges.push((from, to));
+    }
+
+    fn is_horizontal(&self) -> bool {
+        matches!(self.direction, FlowDirection::LeftToRight | FlowDirection::RightToLeft)
+    }
+
+    /// Clusters containing a cluster, outermost first
+    fn cluster_path(&self, cluster: Option<usize>) -> Vec<usize> {
+        let mut path = Vec::new();
+        let mut current = cluster;
+        while let Some(c) = current {
+            if path.contains(&c) {
+                break;
+            }
+            path.push(c);
+            current = self.cluster_parents[c];
+        }
+        path.reverse();
+        path
+    }
+
+    /// Compute the layout
+    pub fn layout(&self) -> Layout {
+        let n = self.sizes.len();
+        let horizontal = self.is_horizontal();
+
+        // Phase 1: break cycles by reversing DFS back edges
+        let reversed = self.back_edges();
+        let acyclic: Vec<(usize, usize)> = self.edges.iter().enumerate()
+            .filter(|(_, (from, to))| from != to)
+            .map(|(i, &(from, to))| if reversed[i] { (to, from) } else { (from, to) })
+            .collect();
+
+        // Phase 2: longest-path ranking
+        let ranks = longest_path_ranks(n, &acyclic);
+
+        // Dummy nodes split edges spanning more than one rank
+        let mut paths: Vec<Vec<usize>> = (0..n).map(|v| self.cluster_path(self.node_clusters[v])).collect();
+        let mut rank_of = ranks.clone();
+        let mut breadth: Vec<f64> = self.sizes.iter()
+            .map(|&(w, h)| if horizontal { h } else { w } as f64)
+            .collect();
+        let mut depth: Vec<f64> = self.sizes.iter()
+            .map(|&(w, h)| if horizontal { w } else { h } as f64)
+            .collect();
+        let mut up: Vec<Vec<usize>> = vec![Vec::new(); n];
+        let mut down: Vec<Vec<usize>> = vec![Vec::new(); n];
+        let mut chains: Vec<Vec<usize>> = Vec::with_capacity(self.edges.len());
+
+        for (i, &(from, to)) in self.edges.iter().enumerate() {
+            if from == to {
+                chains.push(vec![from]);
+                continue;
+            }
+            let (u, v) = if reversed[i] { (to, from) } else { (from, to) };
+            let common: Vec<usize> = paths[u].iter().zip(&paths[v])
+                .take_while(|(a, b)| a == b)
+                .map(|(a, _)| *a)
+                .collect();
+            let mut chain = vec![u];
+            for r in ranks[u] + 1..ranks[v] {
+                let dummy = rank_of.len();
+                rank_of.push(r);
+                paths.push(common.clone());
+                breadth.push(0.0);
+                depth.push(0.0);
+                up.push(Vec::new());
+                down.push(Vec::new());
+                chain.push(dummy);
+            }
+            chain.push(v);
+            for pair in chain.windows(2) {
+                down[pair[0]].push(pair[1]);
+                up[pair[1]].push(pair[0]);
+            }
+            if reversed[i] {
+                chain.reverse();
+            }
+            chains.push(chain);
+        }
+
+        let rank_count = rank_of.iter().max().map_or(0, |r| r + 1);
+        let mut layers: Vec<Vec<usize>> = vec![Vec::new(); rank_count];
+        for (v, &r) in rank_of.iter().enumerate() {
+            layers[r].push(v);
+        }
+
+        // Phase 3: crossing minimisation
+        let layers = self.minimise_crossings(layers, &up, &down, &paths);
+
+        // Phase 4: coordinates, first along the ranks then across them
+        let (rank_margin, layer_margin) = if horizontal {
+            (self.cluster_padding as f64, (self.cluster_padding + self.title_height) as f64)
+        } else {
+            ((self.cluster_padding + self.title_height) as f64, self.cluster_padding as f64)
+        };
+        let across = self.assign_positions(&layers, &up, &down, &paths, &breadth, layer_margin);
+        let along = self.assign_rank_positions(&layers, &paths, &depth, rank_margin);
+
+        // Map to slide axes
+        let flip = |value: f64| match self.direction {
+            FlowDirection::BottomToTop | FlowDirection::RightToLeft => -value,
+            _ => value,
+        };
+        let center = |v: usize| -> (f64, f64) {
+            let (p, q) = (across[v], flip(along[v]));
+            if horizontal { (q, p) } else { (p, q) }
+        };
+
+        let node_rects: Vec<(f64, f64, f64, f64)> = (0..n).map(|v| {
+            let (cx, cy) = center(v);
+            let (w, h) = self.sizes[v];
+            (cx - w as f64 / 2.0, cy - h as f64 / 2.0, w as f64, h as f64)
+        }).collect();
+
+        // Clusters: children are handled before their parents so boxes nest
+        let cluster_count = self.cluster_parents.len();
+        let mut cluster_rects: Vec<Option<(f64, f64, f64, f64)>> = vec![None; cluster_count];
+        let mut order: Vec<usize> = (0..cluster_count).collect();
+        order.sort_by_key(|&c| std::cmp::Reverse(self.cluster_path(Some(c)).len()));
+        let padding = self.cluster_padding as f64;
+        for c in order {
+            let mut members: Vec<(f64, f64, f64, f64)> = (0..n)
+                .filter(|&v| paths[v].contains(&c))
+                .map(|v| node_rects[v])
+                .collect();
+            members.extend((0..cluster_count)
+                .filter(|&child| self.cluster_parents[child] == Some(c) && child != c)
+                .filter_map(|child| cluster_rects[child]));
+            if let Some((x, y, r, b)) = union(&members) {
+                let top = y - padding - self.title_height as f64;
+                cluster_rects[c] = Some((x - padding, top, r - x + 2.0 * padding, b - top + padding));
+            }
+        }
+
+        // Edge routes through the dummy nodes
+        let route_points: Vec<Vec<(f64, f64)>> = chains.iter().map(|chain| {
+            chain.iter().map(|&v| center(v)).collect()
+        }).collect();
+
+        // Normalise so the top-left corner is at the origin
+        let mut all: Vec<(f64, f64, f64, f64)> = node_rects.clone();
+        all.extend(cluster_rects.iter().flatten().copied());
+        all.extend(route_points.iter().flatten().map(|&(x, y)| (x, y, 0.0, 0.0)));
+        let (min_x, min_y, max_x, max_y) = union(&all).unwrap_or((0.0, 0.0, 0.0, 0.0));
+        let to_bounds = |(x, y, w, h): (f64, f64, f64, f64)| DiagramBounds {
+            x: (x - min_x).round() as u32,
+            y: (y - min_y).round() as u32,
+            width: w.round() as u32,
+            height: h.round() as u32,
+        };
+
+        let nodes: Vec<DiagramBounds> = node_rects.into_iter().map(to_bounds).collect();
+        let clusters = cluster_rects.into_iter().map(|r| r.map(to_bounds)).collect();
+        let edges = self.edges.iter().zip(route_points).map(|(&(from, to), points)| {
+            let points: Vec<(u32, u32)> = points.into_iter()
+                .map(|(x, y)| ((x - min_x).round() as u32, (y - min_y).round() as u32))
+                .collect();
+            self.route(&nodes[from], &nodes[to], points)
+        }).collect();
+
+        Layout {
+            nodes,
+            clusters,
+            edges,
+            title_height: self.title_height,
+            width: (max_x - min_x).round() as u32,
+            height: (max_y - min_y).round() as u32,
+        }
+    }
+
+    /// Edges closing a cycle, found with a depth-first search in insertion order
+    fn back_edges(&self) -> Vec<bool> {
+        let n = self.sizes.len();
+        let mut outgoing: Vec<Vec<usize>> = vec![Vec::new(); n];
+        for (i, &(from, to)) in self.edges.iter().enumerate() {
+            if from != to {
+                outgoing[from].push(i);
+            }
+        }
+
+        // 0 = unvisited, 1 = on the DFS stack, 2 = done
+        let mut state = vec![0u8; n];
+        let mut reversed = vec![false; self.edges.len()];
+        for root in 0..n {
+            if state[root] != 0 {
+                continue;
+            }
+            let mut stack: Vec<(usize, usize)> = vec![(root, 0)];
+            state[root] = 1;
+            while let Some(top) = stack.last_mut() {
+                let v = top.0;
+                if let Some(&edge) = outgoing[v].get(top.1) {
+                    top.1 += 1;
+                    let target = self.edges[edge].1;
+                    match state[target] {
+                        0 => {
+                            state[target] = 1;
+                            stack.push((target, 0));
+                        }
+                        1 => reversed[edge] = true,
+                        _ => {}
+                    }
+                } else {
+                    state[v] = 2;
+                    stack.pop();
+                }
+            }
+        }
+        reversed
+    }
+
+    /// Reorder every rank by barycenter, keeping the ordering with fewest crossings
+    fn minimise_crossings(
+        &self,
+        mut layers: Vec<Vec<usize>>,
+        up: &[Vec<usize>],
+        down: &[Vec<usize>],
+        paths: &[Vec<usize>],
+    ) -> Vec<Vec<usize>> {
+        let mut position = vec![0usize; up.len()];
+        let index = |layers: &[Vec<usize>], position: &mut [usize]| {
+            for layer in layers {
+                for (i, &v) in layer.iter().enumerate() {
+                    position[v] = i;
+                }
+            }
+        };
+
+        // Keep clusters contiguous from the start
+        index(&layers, &mut position);
+        for layer in &mut layers {
+            let keys: Vec<f64> = layer.iter().map(|&v| position[v] as f64).collect();
+            reorder(layer, &keys, paths);
+        }
+        index(&layers, &mut position);
+
+        let mut best = layers.clone();
+        let mut best_crossings = count_crossings(&layers, down, &position);
+        for sweep in 0..ORDER_SWEEPS {
+            let downward = sweep % 2 == 0;
+            let ranks: Vec<usize> = if downward {
+                (1..layers.len()).collect()
+            } else {
+                (0..layers.len().saturating_sub(1)).rev().collect()
+            };
+            for r in ranks {
+                let neighbours = if downward { up } else { down };
+                let keys: Vec<f64> = layers[r].iter().map(|&v| {
+                    let adjacent = &neighbours[v];
+                    if adjacent.is_empty() {
+                        position[v] as f64
+                    } else {
+                        adjacent.iter().map(|&u| position[u] as f64).sum::<f64>() / adjacent.len() as f64
+                    }
+                }).collect();
+                reorder(&mut layers[r], &keys, paths);
+                for (i, &v) in layers[r].iter().enumerate() {
+                    position[v] = i;
+                }
+            }
+            let crossings = count_crossings(&layers, down, &position);
+            if crossings < best_crossings {
+                best_crossings = crossings;
+                best = layers.clone();
+            }
+        }
+        best
+    }
+
+    /// Positions across the ranks, pulling nodes towards their neighbours
+    fn assign_positions(
+        &self,
+        layers: &[Vec<usize>],
+        up: &[Vec<usize>],
+        down: &[Vec<usize>],
+        paths: &[Vec<usize>],
+        breadth: &[f64],
+        margin: f64,
+    ) -> Vec<f64> {
+        let spacing = self.node_spacing as f64;
+        let separation = |a: usize, b: usize| {
+            let common = paths[a].iter().zip(&paths[b]).take_while(|(x, y)| x == y).count();
+            let boundaries = (paths[a].len() - common) + (paths[b].len() - common);
+            (breadth[a] + breadth[b]) / 2.0 + spacing + margin * boundaries as f64
+        };
+
+        let mut pos = vec![0.0f64; breadth.len()];
+        for layer in layers {
+            for i in 1..layer.len() {
+                pos[layer[i]] = pos[layer[i - 1]] + separation(layer[i - 1], layer[i]);
+            }
+        }
+
+        for sweep in 0..POSITION_SWEEPS * 2 {
+            let downward = sweep % 2 == 0;
+            let neighbours = if downward { up } else { down };
+            let ranks: Vec<usize> = if downward {
+                (0..layers.len()).collect()
+            } else {
+                (0..layers.len()).rev().collect()
+            };
+            for r in ranks {
+                let layer = &layers[r];
+                let desired: Vec<f64> = layer.iter().map(|&v| {
+                    let adjacent = &neighbours[v];
+                    if adjacent.is_empty() {
+                        pos[v]
+                    } else {
+                        let mut values: Vec<f64> = adjacent.iter().map(|&u| pos[u]).collect();
+                        values.sort_by(|a, b| a.total_cmp(b));
+                        let mid = values.len() / 2;
+                        if values.len() % 2 == 1 { values[mid] } else { (values[mid - 1] + values[mid]) / 2.0 }
+                    }
+                }).collect();
+
+                // Pack from both sides and average: both respect the separations
+                let mut left = desired.clone();
+                for i in 1..layer.len() {
+                    left[i] = left[i].max(left[i - 1] + separation(layer[i - 1], layer[i]));
+                }
+                let mut right = desired;
+                for i in (0..layer.len().saturating_sub(1)).rev() {
+                    right[i] = right[i].min(right[i + 1] - separation(layer[i], layer[i + 1]));
+                }
+                for (i, &v) in layer.iter().enumerate() {
+                    pos[v] = (left[i] + right[i]) / 2.0;
+                }
+            }
+        }
+        pos
+    }
+
+    /// Positions along the ranks, leaving room for cluster borders and titles
+    fn assign_rank_positions(
+        &self,
+        layers: &[Vec<usize>],
+        paths: &[Vec<usize>],
+        depth: &[f64],
+        margin: f64,
+    ) -> Vec<f64> {
+        let rank_count = layers.len();
+        let mut first = vec![usize::MAX; self.cluster_parents.len()];
+        let mut last = vec![0usize; self.cluster_parents.len()];
+        for (r, layer) in layers.iter().enumerate() {
+            for &v in layer {
+                for &c in &paths[v] {
+                    first[c] = first[c].min(r);
+                    last[c] = last[c].max(r);
+                }
+            }
+        }
+
+        // Clusters opening/closing at each rank, counted along one nesting path
+        let mut opening = vec![0usize; rank_count];
+        let mut closing = vec![0usize; rank_count];
+        for (r, layer) in layers.iter().enumerate() {
+            for &v in layer {
+                opening[r] = opening[r].max(paths[v].iter().filter(|&&c| first[c] == r).count());
+                closing[r] = closing[r].max(paths[v].iter().filter(|&&c| last[c] == r).count());
+            }
+        }
+
+        let mut pos = vec![0.0f64; depth.len()];
+        let mut offset = 0.0;
+        for (r, layer) in layers.iter().enumerate() {
+            if r > 0 {
+                offset += self.rank_spacing as f64 + (closing[r - 1] + opening[r]) as f64 * margin;
+            }
+            let rank_depth = layer.iter().map(|&v| depth[v]).fold(0.0, f64::max);
+            for &v in layer {
+                pos[v] = offset + rank_depth / 2.0;
+            }
+            offset += rank_depth;
+        }
+        pos
+    }
+
+    /// Clip a route to the node borders and pick the connection sites
+    fn route(&self, from: &DiagramBounds, to: &DiagramBounds, mut points: Vec<(u32, u32)>) -> EdgeRoute {
+        if points.len() < 2 {
+            // Self-loop: leave and re-enter on the side facing away from the flow
+            let site = if self.is_horizontal() { ConnectionSite::Top } else { ConnectionSite::Right };
+            let point = site_point(from, site);
+            return EdgeRoute { points: vec![point, point], start_site: site, end_site: site };
+        }
+
+        let start_site = facing_site(from, points[1], self.is_horizontal());
+        let end_site = facing_site(to, points[points.len() - 2], self.is_horizontal());
+        let last = points.len() - 1;
+        points[0] = site_point(from, start_site);
+        points[last] = site_point(to, end_site);
+        EdgeRoute { points, start_site, end_site }
+    }
+}
+
+/// Side of a box facing a point, preferring the rank axis
+fn facing_site(rect: &DiagramBounds, toward: (u32, u32), horizontal: bool) -> ConnectionSite {
+    let (cx, cy) = (rect.x + rect.width / 2, rect.y + rect.height / 2);
+    let (tx, ty) = toward;
+    let beyond_x = tx < rect.x || tx > rect.x + rect.width;
+    let beyond_y = ty < rect.y || ty > rect.y + rect.height;
+    let use_x = if horizontal { beyond_x || !beyond_y } else { beyond_x && !beyond_y };
+    if use_x {
+        if tx >= cx { ConnectionSite::Right } else { ConnectionSite::Left }
+    } else if ty >= cy {
+        ConnectionSite::Bottom
+    } else {
+        ConnectionSite::Top
+    }
+}
+
+/// Point of a connection site on a box
+pub fn site_point(rect: &DiagramBounds, site: ConnectionSite) -> (u32, u32) {
+    let (x, y, w, h) = (rect.x, rect.y, rect.width, rect.height);
+    match site {
+        ConnectionSite::Top => (x + w / 2, y),
+        ConnectionSite::Bottom => (x + w / 2, y + h),
+        ConnectionSite::Left => (x, y + h / 2),
+        ConnectionSite::Right => (x + w, y + h / 2),
+        ConnectionSite::TopLeft => (x, y),
+        ConnectionSite::TopRight => (x + w, y),
+        ConnectionSite::BottomLeft => (x, y + h),
+        ConnectionSite::BottomRight => (x + w, y + h),
+        ConnectionSite::Center => (x + w / 2, y + h / 2),
+    }
+}
+
+/// Longest path from the sources, on an acyclic edge list
+fn longest_path_ranks(n: usize, edges: &[(usize, usize)]) -> Vec<usize> {
+    let mut incoming = vec![0usize; n];
+    let mut outgoing: Vec<Vec<usize>> = vec![Vec::new(); n];
+    for &(from, to) in edges {
+        incoming[to] += 1;
+        outgoing[from].push(to);
+    }
+
+    let mut ranks = vec![0usize; n];
+    let mut queue: VecDeque<usize> = (0..n).filter(|&v| incoming[v] == 0).collect();
+    while let Some(v) = queue.pop_front() {
+        for &w in &outgoing[v] {
+            ranks[w] = ranks[w].max(ranks[v] + 1);
+            incoming[w] -= 1;
+            if incoming[w] == 0 {
+                queue.push_back(w);
+            }
+        }
+    }
+    ranks
+}
+
+/// Sort a rank by key, keeping the members of each cluster next to each other
+fn reorder(layer: &mut [usize], keys: &[f64], paths: &[Vec<usize>]) {
+    let keyed: Vec<(usize, f64)> = layer.iter().copied().zip(keys.iter().copied()).collect();
+    let ordered = arrange(keyed, 0, paths);
+    layer.copy_from_slice(&ordered);
+}
+
+/// Nodes of a rank with their sort keys
+type KeyedNodes = Vec<(usize, f64)>;
+
+fn arrange(members: KeyedNodes, depth: usize, paths: &[Vec<usize>]) -> Vec<usize> {
+    // Group members by their cluster at this nesting depth, in order of appearance
+    let mut groups: Vec<(Option<usize>, KeyedNodes)> = Vec::new();
+    for (v, key) in members {
+        match paths[v].get(depth) {
+            Some(&c) => match groups.iter_mut().find(|(g, _)| *g == Some(c)) {
+                Some((_, items)) => items.push((v, key)),
+                None => groups.push((Some(c), vec![(v, key)])),
+            },
+            None => groups.push((None, vec![(v, key)])),
+        }
+    }
+
+    let mut items: Vec<(f64, Vec<usize>)> = groups.into_iter().map(|(cluster, items)| {
+        let key = items.iter().map(|(_, k)| k).sum::<f64>() / items.len() as f64;
+        let ordered = if cluster.is_some() {
+            arrange(items, depth + 1, paths)
+        } else {
+            items.into_iter().map(|(v, _)| v).collect()
+        };
+        (key, ordered)
+    }).collect();
+    items.sort_by(|a, b| a.0.total_cmp(&b.0));
+    items.into_iter().flat_map(|(_, ordered)| ordered).collect()
+}
+
+/// Number of edge crossings between consecutive ranks
+fn count_crossings(layers: &[Vec<usize>], down: &[Vec<usize>], position: &[usize]) -> usize {
+    let mut total = 0;
+    for layer in layers {
+        let segments: Vec<(usize, usize)> = layer.iter()
+            .flat_map(|&v| down[v].iter().map(move |&w| (position[v], position[w])))
+            .collect();
+        for (i, a) in segments.iter().enumerate() {
+            for b in &segments[i + 1..] {
+                if (a.0 < b.0 && a.1 > b.1) || (a.0 > b.0 && a.1 < b.1) {
+                    total += 1;
+                }
+            }
+        }
+    }
+    total
+}
+
+/// Bounding box (left, top, right, bottom) of `(x, y, width, height)` boxes
+fn union(rects: &[(f64, f64, f64, f64)]) -> Option<(f64, f64, f64, f64)> {
+    rects.iter().fold(None, |acc, &(x, y, w, h)| {
+        Some(match acc {
+            None => (x, y, x + w, y + h),
+            Some((l, t, r, b)) => (l.min(x), t.min(y), r.max(x + w), b.max(y + h)),
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn overlaps(a: &DiagramBounds, b: &DiagramBounds) -> bool {
+        a.x < b.x + b.width && b.x < a.x + a.width && a.y < b.y + b.height && b.y < a.y + a.height
+    }
+
+    fn chain(direction: FlowDirection, count: usize) -> LayoutGraph {
+        let mut graph = LayoutGraph::new(direction);
+        for _ in 0..count {
+            graph.add_node(1_000_000, 500_000);
+        }
+        for i in 1..count {
+            graph.add_edge(i - 1, i);
+        }
+        graph
+    }
+
+    #[test]
+    fn test_ranks_follow_direction() {
+        let layout = chain(FlowDirection::TopToBottom, 3).layout();
+        assert!(layout.nodes[0].y < layout.nodes[1].y && layout.nodes[1].y < layout.nodes[2].y);
+        assert_eq!(layout.nodes[0].x, layout.nodes[2].x);
+        assert_eq!(layout.edges[0].start_site, ConnectionSite::Bottom);
+        assert_eq!(layout.edges[0].end_site, ConnectionSite::Top);
+
+        let layout = chain(FlowDirection::RightToLeft, 3).layout();
+        assert!(layout.nodes[0].x > layout.nodes[1].x && layout.nodes[1].x > layout.nodes[2].x);
+        assert_eq!(layout.edges[0].start_site, ConnectionSite::Left);
+
+        let layout = chain(FlowDirection::BottomToTop, 2).layout();
+        assert!(layout.nodes[0].y > layout.nodes[1].y);
+    }
+
+    #[test]
+    fn test_cycles_and_long_edges() {
+        let mut graph = chain(FlowDirection::TopToBottom, 4);
+        graph.add_edge(3, 0);
+        graph.add_edge(0, 3);
+        graph.add_edge(2, 2);
+        let layout = graph.layout();
+
+        // The back edge is routed against the flow, the long edge through dummies
+        assert!(layout.nodes[3].y > layout.nodes[0].y);
+        assert_eq!(layout.edges[3].points.len(), 4);
+        assert!(layout.edges[3].start().1 < layout.nodes[3].y + 1);
+        assert_eq!((layout.edges[3].start_site, layout.edges[3].end_site), (ConnectionSite::Top, ConnectionSite::Bottom));
+        assert!(layout.edges[4].has_bends());
+        assert_eq!(layout.edges[5].points.len(), 2);
+
+        for (i, a) in layout.nodes.iter().enumerate() {
+            for b in &layout.nodes[i + 1..] {
+                assert!(!overlaps(a, b));
+            }
+        }
+    }
+
+    #[test]
+    fn test_crossings_removed() {
+        // Two parents each linked to the child on the "wrong" side
+        let mut graph = LayoutGraph::new(FlowDirection::TopToBottom);
+        for _ in 0..4 {
+            graph.add_node(1_000_000, 500_000);
+        }
+        graph.add_edge(0, 3);
+        graph.add_edge(1, 2);
+        let layout = graph.layout();
+        assert_eq!(layout.nodes[0].x < layout.nodes[1].x, layout.nodes[3].x < layout.nodes[2].x);
+    }
+
+    #[test]
+    fn test_nested_clusters() {
+        let mut graph = chain(FlowDirection::LeftToRight, 4);
+        let outer = graph.add_cluster(None);
+        let inner = graph.add_cluster(Some(outer));
+        graph.set_cluster(1, outer);
+        graph.set_cluster(2, inner);
+        let layout = graph.layout();
+
+        let outer_box = layout.clusters[outer].unwrap();
+        let inner_box = layout.clusters[inner].unwrap();
+        let contains = |a: &DiagramBounds, b: &DiagramBounds| {
+            a.x <= b.x && a.y <= b.y && a.x + a.width >= b.x + b.width && a.y + a.height >= b.y + b.height
+        };
+        assert!(contains(&outer_box, &inner_box));
+        assert!(contains(&inner_box, &layout.nodes[2]));
+        assert!(contains(&outer_box, &layout.nodes[1]));
+        assert!(!overlaps(&outer_box, &layout.nodes[0]) && !overlaps(&outer_box, &layout.nodes[3]));
+    }
+
+    #[test]
+    fn test_fit_scales_down() {
+        let mut layout = chain(FlowDirection::LeftToRight, 12).layout();
+        assert!(layout.width > DIAGRAM_AREA.width);
+        layout.fit(DIAGRAM_AREA);
+        assert!(layout.width <= DIAGRAM_AREA.width);
+        for node in &layout.nodes {
+            assert!(node.x >= DIAGRAM_AREA.x && node.x + node.width <= DIAGRAM_AREA.x + DIAGRAM_AREA.width + 1);
+            assert!(node.y >= DIAGRAM_AREA.y && node.y + node.height <= DIAGRAM_AREA.y + DIAGRAM_AREA.height + 1);
+        }
+    }
+}
@@ -1,17 +1,18 @@
 //! Mindmap diagram parsing and rendering
 
-use crate::generator::{Shape, ShapeType};
-use super::types::{create_labeled_shape, LabelPosition};
+use crate::generator::ShapeType;
+use super::types::{create_labeled_shape, DiagramElements, LabelPosition, MermaidDiagnostic, MermaidResult};
 
 /// Generate shapes for a mindmap
-pub fn generate_shapes(code: &str) -> Vec<Shape> {
+pub fn generate_elements(code: &str) -> MermaidResult<DiagramElements> {
     let mut shapes = Vec::new();
+    let mut warnings = Vec::new();
     
     let mut root = String::new();
     let mut level1: Vec<String> = Vec::new();
     let mut level2: Vec<(usize, String)> = Vec::new();
     
-    for line in code.lines().skip(1) {
+    for (index, line) in code.lines().enumerate().skip(1) {
         let trimmed = line.trim();
         if trimmed.is_empty() || trimmed.starts_with("%%") {
             continue;
@@ -28,15 +29,22 @@ pub fn generate_shapes(code: &str) -> Vec<Shape> {
         if spaces == 0 || (root.is_empty() && spaces <= 4) {
             if root.is_empty() {
                 root = text;
+            } else {
+                warnings.push(MermaidDiagnostic::at(index, line, "", format!("a mindmap has a single root, `{text}` is ignored")));
             }
         } else if spaces <= 8 {
             level1.push(text);
+        } else if level1.is_empty() {
+            warnings.push(MermaidDiagnostic::at(index, line, "", format!("`{text}` is nested deeper than any branch and is ignored")));
         } else {
-            let parent_idx = level1.len().saturating_sub(1);
-            level2.push((parent_idx, text));
+            level2.push((level1.len() - 1, text));
         }
     }
     
+    if root.is_empty() {
+        return Err(MermaidDiagnostic::empty("mindmap"));
+    }
+    
     // Layout parameters
     let center_x = 4_000_000u32;
     let center_y = 3_000_000u32;
@@ -74,19 +82,17 @@ pub fn generate_shapes(code: &str) -> Vec<Shape> {
     
     // Level 2 nodes
     for (parent_idx, text) in &level2 {
-        if *parent_idx < level1.len() {
-            let parent_angle = (*parent_idx as f64) * angle_step - std::f64::consts::PI / 2.0;
-            let x = center_x + (radius2 as f64 * parent_angle.cos()) as u32 - node_width / 2;
-            let y = center_y + (radius2 as f64 * parent_angle.sin()) as u32 - node_height / 2;
-            
-            shapes.extend(create_labeled_shape(
-                ShapeType::RoundedRectangle, x, y, node_width, node_height,
-                Some("E8EAF6"), Some("3949AB"), text, LabelPosition::Inside
-            ));
-        }
+        let parent_angle = (*parent_idx as f64) * angle_step - std::f64::consts::PI / 2.0;
+        let x = center_x + (radius2 as f64 * parent_angle.cos()) as u32 - node_width / 2;
+        let y = center_y + (radius2 as f64 * parent_angle.sin()) as u32 - node_height / 2;
+        
+        shapes.extend(create_labeled_shape(
+            ShapeType::RoundedRectangle, x, y, node_width, node_height,
+            Some("E8EAF6"), Some("3949AB"), text, LabelPosition::Inside
+        ));
     }
     
-    shapes
+    Ok(DiagramElements::from_shapes(shapes).with_warnings(warnings))
 }
 
 #[cfg(test)]
@@ -96,7 +102,18 @@ mod tests {
     #[test]
     fn test_generate_mindmap_shapes() {
         let code = "mindmap\n    root((Central))\n        Branch1\n        Branch2";
-        let shapes = generate_shapes(code);
-        assert!(!shapes.is_empty());
+        let elements = generate_elements(code).unwrap();
+        assert!(!elements.shapes.is_empty());
+        assert!(elements.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_mindmap_diagnostics() {
+        let code = "mindmap\nRoot\n            Orphan\n        Branch\nSecond";
+        let elements = generate_elements(code).unwrap();
+        let warnings: Vec<(usize, usize)> = elements.warnings.iter().map(|w| (w.line, w.column)).collect();
+        assert_eq!(warnings, [(3, 13), (5, 1)]);
+
+        assert!(generate_elements("mindmap").is_err());
     }
 }
@@ -15,8 +15,14 @@
 //! - Journey (user journey)
 //! - Quadrant charts
 //! - Git graphs
+//! - C4 diagrams (context, container, component, dynamic, deployment)
+//! - Sankey diagrams
+//! - XY charts
+//! - Block diagrams
+//! - Requirement diagrams
 
 mod types;
+mod layout;
 mod flowchart;
 mod sequence;
 mod pie;
@@ -29,6 +35,12 @@ mod timeline;
 mod journey;
 mod quadrant;
 mod gitgraph;
+mod c4;
+mod sankey;
+mod xychart;
+mod block;
+mod requirement;
+mod style;
 
 pub use types::*;
 
@@ -36,6 +48,7 @@ use crate::generator::{Shape, ShapeType, ShapeFill, ShapeLine};
 
 /// Detect the type of Mermaid diagram from code
 pub fn detect_type(code: &str) -> MermaidType {
+    let (_, code) = style::split_directives(code);
     let first_line = code.lines().next().unwrap_or("").trim().to_lowercase();
     
     if first_line.starts_with("graph") || first_line.starts_with("flowchart") {
@@ -62,65 +75,82 @@ pub fn detect_type(code: &str) -> MermaidType {
         MermaidType::Quadrant
     } else if first_line.starts_with("gitgraph") || first_line.starts_with("git") {
         MermaidType::GitGraph
+    } else if first_line.starts_with("c4") {
+        MermaidType::C4
+    } else if first_line.starts_with("sankey") {
+        MermaidType::Sankey
+    } else if first_line.starts_with("xychart") {
+        MermaidType::XyChart
+    } else if first_line.starts_with("block") {
+        MermaidType::Block
+    } else if first_line.starts_with("requirementdiagram") || first_line.starts_with("requirement") {
+        MermaidType::Requirement
     } else {
         MermaidType::Unknown
     }
 }
 
 /// Create shapes and connectors for a Mermaid diagram (main entry point)
-pub fn create_diagram_elements(code: &str) -> DiagramElements {
+///
+/// Fails when the block cannot be drawn at all (unknown diagram type or
+/// nothing to draw); lines that were dropped or only partly understood are
+/// reported in `DiagramElements::warnings`. Diagnostic lines count from the
+/// first line of `code`.
+pub fn create_diagram_elements(code: &str) -> MermaidResult<DiagramElements> {
     let diagram_type = detect_type(code);
+    let (theme, body) = style::split_directives(code);
+    let theme = theme.as_ref();
+    // Lines taken by `%%{init}%%` directives and comments before the keyword
+    let skipped = code[..code.len() - body.len()].matches('\n').count();
     
-    match diagram_type {
+    let result = match diagram_type {
         MermaidType::Flowchart => {
-            let fc = flowchart::parse(code);
-            flowchart::generate_elements(&fc)
-        }
-        MermaidType::Pie => {
-            let slices = pie::parse(code);
-            let shapes = pie::generate_shapes(&slices);
-            DiagramElements::from_shapes(shapes)
-        }
-        MermaidType::Sequence => {
-            DiagramElements::from_shapes(sequence::generate_shapes(code))
-        }
-        MermaidType::Gantt => {
-            DiagramElements::from_shapes(gantt::generate_shapes(code))
-        }
-        MermaidType::ClassDiagram => {
-            class_diagram::generate_elements(code)
-        }
-        MermaidType::StateDiagram => {
-            state_diagram::generate_elements(code)
-        }
-        MermaidType::ErDiagram => {
-            er_diagram::generate_elements(code)
+            flowchart::parse(body).map(|fc| flowchart::generate_elements(&fc, theme))
         }
-        MermaidType::Mindmap => {
-            DiagramElements::from_shapes(mindmap::generate_shapes(code))
+        MermaidType::Pie => pie::generate_elements(body),
+        MermaidType::Sequence => sequence::generate_elements(body),
+        MermaidType::Gantt => gantt::generate_elements(body),
+        MermaidType::ClassDiagram => class_diagram::generate_elements(body, theme),
+        MermaidType::StateDiagram => state_diagram::generate_elements(body, theme),
+        MermaidType::ErDiagram => er_diagram::generate_elements(body, theme),
+        MermaidType::Mindmap => mindmap::generate_elements(body),
+        MermaidType::Timeline => timeline::generate_elements(body),
+        MermaidType::Journey => journey::generate_elements(body),
+        MermaidType::Quadrant => quadrant::generate_elements(body),
+        MermaidType::GitGraph => gitgraph::generate_elements(body),
+        MermaidType::C4 => c4::generate_elements(body),
+        MermaidType::Sankey => sankey::generate_elements(body),
+        MermaidType::XyChart => xychart::generate_elements(body),
+        MermaidType::Block => block::generate_elements(body, theme),
+        MermaidType::Requirement => requirement::generate_elements(body, theme),
+        MermaidType::Unknown => {
+            let keyword = body.split_whitespace().next().unwrap_or("");
+            let message = if keyword.is_empty() { "empty diagram".to_string() } else { format!("unknown diagram type `{keyword}`") };
+            Err(MermaidDiagnostic::at(0, body.lines().next().unwrap_or(""), keyword, message))
         }
-        MermaidType::Timeline => {
-            DiagramElements::from_shapes(timeline::generate_shapes(code))
-        }
-        MermaidType::Journey => {
-            DiagramElements::from_shapes(journey::generate_shapes(code))
-        }
-        MermaidType::Quadrant => {
-            DiagramElements::from_shapes(quadrant::generate_shapes(code))
-        }
-        MermaidType::GitGraph => {
-            DiagramElements::from_shapes(gitgraph::generate_shapes(code))
-        }
-        _ => {
-            // Fallback: create a placeholder
-            DiagramElements::from_shapes(vec![
-                Shape::new(ShapeType::Rectangle, 1_000_000, 2_000_000, 7_000_000, 3_000_000)
-                    .with_fill(ShapeFill::new("F5F5F5"))
-                    .with_line(ShapeLine::new("757575", 1))
-                    .with_text(&format!("Diagram: {}", code.lines().next().unwrap_or("Unknown")))
-            ])
-        }
-    }
+    };
+    
+    let shift = |mut diagnostic: MermaidDiagnostic| {
+        diagnostic.line += skipped;
+        diagnostic
+    };
+    result
+        .map(|mut elements| {
+            elements.warnings = elements.warnings.into_iter().map(shift).collect();
+            elements
+        })
+        .map_err(shift)
+}
+
+/// Placeholder drawn for a block that could not be rendered
+pub fn placeholder_elements(code: &str) -> DiagramElements {
+    let (_, body) = style::split_directives(code);
+    DiagramElements::from_shapes(vec![
+        Shape::new(ShapeType::Rectangle, 1_000_000, 2_000_000, 7_000_000, 3_000_000)
+            .with_fill(ShapeFill::new("F5F5F5"))
+            .with_line(ShapeLine::new("757575", 1))
+            .with_text(&format!("Diagram: {}", body.lines().next().unwrap_or("Unknown")))
+    ])
 }
 
 /// Get diagram style info (for backward compatibility)
@@ -138,6 +168,11 @@ pub fn get_diagram_style(diagram_type: MermaidType) -> (&'static str, &'static s
         MermaidType::Journey => ("F3E5F5", "7B1FA2", "User Journey", ""),
         MermaidType::Quadrant => ("E3F2FD", "1565C0", "Quadrant Chart", ""),
         MermaidType::GitGraph => ("ECEFF1", "607D8B", "Git Graph", ""),
+        MermaidType::C4 => ("E3F2FD", "1168BD", "C4 Diagram", ""),
+        MermaidType::Sankey => ("FFF8E1", "ED7D31", "Sankey Diagram", ""),
+        MermaidType::XyChart => ("E8F5E9", "2E7D32", "XY Chart", ""),
+        MermaidType::Block => ("EDE7F6", "5E35B1", "Block Diagram", ""),
+        MermaidType::Requirement => ("E0F2F1", "00695C", "Requirement Diagram", ""),
         MermaidType::Unknown => ("F5F5F5", "757575", "Diagram", ""),
     }
 }
@@ -187,6 +222,35 @@ mod tests {
         assert_eq!(detect_type("mindmap"), MermaidType::Mindmap);
     }
 
+    #[test]
+    fn test_detect_after_directive() {
+        let code = "%%{init: {'theme': 'forest'}}%%\n%% comment\nerDiagram\n    A ||--o{ B : has";
+        assert_eq!(detect_type(code), MermaidType::ErDiagram);
+        let elements = create_diagram_elements(code).unwrap();
+        assert_eq!(elements.shapes[0].fill.as_ref().unwrap().color, crate::prelude::themes::NATURE.primary);
+    }
+
+    #[test]
+    fn test_diagnostic_lines_count_directives() {
+        let code = "%%{init: {'theme': 'forest'}}%%\n%% comment\nflowchart LR\n    A --> B\n    ???";
+        let elements = create_diagram_elements(code).unwrap();
+        assert_eq!(elements.warnings, [MermaidDiagnostic::new(5, 5, "unsupported statement `???`")]);
+
+        let error = create_diagram_elements("%% comment\n  venn\n    A").err().unwrap();
+        assert_eq!(error, MermaidDiagnostic::new(2, 1, "unknown diagram type `venn`"));
+        assert_eq!(placeholder_elements("venn").shapes[0].text.as_deref(), Some("Diagram: venn"));
+    }
+
+    #[test]
+    fn test_detect_new_types() {
+        assert_eq!(detect_type("C4Context"), MermaidType::C4);
+        assert_eq!(detect_type("C4Deployment"), MermaidType::C4);
+        assert_eq!(detect_type("sankey-beta"), MermaidType::Sankey);
+        assert_eq!(detect_type("xychart-beta horizontal"), MermaidType::XyChart);
+        assert_eq!(detect_type("block-beta"), MermaidType::Block);
+        assert_eq!(detect_type("requirementDiagram"), MermaidType::Requirement);
+    }
+
     #[test]
     fn test_detect_timeline() {
         assert_eq!(detect_type("timeline"), MermaidType::Timeline);
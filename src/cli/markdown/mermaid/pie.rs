@@ -1,69 +1,86 @@
 //! Pie chart diagram parsing and rendering
+//!
+//! Rendered as a native chart, so the slices stay editable in PowerPoint.
 
-use crate::generator::{Shape, ShapeType, ShapeFill, ShapeLine};
+use crate::generator::charts::{Chart, ChartBuilder, ChartSeries, ChartType};
+use super::layout::DIAGRAM_AREA;
+use super::types::{DiagramElements, MermaidDiagnostic, MermaidResult};
+
+/// Parsed pie chart
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PieChart {
+    pub title: String,
+    /// Show slice values next to their labels (`pie showData`)
+    pub show_data: bool,
+    pub slices: Vec<(String, f64)>,
+    /// Lines that were dropped
+    pub warnings: Vec<MermaidDiagnostic>,
+}
 
 /// Parse pie chart data
-pub fn parse(code: &str) -> Vec<(String, f64)> {
-    let mut slices = Vec::new();
-    
-    for line in code.lines().skip(1) {
-        let line = line.trim();
-        if line.contains(':') && !line.starts_with("title") {
-            if let Some((label, value)) = line.split_once(':') {
-                let label = label.trim().trim_matches('"').to_string();
-                if let Ok(val) = value.trim().parse::<f64>() {
-                    slices.push((label, val));
-                }
+pub fn parse(code: &str) -> MermaidResult<PieChart> {
+    let mut pie = PieChart::default();
+    let mut lines = code.lines().enumerate().filter(|(_, l)| !l.trim().is_empty() && !l.trim().starts_with("%%"));
+
+    // Header: pie [showData] [title ...]
+    let header = lines.next().map_or("", |(_, l)| l.trim());
+    let mut rest = header.strip_prefix("pie").unwrap_or(header).trim();
+    if let Some(after) = rest.strip_prefix("showData") {
+        pie.show_data = true;
+        rest = after.trim();
+    }
+    if let Some(title) = rest.strip_prefix("title") {
+        pie.title = title.trim().to_string();
+    }
+
+    for (index, source) in lines {
+        let line = source.trim();
+        if line == "showData" {
+            pie.show_data = true;
+        } else if let Some(title) = line.strip_prefix("title ") {
+            pie.title = title.trim().to_string();
+        } else if let Some((label, value)) = line.split_once(':') {
+            if label.trim_start().starts_with("acc") {
+                continue;
             }
+            let label = label.trim().trim_matches('"').to_string();
+            let value = value.trim();
+            match value.parse::<f64>() {
+                Ok(val) if val >= 0.0 => pie.slices.push((label, val)),
+                _ => pie.warnings.push(MermaidDiagnostic::at(index, source, value, format!("slice value `{value}` is not a non-negative number"))),
+            }
+        } else {
+            pie.warnings.push(MermaidDiagnostic::unsupported(index, source));
         }
     }
-    
-    slices
-}
 
-/// Generate shapes for a pie chart
-pub fn generate_shapes(slices: &[(String, f64)]) -> Vec<Shape> {
-    let mut shapes = Vec::new();
-    
-    if slices.is_empty() {
-        return shapes;
-    }
-    
-    let colors = ["4472C4", "ED7D31", "A5A5A5", "FFC000", "5B9BD5", "70AD47", "9E480E", "997300"];
-    let center_x = 2_500_000u32;
-    let center_y = 3_000_000u32;
-    let radius = 1_500_000u32;
-    
-    // Create a circle for the pie
-    let pie_circle = Shape::new(ShapeType::Ellipse, center_x - radius, center_y - radius, radius * 2, radius * 2)
-        .with_fill(ShapeFill::new(colors[0]))
-        .with_line(ShapeLine::new("FFFFFF", 2));
-    shapes.push(pie_circle);
-    
-    // Create legend
-    let legend_x = 5_000_000u32;
-    let legend_y = 2_000_000u32;
-    let legend_height = 350_000u32;
-    
-    let total: f64 = slices.iter().map(|(_, v)| v).sum();
-    
-    for (i, (label, value)) in slices.iter().enumerate() {
-        let color = colors[i % colors.len()];
-        let percentage = if total > 0.0 { value / total * 100.0 } else { 0.0 };
-        
-        // Color box
-        let box_shape = Shape::new(ShapeType::Rectangle, legend_x, legend_y + (i as u32) * legend_height, 200_000, 200_000)
-            .with_fill(ShapeFill::new(color));
-        shapes.push(box_shape);
-        
-        // Label
-        let label_text = format!("{} ({:.1}%)", label, percentage);
-        let label_shape = Shape::new(ShapeType::Rectangle, legend_x + 300_000, legend_y + (i as u32) * legend_height, 2_500_000, 200_000)
-            .with_text(&label_text);
-        shapes.push(label_shape);
+    if pie.slices.is_empty() {
+        return Err(MermaidDiagnostic::empty("pie chart"));
     }
-    
-    shapes
+    Ok(pie)
+}
+
+/// Build the native pie chart, filling the diagram area
+pub fn generate_chart(pie: &PieChart) -> Chart {
+    let categories: Vec<String> = pie.slices
+        .iter()
+        .map(|(label, value)| if pie.show_data { format!("{label} [{value}]") } else { label.clone() })
+        .collect();
+    let values = pie.slices.iter().map(|(_, value)| *value).collect();
+    let series_name = if pie.title.is_empty() { "Value" } else { &pie.title };
+
+    ChartBuilder::new(&pie.title, ChartType::Pie)
+        .position(DIAGRAM_AREA.x, DIAGRAM_AREA.y)
+        .size(DIAGRAM_AREA.width, DIAGRAM_AREA.height)
+        .categories(categories.iter().map(String::as_str).collect())
+        .add_series(ChartSeries::new(series_name, values))
+        .build()
+}
+
+/// Generate the elements for a pie chart block
+pub fn generate_elements(code: &str) -> MermaidResult<DiagramElements> {
+    let pie = parse(code)?;
+    Ok(DiagramElements::from_chart(generate_chart(&pie)).with_warnings(pie.warnings))
 }
 
 #[cfg(test)]
@@ -73,16 +90,46 @@ mod tests {
     #[test]
     fn test_parse_pie() {
         let code = "pie\n    \"Dogs\" : 45\n    \"Cats\" : 30";
-        let slices = parse(code);
-        assert_eq!(slices.len(), 2);
-        assert_eq!(slices[0].0, "Dogs");
-        assert_eq!(slices[0].1, 45.0);
+        let pie = parse(code).unwrap();
+        assert_eq!(pie.slices.len(), 2);
+        assert_eq!(pie.slices[0].0, "Dogs");
+        assert_eq!(pie.slices[0].1, 45.0);
+        assert!(pie.title.is_empty());
+    }
+
+    #[test]
+    fn test_parse_pie_title_and_show_data() {
+        let pie = parse("pie showData title Pets adopted\n    \"Dogs\" : 386\n    \"Rats\" : 15.5").unwrap();
+        assert_eq!(pie.title, "Pets adopted");
+        assert!(pie.show_data);
+
+        let pie = parse("pie\n    title Key elements\n    \"Calcium\" : 42.96").unwrap();
+        assert_eq!(pie.title, "Key elements");
+        assert!(!pie.show_data);
+        assert_eq!(pie.slices, vec![("Calcium".to_string(), 42.96)]);
+    }
+
+    #[test]
+    fn test_generate_pie_chart() {
+        let elements = generate_elements("pie showData title Pets\n    \"Dogs\" : 45\n    \"Cats\" : 30").unwrap();
+        assert!(elements.shapes.is_empty());
+        let chart = &elements.charts[0];
+        assert_eq!(chart.chart_type, ChartType::Pie);
+        assert_eq!(chart.title, "Pets");
+        assert_eq!(chart.categories, vec!["Dogs [45]", "Cats [30]"]);
+        assert_eq!(chart.series[0].values, vec![45.0, 30.0]);
+        assert!(elements.bounds.is_some());
+
+        assert!(elements.warnings.is_empty());
+        assert_eq!(generate_elements("pie").err(), Some(MermaidDiagnostic::empty("pie chart")));
     }
 
     #[test]
-    fn test_generate_pie_shapes() {
-        let slices = vec![("A".to_string(), 50.0), ("B".to_string(), 50.0)];
-        let shapes = generate_shapes(&slices);
-        assert!(!shapes.is_empty());
+    fn test_pie_diagnostics() {
+        let pie = parse("pie\n    \"Dogs\" : 45\n    \"Cats\" : lots\n    Birds").unwrap();
+        assert_eq!(pie.slices.len(), 1);
+        assert_eq!(pie.warnings.len(), 2);
+        assert_eq!((pie.warnings[0].line, pie.warnings[0].column), (3, 14));
+        assert_eq!(pie.warnings[1].message, "unsupported statement `Birds`");
     }
 }
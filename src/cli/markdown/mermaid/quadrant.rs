@@ -1,19 +1,20 @@
 //! Quadrant chart rendering
 
 use crate::generator::{Shape, ShapeType, ShapeFill, ShapeLine};
-use super::types::{create_labeled_dot, LabelPosition};
+use super::types::{create_labeled_dot, DiagramElements, LabelPosition, MermaidDiagnostic, MermaidResult};
 
 /// Generate shapes for a quadrant chart
-pub fn generate_shapes(code: &str) -> Vec<Shape> {
+pub fn generate_elements(code: &str) -> MermaidResult<DiagramElements> {
     let mut shapes = Vec::new();
+    let mut warnings = Vec::new();
     let mut points: Vec<(String, f32, f32)> = Vec::new();
     let mut x_axis = ("Low", "High");
     let mut y_axis = ("Low", "High");
     let mut quadrant_labels = ["", "", "", ""];
     
-    for line in code.lines().skip(1) {
+    for (index, line) in code.lines().enumerate().skip(1) {
         let trimmed = line.trim();
-        if trimmed.is_empty() || trimmed.starts_with("title") {
+        if trimmed.is_empty() || trimmed.starts_with("%%") || trimmed.starts_with("title") {
             continue;
         }
         
@@ -41,21 +42,20 @@ pub fn generate_shapes(code: &str) -> Vec<Shape> {
             } else if let Some(rest) = trimmed.strip_prefix("quadrant-4") {
                 quadrant_labels[3] = rest.trim();
             }
-        } else if trimmed.contains('[') && trimmed.contains(']') {
+        } else if let Some((label, coords)) = trimmed.split_once('[')
+            && let Some((coords, _)) = coords.split_once(']')
+        {
             // Parse point: "Label: [x, y]"
-            if let Some(bracket_start) = trimmed.find('[') {
-                let label = trimmed[..bracket_start].trim_end_matches(':').trim();
-                let coords = &trimmed[bracket_start..];
-                if let Some(bracket_end) = coords.find(']') {
-                    let coord_str = &coords[1..bracket_end];
-                    let parts: Vec<&str> = coord_str.split(',').collect();
-                    if parts.len() == 2 {
-                        let x = parts[0].trim().parse::<f32>().unwrap_or(0.5);
-                        let y = parts[1].trim().parse::<f32>().unwrap_or(0.5);
-                        points.push((label.to_string(), x, y));
-                    }
-                }
+            let label = label.trim().trim_end_matches(':').trim();
+            let point = coords.split_once(',')
+                .and_then(|(x, y)| Some((x.trim().parse::<f32>().ok()?, y.trim().parse::<f32>().ok()?)))
+                .filter(|(x, y)| (0.0..=1.0).contains(x) && (0.0..=1.0).contains(y));
+            match point {
+                Some((x, y)) => points.push((label.to_string(), x, y)),
+                None => warnings.push(MermaidDiagnostic::at(index, line, coords, format!("point `{label}` needs coordinates `[x, y]` between 0 and 1"))),
             }
+        } else {
+            warnings.push(MermaidDiagnostic::unsupported(index, line));
         }
     }
     
@@ -117,7 +117,7 @@ pub fn generate_shapes(code: &str) -> Vec<Shape> {
         );
     }
     
-    shapes
+    Ok(DiagramElements::from_shapes(shapes).with_warnings(warnings))
 }
 
 #[cfg(test)]
@@ -127,14 +127,23 @@ mod tests {
     #[test]
     fn test_generate_quadrant() {
         let code = "quadrantChart\n    x-axis Low --> High\n    y-axis Low --> High\n    Point A: [0.3, 0.7]";
-        let shapes = generate_shapes(code);
-        assert!(shapes.len() >= 4); // At least 4 quadrants
+        let elements = generate_elements(code).unwrap();
+        assert!(elements.shapes.len() >= 4); // At least 4 quadrants
+        assert!(elements.warnings.is_empty());
     }
 
     #[test]
     fn test_quadrant_empty() {
         let code = "quadrantChart";
-        let shapes = generate_shapes(code);
-        assert!(!shapes.is_empty());
+        let elements = generate_elements(code).unwrap();
+        assert!(!elements.shapes.is_empty());
+    }
+
+    #[test]
+    fn test_quadrant_diagnostics() {
+        let code = "quadrantChart\n    Point A: [1.5, 0.2]\n    Point B: [x, 0.2]\n    Point C";
+        let elements = generate_elements(code).unwrap();
+        let warnings: Vec<(usize, usize)> = elements.warnings.iter().map(|w| (w.line, w.column)).collect();
+        assert_eq!(warnings, [(2, 15), (3, 15), (4, 5)]);
     }
 }
@@ -0,0 +1,253 @@
+//! Requirement diagram parsing and rendering
+
+use crate::generator::{Shape, ShapeType, ShapeFill, ShapeLine};
+use crate::generator::connectors::{Connector, ConnectorType, ConnectorLine, ArrowType, LineDash};
+use super::layout::{LayoutGraph, DIAGRAM_AREA};
+use super::style::{DiagramTheme, Styles};
+use super::types::{DiagramElements, FlowDirection, MermaidDiagnostic, MermaidResult};
+
+const REQUIREMENT_TYPES: [&str; 7] = [
+    "requirement",
+    "functionalRequirement",
+    "interfaceRequirement",
+    "performanceRequirement",
+    "physicalRequirement",
+    "designConstraint",
+    "element",
+];
+
+const RELATIONSHIPS: [&str; 7] = ["contains", "copies", "derives", "satisfies", "verifies", "refines", "traces"];
+
+/// Default requirement diagram colors
+fn default_theme() -> DiagramTheme {
+    DiagramTheme {
+        node_fill: "FFFFFF".to_string(),
+        primary_fill: "00695C".to_string(),
+        secondary_fill: "E0F2F1".to_string(),
+        stroke: "004D40".to_string(),
+        line: "004D40".to_string(),
+        text: None,
+        accent: "004D40".to_string(),
+        muted: "4DB6AC".to_string(),
+    }
+}
+
+/// A requirement or element with its `key: value` fields
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequirementNode {
+    pub name: String,
+    /// `requirement`, `functionalRequirement`, ..., or `element`
+    pub kind: String,
+    pub fields: Vec<(String, String)>,
+}
+
+/// A relationship such as `a - satisfies -> b`
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequirementRelation {
+    pub from: String,
+    pub to: String,
+    pub kind: String,
+}
+
+/// Parsed requirement diagram
+#[derive(Debug, Clone)]
+pub struct RequirementDiagram {
+    pub nodes: Vec<RequirementNode>,
+    pub relations: Vec<RequirementRelation>,
+    pub styles: Styles,
+    /// Lines that were dropped or only partly understood
+    pub warnings: Vec<MermaidDiagnostic>,
+}
+
+/// Parse a requirement diagram
+pub fn parse(code: &str) -> MermaidResult<RequirementDiagram> {
+    let mut diagram = RequirementDiagram { nodes: Vec::new(), relations: Vec::new(), styles: Styles::default(), warnings: Vec::new() };
+    // Index and opening line of the block being read
+    let mut current: Option<(usize, usize)> = None;
+
+    for (index, source) in code.lines().enumerate().skip(1) {
+        let line = source.trim();
+        if line.is_empty() || line.starts_with("%%") || (current.is_none() && diagram.styles.parse_line(index, source)) {
+            continue;
+        }
+
+        if let Some((node, _)) = current {
+            if line == "}" {
+                current = None;
+            } else if let Some((key, value)) = line.split_once(':') {
+                let value = value.trim().trim_matches('"').to_string();
+                diagram.nodes[node].fields.push((key.trim().to_string(), value));
+            } else {
+                diagram.warnings.push(MermaidDiagnostic::at(index, source, "", "expected a `key: value` field"));
+            }
+        } else if let Some((kind, name)) = line.strip_suffix('{').and_then(|head| head.trim().split_once(char::is_whitespace))
+            && REQUIREMENT_TYPES.iter().any(|t| t.eq_ignore_ascii_case(kind))
+        {
+            let name = name.trim().trim_matches('"').to_string();
+            let kind = REQUIREMENT_TYPES.iter().find(|t| t.eq_ignore_ascii_case(kind)).copied().unwrap_or(kind).to_string();
+            match diagram.nodes.iter().position(|n| n.name == name) {
+                Some(node) => {
+                    diagram.warnings.push(MermaidDiagnostic::at(index, source, &name, format!("`{name}` is already declared")));
+                    current = Some((node, index));
+                }
+                None => {
+                    diagram.nodes.push(RequirementNode { name, kind, fields: Vec::new() });
+                    current = Some((diagram.nodes.len() - 1, index));
+                }
+            }
+        } else if let Some(relation) = parse_relation(line) {
+            if !RELATIONSHIPS.contains(&relation.kind.as_str()) {
+                diagram.warnings.push(MermaidDiagnostic::at(index, source, &relation.kind, format!("unknown relationship `{}`", relation.kind)));
+            }
+            let missing = [&relation.from, &relation.to].into_iter().find(|name| !diagram.nodes.iter().any(|n| n.name == **name)).cloned();
+            match missing {
+                Some(name) => diagram.warnings.push(MermaidDiagnostic::at(index, source, &name, format!("relationship to undeclared `{name}`"))),
+                None => diagram.relations.push(relation),
+            }
+        } else {
+            diagram.warnings.push(MermaidDiagnostic::unsupported(index, source));
+        }
+    }
+
+    if let Some((node, line)) = current {
+        let source = code.lines().nth(line).unwrap_or("");
+        let name = &diagram.nodes[node].name;
+        diagram.warnings.push(MermaidDiagnostic::at(line, source, "{", format!("`{name}` is never closed with `}}`")));
+    }
+    if diagram.nodes.is_empty() {
+        return Err(MermaidDiagnostic::empty("requirement diagram"));
+    }
+    let diagnostics = diagram.styles.diagnostics(diagram.relations.len());
+    diagram.warnings.extend(diagnostics);
+    Ok(diagram)
+}
+
+/// Parse `a - satisfies -> b` or `b <- satisfies - a`
+fn parse_relation(line: &str) -> Option<RequirementRelation> {
+    let relation = |from: &str, kind: &str, to: &str| {
+        let (from, kind, to) = (from.trim().trim_matches('"'), kind.trim(), to.trim().trim_matches('"'));
+        (!from.is_empty() && !kind.is_empty() && !to.is_empty()).then(|| RequirementRelation {
+            from: from.to_string(),
+            to: to.to_string(),
+            kind: kind.to_string(),
+        })
+    };
+    if let Some((to, rest)) = line.split_once("<-") {
+        let (kind, from) = rest.split_once(" - ")?;
+        relation(from, kind, to)
+    } else {
+        let (from, rest) = line.split_once(" - ")?;
+        let (kind, to) = rest.split_once("->")?;
+        relation(from, kind, to)
+    }
+}
+
+/// Generate shapes and connectors for a requirement diagram
+pub fn generate_elements(code: &str, theme: Option<&DiagramTheme>) -> MermaidResult<DiagramElements> {
+    let diagram = parse(code)?;
+    let theme = theme.cloned().unwrap_or_else(default_theme);
+    let mut shapes = Vec::new();
+    let mut connectors = Vec::new();
+
+    // Layout parameters
+    let box_width = 2_400_000u32;
+    let header_height = 500_000u32;
+    let field_height = 260_000u32;
+    let box_height = |node: &RequirementNode| header_height + (node.fields.len().max(1) as u32) * field_height;
+
+    let mut graph = LayoutGraph::new(FlowDirection::TopToBottom).with_spacing(600_000, 800_000);
+    for node in &diagram.nodes {
+        graph.add_node(box_width, box_height(node));
+    }
+    let index = |name: &str| diagram.nodes.iter().position(|n| n.name == name).unwrap_or_default();
+    for relation in &diagram.relations {
+        graph.add_edge(index(&relation.from), index(&relation.to));
+    }
+    let mut layout = graph.layout();
+    layout.fit(DIAGRAM_AREA);
+
+    let mut node_shape_ids = Vec::with_capacity(diagram.nodes.len());
+    let mut shape_id = 10u32;
+    for (node, rect) in diagram.nodes.iter().zip(&layout.nodes) {
+        let header_height = (header_height as f64 * rect.height as f64 / box_height(node) as f64) as u32;
+        let style = diagram.styles.node_style(&node.name);
+        // Elements are drawn lighter than requirements
+        let header_fill = if node.kind == "element" { &theme.muted } else { &theme.primary_fill };
+
+        node_shape_ids.push(shape_id);
+        let header = Shape::new(ShapeType::Rectangle, rect.x, rect.y, rect.width, header_height)
+            .with_id(shape_id)
+            .with_fill(ShapeFill::new(header_fill))
+            .with_line(ShapeLine::new(&theme.stroke, 2))
+            .with_text(&format!("«{}»\n{}", node.kind, node.name));
+        shapes.push(style.apply_to_shape(header));
+        shape_id += 1;
+
+        let fields: Vec<String> = node.fields.iter().map(|(key, value)| format!("{key}: {value}")).collect();
+        let body = Shape::new(ShapeType::Rectangle, rect.x, rect.y + header_height, rect.width, rect.height - header_height)
+            .with_id(shape_id)
+            .with_fill(ShapeFill::new(&theme.secondary_fill))
+            .with_line(ShapeLine::new(&theme.stroke, 1))
+            .with_text(&fields.join("\n"));
+        shapes.push(style.apply_to_shape(body));
+        shape_id += 1;
+    }
+
+    for ((link_index, relation), route) in diagram.relations.iter().enumerate().zip(&layout.edges) {
+        let (start_x, start_y) = route.start();
+        let (end_x, end_y) = route.end();
+        // `contains` is drawn solid like a composition, the rest dashed
+        let dash = if relation.kind == "contains" { LineDash::Solid } else { LineDash::Dash };
+        let line = ConnectorLine::new(&theme.line, 19050).with_dash(dash);
+
+        connectors.push(
+            Connector::new(ConnectorType::Elbow, start_x, start_y, end_x, end_y)
+                .with_line(diagram.styles.link_style(link_index).apply_to_line(line))
+                .with_end_arrow(ArrowType::Triangle)
+                .connect_start(node_shape_ids[index(&relation.from)], route.start_site)
+                .connect_end(node_shape_ids[index(&relation.to)], route.end_site)
+        );
+
+        let (label_width, label_height) = (1_000_000u32, 250_000u32);
+        let (mid_x, mid_y) = route.midpoint();
+        shapes.push(
+            Shape::new(ShapeType::RoundedRectangle, mid_x.saturating_sub(label_width / 2), mid_y.saturating_sub(label_height / 2), label_width, label_height)
+                .with_id(shape_id)
+                .with_fill(ShapeFill::new(&theme.node_fill))
+                .with_line(ShapeLine::new(&theme.muted, 1))
+                .with_text(&format!("«{}»", relation.kind))
+        );
+        shape_id += 1;
+    }
+
+    Ok(DiagramElements::from_shapes_and_connectors(shapes, connectors).with_warnings(diagram.warnings))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_requirement_diagram() {
+        let code = "requirementDiagram\n    requirement test_req {\n        id: 1\n        text: \"the test text.\"\n        risk: high\n    }\n    element test_entity {\n        type: simulation\n    }\n    test_entity - satisfies -> test_req\n    test_req <- copies - test_entity";
+        let diagram = parse(code).unwrap();
+        assert_eq!(diagram.nodes[0].fields[1], ("text".to_string(), "the test text.".to_string()));
+        assert_eq!(diagram.nodes[1].kind, "element");
+        assert_eq!(diagram.relations[1], RequirementRelation { from: "test_entity".into(), to: "test_req".into(), kind: "copies".into() });
+        assert!(diagram.warnings.is_empty());
+
+        let elements = generate_elements(code, None).unwrap();
+        assert_eq!(elements.connectors.len(), 2);
+        assert!(elements.shapes.iter().any(|s| s.text.as_deref() == Some("«requirement»\ntest_req")));
+        assert!(elements.shapes.iter().any(|s| s.text.as_deref() == Some("«satisfies»")));
+    }
+
+    #[test]
+    fn test_requirement_diagnostics() {
+        let code = "requirementDiagram\n    designConstraint dc {\n        riskHigh\n    }\n    dc - blocks -> dc\n    dc - satisfies -> ghost\n    element e {";
+        let diagram = parse(code).unwrap();
+        let warnings: Vec<(usize, usize)> = diagram.warnings.iter().map(|w| (w.line, w.column)).collect();
+        assert_eq!(warnings, [(3, 9), (5, 10), (6, 23), (7, 15)]);
+        assert!(parse("requirementDiagram").is_err());
+    }
+}
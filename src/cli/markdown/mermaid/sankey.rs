@@ -0,0 +1,275 @@
+//! Sankey diagram (`sankey-beta`) parsing and rendering
+//!
+//! Nodes are placed in columns by their longest path from a source; node
+//! heights and link widths are proportional to the flow.
+
+use crate::generator::{Shape, ShapeType, ShapeFill, ShapeLine};
+use crate::generator::connectors::{Connector, ConnectorType, ConnectorLine};
+use super::layout::DIAGRAM_AREA;
+use super::types::{DiagramElements, MermaidDiagnostic, MermaidResult};
+
+const NODE_COLORS: [&str; 8] = ["4472C4", "ED7D31", "70AD47", "FFC000", "5B9BD5", "8E7CC3", "C55A11", "A5A5A5"];
+
+/// A flow between two nodes
+#[derive(Debug, Clone, PartialEq)]
+pub struct SankeyLink {
+    pub source: usize,
+    pub target: usize,
+    pub value: f64,
+}
+
+/// Parsed sankey diagram
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sankey {
+    /// Node names, in order of appearance
+    pub nodes: Vec<String>,
+    pub links: Vec<SankeyLink>,
+    /// Lines that were dropped or only partly understood
+    pub warnings: Vec<MermaidDiagnostic>,
+}
+
+/// Parse the `source,target,value` rows of a sankey diagram
+pub fn parse(code: &str) -> MermaidResult<Sankey> {
+    let mut sankey = Sankey { nodes: Vec::new(), links: Vec::new(), warnings: Vec::new() };
+
+    for (index, source) in code.lines().enumerate().skip(1) {
+        let line = source.trim();
+        if line.is_empty() || line.starts_with("%%") {
+            continue;
+        }
+
+        let fields = split_csv(line);
+        let [from, to, value] = fields.as_slice() else {
+            sankey.warnings.push(MermaidDiagnostic::at(index, source, "", format!("expected `source,target,value`, found {} fields", fields.len())));
+            continue;
+        };
+        let value = match value.parse::<f64>() {
+            Ok(value) if value > 0.0 => value,
+            _ => {
+                sankey.warnings.push(MermaidDiagnostic::at(index, source, value, format!("`{value}` is not a positive number")));
+                continue;
+            }
+        };
+        let from = node_index(&mut sankey.nodes, from);
+        let to = node_index(&mut sankey.nodes, to);
+        if reaches(&sankey.links, to, from) {
+            let message = format!("flow `{}` -> `{}` would form a cycle", sankey.nodes[from], sankey.nodes[to]);
+            sankey.warnings.push(MermaidDiagnostic::at(index, source, "", message));
+            continue;
+        }
+        sankey.links.push(SankeyLink { source: from, target: to, value });
+    }
+
+    if sankey.links.is_empty() {
+        return Err(MermaidDiagnostic::empty("sankey diagram"));
+    }
+    Ok(sankey)
+}
+
+/// Whether `to` can be reached from `from` along the links
+fn reaches(links: &[SankeyLink], from: usize, to: usize) -> bool {
+    let mut stack = vec![from];
+    let mut seen = vec![from];
+    while let Some(node) = stack.pop() {
+        if node == to {
+            return true;
+        }
+        for link in links.iter().filter(|link| link.source == node) {
+            if !seen.contains(&link.target) {
+                seen.push(link.target);
+                stack.push(link.target);
+            }
+        }
+    }
+    false
+}
+
+fn node_index(nodes: &mut Vec<String>, name: &str) -> usize {
+    nodes.iter().position(|n| n == name).unwrap_or_else(|| {
+        nodes.push(name.to_string());
+        nodes.len() - 1
+    })
+}
+
+/// Split a CSV row; fields may be double-quoted with `""` escapes
+fn split_csv(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field).trim().to_string()),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field.trim().to_string());
+    fields
+}
+
+/// Column of every node: the longest path from a node without inputs
+fn columns(sankey: &Sankey) -> Vec<usize> {
+    let mut column = vec![0; sankey.nodes.len()];
+    // The flows are acyclic, so this settles within one pass per node
+    for _ in 0..sankey.nodes.len() {
+        let mut changed = false;
+        for link in &sankey.links {
+            if column[link.target] < column[link.source] + 1 {
+                column[link.target] = column[link.source] + 1;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+    column
+}
+
+/// Generate shapes and connectors for a sankey diagram
+pub fn generate_elements(code: &str) -> MermaidResult<DiagramElements> {
+    let sankey = parse(code)?;
+    let mut shapes = Vec::new();
+    let mut connectors = Vec::new();
+
+    let node_width = 150_000u32;
+    let label_width = 1_500_000u32;
+    let node_gap = 150_000u32;
+    let area = DIAGRAM_AREA;
+
+    // A node is as tall as the larger of its inflow and outflow
+    let n = sankey.nodes.len();
+    let mut inflow = vec![0.0; n];
+    let mut outflow = vec![0.0; n];
+    for link in &sankey.links {
+        outflow[link.source] += link.value;
+        inflow[link.target] += link.value;
+    }
+    let node_value: Vec<f64> = (0..n).map(|i| f64::max(inflow[i], outflow[i])).collect();
+
+    let column = columns(&sankey);
+    let column_count = column.iter().max().map_or(1, |max| max + 1);
+    let mut column_nodes: Vec<Vec<usize>> = vec![Vec::new(); column_count];
+    for (i, &c) in column.iter().enumerate() {
+        column_nodes[c].push(i);
+    }
+
+    // One scale for all columns so equal flows have equal widths
+    let scale = column_nodes.iter()
+        .filter(|nodes| !nodes.is_empty())
+        .map(|nodes| {
+            let gaps = node_gap * (nodes.len() as u32 - 1);
+            area.height.saturating_sub(gaps) as f64 / nodes.iter().map(|&i| node_value[i]).sum::<f64>()
+        })
+        .fold(f64::INFINITY, f64::min);
+
+    let column_step = if column_count > 1 { (area.width - node_width) / (column_count as u32 - 1) } else { 0 };
+    let mut rects = vec![(0u32, 0u32, 0u32); n];
+    for (c, nodes) in column_nodes.iter().enumerate() {
+        let total: u32 = nodes.iter().map(|&i| (node_value[i] * scale) as u32).sum::<u32>()
+            + node_gap * nodes.len().saturating_sub(1) as u32;
+        let x = area.x + column_step * c as u32;
+        let mut y = area.y + area.height.saturating_sub(total) / 2;
+        for &i in nodes {
+            let height = ((node_value[i] * scale) as u32).max(20_000);
+            rects[i] = (x, y, height);
+            y += height + node_gap;
+        }
+    }
+
+    let color = |i: usize| NODE_COLORS[i % NODE_COLORS.len()];
+
+    // Links first so the nodes are drawn on top of their ends
+    let mut out_offset = vec![0u32; n];
+    let mut in_offset = vec![0u32; n];
+    for link in &sankey.links {
+        let width = ((link.value * scale) as u32).max(12_700);
+        let (sx, sy, _) = rects[link.source];
+        let (tx, ty, _) = rects[link.target];
+        let start_y = sy + out_offset[link.source] + width / 2;
+        let end_y = ty + in_offset[link.target] + width / 2;
+        out_offset[link.source] += width;
+        in_offset[link.target] += width;
+
+        connectors.push(
+            Connector::new(ConnectorType::Curved, sx + node_width, start_y, tx, end_y)
+                .with_line(ConnectorLine::new(color(link.source), width))
+        );
+    }
+
+    let mut shape_id = 10u32;
+    for (i, name) in sankey.nodes.iter().enumerate() {
+        let (x, y, height) = rects[i];
+        shapes.push(
+            Shape::new(ShapeType::Rectangle, x, y, node_width, height)
+                .with_id(shape_id)
+                .with_fill(ShapeFill::new(color(i)))
+                .with_line(ShapeLine::new(color(i), 1))
+        );
+        shape_id += 1;
+
+        // Labels sit outside the node, to the left in the last column
+        let label_height = 300_000u32;
+        let label_x = if column[i] + 1 == column_count && column_count > 1 {
+            x.saturating_sub(label_width + 50_000)
+        } else {
+            x + node_width + 50_000
+        };
+        let label_y = (y + height / 2).saturating_sub(label_height / 2);
+        shapes.push(
+            Shape::new(ShapeType::Rectangle, label_x, label_y, label_width, label_height)
+                .with_id(shape_id)
+                .with_text(&format!("{name} ({})", format_value(node_value[i])))
+        );
+        shape_id += 1;
+    }
+
+    Ok(DiagramElements::from_shapes_and_connectors(shapes, connectors).with_warnings(sankey.warnings))
+}
+
+fn format_value(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{value:.0}")
+    } else {
+        format!("{}", (value * 100.0).round() / 100.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sankey() {
+        let code = "sankey-beta\n    Agricultural 'waste',Bio-conversion,124.729\n    Bio-conversion,Liquid,0.597\n    \"Heat, losses\",Liquid,10";
+        let sankey = parse(code).unwrap();
+        assert_eq!(sankey.nodes, ["Agricultural 'waste'", "Bio-conversion", "Liquid", "Heat, losses"]);
+        assert_eq!(sankey.links[2], SankeyLink { source: 3, target: 2, value: 10.0 });
+        assert_eq!(columns(&sankey), [0, 1, 2, 0]);
+    }
+
+    #[test]
+    fn test_sankey_widths_follow_values() {
+        let code = "sankey-beta\nA,B,10\nA,C,30";
+        let elements = generate_elements(code).unwrap();
+        assert_eq!(elements.connectors.len(), 2);
+        let (b, c) = (elements.connectors[0].line.width, elements.connectors[1].line.width);
+        assert!(c > b * 2 && c < b * 4);
+        assert!(elements.shapes.iter().any(|s| s.text.as_deref() == Some("C (30)")));
+    }
+
+    #[test]
+    fn test_sankey_diagnostics() {
+        let code = "sankey-beta\nA,B\nA,B,lots\nA,B,5\nB,A,1";
+        let sankey = parse(code).unwrap();
+        let warnings: Vec<(usize, usize)> = sankey.warnings.iter().map(|w| (w.line, w.column)).collect();
+        assert_eq!(warnings, [(2, 1), (3, 5), (5, 1)]);
+        assert_eq!(sankey.links.len(), 1);
+        assert!(parse("sankey-beta").is_err());
+    }
+}
@@ -2,20 +2,25 @@
 
 use std::collections::HashMap;
 use crate::generator::{Shape, ShapeType, ShapeFill, ShapeLine};
+use super::types::{DiagramElements, MermaidDiagnostic, MermaidResult};
 
 /// Generate shapes for a sequence diagram
-pub fn generate_shapes(code: &str) -> Vec<Shape> {
+pub fn generate_elements(code: &str) -> MermaidResult<DiagramElements> {
     let mut shapes = Vec::new();
+    let mut warnings = Vec::new();
     let mut participant_ids: Vec<String> = Vec::new();
     let mut participant_names: HashMap<String, String> = HashMap::new();
     let mut messages: Vec<(String, String, String)> = Vec::new();
     
-    for line in code.lines().skip(1) {
-        let line = line.trim();
+    for (index, source) in code.lines().enumerate().skip(1) {
+        let line = source.trim();
+        if line.is_empty() || line.starts_with("%%") {
+            continue;
+        }
         
-        // Parse participant
-        if line.starts_with("participant") {
-            let rest = line.strip_prefix("participant").unwrap_or("").trim();
+        // Parse participant (actors are drawn as participants)
+        if let Some(rest) = line.strip_prefix("participant").or_else(|| line.strip_prefix("actor ")) {
+            let rest = rest.trim();
             let (id, display_name) = if let Some((id, alias)) = rest.split_once(" as ") {
                 (id.trim().to_string(), alias.trim().to_string())
             } else {
@@ -30,27 +35,34 @@ pub fn generate_shapes(code: &str) -> Vec<Shape> {
         // Parse message
         else if line.contains("->>") || line.contains("-->>") {
             let arrow = if line.contains("-->>") { "-->>" } else { "->>" };
-            if let Some((from_part, rest)) = line.split_once(arrow) {
-                if let Some((to_part, msg)) = rest.split_once(':') {
-                    let from = from_part.trim().to_string();
-                    let to = to_part.trim().to_string();
-                    let text = msg.trim().to_string();
-                    
-                    if !participant_ids.contains(&from) {
-                        participant_ids.push(from.clone());
-                        participant_names.insert(from.clone(), from.clone());
-                    }
-                    if !participant_ids.contains(&to) {
-                        participant_ids.push(to.clone());
-                        participant_names.insert(to.clone(), to.clone());
-                    }
-                    
-                    messages.push((from, to, text));
-                }
+            let (from_part, rest) = line.split_once(arrow).unwrap_or_default();
+            let Some((to_part, msg)) = rest.split_once(':') else {
+                warnings.push(MermaidDiagnostic::at(index, source, arrow, "message needs `: text` after the target"));
+                continue;
+            };
+            let from = from_part.trim().to_string();
+            let to = to_part.trim().to_string();
+            let text = msg.trim().to_string();
+            
+            if !participant_ids.contains(&from) {
+                participant_ids.push(from.clone());
+                participant_names.insert(from.clone(), from.clone());
             }
+            if !participant_ids.contains(&to) {
+                participant_ids.push(to.clone());
+                participant_names.insert(to.clone(), to.clone());
+            }
+            
+            messages.push((from, to, text));
+        } else {
+            warnings.push(MermaidDiagnostic::unsupported(index, source));
         }
     }
     
+    if participant_ids.is_empty() {
+        return Err(MermaidDiagnostic::empty("sequence diagram"));
+    }
+    
     // Layout parameters
     let start_x = 500_000u32;
     let start_y = 1_600_000u32;
@@ -116,7 +128,7 @@ pub fn generate_shapes(code: &str) -> Vec<Shape> {
         }
     }
     
-    shapes
+    Ok(DiagramElements::from_shapes(shapes).with_warnings(warnings))
 }
 
 #[cfg(test)]
@@ -126,7 +138,19 @@ mod tests {
     #[test]
     fn test_generate_sequence_shapes() {
         let code = "sequenceDiagram\n    participant A as Alice\n    A->>B: Hello";
-        let shapes = generate_shapes(code);
-        assert!(!shapes.is_empty());
+        let elements = generate_elements(code).unwrap();
+        assert!(!elements.shapes.is_empty());
+        assert!(elements.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_sequence_diagnostics() {
+        let code = "sequenceDiagram\n    actor A\n    A->>B Hello\n    loop Every minute\n    A->>B: Ping\n    end";
+        let elements = generate_elements(code).unwrap();
+        let warnings: Vec<(usize, usize)> = elements.warnings.iter().map(|w| (w.line, w.column)).collect();
+        assert_eq!(warnings, [(3, 6), (4, 5), (6, 5)]);
+        assert_eq!(elements.warnings[1].message, "unsupported statement `loop Every minute`");
+
+        assert!(generate_elements("sequenceDiagram").is_err());
     }
 }
@@ -1,143 +1,193 @@
 //! State diagram parsing and rendering
 
-use std::collections::HashMap;
 use crate::generator::{Shape, ShapeType, ShapeFill, ShapeLine};
-use crate::generator::connectors::{Connector, ConnectorType, ConnectorLine, ArrowType, ConnectionSite};
-use super::types::DiagramElements;
+use crate::generator::connectors::{Connector, ConnectorType, ConnectorLine, ArrowType};
+use super::layout::{LayoutGraph, DIAGRAM_AREA};
+use super::style::{split_class_shorthand, DiagramTheme, Styles};
+use super::types::{DiagramElements, FlowDirection, MermaidDiagnostic, MermaidResult};
+
+/// Default state diagram colors
+fn default_theme() -> DiagramTheme {
+    DiagramTheme {
+        node_fill: "E0F7FA".to_string(),
+        primary_fill: "000000".to_string(),
+        secondary_fill: "FFFDE7".to_string(),
+        stroke: "00838F".to_string(),
+        line: "00838F".to_string(),
+        text: None,
+        accent: "00838F".to_string(),
+        muted: "00838F".to_string(),
+    }
+}
 
 /// Generate shapes and connectors for a state diagram
-pub fn generate_elements(code: &str) -> DiagramElements {
+pub fn generate_elements(code: &str, theme: Option<&DiagramTheme>) -> MermaidResult<DiagramElements> {
+    let theme = theme.cloned().unwrap_or_else(default_theme);
+    let mut styles = Styles::default();
+    let mut warnings = Vec::new();
     let mut shapes = Vec::new();
     let mut connectors = Vec::new();
     
     let mut states: Vec<String> = Vec::new();
     let mut transitions: Vec<(String, String, String)> = Vec::new();
+    let mut direction = FlowDirection::TopToBottom;
     
-    for line in code.lines().skip(1) {
-        let line = line.trim();
-        if line.is_empty() || line.starts_with("%%") || line.starts_with("direction") {
+    for (index, source) in code.lines().enumerate().skip(1) {
+        let line = source.trim();
+        if line.is_empty() || line.starts_with("%%") || styles.parse_line(index, source) {
+            continue;
+        }
+        if let Some(dir) = line.strip_prefix("direction") {
+            match FlowDirection::from_keyword(dir) {
+                Some(dir) => direction = dir,
+                None => warnings.push(MermaidDiagnostic::at(index, source, dir.trim(), format!("unknown direction `{}`", dir.trim()))),
+            }
             continue;
         }
         
+        let mut state_name = |state: &str, terminal: &str| {
+            let (state, class) = split_class_shorthand(state);
+            if let Some(class) = class {
+                styles.add_class(state, class, index, source);
+            }
+            if state == "[*]" { terminal.to_string() } else { state.to_string() }
+        };
+        
         if line.contains("-->") {
             let parts: Vec<&str> = line.split("-->").collect();
-            if parts.len() >= 2 {
-                let from = parts[0].trim().to_string();
-                let (to, label) = if let Some((t, l)) = parts[1].split_once(':') {
-                    (t.trim().to_string(), l.trim().to_string())
-                } else {
-                    (parts[1].trim().to_string(), String::new())
-                };
-                
-                let from_state = if from == "[*]" { "Start".to_string() } else { from };
-                let to_state = if to == "[*]" { "End".to_string() } else { to };
-                
-                if !states.contains(&from_state) { states.push(from_state.clone()); }
-                if !states.contains(&to_state) { states.push(to_state.clone()); }
-                
-                transitions.push((from_state, to_state, label));
+            if let Some((extra, _)) = source.match_indices("-->").nth(1) {
+                warnings.push(MermaidDiagnostic::at(index, source, &source[extra..], "a transition takes a single `-->`, the rest is ignored"));
             }
+            let from = parts[0].trim().to_string();
+            let (to, label) = split_label(parts[1]);
+            let (to, label) = (to.to_string(), label.to_string());
+            if from.is_empty() || to.is_empty() {
+                warnings.push(MermaidDiagnostic::at(index, source, "-->", "transition needs a state on both sides"));
+                continue;
+            }
+            
+            let from_state = state_name(&from, "Start");
+            let to_state = state_name(&to, "End");
+            
+            if !states.contains(&from_state) { states.push(from_state.clone()); }
+            if !states.contains(&to_state) { states.push(to_state.clone()); }
+            
+            transitions.push((from_state, to_state, label));
+        } else if split_class_shorthand(line).0.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            // A bare state declaration, possibly with a `:::class` shorthand
+            let state = state_name(line, "Start");
+            if !states.contains(&state) { states.push(state); }
+        } else {
+            warnings.push(MermaidDiagnostic::unsupported(index, source));
         }
     }
     
+    if states.is_empty() {
+        return Err(MermaidDiagnostic::empty("state diagram"));
+    }
+    warnings.extend(styles.diagnostics(transitions.len()));
+    
     // Layout parameters
-    let start_x = 1_000_000u32;
-    let start_y = 1_800_000u32;
     let state_width = 1_500_000u32;
     let state_height = 500_000u32;
-    let h_spacing = 2_200_000u32;
-    let v_spacing = 1_200_000u32;
+    let terminal_size = 400_000u32;
     
-    let mut state_positions: HashMap<String, (u32, u32)> = HashMap::new();
-    let mut state_shape_ids: HashMap<String, u32> = HashMap::new();
+    let is_terminal = |state: &str| state == "Start" || state == "End";
+    let mut graph = LayoutGraph::new(direction);
+    for state in &states {
+        if is_terminal(state) {
+            graph.add_node(terminal_size, terminal_size);
+        } else {
+            graph.add_node(state_width, state_height);
+        }
+    }
+    let index = |name: &str| states.iter().position(|s| s == name);
+    for (from, to, _) in &transitions {
+        if let (Some(from), Some(to)) = (index(from), index(to)) {
+            graph.add_edge(from, to);
+        }
+    }
+    let mut layout = graph.layout();
+    layout.fit(DIAGRAM_AREA);
+    
+    let mut state_shape_ids: Vec<u32> = Vec::with_capacity(states.len());
     let mut shape_id = 10u32;
     
-    for (i, state) in states.iter().enumerate() {
-        let x = start_x + (i as u32 % 3) * h_spacing;
-        let y = start_y + (i as u32 / 3) * v_spacing;
-        state_positions.insert(state.clone(), (x, y));
-        state_shape_ids.insert(state.clone(), shape_id);
+    for (state, rect) in states.iter().zip(&layout.nodes) {
+        state_shape_ids.push(shape_id);
         
-        let shape_type = if state == "Start" || state == "End" {
+        let shape_type = if is_terminal(state) {
             ShapeType::Ellipse
         } else {
             ShapeType::RoundedRectangle
         };
         
-        let fill_color = if state == "Start" { "000000" }
-                        else if state == "End" { "000000" }
-                        else { "E0F7FA" };
+        let fill_color = if is_terminal(state) { &theme.primary_fill } else { &theme.node_fill };
         
-        let shape = Shape::new(shape_type, x, y, state_width, state_height)
+        let mut shape = Shape::new(shape_type, rect.x, rect.y, rect.width, rect.height)
             .with_id(shape_id)
             .with_fill(ShapeFill::new(fill_color))
-            .with_line(ShapeLine::new("00838F", 2))
-            .with_text(state);
-        shapes.push(shape);
+            .with_line(ShapeLine::new(&theme.stroke, 2));
+        if !is_terminal(state) {
+            shape = theme.style_text(shape.with_text(state));
+        }
+        shapes.push(styles.node_style(state).apply_to_shape(shape));
         shape_id += 1;
     }
     
-    for (from, to, label) in &transitions {
-        if let (Some(&(from_x, from_y)), Some(&(to_x, to_y))) = 
-            (state_positions.get(from), state_positions.get(to)) 
-        {
-            let from_shape_id = state_shape_ids.get(from).copied();
-            let to_shape_id = state_shape_ids.get(to).copied();
-            
-            // Smart connection site selection
-            let (start_site, end_site) = if from_x < to_x {
-                (ConnectionSite::Right, ConnectionSite::Left)
-            } else if from_x > to_x {
-                (ConnectionSite::Left, ConnectionSite::Right)
-            } else if from_y < to_y {
-                (ConnectionSite::Bottom, ConnectionSite::Top)
-            } else {
-                (ConnectionSite::Top, ConnectionSite::Bottom)
-            };
+    // Transitions between known states map one-to-one to the layout edges
+    let routed = transitions.iter()
+        .enumerate()
+        .filter_map(|(i, (from, to, label))| Some((i, index(from)?, index(to)?, label)))
+        .zip(&layout.edges);
+    for ((link_index, from, to, label), route) in routed {
+        let (start_x, start_y) = route.start();
+        let (end_x, end_y) = route.end();
+        
+        let connector = Connector::new(ConnectorType::Elbow, start_x, start_y, end_x, end_y)
+            .with_line(styles.link_style(link_index).apply_to_line(ConnectorLine::new(&theme.line, 19050)))
+            .with_end_arrow(ArrowType::Triangle)
+            .connect_start(state_shape_ids[from], route.start_site)
+            .connect_end(state_shape_ids[to], route.end_site);
+        
+        // Create separate label shape for better font control
+        if !label.is_empty() {
+            let label_width = 800_000u32;
+            let label_height = 250_000u32;
+            let (mid_x, mid_y) = route.midpoint();
             
-            let mut connector = Connector::new(
-                ConnectorType::Elbow,
-                from_x + state_width, from_y + state_height / 2,
-                to_x, to_y + state_height / 2
+            let label_shape = Shape::new(
+                ShapeType::RoundedRectangle,
+                mid_x.saturating_sub(label_width / 2),
+                mid_y.saturating_sub(label_height / 2),
+                label_width,
+                label_height
             )
-            .with_line(ConnectorLine::new("00838F", 19050))
-            .with_end_arrow(ArrowType::Triangle);
-            
-            // Anchor to shapes
-            if let Some(id) = from_shape_id {
-                connector = connector.connect_start(id, start_site);
-            }
-            if let Some(id) = to_shape_id {
-                connector = connector.connect_end(id, end_site);
-            }
+            .with_id(shape_id)
+            .with_fill(ShapeFill::new(&theme.secondary_fill))
+            .with_line(ShapeLine::new(&theme.muted, 1))
+            .with_text(label);
             
-            // Create separate label shape for better font control
-            if !label.is_empty() {
-                let label_width = 800_000u32;
-                let label_height = 250_000u32;
-                let mid_x = (from_x + state_width + to_x) / 2;
-                let mid_y = (from_y + to_y + state_height) / 2;
-                
-                let label_shape = Shape::new(
-                    ShapeType::RoundedRectangle,
-                    mid_x.saturating_sub(label_width / 2),
-                    mid_y.saturating_sub(label_height / 2),
-                    label_width,
-                    label_height
-                )
-                .with_id(shape_id)
-                .with_fill(ShapeFill::new("FFFDE7"))
-                .with_line(ShapeLine::new("00838F", 1))
-                .with_text(label);
-                
-                shapes.push(label_shape);
-                shape_id += 1;
-            }
-            connectors.push(connector);
+            shapes.push(label_shape);
+            shape_id += 1;
         }
+        connectors.push(connector);
     }
     
-    DiagramElements::from_shapes_and_connectors(shapes, connectors)
+    Ok(DiagramElements::from_shapes_and_connectors(shapes, connectors).with_warnings(warnings))
+}
+
+/// Split a transition target from its `: label`, skipping `:::class` shorthands
+fn split_label(target: &str) -> (&str, &str) {
+    let mut search = 0;
+    while let Some(pos) = target[search..].find(':').map(|p| p + search) {
+        if target[pos..].starts_with(":::") {
+            search = pos + 3;
+            continue;
+        }
+        return (target[..pos].trim(), target[pos + 1..].trim());
+    }
+    (target.trim(), "")
 }
 
 #[cfg(test)]
@@ -147,7 +197,39 @@ mod tests {
     #[test]
     fn test_generate_state_diagram() {
         let code = "stateDiagram\n    [*] --> Active\n    Active --> [*]";
-        let elements = generate_elements(code);
+        let elements = generate_elements(code, None).unwrap();
         assert!(!elements.shapes.is_empty());
     }
+
+    #[test]
+    fn test_state_diagram_direction() {
+        let code = "stateDiagram-v2\n    direction LR\n    [*] --> Idle\n    Idle --> Busy : start\n    Busy --> Idle : done";
+        let elements = generate_elements(code, None).unwrap();
+        // Start, Idle, Busy and two transition labels
+        assert_eq!(elements.shapes.len(), 5);
+        assert_eq!(elements.connectors.len(), 3);
+        assert!(elements.shapes[0].x < elements.shapes[1].x && elements.shapes[1].x < elements.shapes[2].x);
+    }
+
+    #[test]
+    fn test_state_diagram_classes() {
+        let code = "stateDiagram-v2\n    classDef bad fill:#f00,color:white\n    [*] --> Idle\n    Idle --> Failed:::bad\n    class Idle bad";
+        let elements = generate_elements(code, None).unwrap();
+        assert_eq!(elements.shapes.len(), 3);
+        assert_eq!(elements.shapes[1].fill.as_ref().unwrap().color, "FF0000");
+        assert_eq!(elements.shapes[2].fill.as_ref().unwrap().color, "FF0000");
+        assert_eq!(elements.shapes[2].text.as_deref(), Some("Failed"));
+    }
+
+    #[test]
+    fn test_state_diagram_diagnostics() {
+        let code = "stateDiagram-v2\n    Waiting\n    Idle --> Busy --> Done\n    state \"Long name\" as Long\n    --> Idle";
+        let elements = generate_elements(code, None).unwrap();
+        // Waiting, Idle and Busy
+        assert_eq!(elements.shapes.len(), 3);
+        let warnings: Vec<(usize, usize)> = elements.warnings.iter().map(|w| (w.line, w.column)).collect();
+        assert_eq!(warnings, [(3, 19), (4, 5), (5, 5)]);
+
+        assert!(generate_elements("stateDiagram-v2\n    %% empty", None).is_err());
+    }
 }
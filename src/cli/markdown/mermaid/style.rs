@@ -0,0 +1,511 @@
+//! Diagram styling: `classDef`, `class`, `style`, `linkStyle` and theme directives
+
+use std::collections::HashMap;
+
+use super::types::MermaidDiagnostic;
+use crate::generator::{Shape, ShapeFill, ShapeLine};
+use crate::generator::connectors::{ConnectorLine, LineDash};
+use crate::prelude::themes::{self, Theme};
+
+/// EMU per CSS pixel
+const EMU_PER_PX: u32 = 9525;
+
+/// Fill, stroke and text styling of a node or link
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Style {
+    pub fill: Option<String>,
+    pub stroke: Option<String>,
+    /// Text color
+    pub color: Option<String>,
+    /// Stroke width in EMU
+    pub stroke_width: Option<u32>,
+    pub dash: Option<LineDash>,
+}
+
+impl Style {
+    /// Parse CSS-like declarations such as `fill:#f9f,stroke:#333,stroke-width:4px`,
+    /// also returning the color values that are not valid colors
+    pub fn parse(declarations: &str) -> (Self, Vec<&str>) {
+        let mut style = Style::default();
+        let mut invalid = Vec::new();
+        for declaration in split_declarations(declarations) {
+            let Some((property, value)) = declaration.split_once(':') else { continue };
+            let value = value.trim().trim_end_matches("!important").trim();
+            let mut color = || {
+                let color = parse_color(value);
+                if color.is_none() {
+                    invalid.push(value);
+                }
+                color
+            };
+            match property.trim() {
+                "fill" | "background" | "background-color" => style.fill = color(),
+                "stroke" => style.stroke = color(),
+                "color" => style.color = color(),
+                "stroke-width" => {
+                    style.stroke_width = value.trim_end_matches("px").trim().parse::<f64>().ok()
+                        .map(|px| (px * EMU_PER_PX as f64).round() as u32);
+                }
+                "stroke-dasharray" => style.dash = Some(parse_dash(value)),
+                _ => {}
+            }
+        }
+        (style, invalid)
+    }
+
+    /// Override this style with the properties set in `other`
+    pub fn merge(&mut self, other: &Style) {
+        if other.fill.is_some() {
+            self.fill.clone_from(&other.fill);
+        }
+        if other.stroke.is_some() {
+            self.stroke.clone_from(&other.stroke);
+        }
+        if other.color.is_some() {
+            self.color.clone_from(&other.color);
+        }
+        self.stroke_width = other.stroke_width.or(self.stroke_width);
+        self.dash = other.dash.or(self.dash);
+    }
+
+    /// Apply the style to a node shape, keeping its current values where unset
+    pub fn apply_to_shape(&self, mut shape: Shape) -> Shape {
+        if let Some(ref fill) = self.fill {
+            shape = shape.with_fill(ShapeFill::new(fill));
+        }
+        if self.stroke.is_some() || self.stroke_width.is_some() || self.dash.is_some() {
+            let mut line = shape.line.take().unwrap_or_else(|| ShapeLine::new("000000", 12700));
+            if let Some(ref stroke) = self.stroke {
+                line.color.clone_from(stroke);
+            }
+            line.width = self.stroke_width.unwrap_or(line.width);
+            line.dash = self.dash.unwrap_or(line.dash);
+            shape = shape.with_line(line);
+        }
+        if let Some(ref color) = self.color {
+            shape = shape.with_text_color(color);
+        }
+        shape
+    }
+
+    /// Apply the style to a link line
+    pub fn apply_to_line(&self, mut line: ConnectorLine) -> ConnectorLine {
+        if let Some(ref stroke) = self.stroke {
+            line.color.clone_from(stroke);
+        }
+        line.width = self.stroke_width.unwrap_or(line.width);
+        line.dash = self.dash.unwrap_or(line.dash);
+        line
+    }
+}
+
+/// Split declarations at `,` or `;`, keeping commas inside `rgb(...)`
+fn split_declarations(declarations: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let (mut depth, mut start) = (0, 0);
+    for (i, c) in declarations.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' | ';' if depth == 0 => {
+                parts.push(declarations[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(declarations[start..].trim());
+    parts.retain(|p| !p.is_empty());
+    parts
+}
+
+/// Map a `stroke-dasharray` to the closest preset dash
+fn parse_dash(value: &str) -> LineDash {
+    let lengths: Vec<f64> = value
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter_map(|v| v.trim_end_matches("px").parse().ok())
+        .collect();
+    match lengths.as_slice() {
+        [] | [0.0, ..] => LineDash::Solid,
+        [dash, ..] if *dash <= 2.0 => LineDash::Dot,
+        [dash, ..] if *dash >= 10.0 => LineDash::LongDash,
+        _ => LineDash::Dash,
+    }
+}
+
+/// Parse a CSS color (`#rgb`, `#rrggbb`, `rgb(r, g, b)` or a common name) to hex
+pub fn parse_color(value: &str) -> Option<String> {
+    let value = value.trim();
+    if let Some(hex) = value.strip_prefix('#') {
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        return match hex.len() {
+            3 | 4 => Some(hex.chars().take(3).flat_map(|c| [c, c]).collect::<String>().to_uppercase()),
+            6 | 8 => Some(hex[..6].to_uppercase()),
+            _ => None,
+        };
+    }
+    if let Some(args) = value.strip_prefix("rgba(").or_else(|| value.strip_prefix("rgb(")) {
+        let channels: Vec<u8> = args
+            .trim_end_matches(')')
+            .split(',')
+            .take(3)
+            .filter_map(|c| c.trim().parse::<f64>().ok())
+            .map(|c| c.clamp(0.0, 255.0) as u8)
+            .collect();
+        return (channels.len() == 3).then(|| format!("{:02X}{:02X}{:02X}", channels[0], channels[1], channels[2]));
+    }
+    let hex = match value.to_lowercase().as_str() {
+        "black" => "000000",
+        "white" => "FFFFFF",
+        "red" => "FF0000",
+        "green" => "008000",
+        "lime" => "00FF00",
+        "blue" => "0000FF",
+        "yellow" => "FFFF00",
+        "orange" => "FFA500",
+        "purple" => "800080",
+        "pink" => "FFC0CB",
+        "gray" | "grey" => "808080",
+        "lightgray" | "lightgrey" => "D3D3D3",
+        "darkgray" | "darkgrey" => "A9A9A9",
+        "navy" => "000080",
+        "teal" => "008080",
+        "maroon" => "800000",
+        "olive" => "808000",
+        "cyan" | "aqua" => "00FFFF",
+        "magenta" | "fuchsia" => "FF00FF",
+        "silver" => "C0C0C0",
+        "gold" => "FFD700",
+        "brown" => "A52A2A",
+        "lightblue" => "ADD8E6",
+        "lightgreen" => "90EE90",
+        "darkblue" => "00008B",
+        "darkgreen" => "006400",
+        "darkred" => "8B0000",
+        _ => return None,
+    };
+    Some(hex.to_string())
+}
+
+/// Styling directives collected from a diagram
+#[derive(Debug, Clone, Default)]
+pub struct Styles {
+    classes: HashMap<String, Style>,
+    /// Classes assigned to each node, in assignment order
+    node_classes: HashMap<String, Vec<String>>,
+    node_styles: HashMap<String, Style>,
+    link_styles: HashMap<usize, Style>,
+    default_link: Style,
+    /// Invalid colors
+    warnings: Vec<MermaidDiagnostic>,
+    /// Where each class is assigned, checked once all `classDef`s are read
+    class_uses: Vec<(String, MermaidDiagnostic)>,
+    /// Where each `linkStyle` index is given, checked against the link count
+    link_uses: Vec<(usize, MermaidDiagnostic)>,
+}
+
+impl Styles {
+    /// Record a styling statement from the source line with 0-based index
+    /// `index`; returns false for any other line
+    pub fn parse_line(&mut self, index: usize, source: &str) -> bool {
+        let line = source.trim().trim_end_matches(';');
+        let Some((keyword, rest)) = line.split_once(char::is_whitespace) else { return false };
+        let rest = rest.trim();
+        match keyword {
+            "classDef" => {
+                let (names, declarations) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+                let style = self.parse_style(index, source, declarations);
+                for name in names.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+                    self.classes.entry(name.to_string()).or_default().merge(&style);
+                }
+            }
+            "class" | "cssClass" => {
+                let Some((nodes, class)) = rest.rsplit_once(char::is_whitespace) else { return false };
+                let class = class.trim().trim_matches('"');
+                for node in nodes.trim().trim_matches('"').split(',').map(str::trim).filter(|n| !n.is_empty()) {
+                    self.add_class(node, class, index, source);
+                }
+            }
+            "style" => {
+                let (node, declarations) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+                let style = self.parse_style(index, source, declarations);
+                self.node_styles.entry(node.to_string()).or_default().merge(&style);
+            }
+            "linkStyle" => {
+                let (links, declarations) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+                let style = self.parse_style(index, source, declarations);
+                for link in links.split(',').map(str::trim) {
+                    if link == "default" {
+                        self.default_link.merge(&style);
+                    } else if let Ok(link_index) = link.parse::<usize>() {
+                        self.link_styles.entry(link_index).or_default().merge(&style);
+                        self.link_uses.push((link_index, MermaidDiagnostic::at(index, source, link, "")));
+                    }
+                }
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    fn parse_style(&mut self, index: usize, source: &str, declarations: &str) -> Style {
+        let (style, invalid) = Style::parse(declarations);
+        for value in invalid {
+            self.warnings.push(MermaidDiagnostic::at(index, source, value, format!("`{value}` is not a color")));
+        }
+        style
+    }
+
+    /// Assign a class to a node (`class A name` or the `A:::name` shorthand)
+    /// on the source line with 0-based index `index`
+    pub fn add_class(&mut self, node: &str, class: &str, index: usize, source: &str) {
+        self.node_classes.entry(node.to_string()).or_default().push(class.to_string());
+        self.class_uses.push((class.to_string(), MermaidDiagnostic::at(index, source, class, format!("undefined class `{class}`"))));
+    }
+
+    /// Invalid colors, classes without a `classDef` and `linkStyle` indexes
+    /// past the `link_count` links of the diagram
+    pub fn diagnostics(&self, link_count: usize) -> Vec<MermaidDiagnostic> {
+        let undefined = self.class_uses.iter()
+            .filter(|(class, _)| !self.classes.contains_key(class))
+            .map(|(_, diagnostic)| diagnostic.clone());
+        let out_of_range = self.link_uses.iter()
+            .filter(|&&(link, _)| link >= link_count)
+            .map(|(link, diagnostic)| MermaidDiagnostic {
+                message: format!("`linkStyle {link}` is out of range, the diagram has {link_count} links"),
+                ..diagnostic.clone()
+            });
+        let mut diagnostics: Vec<MermaidDiagnostic> = self.warnings.iter().cloned().chain(undefined).chain(out_of_range).collect();
+        diagnostics.sort_by_key(|diagnostic| (diagnostic.line, diagnostic.column));
+        diagnostics
+    }
+
+    /// Resolved style of a node: the `default` class, its classes, then its `style`
+    pub fn node_style(&self, node: &str) -> Style {
+        let mut style = self.classes.get("default").cloned().unwrap_or_default();
+        for class in self.node_classes.get(node).into_iter().flatten() {
+            if let Some(class_style) = self.classes.get(class) {
+                style.merge(class_style);
+            }
+        }
+        if let Some(node_style) = self.node_styles.get(node) {
+            style.merge(node_style);
+        }
+        style
+    }
+
+    /// Resolved style of the link at `index` in declaration order
+    pub fn link_style(&self, index: usize) -> Style {
+        let mut style = self.default_link.clone();
+        if let Some(link_style) = self.link_styles.get(&index) {
+            style.merge(link_style);
+        }
+        style
+    }
+}
+
+/// Split a node reference into its ID and a `:::class` shorthand
+pub fn split_class_shorthand(node: &str) -> (&str, Option<&str>) {
+    match node.split_once(":::") {
+        Some((id, class)) => (id.trim(), Some(class.trim())),
+        None => (node, None),
+    }
+}
+
+/// Colors used to draw a diagram
+///
+/// Each diagram type has its own default palette; a theme directive replaces
+/// it with colors from `prelude::themes`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiagramTheme {
+    /// Fill of plain nodes
+    pub node_fill: String,
+    /// Fill of emphasised parts: decisions, class and entity headers
+    pub primary_fill: String,
+    /// Fill of secondary parts: subgraphs, class and entity bodies
+    pub secondary_fill: String,
+    pub stroke: String,
+    pub line: String,
+    /// Text color of plain nodes; contrasts with the fill when unset
+    pub text: Option<String>,
+    /// Thick links
+    pub accent: String,
+    /// Dotted links and subgraph borders
+    pub muted: String,
+}
+
+impl DiagramTheme {
+    /// Diagram colors from a presentation theme
+    pub fn from_theme(theme: &Theme) -> Self {
+        DiagramTheme {
+            node_fill: theme.background.to_string(),
+            primary_fill: theme.primary.to_string(),
+            secondary_fill: theme.light.to_string(),
+            stroke: theme.primary.to_string(),
+            line: theme.secondary.to_string(),
+            text: None,
+            accent: theme.accent.to_string(),
+            muted: theme.secondary.to_string(),
+        }
+    }
+
+    /// Presentation theme for a Mermaid theme name
+    ///
+    /// Mermaid's built-in themes map to `default` → Corporate, `neutral` →
+    /// Modern, `dark` → Dark, `forest` → Nature and `base` → Carbon; the names
+    /// of `prelude::themes` are accepted as well.
+    pub fn named(name: &str) -> Option<Theme> {
+        match name.to_lowercase().as_str() {
+            "default" => Some(themes::CORPORATE),
+            "neutral" => Some(themes::MODERN),
+            "dark" => Some(themes::DARK),
+            "forest" => Some(themes::NATURE),
+            "base" => Some(themes::CARBON),
+            other => themes::all().into_iter().find(|t| t.name.eq_ignore_ascii_case(other)),
+        }
+    }
+
+    /// Parse an `init` directive body such as `init: {'theme': 'forest'}`
+    ///
+    /// `themeVariables` override single colors: `primaryColor`,
+    /// `primaryBorderColor`, `primaryTextColor`, `secondaryColor`,
+    /// `tertiaryColor` and `lineColor`.
+    pub fn from_init(directive: &str) -> Option<Self> {
+        let body = directive.trim();
+        let body = body.strip_prefix("initialize").or_else(|| body.strip_prefix("init"))?;
+        let body = body.trim_start().trim_start_matches(':').trim().replace('\'', "\"");
+        let config: serde_json::Value = serde_json::from_str(&body).ok()?;
+
+        let theme = config.get("theme").and_then(|t| t.as_str()).and_then(Self::named);
+        let variables = config.get("themeVariables").and_then(|v| v.as_object());
+        if theme.is_none() && variables.is_none() {
+            return None;
+        }
+
+        let mut diagram_theme = Self::from_theme(&theme.unwrap_or(themes::CORPORATE));
+        let variable = |name: &str| variables?.get(name)?.as_str().and_then(parse_color);
+        if let Some(color) = variable("primaryColor") {
+            diagram_theme.node_fill = color;
+        }
+        if let Some(color) = variable("primaryBorderColor") {
+            diagram_theme.stroke = color;
+        }
+        diagram_theme.text = variable("primaryTextColor");
+        if let Some(color) = variable("secondaryColor") {
+            diagram_theme.primary_fill = color;
+        }
+        if let Some(color) = variable("tertiaryColor") {
+            diagram_theme.secondary_fill = color;
+        }
+        if let Some(color) = variable("lineColor") {
+            diagram_theme.line = color;
+        }
+        Some(diagram_theme)
+    }
+
+    /// Apply the node text color, if set
+    pub fn style_text(&self, shape: Shape) -> Shape {
+        match self.text {
+            Some(ref color) => shape.with_text_color(color),
+            None => shape,
+        }
+    }
+}
+
+/// Split leading `%%{ ... }%%` directives off a diagram
+///
+/// Returns the theme set by an `init` directive and the remaining code, which
+/// starts with the diagram keyword.
+pub fn split_directives(code: &str) -> (Option<DiagramTheme>, &str) {
+    let mut theme = None;
+    let mut rest = code.trim_start();
+    loop {
+        if let Some(directive) = rest.strip_prefix("%%{") {
+            let Some(end) = directive.find("}%%") else { break };
+            theme = DiagramTheme::from_init(&directive[..end]).or(theme);
+            rest = directive[end + 3..].trim_start();
+        } else if rest.starts_with("%%") {
+            // Comment line before the diagram keyword
+            rest = rest.split_once('\n').map_or("", |(_, after)| after).trim_start();
+        } else {
+            break;
+        }
+    }
+    (theme, rest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::ShapeType;
+
+    #[test]
+    fn test_parse_style() {
+        let (style, invalid) = Style::parse("fill:#f9f,stroke:rgb(51, 51, 51),stroke-width:4px,color:white,stroke-dasharray: 5 5");
+        assert!(invalid.is_empty());
+        assert_eq!(style.fill.as_deref(), Some("FF99FF"));
+        assert_eq!(style.stroke.as_deref(), Some("333333"));
+        assert_eq!(style.color.as_deref(), Some("FFFFFF"));
+        assert_eq!(style.stroke_width, Some(38_100));
+        assert_eq!(style.dash, Some(LineDash::Dash));
+        assert_eq!(Style::parse("font-size:12px"), (Style::default(), vec![]));
+        assert_eq!(Style::parse("fill:notacolor,stroke:#12").1, ["notacolor", "#12"]);
+    }
+
+    #[test]
+    fn test_styles_resolution() {
+        let mut styles = Styles::default();
+        assert!(styles.parse_line(0, "classDef default fill:#eee,stroke:#999"));
+        assert!(styles.parse_line(0, "classDef hot,warm fill:#f96;"));
+        assert!(styles.parse_line(0, "class A,B hot"));
+        assert!(styles.parse_line(0, "style B stroke:#f00,stroke-width:2px"));
+        assert!(styles.parse_line(0, "linkStyle default stroke:#aaa"));
+        assert!(styles.parse_line(0, "linkStyle 1,2 stroke:#0f0,stroke-dasharray:2"));
+        assert!(!styles.parse_line(0, "A --> B"));
+
+        let a = styles.node_style("A");
+        assert_eq!((a.fill.as_deref(), a.stroke.as_deref()), (Some("FF9966"), Some("999999")));
+        let b = styles.node_style("B");
+        assert_eq!((b.stroke.as_deref(), b.stroke_width), (Some("FF0000"), Some(19_050)));
+        assert_eq!(styles.node_style("C").fill.as_deref(), Some("EEEEEE"));
+        assert_eq!(styles.link_style(0).stroke.as_deref(), Some("AAAAAA"));
+        assert_eq!(styles.link_style(2).dash, Some(LineDash::Dot));
+
+        let shape = styles.node_style("B").apply_to_shape(Shape::new(ShapeType::Rectangle, 0, 0, 10, 10));
+        assert_eq!(shape.fill.unwrap().color, "FF9966");
+        assert_eq!(shape.line.unwrap().color, "FF0000");
+        assert!(styles.diagnostics(3).is_empty());
+    }
+
+    #[test]
+    fn test_style_diagnostics() {
+        let mut styles = Styles::default();
+        assert!(styles.parse_line(0, "    classDef hot fill:#f96"));
+        assert!(styles.parse_line(1, "    style A fill:notacolor,stroke:#333"));
+        assert!(styles.parse_line(2, "    class A,B hot"));
+        styles.add_class("C", "undefinedClass", 3, "    C:::undefinedClass --> A");
+        assert!(styles.parse_line(4, "    linkStyle 0,99 stroke:#f00"));
+
+        assert_eq!(styles.diagnostics(2), [
+            MermaidDiagnostic::new(2, 18, "`notacolor` is not a color"),
+            MermaidDiagnostic::new(4, 9, "undefined class `undefinedClass`"),
+            MermaidDiagnostic::new(5, 17, "`linkStyle 99` is out of range, the diagram has 2 links"),
+        ]);
+    }
+
+    #[test]
+    fn test_theme_directive() {
+        let code = "%%{init: {'theme': 'forest', 'themeVariables': {'lineColor': '#ff0000'}}}%%\nflowchart LR\n    A --> B";
+        let (theme, rest) = split_directives(code);
+        let theme = theme.unwrap();
+        assert_eq!(theme.stroke, themes::NATURE.primary);
+        assert_eq!(theme.line, "FF0000");
+        assert!(rest.starts_with("flowchart LR"));
+
+        assert_eq!(DiagramTheme::named("dark").unwrap().name, "Dark");
+        assert_eq!(DiagramTheme::named("Vibrant").unwrap().name, "Vibrant");
+        assert!(split_directives("graph TD\n    A").0.is_none());
+    }
+}
@@ -1,18 +1,20 @@
 //! Timeline diagram parsing and rendering
 
 use crate::generator::{Shape, ShapeType, ShapeFill, ShapeLine};
+use super::types::{DiagramElements, MermaidDiagnostic, MermaidResult};
 
 /// Generate shapes for a timeline
-pub fn generate_shapes(code: &str) -> Vec<Shape> {
+pub fn generate_elements(code: &str) -> MermaidResult<DiagramElements> {
     let mut shapes = Vec::new();
+    let mut warnings = Vec::new();
     
     let mut title = String::new();
     let mut events: Vec<(String, Vec<String>)> = Vec::new();
     let mut current_date = String::new();
     let mut current_items: Vec<String> = Vec::new();
     
-    for line in code.lines().skip(1) {
-        let line = line.trim();
+    for (index, source) in code.lines().enumerate().skip(1) {
+        let line = source.trim();
         if line.is_empty() || line.starts_with("%%") {
             continue;
         }
@@ -31,12 +33,17 @@ pub fn generate_shapes(code: &str) -> Vec<Shape> {
             }
         } else if !current_date.is_empty() {
             current_items.push(line.to_string());
+        } else {
+            warnings.push(MermaidDiagnostic::unsupported(index, source));
         }
     }
     
     if !current_date.is_empty() {
         events.push((current_date, current_items));
     }
+    if events.is_empty() {
+        return Err(MermaidDiagnostic::empty("timeline"));
+    }
     
     // Layout parameters
     let start_x = 500_000u32;
@@ -87,7 +94,7 @@ pub fn generate_shapes(code: &str) -> Vec<Shape> {
         shapes.push(items_shape);
     }
     
-    shapes
+    Ok(DiagramElements::from_shapes(shapes).with_warnings(warnings))
 }
 
 #[cfg(test)]
@@ -97,7 +104,16 @@ mod tests {
     #[test]
     fn test_generate_timeline_shapes() {
         let code = "timeline\n    title History\n    2020 : Event A\n    2021 : Event B";
-        let shapes = generate_shapes(code);
-        assert!(!shapes.is_empty());
+        let elements = generate_elements(code).unwrap();
+        assert!(!elements.shapes.is_empty());
+        assert!(elements.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_timeline_diagnostics() {
+        let elements = generate_elements("timeline\n    Kickoff\n    2020 : Event A").unwrap();
+        assert_eq!(elements.warnings, [MermaidDiagnostic::new(2, 5, "unsupported statement `Kickoff`")]);
+
+        assert!(generate_elements("timeline\n    title Nothing yet").is_err());
     }
 }
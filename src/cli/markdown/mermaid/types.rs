@@ -3,6 +3,54 @@
 use crate::generator::{Shape, ShapeType, ShapeFill, ShapeLine};
 use crate::generator::shapes::{GradientFill, GradientDirection};
 use crate::generator::connectors::Connector;
+use crate::generator::charts::Chart;
+use super::style::Styles;
+
+/// A problem found while parsing a Mermaid block
+///
+/// Lines and columns are 1-based and count from the diagram keyword line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MermaidDiagnostic {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl MermaidDiagnostic {
+    pub fn new(line: usize, column: usize, message: impl Into<String>) -> Self {
+        Self { line, column, message: message.into() }
+    }
+
+    /// Point at `token` within the source line with 0-based index `index`,
+    /// or at its first non-blank character when the token is not found
+    pub fn at(index: usize, source: &str, token: &str, message: impl Into<String>) -> Self {
+        let byte = source.find(token)
+            .filter(|_| !token.is_empty())
+            .unwrap_or(source.len() - source.trim_start().len());
+        Self::new(index + 1, source[..byte].chars().count() + 1, message)
+    }
+
+    /// A statement the parser does not understand and drops
+    pub fn unsupported(index: usize, source: &str) -> Self {
+        Self::at(index, source, "", format!("unsupported statement `{}`", source.trim()))
+    }
+
+    /// A diagram without anything to draw
+    pub fn empty(kind: &str) -> Self {
+        Self::new(1, 1, format!("{kind} has nothing to draw"))
+    }
+}
+
+impl std::fmt::Display for MermaidDiagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}, column {}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for MermaidDiagnostic {}
+
+/// Result of parsing a Mermaid block; the error is the problem that stopped it
+pub type MermaidResult<T> = Result<T, MermaidDiagnostic>;
 
 /// Mermaid diagram types
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -19,6 +67,11 @@ pub enum MermaidType {
     Journey,
     Quadrant,
     GitGraph,
+    C4,
+    Sankey,
+    XyChart,
+    Block,
+    Requirement,
     Unknown,
 }
 
@@ -31,6 +84,19 @@ pub enum FlowDirection {
     BottomToTop,  // BT
 }
 
+impl FlowDirection {
+    /// Parse a direction keyword (`TB`, `TD`, `BT`, `LR`, `RL`)
+    pub fn from_keyword(keyword: &str) -> Option<Self> {
+        match keyword.trim().to_uppercase().as_str() {
+            "TB" | "TD" => Some(FlowDirection::TopToBottom),
+            "BT" => Some(FlowDirection::BottomToTop),
+            "LR" => Some(FlowDirection::LeftToRight),
+            "RL" => Some(FlowDirection::RightToLeft),
+            _ => None,
+        }
+    }
+}
+
 /// A parsed flowchart node
 #[derive(Debug, Clone)]
 pub struct FlowNode {
@@ -73,6 +139,8 @@ pub enum ArrowStyle {
 pub struct Subgraph {
     pub name: String,
     pub nodes: Vec<String>,
+    /// Index of the enclosing subgraph, for nested subgraphs
+    pub parent: Option<usize>,
 }
 
 /// Parsed flowchart
@@ -82,6 +150,10 @@ pub struct Flowchart {
     pub nodes: Vec<FlowNode>,
     pub connections: Vec<FlowConnection>,
     pub subgraphs: Vec<Subgraph>,
+    /// `classDef`, `class`, `style` and `linkStyle` directives
+    pub styles: Styles,
+    /// Lines that were dropped or only partly understood
+    pub warnings: Vec<MermaidDiagnostic>,
 }
 
 /// Bounding box for diagram positioning
@@ -125,11 +197,15 @@ impl DiagramBounds {
 pub struct DiagramElements {
     pub shapes: Vec<Shape>,
     pub connectors: Vec<Connector>,
+    /// Native charts (pie, gantt)
+    pub charts: Vec<Chart>,
     /// Bounding box of the diagram for positioning
     pub bounds: Option<DiagramBounds>,
     /// Whether elements should be grouped (for future `<p:grpSp>` support)
     #[allow(dead_code)]
     pub grouped: bool,
+    /// Lines that were dropped or only partly understood
+    pub warnings: Vec<MermaidDiagnostic>,
 }
 
 impl DiagramElements {
@@ -144,8 +220,10 @@ impl DiagramElements {
         Self {
             shapes,
             connectors: Vec::new(),
+            charts: Vec::new(),
             bounds,
             grouped: true,
+            warnings: Vec::new(),
         }
     }
     
@@ -160,10 +238,31 @@ impl DiagramElements {
         Self {
             shapes,
             connectors,
+            charts: Vec::new(),
             bounds,
             grouped: true,
+            warnings: Vec::new(),
         }
     }
+
+    /// Create from a single native chart
+    pub fn from_chart(chart: Chart) -> Self {
+        let bounds = DiagramBounds { x: chart.x, y: chart.y, width: chart.width, height: chart.height };
+        Self {
+            shapes: Vec::new(),
+            connectors: Vec::new(),
+            charts: vec![chart],
+            bounds: Some(bounds),
+            grouped: false,
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Attach the parser warnings
+    pub fn with_warnings(mut self, warnings: Vec<MermaidDiagnostic>) -> Self {
+        self.warnings = warnings;
+        self
+    }
 }
 
 /// Position for label relative to shape
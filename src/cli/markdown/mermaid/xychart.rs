@@ -0,0 +1,278 @@
+//! XY chart (`xychart-beta`) parsing and rendering
+//!
+//! Rendered as a native chart: bars only become a column (or horizontal bar)
+//! chart, lines only a line chart, and one bar series with lines a combo chart.
+
+use crate::generator::charts::{Chart, ChartBuilder, ChartSeries, ChartType, ValueAxis};
+use super::layout::DIAGRAM_AREA;
+use super::types::{DiagramElements, MermaidDiagnostic, MermaidResult};
+
+/// Kind of an XY chart series
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum XySeriesKind {
+    Bar,
+    Line,
+}
+
+/// A parsed XY chart series
+#[derive(Debug, Clone, PartialEq)]
+pub struct XySeries {
+    pub kind: XySeriesKind,
+    pub name: String,
+    pub values: Vec<f64>,
+}
+
+/// X axis: named categories or a numeric range spread over the data points
+#[derive(Debug, Clone, PartialEq)]
+pub enum XAxis {
+    Categories(Vec<String>),
+    Range(f64, f64),
+}
+
+/// Parsed XY chart
+#[derive(Debug, Clone, PartialEq)]
+pub struct XyChart {
+    pub title: String,
+    pub horizontal: bool,
+    pub x_axis: Option<XAxis>,
+    /// Value axis range (`y-axis min --> max`)
+    pub y_range: Option<(f64, f64)>,
+    pub series: Vec<XySeries>,
+    /// Lines that were dropped or only partly understood
+    pub warnings: Vec<MermaidDiagnostic>,
+}
+
+/// Parse an XY chart
+pub fn parse(code: &str) -> MermaidResult<XyChart> {
+    let mut lines = code.lines().enumerate();
+    let header = lines.next().map_or("", |(_, line)| line);
+    let mut chart = XyChart {
+        title: String::new(),
+        horizontal: header.split_whitespace().nth(1) == Some("horizontal"),
+        x_axis: None,
+        y_range: None,
+        series: Vec::new(),
+        warnings: Vec::new(),
+    };
+
+    for (index, source) in lines {
+        let line = source.trim();
+        if line.is_empty() || line.starts_with("%%") || line.starts_with("acc") {
+            continue;
+        }
+
+        if let Some(title) = line.strip_prefix("title") {
+            chart.title = unquote(title).to_string();
+        } else if let Some(axis) = line.strip_prefix("x-axis") {
+            let (_, spec) = split_axis_title(axis);
+            if spec.starts_with('[') {
+                chart.x_axis = Some(XAxis::Categories(parse_list(spec).into_iter().map(|s| unquote(s).to_string()).collect()));
+            } else if let Some(range) = parse_range(spec) {
+                chart.x_axis = Some(XAxis::Range(range.0, range.1));
+            } else if !spec.is_empty() {
+                chart.warnings.push(MermaidDiagnostic::at(index, source, spec, format!("x-axis needs `[a, b, ...]` or `min --> max`, found `{spec}`")));
+            }
+        } else if let Some(axis) = line.strip_prefix("y-axis") {
+            let (_, spec) = split_axis_title(axis);
+            if let Some(range) = parse_range(spec) {
+                chart.y_range = Some(range);
+            } else if !spec.is_empty() {
+                chart.warnings.push(MermaidDiagnostic::at(index, source, spec, format!("y-axis needs `min --> max`, found `{spec}`")));
+            }
+        } else if let Some((kind, rest)) = line.strip_prefix("bar").map(|rest| (XySeriesKind::Bar, rest))
+            .or_else(|| line.strip_prefix("line").map(|rest| (XySeriesKind::Line, rest)))
+        {
+            let (name, data) = split_axis_title(rest);
+            if !data.starts_with('[') {
+                chart.warnings.push(MermaidDiagnostic::at(index, source, "", "series data must be a list like `[1, 2, 3]`"));
+                continue;
+            }
+            let mut values = Vec::new();
+            for item in parse_list(data) {
+                match item.parse::<f64>() {
+                    Ok(value) => values.push(value),
+                    Err(_) => {
+                        chart.warnings.push(MermaidDiagnostic::at(index, source, item, format!("`{item}` is not a number, using 0")));
+                        values.push(0.0);
+                    }
+                }
+            }
+            let count = chart.series.iter().filter(|s| s.kind == kind).count() + 1;
+            let name = match (name.is_empty(), kind) {
+                (false, _) => name.to_string(),
+                (true, XySeriesKind::Bar) => format!("Bar {count}"),
+                (true, XySeriesKind::Line) => format!("Line {count}"),
+            };
+            chart.series.push(XySeries { kind, name, values });
+        } else {
+            chart.warnings.push(MermaidDiagnostic::unsupported(index, source));
+        }
+    }
+
+    if chart.series.is_empty() {
+        return Err(MermaidDiagnostic::empty("xychart"));
+    }
+    if let Some(XAxis::Categories(categories)) = &chart.x_axis
+        && let Some(series) = chart.series.iter().find(|s| s.values.len() != categories.len())
+    {
+        chart.warnings.push(MermaidDiagnostic::new(1, 1, format!(
+            "series `{}` has {} values for {} x-axis categories",
+            series.name, series.values.len(), categories.len()
+        )));
+    }
+    Ok(chart)
+}
+
+/// Split an optional quoted or single-word title from an axis or series spec
+fn split_axis_title(spec: &str) -> (&str, &str) {
+    let spec = spec.trim();
+    if let Some(quoted) = spec.strip_prefix('"')
+        && let Some((title, rest)) = quoted.split_once('"')
+    {
+        return (title, rest.trim());
+    }
+    match spec.split_once(char::is_whitespace) {
+        Some((title, rest)) if !spec.starts_with('[') && parse_range(spec).is_none() => (title, rest.trim()),
+        _ if spec.starts_with('[') || parse_range(spec).is_some() => ("", spec),
+        _ => (spec, ""),
+    }
+}
+
+/// Parse `min --> max`
+fn parse_range(spec: &str) -> Option<(f64, f64)> {
+    let (min, max) = spec.split_once("-->")?;
+    Some((min.trim().parse().ok()?, max.trim().parse().ok()?))
+}
+
+/// Items of a `[a, "b c", 3]` list
+fn parse_list(spec: &str) -> Vec<&str> {
+    let inner = spec.trim().trim_start_matches('[');
+    let inner = inner.split_once(']').map_or(inner, |(inner, _)| inner);
+    let mut items = Vec::new();
+    let mut start = 0;
+    let mut quoted = false;
+    for (i, c) in inner.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            ',' if !quoted => {
+                items.push(inner[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(inner[start..].trim());
+    items.retain(|item| !item.is_empty());
+    items
+}
+
+fn unquote(s: &str) -> &str {
+    s.trim().trim_matches('"')
+}
+
+/// Category labels: the x-axis categories, or the range spread over the points
+fn categories(chart: &XyChart) -> Vec<String> {
+    let points = chart.series.iter().map(|s| s.values.len()).max().unwrap_or(0);
+    match &chart.x_axis {
+        Some(XAxis::Categories(categories)) => categories.clone(),
+        Some(XAxis::Range(min, max)) => (0..points)
+            .map(|i| {
+                let value = if points > 1 { min + (max - min) * i as f64 / (points - 1) as f64 } else { *min };
+                format!("{}", (value * 100.0).round() / 100.0)
+            })
+            .collect(),
+        None => (1..=points).map(|i| i.to_string()).collect(),
+    }
+}
+
+/// Build the native chart, filling the diagram area
+pub fn generate_chart(chart: &XyChart) -> Chart {
+    let bars = chart.series.iter().filter(|s| s.kind == XySeriesKind::Bar).count();
+    let lines = chart.series.len() - bars;
+    let chart_type = match (bars, lines, chart.horizontal) {
+        (_, 0, true) => ChartType::BarHorizontal,
+        (_, 0, false) => ChartType::Bar,
+        (0, _, _) => ChartType::LineMarkers,
+        _ => ChartType::Combo,
+    };
+
+    // Combo charts draw their first series as columns
+    let mut series: Vec<&XySeries> = chart.series.iter().collect();
+    series.sort_by_key(|s| s.kind == XySeriesKind::Line);
+
+    let categories = categories(chart);
+    let mut builder = ChartBuilder::new(&chart.title, chart_type)
+        .position(DIAGRAM_AREA.x, DIAGRAM_AREA.y)
+        .size(DIAGRAM_AREA.width, DIAGRAM_AREA.height)
+        .categories(categories.iter().map(String::as_str).collect());
+    for s in series {
+        let mut values = s.values.clone();
+        values.resize(categories.len(), 0.0);
+        builder = builder.add_series(ChartSeries::new(&s.name, values));
+    }
+
+    builder
+        .build()
+        .with_value_axis(ValueAxis {
+            min: chart.y_range.map(|(min, _)| min),
+            max: chart.y_range.map(|(_, max)| max),
+            ..ValueAxis::default()
+        })
+        .legend(chart.series.len() > 1)
+}
+
+/// Generate the elements for an XY chart block
+pub fn generate_elements(code: &str) -> MermaidResult<DiagramElements> {
+    let mut chart = parse(code)?;
+    let bars = chart.series.iter().filter(|s| s.kind == XySeriesKind::Bar).count();
+    if bars > 1 && bars < chart.series.len() {
+        chart.warnings.push(MermaidDiagnostic::new(1, 1, "with line series only the first bar series is drawn as bars"));
+    }
+    if chart.horizontal && bars < chart.series.len() {
+        chart.warnings.push(MermaidDiagnostic::new(1, 1, "charts with line series are drawn vertically"));
+    }
+    Ok(DiagramElements::from_chart(generate_chart(&chart)).with_warnings(chart.warnings))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_xychart() {
+        let code = "xychart-beta\n    title \"Sales Revenue\"\n    x-axis [jan, feb, \"mar, q1\"]\n    y-axis \"Revenue (in $)\" 4000 --> 11000\n    bar [5000, 6000, 7500]\n    line \"Trend\" [5000, 6500, 7000]";
+        let chart = parse(code).unwrap();
+        assert_eq!(chart.title, "Sales Revenue");
+        assert_eq!(chart.x_axis, Some(XAxis::Categories(vec!["jan".into(), "feb".into(), "mar, q1".into()])));
+        assert_eq!(chart.y_range, Some((4000.0, 11000.0)));
+        assert_eq!(chart.series[0].name, "Bar 1");
+        assert_eq!(chart.series[1].name, "Trend");
+        assert!(chart.warnings.is_empty());
+
+        let native = generate_chart(&chart);
+        assert_eq!(native.chart_type, ChartType::Combo);
+        assert_eq!(native.value_axis.min, Some(4000.0));
+        assert!(native.show_legend);
+    }
+
+    #[test]
+    fn test_xychart_horizontal_range() {
+        let code = "xychart-beta horizontal\n    x-axis \"Score\" 0 --> 10\n    bar [1, 2, 3]";
+        let elements = generate_elements(code).unwrap();
+        let chart = &elements.charts[0];
+        assert_eq!(chart.chart_type, ChartType::BarHorizontal);
+        assert_eq!(chart.categories, vec!["0", "5", "10"]);
+        assert!(!chart.show_legend);
+    }
+
+    #[test]
+    fn test_xychart_diagnostics() {
+        let code = "xychart-beta\n    x-axis [a, b]\n    line [1, x, 3]\n    scatter [1, 2]";
+        let chart = parse(code).unwrap();
+        let warnings: Vec<(usize, usize)> = chart.warnings.iter().map(|w| (w.line, w.column)).collect();
+        assert_eq!(warnings, [(3, 14), (4, 5), (1, 1)]);
+        assert_eq!(generate_chart(&chart).series[0].values, vec![1.0, 0.0]);
+
+        assert!(parse("xychart-beta\n    title Empty").is_err());
+    }
+}
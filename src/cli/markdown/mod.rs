@@ -1,7 +1,8 @@
 //! Markdown to PowerPoint conversion
 //!
 //! This module provides functionality to parse markdown content
-//! and convert it into PowerPoint slide structures.
+//! and convert it into PowerPoint slide structures, and to export a
+//! presentation back to Markdown with [`export_markdown`].
 //!
 //! # Supported Features
 //!
@@ -10,17 +11,29 @@
 //! - **Numbered lists**: `1.`, `2.` create numbered lists
 //! - **Tables**: GFM-style tables with header styling
 //! - **Code blocks**: Fenced code blocks with syntax highlighting
-//! - **Mermaid diagrams**: Visual placeholders for 12 diagram types
+//! - **Mermaid diagrams**: 17 diagram types, with line/column diagnostics
 //! - **Inline formatting**: Bold, italic, inline code
-//! - **Images**: Placeholder shapes for images
+//! - **Images**: Placeholder shapes for images; `![bg](img.png)` sets the
+//!   background and `![bg right](img.png)` fills half of the slide
 //! - **Horizontal rules**: Create slide breaks
 //! - **Speaker notes**: Blockquotes become speaker notes
+//! - **Front matter**: YAML or TOML deck settings (title, author, theme, ...)
+//! - **Slide directives**: `<!-- layout: two-column -->`, `background`, `transition`
+//! - **Columns**: `:::columns` / `:::column` containers or `<!-- split -->`;
+//!   columns opening with headings make a comparison slide
+//! - **Math**: `$...$` and `$$...$$` (or ```` ```math ````) LaTeX becomes
+//!   editable Office Math equations
 
+mod directives;
+mod export;
+mod math;
 mod mermaid;
 mod parser;
 
-pub use mermaid::MermaidType;
-pub use parser::parse;
+pub use directives::{DirectiveWarning, FrontMatter};
+pub use export::{export_markdown, MarkdownExport};
+pub use mermaid::{MermaidDiagnostic, MermaidType};
+pub use parser::{parse, parse_document, parse_document_in, parse_with_diagnostics, MarkdownDocument};
 
 /// Parse markdown content into slides (convenience re-export)
 pub fn parse_markdown(content: &str) -> Result<Vec<crate::generator::SlideContent>, String> {
@@ -2,14 +2,99 @@
 //!
 //! Handles parsing of markdown content into slide structures.
 
-use pulldown_cmark::{Event, HeadingLevel, Options, Parser, Tag, TagEnd};
-use crate::generator::{SlideContent, TableBuilder, TableRow, TableCell, Shape, ShapeType, ShapeFill, CodeBlock};
-use super::mermaid;
+use std::path::Path;
+use pulldown_cmark::{Alignment, Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+use crate::generator::{SlideContent, SlideLayout, TableBuilder, TablePagination, TableRow, TableCell, CellAlign, Shape, ShapeType, ShapeFill, CodeBlock, Equation, Hyperlink, BulletPoint, Run, Image, ImageSide, Background};
+use crate::generator::constants::{SLIDE_WIDTH, SLIDE_HEIGHT};
+use crate::exc::PptxError;
+use super::mermaid::{self, MermaidDiagnostic};
+use super::directives::{self, ColumnMarker, DirectiveWarning, FrontMatter, SlideDirective};
+use super::math;
+
+/// A parsed Markdown file
+#[derive(Debug, Clone)]
+pub struct MarkdownDocument {
+    pub slides: Vec<SlideContent>,
+    /// Deck settings from the front matter
+    pub front_matter: FrontMatter,
+    /// Problems found in Mermaid blocks, with their line and column
+    pub diagnostics: Vec<MermaidDiagnostic>,
+    /// Problems in the front matter and slide directives, with their line
+    pub warnings: Vec<DirectiveWarning>,
+}
 
 /// Parse markdown content into slides
 pub fn parse(content: &str) -> Result<Vec<SlideContent>, String> {
+    parse_with_diagnostics(content).map(|(slides, _)| slides)
+}
+
+/// Parse markdown content into slides, also returning the problems found in
+/// Mermaid blocks with their line and column in `content`
+pub fn parse_with_diagnostics(content: &str) -> Result<(Vec<SlideContent>, Vec<MermaidDiagnostic>), String> {
+    parse_document(content).map(|document| (document.slides, document.diagnostics))
+}
+
+/// Parse a Markdown file with its front matter and slide directives
+///
+/// A front matter `theme` also colours the slide titles and text.
+pub fn parse_document(content: &str) -> Result<MarkdownDocument, String> {
+    parse_document_in(content, Path::new(""))
+}
+
+/// Parse a Markdown file like [`parse_document`], reading the pictures of
+/// relative `![bg](path)` images from `base_dir`
+pub fn parse_document_in(content: &str, base_dir: &Path) -> Result<MarkdownDocument, String> {
+    let (mut front_matter, warnings, body, line_offset) = directives::split_front_matter(content);
     let mut parser = MarkdownParser::new();
-    parser.parse(content)
+    parser.line_offset = line_offset;
+    parser.warnings = warnings;
+    parser.base_dir = base_dir.to_path_buf();
+    if let Some(ref theme) = front_matter.code_theme {
+        parser.code_theme = Some(code_theme_in(theme, base_dir, content, &mut parser.warnings));
+    }
+    if let Some(template) = front_matter.template.take() {
+        front_matter.template = template_in(&template, base_dir, content, &mut parser.warnings);
+    }
+    let body = math::math_to_code(body);
+    let mut slides = parser.parse(&directives::container_fences_to_comments(&body))?;
+
+    if let Some(theme) = front_matter.theme() {
+        for slide in &mut slides {
+            slide.title_color.get_or_insert_with(|| theme.primary.to_string());
+            slide.content_color.get_or_insert_with(|| theme.text.to_string());
+        }
+    }
+    Ok(MarkdownDocument { slides, front_matter, diagnostics: parser.diagnostics, warnings: parser.warnings })
+}
+
+/// Front matter code theme, with `.tmTheme` paths read from `base_dir`;
+/// a file that doesn't load is warned about, and code keeps the default theme
+fn code_theme_in(theme: &str, base_dir: &Path, content: &str, warnings: &mut Vec<DirectiveWarning>) -> String {
+    if !theme.to_lowercase().ends_with(".tmtheme") {
+        return theme.to_string();
+    }
+    let path = base_dir.join(theme).to_string_lossy().into_owned();
+    if let Err(e) = crate::cli::syntax::load_theme(&path) {
+        let line = content.lines().position(|line| line.trim_start().starts_with("code_theme")).unwrap_or(0);
+        warnings.push((line + 1, e.to_string()));
+    }
+    path
+}
+
+/// Front matter template path read from `base_dir`; a file that isn't a
+/// presentation is warned about and dropped
+fn template_in(template: &str, base_dir: &Path, content: &str, warnings: &mut Vec<DirectiveWarning>) -> Option<String> {
+    let path = base_dir.join(template);
+    let problem = match std::fs::read(&path) {
+        Ok(data) => crate::generator::template::template_slide_size(&data).err().map(|e| e.to_string()),
+        Err(e) => Some(e.to_string()),
+    };
+    let Some(problem) = problem else {
+        return Some(path.to_string_lossy().into_owned());
+    };
+    let line = content.lines().position(|line| line.trim_start().starts_with("template")).unwrap_or(0);
+    warnings.push((line + 1, format!("template `{template}` can't be used: {problem}")));
+    None
 }
 
 /// State machine for markdown parsing
@@ -19,15 +104,20 @@ struct MarkdownParser {
     current_text: String,
     // List state
     in_list: bool,
-    list_items: Vec<String>,
+    // Nesting depth of the open lists
+    list_depth: u32,
+    list_items: Vec<BulletPoint>,
     // Table state
     in_table: bool,
-    table_rows: Vec<Vec<String>>,
-    current_row: Vec<String>,
+    table_rows: Vec<Vec<(String, Vec<Run>)>>,
+    table_alignments: Vec<Alignment>,
+    current_row: Vec<(String, Vec<Run>)>,
     current_cell: String,
     in_table_head: bool,
     // Code block state
     in_code_block: bool,
+    // Front matter `code_theme`, a theme name or `.tmTheme` path
+    code_theme: Option<String>,
     code_content: String,
     code_language: Option<String>,
     // Formatting state
@@ -36,8 +126,39 @@ struct MarkdownParser {
     // Blockquote (speaker notes)
     in_blockquote: bool,
     blockquote_text: String,
-    // Image state
+    // Image state: URL and title, alt text, directory of relative pictures
     pending_image: Option<(String, String)>,
+    image_alt: String,
+    base_dir: std::path::PathBuf,
+    // Rich text runs of the current paragraph/list item and table cell
+    current_runs: Vec<Run>,
+    cell_runs: Vec<Run>,
+    // URL of the link being parsed
+    link_url: Option<String>,
+    // Line of the opening fence of the current code block
+    code_line: usize,
+    // Problems found in Mermaid blocks, in file coordinates
+    diagnostics: Vec<MermaidDiagnostic>,
+    // Lines of front matter before the parsed body
+    line_offset: usize,
+    // HTML block being read and the line of its start (or of inline HTML)
+    html: String,
+    html_line: usize,
+    // Slide directives waiting for the next slide
+    pending_directives: Vec<SlideDirective>,
+    // Problems in front matter and slide directives
+    warnings: Vec<DirectiveWarning>,
+    // Column containers: inside `:::columns`, columns opened, one still open
+    in_columns: bool,
+    column_count: usize,
+    column_open: bool,
+    // Bullets of the current slide made from H2+ headings, by index
+    heading_bullets: Vec<(usize, String)>,
+    in_heading: bool,
+    // Math of the current paragraph/list item: offset in its run text, TeX,
+    // display or inline; and the line of the last math span
+    math_spans: Vec<(usize, String, bool)>,
+    math_line: usize,
 }
 
 impl MarkdownParser {
@@ -47,13 +168,16 @@ impl MarkdownParser {
             current_slide: None,
             current_text: String::new(),
             in_list: false,
+            list_depth: 0,
             list_items: Vec::new(),
             in_table: false,
             table_rows: Vec::new(),
+            table_alignments: Vec::new(),
             current_row: Vec::new(),
             current_cell: String::new(),
             in_table_head: false,
             in_code_block: false,
+            code_theme: None,
             code_content: String::new(),
             code_language: None,
             is_bold: false,
@@ -61,6 +185,25 @@ impl MarkdownParser {
             in_blockquote: false,
             blockquote_text: String::new(),
             pending_image: None,
+            image_alt: String::new(),
+            base_dir: std::path::PathBuf::new(),
+            current_runs: Vec::new(),
+            cell_runs: Vec::new(),
+            link_url: None,
+            code_line: 0,
+            diagnostics: Vec::new(),
+            line_offset: 0,
+            html: String::new(),
+            html_line: 0,
+            pending_directives: Vec::new(),
+            warnings: Vec::new(),
+            in_columns: false,
+            column_count: 0,
+            column_open: false,
+            heading_bullets: Vec::new(),
+            in_heading: false,
+            math_spans: Vec::new(),
+            math_line: 0,
         }
     }
 
@@ -71,7 +214,14 @@ impl MarkdownParser {
         
         let parser = Parser::new_ext(content, options);
         
-        for event in parser {
+        for (event, range) in parser.into_offset_iter() {
+            let line = || self.line_offset + content[..range.start].matches('\n').count() + 1;
+            match event {
+                Event::Start(Tag::CodeBlock(_)) => self.code_line = line(),
+                Event::Start(Tag::HtmlBlock) | Event::InlineHtml(_) => self.html_line = line(),
+                Event::Code(_) => self.math_line = line(),
+                _ => {}
+            }
             self.handle_event(event);
         }
         
@@ -92,40 +242,50 @@ impl MarkdownParser {
                     self.finalize_current_slide();
                 }
                 self.current_text.clear();
+                self.in_heading = true;
             }
             Event::End(TagEnd::Heading(level)) => {
+                self.in_heading = false;
                 let title = std::mem::take(&mut self.current_text).trim().to_string();
+                self.current_runs.clear();
                 if level == HeadingLevel::H1 {
                     self.current_slide = Some(SlideContent::new(&title));
                 } else if let Some(ref mut slide) = self.current_slide {
                     let formatted = format!("**{}**", title);
+                    self.heading_bullets.push((slide.bullets.len(), title));
                     *slide = slide.clone().add_bullet(&formatted);
                 }
             }
             
             // Lists
             Event::Start(Tag::List(_)) => {
+                if self.list_depth == 0 {
+                    self.list_items.clear();
+                } else {
+                    // The parent item's text comes before its sub-list
+                    self.end_list_item();
+                }
                 self.in_list = true;
-                self.list_items.clear();
+                self.list_depth += 1;
             }
             Event::End(TagEnd::List(_)) => {
-                self.in_list = false;
-                self.flush_list_items();
+                self.list_depth -= 1;
+                if self.list_depth == 0 {
+                    self.in_list = false;
+                    self.flush_list_items();
+                }
             }
             Event::Start(Tag::Item) => {
                 self.current_text.clear();
+                self.current_runs.clear();
             }
-            Event::End(TagEnd::Item) => {
-                let item = std::mem::take(&mut self.current_text).trim().to_string();
-                if !item.is_empty() {
-                    self.list_items.push(item);
-                }
-            }
+            Event::End(TagEnd::Item) => self.end_list_item(),
             
             // Tables
-            Event::Start(Tag::Table(_)) => {
+            Event::Start(Tag::Table(alignments)) => {
                 self.in_table = true;
                 self.table_rows.clear();
+                self.table_alignments = alignments;
                 self.in_table_head = false;
             }
             Event::End(TagEnd::Table) => {
@@ -152,9 +312,12 @@ impl MarkdownParser {
             }
             Event::Start(Tag::TableCell) => {
                 self.current_cell.clear();
+                self.cell_runs.clear();
             }
             Event::End(TagEnd::TableCell) => {
-                self.current_row.push(std::mem::take(&mut self.current_cell).trim().to_string());
+                let text = std::mem::take(&mut self.current_cell).trim().to_string();
+                let runs = trim_runs(std::mem::take(&mut self.cell_runs));
+                self.current_row.push((text, runs));
             }
             
             // Code blocks
@@ -189,21 +352,45 @@ impl MarkdownParser {
             Event::End(TagEnd::Strong) => self.is_bold = false,
             Event::Start(Tag::Emphasis) => self.is_italic = true,
             Event::End(TagEnd::Emphasis) => self.is_italic = false,
+            Event::Code(code) if math::code_math(&code).is_some() => {
+                if let Some((tex, display)) = math::code_math(&code) {
+                    self.push_math(tex, display);
+                }
+            }
             Event::Code(code) => {
                 let formatted = format!("`{}`", code);
-                self.push_text(&formatted);
+                self.push_marked_text(&formatted);
+                let run = self.styled_run(&code).code().color("C7254E");
+                self.push_run(run);
             }
             
             // Images
             Event::Start(Tag::Image { dest_url, title, .. }) => {
                 self.pending_image = Some((dest_url.to_string(), title.to_string()));
+                self.image_alt.clear();
             }
             Event::End(TagEnd::Image) => {
-                if let Some((url, alt)) = self.pending_image.take() {
-                    self.add_image_placeholder(&url, &alt);
+                if let Some((url, title)) = self.pending_image.take() {
+                    let alt = std::mem::take(&mut self.image_alt);
+                    self.add_image(&url, &title, alt.trim());
                 }
             }
             
+            // Links become clickable runs
+            Event::Start(Tag::Link { dest_url, .. }) => {
+                self.link_url = (!dest_url.is_empty()).then(|| dest_url.to_string());
+            }
+            Event::End(TagEnd::Link) => self.link_url = None,
+
+            // `<!-- key: value -->` comments set up the slide
+            Event::Start(Tag::HtmlBlock) => self.html.clear(),
+            Event::Html(html) => self.html.push_str(&html),
+            Event::End(TagEnd::HtmlBlock) => {
+                let html = std::mem::take(&mut self.html);
+                self.add_directives(&html);
+            }
+            Event::InlineHtml(html) => self.add_directives(&html),
+            
             // Horizontal rule = slide break
             Event::Rule => {
                 self.finalize_current_slide();
@@ -225,13 +412,19 @@ impl MarkdownParser {
             Event::Start(Tag::Paragraph) => {
                 if !self.in_list && !self.in_table && !self.in_blockquote && !self.in_code_block {
                     self.current_text.clear();
+                    self.current_runs.clear();
                 }
             }
+            Event::End(TagEnd::Paragraph) if !self.in_list && !self.math_spans.is_empty() => {
+                let bullet = self.take_math_paragraph();
+                self.add_bullet_point(bullet);
+            }
             Event::End(TagEnd::Paragraph) => {
                 if !self.in_list && !self.in_table && !self.in_blockquote && !self.in_code_block {
                     let text = std::mem::take(&mut self.current_text).trim().to_string();
+                    let runs = trim_runs(std::mem::take(&mut self.current_runs));
                     if !text.is_empty() {
-                        self.add_paragraph(&text);
+                        self.add_paragraph(&text, runs);
                     }
                 }
             }
@@ -241,6 +434,45 @@ impl MarkdownParser {
     }
 
     fn push_text(&mut self, text: &str) {
+        if self.pending_image.is_some() {
+            self.image_alt.push_str(text);
+            return;
+        }
+        self.push_marked_text(text);
+        if !self.in_code_block && !self.in_blockquote {
+            let run = self.styled_run(text);
+            self.push_run(run);
+        }
+    }
+
+    /// Run with the current bold/italic/link state
+    fn styled_run(&self, text: &str) -> Run {
+        let mut run = Run::new(text);
+        if self.is_bold {
+            run = run.bold();
+        }
+        if self.is_italic {
+            run = run.italic();
+        }
+        if let Some(url) = &self.link_url {
+            run = run.with_hyperlink(Hyperlink::url(url));
+        }
+        run
+    }
+
+    fn push_run(&mut self, run: Run) {
+        if self.in_code_block || self.in_blockquote {
+            return;
+        }
+        if self.in_table {
+            self.cell_runs.push(run);
+        } else {
+            self.current_runs.push(run);
+        }
+    }
+
+    /// Append text to the plain buffers, keeping Markdown emphasis markers
+    fn push_marked_text(&mut self, text: &str) {
         let formatted = if self.is_bold && self.is_italic {
             format!("***{}***", text)
         } else if self.is_bold {
@@ -262,14 +494,13 @@ impl MarkdownParser {
         }
     }
 
-    fn add_paragraph(&mut self, text: &str) {
-        if let Some(ref mut slide) = self.current_slide {
-            *slide = slide.clone().add_bullet(text);
-        } else {
-            let mut slide = SlideContent::new("Slide");
-            slide = slide.add_bullet(text);
-            self.current_slide = Some(slide);
-        }
+    fn add_paragraph(&mut self, text: &str, runs: Vec<Run>) {
+        self.add_bullet_point(text_bullet(text, runs));
+    }
+
+    fn add_bullet_point(&mut self, bullet: BulletPoint) {
+        let slide = self.current_slide.take().unwrap_or_else(|| SlideContent::new("Slide"));
+        self.current_slide = Some(add_bullet(slide, bullet));
     }
 
     fn flush_list_items(&mut self) {
@@ -279,16 +510,8 @@ impl MarkdownParser {
         
         let items = std::mem::take(&mut self.list_items);
         
-        if let Some(ref mut slide) = self.current_slide {
-            for item in items {
-                *slide = slide.clone().add_bullet(&item);
-            }
-        } else {
-            let mut slide = SlideContent::new("Slide");
-            for item in items {
-                slide = slide.add_bullet(&item);
-            }
-            self.current_slide = Some(slide);
+        for bullet in items {
+            self.add_bullet_point(bullet);
         }
     }
 
@@ -305,13 +528,24 @@ impl MarkdownParser {
         let mut builder = TableBuilder::new(col_widths);
         
         for (i, row_data) in rows.iter().enumerate() {
-            let cells: Vec<TableCell> = row_data.iter().map(|cell_text| {
-                let mut cell = TableCell::new(cell_text);
+            let cells: Vec<TableCell> = row_data.iter().map(|(cell_text, runs)| {
+                let mut cell = if is_rich(runs) {
+                    TableCell::rich(runs.clone())
+                } else {
+                    TableCell::new(cell_text)
+                };
                 if i == 0 {
                     cell = cell.bold().background_color("4472C4").text_color("FFFFFF");
                 }
                 cell
-            }).collect();
+            }).zip(self.table_alignments.iter().chain(std::iter::repeat(&Alignment::None)))
+            .map(|(cell, alignment)| match alignment {
+                Alignment::Left => cell.align(CellAlign::Left),
+                Alignment::Center => cell.align(CellAlign::Center),
+                Alignment::Right => cell.align(CellAlign::Right),
+                Alignment::None => cell,
+            })
+            .collect();
             
             let mut cells = cells;
             while cells.len() < col_count {
@@ -321,7 +555,7 @@ impl MarkdownParser {
             builder = builder.add_row(TableRow::new(cells));
         }
         
-        let table = builder.position(500000, 1800000).build();
+        let table = builder.position(500000, 1800000).pagination(TablePagination::new()).build();
         
         if let Some(ref mut slide) = self.current_slide {
             slide.table = Some(table);
@@ -340,15 +574,24 @@ impl MarkdownParser {
         }
         
         let code = std::mem::take(&mut self.code_content);
-        let lang = self.code_language.take();
-        let lang_str = lang.as_deref().unwrap_or("text");
+        let info = self.code_language.take();
+        let (code_block, warnings) = code_fence_block(code.trim_end(), info.as_deref().unwrap_or("text"));
+        self.warnings.extend(warnings.into_iter().map(|warning| (self.code_line, warning)));
         
-        if lang_str == "mermaid" {
+        if code_block.language == "mermaid" {
             self.add_mermaid_diagram(&code);
             return;
         }
+        if code_block.language == "math" {
+            self.check_math(code.trim(), self.code_line);
+            self.add_bullet_point(BulletPoint::equation(code.trim()));
+            return;
+        }
         
-        let code_block = CodeBlock::new(code.trim(), lang_str);
+        let code_block = match self.code_theme {
+            Some(ref theme) => code_block.theme(theme),
+            None => code_block,
+        };
         
         if let Some(ref mut slide) = self.current_slide {
             slide.code_blocks.push(code_block);
@@ -360,7 +603,22 @@ impl MarkdownParser {
     }
 
     fn add_mermaid_diagram(&mut self, code: &str) {
-        let elements = mermaid::create_diagram_elements(code);
+        // Diagram lines count from the line after the opening fence
+        let to_file = |mut diagnostic: MermaidDiagnostic| {
+            diagnostic.line += self.code_line;
+            diagnostic
+        };
+        let elements = match mermaid::create_diagram_elements(code) {
+            Ok(mut elements) => {
+                let warnings = std::mem::take(&mut elements.warnings);
+                self.diagnostics.extend(warnings.into_iter().map(to_file));
+                elements
+            }
+            Err(error) => {
+                self.diagnostics.push(to_file(error));
+                mermaid::placeholder_elements(code)
+            }
+        };
         let diagram_type = mermaid::detect_type(code);
         let (_, _, title, _) = mermaid::get_diagram_style(diagram_type);
         
@@ -398,6 +656,13 @@ impl MarkdownParser {
             conn
         }).collect();
         
+        // Apply offset to charts
+        let charts: Vec<_> = elements.charts.into_iter().map(|mut chart| {
+            chart.x = (chart.x as i32 + offset_x).max(0) as u32;
+            chart.y = (chart.y as i32 + offset_y).max(0) as u32;
+            chart
+        }).collect();
+        
         if let Some(ref mut slide) = self.current_slide {
             for shape in shapes {
                 slide.shapes.push(shape);
@@ -405,6 +670,7 @@ impl MarkdownParser {
             for connector in connectors {
                 slide.connectors.push(connector);
             }
+            slide.charts.extend(charts);
         } else {
             let mut slide = SlideContent::new(title);
             for shape in shapes {
@@ -413,6 +679,7 @@ impl MarkdownParser {
             for connector in connectors {
                 slide.connectors.push(connector);
             }
+            slide.charts.extend(charts);
             self.current_slide = Some(slide);
         }
     }
@@ -429,6 +696,102 @@ impl MarkdownParser {
         }
     }
 
+    /// Math in a paragraph or list item makes it an equation; elsewhere the
+    /// TeX stays as text
+    fn push_math(&mut self, tex: &str, display: bool) {
+        self.check_math(tex, self.math_line);
+        if self.in_table || self.in_blockquote || self.in_heading || self.pending_image.is_some() {
+            let delimiter = if display { "$$" } else { "$" };
+            self.push_text(&format!("{delimiter}{tex}{delimiter}"));
+            return;
+        }
+        let offset = self.current_runs.iter().map(|run| run.text.len()).sum();
+        self.math_spans.push((offset, tex.to_string(), display));
+    }
+
+    /// Warn about TeX that does not convert; it is shown as written
+    fn check_math(&mut self, tex: &str, line: usize) {
+        if let Err(PptxError::InvalidValue(message)) = Equation::new(tex).validate() {
+            self.warnings.push((line, message));
+        }
+    }
+
+    /// Bullet of the current paragraph or list item with math: a display
+    /// equation on its own, or text with inline equations
+    /// Add the text of the open list item as a bullet at its list's level
+    fn end_list_item(&mut self) {
+        let level = self.list_depth.saturating_sub(1);
+        if !self.math_spans.is_empty() {
+            let bullet = self.take_math_paragraph();
+            self.list_items.push(bullet.with_level(level));
+            return;
+        }
+        let item = std::mem::take(&mut self.current_text).trim().to_string();
+        let runs = trim_runs(std::mem::take(&mut self.current_runs));
+        if !item.is_empty() {
+            self.list_items.push(text_bullet(&item, runs).with_level(level));
+        }
+    }
+
+    fn take_math_paragraph(&mut self) -> BulletPoint {
+        let runs = std::mem::take(&mut self.current_runs);
+        let mut spans = std::mem::take(&mut self.math_spans).into_iter().peekable();
+        self.current_text.clear();
+        if spans.len() == 1
+            && let Some((_, tex, true)) = spans.peek()
+            && runs.iter().all(|run| run.text.trim().is_empty())
+        {
+            return BulletPoint::equation(tex);
+        }
+
+        // Insert the equations between the text runs at their offsets
+        let mut with_math = Vec::new();
+        let mut start = 0;
+        for run in runs {
+            let end = start + run.text.len();
+            let mut rest = run.text.as_str();
+            let mut at = start;
+            while let Some((offset, tex, _)) = spans.next_if(|(offset, _, _)| *offset <= end) {
+                with_math.push(Run { text: rest[..offset - at].to_string(), ..run.clone() });
+                with_math.push(Run::math(&tex));
+                rest = &rest[offset - at..];
+                at = offset;
+            }
+            with_math.push(Run { text: rest.to_string(), ..run });
+            start = end;
+        }
+        with_math.extend(spans.map(|(_, tex, _)| Run::math(&tex)));
+        with_math.retain(|run| !run.text.is_empty());
+        BulletPoint::rich(trim_runs(with_math))
+    }
+
+    /// `![bg](path)` sets the slide background and `![bg left](path)` or
+    /// `![bg right](path)` fills half of the slide beside the text; other
+    /// images become placeholders
+    fn add_image(&mut self, url: &str, title: &str, alt: &str) {
+        let mut words = alt.split_whitespace().map(str::to_lowercase);
+        if words.next().as_deref() != Some("bg") {
+            let label = if alt.is_empty() { title } else { alt };
+            self.add_image_placeholder(url, label);
+            return;
+        }
+        let side = match words.next().as_deref() {
+            Some("left") => Some(ImageSide::Left),
+            Some("right") => Some(ImageSide::Right),
+            _ => None,
+        };
+        let path = if url.contains("://") { url.into() } else { self.base_dir.join(url) };
+        let path = path.to_string_lossy();
+        let format = Path::new(url).extension().and_then(|ext| ext.to_str()).unwrap_or("png");
+        let image = Image::new(&path, SLIDE_WIDTH, SLIDE_HEIGHT, format);
+
+        let slide = self.current_slide.get_or_insert_with(|| SlideContent::new("Slide"));
+        match side {
+            Some(side) => *slide = slide.clone().side_image(image, side),
+            None => slide.background = Some(Background::picture(image)),
+        }
+    }
+
     fn add_image_placeholder(&mut self, url: &str, alt: &str) {
         let label = if alt.is_empty() { url } else { alt };
         
@@ -445,18 +808,187 @@ impl MarkdownParser {
         }
     }
 
+    /// Apply the directives in an HTML comment to the current slide, or to
+    /// the next one when no slide has started yet
+    fn add_directives(&mut self, html: &str) {
+        if let Some(marker) = directives::column_marker(html) {
+            self.add_column_marker(marker);
+            return;
+        }
+        let (directives, warnings) = directives::parse_slide_directives(html, self.html_line);
+        self.warnings.extend(warnings);
+        match self.current_slide {
+            Some(ref mut slide) => std::mem::take(&mut self.pending_directives)
+                .into_iter()
+                .chain(directives)
+                .for_each(|directive| directive.apply(slide)),
+            None => self.pending_directives.extend(directives),
+        }
+    }
+
+    /// Start, close or break columns on the current slide
+    fn add_column_marker(&mut self, marker: ColumnMarker) {
+        self.flush_list_items();
+        if marker == ColumnMarker::End {
+            if self.column_open {
+                self.column_open = false;
+            } else {
+                self.in_columns = false;
+            }
+            return;
+        }
+
+        let slide = self.current_slide.get_or_insert_with(|| SlideContent::new("Slide"));
+        if slide.layout != SlideLayout::Comparison {
+            slide.layout = SlideLayout::TwoColumn;
+        }
+        let breaks = match marker {
+            ColumnMarker::Columns => {
+                self.in_columns = true;
+                self.column_count = 0;
+                false
+            }
+            ColumnMarker::Column => {
+                self.in_columns = true;
+                self.column_open = true;
+                self.column_count += 1;
+                self.column_count > 1
+            }
+            _ => true,
+        };
+        if !breaks {
+            return;
+        }
+        if slide.column_break.is_some() {
+            let warning = "only two columns are supported; the extra column continues the right one";
+            self.warnings.push((self.html_line, warning.to_string()));
+        } else {
+            *slide = slide.clone().column_break();
+        }
+    }
+
     fn finalize_current_slide(&mut self) {
         self.flush_list_items();
         
-        if let Some(slide) = self.current_slide.take() {
+        if let Some(mut slide) = self.current_slide.take() {
+            for directive in std::mem::take(&mut self.pending_directives) {
+                directive.apply(&mut slide);
+            }
+            promote_column_headings(&mut slide, &self.heading_bullets);
             self.slides.push(slide);
         }
+        self.heading_bullets.clear();
+        self.in_columns = false;
+        self.column_count = 0;
+        self.column_open = false;
+    }
+}
+
+/// Bullet of a line of text, keeping its runs when it mixes formatting or links
+fn text_bullet(text: &str, runs: Vec<Run>) -> BulletPoint {
+    if is_rich(&runs) {
+        BulletPoint::new(text).with_runs(runs)
+    } else {
+        BulletPoint::new(text)
+    }
+}
+
+/// Add a bullet in the slide's bullet style; equations stay without a bullet
+fn add_bullet(mut slide: SlideContent, bullet: BulletPoint) -> SlideContent {
+    slide.content.push(bullet.text.clone());
+    let bullet = if bullet.equation { bullet } else { bullet.with_style(slide.bullet_style) };
+    slide.bullets.push(bullet);
+    slide
+}
+
+/// Two columns that both open with a heading make a comparison slide, with
+/// the headings above the columns
+fn promote_column_headings(slide: &mut SlideContent, headings: &[(usize, String)]) {
+    let Some(split) = slide.column_break else { return };
+    if split == 0 || split >= slide.bullets.len() || !matches!(slide.layout, SlideLayout::TwoColumn | SlideLayout::Comparison) {
+        return;
+    }
+    let heading = |index: usize| headings.iter().find(|(i, _)| *i == index).map(|(_, title)| title.clone());
+    let (Some(left), Some(right)) = (heading(0), heading(split)) else { return };
+    for index in [split, 0] {
+        slide.bullets.remove(index);
+        slide.content.remove(index);
+    }
+    slide.column_break = Some(split - 1);
+    slide.column_headings = Some((left, right));
+    slide.layout = SlideLayout::Comparison;
+}
+
+/// Code block of a fence, sized to its lines, from the fence info string:
+/// the language, `{1,3-5}` highlighted lines and `showLineNumbers`. A `diff`
+/// language, or `diff-rust` for a Rust diff, colours added and removed lines.
+/// Ranges are clipped to the lines of the code, with a warning for each range
+/// that is reversed or reaches past them.
+fn code_fence_block(code: &str, info: &str) -> (CodeBlock, Vec<String>) {
+    let line_count = code.lines().count().max(1);
+    let mut warnings = Vec::new();
+    let (language, options) = match info.find(|c: char| c.is_whitespace() || c == '{') {
+        Some(end) => info.split_at(end),
+        None => (info, ""),
+    };
+    let mut block = match language.strip_prefix("diff") {
+        Some(rest) if rest.is_empty() || rest.starts_with('-') => {
+            CodeBlock::new(code, rest.strip_prefix('-').unwrap_or("text")).diff()
+        }
+        _ => CodeBlock::new(code, language),
+    };
+
+    let mut options = options.trim();
+    while !options.is_empty() {
+        if let Some(ranges) = options.strip_prefix('{') {
+            let (ranges, rest) = ranges.split_once('}').unwrap_or((ranges, ""));
+            for range in ranges.split(',').map(str::trim) {
+                let (start, end) = range.split_once('-').unwrap_or((range, range));
+                let (Ok(start), Ok(end)) = (start.trim().parse::<usize>(), end.trim().parse::<usize>()) else {
+                    continue;
+                };
+                if start > end {
+                    warnings.push(format!("highlighted lines `{range}` are reversed"));
+                    continue;
+                }
+                if start == 0 || end > line_count {
+                    warnings.push(format!("highlighted lines `{range}` reach past the {line_count} lines of the code"));
+                }
+                block = block.highlight_lines(start.max(1)..=end.min(line_count));
+            }
+            options = rest.trim_start();
+        } else {
+            let (word, rest) = options.split_once(char::is_whitespace).unwrap_or((options, ""));
+            if ["showlinenumbers", "linenos", "numberlines"].contains(&word.to_lowercase().as_str()) {
+                block = block.line_numbers();
+            }
+            options = rest.trim_start();
+        }
+    }
+    (block.auto_size(), warnings)
+}
+
+/// Check whether runs carry any formatting or links
+fn is_rich(runs: &[Run]) -> bool {
+    runs.iter().any(|r| r.math || r.format.bold || r.format.italic || r.format.font_family.is_some() || r.hyperlink.is_some())
+}
+
+/// Trim leading and trailing whitespace of a run sequence
+fn trim_runs(mut runs: Vec<Run>) -> Vec<Run> {
+    if let Some(first) = runs.first_mut() {
+        first.text = first.text.trim_start().to_string();
+    }
+    if let Some(last) = runs.last_mut() {
+        last.text = last.text.trim_end().to_string();
     }
+    runs.retain(|r| !r.text.is_empty());
+    runs
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::generator::BulletStyle;
 
     #[test]
     fn test_basic_headings() {
@@ -474,11 +1006,26 @@ mod tests {
         assert_eq!(slides[0].content.len(), 3);
     }
 
+    #[test]
+    fn test_nested_lists() {
+        let md = "# Test\n- Parent **one**\n  - Child\n    - Grandchild\n- Parent two\n  1. Step\n\n- Loose\n\n  - Under loose";
+        let slide = &parse(md).unwrap()[0];
+        let bullets: Vec<(&str, u32)> = slide.bullets.iter().map(|b| (b.text.as_str(), b.level)).collect();
+        assert_eq!(bullets, [
+            ("Parent **one**", 0), ("Child", 1), ("Grandchild", 2), ("Parent two", 0), ("Step", 1),
+            ("Loose", 0), ("Under loose", 1),
+        ]);
+        assert!(!slide.bullets[0].runs.is_empty());
+    }
+
     #[test]
     fn test_table() {
-        let md = "# Data\n\n| A | B |\n|---|---|\n| 1 | 2 |";
+        let md = "# Data\n\n| A | B | C |\n|:---|---:|---|\n| 1 | 2 | 3 |";
         let slides = parse(md).unwrap();
-        assert!(slides[0].table.is_some());
+        let table = slides[0].table.as_ref().unwrap();
+        let aligns: Vec<CellAlign> = table.rows[1].cells.iter().map(|cell| cell.align.clone()).collect();
+        assert_eq!(aligns, [CellAlign::Left, CellAlign::Right, CellAlign::Center]);
+        assert_eq!(table.rows[0].cells[0].align, CellAlign::Left);
     }
 
     #[test]
@@ -489,6 +1036,32 @@ mod tests {
         assert_eq!(slides[0].code_blocks[0].language, "rust");
     }
 
+    #[test]
+    fn test_code_fence_options() {
+        let md = "---\ncode_theme: solarized-light\n---\n# Code\n\n```rust {1,3-4} showLineNumbers\na\nb\nc\nd\n```\n\n```diff-python\n-x = 1\n+x = 2\n```";
+        let document = parse_document(md).unwrap();
+        assert!(document.warnings.is_empty());
+        let blocks = &document.slides[0].code_blocks;
+        assert_eq!(blocks[0].language, "rust");
+        assert_eq!(blocks[0].highlight_lines, [1, 3, 4]);
+        assert!(blocks[0].line_numbers && !blocks[0].diff);
+        assert_eq!((blocks[1].language.as_str(), blocks[1].diff), ("python", true));
+        assert!(blocks.iter().all(|b| b.theme == "solarized-light" && b.height < 4000000));
+        assert_eq!(blocks[0].info_string(), "rust {1,3-4} showLineNumbers");
+        assert_eq!(blocks[1].info_string(), "diff-python");
+
+        let document = parse_document("# Code\n\n```rust {2-999999999999,3-1}\na\nb\nc\n```").unwrap();
+        assert_eq!(document.slides[0].code_blocks[0].highlight_lines, [2, 3]);
+        assert_eq!(document.warnings, [
+            (3, "highlighted lines `2-999999999999` reach past the 3 lines of the code".to_string()),
+            (3, "highlighted lines `3-1` are reversed".to_string()),
+        ]);
+
+        let document = parse_document("---\ncode_theme: missing.tmTheme\n---\n# Code\n\n```\nx\n```").unwrap();
+        assert_eq!(document.warnings[0].0, 2);
+        assert_eq!(document.slides[0].code_blocks[0].theme, "missing.tmTheme");
+    }
+
     #[test]
     fn test_speaker_notes() {
         let md = "# Slide\n- Content\n\n> Speaker notes here";
@@ -516,4 +1089,118 @@ mod tests {
         let slides = parse(md).unwrap();
         assert!(!slides[0].shapes.is_empty());
     }
+
+    #[test]
+    fn test_mermaid_diagnostics() {
+        let md = "# Flow\n\n```mermaid\nflowchart LR\n    A --> B\n    ???\n```\n\n# Other\n\n```mermaid\nvenn\n```";
+        let (slides, diagnostics) = parse_with_diagnostics(md).unwrap();
+        assert_eq!(slides.len(), 2);
+        assert_eq!(diagnostics[0], MermaidDiagnostic::new(6, 5, "unsupported statement `???`"));
+        assert_eq!(diagnostics[1], MermaidDiagnostic::new(12, 1, "unknown diagram type `venn`"));
+        // The unknown block still gets a placeholder
+        assert_eq!(slides[1].shapes[0].text.as_deref(), Some("Diagram: venn"));
+    }
+
+    #[test]
+    fn test_links() {
+        let md = "# Links\n- See [the docs](https://docs.rs)\n- Plain\n\n| Site |\n|---|\n| [crates](https://crates.io) |";
+        let slides = parse(md).unwrap();
+        assert_eq!(slides[0].content[0], "See the docs");
+        let runs = &slides[0].bullets[0].runs;
+        assert_eq!(runs[0].text, "See ");
+        assert!(runs[0].hyperlink.is_none());
+        assert_eq!(runs[1].hyperlink.as_ref().unwrap().action.relationship_target(), "https://docs.rs");
+        assert!(slides[0].bullets[1].runs.is_empty());
+
+        let table = slides[0].table.as_ref().unwrap();
+        assert!(table.rows[1].cells[0].runs[0].hyperlink.is_some());
+    }
+
+    #[test]
+    fn test_rich_list_item() {
+        let md = "# Test\n- Use **bold**, *italic* and `code` here";
+        let slides = parse(md).unwrap();
+        let runs = &slides[0].bullets[0].runs;
+        let texts: Vec<_> = runs.iter().map(|r| r.text.as_str()).collect();
+        assert_eq!(texts, vec!["Use ", "bold", ", ", "italic", " and ", "code", " here"]);
+        assert!(runs[1].format.bold);
+        assert!(runs[3].format.italic);
+        assert_eq!(runs[5].format.font_family.as_deref(), Some("Consolas"));
+    }
+
+    #[test]
+    fn test_front_matter_and_directives() {
+        let md = "---\ntitle: Deck\ntheme: dark\n---\n<!-- transition: fade -->\n# One\n- A\n\n# Two\n<!-- layout: two-column -->\n<!-- background: #222 -->\n\n```mermaid\nflowchart LR\n    A --> B\n    ???\n```\n\nText <!-- layout: sideways -->";
+        let document = parse_document(md).unwrap();
+        assert_eq!(document.front_matter.title.as_deref(), Some("Deck"));
+        assert_eq!(document.slides.len(), 2);
+        assert!(document.slides[0].transition.is_some());
+        assert_eq!(document.slides[0].title_color.as_deref(), Some("BB86FC"));
+        assert_eq!(document.slides[1].layout, crate::generator::SlideLayout::TwoColumn);
+        assert!(document.slides[1].background.is_some());
+
+        // Lines count from the top of the file, front matter included
+        assert_eq!(document.diagnostics[0].line, 16);
+        assert_eq!(document.warnings, [(19, "unknown layout `sideways`".to_string())]);
+    }
+
+    #[test]
+    fn test_columns() {
+        let md = "# Plan\n:::columns\n:::column\n- One\n- Two\n:::\n:::column\nThree\n:::\n:::\n\n# Split\nLeft\n<!-- split -->\nRight";
+        let slides = parse(md).unwrap();
+        assert_eq!(slides[0].layout, SlideLayout::TwoColumn);
+        assert_eq!(slides[0].column_break, Some(2));
+        assert_eq!(slides[0].content, ["One", "Two", "Three"]);
+        assert_eq!(slides[1].layout, SlideLayout::TwoColumn);
+        assert_eq!(slides[1].column_break, Some(1));
+
+        // Columns opening with headings make a comparison slide
+        let md = "# Build vs Buy\n:::columns\n:::column\n## Build\n- Control\n:::\n:::column\n## Buy\n- Speed\n:::\n:::";
+        let slide = &parse(md).unwrap()[0];
+        assert_eq!(slide.layout, SlideLayout::Comparison);
+        assert_eq!(slide.column_headings, Some(("Build".to_string(), "Buy".to_string())));
+        assert_eq!(slide.content, ["Control", "Speed"]);
+        assert_eq!(slide.column_break, Some(1));
+    }
+
+    #[test]
+    fn test_background_images() {
+        let md = "# Team\n![bg right](missing.png)\n- Ten engineers\n\n# Cover\n![bg](cover.jpg)\n\n# Chart\n![Sales chart](chart.png)";
+        let slides = parse(md).unwrap();
+        assert_eq!(slides[0].image_side, Some(ImageSide::Right));
+        assert_eq!(slides[0].images[0].x, SLIDE_WIDTH / 2);
+        assert_eq!(slides[0].content, ["Ten engineers"]);
+        assert!(matches!(slides[1].background, Some(Background::Picture(ref image)) if image.filename == "cover.jpg"));
+        assert!(slides[1].content.is_empty());
+        assert_eq!(slides[2].shapes[0].text.as_deref(), Some("[Image: Sales chart]"));
+    }
+
+    #[test]
+    fn test_math_order() {
+        let md = "# Order\n\n- Before\n\n$$\nx^2\n$$\n\n- After $y$\n\n```math\nz\n```\n\nEnd";
+        let slide = &parse(md).unwrap()[0];
+        let bullets: Vec<(&str, bool)> = slide.bullets.iter().map(|b| (b.text.as_str(), b.equation)).collect();
+        assert_eq!(bullets, [("Before", false), ("x^2", true), ("After y", false), ("z", true), ("End", false)]);
+        assert!(slide.bullets[2].has_math() && slide.equations.is_empty());
+    }
+
+    #[test]
+    fn test_math() {
+        let md = "# Formulas\n\n- Energy\n- Mass $m$ and speed $c^2$\n\n$$\n\\int_0^1 x\\,dx\n$$\n\n| $a_b$ |\n|---|\n\n$\\foo$\n";
+        let document = parse_document(md).unwrap();
+        let slide = &document.slides[0];
+        assert!(slide.equations.is_empty());
+        assert_eq!(slide.bullets.len(), 4);
+        // Inline math stays in its sentence
+        let runs: Vec<(&str, bool)> = slide.bullets[1].runs.iter().map(|run| (run.text.as_str(), run.math)).collect();
+        assert_eq!(runs, [("Mass ", false), ("m", true), (" and speed ", false), ("c^2", true)]);
+        assert!(!slide.bullets[1].equation);
+        assert!(slide.bullets[2].equation);
+        assert_eq!(slide.bullets[2].runs[0].text, r"\int_0^1 x\,dx");
+        assert_eq!(slide.bullets[2].style, BulletStyle::None);
+        // Math in tables stays as text
+        let table = slide.table.as_ref().unwrap();
+        assert_eq!(table.rows[0].cells[0].text, "$a_b$");
+        assert_eq!(document.warnings, [(13, "LaTeX: unknown command \\foo at column 1".to_string())]);
+    }
 }
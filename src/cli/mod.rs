@@ -5,10 +5,10 @@ pub mod parser;
 pub mod markdown;
 pub mod syntax;
 
-pub use commands::{CreateCommand, FromMarkdownCommand, InfoCommand, ValidateCommand};
+pub use commands::{CreateCommand, FromMarkdownCommand, InfoCommand, ToMarkdownCommand, ValidateCommand};
 pub use parser::{
     Cli, Commands, Parser, Command, 
-    CreateArgs, FromMarkdownArgs, InfoArgs, ValidateArgs, Web2PptArgs,
+    CreateArgs, FromMarkdownArgs, InfoArgs, ToMarkdownArgs, ValidateArgs, Web2PptArgs,
 };
 pub use markdown::parse_markdown;
-pub use syntax::{highlight_code, generate_highlighted_code_xml};
+pub use syntax::{highlight_code, highlight_code_with_theme, generate_highlighted_code_xml, load_theme, render_code_block, theme_names, RenderedCode};
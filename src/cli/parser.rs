@@ -18,6 +18,9 @@ Examples:
   # Auto-generate output filename from Markdown
   pptcli md2ppt slides.md
 
+  # Export a presentation back to Markdown
+  pptcli ppt2md deck.pptx
+
   # Convert webpage to PowerPoint (requires --features web2ppt)
   pptcli web2ppt https://example.com -o output.pptx
 
@@ -79,10 +82,23 @@ Supported Markdown Features:
   > Blockquote   → Speaker notes
   | Table |      → Tables (GFM style)
   ```code```     → Code blocks (as shapes)
-  ```mermaid     → Mermaid diagrams (12 types)
+  ```mermaid     → Mermaid diagrams (17 types)
   ---            → Slide break (continuation)
+  <!-- layout: two-column -->  → Slide layout, background or transition
+  :::columns / <!-- split -->  → Two columns (comparison with headings)
+  ![bg right](photo.jpg)       → Picture on half the slide (bg: background)
+  $x^2$ / $$ ... $$            → Equations (LaTeX, editable in PowerPoint)
+  ```rust {3-5} showLineNumbers → Highlighted lines and line numbers (```diff too)
+
+Front matter (YAML between --- lines or TOML between +++ lines) sets
+title, author, theme, aspect, footer, slide_numbers, transition and
+code_theme.
 
 Example Markdown:
+  ---
+  title: Quarterly Review
+  theme: dark
+  ---
   # Introduction
   - Welcome to the presentation
   - **Key point** with emphasis
@@ -97,6 +113,7 @@ Example Markdown:
 Examples:
   pptcli md2ppt slides.md presentation.pptx
   pptcli md2ppt slides.md --title \"My Presentation\"
+  pptcli md2ppt slides.md --strict  # Fail on Mermaid diagram problems
   pptcli md2ppt slides.md  # Auto-generates slides.pptx"
     )]
     Md2Ppt {
@@ -109,10 +126,43 @@ Examples:
         output: Option<String>,
         
         /// Presentation title
-        #[arg(long, help = "Title of the presentation (overrides Markdown content)")]
+        #[arg(long, help = "Title of the presentation (overrides the front matter title)")]
         title: Option<String>,
+        
+        /// Treat Mermaid diagram problems as errors
+        #[arg(long, help = "Fail instead of warning when a Mermaid diagram cannot be fully parsed")]
+        strict: bool,
     },
     
+    /// Export a PPTX file to Markdown
+    #[command(
+        name = "ppt2md",
+        alias = "to-md",
+        long_about = "Export a PowerPoint presentation to Markdown that md2ppt can read back.
+
+Each slide becomes a '# title' section with its bullets (nested by level),
+two-column layouts as :::columns, GFM tables, code blocks (from monospace text
+boxes), pictures and speaker notes as blockquotes. Pictures are extracted to a
+folder next to the Markdown file and linked relatively.
+
+Examples:
+  pptcli ppt2md deck.pptx                     # Writes deck.md and deck_images/
+  pptcli ppt2md deck.pptx notes/deck.md --images media"
+    )]
+    Ppt2Md {
+        /// Input PPTX file
+        #[arg(value_name = "INPUT", help = "Path to the input PPTX file")]
+        input: String,
+
+        /// Output Markdown file (optional: auto-generated from input if not provided)
+        #[arg(value_name = "OUTPUT", help = "Path to the output Markdown file (default: INPUT.md)")]
+        output: Option<String>,
+
+        /// Folder for extracted pictures
+        #[arg(long, value_name = "DIR", help = "Folder for pictures, relative to the Markdown file (default: OUTPUT_images)")]
+        images: Option<String>,
+    },
+
     /// Show presentation information
     #[command(
         long_about = "Display information about a PPTX file.
@@ -225,6 +275,7 @@ pub struct FromMarkdownArgs {
     pub input: String,
     pub output: String,
     pub title: Option<String>,
+    pub strict: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -232,6 +283,14 @@ pub struct Md2PptArgs {
     pub input: String,
     pub output: Option<String>,
     pub title: Option<String>,
+    pub strict: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct ToMarkdownArgs {
+    pub input: String,
+    pub output: String,
+    pub images: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -264,6 +323,7 @@ pub enum Command {
     Create(CreateArgs),
     FromMarkdown(FromMarkdownArgs),
     Md2Ppt(Md2PptArgs),
+    ToMarkdown(ToMarkdownArgs),
     Info(InfoArgs),
     Validate(ValidateArgs),
     Web2Ppt(Web2PptArgs),
@@ -280,7 +340,7 @@ impl From<Commands> for Command {
                     template,
                 })
             }
-            Commands::Md2Ppt { input, output, title } => {
+            Commands::Md2Ppt { input, output, title, strict } => {
                 // If output is not provided, auto-generate it
                 let output = output.unwrap_or_else(|| {
                     use std::path::Path;
@@ -304,8 +364,15 @@ impl From<Commands> for Command {
                     input,
                     output,
                     title,
+                    strict,
                 })
             }
+            Commands::Ppt2Md { input, output, images } => {
+                let output = output.unwrap_or_else(|| {
+                    std::path::Path::new(&input).with_extension("md").to_string_lossy().into_owned()
+                });
+                Command::ToMarkdown(ToMarkdownArgs { input, output, images })
+            }
             Commands::Info { file } => {
                 Command::Info(InfoArgs { file })
             }
@@ -379,10 +446,11 @@ mod tests {
         ];
         let cli = Cli::parse_from(args.iter());
         match cli.command {
-            Commands::Md2Ppt { input, output, title } => {
+            Commands::Md2Ppt { input, output, title, strict } => {
                 assert_eq!(input, "input.md");
                 assert_eq!(output, Some("output.pptx".to_string()));
                 assert_eq!(title, Some("From Markdown".to_string()));
+                assert!(!strict);
             }
             _ => panic!("Expected Md2Ppt command"),
         }
@@ -399,7 +467,7 @@ mod tests {
         ];
         let cli = Cli::parse_from(args.iter());
         match cli.command {
-            Commands::Md2Ppt { input, output, title } => {
+            Commands::Md2Ppt { input, output, title, .. } => {
                 assert_eq!(input, "input.md");
                 assert_eq!(output, None);
                 assert_eq!(title, Some("From Markdown".to_string()));
@@ -426,6 +494,44 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_md2ppt_strict() {
+        let args = vec![
+            "pptcli".to_string(),
+            "md2ppt".to_string(),
+            "input.md".to_string(),
+            "--strict".to_string(),
+        ];
+        let cli = Cli::parse_from(args.iter());
+        match Command::from(cli.command) {
+            Command::FromMarkdown(args) => {
+                assert!(args.strict);
+                assert_eq!(args.output, "input.pptx");
+            }
+            _ => panic!("Expected FromMarkdown command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_ppt2md() {
+        let args = vec![
+            "pptcli".to_string(),
+            "ppt2md".to_string(),
+            "slides/deck.pptx".to_string(),
+            "--images".to_string(),
+            "media".to_string(),
+        ];
+        let cli = Cli::parse_from(args.iter());
+        match Command::from(cli.command) {
+            Command::ToMarkdown(args) => {
+                assert_eq!(args.input, "slides/deck.pptx");
+                assert_eq!(args.output, "slides/deck.md");
+                assert_eq!(args.images.as_deref(), Some("media"));
+            }
+            _ => panic!("Expected ToMarkdown command"),
+        }
+    }
+
     #[test]
     fn test_parse_info() {
         let args = vec![
@@ -1,11 +1,29 @@
 //! Syntax highlighting for code blocks
 //!
 //! Uses syntect to provide syntax highlighting for code blocks in presentations.
+//! Themes are syntect's built-in ones or TextMate `.tmTheme` files; the box,
+//! line number and highlight colours come from the theme too.
 
-use syntect::highlighting::{ThemeSet, Style};
+use std::sync::LazyLock;
+
+use syntect::highlighting::{Color, FontStyle, ThemeSet, Style, Theme};
 use syntect::parsing::SyntaxSet;
 use syntect::easy::HighlightLines;
 
+use crate::exc::PptxError;
+use crate::generator::slide_content::CodeBlock;
+
+/// Theme of code blocks that don't choose one
+pub const DEFAULT_THEME: &str = "Solarized (dark)";
+
+/// Colour of added lines in diffs
+const DIFF_ADDED: Color = Color { r: 0x2E, g: 0xA0, b: 0x43, a: 0xFF };
+/// Colour of removed lines in diffs
+const DIFF_REMOVED: Color = Color { r: 0xF8, g: 0x51, b: 0x49, a: 0xFF };
+
+static SYNTAXES: LazyLock<SyntaxSet> = LazyLock::new(SyntaxSet::load_defaults_newlines);
+static THEMES: LazyLock<ThemeSet> = LazyLock::new(ThemeSet::load_defaults);
+
 /// A highlighted text segment with color
 #[derive(Debug, Clone)]
 pub struct HighlightedSegment {
@@ -15,11 +33,40 @@ pub struct HighlightedSegment {
     pub italic: bool,
 }
 
+/// Names of the built-in themes
+pub fn theme_names() -> Vec<&'static str> {
+    THEMES.themes.keys().map(String::as_str).collect()
+}
+
+/// Load a built-in theme by name, ignoring case and punctuation so that
+/// `solarized-light` works, or a `.tmTheme` file by path
+pub fn load_theme(name: &str) -> Result<Theme, PptxError> {
+    if name.to_lowercase().ends_with(".tmtheme") {
+        return ThemeSet::get_theme(name)
+            .map_err(|e| PptxError::InvalidValue(format!("cannot load code theme {name}: {e}")));
+    }
+    let key = |name: &str| name.chars().filter(|c| c.is_alphanumeric()).collect::<String>().to_lowercase();
+    THEMES.themes.iter()
+        .find(|(theme, _)| key(theme) == key(name))
+        .map(|(_, theme)| theme.clone())
+        .ok_or_else(|| PptxError::InvalidValue(format!(
+            "unknown code theme `{name}`; built-in themes are {}",
+            theme_names().join(", ")
+        )))
+}
+
 /// Highlight code with syntax coloring
 pub fn highlight_code(code: &str, language: &str) -> Vec<Vec<HighlightedSegment>> {
-    let ps = SyntaxSet::load_defaults_newlines();
-    let ts = ThemeSet::load_defaults();
-    
+    highlight_code_with_theme(code, language, &THEMES.themes[DEFAULT_THEME])
+}
+
+/// Highlight code with the colours of `theme`
+pub fn highlight_code_with_theme(code: &str, language: &str, theme: &Theme) -> Vec<Vec<HighlightedSegment>> {
+    let mut highlighter = highlighter(language, theme);
+    code.lines().map(|line| highlight_line(&mut highlighter, line)).collect()
+}
+
+fn highlighter<'a>(language: &str, theme: &'a Theme) -> HighlightLines<'a> {
     // Map common language names to syntect syntax names
     let syntax_name = match language.to_lowercase().as_str() {
         "rust" | "rs" => "Rust",
@@ -48,67 +95,139 @@ pub fn highlight_code(code: &str, language: &str) -> Vec<Vec<HighlightedSegment>
         "toml" => "TOML",
         _ => "Plain Text",
     };
-    
-    let syntax = ps.find_syntax_by_name(syntax_name)
-        .or_else(|| ps.find_syntax_by_extension(language))
-        .unwrap_or_else(|| ps.find_syntax_plain_text());
-    
-    // Use Solarized (dark) theme for vibrant syntax colors
-    let theme = &ts.themes["Solarized (dark)"];
-    let mut highlighter = HighlightLines::new(syntax, theme);
-    
-    let mut lines = Vec::new();
-    
-    for line in code.lines() {
-        let ranges = highlighter.highlight_line(line, &ps).unwrap_or_default();
-        let segments: Vec<HighlightedSegment> = ranges.iter().map(|(style, text)| {
-            HighlightedSegment {
-                text: text.to_string(),
-                color: style_to_hex(style),
-                bold: style.font_style.contains(syntect::highlighting::FontStyle::BOLD),
-                italic: style.font_style.contains(syntect::highlighting::FontStyle::ITALIC),
-            }
-        }).collect();
-        lines.push(segments);
-    }
-    
-    lines
+
+    let syntax = SYNTAXES.find_syntax_by_name(syntax_name)
+        .or_else(|| SYNTAXES.find_syntax_by_extension(language))
+        .unwrap_or_else(|| SYNTAXES.find_syntax_plain_text());
+    HighlightLines::new(syntax, theme)
+}
+
+/// Highlight the next line; the highlighter keeps state between lines
+fn highlight_line(highlighter: &mut HighlightLines, line: &str) -> Vec<HighlightedSegment> {
+    let ranges = highlighter.highlight_line(line, &SYNTAXES).unwrap_or_default();
+    ranges.iter().map(|(style, text)| {
+        HighlightedSegment {
+            text: text.to_string(),
+            color: style_to_hex(style),
+            bold: style.font_style.contains(FontStyle::BOLD),
+            italic: style.font_style.contains(FontStyle::ITALIC),
+        }
+    }).collect()
 }
 
 /// Convert syntect Style to hex color
 fn style_to_hex(style: &Style) -> String {
-    format!("{:02X}{:02X}{:02X}", style.foreground.r, style.foreground.g, style.foreground.b)
+    hex(style.foreground)
 }
 
-/// Generate PPTX XML for highlighted code
-pub fn generate_highlighted_code_xml(code: &str, language: &str) -> String {
-    let highlighted = highlight_code(code, language);
-    let mut xml = String::new();
-    
-    for line_segments in highlighted {
-        xml.push_str("<a:p><a:pPr algn=\"l\"/>");
-        
-        if line_segments.is_empty() {
-            // Empty line - use Solarized base0 color (solidFill before latin)
-            xml.push_str(r#"<a:r><a:rPr lang="en-US" sz="1400" dirty="0"><a:solidFill><a:srgbClr val="839496"/></a:solidFill><a:latin typeface="Consolas"/></a:rPr><a:t> </a:t></a:r>"#);
+fn hex(color: Color) -> String {
+    format!("{:02X}{:02X}{:02X}", color.r, color.g, color.b)
+}
+
+/// `color` drawn over `background`, by its alpha
+fn blend(color: Color, background: Color) -> Color {
+    let alpha = color.a as u32;
+    let mix = |c: u8, b: u8| ((c as u32 * alpha + b as u32 * (255 - alpha)) / 255) as u8;
+    Color { r: mix(color.r, background.r), g: mix(color.g, background.g), b: mix(color.b, background.b), a: 0xFF }
+}
+
+/// Text of a code block and the colours of its box, from its theme
+#[derive(Debug, Clone)]
+pub struct RenderedCode {
+    /// `a:p` paragraphs, one per line
+    pub paragraphs: String,
+    pub background: String,
+    pub border: String,
+}
+
+/// Render a code block with its theme, line numbers, highlighted lines and
+/// diff colouring. Themes that don't load fall back to [`DEFAULT_THEME`].
+pub fn render_code_block(block: &CodeBlock) -> RenderedCode {
+    let theme = load_theme(&block.theme).unwrap_or_else(|_| THEMES.themes[DEFAULT_THEME].clone());
+    let settings = &theme.settings;
+    let background = settings.background.unwrap_or(Color::WHITE);
+    let foreground = blend(settings.foreground.unwrap_or(Color::BLACK), background);
+    let faded = |alpha: u8| blend(Color { a: alpha, ..foreground }, background);
+    // Editor line highlights are faint; slides need them stronger
+    let line_highlight = settings.line_highlight
+        .map(|c| blend(Color { a: c.a.max(0x40), ..c }, background))
+        .unwrap_or_else(|| faded(0x30));
+    let gutter = settings.gutter_foreground.map(|c| blend(c, background)).unwrap_or_else(|| faded(0x80));
+    let tint = |color: Color| hex(blend(Color { a: 0x40, ..color }, background));
+
+    let run = |text: &str, color: &str, bold: bool, italic: bool, highlight: Option<&str>| {
+        let bold = if bold { r#" b="1""# } else { "" };
+        let italic = if italic { r#" i="1""# } else { "" };
+        let highlight = highlight
+            .map(|color| format!(r#"<a:highlight><a:srgbClr val="{color}"/></a:highlight>"#))
+            .unwrap_or_default();
+        // OOXML order: solidFill, then highlight, then the latin font
+        format!(
+            r#"<a:r><a:rPr lang="en-US" sz="{}" dirty="0"{bold}{italic}><a:solidFill><a:srgbClr val="{color}"/></a:solidFill>{highlight}<a:latin typeface="{}"/></a:rPr><a:t>{}</a:t></a:r>"#,
+            block.font_size * 100,
+            escape_xml(&block.font_family),
+            escape_xml(text)
+        )
+    };
+
+    let width = block.last_line().to_string().len();
+    let mut highlighter = highlighter(&block.language, &theme);
+    let mut paragraphs = String::new();
+    for (i, line) in block.code.lines().enumerate() {
+        let number = block.first_line + i;
+        let header = block.diff && ["@@", "+++", "---"].iter().any(|prefix| line.starts_with(prefix));
+        let (marker, text) = match line.as_bytes().first() {
+            _ if !block.diff || header => ("", line),
+            Some(b'+' | b'-' | b' ') => line.split_at(1),
+            _ => (" ", line),
+        };
+        let highlight = if block.highlight_lines.contains(&number) || header {
+            Some(hex(line_highlight))
         } else {
-            for segment in line_segments {
-                let bold = if segment.bold { r#" b="1""# } else { "" };
-                let italic = if segment.italic { r#" i="1""# } else { "" };
-                let text = escape_xml(&segment.text);
-                
-                // OOXML order: solidFill must come before latin font
-                xml.push_str(&format!(
-                    r#"<a:r><a:rPr lang="en-US" sz="1400" dirty="0"{}{}><a:solidFill><a:srgbClr val="{}"/></a:solidFill><a:latin typeface="Consolas"/></a:rPr><a:t>{}</a:t></a:r>"#,
-                    bold, italic, segment.color, text
-                ));
+            match marker {
+                "+" => Some(tint(DIFF_ADDED)),
+                "-" => Some(tint(DIFF_REMOVED)),
+                _ => None,
             }
+        };
+        let highlight = highlight.as_deref();
+
+        paragraphs.push_str("<a:p><a:pPr algn=\"l\"/>");
+        if block.line_numbers {
+            // Line numbers are excluded from proofing, which also tells them
+            // apart from the code when reading the slide back
+            let number = run(&format!("{number:>width$} "), &hex(gutter), false, false, None);
+            paragraphs.push_str(&number.replacen(r#" dirty="0""#, r#" dirty="0" noProof="1""#, 1));
         }
-        
-        xml.push_str("</a:p>");
+        if !marker.is_empty() {
+            let color = match marker {
+                "+" => DIFF_ADDED,
+                "-" => DIFF_REMOVED,
+                _ => gutter,
+            };
+            paragraphs.push_str(&run(marker, &hex(color), true, false, highlight));
+        }
+        let segments = if header {
+            vec![HighlightedSegment { text: text.to_string(), color: hex(gutter), bold: false, italic: true }]
+        } else {
+            highlight_line(&mut highlighter, text)
+        };
+        if segments.is_empty() {
+            // Empty line: a space keeps its height and highlight
+            paragraphs.push_str(&run(" ", &hex(foreground), false, false, highlight));
+        }
+        for segment in segments {
+            paragraphs.push_str(&run(&segment.text, &segment.color, segment.bold, segment.italic, highlight));
+        }
+        paragraphs.push_str("</a:p>");
     }
-    
-    xml
+
+    RenderedCode { paragraphs, background: hex(background), border: hex(faded(0x20)) }
+}
+
+/// Generate PPTX XML for highlighted code
+pub fn generate_highlighted_code_xml(code: &str, language: &str) -> String {
+    render_code_block(&CodeBlock::new(code, language)).paragraphs
 }
 
 /// Escape XML special characters
@@ -183,4 +302,38 @@ mod tests {
                         xml.contains("839496") || xml.contains("93A1A1");
         assert!(has_color, "XML should contain Solarized theme colors, got: {}", xml);
     }
+
+    #[test]
+    fn test_load_theme() {
+        assert!(theme_names().contains(&DEFAULT_THEME));
+        assert_eq!(load_theme("solarized-light").unwrap().name.as_deref(), Some("Solarized (light)"));
+        assert!(load_theme("InspiredGitHub").is_ok());
+        assert!(load_theme("no-such-theme").unwrap_err().to_string().contains("base16-ocean.dark"));
+        assert!(load_theme("/missing/theme.tmTheme").is_err());
+    }
+
+    #[test]
+    fn test_render_code_block() {
+        let rendered = render_code_block(&CodeBlock::new("fn main() {}\n", "rust"));
+        assert_eq!((rendered.background.as_str(), rendered.border.as_str()), ("002B36", "103842"));
+
+        let code = "use std::fs;\nfn main() {\n    let x = 1;\n}";
+        let block = CodeBlock::new(code, "rust").theme("InspiredGitHub").line_numbers().first_line(9).highlight_lines([10]);
+        let rendered = render_code_block(&block);
+        assert_eq!(rendered.background, "FFFFFF");
+        assert_eq!(rendered.paragraphs.matches("<a:p>").count(), 4);
+        assert!(rendered.paragraphs.contains(r#"noProof="1"><a:solidFill><a:srgbClr val="B3B3B3"/></a:solidFill><a:latin typeface="Consolas"/></a:rPr><a:t> 9 </a:t>"#));
+        assert!(rendered.paragraphs.contains("<a:t>12 </a:t>"));
+        // Only the line numbered 10 is highlighted
+        let highlighted: Vec<&str> = rendered.paragraphs.split("</a:p>").filter(|p| p.contains("<a:highlight>")).collect();
+        assert_eq!(highlighted.len(), 1);
+        assert!(highlighted[0].contains("main"));
+
+        let block = CodeBlock::new("@@ -1 +1 @@\n-let a = 1;\n+let a = 2;\n let b = a;", "rust").diff().font("Fira Code", 12);
+        let paragraphs = render_code_block(&block).paragraphs;
+        assert!(paragraphs.contains(r#"<a:srgbClr val="2EA043"/></a:solidFill><a:highlight>"#));
+        assert!(paragraphs.contains(r#"<a:srgbClr val="F85149"/></a:solidFill><a:highlight>"#));
+        assert!(paragraphs.contains(r#"sz="1200""#) && paragraphs.contains("Fira Code"));
+        assert!(!paragraphs.contains("<a:t>+let"));
+    }
 }
@@ -0,0 +1,247 @@
+//! Slide and master backgrounds
+//!
+//! Backgrounds are written as a `p:bg/p:bgPr` fill on a slide or on the slide
+//! master. Picture backgrounds need an image relationship, allocated by
+//! `SlideRelationships` for slides and by the builder for the master.
+
+use super::gradients::{generate_gradient_fill_xml, GradientFill};
+use super::images::Image;
+use crate::prelude::themes::Theme;
+
+/// Default background (`bg1` of the theme), as written by the slide templates
+pub const DEFAULT_BACKGROUND_XML: &str = r#"<p:bg>
+<p:bgRef idx="1001">
+<a:schemeClr val="bg1"/>
+</p:bgRef>
+</p:bg>"#;
+
+/// Preset pattern (`a:pattFill prst`)
+#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+pub enum PatternType {
+    Percent5,
+    Percent10,
+    Percent20,
+    Percent25,
+    Percent50,
+    Percent75,
+    Horizontal,
+    Vertical,
+    LightHorizontal,
+    LightVertical,
+    DiagonalDown,
+    DiagonalUp,
+    DiagonalCross,
+    Cross,
+    SmallGrid,
+    LargeGrid,
+    DottedGrid,
+    SmallCheck,
+    LargeCheck,
+    Trellis,
+    Weave,
+    Plaid,
+    Shingle,
+    Wave,
+    ZigZag,
+    Sphere,
+    Divot,
+}
+
+impl PatternType {
+    /// Preset name used in `a:pattFill`
+    pub fn preset_name(&self) -> &'static str {
+        match self {
+            PatternType::Percent5 => "pct5",
+            PatternType::Percent10 => "pct10",
+            PatternType::Percent20 => "pct20",
+            PatternType::Percent25 => "pct25",
+            PatternType::Percent50 => "pct50",
+            PatternType::Percent75 => "pct75",
+            PatternType::Horizontal => "horz",
+            PatternType::Vertical => "vert",
+            PatternType::LightHorizontal => "ltHorz",
+            PatternType::LightVertical => "ltVert",
+            PatternType::DiagonalDown => "dnDiag",
+            PatternType::DiagonalUp => "upDiag",
+            PatternType::DiagonalCross => "diagCross",
+            PatternType::Cross => "cross",
+            PatternType::SmallGrid => "smGrid",
+            PatternType::LargeGrid => "lgGrid",
+            PatternType::DottedGrid => "dotGrid",
+            PatternType::SmallCheck => "smCheck",
+            PatternType::LargeCheck => "lgCheck",
+            PatternType::Trellis => "trellis",
+            PatternType::Weave => "weave",
+            PatternType::Plaid => "plaid",
+            PatternType::Shingle => "shingle",
+            PatternType::Wave => "wave",
+            PatternType::ZigZag => "zigZag",
+            PatternType::Sphere => "sphere",
+            PatternType::Divot => "divot",
+        }
+    }
+}
+
+/// Background fill of a slide or of the slide master
+#[derive(Clone, Debug)]
+pub enum Background {
+    /// Solid RGB colour (hex)
+    Solid(String),
+    /// Theme colour (`bg1`, `bg2`, `accent1`, ...)
+    Scheme(String),
+    /// Gradient fill
+    Gradient(GradientFill),
+    /// Picture, stretched by default or tiled with `Image::with_tile`
+    Picture(Box<Image>),
+    /// Preset pattern with foreground and background colours (hex)
+    Pattern {
+        pattern: PatternType,
+        foreground: String,
+        background: String,
+    },
+}
+
+impl Background {
+    /// Solid colour background (RGB hex, with or without `#`)
+    pub fn solid(color: &str) -> Self {
+        Background::Solid(color.trim_start_matches('#').to_uppercase())
+    }
+
+    /// Theme colour background (`bg1`, `accent1`, ...)
+    pub fn scheme(name: &str) -> Self {
+        Background::Scheme(name.to_string())
+    }
+
+    /// Gradient background
+    pub fn gradient(gradient: GradientFill) -> Self {
+        Background::Gradient(gradient)
+    }
+
+    /// Picture background
+    pub fn picture(image: Image) -> Self {
+        Background::Picture(Box::new(image))
+    }
+
+    /// Pattern background
+    pub fn pattern(pattern: PatternType, foreground: &str, background: &str) -> Self {
+        Background::Pattern {
+            pattern,
+            foreground: foreground.trim_start_matches('#').to_uppercase(),
+            background: background.trim_start_matches('#').to_uppercase(),
+        }
+    }
+
+    /// Background colour of a theme preset
+    pub fn from_theme(theme: &Theme) -> Self {
+        Background::solid(theme.background)
+    }
+
+    /// Picture to embed for this background, if it has data
+    pub fn image(&self) -> Option<&Image> {
+        match self {
+            Background::Picture(image) if image.has_data() => Some(image.as_ref()),
+            _ => None,
+        }
+    }
+
+    /// Generate the `p:bg` XML
+    ///
+    /// `image_rel_id` is the relationship of the picture; a picture without
+    /// one falls back to the default background.
+    pub fn to_xml(&self, image_rel_id: Option<usize>) -> String {
+        let fill = match self {
+            Background::Solid(color) => format!(r#"<a:solidFill><a:srgbClr val="{color}"/></a:solidFill>"#),
+            Background::Scheme(name) => format!(r#"<a:solidFill><a:schemeClr val="{name}"/></a:solidFill>"#),
+            Background::Gradient(gradient) => generate_gradient_fill_xml(gradient),
+            Background::Picture(image) => match image_rel_id {
+                Some(rel_id) => {
+                    let effects = image.blip_effects_xml();
+                    let blip = if effects.is_empty() {
+                        format!(r#"<a:blip r:embed="rId{rel_id}"/>"#)
+                    } else {
+                        format!(r#"<a:blip r:embed="rId{rel_id}">{effects}</a:blip>"#)
+                    };
+                    format!(r#"<a:blipFill dpi="0" rotWithShape="1">{blip}{}</a:blipFill>"#, image.fill_mode_xml())
+                }
+                None => return DEFAULT_BACKGROUND_XML.to_string(),
+            },
+            Background::Pattern { pattern, foreground, background } => format!(
+                r#"<a:pattFill prst="{}"><a:fgClr><a:srgbClr val="{foreground}"/></a:fgClr><a:bgClr><a:srgbClr val="{background}"/></a:bgClr></a:pattFill>"#,
+                pattern.preset_name()
+            ),
+        };
+        format!("<p:bg>\n<p:bgPr>{fill}<a:effectLst/></p:bgPr>\n</p:bg>")
+    }
+}
+
+/// Replace the `p:bg` element of a slide or master XML
+pub fn replace_background(xml: &str, background_xml: &str) -> String {
+    match (xml.find("<p:bg>"), xml.find("</p:bg>")) {
+        (Some(start), Some(end)) if start < end => {
+            format!("{}{background_xml}{}", &xml[..start], &xml[end + "</p:bg>".len()..])
+        }
+        // No background yet: it is the first child of p:cSld
+        _ => match xml.find("<p:spTree") {
+            Some(pos) => format!("{}{background_xml}\n{}", &xml[..pos], &xml[pos..]),
+            None => xml.to_string(),
+        },
+    }
+}
+
+/// Hide the master's shapes (logos, lines, ...) behind a slide (`showMasterSp="0"`)
+pub fn hide_master_shapes(xml: &str) -> String {
+    if xml.contains("showMasterSp=") {
+        return xml.to_string();
+    }
+    xml.replacen("<p:sld ", r#"<p:sld showMasterSp="0" "#, 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::gradients::GradientDirection;
+
+    const PNG_1X1: &str = "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNk+M9QDwADhgGAWjR9awAAAABJRU5ErkJggg==";
+
+    #[test]
+    fn test_background_fills() {
+        assert_eq!(
+            Background::solid("#1f4e79").to_xml(None),
+            "<p:bg>\n<p:bgPr><a:solidFill><a:srgbClr val=\"1F4E79\"/></a:solidFill><a:effectLst/></p:bgPr>\n</p:bg>"
+        );
+        assert!(Background::scheme("accent1").to_xml(None).contains(r#"<a:schemeClr val="accent1"/>"#));
+
+        let gradient = GradientFill::two_color("000000", "FFFFFF").with_direction(GradientDirection::Vertical);
+        assert!(Background::gradient(gradient).to_xml(None).contains(r#"<a:lin ang="5400000" scaled="1"/>"#));
+
+        let pattern = Background::pattern(PatternType::DiagonalCross, "FF0000", "ffffff").to_xml(None);
+        assert!(pattern.contains(r#"<a:pattFill prst="diagCross"><a:fgClr><a:srgbClr val="FF0000"/></a:fgClr><a:bgClr><a:srgbClr val="FFFFFF"/></a:bgClr></a:pattFill>"#));
+    }
+
+    #[test]
+    fn test_picture_background() {
+        let stretched = Background::picture(Image::from_base64(PNG_1X1, 100, 100, "PNG"));
+        assert!(stretched.image().is_some());
+        assert!(stretched.to_xml(Some(2)).contains(r#"<a:blip r:embed="rId2"/><a:stretch><a:fillRect/></a:stretch></a:blipFill>"#));
+        assert_eq!(stretched.to_xml(None), DEFAULT_BACKGROUND_XML);
+
+        let tiled = Background::picture(Image::from_base64(PNG_1X1, 100, 100, "PNG").with_tile(50.0, 50.0));
+        assert!(tiled.to_xml(Some(3)).contains("<a:tile "));
+
+        assert!(Background::picture(Image::new("missing.png", 100, 100, "PNG")).image().is_none());
+    }
+
+    #[test]
+    fn test_replace_background() {
+        let slide = format!("<p:sld xmlns:p=\"p\"><p:cSld>{DEFAULT_BACKGROUND_XML}<p:spTree/></p:cSld></p:sld>");
+        let xml = replace_background(&slide, &Background::solid("000000").to_xml(None));
+        assert_eq!(xml.matches("<p:bg>").count(), 1);
+        assert!(xml.contains(r#"<a:srgbClr val="000000"/>"#));
+        assert!(!xml.contains("bgRef"));
+
+        let bare = replace_background("<p:cSld><p:spTree/></p:cSld>", "<p:bg/>");
+        assert_eq!(bare, "<p:cSld><p:bg/>\n<p:spTree/></p:cSld>");
+
+        assert!(hide_master_shapes(&slide).starts_with(r#"<p:sld showMasterSp="0" xmlns:p="p">"#));
+    }
+}
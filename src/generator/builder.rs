@@ -5,7 +5,91 @@ use zip::ZipWriter;
 use zip::write::FileOptions;
 use super::xml::*;
 use super::notes_xml::*;
-use super::package_xml::{create_content_types_xml_with_notes, create_presentation_rels_xml_with_notes, create_slide_rels_xml_with_notes};
+use super::package_xml::{add_notes_master_id, create_content_types_xml_with_notes, create_presentation_rels_xml_with_notes, fit_to_slide_size, set_slide_size};
+use super::props_xml::create_core_props_xml_with_author;
+use super::images_xml::{generate_image_content_type, generate_image_relationship};
+use super::slide_rels::{SlideRelationships, slide_media, chart_file_name, chart_workbook_file_name};
+use super::charts::generate_chart_part_xml;
+use crate::opc::constants::{CONTENT_TYPE, RELATIONSHIP_TYPE};
+use crate::exc::PptxError;
+use crate::oxml::chart::EmbeddedWorkbook;
+use super::table::{generate_table_styles_xml, is_style_id};
+use super::text::TextMeasurer;
+use super::background::{replace_background, Background};
+use super::header_footer::HeaderFooter;
+use super::slide_xml::add_transition;
+use super::template::{apply_template, template_slide_size};
+use crate::parts::animation::SlideTransition;
+use crate::prelude::themes::Theme;
+
+/// Presentation-wide settings, applied to the slide master
+#[derive(Clone, Debug, Default)]
+pub struct PresentationOptions {
+    /// Master background, used by slides without their own
+    pub background: Option<Background>,
+    /// Footer, slide number and date placeholders
+    pub header_footer: Option<HeaderFooter>,
+    /// Author recorded in the document properties
+    pub author: Option<String>,
+    /// Slide width and height in EMU (None = 4:3, 9144000 x 6858000); slides
+    /// are laid out for 4:3, scaled evenly to fit and centred
+    pub slide_size: Option<(u32, u32)>,
+    /// Transition for slides without their own
+    pub transition: Option<SlideTransition>,
+    /// `.pptx` or `.potx` whose slide masters and layouts replace the
+    /// generated ones
+    pub template: Option<Vec<u8>>,
+}
+
+impl PresentationOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Options using the colours of a theme preset
+    pub fn from_theme(theme: &Theme) -> Self {
+        Self::new().background(Background::from_theme(theme))
+    }
+
+    /// Set the master background
+    pub fn background(mut self, background: Background) -> Self {
+        self.background = Some(background);
+        self
+    }
+
+    /// Set the footer, slide number and date shown on the slides
+    pub fn header_footer(mut self, header_footer: HeaderFooter) -> Self {
+        self.header_footer = Some(header_footer);
+        self
+    }
+
+    /// Set the author recorded in the document properties
+    pub fn author(mut self, author: &str) -> Self {
+        self.author = Some(author.to_string());
+        self
+    }
+
+    /// Set the slide size in EMU; the 4:3 layout is scaled by the same factor
+    /// both ways and centred, side pictures reach the slide edges
+    pub fn slide_size(mut self, width: u32, height: u32) -> Self {
+        self.slide_size = Some((width, height));
+        self
+    }
+
+    /// Set the transition used by slides without their own
+    pub fn transition(mut self, transition: SlideTransition) -> Self {
+        self.transition = Some(transition);
+        self
+    }
+
+    /// Use the slide masters, layouts and theme of a `.pptx` or `.potx` file;
+    /// without a `slide_size` the slides take the template's size. The
+    /// template's master replaces the `background` and footer placeholders.
+    pub fn template(mut self, data: Vec<u8>) -> Self {
+        self.template = Some(data);
+        self
+    }
+}
 
 /// Create a minimal but valid PPTX file
 pub fn create_pptx(title: &str, slides: usize) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
@@ -14,7 +98,7 @@ pub fn create_pptx(title: &str, slides: usize) -> Result<Vec<u8>, Box<dyn std::e
     let mut zip = ZipWriter::new(cursor);
     let options = FileOptions::default();
 
-    write_package_files(&mut zip, &options, title, slides, None)?;
+    write_package_files(&mut zip, &options, title, slides, None, &PresentationOptions::default())?;
 
     let cursor = zip.finish()?;
     Ok(cursor.into_inner())
@@ -24,16 +108,43 @@ pub fn create_pptx(title: &str, slides: usize) -> Result<Vec<u8>, Box<dyn std::e
 pub fn create_pptx_with_content(
     title: &str,
     slides: Vec<super::xml::SlideContent>,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    create_pptx_with_options(title, slides, &PresentationOptions::default())
+}
+
+/// Create a PPTX file with custom slide content and presentation-wide options
+pub fn create_pptx_with_options(
+    title: &str,
+    slides: Vec<super::xml::SlideContent>,
+    options: &PresentationOptions,
 ) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
     let buffer = Vec::new();
     let cursor = Cursor::new(buffer);
     let mut zip = ZipWriter::new(cursor);
-    let options = FileOptions::default();
+    let file_options = FileOptions::default();
 
-    write_package_files(&mut zip, &options, title, slides.len(), Some(&slides))?;
+    // Spread tables with pagination enabled over continuation slides
+    let measurer = TextMeasurer::new();
+    let slides: Vec<SlideContent> = slides
+        .into_iter()
+        .flat_map(|slide| match slide.table {
+            Some(ref table) if table.pagination.is_some() => slide.paginate_table(&measurer),
+            _ => vec![slide],
+        })
+        .collect();
 
-    let cursor = zip.finish()?;
-    Ok(cursor.into_inner())
+    // Slides are fitted to the template's size unless another is given
+    let Some(template) = options.template.as_deref() else {
+        write_package_files(&mut zip, &file_options, title, slides.len(), Some(&slides), options)?;
+        return Ok(zip.finish()?.into_inner());
+    };
+    let mut options = options.clone();
+    if options.slide_size.is_none() {
+        options.slide_size = template_slide_size(template)?;
+    }
+    write_package_files(&mut zip, &file_options, title, slides.len(), Some(&slides), &options)?;
+    let layouts: Vec<SlideLayout> = slides.iter().map(|slide| slide.layout).collect();
+    Ok(apply_template(&zip.finish()?.into_inner(), template, &layouts)?)
 }
 
 /// Write all package files to the ZIP archive
@@ -43,7 +154,10 @@ fn write_package_files(
     title: &str,
     slide_count: usize,
     custom_slides: Option<&Vec<super::xml::SlideContent>>,
+    presentation_options: &PresentationOptions,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    let master_background = presentation_options.background.as_ref();
+
     // Check if any slides have notes
     let has_notes = custom_slides
         .map(|slides| slides.iter().any(|s| s.notes.is_some()))
@@ -55,6 +169,8 @@ fn write_package_files(
     } else {
         create_content_types_xml(slide_count)
     };
+    let content_types = add_media_content_types(content_types, custom_slides, master_background);
+    let content_types = add_chart_content_types(content_types, custom_slides);
     zip.start_file("[Content_Types].xml", *options)?;
     zip.write_all(content_types.as_bytes())?;
 
@@ -72,13 +188,19 @@ fn write_package_files(
     zip.start_file("ppt/_rels/presentation.xml.rels", *options)?;
     zip.write_all(pres_rels.as_bytes())?;
 
-    // 4. Presentation document
-    let presentation = create_presentation_xml(title, slide_count);
+    // 4. Presentation document (referencing the notes master if notes present)
+    let mut presentation = create_presentation_xml(title, slide_count);
+    if has_notes {
+        presentation = add_notes_master_id(&presentation, slide_count + 3);
+    }
+    if let Some((width, height)) = presentation_options.slide_size {
+        presentation = set_slide_size(&presentation, width, height);
+    }
     zip.start_file("ppt/presentation.xml", *options)?;
     zip.write_all(presentation.as_bytes())?;
 
     // 5. Slides (and notes if present)
-    write_slides(zip, options, slide_count, custom_slides)?;
+    write_slides(zip, options, slide_count, custom_slides, presentation_options)?;
 
     // 6. Slide relationships (with notes references if present)
     write_slide_relationships_with_notes(zip, options, custom_slides)?;
@@ -99,7 +221,10 @@ fn write_package_files(
     }
 
     // 8. Slide layouts
-    let slide_layout = create_slide_layout_xml();
+    let mut slide_layout = create_slide_layout_xml();
+    if let Some((width, height)) = presentation_options.slide_size {
+        slide_layout = fit_to_slide_size(&slide_layout, width, height);
+    }
     zip.start_file("ppt/slideLayouts/slideLayout1.xml", *options)?;
     zip.write_all(slide_layout.as_bytes())?;
 
@@ -108,13 +233,33 @@ fn write_package_files(
     zip.start_file("ppt/slideLayouts/_rels/slideLayout1.xml.rels", *options)?;
     zip.write_all(layout_rels.as_bytes())?;
 
-    // 10. Slide master
-    let slide_master = create_slide_master_xml();
+    // 10. Slide master (with its background picture as rId3)
+    let mut slide_master = create_slide_master_xml();
+    let mut master_rels = create_master_rels_xml();
+    if let Some(background) = master_background {
+        let image = background.image().and_then(|image| Some((image.extension(), image.get_bytes()?)));
+        slide_master = replace_background(&slide_master, &background.to_xml(image.as_ref().map(|_| 3)));
+        if let Some((extension, data)) = image {
+            let media_path = format!("ppt/media/master_background.{extension}");
+            zip.start_file(&media_path, *options)?;
+            zip.write_all(&data)?;
+            master_rels = master_rels.replacen(
+                "</Relationships>",
+                &format!("{}\n</Relationships>", generate_image_relationship(3, &format!("../media/master_background.{extension}"))),
+                1,
+            );
+        }
+    }
+    if let Some(header_footer) = presentation_options.header_footer.as_ref() {
+        slide_master = header_footer.apply_to_master(&slide_master);
+    }
+    if let Some((width, height)) = presentation_options.slide_size {
+        slide_master = fit_to_slide_size(&slide_master, width, height);
+    }
     zip.start_file("ppt/slideMasters/slideMaster1.xml", *options)?;
     zip.write_all(slide_master.as_bytes())?;
 
     // 11. Master relationships
-    let master_rels = create_master_rels_xml();
     zip.start_file("ppt/slideMasters/_rels/slideMaster1.xml.rels", *options)?;
     zip.write_all(master_rels.as_bytes())?;
 
@@ -123,12 +268,27 @@ fn write_package_files(
     zip.start_file("ppt/theme/theme1.xml", *options)?;
     zip.write_all(theme.as_bytes())?;
 
-    // 13. Core properties
-    let core_props = create_core_props_xml(title);
+    // 13. Table styles (custom styles used by the tables)
+    let tables: Vec<_> = custom_slides.into_iter().flatten().filter_map(|slide| slide.table.as_ref()).collect();
+    let table_styles: Vec<_> = tables.iter().filter_map(|table| table.style.clone()).collect();
+    // PowerPoint drops a table whose style id is not a GUID
+    for id in tables.iter().filter_map(|table| table.style_id.as_deref()) {
+        if !is_style_id(id) {
+            return Err(PptxError::InvalidValue(format!("table style id `{id}` is not a {{GUID}}")).into());
+        }
+    }
+    zip.start_file("ppt/tableStyles.xml", *options)?;
+    zip.write_all(generate_table_styles_xml(&table_styles).as_bytes())?;
+
+    // 14. Core properties
+    let core_props = match presentation_options.author.as_deref() {
+        Some(author) => create_core_props_xml_with_author(title, author),
+        None => create_core_props_xml(title),
+    };
     zip.start_file("docProps/core.xml", *options)?;
     zip.write_all(core_props.as_bytes())?;
 
-    // 14. App properties
+    // 15. App properties
     let app_props = create_app_props_xml(slide_count);
     zip.start_file("docProps/app.xml", *options)?;
     zip.write_all(app_props.as_bytes())?;
@@ -136,24 +296,121 @@ fn write_package_files(
     Ok(())
 }
 
+/// Add `Default` content types for the media extensions used by the slides
+fn add_media_content_types(
+    content_types: String,
+    custom_slides: Option<&Vec<super::xml::SlideContent>>,
+    master_background: Option<&Background>,
+) -> String {
+    let mut extensions: Vec<String> = custom_slides
+        .into_iter()
+        .flatten()
+        .flat_map(|slide| {
+            let background = slide.background.as_ref().and_then(|bg| bg.image());
+            slide.images.iter().filter(|img| img.has_data()).chain(background)
+        })
+        .chain(master_background.and_then(|bg| bg.image()))
+        .map(|img| img.extension())
+        .collect();
+    extensions.sort();
+    extensions.dedup();
+
+    let defaults: String = extensions
+        .iter()
+        .filter(|ext| !matches!(ext.as_str(), "xml" | "rels"))
+        .map(|ext| format!("\n{}", generate_image_content_type(ext)))
+        .collect();
+    let anchor = r#"<Default Extension="xml" ContentType="application/xml"/>"#;
+    content_types.replacen(anchor, &format!("{anchor}{defaults}"), 1)
+}
+
+/// Add content types for the slides' chart parts and their data workbooks
+fn add_chart_content_types(content_types: String, custom_slides: Option<&Vec<super::xml::SlideContent>>) -> String {
+    let overrides: String = custom_slides
+        .into_iter()
+        .flatten()
+        .enumerate()
+        .flat_map(|(i, slide)| (0..slide.charts.len()).map(move |c| chart_file_name(i + 1, c)))
+        .map(|name| format!("\n<Override PartName=\"/ppt/charts/{name}\" ContentType=\"{}\"/>", CONTENT_TYPE::DML_CHART))
+        .collect();
+    if overrides.is_empty() {
+        return content_types;
+    }
+    let anchor = r#"<Default Extension="xml" ContentType="application/xml"/>"#;
+    let workbook = format!("\n<Default Extension=\"xlsx\" ContentType=\"{}\"/>", CONTENT_TYPE::SML_SHEET);
+    content_types
+        .replacen(anchor, &format!("{anchor}{workbook}"), 1)
+        .replacen("</Types>", &format!("{overrides}\n</Types>"), 1)
+}
+
+/// Write a slide's chart parts, each with the workbook holding its data
+fn write_slide_charts(
+    zip: &mut ZipWriter<Cursor<Vec<u8>>>,
+    options: &FileOptions,
+    slide_num: usize,
+    slide: &super::xml::SlideContent,
+) -> Result<(), Box<dyn std::error::Error>> {
+    for (i, chart) in slide.charts.iter().enumerate() {
+        let chart_name = chart_file_name(slide_num, i);
+        let workbook_name = chart_workbook_file_name(slide_num, i);
+
+        zip.start_file(format!("ppt/charts/{chart_name}"), *options)?;
+        zip.write_all(generate_chart_part_xml(chart, Some("rId1")).as_bytes())?;
+
+        let chart_rels = format!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+<Relationship Id="rId1" Type="{}" Target="../embeddings/{workbook_name}"/>
+</Relationships>"#,
+            RELATIONSHIP_TYPE::PACKAGE
+        );
+        zip.start_file(format!("ppt/charts/_rels/{chart_name}.rels"), *options)?;
+        zip.write_all(chart_rels.as_bytes())?;
+
+        zip.start_file(format!("ppt/embeddings/{workbook_name}"), *options)?;
+        zip.write_all(&EmbeddedWorkbook::for_chart(chart)?.to_bytes()?)?;
+    }
+    Ok(())
+}
+
 /// Write slide XML files
 fn write_slides(
     zip: &mut ZipWriter<Cursor<Vec<u8>>>,
     options: &FileOptions,
     slide_count: usize,
     custom_slides: Option<&Vec<super::xml::SlideContent>>,
+    presentation_options: &PresentationOptions,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    let header_footer = presentation_options.header_footer.as_ref();
     match custom_slides {
         Some(slides) => {
             for (i, slide) in slides.iter().enumerate() {
                 let slide_num = i + 1;
-                let slide_xml = create_slide_xml_with_content(slide_num, slide);
+                let mut slide_xml = create_slide_xml_with_content(slide_num, slide);
+                if let Some(hf) = header_footer.filter(|hf| hf.applies_to(slide)) {
+                    slide_xml = hf.apply_to_slide(&slide_xml, slide_num);
+                }
+                if let Some(transition) = presentation_options.transition.as_ref().filter(|_| slide.transition.is_none()) {
+                    slide_xml = add_transition(&slide_xml, transition);
+                }
+                if let Some((width, height)) = presentation_options.slide_size {
+                    slide_xml = fit_to_slide_size(&slide_xml, width, height);
+                }
                 zip.start_file(format!("ppt/slides/slide{slide_num}.xml"), *options)?;
                 zip.write_all(slide_xml.as_bytes())?;
                 
+                // Write embedded media
+                for media in slide_media(slide_num, slide) {
+                    zip.start_file(&media.path, *options)?;
+                    zip.write_all(&media.data)?;
+                }
+
+                // Write chart parts
+                write_slide_charts(zip, options, slide_num, slide)?;
+                
                 // Write notes if present
-                if let Some(notes) = &slide.notes {
-                    let notes_xml = create_notes_xml(slide_num, notes);
+                if let Some(notes) = slide.notes_frame() {
+                    let notes_xml = create_rich_notes_xml(slide_num, &notes);
                     zip.start_file(format!("ppt/notesSlides/notesSlide{slide_num}.xml"), *options)?;
                     zip.write_all(notes_xml.as_bytes())?;
                 }
@@ -161,7 +418,10 @@ fn write_slides(
         }
         None => {
             for i in 1..=slide_count {
-                let slide_xml = create_slide_xml(i, "Presentation");
+                let mut slide_xml = create_slide_xml(i, "Presentation");
+                if let Some((width, height)) = presentation_options.slide_size {
+                    slide_xml = fit_to_slide_size(&slide_xml, width, height);
+                }
                 zip.start_file(format!("ppt/slides/slide{i}.xml"), *options)?;
                 zip.write_all(slide_xml.as_bytes())?;
             }
@@ -195,11 +455,7 @@ fn write_slide_relationships_with_notes(
         Some(slides) => {
             for (i, slide) in slides.iter().enumerate() {
                 let slide_num = i + 1;
-                let slide_rels = if slide.notes.is_some() {
-                    create_slide_rels_xml_with_notes(slide_num)
-                } else {
-                    create_slide_rels_xml()
-                };
+                let slide_rels = SlideRelationships::for_slide(slide_num, slide).to_xml();
                 zip.start_file(format!("ppt/slides/_rels/slide{slide_num}.xml.rels"), *options)?;
                 zip.write_all(slide_rels.as_bytes())?;
             }
@@ -219,9 +475,9 @@ fn write_notes_relationships(
 ) -> Result<(), Box<dyn std::error::Error>> {
     if let Some(slides) = custom_slides {
         for (i, slide) in slides.iter().enumerate() {
-            if slide.notes.is_some() {
+            if let Some(notes) = slide.notes_frame() {
                 let slide_num = i + 1;
-                let notes_rels = create_notes_rels_xml(slide_num);
+                let notes_rels = create_rich_notes_rels_xml(slide_num, &notes);
                 zip.start_file(format!("ppt/notesSlides/_rels/notesSlide{slide_num}.xml.rels"), *options)?;
                 zip.write_all(notes_rels.as_bytes())?;
             }
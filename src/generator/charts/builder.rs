@@ -1,7 +1,7 @@
 //! Chart builder for fluent API
 
 use super::types::ChartType;
-use super::data::{Chart, ChartSeries};
+use super::data::{Chart, ChartSeries, ValueAxis};
 
 /// Chart builder for fluent API
 pub struct ChartBuilder {
@@ -67,6 +67,9 @@ impl ChartBuilder {
             y: self.y,
             width: self.width,
             height: self.height,
+            value_axis: ValueAxis::default(),
+            reverse_categories: false,
+            show_legend: true,
         }
     }
 }
@@ -7,6 +7,12 @@ use super::types::ChartType;
 pub struct ChartSeries {
     pub name: String,
     pub values: Vec<f64>,
+    /// Fill colour of the series (RGB hex), theme colour if unset
+    pub color: Option<String>,
+    /// Fill colours of individual data points, overriding the series colour
+    pub point_colors: Vec<Option<String>>,
+    /// Draw the series without fill or line and leave it out of the legend
+    pub hidden: bool,
 }
 
 impl ChartSeries {
@@ -15,9 +21,33 @@ impl ChartSeries {
         ChartSeries {
             name: name.to_string(),
             values,
+            color: None,
+            point_colors: Vec::new(),
+            hidden: false,
         }
     }
 
+    /// Set the fill colour (RGB hex, with or without `#`)
+    pub fn with_color(mut self, color: &str) -> Self {
+        self.color = Some(color.trim_start_matches('#').to_uppercase());
+        self
+    }
+
+    /// Set the fill colour of one data point
+    pub fn with_point_color(mut self, index: usize, color: &str) -> Self {
+        if self.point_colors.len() <= index {
+            self.point_colors.resize(index + 1, None);
+        }
+        self.point_colors[index] = Some(color.trim_start_matches('#').to_uppercase());
+        self
+    }
+
+    /// Hide the series (e.g. the offsets of a stacked timeline)
+    pub fn hidden(mut self) -> Self {
+        self.hidden = true;
+        self
+    }
+
     /// Get the number of data points
     pub fn len(&self) -> usize {
         self.values.len()
@@ -29,6 +59,17 @@ impl ChartSeries {
     }
 }
 
+/// Value axis scaling and number format
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ValueAxis {
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    /// Distance between major tick marks and gridlines
+    pub major_unit: Option<f64>,
+    /// Number format code, e.g. `yyyy-mm-dd` for values holding serial dates
+    pub number_format: Option<String>,
+}
+
 /// Chart definition
 #[derive(Clone, Debug)]
 pub struct Chart {
@@ -40,6 +81,11 @@ pub struct Chart {
     pub y: u32,      // Position Y in EMU
     pub width: u32,  // Width in EMU
     pub height: u32, // Height in EMU
+    /// Value axis settings
+    pub value_axis: ValueAxis,
+    /// List categories from the top (horizontal bars) instead of the bottom
+    pub reverse_categories: bool,
+    pub show_legend: bool,
 }
 
 impl Chart {
@@ -62,6 +108,9 @@ impl Chart {
             y,
             width,
             height,
+            value_axis: ValueAxis::default(),
+            reverse_categories: false,
+            show_legend: true,
         }
     }
 
@@ -71,6 +120,24 @@ impl Chart {
         self
     }
 
+    /// Set the value axis scaling and number format
+    pub fn with_value_axis(mut self, axis: ValueAxis) -> Self {
+        self.value_axis = axis;
+        self
+    }
+
+    /// List the first category at the top of a horizontal bar chart
+    pub fn reverse_categories(mut self) -> Self {
+        self.reverse_categories = true;
+        self
+    }
+
+    /// Show or hide the legend
+    pub fn legend(mut self, show: bool) -> Self {
+        self.show_legend = show;
+        self
+    }
+
     /// Get number of categories
     pub fn category_count(&self) -> usize {
         self.categories.len()
@@ -5,16 +5,19 @@
 //! - `data` - Chart data structures (Series, Chart)
 //! - `builder` - Fluent chart builder
 //! - `xml` - XML generation for charts
+//! - `part` - Chart parts and the slide frames referencing them
 
 mod types;
 mod data;
 mod builder;
 mod xml;
+mod part;
 
 pub use types::ChartType;
-pub use data::{Chart, ChartSeries};
+pub use data::{Chart, ChartSeries, ValueAxis};
 pub use builder::ChartBuilder;
 pub use xml::generate_chart_xml;
+pub use part::{generate_chart_part_xml, generate_chart_frame_xml};
 
 /// Escape XML special characters
 pub(crate) fn escape_xml(s: &str) -> String {
@@ -0,0 +1,434 @@
+//! Chart parts (`ppt/charts/chartN.xml`) and the slide frames referencing them
+//!
+//! Series formulas point into the embedded workbook in the layout written by
+//! `EmbeddedWorkbook::write_chart_data`: categories in column A from row 2 and
+//! one column per series from column B, with the series names in row 1.
+
+use super::data::{Chart, ChartSeries};
+use super::escape_xml;
+use super::types::ChartType;
+use crate::oxml::chart::workbook::column_name;
+
+const CATEGORY_AXIS_ID: u32 = 500_000_001;
+const VALUE_AXIS_ID: u32 = 500_000_002;
+
+/// How a series is drawn, which decides its child elements
+#[derive(Clone, Copy, PartialEq)]
+enum SeriesStyle {
+    Bar,
+    Line { markers: bool },
+    Area,
+    Pie,
+    Radar { filled: bool },
+    Scatter { lines: bool, smooth: bool },
+    Bubble,
+    Stock,
+}
+
+impl SeriesStyle {
+    fn is_filled(self) -> bool {
+        matches!(self, SeriesStyle::Bar | SeriesStyle::Area | SeriesStyle::Pie | SeriesStyle::Bubble | SeriesStyle::Radar { filled: true })
+    }
+
+    fn has_marker(self) -> bool {
+        matches!(self, SeriesStyle::Line { .. } | SeriesStyle::Radar { .. } | SeriesStyle::Scatter { .. } | SeriesStyle::Stock)
+    }
+
+    fn uses_xy(self) -> bool {
+        matches!(self, SeriesStyle::Scatter { .. } | SeriesStyle::Bubble)
+    }
+}
+
+/// Generate the chart part XML
+///
+/// `workbook_rel_id` is the relationship of the embedded workbook holding the
+/// data, which lets PowerPoint edit it.
+pub fn generate_chart_part_xml(chart: &Chart, workbook_rel_id: Option<&str>) -> String {
+    let mut xml = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<c:chartSpace xmlns:c="http://schemas.openxmlformats.org/drawingml/2006/chart" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">
+<c:date1904 val="0"/>
+<c:roundedCorners val="0"/>
+<c:chart>"#,
+    );
+
+    if chart.title.is_empty() {
+        xml.push_str(r#"<c:autoTitleDeleted val="1"/>"#);
+    } else {
+        xml.push_str(&format!(
+            r#"<c:title><c:tx><c:rich><a:bodyPr/><a:lstStyle/><a:p><a:pPr><a:defRPr sz="1800" b="0"/></a:pPr><a:r><a:rPr lang="en-US" sz="1800" b="0"/><a:t>{}</a:t></a:r></a:p></c:rich></c:tx><c:overlay val="0"/></c:title><c:autoTitleDeleted val="0"/>"#,
+            escape_xml(&chart.title)
+        ));
+    }
+
+    xml.push_str("<c:plotArea><c:layout/>");
+    xml.push_str(&plot_area_xml(chart));
+    xml.push_str("</c:plotArea>");
+
+    if chart.show_legend {
+        xml.push_str(r#"<c:legend><c:legendPos val="r"/>"#);
+        if !matches!(chart.chart_type, ChartType::Pie | ChartType::Doughnut) {
+            for (i, _) in chart.series.iter().enumerate().filter(|(_, s)| s.hidden) {
+                xml.push_str(&format!(r#"<c:legendEntry><c:idx val="{i}"/><c:delete val="1"/></c:legendEntry>"#));
+            }
+        }
+        xml.push_str(r#"<c:overlay val="0"/></c:legend>"#);
+    }
+    xml.push_str(r#"<c:plotVisOnly val="1"/><c:dispBlanksAs val="gap"/></c:chart>"#);
+
+    if let Some(rel_id) = workbook_rel_id {
+        xml.push_str(&format!(r#"<c:externalData r:id="{rel_id}"><c:autoUpdate val="0"/></c:externalData>"#));
+    }
+    xml.push_str("</c:chartSpace>");
+    xml
+}
+
+/// Generate the slide `p:graphicFrame` showing a chart part
+pub fn generate_chart_frame_xml(chart: &Chart, shape_id: usize, rel_id: usize) -> String {
+    let name = if chart.title.is_empty() {
+        format!("Chart {shape_id}")
+    } else {
+        escape_xml(&chart.title)
+    };
+    format!(
+        r#"<p:graphicFrame>
+<p:nvGraphicFramePr>
+<p:cNvPr id="{shape_id}" name="{name}"/>
+<p:cNvGraphicFramePr><a:graphicFrameLocks noGrp="1"/></p:cNvGraphicFramePr>
+<p:nvPr/>
+</p:nvGraphicFramePr>
+<p:xfrm>
+<a:off x="{}" y="{}"/>
+<a:ext cx="{}" cy="{}"/>
+</p:xfrm>
+<a:graphic>
+<a:graphicData uri="http://schemas.openxmlformats.org/drawingml/2006/chart">
+<c:chart xmlns:c="http://schemas.openxmlformats.org/drawingml/2006/chart" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" r:id="rId{rel_id}"/>
+</a:graphicData>
+</a:graphic>
+</p:graphicFrame>"#,
+        chart.x, chart.y, chart.width, chart.height
+    )
+}
+
+/// Plot groups and axes of the plot area
+fn plot_area_xml(chart: &Chart) -> String {
+    let axis_ids = format!(r#"<c:axId val="{CATEGORY_AXIS_ID}"/><c:axId val="{VALUE_AXIS_ID}"/>"#);
+    let all: Vec<(usize, &ChartSeries)> = chart.series.iter().enumerate().collect();
+    let series = |style: SeriesStyle, items: &[(usize, &ChartSeries)]| -> String {
+        items.iter().map(|(i, s)| series_xml(chart, *i, s, style)).collect()
+    };
+
+    match chart.chart_type {
+        ChartType::Bar | ChartType::BarHorizontal | ChartType::BarHorizontalStacked
+        | ChartType::BarStacked | ChartType::BarStacked100 => {
+            let horizontal = chart.chart_type.bar_direction() == Some("bar");
+            let mut xml = bar_chart_xml(chart, &series(SeriesStyle::Bar, &all), &axis_ids);
+            xml.push_str(&axes_xml(chart, horizontal));
+            xml
+        }
+        ChartType::Line | ChartType::LineMarkers | ChartType::LineStacked => {
+            let style = SeriesStyle::Line { markers: chart.chart_type == ChartType::LineMarkers };
+            let grouping = chart.chart_type.grouping().unwrap_or("standard");
+            format!(
+                r#"<c:lineChart><c:grouping val="{grouping}"/><c:varyColors val="0"/>{}<c:marker val="1"/>{axis_ids}</c:lineChart>{}"#,
+                series(style, &all),
+                axes_xml(chart, false)
+            )
+        }
+        ChartType::Area | ChartType::AreaStacked | ChartType::AreaStacked100 => {
+            let grouping = chart.chart_type.grouping().unwrap_or("standard");
+            format!(
+                r#"<c:areaChart><c:grouping val="{grouping}"/><c:varyColors val="0"/>{}{axis_ids}</c:areaChart>{}"#,
+                series(SeriesStyle::Area, &all),
+                axes_xml(chart, false)
+            )
+        }
+        ChartType::Pie => format!(
+            r#"<c:pieChart><c:varyColors val="1"/>{}<c:firstSliceAng val="0"/></c:pieChart>"#,
+            series(SeriesStyle::Pie, &all[..all.len().min(1)])
+        ),
+        ChartType::Doughnut => format!(
+            r#"<c:doughnutChart><c:varyColors val="1"/>{}<c:firstSliceAng val="0"/><c:holeSize val="50"/></c:doughnutChart>"#,
+            series(SeriesStyle::Pie, &all)
+        ),
+        ChartType::Radar | ChartType::RadarFilled => {
+            let filled = chart.chart_type == ChartType::RadarFilled;
+            format!(
+                r#"<c:radarChart><c:radarStyle val="{}"/><c:varyColors val="0"/>{}{axis_ids}</c:radarChart>{}"#,
+                chart.chart_type.radar_style().unwrap_or("marker"),
+                series(SeriesStyle::Radar { filled }, &all),
+                axes_xml(chart, false)
+            )
+        }
+        ChartType::Scatter | ChartType::ScatterLines | ChartType::ScatterSmooth => {
+            let style = SeriesStyle::Scatter {
+                lines: chart.chart_type != ChartType::Scatter,
+                smooth: chart.chart_type.is_smooth(),
+            };
+            format!(
+                r#"<c:scatterChart><c:scatterStyle val="lineMarker"/><c:varyColors val="0"/>{}{axis_ids}</c:scatterChart>{}"#,
+                series(style, &all),
+                xy_axes_xml(chart)
+            )
+        }
+        ChartType::Bubble => format!(
+            r#"<c:bubbleChart><c:varyColors val="0"/>{}<c:bubbleScale val="100"/>{axis_ids}</c:bubbleChart>{}"#,
+            series(SeriesStyle::Bubble, &all),
+            xy_axes_xml(chart)
+        ),
+        ChartType::StockHLC | ChartType::StockOHLC => {
+            let up_down = if chart.chart_type == ChartType::StockOHLC {
+                r#"<c:upDownBars><c:gapWidth val="150"/><c:upBars/><c:downBars/></c:upDownBars>"#
+            } else {
+                ""
+            };
+            format!(
+                r#"<c:stockChart>{}<c:hiLowLines/>{up_down}{axis_ids}</c:stockChart>{}"#,
+                series(SeriesStyle::Stock, &all),
+                axes_xml(chart, false)
+            )
+        }
+        ChartType::Combo => {
+            // First series as columns, the others as lines on the same axes
+            let (bars, lines) = all.split_at(all.len().min(1));
+            let mut xml = bar_chart_xml(chart, &series(SeriesStyle::Bar, bars), &axis_ids);
+            if !lines.is_empty() {
+                xml.push_str(&format!(
+                    r#"<c:lineChart><c:grouping val="standard"/><c:varyColors val="0"/>{}<c:marker val="1"/>{axis_ids}</c:lineChart>"#,
+                    series(SeriesStyle::Line { markers: true }, lines)
+                ));
+            }
+            xml.push_str(&axes_xml(chart, false));
+            xml
+        }
+    }
+}
+
+fn bar_chart_xml(chart: &Chart, series: &str, axis_ids: &str) -> String {
+    let grouping = chart.chart_type.grouping().unwrap_or("clustered");
+    let overlap = if grouping == "clustered" { "" } else { r#"<c:overlap val="100"/>"# };
+    format!(
+        r#"<c:barChart><c:barDir val="{}"/><c:grouping val="{grouping}"/><c:varyColors val="0"/>{series}<c:gapWidth val="{}"/>{overlap}{axis_ids}</c:barChart>"#,
+        chart.chart_type.bar_direction().unwrap_or("col"),
+        if grouping == "clustered" { 150 } else { 60 },
+    )
+}
+
+/// Category and value axes; horizontal bars put the categories on the left
+fn axes_xml(chart: &Chart, horizontal: bool) -> String {
+    let (category_pos, value_pos) = if horizontal { ("l", "b") } else { ("b", "l") };
+    let orientation = if chart.reverse_categories { "maxMin" } else { "minMax" };
+    // With reversed categories the value axis stays at the bottom by crossing at the last one
+    let crosses = if chart.reverse_categories { "max" } else { "autoZero" };
+    format!(
+        r#"<c:catAx><c:axId val="{CATEGORY_AXIS_ID}"/><c:scaling><c:orientation val="{orientation}"/></c:scaling><c:delete val="0"/><c:axPos val="{category_pos}"/><c:numFmt formatCode="General" sourceLinked="1"/><c:majorTickMark val="out"/><c:minorTickMark val="none"/><c:tickLblPos val="nextTo"/><c:crossAx val="{VALUE_AXIS_ID}"/><c:crosses val="autoZero"/><c:auto val="1"/><c:lblAlgn val="ctr"/><c:lblOffset val="100"/><c:noMultiLvlLbl val="0"/></c:catAx>{}"#,
+        value_axis_xml(chart, VALUE_AXIS_ID, CATEGORY_AXIS_ID, value_pos, crosses)
+    )
+}
+
+/// Two value axes for scatter and bubble charts
+fn xy_axes_xml(chart: &Chart) -> String {
+    let x_axis = format!(
+        r#"<c:valAx><c:axId val="{CATEGORY_AXIS_ID}"/><c:scaling><c:orientation val="minMax"/></c:scaling><c:delete val="0"/><c:axPos val="b"/><c:numFmt formatCode="General" sourceLinked="1"/><c:majorTickMark val="out"/><c:minorTickMark val="none"/><c:tickLblPos val="nextTo"/><c:crossAx val="{VALUE_AXIS_ID}"/><c:crosses val="autoZero"/><c:crossBetween val="midCat"/></c:valAx>"#
+    );
+    x_axis + &value_axis_xml(chart, VALUE_AXIS_ID, CATEGORY_AXIS_ID, "l", "autoZero")
+}
+
+fn value_axis_xml(chart: &Chart, id: u32, cross_id: u32, position: &str, crosses: &str) -> String {
+    let axis = &chart.value_axis;
+    let max = axis.max.map(|v| format!(r#"<c:max val="{v}"/>"#)).unwrap_or_default();
+    let min = axis.min.map(|v| format!(r#"<c:min val="{v}"/>"#)).unwrap_or_default();
+    let number_format = match axis.number_format {
+        Some(ref code) => format!(r#"<c:numFmt formatCode="{}" sourceLinked="0"/>"#, escape_xml(code)),
+        None => r#"<c:numFmt formatCode="General" sourceLinked="1"/>"#.to_string(),
+    };
+    let major_unit = axis.major_unit.map(|v| format!(r#"<c:majorUnit val="{v}"/>"#)).unwrap_or_default();
+    format!(
+        r#"<c:valAx><c:axId val="{id}"/><c:scaling><c:orientation val="minMax"/>{max}{min}</c:scaling><c:delete val="0"/><c:axPos val="{position}"/><c:majorGridlines/>{number_format}<c:majorTickMark val="out"/><c:minorTickMark val="none"/><c:tickLblPos val="nextTo"/><c:crossAx val="{cross_id}"/><c:crosses val="{crosses}"/><c:crossBetween val="between"/>{major_unit}</c:valAx>"#
+    )
+}
+
+fn solid_fill(color: &str) -> String {
+    format!(r#"<a:solidFill><a:srgbClr val="{color}"/></a:solidFill>"#)
+}
+
+/// Series `c:spPr`: fill for filled styles, line for line styles
+fn series_shape_xml(series: &ChartSeries, style: SeriesStyle) -> String {
+    if series.hidden {
+        return if style.is_filled() {
+            r#"<c:spPr><a:noFill/><a:ln><a:noFill/></a:ln></c:spPr>"#.to_string()
+        } else {
+            r#"<c:spPr><a:ln><a:noFill/></a:ln></c:spPr>"#.to_string()
+        };
+    }
+    let no_line = matches!(style, SeriesStyle::Stock | SeriesStyle::Scatter { lines: false, .. });
+    match (&series.color, style.is_filled()) {
+        _ if no_line => r#"<c:spPr><a:ln w="19050"><a:noFill/></a:ln></c:spPr>"#.to_string(),
+        (Some(color), true) => format!("<c:spPr>{}</c:spPr>", solid_fill(color)),
+        (Some(color), false) => format!(r#"<c:spPr><a:ln w="28575" cap="rnd">{}</a:ln></c:spPr>"#, solid_fill(color)),
+        (None, _) => String::new(),
+    }
+}
+
+fn series_xml(chart: &Chart, index: usize, series: &ChartSeries, style: SeriesStyle) -> String {
+    let column = column_name(index as u32 + 2);
+    let rows = series.values.len().max(chart.categories.len());
+    let last_row = rows + 1;
+
+    let mut xml = format!(
+        r#"<c:ser><c:idx val="{index}"/><c:order val="{index}"/><c:tx><c:strRef><c:f>Sheet1!${column}$1</c:f><c:strCache><c:ptCount val="1"/><c:pt idx="0"><c:v>{}</c:v></c:pt></c:strCache></c:strRef></c:tx>"#,
+        escape_xml(&series.name)
+    );
+    xml.push_str(&series_shape_xml(series, style));
+
+    if matches!(style, SeriesStyle::Bar | SeriesStyle::Bubble) {
+        xml.push_str(r#"<c:invertIfNegative val="0"/>"#);
+    }
+    if style.has_marker() {
+        let hide_marker = series.hidden
+            || matches!(style, SeriesStyle::Line { markers: false } | SeriesStyle::Stock | SeriesStyle::Radar { filled: true });
+        if hide_marker {
+            xml.push_str(r#"<c:marker><c:symbol val="none"/></c:marker>"#);
+        }
+    }
+
+    if !series.hidden {
+        for (i, color) in series.point_colors.iter().enumerate() {
+            let Some(color) = color else { continue };
+            let flag = if style == SeriesStyle::Bar { r#"<c:invertIfNegative val="0"/>"# } else { r#"<c:bubble3D val="0"/>"# };
+            xml.push_str(&format!(r#"<c:dPt><c:idx val="{i}"/>{flag}<c:spPr>{}</c:spPr></c:dPt>"#, solid_fill(color)));
+        }
+    }
+
+    if style == SeriesStyle::Pie {
+        xml.push_str(r#"<c:dLbls><c:showLegendKey val="0"/><c:showVal val="0"/><c:showCatName val="0"/><c:showSerName val="0"/><c:showPercent val="1"/><c:showBubbleSize val="0"/><c:showLeaderLines val="1"/></c:dLbls>"#);
+    }
+
+    let values = number_cache(&series.values, &format!("Sheet1!${column}$2:${column}${last_row}"));
+    if style.uses_xy() {
+        // X values come from the categories, numbered when they are not numbers
+        let x_values: Vec<f64> = (0..series.values.len())
+            .map(|i| chart.categories.get(i).and_then(|c| c.trim().parse().ok()).unwrap_or(i as f64 + 1.0))
+            .collect();
+        xml.push_str(&format!("<c:xVal>{}</c:xVal>", number_cache(&x_values, &format!("Sheet1!$A$2:$A${last_row}"))));
+        xml.push_str(&format!("<c:yVal>{values}</c:yVal>"));
+    } else {
+        if !chart.categories.is_empty() {
+            xml.push_str(&format!(
+                "<c:cat>{}</c:cat>",
+                string_cache(&chart.categories, &format!("Sheet1!$A$2:$A${}", chart.categories.len() + 1))
+            ));
+        }
+        xml.push_str(&format!("<c:val>{values}</c:val>"));
+    }
+
+    match style {
+        SeriesStyle::Line { .. } | SeriesStyle::Stock => xml.push_str(r#"<c:smooth val="0"/>"#),
+        SeriesStyle::Scatter { smooth, .. } => xml.push_str(&format!(r#"<c:smooth val="{}"/>"#, u8::from(smooth))),
+        SeriesStyle::Bubble => {
+            let sizes = vec![1.0; series.values.len()];
+            xml.push_str(&format!("<c:bubbleSize>{}</c:bubbleSize>", number_literal(&sizes)));
+            xml.push_str(r#"<c:bubble3D val="0"/>"#);
+        }
+        _ => {}
+    }
+    xml.push_str("</c:ser>");
+    xml
+}
+
+fn number_points(values: &[f64]) -> String {
+    let points: String = values
+        .iter()
+        .enumerate()
+        .map(|(i, v)| format!(r#"<c:pt idx="{i}"><c:v>{v}</c:v></c:pt>"#))
+        .collect();
+    format!(r#"<c:formatCode>General</c:formatCode><c:ptCount val="{}"/>{points}"#, values.len())
+}
+
+fn number_cache(values: &[f64], formula: &str) -> String {
+    format!("<c:numRef><c:f>{formula}</c:f><c:numCache>{}</c:numCache></c:numRef>", number_points(values))
+}
+
+fn number_literal(values: &[f64]) -> String {
+    format!("<c:numLit>{}</c:numLit>", number_points(values))
+}
+
+fn string_cache(values: &[String], formula: &str) -> String {
+    let points: String = values
+        .iter()
+        .enumerate()
+        .map(|(i, v)| format!(r#"<c:pt idx="{i}"><c:v>{}</c:v></c:pt>"#, escape_xml(v)))
+        .collect();
+    format!(
+        r#"<c:strRef><c:f>{formula}</c:f><c:strCache><c:ptCount val="{}"/>{points}</c:strCache></c:strRef>"#,
+        values.len()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::charts::{ChartBuilder, ValueAxis};
+    use crate::oxml::chart::ChartParser;
+
+    #[test]
+    fn test_chart_part_round_trip() {
+        let chart = ChartBuilder::new("Sales & Costs", ChartType::BarStacked)
+            .categories(vec!["Q1", "Q2", "Q3"])
+            .add_series(ChartSeries::new("Sales", vec![10.0, 20.0, 30.0]).with_color("#4472c4"))
+            .add_series(ChartSeries::new("Costs", vec![5.0, 8.0, 12.5]))
+            .build();
+        let xml = generate_chart_part_xml(&chart, Some("rId1"));
+
+        assert!(xml.contains(r#"<c:barDir val="col"/><c:grouping val="stacked"/>"#));
+        assert!(xml.contains(r#"<c:overlap val="100"/>"#));
+        assert!(xml.contains(r#"<c:f>Sheet1!$C$2:$C$4</c:f>"#));
+        assert!(xml.contains(r#"<a:srgbClr val="4472C4"/>"#));
+        assert!(xml.contains(r#"<c:externalData r:id="rId1">"#));
+
+        let parsed = ChartParser::parse(&xml, None).unwrap();
+        assert_eq!(parsed.title, "Sales & Costs");
+        assert_eq!(parsed.chart_type, ChartType::BarStacked);
+        assert_eq!(parsed.categories, vec!["Q1", "Q2", "Q3"]);
+        assert_eq!(parsed.series[1].name, "Costs");
+        assert_eq!(parsed.series[1].values, vec![5.0, 8.0, 12.5]);
+    }
+
+    #[test]
+    fn test_timeline_chart_part() {
+        let chart = Chart::new("Plan", ChartType::BarHorizontalStacked, vec!["Design".into(), "Build".into()], 0, 0, 100, 100)
+            .add_series(ChartSeries::new("Start", vec![45292.0, 45300.0]).hidden())
+            .add_series(ChartSeries::new("Phase 1", vec![8.0, 0.0]).with_point_color(1, "E53935"))
+            .with_value_axis(ValueAxis { min: Some(45292.0), number_format: Some("yyyy-mm-dd".into()), ..Default::default() })
+            .reverse_categories();
+        let xml = generate_chart_part_xml(&chart, None);
+
+        assert!(xml.contains(r#"<c:barDir val="bar"/><c:grouping val="stacked"/>"#));
+        assert!(xml.contains(r#"<c:spPr><a:noFill/><a:ln><a:noFill/></a:ln></c:spPr>"#));
+        assert!(xml.contains(r#"<c:legendEntry><c:idx val="0"/><c:delete val="1"/></c:legendEntry>"#));
+        assert!(xml.contains(r#"<c:dPt><c:idx val="1"/>"#));
+        assert!(xml.contains(r#"<c:orientation val="maxMin"/>"#));
+        assert!(xml.contains(r#"<c:min val="45292"/>"#));
+        assert!(xml.contains(r#"<c:numFmt formatCode="yyyy-mm-dd" sourceLinked="0"/>"#));
+        assert!(!xml.contains("externalData"));
+        assert_eq!(ChartParser::parse(&xml, None).unwrap().chart_type, ChartType::BarHorizontalStacked);
+    }
+
+    #[test]
+    fn test_pie_chart_part_and_frame() {
+        let chart = ChartBuilder::new("", ChartType::Pie)
+            .categories(vec!["Dogs", "Cats"])
+            .add_series(ChartSeries::new("Pets", vec![45.0, 30.0]))
+            .build();
+        let xml = generate_chart_part_xml(&chart, None);
+        assert!(xml.contains(r#"<c:autoTitleDeleted val="1"/>"#));
+        assert!(xml.contains(r#"<c:pieChart><c:varyColors val="1"/>"#));
+        assert!(xml.contains(r#"<c:showPercent val="1"/>"#));
+        assert!(!xml.contains("<c:catAx>"));
+
+        let frame = generate_chart_frame_xml(&chart, 7, 3);
+        assert!(frame.contains(r#"<p:cNvPr id="7" name="Chart 7"/>"#));
+        assert!(frame.contains(r#"r:id="rId3"/>"#));
+    }
+}
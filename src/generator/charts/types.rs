@@ -7,6 +7,8 @@ pub enum ChartType {
     Bar,
     /// Horizontal bar chart
     BarHorizontal,
+    /// Stacked horizontal bar chart (timelines, Gantt charts)
+    BarHorizontalStacked,
     /// Stacked bar chart
     BarStacked,
     /// 100% stacked bar chart
@@ -53,6 +55,7 @@ impl ChartType {
         match self {
             ChartType::Bar => "bar",
             ChartType::BarHorizontal => "barHorizontal",
+            ChartType::BarHorizontalStacked => "barHorizontalStacked",
             ChartType::BarStacked => "barStacked",
             ChartType::BarStacked100 => "barStacked100",
             ChartType::Line => "line",
@@ -79,7 +82,7 @@ impl ChartType {
     pub fn xml_element(&self) -> &str {
         match self {
             ChartType::Bar | ChartType::BarStacked | ChartType::BarStacked100 => "c:barChart",
-            ChartType::BarHorizontal => "c:barChart",
+            ChartType::BarHorizontal | ChartType::BarHorizontalStacked => "c:barChart",
             ChartType::Line | ChartType::LineMarkers | ChartType::LineStacked => "c:lineChart",
             ChartType::Pie => "c:pieChart",
             ChartType::Doughnut => "c:doughnutChart",
@@ -96,7 +99,7 @@ impl ChartType {
     pub fn bar_direction(&self) -> Option<&str> {
         match self {
             ChartType::Bar | ChartType::BarStacked | ChartType::BarStacked100 => Some("col"),
-            ChartType::BarHorizontal => Some("bar"),
+            ChartType::BarHorizontal | ChartType::BarHorizontalStacked => Some("bar"),
             _ => None,
         }
     }
@@ -105,7 +108,7 @@ impl ChartType {
     pub fn grouping(&self) -> Option<&str> {
         match self {
             ChartType::Bar | ChartType::BarHorizontal => Some("clustered"),
-            ChartType::BarStacked | ChartType::LineStacked | ChartType::AreaStacked => Some("stacked"),
+            ChartType::BarStacked | ChartType::BarHorizontalStacked | ChartType::LineStacked | ChartType::AreaStacked => Some("stacked"),
             ChartType::BarStacked100 | ChartType::AreaStacked100 => Some("percentStacked"),
             ChartType::Line | ChartType::LineMarkers | ChartType::Area => Some("standard"),
             _ => None,
@@ -174,6 +177,7 @@ mod tests {
     fn test_bar_direction() {
         assert_eq!(ChartType::Bar.bar_direction(), Some("col"));
         assert_eq!(ChartType::BarHorizontal.bar_direction(), Some("bar"));
+        assert_eq!(ChartType::BarHorizontalStacked.bar_direction(), Some("bar"));
         assert_eq!(ChartType::Line.bar_direction(), None);
     }
 
@@ -182,6 +186,7 @@ mod tests {
         assert_eq!(ChartType::Bar.grouping(), Some("clustered"));
         assert_eq!(ChartType::BarStacked.grouping(), Some("stacked"));
         assert_eq!(ChartType::BarStacked100.grouping(), Some("percentStacked"));
+        assert_eq!(ChartType::BarHorizontalStacked.grouping(), Some("stacked"));
     }
 
     #[test]
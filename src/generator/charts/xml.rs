@@ -7,7 +7,8 @@ use super::escape_xml;
 /// Generate chart XML for a slide
 pub fn generate_chart_xml(chart: &Chart, shape_id: usize) -> String {
     match chart.chart_type {
-        ChartType::Bar | ChartType::BarHorizontal | ChartType::BarStacked | ChartType::BarStacked100 => {
+        ChartType::Bar | ChartType::BarHorizontal | ChartType::BarHorizontalStacked
+        | ChartType::BarStacked | ChartType::BarStacked100 => {
             generate_bar_chart_xml(chart, shape_id)
         }
         ChartType::Line | ChartType::LineMarkers | ChartType::LineStacked => {
@@ -125,6 +125,43 @@ impl ConnectionSite {
     }
 }
 
+/// An element a connector can attach to (a shape or a shape group)
+pub trait ConnectorTarget {
+    /// Shape ID referenced by `a:stCxn`/`a:endCxn` (None = not anchored)
+    fn target_id(&self) -> Option<u32>;
+
+    /// Bounding frame as (x, y, width, height) in EMU
+    fn frame(&self) -> (u32, u32, u32, u32);
+
+    /// Position of a connection site in EMU
+    fn connection_point(&self, site: ConnectionSite) -> (u32, u32) {
+        let (x, y, cx, cy) = self.frame();
+        let (right, bottom) = (x + cx, y + cy);
+        let (mid_x, mid_y) = (x + cx / 2, y + cy / 2);
+        match site {
+            ConnectionSite::Top => (mid_x, y),
+            ConnectionSite::Bottom => (mid_x, bottom),
+            ConnectionSite::Left => (x, mid_y),
+            ConnectionSite::Right => (right, mid_y),
+            ConnectionSite::TopLeft => (x, y),
+            ConnectionSite::TopRight => (right, y),
+            ConnectionSite::BottomLeft => (x, bottom),
+            ConnectionSite::BottomRight => (right, bottom),
+            ConnectionSite::Center => (mid_x, mid_y),
+        }
+    }
+}
+
+impl ConnectorTarget for crate::generator::shapes::Shape {
+    fn target_id(&self) -> Option<u32> {
+        self.id
+    }
+
+    fn frame(&self) -> (u32, u32, u32, u32) {
+        (self.x, self.y, self.width, self.height)
+    }
+}
+
 /// Connector line style
 #[derive(Clone, Debug)]
 pub struct ConnectorLine {
@@ -250,6 +287,29 @@ impl Connector {
         }
     }
 
+    /// Create a connector between two shapes or groups
+    ///
+    /// End points are taken from the connection sites of the targets and the
+    /// connector is anchored to every target with an ID to glue to.
+    pub fn between(
+        connector_type: ConnectorType,
+        start: &dyn ConnectorTarget,
+        start_site: ConnectionSite,
+        end: &dyn ConnectorTarget,
+        end_site: ConnectionSite,
+    ) -> Self {
+        let (start_x, start_y) = start.connection_point(start_site);
+        let (end_x, end_y) = end.connection_point(end_site);
+        let mut connector = Self::new(connector_type, start_x, start_y, end_x, end_y);
+        if let Some(id) = start.target_id() {
+            connector = connector.connect_start(id, start_site);
+        }
+        if let Some(id) = end.target_id() {
+            connector = connector.connect_end(id, end_site);
+        }
+        connector
+    }
+
     /// Create a straight connector
     pub fn straight(start_x: u32, start_y: u32, end_x: u32, end_y: u32) -> Self {
         Self::new(ConnectorType::Straight, start_x, start_y, end_x, end_y)
@@ -470,6 +530,19 @@ pub fn generate_connector_xml(connector: &Connector, shape_id: usize) -> String
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::generator::shapes::{Shape, ShapeType};
+
+    #[test]
+    fn test_connector_between_shapes() {
+        let a = Shape::new(ShapeType::Rectangle, 0, 0, 1000, 500).with_id(10);
+        let b = Shape::new(ShapeType::Rectangle, 3000, 0, 1000, 500);
+        let connector = Connector::between(ConnectorType::Straight, &a, ConnectionSite::Right, &b, ConnectionSite::Left);
+
+        assert_eq!((connector.start_x, connector.start_y), (1000, 250));
+        assert_eq!((connector.end_x, connector.end_y), (3000, 250));
+        assert_eq!(connector.start_shape_id, Some(10));
+        assert_eq!(connector.end_shape_id, None);
+    }
 
     #[test]
     fn test_connector_type_preset() {
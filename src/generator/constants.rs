@@ -102,6 +102,10 @@ pub const TWO_COL_TITLE_HEIGHT: u32 = 914400;  // 1 inch
 /// ~1.3 inches from top
 pub const TWO_COL_CONTENT_Y: u32 = 1189200;  // ~1.3 inches
 
+/// Comparison layout: Column heading height
+/// 0.6 inches, above each column
+pub const COLUMN_HEADING_HEIGHT: u32 = 548640;  // 0.6 inches
+
 // ============================================================================
 // Notes Slide Positioning
 // ============================================================================
@@ -0,0 +1,260 @@
+//! Visual effects for shapes and images
+//!
+//! Provides soft edge and bevel effects, with the shadow, glow and reflection
+//! of `oxml::dml`, and the `a:effectLst` / `a:scene3d` / `a:sp3d` XML that
+//! carries them in `p:spPr`.
+
+pub use crate::oxml::dml::{Glow, Reflection, Shadow};
+
+/// Bevel preset types
+#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+pub enum BevelType {
+    Circle,
+    RelaxedInset,
+    Cross,
+    CoolSlant,
+    Angle,
+    SoftRound,
+    Convex,
+    Slope,
+    Divot,
+    Riblet,
+    HardEdge,
+    ArtDeco,
+}
+
+impl BevelType {
+    /// Get OOXML bevel preset value
+    pub fn xml_value(&self) -> &'static str {
+        match self {
+            BevelType::Circle => "circle",
+            BevelType::RelaxedInset => "relaxedInset",
+            BevelType::Cross => "cross",
+            BevelType::CoolSlant => "coolSlant",
+            BevelType::Angle => "angle",
+            BevelType::SoftRound => "softRound",
+            BevelType::Convex => "convex",
+            BevelType::Slope => "slope",
+            BevelType::Divot => "divot",
+            BevelType::Riblet => "riblet",
+            BevelType::HardEdge => "hardEdge",
+            BevelType::ArtDeco => "artDeco",
+        }
+    }
+}
+
+/// Camera presets for the 3-D scene
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+pub enum CameraPreset {
+    #[default]
+    OrthographicFront,
+    PerspectiveFront,
+    PerspectiveAbove,
+    PerspectiveBelow,
+    IsometricTopUp,
+    IsometricLeftDown,
+    IsometricRightUp,
+}
+
+impl CameraPreset {
+    /// Get OOXML camera preset value
+    pub fn xml_value(&self) -> &'static str {
+        match self {
+            CameraPreset::OrthographicFront => "orthographicFront",
+            CameraPreset::PerspectiveFront => "perspectiveFront",
+            CameraPreset::PerspectiveAbove => "perspectiveAbove",
+            CameraPreset::PerspectiveBelow => "perspectiveBelow",
+            CameraPreset::IsometricTopUp => "isometricTopUp",
+            CameraPreset::IsometricLeftDown => "isometricLeftDown",
+            CameraPreset::IsometricRightUp => "isometricRightUp",
+        }
+    }
+}
+
+/// Top bevel of a 3-D shape
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bevel {
+    pub bevel_type: BevelType,
+    /// Bevel width in EMU
+    pub width: u32,
+    /// Bevel height in EMU
+    pub height: u32,
+}
+
+impl Bevel {
+    /// Create a bevel with PowerPoint's default size (6pt x 2pt)
+    pub fn new(bevel_type: BevelType) -> Self {
+        Bevel {
+            bevel_type,
+            width: 76200,
+            height: 25400,
+        }
+    }
+
+    /// Set bevel width and height in EMU
+    pub fn with_size(mut self, width: u32, height: u32) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+}
+
+/// Effects applied to a shape or picture
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ShapeEffects {
+    pub outer_shadow: Option<Shadow>,
+    pub inner_shadow: Option<Shadow>,
+    pub glow: Option<Glow>,
+    /// Soft edge radius in EMU
+    pub soft_edge: Option<u32>,
+    pub reflection: Option<Reflection>,
+    pub bevel: Option<Bevel>,
+    /// Camera for the 3-D scene (defaults to orthographic front when a bevel is set)
+    pub camera: Option<CameraPreset>,
+}
+
+impl ShapeEffects {
+    /// Create an empty effect set
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an outer (drop) shadow
+    pub fn with_outer_shadow(mut self, shadow: Shadow) -> Self {
+        self.outer_shadow = Some(shadow);
+        self
+    }
+
+    /// Add an inner shadow
+    pub fn with_inner_shadow(mut self, shadow: Shadow) -> Self {
+        self.inner_shadow = Some(shadow);
+        self
+    }
+
+    /// Add a glow
+    pub fn with_glow(mut self, glow: Glow) -> Self {
+        self.glow = Some(glow);
+        self
+    }
+
+    /// Soften the edges by the given radius (EMU)
+    pub fn with_soft_edges(mut self, radius: u32) -> Self {
+        self.soft_edge = Some(radius);
+        self
+    }
+
+    /// Add a reflection
+    pub fn with_reflection(mut self, reflection: Reflection) -> Self {
+        self.reflection = Some(reflection);
+        self
+    }
+
+    /// Add a top bevel
+    pub fn with_bevel(mut self, bevel: Bevel) -> Self {
+        self.bevel = Some(bevel);
+        self
+    }
+
+    /// Set the 3-D scene camera
+    pub fn with_camera(mut self, camera: CameraPreset) -> Self {
+        self.camera = Some(camera);
+        self
+    }
+
+    /// Check if no effect is set
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Generate the effect XML for `p:spPr` (goes after fill and line)
+    pub fn to_xml(&self) -> String {
+        let mut effects = String::new();
+        // Children of a:effectLst must follow schema order
+        if let Some(glow) = &self.glow {
+            effects.push_str(&glow.to_xml());
+        }
+        if let Some(shadow) = &self.inner_shadow {
+            effects.push_str(&shadow.to_inner_xml());
+        }
+        if let Some(shadow) = &self.outer_shadow {
+            effects.push_str(&shadow.to_xml());
+        }
+        if let Some(reflection) = &self.reflection {
+            effects.push_str(&reflection.to_xml());
+        }
+        if let Some(radius) = self.soft_edge {
+            effects.push_str(&format!(r#"<a:softEdge rad="{radius}"/>"#));
+        }
+
+        let mut xml = String::new();
+        if !effects.is_empty() {
+            xml.push_str(&format!("<a:effectLst>{effects}</a:effectLst>"));
+        }
+        if self.bevel.is_some() || self.camera.is_some() {
+            xml.push_str(&format!(
+                r#"<a:scene3d><a:camera prst="{}"/><a:lightRig rig="threePt" dir="t"/></a:scene3d>"#,
+                self.camera.unwrap_or_default().xml_value()
+            ));
+        }
+        if let Some(bevel) = &self.bevel {
+            xml.push_str(&format!(
+                r#"<a:sp3d><a:bevelT w="{}" h="{}" prst="{}"/></a:sp3d>"#,
+                bevel.width,
+                bevel.height,
+                bevel.bevel_type.xml_value()
+            ));
+        }
+        xml
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_effects() {
+        let effects = ShapeEffects::new();
+        assert!(effects.is_empty());
+        assert_eq!(effects.to_xml(), "");
+    }
+
+    #[test]
+    fn test_shadow_xml() {
+        let effects = ShapeEffects::new()
+            .with_outer_shadow(Shadow::new("#333333").with_blur(76200).with_angle(90).with_transparency(50));
+        let xml = effects.to_xml();
+
+        assert!(xml.contains(r#"<a:outerShdw blurRad="76200" dist="38100" dir="5400000" algn="tl""#));
+        assert!(xml.contains(r#"<a:srgbClr val="333333"><a:alpha val="50000"/></a:srgbClr>"#));
+    }
+
+    #[test]
+    fn test_effect_order() {
+        let xml = ShapeEffects::new()
+            .with_soft_edges(12700)
+            .with_reflection(Reflection::new())
+            .with_outer_shadow(Shadow::default())
+            .with_inner_shadow(Shadow::default())
+            .with_glow(Glow::new("FFC000", 63500))
+            .to_xml();
+
+        let pos = |tag: &str| xml.find(tag).unwrap();
+        assert!(pos("<a:glow") < pos("<a:innerShdw"));
+        assert!(pos("<a:innerShdw") < pos("<a:outerShdw"));
+        assert!(pos("<a:outerShdw") < pos("<a:reflection"));
+        assert!(pos("<a:reflection") < pos("<a:softEdge"));
+    }
+
+    #[test]
+    fn test_bevel_xml() {
+        let xml = ShapeEffects::new()
+            .with_bevel(Bevel::new(BevelType::SoftRound))
+            .to_xml();
+
+        assert!(!xml.contains("effectLst"));
+        assert!(xml.contains(r#"<a:camera prst="orthographicFront"/>"#));
+        assert!(xml.contains(r#"<a:bevelT w="76200" h="25400" prst="softRound"/>"#));
+        assert!(xml.find("<a:scene3d>").unwrap() < xml.find("<a:sp3d>").unwrap());
+    }
+}
@@ -0,0 +1,664 @@
+//! Freeform (custom geometry) shapes
+//!
+//! Builds `a:custGeom` outlines from move/line/curve/arc commands, either with
+//! [`PathBuilder`] or by converting an SVG path `d` string.
+
+use crate::core::escape_xml;
+use crate::exc::{PptxError, Result};
+
+/// Longest side of the path coordinate space produced by SVG conversion
+const SVG_PATH_SIZE: f64 = 100000.0;
+
+/// A drawing command in path coordinates
+#[derive(Clone, Debug, PartialEq)]
+pub enum PathCommand {
+    /// Start a new sub-path
+    MoveTo(i64, i64),
+    /// Straight line to a point
+    LineTo(i64, i64),
+    /// Cubic Bézier curve (two control points, end point)
+    CubicTo((i64, i64), (i64, i64), (i64, i64)),
+    /// Quadratic Bézier curve (control point, end point)
+    QuadTo((i64, i64), (i64, i64)),
+    /// Elliptical arc from the current point (angles in degrees, clockwise)
+    ArcTo {
+        width_radius: i64,
+        height_radius: i64,
+        start_angle: f64,
+        swing_angle: f64,
+    },
+    /// Close the current sub-path
+    Close,
+}
+
+/// How a path is filled
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+pub enum PathFill {
+    /// Use the shape fill
+    #[default]
+    Normal,
+    /// Outline only
+    None,
+    Lighten,
+    LightenLess,
+    Darken,
+    DarkenLess,
+}
+
+impl PathFill {
+    /// Get OOXML path fill mode value
+    pub fn xml_value(&self) -> &'static str {
+        match self {
+            PathFill::Normal => "norm",
+            PathFill::None => "none",
+            PathFill::Lighten => "lighten",
+            PathFill::LightenLess => "lightenLess",
+            PathFill::Darken => "darken",
+            PathFill::DarkenLess => "darkenLess",
+        }
+    }
+}
+
+/// One path of a custom geometry, with its own coordinate space
+#[derive(Clone, Debug, PartialEq)]
+pub struct GeometryPath {
+    /// Width of the path coordinate space
+    pub width: i64,
+    /// Height of the path coordinate space
+    pub height: i64,
+    pub commands: Vec<PathCommand>,
+    pub fill: PathFill,
+    /// Draw the shape outline along this path
+    pub stroke: bool,
+}
+
+impl GeometryPath {
+    fn to_xml(&self) -> String {
+        let mut xml = format!(r#"<a:path w="{}" h="{}""#, self.width, self.height);
+        if self.fill != PathFill::Normal {
+            xml.push_str(&format!(r#" fill="{}""#, self.fill.xml_value()));
+        }
+        if !self.stroke {
+            xml.push_str(r#" stroke="0""#);
+        }
+        xml.push('>');
+
+        let pt = |(x, y): (i64, i64)| format!(r#"<a:pt x="{x}" y="{y}"/>"#);
+        for command in &self.commands {
+            match command {
+                PathCommand::MoveTo(x, y) => xml.push_str(&format!("<a:moveTo>{}</a:moveTo>", pt((*x, *y)))),
+                PathCommand::LineTo(x, y) => xml.push_str(&format!("<a:lnTo>{}</a:lnTo>", pt((*x, *y)))),
+                PathCommand::CubicTo(c1, c2, end) => xml.push_str(&format!(
+                    "<a:cubicBezTo>{}{}{}</a:cubicBezTo>",
+                    pt(*c1), pt(*c2), pt(*end)
+                )),
+                PathCommand::QuadTo(c, end) => xml.push_str(&format!(
+                    "<a:quadBezTo>{}{}</a:quadBezTo>",
+                    pt(*c), pt(*end)
+                )),
+                PathCommand::ArcTo { width_radius, height_radius, start_angle, swing_angle } => xml.push_str(&format!(
+                    r#"<a:arcTo wR="{}" hR="{}" stAng="{}" swAng="{}"/>"#,
+                    width_radius,
+                    height_radius,
+                    (start_angle * 60000.0).round() as i64,
+                    (swing_angle * 60000.0).round() as i64
+                )),
+                PathCommand::Close => xml.push_str("<a:close/>"),
+            }
+        }
+        xml.push_str("</a:path>");
+        xml
+    }
+}
+
+/// Fluent builder for a [`GeometryPath`]
+///
+/// # Example
+/// ```rust
+/// use ppt_rs::generator::PathBuilder;
+///
+/// let triangle = PathBuilder::new(100, 100)
+///     .move_to(50, 0)
+///     .line_to(100, 100)
+///     .line_to(0, 100)
+///     .close()
+///     .build();
+/// assert_eq!(triangle.commands.len(), 4);
+/// ```
+#[derive(Clone, Debug)]
+pub struct PathBuilder {
+    path: GeometryPath,
+}
+
+impl PathBuilder {
+    /// Create a builder for a path with the given coordinate space
+    pub fn new(width: i64, height: i64) -> Self {
+        PathBuilder {
+            path: GeometryPath {
+                width: width.max(1),
+                height: height.max(1),
+                commands: Vec::new(),
+                fill: PathFill::Normal,
+                stroke: true,
+            },
+        }
+    }
+
+    /// Start a new sub-path
+    pub fn move_to(mut self, x: i64, y: i64) -> Self {
+        self.path.commands.push(PathCommand::MoveTo(x, y));
+        self
+    }
+
+    /// Draw a straight line
+    pub fn line_to(mut self, x: i64, y: i64) -> Self {
+        self.path.commands.push(PathCommand::LineTo(x, y));
+        self
+    }
+
+    /// Draw a cubic Bézier curve
+    pub fn cubic_to(mut self, x1: i64, y1: i64, x2: i64, y2: i64, x: i64, y: i64) -> Self {
+        self.path.commands.push(PathCommand::CubicTo((x1, y1), (x2, y2), (x, y)));
+        self
+    }
+
+    /// Draw a quadratic Bézier curve
+    pub fn quad_to(mut self, x1: i64, y1: i64, x: i64, y: i64) -> Self {
+        self.path.commands.push(PathCommand::QuadTo((x1, y1), (x, y)));
+        self
+    }
+
+    /// Draw an elliptical arc starting at the current point
+    ///
+    /// `start_angle` is the angle of the current point on the ellipse and
+    /// `swing_angle` the clockwise sweep, both in degrees.
+    pub fn arc_to(mut self, width_radius: i64, height_radius: i64, start_angle: f64, swing_angle: f64) -> Self {
+        self.path.commands.push(PathCommand::ArcTo { width_radius, height_radius, start_angle, swing_angle });
+        self
+    }
+
+    /// Close the current sub-path
+    pub fn close(mut self) -> Self {
+        self.path.commands.push(PathCommand::Close);
+        self
+    }
+
+    /// Draw connected line segments through the points
+    pub fn polyline(mut self, points: &[(i64, i64)]) -> Self {
+        for (i, &(x, y)) in points.iter().enumerate() {
+            let command = if i == 0 { PathCommand::MoveTo(x, y) } else { PathCommand::LineTo(x, y) };
+            self.path.commands.push(command);
+        }
+        self
+    }
+
+    /// Set the fill mode
+    pub fn fill(mut self, fill: PathFill) -> Self {
+        self.path.fill = fill;
+        self
+    }
+
+    /// Enable or disable the outline
+    pub fn stroke(mut self, stroke: bool) -> Self {
+        self.path.stroke = stroke;
+        self
+    }
+
+    /// Build the path
+    pub fn build(self) -> GeometryPath {
+        self.path
+    }
+}
+
+/// A shape guide (`a:gd`), e.g. `("inset", "*/ w 1 8")`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Guide {
+    pub name: String,
+    pub formula: String,
+}
+
+/// Custom shape geometry (`a:custGeom`)
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CustomGeometry {
+    pub guides: Vec<Guide>,
+    pub paths: Vec<GeometryPath>,
+}
+
+impl CustomGeometry {
+    /// Create an empty geometry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a path
+    pub fn add_path(mut self, path: GeometryPath) -> Self {
+        self.paths.push(path);
+        self
+    }
+
+    /// Add a shape guide
+    pub fn with_guide(mut self, name: &str, formula: &str) -> Self {
+        self.guides.push(Guide { name: name.to_string(), formula: formula.to_string() });
+        self
+    }
+
+    /// Convert an SVG path `d` attribute into a custom geometry
+    ///
+    /// All SVG commands are supported. Arcs are approximated with cubic
+    /// Béziers and the outline is normalized to its bounding box, so the
+    /// shape's size determines the final scale.
+    pub fn from_svg_path(d: &str) -> Result<Self> {
+        let segments = SvgPathParser::new(d).parse()?;
+        Ok(Self::new().add_path(svg_segments_to_path(&segments)))
+    }
+
+    /// Generate `a:custGeom` XML
+    pub fn to_xml(&self) -> String {
+        let mut xml = String::from("<a:custGeom>\n<a:avLst/>\n");
+        if self.guides.is_empty() {
+            xml.push_str("<a:gdLst/>\n");
+        } else {
+            xml.push_str("<a:gdLst>");
+            for guide in &self.guides {
+                xml.push_str(&format!(r#"<a:gd name="{}" fmla="{}"/>"#, escape_xml(&guide.name), escape_xml(&guide.formula)));
+            }
+            xml.push_str("</a:gdLst>\n");
+        }
+        xml.push_str("<a:ahLst/>\n<a:cxnLst/>\n<a:rect l=\"l\" t=\"t\" r=\"r\" b=\"b\"/>\n<a:pathLst>");
+        for path in &self.paths {
+            xml.push_str(&path.to_xml());
+        }
+        xml.push_str("</a:pathLst>\n</a:custGeom>");
+        xml
+    }
+}
+
+/// SVG path segment in absolute user units
+#[derive(Clone, Debug, PartialEq)]
+enum SvgSegment {
+    Move(f64, f64),
+    Line(f64, f64),
+    Cubic((f64, f64), (f64, f64), (f64, f64)),
+    Quad((f64, f64), (f64, f64)),
+    Close,
+}
+
+struct SvgPathParser<'a> {
+    input: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> SvgPathParser<'a> {
+    fn new(d: &'a str) -> Self {
+        SvgPathParser { input: d.as_bytes(), pos: 0 }
+    }
+
+    fn skip_separators(&mut self) {
+        while self.pos < self.input.len() && (self.input[self.pos].is_ascii_whitespace() || self.input[self.pos] == b',') {
+            self.pos += 1;
+        }
+    }
+
+    fn at_number(&mut self) -> bool {
+        self.skip_separators();
+        self.input
+            .get(self.pos)
+            .is_some_and(|c| c.is_ascii_digit() || matches!(c, b'-' | b'+' | b'.'))
+    }
+
+    fn number(&mut self) -> Result<f64> {
+        self.skip_separators();
+        let start = self.pos;
+        let bytes = self.input;
+        if matches!(bytes.get(self.pos), Some(b'-' | b'+')) {
+            self.pos += 1;
+        }
+        let mut seen_dot = false;
+        while let Some(&c) = bytes.get(self.pos) {
+            if c.is_ascii_digit() {
+                self.pos += 1;
+            } else if c == b'.' && !seen_dot {
+                seen_dot = true;
+                self.pos += 1;
+            } else if (c == b'e' || c == b'E') && self.pos > start {
+                self.pos += 1;
+                if matches!(bytes.get(self.pos), Some(b'-' | b'+')) {
+                    self.pos += 1;
+                }
+            } else {
+                break;
+            }
+        }
+        std::str::from_utf8(&bytes[start..self.pos])
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .ok_or_else(|| PptxError::InvalidValue(format!("Invalid number in SVG path at offset {}", start)))
+    }
+
+    fn flag(&mut self) -> Result<bool> {
+        self.skip_separators();
+        let flag = match self.input.get(self.pos) {
+            Some(b'0') => false,
+            Some(b'1') => true,
+            _ => return Err(PptxError::InvalidValue(format!("Invalid arc flag in SVG path at offset {}", self.pos))),
+        };
+        self.pos += 1;
+        Ok(flag)
+    }
+
+    fn point(&mut self) -> Result<(f64, f64)> {
+        Ok((self.number()?, self.number()?))
+    }
+
+    fn parse(mut self) -> Result<Vec<SvgSegment>> {
+        let mut segments = Vec::new();
+        let mut current = (0.0, 0.0);
+        let mut start = (0.0, 0.0);
+        // Last control point, for the smooth S/T commands
+        let mut last_cubic: Option<(f64, f64)> = None;
+        let mut last_quad: Option<(f64, f64)> = None;
+        let mut command: Option<u8> = None;
+
+        loop {
+            self.skip_separators();
+            let Some(&c) = self.input.get(self.pos) else { break };
+            if c.is_ascii_alphabetic() {
+                self.pos += 1;
+                command = Some(c);
+            } else if command.is_none() || matches!(command, Some(b'z' | b'Z')) {
+                return Err(PptxError::InvalidValue(format!("Expected SVG path command at offset {}", self.pos)));
+            }
+            let cmd = command.unwrap_or(b'M');
+            let relative = cmd.is_ascii_lowercase();
+            let abs = |p: (f64, f64), cur: (f64, f64)| if relative { (cur.0 + p.0, cur.1 + p.1) } else { p };
+
+            let (mut next_cubic, mut next_quad) = (None, None);
+            match cmd.to_ascii_uppercase() {
+                b'M' => {
+                    current = abs(self.point()?, current);
+                    start = current;
+                    segments.push(SvgSegment::Move(current.0, current.1));
+                    // Further coordinate pairs are implicit line-tos
+                    command = Some(if relative { b'l' } else { b'L' });
+                }
+                b'L' => {
+                    current = abs(self.point()?, current);
+                    segments.push(SvgSegment::Line(current.0, current.1));
+                }
+                b'H' => {
+                    let x = self.number()?;
+                    current.0 = if relative { current.0 + x } else { x };
+                    segments.push(SvgSegment::Line(current.0, current.1));
+                }
+                b'V' => {
+                    let y = self.number()?;
+                    current.1 = if relative { current.1 + y } else { y };
+                    segments.push(SvgSegment::Line(current.0, current.1));
+                }
+                b'C' | b'S' => {
+                    let c1 = if cmd.eq_ignore_ascii_case(&b'C') {
+                        abs(self.point()?, current)
+                    } else {
+                        last_cubic.map(|c| (2.0 * current.0 - c.0, 2.0 * current.1 - c.1)).unwrap_or(current)
+                    };
+                    let c2 = abs(self.point()?, current);
+                    let end = abs(self.point()?, current);
+                    segments.push(SvgSegment::Cubic(c1, c2, end));
+                    next_cubic = Some(c2);
+                    current = end;
+                }
+                b'Q' | b'T' => {
+                    let ctrl = if cmd.eq_ignore_ascii_case(&b'Q') {
+                        abs(self.point()?, current)
+                    } else {
+                        last_quad.map(|c| (2.0 * current.0 - c.0, 2.0 * current.1 - c.1)).unwrap_or(current)
+                    };
+                    let end = abs(self.point()?, current);
+                    segments.push(SvgSegment::Quad(ctrl, end));
+                    next_quad = Some(ctrl);
+                    current = end;
+                }
+                b'A' => {
+                    let (rx, ry) = (self.number()?.abs(), self.number()?.abs());
+                    let rotation = self.number()?;
+                    let large_arc = self.flag()?;
+                    let sweep = self.flag()?;
+                    let end = abs(self.point()?, current);
+                    segments.extend(arc_to_cubics(current, end, rx, ry, rotation, large_arc, sweep));
+                    current = end;
+                }
+                b'Z' => {
+                    segments.push(SvgSegment::Close);
+                    current = start;
+                }
+                other => {
+                    return Err(PptxError::InvalidValue(format!("Unsupported SVG path command '{}'", other as char)));
+                }
+            }
+            last_cubic = next_cubic;
+            last_quad = next_quad;
+
+            if !matches!(cmd, b'z' | b'Z') && !self.at_number() {
+                command = None;
+            }
+        }
+
+        if !matches!(segments.first(), Some(SvgSegment::Move(..))) {
+            return Err(PptxError::InvalidValue("SVG path must start with a moveto".to_string()));
+        }
+        Ok(segments)
+    }
+}
+
+/// Convert an SVG endpoint arc into cubic Bézier segments (at most 90° each)
+fn arc_to_cubics(
+    from: (f64, f64),
+    to: (f64, f64),
+    mut rx: f64,
+    mut ry: f64,
+    rotation: f64,
+    large_arc: bool,
+    sweep: bool,
+) -> Vec<SvgSegment> {
+    if rx == 0.0 || ry == 0.0 || from == to {
+        return vec![SvgSegment::Line(to.0, to.1)];
+    }
+    let phi = rotation.to_radians();
+    let (sin_phi, cos_phi) = phi.sin_cos();
+
+    // Center parameterization (SVG implementation notes, F.6.5)
+    let dx = (from.0 - to.0) / 2.0;
+    let dy = (from.1 - to.1) / 2.0;
+    let x1p = cos_phi * dx + sin_phi * dy;
+    let y1p = -sin_phi * dx + cos_phi * dy;
+
+    let lambda = (x1p * x1p) / (rx * rx) + (y1p * y1p) / (ry * ry);
+    if lambda > 1.0 {
+        rx *= lambda.sqrt();
+        ry *= lambda.sqrt();
+    }
+
+    let num = rx * rx * ry * ry - rx * rx * y1p * y1p - ry * ry * x1p * x1p;
+    let den = rx * rx * y1p * y1p + ry * ry * x1p * x1p;
+    let mut coef = (num / den).max(0.0).sqrt();
+    if large_arc == sweep {
+        coef = -coef;
+    }
+    let cxp = coef * rx * y1p / ry;
+    let cyp = -coef * ry * x1p / rx;
+    let cx = cos_phi * cxp - sin_phi * cyp + (from.0 + to.0) / 2.0;
+    let cy = sin_phi * cxp + cos_phi * cyp + (from.1 + to.1) / 2.0;
+
+    let angle = |ux: f64, uy: f64, vx: f64, vy: f64| {
+        let a = (ux * vy - uy * vx).atan2(ux * vx + uy * vy);
+        if a.is_nan() { 0.0 } else { a }
+    };
+    let theta1 = angle(1.0, 0.0, (x1p - cxp) / rx, (y1p - cyp) / ry);
+    let mut delta = angle((x1p - cxp) / rx, (y1p - cyp) / ry, (-x1p - cxp) / rx, (-y1p - cyp) / ry);
+    if !sweep && delta > 0.0 {
+        delta -= std::f64::consts::TAU;
+    } else if sweep && delta < 0.0 {
+        delta += std::f64::consts::TAU;
+    }
+
+    let count = (delta.abs() / std::f64::consts::FRAC_PI_2).ceil().max(1.0) as usize;
+    let step = delta / count as f64;
+    let k = 4.0 / 3.0 * (step / 4.0).tan();
+    let point = |t: f64| {
+        let (sin_t, cos_t) = t.sin_cos();
+        (
+            cx + rx * cos_t * cos_phi - ry * sin_t * sin_phi,
+            cy + rx * cos_t * sin_phi + ry * sin_t * cos_phi,
+        )
+    };
+    let derivative = |t: f64| {
+        let (sin_t, cos_t) = t.sin_cos();
+        (
+            -rx * sin_t * cos_phi - ry * cos_t * sin_phi,
+            -rx * sin_t * sin_phi + ry * cos_t * cos_phi,
+        )
+    };
+
+    (0..count)
+        .map(|i| {
+            let t1 = theta1 + step * i as f64;
+            let t2 = t1 + step;
+            let (p1, d1) = (point(t1), derivative(t1));
+            let (d2, end) = (derivative(t2), if i + 1 == count { to } else { point(t2) });
+            SvgSegment::Cubic(
+                (p1.0 + k * d1.0, p1.1 + k * d1.1),
+                (end.0 - k * d2.0, end.1 - k * d2.1),
+                end,
+            )
+        })
+        .collect()
+}
+
+/// Normalize SVG segments into a single path in its bounding box
+fn svg_segments_to_path(segments: &[SvgSegment]) -> GeometryPath {
+    let points: Vec<(f64, f64)> = segments
+        .iter()
+        .flat_map(|segment| match *segment {
+            SvgSegment::Move(x, y) | SvgSegment::Line(x, y) => vec![(x, y)],
+            SvgSegment::Cubic(c1, c2, end) => vec![c1, c2, end],
+            SvgSegment::Quad(c, end) => vec![c, end],
+            SvgSegment::Close => vec![],
+        })
+        .collect();
+
+    let min_x = points.iter().map(|p| p.0).fold(f64::INFINITY, f64::min);
+    let min_y = points.iter().map(|p| p.1).fold(f64::INFINITY, f64::min);
+    let max_x = points.iter().map(|p| p.0).fold(f64::NEG_INFINITY, f64::max);
+    let max_y = points.iter().map(|p| p.1).fold(f64::NEG_INFINITY, f64::max);
+    let longest = (max_x - min_x).max(max_y - min_y);
+    let scale = if longest > 0.0 { SVG_PATH_SIZE / longest } else { 1.0 };
+    let map = |(x, y): (f64, f64)| (((x - min_x) * scale).round() as i64, ((y - min_y) * scale).round() as i64);
+
+    let mut builder = PathBuilder::new(
+        ((max_x - min_x) * scale).round() as i64,
+        ((max_y - min_y) * scale).round() as i64,
+    );
+    for segment in segments {
+        builder = match *segment {
+            SvgSegment::Move(x, y) => {
+                let (x, y) = map((x, y));
+                builder.move_to(x, y)
+            }
+            SvgSegment::Line(x, y) => {
+                let (x, y) = map((x, y));
+                builder.line_to(x, y)
+            }
+            SvgSegment::Cubic(c1, c2, end) => {
+                let ((x1, y1), (x2, y2), (x, y)) = (map(c1), map(c2), map(end));
+                builder.cubic_to(x1, y1, x2, y2, x, y)
+            }
+            SvgSegment::Quad(c, end) => {
+                let ((x1, y1), (x, y)) = (map(c), map(end));
+                builder.quad_to(x1, y1, x, y)
+            }
+            SvgSegment::Close => builder.close(),
+        };
+    }
+    builder.build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_builder_xml() {
+        let geometry = CustomGeometry::new()
+            .add_path(
+                PathBuilder::new(200, 100)
+                    .move_to(0, 100)
+                    .cubic_to(50, 0, 150, 0, 200, 100)
+                    .quad_to(100, 50, 0, 100)
+                    .close()
+                    .build(),
+            )
+            .add_path(PathBuilder::new(200, 100).polyline(&[(0, 0), (200, 0)]).fill(PathFill::None).build());
+        let xml = geometry.to_xml();
+
+        assert!(xml.starts_with("<a:custGeom>"));
+        assert!(xml.contains("<a:gdLst/>"));
+        assert!(xml.contains(r#"<a:path w="200" h="100"><a:moveTo><a:pt x="0" y="100"/></a:moveTo>"#));
+        assert!(xml.contains(r#"<a:cubicBezTo><a:pt x="50" y="0"/><a:pt x="150" y="0"/><a:pt x="200" y="100"/></a:cubicBezTo>"#));
+        assert!(xml.contains("<a:quadBezTo>"));
+        assert!(xml.contains(r#"<a:path w="200" h="100" fill="none">"#));
+        assert_eq!(xml.matches("<a:path ").count(), 2);
+    }
+
+    #[test]
+    fn test_guides_and_arc() {
+        let geometry = CustomGeometry::new()
+            .with_guide("inset", "*/ w 1 8")
+            .add_path(PathBuilder::new(100, 100).move_to(100, 50).arc_to(50, 50, 0.0, 360.0).stroke(false).build());
+        let xml = geometry.to_xml();
+
+        assert!(xml.contains(r#"<a:gdLst><a:gd name="inset" fmla="*/ w 1 8"/></a:gdLst>"#));
+        assert!(xml.contains(r#"<a:arcTo wR="50" hR="50" stAng="0" swAng="21600000"/>"#));
+        assert!(xml.contains(r#"stroke="0""#));
+    }
+
+    #[test]
+    fn test_svg_path_lines() {
+        let geometry = CustomGeometry::from_svg_path("M10 10 h 20 v20 H10 z m5,5 l5 0 0 5z").unwrap();
+        let path = &geometry.paths[0];
+
+        assert_eq!((path.width, path.height), (100000, 100000));
+        assert_eq!(path.commands[0], PathCommand::MoveTo(0, 0));
+        assert_eq!(path.commands[1], PathCommand::LineTo(100000, 0));
+        assert_eq!(path.commands[4], PathCommand::Close);
+        // Relative moveto after close starts from the sub-path start
+        assert_eq!(path.commands[5], PathCommand::MoveTo(25000, 25000));
+        assert_eq!(path.commands[7], PathCommand::LineTo(50000, 50000));
+    }
+
+    #[test]
+    fn test_svg_path_curves_and_arcs() {
+        let geometry = CustomGeometry::from_svg_path("M0,0 C0,10 10,10 10,0 S20,-10 20,0 Q25,5 30,0 T40,0 A5 5 0 0 1 50 0").unwrap();
+        let commands = &geometry.paths[0].commands;
+
+        assert!(matches!(commands[1], PathCommand::CubicTo(..)));
+        assert!(matches!(commands[3], PathCommand::QuadTo(..)));
+        // Half circle becomes two cubic segments ending at the arc end point
+        let arcs: Vec<_> = commands[5..].iter().collect();
+        assert_eq!(arcs.len(), 2);
+        let PathCommand::CubicTo(_, _, end) = arcs[1] else { panic!("expected cubic") };
+        assert_eq!(*end, (100000, 20000));
+    }
+
+    #[test]
+    fn test_svg_compact_arc_flags() {
+        let geometry = CustomGeometry::from_svg_path("M0 0a1 1 0 011 1").unwrap();
+        assert_eq!(geometry.paths[0].commands.len(), 2);
+    }
+
+    #[test]
+    fn test_svg_path_errors() {
+        assert!(CustomGeometry::from_svg_path("L 10 10").is_err());
+        assert!(CustomGeometry::from_svg_path("M 0 0 X 1").is_err());
+        assert!(CustomGeometry::from_svg_path("M 0 0 L 1").is_err());
+    }
+}
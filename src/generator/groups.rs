@@ -0,0 +1,330 @@
+//! Shape groups for PPTX generation
+//!
+//! A group (`p:grpSp`) treats several shapes, or other groups, as one object.
+//! Children are positioned in the group's child coordinate space; the group's
+//! own position and size map that space onto the slide (or the parent group).
+
+use crate::core::escape_xml;
+use super::connectors::ConnectorTarget;
+use super::hyperlinks::Hyperlink;
+use super::shapes::Shape;
+use super::shapes_xml::{generate_shape_xml, xfrm_attrs};
+
+/// A member of a shape group
+#[derive(Clone, Debug)]
+pub enum GroupItem {
+    Shape(Box<Shape>),
+    Group(ShapeGroup),
+}
+
+impl GroupItem {
+    /// Frame of the item in the group's child space
+    fn frame(&self) -> (u32, u32, u32, u32) {
+        match self {
+            GroupItem::Shape(shape) => shape.frame(),
+            GroupItem::Group(group) => group.frame(),
+        }
+    }
+}
+
+/// Group of shapes rendered as a single `p:grpSp`
+#[derive(Clone, Debug, Default)]
+pub struct ShapeGroup {
+    pub items: Vec<GroupItem>,
+    pub name: Option<String>,
+    /// Optional fixed shape ID
+    pub id: Option<u32>,
+    /// Position of the group; defaults to the top-left of its children
+    pub position: Option<(u32, u32)>,
+    /// Rendered size of the group; defaults to the extent of its children
+    pub size: Option<(u32, u32)>,
+    /// Rotation in degrees (clockwise)
+    pub rotation: f64,
+    /// Mirror horizontally
+    pub flip_h: bool,
+    /// Mirror vertically
+    pub flip_v: bool,
+}
+
+impl ShapeGroup {
+    /// Create an empty group
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set a fixed shape ID
+    pub fn with_id(mut self, id: u32) -> Self {
+        self.id = Some(id);
+        self
+    }
+
+    /// Set the group name shown in the selection pane
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    /// Add a shape to the group
+    pub fn add_shape(mut self, shape: Shape) -> Self {
+        self.items.push(GroupItem::Shape(Box::new(shape)));
+        self
+    }
+
+    /// Add multiple shapes to the group
+    pub fn with_shapes(mut self, shapes: Vec<Shape>) -> Self {
+        self.items.extend(shapes.into_iter().map(|shape| GroupItem::Shape(Box::new(shape))));
+        self
+    }
+
+    /// Nest another group inside this one
+    pub fn add_group(mut self, group: ShapeGroup) -> Self {
+        self.items.push(GroupItem::Group(group));
+        self
+    }
+
+    /// Place the group at a position (EMU) without moving its children
+    pub fn with_position(mut self, x: u32, y: u32) -> Self {
+        self.position = Some((x, y));
+        self
+    }
+
+    /// Scale the group to a size (EMU)
+    pub fn with_size(mut self, width: u32, height: u32) -> Self {
+        self.size = Some((width, height));
+        self
+    }
+
+    /// Set rotation in degrees (clockwise)
+    pub fn with_rotation(mut self, degrees: f64) -> Self {
+        self.rotation = degrees.rem_euclid(360.0);
+        self
+    }
+
+    /// Mirror the group horizontally
+    pub fn with_flip_h(mut self) -> Self {
+        self.flip_h = true;
+        self
+    }
+
+    /// Mirror the group vertically
+    pub fn with_flip_v(mut self) -> Self {
+        self.flip_v = true;
+        self
+    }
+
+    /// Bounding box of the children as (x, y, width, height) in child space
+    pub fn child_bounds(&self) -> (u32, u32, u32, u32) {
+        let mut frames = self.items.iter().map(GroupItem::frame);
+        let Some((x, y, cx, cy)) = frames.next() else {
+            return (0, 0, 0, 0);
+        };
+        let (mut left, mut top, mut right, mut bottom) = (x, y, x + cx, y + cy);
+        for (x, y, cx, cy) in frames {
+            left = left.min(x);
+            top = top.min(y);
+            right = right.max(x + cx);
+            bottom = bottom.max(y + cy);
+        }
+        (left, top, right - left, bottom - top)
+    }
+
+    /// Map a point from the group's child space to its parent's space
+    pub fn map_point(&self, x: u32, y: u32) -> (u32, u32) {
+        let (ch_x, ch_y, ch_cx, ch_cy) = self.child_bounds();
+        let (off_x, off_y, cx, cy) = self.frame();
+        let scale = |v: u32, origin: u32, from: u32, to: u32| {
+            if from == 0 {
+                0
+            } else {
+                (v.saturating_sub(origin) as u64 * to as u64 / from as u64) as u32
+            }
+        };
+        (off_x + scale(x, ch_x, ch_cx, cx), off_y + scale(y, ch_y, ch_cy, cy))
+    }
+
+    /// Hyperlinks of the shapes inside this group (recursively)
+    pub fn hyperlinks(&self) -> Vec<&Hyperlink> {
+        self.items
+            .iter()
+            .flat_map(|item| match item {
+                GroupItem::Shape(shape) => shape.hyperlinks(),
+                GroupItem::Group(group) => group.hyperlinks(),
+            })
+            .collect()
+    }
+
+    /// Mutable hyperlinks of the shapes inside this group (recursively)
+    pub fn hyperlinks_mut(&mut self) -> Vec<&mut Hyperlink> {
+        self.items
+            .iter_mut()
+            .flat_map(|item| match item {
+                GroupItem::Shape(shape) => shape.hyperlinks_mut(),
+                GroupItem::Group(group) => group.hyperlinks_mut(),
+            })
+            .collect()
+    }
+
+    /// Count shapes and groups inside this group (recursively)
+    pub fn element_count(&self) -> usize {
+        self.items
+            .iter()
+            .map(|item| match item {
+                GroupItem::Shape(_) => 1,
+                GroupItem::Group(group) => 1 + group.element_count(),
+            })
+            .sum()
+    }
+}
+
+impl ConnectorTarget for ShapeGroup {
+    /// Always None: a `p:grpSp` has no connection sites to glue to, so
+    /// connectors to a group are placed by coordinates only
+    fn target_id(&self) -> Option<u32> {
+        None
+    }
+
+    /// Frame in the parent's space (the slide for top-level groups)
+    fn frame(&self) -> (u32, u32, u32, u32) {
+        let (x, y, cx, cy) = self.child_bounds();
+        let (x, y) = self.position.unwrap_or((x, y));
+        let (cx, cy) = self.size.unwrap_or((cx, cy));
+        (x, y, cx, cy)
+    }
+}
+
+/// Generate XML for a shape group
+///
+/// Shapes and groups without a fixed ID take IDs from `next_id`, which is
+/// advanced past every ID handed out.
+pub fn generate_group_xml(group: &ShapeGroup, next_id: &mut u32) -> String {
+    let group_id = group.id.unwrap_or_else(|| take_id(next_id));
+    let name = group.name.clone().unwrap_or_else(|| format!("Group {}", group_id));
+    let (x, y, cx, cy) = group.frame();
+    let (ch_x, ch_y, ch_cx, ch_cy) = group.child_bounds();
+
+    let mut xml = format!(
+        r#"<p:grpSp>
+<p:nvGrpSpPr>
+<p:cNvPr id="{}" name="{}"/>
+<p:cNvGrpSpPr/>
+<p:nvPr/>
+</p:nvGrpSpPr>
+<p:grpSpPr>
+<a:xfrm{}>
+<a:off x="{}" y="{}"/>
+<a:ext cx="{}" cy="{}"/>
+<a:chOff x="{}" y="{}"/>
+<a:chExt cx="{}" cy="{}"/>
+</a:xfrm>
+</p:grpSpPr>"#,
+        group_id,
+        escape_xml(&name),
+        xfrm_attrs(group.rotation, group.flip_h, group.flip_v),
+        x, y, cx, cy,
+        ch_x, ch_y, ch_cx, ch_cy
+    );
+
+    for item in &group.items {
+        xml.push('\n');
+        match item {
+            GroupItem::Shape(shape) => {
+                let shape_id = shape.id.unwrap_or_else(|| take_id(next_id));
+                xml.push_str(&generate_shape_xml(shape, shape_id));
+            }
+            GroupItem::Group(child) => xml.push_str(&generate_group_xml(child, next_id)),
+        }
+    }
+
+    xml.push_str("\n</p:grpSp>");
+    xml
+}
+
+fn take_id(next_id: &mut u32) -> u32 {
+    let id = *next_id;
+    *next_id += 1;
+    id
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::connectors::{Connector, ConnectorType, ConnectionSite};
+    use crate::generator::shapes::ShapeType;
+
+    fn component() -> ShapeGroup {
+        ShapeGroup::new()
+            .with_name("Service")
+            .add_shape(Shape::new(ShapeType::Rectangle, 1000, 2000, 4000, 1000))
+            .add_shape(Shape::new(ShapeType::Ellipse, 2000, 2500, 1000, 1500))
+    }
+
+    #[test]
+    fn test_child_bounds() {
+        let group = component();
+        assert_eq!(group.child_bounds(), (1000, 2000, 4000, 2000));
+        assert_eq!(group.frame(), (1000, 2000, 4000, 2000));
+        assert_eq!(ShapeGroup::new().child_bounds(), (0, 0, 0, 0));
+    }
+
+    #[test]
+    fn test_group_xml_offsets() {
+        let group = component().with_position(10000, 20000).with_size(8000, 4000).with_rotation(90.0);
+        let mut next_id = 100;
+        let xml = generate_group_xml(&group, &mut next_id);
+
+        assert!(xml.starts_with("<p:grpSp>"));
+        assert!(xml.contains(r#"<p:cNvPr id="100" name="Service"/>"#));
+        assert!(xml.contains(r#"<a:xfrm rot="5400000">"#));
+        assert!(xml.contains(r#"<a:off x="10000" y="20000"/>"#));
+        assert!(xml.contains(r#"<a:ext cx="8000" cy="4000"/>"#));
+        assert!(xml.contains(r#"<a:chOff x="1000" y="2000"/>"#));
+        assert!(xml.contains(r#"<a:chExt cx="4000" cy="2000"/>"#));
+        assert_eq!(xml.matches("<p:sp>").count(), 2);
+        assert_eq!(next_id, 103);
+    }
+
+    #[test]
+    fn test_nested_groups() {
+        let outer = ShapeGroup::new()
+            .add_group(component())
+            .add_shape(Shape::new(ShapeType::Rectangle, 0, 0, 500, 500).with_id(7));
+        let mut next_id = 1;
+        let xml = generate_group_xml(&outer, &mut next_id);
+
+        assert_eq!(xml.matches("<p:grpSp>").count(), 2);
+        assert!(xml.contains(r#"<a:chExt cx="5000" cy="4000"/>"#));
+        assert!(xml.contains(r#"id="7""#));
+        assert_eq!(outer.element_count(), 4);
+        assert_eq!(next_id, 5);
+    }
+
+    #[test]
+    fn test_group_as_connector_target() {
+        let api = component().with_id(40);
+        let db = component().with_id(41).with_position(10000, 2000);
+        let connector = Connector::between(ConnectorType::Elbow, &api, ConnectionSite::Right, &db, ConnectionSite::Left);
+
+        assert_eq!((connector.start_x, connector.start_y), (5000, 3000));
+        assert_eq!((connector.end_x, connector.end_y), (10000, 3000));
+        assert_eq!(connector.start_shape_id, None);
+        assert_eq!(connector.end_shape_id, None);
+    }
+
+    #[test]
+    fn test_group_in_slide() {
+        use crate::generator::slide_content::SlideContent;
+        use crate::generator::slide_xml::create_slide_xml_with_content;
+
+        let slide = SlideContent::new("Architecture").add_group(component());
+        let xml = create_slide_xml_with_content(1, &slide);
+        assert!(xml.contains(r#"name="Service""#));
+        assert!(xml.contains("<a:chOff"));
+    }
+
+    #[test]
+    fn test_map_point() {
+        let group = component().with_position(0, 0).with_size(8000, 4000);
+        assert_eq!(group.map_point(1000, 2000), (0, 0));
+        assert_eq!(group.map_point(3000, 3000), (4000, 2000));
+    }
+}
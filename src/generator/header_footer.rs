@@ -0,0 +1,330 @@
+//! Header/footer placeholders: footer text, slide numbers and dates
+//!
+//! The slide master and layout always define the `dt`, `ftr` and `sldNum`
+//! placeholders; slides get placeholder shapes referencing them when
+//! `PresentationOptions::header_footer` enables them, which is what
+//! PowerPoint's Insert > Header & Footer dialog reads back.
+
+use super::package_xml::escape_xml;
+use super::slide_content::{SlideContent, SlideLayout};
+
+/// Field id of slide number fields
+const SLIDE_NUMBER_FIELD_ID: &str = "{B6F15528-21DE-4FAA-801E-634DDDAF4B2B}";
+/// Field id of date fields
+const DATE_FIELD_ID: &str = "{C764DE79-268F-4C1A-8933-263129D2AF90}";
+
+/// Placeholder geometry on a 4:3 slide: (type, size, layout idx, x, width, alignment)
+const PLACEHOLDERS: [(&str, &str, u32, u32, u32, &str); 3] = [
+    ("dt", "half", 10, 457200, 2133600, "l"),
+    ("ftr", "quarter", 11, 3124200, 2895600, "ctr"),
+    ("sldNum", "quarter", 12, 6553200, 2133600, "r"),
+];
+const PLACEHOLDER_Y: u32 = 6356350;
+const PLACEHOLDER_HEIGHT: u32 = 365125;
+
+/// Display format of an automatically updated date
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+pub enum DateFormat {
+    /// 10/18/2026
+    #[default]
+    Short,
+    /// Sunday, October 18, 2026
+    Long,
+    /// 18 October 2026
+    DayMonthYear,
+    /// October 18, 2026
+    MonthDayYear,
+    /// 18-Oct-26
+    Abbreviated,
+    /// 13:45
+    Time24,
+    /// 1:45 PM
+    Time12,
+}
+
+impl DateFormat {
+    /// Field type (`datetimeN`) PowerPoint uses for this format
+    pub fn field_type(&self) -> &'static str {
+        match self {
+            DateFormat::Short => "datetime1",
+            DateFormat::Long => "datetime2",
+            DateFormat::DayMonthYear => "datetime3",
+            DateFormat::MonthDayYear => "datetime4",
+            DateFormat::Abbreviated => "datetime5",
+            DateFormat::Time24 => "datetime10",
+            DateFormat::Time12 => "datetime12",
+        }
+    }
+
+    /// `chrono` format string for the text cached in the field
+    fn chrono_format(&self) -> &'static str {
+        match self {
+            DateFormat::Short => "%-m/%-d/%Y",
+            DateFormat::Long => "%A, %B %-d, %Y",
+            DateFormat::DayMonthYear => "%-d %B %Y",
+            DateFormat::MonthDayYear => "%B %-d, %Y",
+            DateFormat::Abbreviated => "%-d-%b-%y",
+            DateFormat::Time24 => "%H:%M",
+            DateFormat::Time12 => "%-I:%M %p",
+        }
+    }
+
+    /// Today's date in this format
+    pub fn today(&self) -> String {
+        chrono::Local::now().format(self.chrono_format()).to_string()
+    }
+}
+
+/// Date shown in the date placeholder
+#[derive(Clone, Debug, PartialEq)]
+pub enum DateField {
+    /// Updated automatically when the presentation is opened
+    Auto(DateFormat),
+    /// Fixed text
+    Fixed(String),
+}
+
+impl DateField {
+    fn to_xml(&self) -> String {
+        match self {
+            DateField::Auto(format) => format!(
+                r#"<a:fld id="{DATE_FIELD_ID}" type="{}"><a:rPr lang="en-US"/><a:t>{}</a:t></a:fld>"#,
+                format.field_type(),
+                escape_xml(&format.today())
+            ),
+            DateField::Fixed(text) => format!(r#"<a:r><a:rPr lang="en-US"/><a:t>{}</a:t></a:r>"#, escape_xml(text)),
+        }
+    }
+}
+
+/// Footer text, slide numbers and date shown on every slide
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HeaderFooter {
+    pub footer: Option<String>,
+    pub slide_number: bool,
+    pub date: Option<DateField>,
+    /// Leave title slides (`SlideLayout::CenteredTitle`) without them
+    pub hide_on_title_slide: bool,
+}
+
+impl HeaderFooter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the footer text
+    pub fn footer(mut self, text: &str) -> Self {
+        self.footer = Some(text.to_string());
+        self
+    }
+
+    /// Show slide numbers
+    pub fn slide_numbers(mut self) -> Self {
+        self.slide_number = true;
+        self
+    }
+
+    /// Show a date that updates automatically
+    pub fn auto_date(mut self, format: DateFormat) -> Self {
+        self.date = Some(DateField::Auto(format));
+        self
+    }
+
+    /// Show a fixed date text
+    pub fn fixed_date(mut self, text: &str) -> Self {
+        self.date = Some(DateField::Fixed(text.to_string()));
+        self
+    }
+
+    /// Don't show footer, slide number and date on title slides
+    pub fn hide_on_title_slide(mut self) -> Self {
+        self.hide_on_title_slide = true;
+        self
+    }
+
+    /// Check if a slide shows the placeholders
+    pub fn applies_to(&self, content: &SlideContent) -> bool {
+        !(self.hide_on_title_slide && content.layout == SlideLayout::CenteredTitle)
+    }
+
+    /// Placeholder shapes for a slide, in `dt`, `ftr`, `sldNum` order
+    pub fn slide_placeholders_xml(&self, slide_num: usize) -> String {
+        let mut xml = String::new();
+        for (i, (ph_type, size, idx, _, _, _)) in PLACEHOLDERS.iter().enumerate() {
+            let body = match *ph_type {
+                "dt" => match self.date {
+                    Some(ref date) => date.to_xml(),
+                    None => continue,
+                },
+                "ftr" => match self.footer {
+                    Some(ref text) => format!(r#"<a:r><a:rPr lang="en-US"/><a:t>{}</a:t></a:r>"#, escape_xml(text)),
+                    None => continue,
+                },
+                _ if self.slide_number => format!(
+                    r#"<a:fld id="{SLIDE_NUMBER_FIELD_ID}" type="slidenum"><a:rPr lang="en-US"/><a:t>{slide_num}</a:t></a:fld>"#
+                ),
+                _ => continue,
+            };
+            xml.push_str(&format!(
+                r#"
+<p:sp>
+<p:nvSpPr>
+<p:cNvPr id="{}" name="{} Placeholder {}"/>
+<p:cNvSpPr><a:spLocks noGrp="1"/></p:cNvSpPr>
+<p:nvPr><p:ph type="{ph_type}" sz="{size}" idx="{idx}"/></p:nvPr>
+</p:nvSpPr>
+<p:spPr/>
+<p:txBody>
+<a:bodyPr/>
+<a:lstStyle/>
+<a:p>{body}</a:p>
+</p:txBody>
+</p:sp>"#,
+                9001 + i,
+                placeholder_name(ph_type),
+                i + 1,
+            ));
+        }
+        xml
+    }
+
+    /// `p:hf` element of the slide master, flagging the placeholders in use
+    pub fn master_hf_xml(&self) -> String {
+        let flag = |on: bool| if on { "1" } else { "0" };
+        format!(
+            r#"<p:hf hdr="0" ftr="{}" dt="{}" sldNum="{}"/>"#,
+            flag(self.footer.is_some()),
+            flag(self.date.is_some()),
+            flag(self.slide_number)
+        )
+    }
+
+    /// Add the `p:hf` flags to the slide master XML
+    pub fn apply_to_master(&self, master_xml: &str) -> String {
+        master_xml.replacen("</p:sldMaster>", &format!("{}\n</p:sldMaster>", self.master_hf_xml()), 1)
+    }
+
+    /// Add the placeholder shapes to a generated slide
+    pub fn apply_to_slide(&self, slide_xml: &str, slide_num: usize) -> String {
+        let placeholders = self.slide_placeholders_xml(slide_num);
+        if placeholders.is_empty() {
+            return slide_xml.to_string();
+        }
+        insert_placeholders(slide_xml, &placeholders)
+    }
+}
+
+fn placeholder_name(ph_type: &str) -> &'static str {
+    match ph_type {
+        "dt" => "Date",
+        "ftr" => "Footer",
+        _ => "Slide Number",
+    }
+}
+
+/// Date, footer and slide number placeholders of the slide master
+pub fn master_placeholders_xml() -> String {
+    placeholders_xml(true)
+}
+
+/// Date, footer and slide number placeholders of a slide layout
+pub fn layout_placeholders_xml() -> String {
+    placeholders_xml(false)
+}
+
+fn placeholders_xml(master: bool) -> String {
+    let mut xml = String::new();
+    for (i, (ph_type, size, idx, x, width, align)) in PLACEHOLDERS.iter().enumerate() {
+        let field = match *ph_type {
+            "dt" => format!(r#"<a:fld id="{DATE_FIELD_ID}" type="datetime1"><a:rPr lang="en-US"/><a:t></a:t></a:fld>"#),
+            "sldNum" => format!(r#"<a:fld id="{SLIDE_NUMBER_FIELD_ID}" type="slidenum"><a:rPr lang="en-US"/><a:t>‹#›</a:t></a:fld>"#),
+            _ => String::new(),
+        };
+        // The master positions and styles the placeholders; layouts inherit them
+        let (ph_idx, sp_pr, list_style) = if master {
+            (
+                i + 2,
+                format!(
+                    r#"<p:spPr><a:xfrm><a:off x="{x}" y="{PLACEHOLDER_Y}"/><a:ext cx="{width}" cy="{PLACEHOLDER_HEIGHT}"/></a:xfrm><a:prstGeom prst="rect"><a:avLst/></a:prstGeom></p:spPr>"#
+                ),
+                format!(
+                    r#"<a:lstStyle><a:lvl1pPr algn="{align}"><a:defRPr sz="1200"><a:solidFill><a:schemeClr val="tx1"><a:tint val="75000"/></a:schemeClr></a:solidFill></a:defRPr></a:lvl1pPr></a:lstStyle>"#
+                ),
+            )
+        } else {
+            (*idx as usize, "<p:spPr/>".to_string(), "<a:lstStyle/>".to_string())
+        };
+        xml.push_str(&format!(
+            r#"
+<p:sp>
+<p:nvSpPr>
+<p:cNvPr id="{}" name="{} Placeholder {}"/>
+<p:cNvSpPr><a:spLocks noGrp="1"/></p:cNvSpPr>
+<p:nvPr><p:ph type="{ph_type}" sz="{size}" idx="{ph_idx}"/></p:nvPr>
+</p:nvSpPr>
+{sp_pr}
+<p:txBody>
+<a:bodyPr vert="horz" lIns="91440" tIns="45720" rIns="91440" bIns="45720" rtlCol="0" anchor="ctr"/>
+{list_style}
+<a:p>{field}<a:endParaRPr lang="en-US"/></a:p>
+</p:txBody>
+</p:sp>"#,
+            i + 2,
+            placeholder_name(ph_type),
+            i + 1,
+        ));
+    }
+    xml
+}
+
+/// Insert placeholder shapes at the end of the shape tree
+pub(crate) fn insert_placeholders(xml: &str, placeholders: &str) -> String {
+    match xml.rfind("</p:spTree>") {
+        Some(pos) => format!("{}{placeholders}\n{}", &xml[..pos], &xml[pos..]),
+        None => xml.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slide_placeholders() {
+        let hf = HeaderFooter::new().footer("ACME & Co").slide_numbers().fixed_date("Q3 Review");
+        let xml = hf.slide_placeholders_xml(7);
+
+        assert!(xml.contains(r#"<p:ph type="dt" sz="half" idx="10"/>"#));
+        assert!(xml.contains(r#"<p:ph type="ftr" sz="quarter" idx="11"/>"#));
+        assert!(xml.contains("<a:t>ACME &amp; Co</a:t>"));
+        assert!(xml.contains(r#"type="slidenum"><a:rPr lang="en-US"/><a:t>7</a:t></a:fld>"#));
+        assert!(xml.contains("<a:t>Q3 Review</a:t>"));
+
+        let numbers_only = HeaderFooter::new().slide_numbers().slide_placeholders_xml(1);
+        assert_eq!(numbers_only.matches("<p:sp>").count(), 1);
+        assert!(HeaderFooter::new().slide_placeholders_xml(1).is_empty());
+    }
+
+    #[test]
+    fn test_auto_date_and_title_slides() {
+        let hf = HeaderFooter::new().auto_date(DateFormat::Long).hide_on_title_slide();
+        assert!(hf.slide_placeholders_xml(1).contains(r#"type="datetime2""#));
+
+        let title = SlideContent::new("Welcome").layout(SlideLayout::CenteredTitle);
+        assert!(!hf.applies_to(&title));
+        assert!(hf.applies_to(&SlideContent::new("Agenda")));
+    }
+
+    #[test]
+    fn test_master_placeholders() {
+        let master = master_placeholders_xml();
+        assert!(master.contains(r#"<p:ph type="sldNum" sz="quarter" idx="4"/>"#));
+        assert!(master.contains(r#"<a:lvl1pPr algn="r">"#));
+        let hf = HeaderFooter::new().slide_numbers().apply_to_master(&crate::generator::xml::create_slide_master_xml());
+        assert!(crate::oxml::XmlParser::parse_str(&hf).is_ok());
+        assert!(hf.contains("<p:hf hdr=\"0\" ftr=\"0\" dt=\"0\" sldNum=\"1\"/>\n</p:sldMaster>"));
+        let layout = layout_placeholders_xml();
+        assert!(layout.contains(r#"<p:ph type="dt" sz="half" idx="10"/>"#));
+        assert!(!layout.contains("<a:xfrm>"));
+    }
+}
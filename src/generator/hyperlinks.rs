@@ -3,6 +3,7 @@
 //! Provides hyperlink types for shapes, text, and images.
 
 use crate::core::escape_xml;
+use super::slide_rels::{REL_TYPE_HYPERLINK, REL_TYPE_SLIDE};
 
 /// Hyperlink action types
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -88,9 +89,19 @@ impl HyperlinkAction {
         )
     }
 
+    /// Get the relationship type, or None for show-jump actions that need no relationship
+    pub fn relationship_type(&self) -> Option<&'static str> {
+        match self {
+            HyperlinkAction::Url(_) | HyperlinkAction::Email { .. } | HyperlinkAction::File(_) => Some(REL_TYPE_HYPERLINK),
+            HyperlinkAction::Slide(_) => Some(REL_TYPE_SLIDE),
+            _ => None,
+        }
+    }
+
     /// Get the action type for internal links
     pub fn action_type(&self) -> Option<&'static str> {
         match self {
+            HyperlinkAction::Slide(_) => Some("ppaction://hlinksldjump"),
             HyperlinkAction::FirstSlide => Some("ppaction://hlinkshowjump?jump=firstslide"),
             HyperlinkAction::LastSlide => Some("ppaction://hlinkshowjump?jump=lastslide"),
             HyperlinkAction::NextSlide => Some("ppaction://hlinkshowjump?jump=nextslide"),
@@ -157,6 +168,34 @@ impl Hyperlink {
         self.r_id = Some(r_id.to_string());
         self
     }
+
+    /// Generate the `a:hlinkClick` element using the assigned relationship ID
+    pub fn click_xml(&self) -> String {
+        generate_text_hyperlink_xml(self, self.r_id.as_deref().unwrap_or(""))
+    }
+}
+
+/// Add a hyperlink to run properties (`a:rPr`), as its last child
+pub(crate) fn insert_run_hyperlink(rpr: &str, hyperlink: Option<&Hyperlink>) -> String {
+    let Some(hyperlink) = hyperlink else {
+        return rpr.to_string();
+    };
+    let click = hyperlink.click_xml();
+    if let Some(open) = rpr.strip_suffix("</a:rPr>") {
+        format!("{open}{click}</a:rPr>")
+    } else if let Some(open) = rpr.strip_suffix("/>") {
+        format!("{open}>{click}</a:rPr>")
+    } else {
+        rpr.to_string()
+    }
+}
+
+/// Generate `p:cNvPr` with an optional click action (`name` must already be escaped)
+pub(crate) fn generate_cnvpr_xml(id: impl std::fmt::Display, name: &str, hyperlink: Option<&Hyperlink>) -> String {
+    match hyperlink {
+        Some(link) => format!(r#"<p:cNvPr id="{}" name="{}">{}</p:cNvPr>"#, id, name, link.click_xml()),
+        None => format!(r#"<p:cNvPr id="{}" name="{}"/>"#, id, name),
+    }
 }
 
 /// Generate hyperlink XML for text run
@@ -210,8 +249,9 @@ pub fn generate_hyperlink_relationship_xml(hyperlink: &Hyperlink, r_id: &str) ->
     };
 
     format!(
-        r#"<Relationship Id="{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/hyperlink" Target="{}"{}/>"#,
+        r#"<Relationship Id="{}" Type="{}" Target="{}"{}/>"#,
         r_id,
+        hyperlink.action.relationship_type().unwrap_or(REL_TYPE_HYPERLINK),
         escape_xml(&target),
         target_mode
     )
@@ -285,4 +325,27 @@ mod tests {
         assert!(target.contains("mailto:"));
         assert!(target.contains("subject=Hello"));
     }
+
+    #[test]
+    fn test_slide_jump() {
+        let link = Hyperlink::slide(4).with_r_id("rId5");
+        assert_eq!(link.action.relationship_type(), Some(REL_TYPE_SLIDE));
+        assert!(link.click_xml().contains(r#"r:id="rId5""#));
+        assert!(link.click_xml().contains(r#"action="ppaction://hlinksldjump""#));
+
+        let xml = generate_hyperlink_relationship_xml(&link, "rId5");
+        assert!(xml.contains(r#"Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slide" Target="slide4.xml"/>"#));
+        assert_eq!(HyperlinkAction::NextSlide.relationship_type(), None);
+    }
+
+    #[test]
+    fn test_insert_run_hyperlink() {
+        let link = Hyperlink::url("https://example.com").with_r_id("rId3");
+        let rpr = insert_run_hyperlink(r#"<a:rPr lang="en-US"/>"#, Some(&link));
+        assert_eq!(rpr, r#"<a:rPr lang="en-US"><a:hlinkClick r:id="rId3" highlightClick="1"/></a:rPr>"#);
+
+        let rpr = insert_run_hyperlink(r#"<a:rPr lang="en-US"><a:latin typeface="Arial"/></a:rPr>"#, Some(&link));
+        assert!(rpr.ends_with(r#"<a:latin typeface="Arial"/><a:hlinkClick r:id="rId3" highlightClick="1"/></a:rPr>"#));
+        assert_eq!(insert_run_hyperlink("<a:rPr/>", None), "<a:rPr/>");
+    }
 }
@@ -3,6 +3,10 @@
 //! Handles image metadata, embedding, and XML generation
 
 use std::path::Path;
+use crate::exc::PptxError;
+use super::effects::{ShapeEffects, Shadow, Reflection};
+use super::shapes::ShapeType;
+use super::hyperlinks::Hyperlink;
 
 /// Image data source
 #[derive(Clone, Debug)]
@@ -15,6 +19,68 @@ pub enum ImageSource {
     Bytes(Vec<u8>),
 }
 
+/// Source rectangle cropping, in 1000ths of a percent of each side (100000 = 100%)
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+pub struct ImageCrop {
+    pub left: u32,
+    pub top: u32,
+    pub right: u32,
+    pub bottom: u32,
+}
+
+impl ImageCrop {
+    /// Crop by percent of the image size on each side
+    pub fn percent(left: f64, top: f64, right: f64, bottom: f64) -> Self {
+        let to_value = |p: f64| (p.clamp(0.0, 100.0) * 1000.0).round() as u32;
+        ImageCrop {
+            left: to_value(left),
+            top: to_value(top),
+            right: to_value(right),
+            bottom: to_value(bottom),
+        }
+    }
+
+    /// Crop by pixels, given the pixel size of the image
+    pub fn pixels(left: u32, top: u32, right: u32, bottom: u32, image_size: (u32, u32)) -> Self {
+        let (w, h) = (image_size.0.max(1) as f64, image_size.1.max(1) as f64);
+        Self::percent(
+            left as f64 * 100.0 / w,
+            top as f64 * 100.0 / h,
+            right as f64 * 100.0 / w,
+            bottom as f64 * 100.0 / h,
+        )
+    }
+
+    fn to_xml(self) -> String {
+        let mut attrs = String::new();
+        for (name, value) in [("l", self.left), ("t", self.top), ("r", self.right), ("b", self.bottom)] {
+            if value != 0 {
+                attrs.push_str(&format!(r#" {}="{}""#, name, value));
+            }
+        }
+        format!("<a:srcRect{}/>", attrs)
+    }
+}
+
+/// How the picture fills its frame
+#[derive(Clone, Debug, Copy, PartialEq, Default)]
+pub enum ImageFillMode {
+    /// Stretch the picture to the frame
+    #[default]
+    Stretch,
+    /// Repeat the picture at the given scale (percent of original size)
+    Tile { scale_x: f64, scale_y: f64 },
+}
+
+/// Recolouring applied to the picture
+#[derive(Clone, Debug, PartialEq)]
+pub enum ImageRecolor {
+    /// Greyscale
+    Grayscale,
+    /// Duotone between a dark and a light colour (RGB hex)
+    Duotone(String, String),
+}
+
 /// Image metadata and properties
 #[derive(Clone, Debug)]
 pub struct Image {
@@ -26,6 +92,24 @@ pub struct Image {
     pub format: String,  // PNG, JPG, GIF, etc.
     /// Image data source (file path, base64, or bytes)
     pub source: Option<ImageSource>,
+    /// Shadow, glow, reflection and 3-D effects
+    pub effects: Option<ShapeEffects>,
+    /// Cropping of the source picture
+    pub crop: Option<ImageCrop>,
+    /// Stretch or tile fill
+    pub fill_mode: ImageFillMode,
+    /// Opacity (0-100000, where 100000 = fully opaque)
+    pub alpha: Option<u32>,
+    /// Greyscale or duotone recolouring
+    pub recolor: Option<ImageRecolor>,
+    /// Brightness adjustment (-100 to 100 percent)
+    pub brightness: i32,
+    /// Contrast adjustment (-100 to 100 percent)
+    pub contrast: i32,
+    /// Crop the picture to a preset shape (e.g. a circle for avatars)
+    pub crop_shape: Option<ShapeType>,
+    /// Click action of the picture
+    pub hyperlink: Option<Hyperlink>,
 }
 
 impl Image {
@@ -39,6 +123,15 @@ impl Image {
             y: 0,
             format: format.to_uppercase(),
             source: Some(ImageSource::File(filename.to_string())),
+            effects: None,
+            crop: None,
+            fill_mode: ImageFillMode::Stretch,
+            alpha: None,
+            recolor: None,
+            brightness: 0,
+            contrast: 0,
+            crop_shape: None,
+            hyperlink: None,
         }
     }
     
@@ -66,12 +159,9 @@ impl Image {
         
         Image {
             filename,
-            width,
-            height,
-            x: 0,
-            y: 0,
             format: format_upper,
             source: Some(ImageSource::Base64(data.to_string())),
+            ..Self::new("", width, height, "")
         }
     }
     
@@ -86,12 +176,9 @@ impl Image {
         
         Image {
             filename,
-            width,
-            height,
-            x: 0,
-            y: 0,
             format: format_upper,
             source: Some(ImageSource::Bytes(data)),
+            ..Self::new("", width, height, "")
         }
     }
     
@@ -110,6 +197,25 @@ impl Image {
         }
     }
 
+    /// Check if the image data is available for embedding
+    pub fn has_data(&self) -> bool {
+        match &self.source {
+            Some(ImageSource::Base64(_)) | Some(ImageSource::Bytes(_)) => true,
+            Some(ImageSource::File(path)) => Path::new(path).is_file(),
+            None => false,
+        }
+    }
+
+    /// Get the pixel size of the image by decoding its header
+    pub fn pixel_size(&self) -> Option<(u32, u32)> {
+        let bytes = self.get_bytes()?;
+        image::io::Reader::new(std::io::Cursor::new(bytes))
+            .with_guessed_format()
+            .ok()?
+            .into_dimensions()
+            .ok()
+    }
+
     /// Set image position
     pub fn position(mut self, x: u32, y: u32) -> Self {
         self.x = x;
@@ -117,6 +223,132 @@ impl Image {
         self
     }
 
+    /// Crop the picture by percent of its size on each side
+    pub fn with_crop(mut self, left: f64, top: f64, right: f64, bottom: f64) -> Self {
+        self.crop = Some(ImageCrop::percent(left, top, right, bottom));
+        self
+    }
+
+    /// Crop the picture by pixels on each side
+    ///
+    /// Fails if the image data cannot be decoded to find its pixel size.
+    pub fn with_crop_pixels(mut self, left: u32, top: u32, right: u32, bottom: u32) -> Result<Self, PptxError> {
+        let size = self.pixel_size()
+            .ok_or_else(|| PptxError::InvalidValue(format!("Cannot read pixel size of image {}", self.filename)))?;
+        self.crop = Some(ImageCrop::pixels(left, top, right, bottom, size));
+        Ok(self)
+    }
+
+    /// Tile the picture inside its frame at the given scale (percent)
+    pub fn with_tile(mut self, scale_x: f64, scale_y: f64) -> Self {
+        self.fill_mode = ImageFillMode::Tile { scale_x, scale_y };
+        self
+    }
+
+    /// Set transparency (0-100 percent)
+    pub fn with_transparency(mut self, percent: u32) -> Self {
+        self.alpha = Some((100 - percent.min(100)) * 1000);
+        self
+    }
+
+    /// Render the picture in greyscale
+    pub fn grayscale(mut self) -> Self {
+        self.recolor = Some(ImageRecolor::Grayscale);
+        self
+    }
+
+    /// Recolour the picture as a duotone between two colours
+    pub fn with_duotone(mut self, dark: &str, light: &str) -> Self {
+        self.recolor = Some(ImageRecolor::Duotone(
+            dark.trim_start_matches('#').to_uppercase(),
+            light.trim_start_matches('#').to_uppercase(),
+        ));
+        self
+    }
+
+    /// Adjust brightness (-100 to 100 percent)
+    pub fn with_brightness(mut self, percent: i32) -> Self {
+        self.brightness = percent.clamp(-100, 100);
+        self
+    }
+
+    /// Adjust contrast (-100 to 100 percent)
+    pub fn with_contrast(mut self, percent: i32) -> Self {
+        self.contrast = percent.clamp(-100, 100);
+        self
+    }
+
+    /// Crop the picture to a preset shape, e.g. `ShapeType::Ellipse` for avatars
+    pub fn with_crop_shape(mut self, shape: ShapeType) -> Self {
+        self.crop_shape = Some(shape);
+        self
+    }
+
+    /// Generate the `a:blip` child elements for transparency and recolouring
+    pub(crate) fn blip_effects_xml(&self) -> String {
+        let mut xml = String::new();
+        if let Some(alpha) = self.alpha {
+            xml.push_str(&format!(r#"<a:alphaModFix amt="{}"/>"#, alpha));
+        }
+        match &self.recolor {
+            Some(ImageRecolor::Duotone(dark, light)) => xml.push_str(&format!(
+                r#"<a:duotone><a:srgbClr val="{}"/><a:srgbClr val="{}"/></a:duotone>"#,
+                dark, light
+            )),
+            Some(ImageRecolor::Grayscale) => xml.push_str("<a:grayscl/>"),
+            None => {}
+        }
+        if self.brightness != 0 || self.contrast != 0 {
+            xml.push_str("<a:lum");
+            if self.brightness != 0 {
+                xml.push_str(&format!(r#" bright="{}""#, self.brightness * 1000));
+            }
+            if self.contrast != 0 {
+                xml.push_str(&format!(r#" contrast="{}""#, self.contrast * 1000));
+            }
+            xml.push_str("/>");
+        }
+        xml
+    }
+
+    /// Generate the `a:srcRect` and fill mode elements of `p:blipFill`
+    pub(crate) fn fill_mode_xml(&self) -> String {
+        let mut xml = self.crop.map(ImageCrop::to_xml).unwrap_or_default();
+        match self.fill_mode {
+            ImageFillMode::Stretch => xml.push_str("<a:stretch><a:fillRect/></a:stretch>"),
+            ImageFillMode::Tile { scale_x, scale_y } => xml.push_str(&format!(
+                r#"<a:tile tx="0" ty="0" sx="{}" sy="{}" flip="none" algn="tl"/>"#,
+                (scale_x * 1000.0).round() as i64,
+                (scale_y * 1000.0).round() as i64
+            )),
+        }
+        xml
+    }
+
+    /// Make the picture clickable
+    pub fn with_hyperlink(mut self, hyperlink: Hyperlink) -> Self {
+        self.hyperlink = Some(hyperlink);
+        self
+    }
+
+    /// Set image effects (shadow, glow, soft edges, reflection, bevel)
+    pub fn with_effects(mut self, effects: ShapeEffects) -> Self {
+        self.effects = Some(effects);
+        self
+    }
+
+    /// Add an outer shadow, keeping any other effects
+    pub fn with_shadow(mut self, shadow: Shadow) -> Self {
+        self.effects.get_or_insert_with(ShapeEffects::default).outer_shadow = Some(shadow);
+        self
+    }
+
+    /// Add a reflection, keeping any other effects
+    pub fn with_reflection(mut self, reflection: Reflection) -> Self {
+        self.effects.get_or_insert_with(ShapeEffects::default).reflection = Some(reflection);
+        self
+    }
+
     /// Get aspect ratio
     pub fn aspect_ratio(&self) -> f64 {
         self.width as f64 / self.height as f64
@@ -224,6 +456,7 @@ pub struct ImageBuilder {
     y: u32,
     format: String,
     source: Option<ImageSource>,
+    effects: Option<ShapeEffects>,
 }
 
 impl ImageBuilder {
@@ -243,6 +476,7 @@ impl ImageBuilder {
             y: 0,
             format,
             source: Some(ImageSource::File(filename.to_string())),
+            effects: None,
         }
     }
     
@@ -262,6 +496,7 @@ impl ImageBuilder {
             y: 0,
             format: format_upper,
             source: Some(ImageSource::Base64(data.to_string())),
+            effects: None,
         }
     }
     
@@ -281,6 +516,7 @@ impl ImageBuilder {
             y: 0,
             format: format_upper,
             source: Some(ImageSource::Bytes(data)),
+            effects: None,
         }
     }
 
@@ -313,6 +549,12 @@ impl ImageBuilder {
         self
     }
 
+    /// Set image effects
+    pub fn effects(mut self, effects: ShapeEffects) -> Self {
+        self.effects = Some(effects);
+        self
+    }
+
     /// Build the image
     pub fn build(self) -> Image {
         Image {
@@ -323,6 +565,8 @@ impl ImageBuilder {
             y: self.y,
             format: self.format,
             source: self.source,
+            effects: self.effects,
+            ..Image::new("", 0, 0, "")
         }
     }
 }
@@ -465,4 +709,24 @@ mod tests {
         assert_eq!(img.y, 2000);
         assert_eq!(img.format, "JPEG");
     }
+
+    #[test]
+    fn test_image_crop_pixels() {
+        // 1x1 PNG
+        let png = "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNk+M9QDwADhgGAWjR9awAAAABJRU5ErkJggg==";
+        let img = Image::from_base64(png, 100, 100, "PNG");
+        assert_eq!(img.pixel_size(), Some((1, 1)));
+
+        let cropped = img.with_crop_pixels(0, 0, 1, 0).unwrap();
+        assert_eq!(cropped.crop, Some(ImageCrop { left: 0, top: 0, right: 100000, bottom: 0 }));
+
+        let missing = Image::new("missing.png", 100, 100, "PNG");
+        assert!(missing.with_crop_pixels(1, 1, 1, 1).is_err());
+    }
+
+    #[test]
+    fn test_image_crop_percent() {
+        let crop = ImageCrop::percent(10.0, 0.0, 25.5, 150.0);
+        assert_eq!(crop, ImageCrop { left: 10000, top: 0, right: 25500, bottom: 100000 });
+    }
 }
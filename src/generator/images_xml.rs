@@ -3,43 +3,52 @@
 //! Generates proper PPTX XML for image embedding and display
 
 use crate::generator::images::Image;
+use crate::generator::hyperlinks::generate_cnvpr_xml;
 
 /// Generate image XML for a slide
 pub fn generate_image_xml(image: &Image, shape_id: usize, rel_id: usize) -> String {
     let rel_id_str = format!("rId{}", rel_id);
+    let effects_xml = image.effects.as_ref().map(|e| e.to_xml()).unwrap_or_default();
+    let blip_effects = image.blip_effects_xml();
+    let blip_xml = if blip_effects.is_empty() {
+        format!(r#"<a:blip r:embed="{}"/>"#, rel_id_str)
+    } else {
+        format!(r#"<a:blip r:embed="{}">{}</a:blip>"#, rel_id_str, blip_effects)
+    };
+    let geometry = image.crop_shape.map(|s| s.preset_name()).unwrap_or("rect");
     
     let xml = format!(
         r#"<p:pic>
 <p:nvPicPr>
-<p:cNvPr id="{}" name="{}"/>
+{}
 <p:cNvPicPr>
 <a:picLocks noChangeAspect="1"/>
 </p:cNvPicPr>
 <p:nvPr/>
 </p:nvPicPr>
 <p:blipFill>
-<a:blip r:embed="{}"/>
-<a:stretch>
-<a:fillRect/>
-</a:stretch>
+{}
+{}
 </p:blipFill>
 <p:spPr>
 <a:xfrm>
 <a:off x="{}" y="{}"/>
 <a:ext cx="{}" cy="{}"/>
 </a:xfrm>
-<a:prstGeom prst="rect">
+<a:prstGeom prst="{}">
 <a:avLst/>
-</a:prstGeom>
+</a:prstGeom>{}
 </p:spPr>
 </p:pic>"#,
-        shape_id,
-        escape_xml(&image.filename),
-        rel_id_str,
+        generate_cnvpr_xml(shape_id, &escape_xml(&image.filename), image.hyperlink.as_ref()),
+        blip_xml,
+        image.fill_mode_xml(),
         image.x,
         image.y,
         image.width,
-        image.height
+        image.height,
+        geometry,
+        effects_xml
     );
 
     xml
@@ -86,6 +95,7 @@ fn escape_xml(s: &str) -> String {
 mod tests {
     use super::*;
     use crate::generator::images::Image;
+    use crate::generator::effects::{Shadow, Reflection};
 
     #[test]
     fn test_generate_simple_image_xml() {
@@ -116,6 +126,44 @@ mod tests {
         assert!(xml.contains("cy=\"1080000\""));
     }
 
+    #[test]
+    fn test_generate_image_with_effects() {
+        let img = Image::new("photo.png", 1920000, 1080000, "PNG")
+            .with_shadow(Shadow::new("000000"))
+            .with_reflection(Reflection::new());
+        let xml = generate_image_xml(&img, 1, 1);
+
+        assert!(xml.contains("<a:effectLst><a:outerShdw"));
+        assert!(xml.contains("<a:reflection"));
+        assert!(xml.find("</a:prstGeom>").unwrap() < xml.find("<a:effectLst>").unwrap());
+    }
+
+    #[test]
+    fn test_generate_image_crop_and_recolor() {
+        let img = Image::new("avatar.png", 914400, 914400, "PNG")
+            .with_crop(10.0, 0.0, 10.0, 0.0)
+            .with_transparency(25)
+            .with_duotone("#112233", "ffeedd")
+            .with_brightness(20)
+            .with_contrast(-10)
+            .with_crop_shape(crate::generator::shapes::ShapeType::Ellipse);
+        let xml = generate_image_xml(&img, 1, 2);
+
+        assert!(xml.contains(r#"<a:blip r:embed="rId2"><a:alphaModFix amt="75000"/><a:duotone><a:srgbClr val="112233"/><a:srgbClr val="FFEEDD"/></a:duotone><a:lum bright="20000" contrast="-10000"/></a:blip>"#));
+        assert!(xml.contains(r#"<a:srcRect l="10000" r="10000"/>"#));
+        assert!(xml.contains(r#"<a:prstGeom prst="ellipse">"#));
+    }
+
+    #[test]
+    fn test_generate_image_tile_grayscale() {
+        let img = Image::new("pattern.png", 914400, 914400, "PNG").with_tile(50.0, 50.0).grayscale();
+        let xml = generate_image_xml(&img, 1, 1);
+
+        assert!(xml.contains("<a:grayscl/>"));
+        assert!(xml.contains(r#"<a:tile tx="0" ty="0" sx="50000" sy="50000" flip="none" algn="tl"/>"#));
+        assert!(!xml.contains("<a:stretch>"));
+    }
+
     #[test]
     fn test_generate_image_relationship() {
         let rel = generate_image_relationship(1, "../media/image1.png");
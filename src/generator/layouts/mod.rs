@@ -25,7 +25,7 @@ pub fn create_slide_xml_for_layout(content: &SlideContent) -> String {
         SlideLayout::TitleOnly => TitleOnlyLayout::generate(content),
         SlideLayout::CenteredTitle => CenteredTitleLayout::generate(content),
         SlideLayout::TitleAndBigContent => TitleBigContentLayout::generate(content),
-        SlideLayout::TwoColumn => TwoColumnLayout::generate(content),
+        SlideLayout::TwoColumn | SlideLayout::Comparison => TwoColumnLayout::generate(content),
         SlideLayout::TitleAndContent => TitleContentLayout::generate(content),
     }
 }
@@ -8,7 +8,7 @@ pub struct TwoColumnLayout;
 
 impl TwoColumnLayout {
     /// Generate two-column slide XML
-    /// Bullets are automatically split between left and right columns
+    /// Bullets are split at the slide's column break, or evenly between the columns
     pub fn generate(content: &SlideContent) -> String {
         let title_size = content.title_size.unwrap_or(44) * 100;
         let content_size = content.content_size.unwrap_or(24) * 100;
@@ -39,10 +39,11 @@ impl TwoColumnLayout {
         let bullet_count = if use_styled_bullets { content.bullets.len() } else { content.content.len() };
         
         if bullet_count > 0 {
-            let mid = bullet_count.div_ceil(2);
+            let mid = content.column_break.unwrap_or(bullet_count.div_ceil(2)).min(bullet_count);
 
             // Left column
-            builder = builder.raw(r#"
+            if mid > 0 {
+                builder = builder.raw(r#"
 <p:sp>
 <p:nvSpPr>
 <p:cNvPr id="3" name="Left Content"/>
@@ -62,16 +63,17 @@ impl TwoColumnLayout {
 <a:lstStyle/>
 "#);
 
-            if use_styled_bullets {
-                for bullet in &content.bullets[..mid] {
-                    builder = builder.add_bullet_with_style(&bullet.text, &content_props, bullet.level, bullet.style);
-                }
-            } else {
-                for bullet in &content.content[..mid] {
-                    builder = builder.add_bullet_with_style(bullet, &content_props, 0, content.bullet_style);
+                if use_styled_bullets {
+                    for bullet in &content.bullets[..mid] {
+                        builder = builder.add_bullet_with_style(&bullet.text, &content_props, bullet.level, bullet.style);
+                    }
+                } else {
+                    for bullet in &content.content[..mid] {
+                        builder = builder.add_bullet_with_style(bullet, &content_props, 0, content.bullet_style);
+                    }
                 }
+                builder = builder.raw("</p:txBody>\n</p:sp>\n");
             }
-            builder = builder.raw("</p:txBody>\n</p:sp>\n");
 
             // Right column
             if mid < bullet_count {
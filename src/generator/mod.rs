@@ -24,6 +24,9 @@ pub mod text;
 
 pub mod shapes;
 pub mod shapes_xml;
+pub mod effects;
+pub mod groups;
+pub mod freeform;
 
 // Table module (modularized)
 pub mod table;
@@ -33,6 +36,7 @@ pub mod tables_xml;
 
 pub mod images;
 pub mod images_xml;
+pub mod slide_rels;
 
 // Charts module (modularized)
 #[path = "charts/mod.rs"]
@@ -49,23 +53,40 @@ pub mod connectors;
 pub mod hyperlinks;
 pub mod gradients;
 pub mod media;
-
-pub use builder::{create_pptx, create_pptx_with_content};
-pub use notes_xml::{create_notes_xml, create_notes_rels_xml, create_notes_master_xml, create_notes_master_rels_xml};
+pub mod background;
+pub mod header_footer;
+pub mod omml;
+pub mod template;
+
+pub use builder::{create_pptx, create_pptx_with_content, create_pptx_with_options, PresentationOptions};
+pub use notes_xml::{
+    create_notes_xml, create_notes_rels_xml, create_rich_notes_xml, create_rich_notes_rels_xml,
+    create_notes_master_xml, create_notes_master_rels_xml,
+};
 pub use xml::{SlideContent, SlideLayout};
-pub use slide_content::{CodeBlock, BulletStyle, BulletPoint, BulletTextFormat};
-pub use text::{TextFormat, FormattedText, TextFrame, Paragraph, Run, TextAlign, TextAnchor};
+pub use slide_content::{CodeBlock, Equation, ImageSide, BulletStyle, BulletPoint, BulletTextFormat, OverflowElement, OverflowWarning, check_overflow, paginate_slides};
+pub use text::{TextFormat, FormattedText, TextFrame, Paragraph, Run, TextAlign, TextAnchor, FontMetrics, TextMeasurer, TextBox};
 pub use shapes::{Shape, ShapeType, ShapeFill, ShapeLine, GradientFill as ShapeGradientFill, GradientStop as ShapeGradientStop, GradientDirection as ShapeGradientDirection, FillType, emu_to_inches, inches_to_emu, cm_to_emu};
+pub use effects::{ShapeEffects, Shadow, Glow, Reflection, Bevel, BevelType, CameraPreset};
+pub use freeform::{CustomGeometry, GeometryPath, PathBuilder, PathCommand, PathFill, Guide};
+pub use groups::{ShapeGroup, GroupItem, generate_group_xml};
 pub use shapes_xml::{generate_shape_xml, generate_shapes_xml, generate_connector_xml};
-pub use tables::{Table, TableRow, TableCell, TableBuilder, CellAlign, CellVAlign};
-pub use images::{Image, ImageBuilder, ImageSource};
+pub use table::{
+    Table, TableRow, TableCell, TableBuilder, TablePagination, CellAlign, CellVAlign,
+    BorderStyle, CellBorder, CellBorders, CellMargins, TableStyleOptions,
+    ColorRule, Condition, NumberFormat, TableStyle, TableStylePart, TableStyleBorders,
+};
+pub use images::{Image, ImageBuilder, ImageSource, ImageCrop, ImageFillMode, ImageRecolor};
 pub use images_xml::{generate_image_xml, generate_image_relationship, generate_image_content_type};
-pub use charts::{Chart, ChartType, ChartSeries, ChartBuilder, generate_chart_xml};
+pub use charts::{Chart, ChartType, ChartSeries, ChartBuilder, ValueAxis, generate_chart_xml, generate_chart_part_xml, generate_chart_frame_xml};
 
 // New element exports
-pub use connectors::{Connector, ConnectorType, ConnectorLine, ArrowType, ArrowSize, ConnectionSite, LineDash, generate_connector_xml as generate_cxn_xml};
+pub use connectors::{Connector, ConnectorTarget, ConnectorType, ConnectorLine, ArrowType, ArrowSize, ConnectionSite, LineDash, generate_connector_xml as generate_cxn_xml};
 pub use hyperlinks::{Hyperlink, HyperlinkAction, generate_text_hyperlink_xml, generate_shape_hyperlink_xml, generate_hyperlink_relationship_xml};
+pub use background::{Background, PatternType};
+pub use header_footer::{HeaderFooter, DateField, DateFormat};
 pub use gradients::{GradientFill, GradientType, GradientDirection, GradientStop, PresetGradients, generate_gradient_fill_xml};
+pub use omml::{MathExpr, latex_to_omml};
 pub use media::{Video, Audio, VideoFormat, AudioFormat, VideoOptions, AudioOptions, generate_video_xml, generate_audio_xml};
 
 #[cfg(test)]
@@ -1,6 +1,8 @@
 //! Notes XML generation for speaker notes
 
 use crate::core::escape_xml;
+use super::hyperlinks::{Hyperlink, HyperlinkAction};
+use super::text::TextFrame;
 
 /// Generate a proper GUID for field IDs
 /// Format: {XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX}
@@ -9,9 +11,28 @@ fn generate_field_guid(slide_num: usize) -> String {
     format!("{{B0E4A5D7-2C3F-4A8B-9E1D-{:012X}}}", slide_num)
 }
 
-/// Generate notes slide XML for speaker notes
+/// Generate notes slide XML for speaker notes (one paragraph per line)
 pub fn create_notes_xml(slide_num: usize, notes_text: &str) -> String {
-    let escaped_notes = escape_xml(notes_text);
+    create_rich_notes_xml(slide_num, &TextFrame::from(notes_text))
+}
+
+/// Generate notes slide XML for formatted speaker notes
+///
+/// Hyperlinks are numbered after the slide and notes master relationships,
+/// matching [`create_rich_notes_rels_xml`].
+pub fn create_rich_notes_xml(slide_num: usize, notes: &TextFrame) -> String {
+    let links = notes_links(notes);
+    let mut notes = notes.clone();
+    for hyperlink in notes.hyperlinks_mut() {
+        if let Some(index) = notes_link(hyperlink).and_then(|link| links.iter().position(|l| *l == link)) {
+            hyperlink.r_id = Some(format!("rId{}", index + 3));
+        }
+    }
+    let paragraphs: String = if notes.paragraphs.is_empty() {
+        "<a:p/>".to_string()
+    } else {
+        notes.paragraphs.iter().map(|p| p.to_xml()).collect()
+    };
     let field_guid = generate_field_guid(slide_num);
     
     format!(r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
@@ -57,12 +78,7 @@ pub fn create_notes_xml(slide_num: usize, notes_text: &str) -> String {
 <p:txBody>
 <a:bodyPr/>
 <a:lstStyle/>
-<a:p>
-<a:r>
-<a:rPr lang="en-US" dirty="0"/>
-<a:t>{escaped_notes}</a:t>
-</a:r>
-</a:p>
+{paragraphs}
 </p:txBody>
 </p:sp>
 <p:sp>
@@ -98,11 +114,47 @@ pub fn create_notes_xml(slide_num: usize, notes_text: &str) -> String {
 
 /// Generate notes slide relationship XML
 pub fn create_notes_rels_xml(slide_num: usize) -> String {
-    format!(r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+    create_rich_notes_rels_xml(slide_num, &TextFrame::new())
+}
+
+/// Generate notes slide relationship XML, including the hyperlinks of the notes
+pub fn create_rich_notes_rels_xml(slide_num: usize, notes: &TextFrame) -> String {
+    let mut xml = format!(r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
 <Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
 <Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slide" Target="../slides/slide{slide_num}.xml"/>
-<Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/notesMaster" Target="../notesMasters/notesMaster1.xml"/>
-</Relationships>"#)
+<Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/notesMaster" Target="../notesMasters/notesMaster1.xml"/>"#);
+    for (i, (rel_type, target, external)) in notes_links(notes).iter().enumerate() {
+        let mode = if *external { r#" TargetMode="External""# } else { "" };
+        xml.push_str(&format!(
+            "\n<Relationship Id=\"rId{}\" Type=\"{rel_type}\" Target=\"{}\"{mode}/>",
+            i + 3,
+            escape_xml(target)
+        ));
+    }
+    xml.push_str("\n</Relationships>");
+    xml
+}
+
+/// Relationship (type, target, external) of a hyperlink in the notes; slide
+/// targets are relative to `ppt/notesSlides`
+fn notes_link(hyperlink: &Hyperlink) -> Option<(&'static str, String, bool)> {
+    let rel_type = hyperlink.action.relationship_type()?;
+    let target = match hyperlink.action {
+        HyperlinkAction::Slide(num) => format!("../slides/slide{num}.xml"),
+        ref action => action.relationship_target(),
+    };
+    Some((rel_type, target, hyperlink.action.is_external()))
+}
+
+/// Distinct hyperlink relationships of the notes, in order of appearance
+fn notes_links(notes: &TextFrame) -> Vec<(&'static str, String, bool)> {
+    let mut links = Vec::new();
+    for link in notes.hyperlinks().into_iter().filter_map(notes_link) {
+        if !links.contains(&link) {
+            links.push(link);
+        }
+    }
+    links
 }
 
 /// Generate notes master XML
@@ -265,6 +317,33 @@ mod tests {
         assert!(xml.contains("&quot;chars&quot;"));
     }
 
+    #[test]
+    fn test_create_rich_notes_xml() {
+        use crate::generator::text::{Paragraph, Run};
+
+        let notes = TextFrame::new()
+            .add_paragraph(Paragraph::new().add_run(Run::new("Key point").bold()).bullet())
+            .add_paragraph(Paragraph::new().add_run(Run::new("Docs").with_hyperlink(Hyperlink::url("https://docs.rs"))))
+            .add_paragraph(Paragraph::new().add_run(Run::new("Back").with_hyperlink(Hyperlink::slide(2))))
+            .add_paragraph(Paragraph::new().add_run(Run::new("Again").with_hyperlink(Hyperlink::url("https://docs.rs"))));
+        let xml = create_rich_notes_xml(4, &notes);
+        assert!(xml.contains(r#"b="1""#));
+        assert!(xml.contains(r#"<a:buChar char="•"/>"#));
+        assert_eq!(xml.matches(r#"r:id="rId3""#).count(), 2);
+        assert!(xml.contains(r#"r:id="rId4""#));
+
+        let rels = create_rich_notes_rels_xml(4, &notes);
+        assert!(rels.contains(r#"Id="rId3" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/hyperlink" Target="https://docs.rs" TargetMode="External""#));
+        assert!(rels.contains(r#"Id="rId4" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slide" Target="../slides/slide2.xml"/>"#));
+        assert!(!rels.contains("rId5"));
+    }
+
+    #[test]
+    fn test_create_notes_xml_multiline() {
+        let xml = create_notes_xml(1, "First\nSecond");
+        assert!(xml.contains("First</a:t></a:r></a:p><a:p>"));
+    }
+
     #[test]
     fn test_create_notes_rels_xml() {
         let xml = create_notes_rels_xml(3);
@@ -0,0 +1,987 @@
+//! LaTeX math to Office Math (OMML)
+//!
+//! Converts the LaTeX most slides need (fractions, sub/superscripts, roots,
+//! sums and integrals with limits, matrices, functions, accents, Greek letters
+//! and operators) into `m:oMath` markup that PowerPoint keeps editable, and
+//! into a linear Unicode form for viewers without math support.
+
+use crate::exc::PptxError;
+use crate::generator::package_xml::escape_xml;
+
+/// Office Math namespace (`m:`)
+pub const MATH_NS: &str = "http://schemas.openxmlformats.org/officeDocument/2006/math";
+
+/// Font PowerPoint draws math with
+pub const MATH_FONT: &str = "Cambria Math";
+
+const GREEK: &[(&str, char)] = &[
+    ("alpha", 'α'), ("beta", 'β'), ("gamma", 'γ'), ("delta", 'δ'), ("epsilon", 'ϵ'),
+    ("varepsilon", 'ε'), ("zeta", 'ζ'), ("eta", 'η'), ("theta", 'θ'), ("vartheta", 'ϑ'),
+    ("iota", 'ι'), ("kappa", 'κ'), ("lambda", 'λ'), ("mu", 'μ'), ("nu", 'ν'), ("xi", 'ξ'),
+    ("omicron", 'ο'), ("pi", 'π'), ("varpi", 'ϖ'), ("rho", 'ρ'), ("varrho", 'ϱ'),
+    ("sigma", 'σ'), ("varsigma", 'ς'), ("tau", 'τ'), ("upsilon", 'υ'), ("phi", 'ϕ'),
+    ("varphi", 'φ'), ("chi", 'χ'), ("psi", 'ψ'), ("omega", 'ω'),
+    ("Gamma", 'Γ'), ("Delta", 'Δ'), ("Theta", 'Θ'), ("Lambda", 'Λ'), ("Xi", 'Ξ'), ("Pi", 'Π'),
+    ("Sigma", 'Σ'), ("Upsilon", 'Υ'), ("Phi", 'Φ'), ("Psi", 'Ψ'), ("Omega", 'Ω'),
+];
+
+/// Relations; like `+` and `-` they end the body of a sum or function
+const RELATIONS: &[(&str, &str)] = &[
+    ("leq", "≤"), ("le", "≤"), ("geq", "≥"), ("ge", "≥"), ("neq", "≠"), ("ne", "≠"),
+    ("approx", "≈"), ("equiv", "≡"), ("sim", "∼"), ("simeq", "≃"), ("cong", "≅"),
+    ("propto", "∝"), ("ll", "≪"), ("gg", "≫"), ("subset", "⊂"), ("supset", "⊃"),
+    ("subseteq", "⊆"), ("supseteq", "⊇"), ("in", "∈"), ("notin", "∉"), ("ni", "∋"),
+    ("perp", "⊥"), ("parallel", "∥"), ("mid", "∣"), ("to", "→"), ("rightarrow", "→"),
+    ("leftarrow", "←"), ("gets", "←"), ("leftrightarrow", "↔"), ("Rightarrow", "⇒"),
+    ("Leftarrow", "⇐"), ("Leftrightarrow", "⇔"), ("implies", "⟹"), ("impliedby", "⟸"),
+    ("iff", "⟺"), ("mapsto", "↦"), ("longrightarrow", "⟶"), ("longleftarrow", "⟵"),
+    ("pm", "±"), ("mp", "∓"),
+];
+
+const SYMBOLS: &[(&str, &str)] = &[
+    ("times", "×"), ("div", "÷"), ("cdot", "⋅"), ("ast", "∗"), ("star", "⋆"), ("circ", "∘"),
+    ("bullet", "∙"), ("oplus", "⊕"), ("ominus", "⊖"), ("otimes", "⊗"), ("odot", "⊙"),
+    ("cup", "∪"), ("cap", "∩"), ("setminus", "∖"), ("wedge", "∧"), ("land", "∧"),
+    ("vee", "∨"), ("lor", "∨"), ("neg", "¬"), ("lnot", "¬"), ("uparrow", "↑"),
+    ("downarrow", "↓"), ("infty", "∞"), ("partial", "∂"), ("nabla", "∇"), ("forall", "∀"),
+    ("exists", "∃"), ("nexists", "∄"), ("emptyset", "∅"), ("varnothing", "∅"), ("hbar", "ℏ"),
+    ("ell", "ℓ"), ("Re", "ℜ"), ("Im", "ℑ"), ("aleph", "ℵ"), ("angle", "∠"),
+    ("triangle", "△"), ("prime", "′"), ("cdots", "⋯"), ("ldots", "…"), ("dots", "…"),
+    ("vdots", "⋮"), ("ddots", "⋱"), ("langle", "⟨"), ("rangle", "⟩"), ("lfloor", "⌊"),
+    ("rfloor", "⌋"), ("lceil", "⌈"), ("rceil", "⌉"), ("vert", "|"), ("Vert", "‖"),
+    ("therefore", "∴"), ("because", "∵"), ("colon", ":"), ("bmod", "mod"),
+];
+
+const NARY: &[(&str, char)] = &[
+    ("sum", '∑'), ("prod", '∏'), ("coprod", '∐'), ("int", '∫'), ("iint", '∬'), ("iiint", '∭'),
+    ("oint", '∮'), ("bigcup", '⋃'), ("bigcap", '⋂'), ("bigoplus", '⨁'), ("bigotimes", '⨂'),
+    ("bigvee", '⋁'), ("bigwedge", '⋀'),
+];
+
+const FUNCTIONS: &[&str] = &[
+    "sin", "cos", "tan", "cot", "sec", "csc", "arcsin", "arccos", "arctan", "sinh", "cosh",
+    "tanh", "coth", "log", "ln", "lg", "exp", "dim", "ker", "deg", "arg", "hom",
+];
+
+/// Functions whose subscript goes below the name
+const LIMIT_FUNCTIONS: &[(&str, &str)] = &[
+    ("lim", "lim"), ("liminf", "lim inf"), ("limsup", "lim sup"), ("max", "max"), ("min", "min"),
+    ("sup", "sup"), ("inf", "inf"), ("det", "det"), ("gcd", "gcd"), ("Pr", "Pr"),
+];
+
+const ACCENTS: &[(&str, char)] = &[
+    ("hat", '\u{302}'), ("widehat", '\u{302}'), ("vec", '\u{20D7}'), ("dot", '\u{307}'),
+    ("ddot", '\u{308}'), ("tilde", '\u{303}'), ("widetilde", '\u{303}'), ("bar", '\u{305}'),
+    ("check", '\u{30C}'), ("breve", '\u{306}'), ("acute", '\u{301}'), ("grave", '\u{300}'),
+];
+
+/// How the characters of a run are drawn
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Style {
+    /// Math italic, for variables
+    Italic,
+    /// Upright: digits, operators, function names, `\mathrm`
+    Upright,
+    /// Normal text (`\text`)
+    Text,
+    Bold,
+    DoubleStruck,
+    Script,
+    Fraktur,
+}
+
+#[derive(Clone, Debug)]
+enum Node {
+    Run(String, Style),
+    Frac { num: Vec<Node>, den: Vec<Node>, bar: bool },
+    Scripts { base: Vec<Node>, sub: Option<Vec<Node>>, sup: Option<Vec<Node>> },
+    Root { degree: Option<Vec<Node>>, body: Vec<Node> },
+    Nary { op: char, sub: Option<Vec<Node>>, sup: Option<Vec<Node>>, body: Vec<Node> },
+    Delim { open: String, close: String, body: Vec<Node> },
+    Matrix { rows: Vec<Vec<Vec<Node>>>, left: bool },
+    /// Rows of equations (`aligned`, or `\\` at the top level)
+    Lines(Vec<Vec<Node>>),
+    Func { name: Vec<Node>, body: Vec<Node> },
+    /// Limit above or below a base (`\lim_{x \to 0}`, `\overset`)
+    Limit { base: Vec<Node>, limit: Vec<Node>, upper: bool },
+    Accent { chr: char, body: Vec<Node> },
+    Bar { top: bool, body: Vec<Node> },
+}
+
+/// LaTeX math, parsed for conversion
+#[derive(Clone, Debug)]
+pub struct MathExpr {
+    nodes: Vec<Node>,
+}
+
+impl MathExpr {
+    /// Parse LaTeX math, without the `$` delimiters
+    pub fn parse(latex: &str) -> Result<Self, PptxError> {
+        let mut parser = Parser { chars: latex.chars().collect(), pos: 0 };
+        let mut rows = parser.rows()?;
+        parser.finish()?;
+        let mut lines: Vec<Vec<Node>> = rows.iter_mut().map(|row| row.drain(..).flatten().collect()).collect();
+        let nodes = if lines.len() == 1 { lines.remove(0) } else { vec![Node::Lines(lines)] };
+        Ok(Self { nodes })
+    }
+
+    /// `m:oMath` element with runs at `font_size` points; the `m` prefix is
+    /// left for the enclosing element to declare
+    pub fn to_omml(&self, font_size: u32) -> String {
+        let mut writer = Writer { size: font_size * 100, xml: String::new() };
+        writer.xml.push_str("<m:oMath>");
+        writer.nodes(&self.nodes);
+        writer.xml.push_str("</m:oMath>");
+        writer.xml
+    }
+
+    /// Plain Unicode rendering (`a/b`, `x^2`, `√x`), one line per row
+    pub fn to_linear(&self) -> String {
+        linear(&self.nodes)
+    }
+}
+
+/// Convert LaTeX math to an `m:oMath` element with runs at `font_size` points
+pub fn latex_to_omml(latex: &str, font_size: u32) -> Result<String, PptxError> {
+    Ok(MathExpr::parse(latex)?.to_omml(font_size))
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn error(&self, pos: usize, message: &str) -> PptxError {
+        PptxError::InvalidValue(format!("LaTeX: {message} at column {}", pos + 1))
+    }
+
+    fn skip_space(&mut self) {
+        while self.chars.get(self.pos).is_some_and(|c| c.is_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_space();
+        self.chars.get(self.pos).copied()
+    }
+
+    /// Name of the command at the current position, if any
+    fn peek_command(&mut self) -> Option<String> {
+        let start = self.pos;
+        let name = (self.peek() == Some('\\')).then(|| {
+            self.pos += 1;
+            self.command_name()
+        });
+        self.pos = start;
+        name
+    }
+
+    /// Command name after a backslash: letters, or one other character
+    fn command_name(&mut self) -> String {
+        let start = self.pos;
+        while self.chars.get(self.pos).is_some_and(|c| c.is_ascii_alphabetic()) {
+            self.pos += 1;
+        }
+        if self.pos == start && self.pos < self.chars.len() {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    fn eat_command(&mut self, name: &str) -> bool {
+        self.skip_space();
+        let found = self.peek_command().as_deref() == Some(name);
+        if found {
+            self.pos += 1 + name.chars().count();
+        }
+        found
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), PptxError> {
+        if self.peek() != Some(c) {
+            return Err(self.error(self.pos, &format!("expected {c}")));
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    /// Error for whatever stopped the top-level expression early
+    fn finish(&mut self) -> Result<(), PptxError> {
+        match self.peek() {
+            None => Ok(()),
+            Some('}') => Err(self.error(self.pos, "unmatched }")),
+            _ => match self.peek_command().as_deref() {
+                Some("right") => Err(self.error(self.pos, "\\right without \\left")),
+                Some("end") => Err(self.error(self.pos, "\\end without \\begin")),
+                _ => Err(self.error(self.pos, "unexpected input")),
+            },
+        }
+    }
+
+    /// Cells split by `&` in rows split by `\\`; a trailing `\\` adds no row
+    fn rows(&mut self) -> Result<Vec<Vec<Vec<Node>>>, PptxError> {
+        let mut rows = Vec::new();
+        let mut row = Vec::new();
+        loop {
+            row.push(self.sequence(None)?);
+            if self.peek() == Some('&') {
+                self.pos += 1;
+            } else if self.eat_command("\\") || self.eat_command("cr") {
+                rows.push(std::mem::take(&mut row));
+            } else {
+                break;
+            }
+        }
+        if rows.is_empty() || row.len() > 1 || row.first().is_some_and(|cell| !cell.is_empty()) {
+            rows.push(row);
+        }
+        Ok(rows)
+    }
+
+    /// Items up to a closing brace, `&`, `\\`, `\right`, `\end` or `stop`
+    fn sequence(&mut self, stop: Option<char>) -> Result<Vec<Node>, PptxError> {
+        let mut nodes = Vec::new();
+        loop {
+            match self.peek() {
+                None | Some('}' | '&') => break,
+                Some(c) if Some(c) == stop => break,
+                Some('\\') if matches!(self.peek_command().as_deref(), Some("\\" | "cr" | "right" | "end")) => break,
+                _ => nodes.extend(self.scripted()?),
+            }
+        }
+        Ok(nodes)
+    }
+
+    /// Body of a sum or function: items up to the next `+`, `-` or relation
+    /// outside brackets
+    fn operand(&mut self) -> Result<Vec<Node>, PptxError> {
+        let mut nodes = Vec::new();
+        let mut depth = 0;
+        loop {
+            let stop = match self.peek() {
+                None | Some('}' | '&') => true,
+                Some('(' | '[') => {
+                    depth += 1;
+                    false
+                }
+                Some(')' | ']') if depth == 0 => true,
+                Some(')' | ']') => {
+                    depth -= 1;
+                    false
+                }
+                Some('+' | '-' | '=' | '<' | '>' | ',' | ';') => depth == 0 && !nodes.is_empty(),
+                Some('\\') => match self.peek_command().as_deref() {
+                    Some("\\" | "cr" | "right" | "end") => true,
+                    Some(name) => depth == 0 && !nodes.is_empty() && RELATIONS.iter().any(|(n, _)| *n == name),
+                    None => false,
+                },
+                Some(_) => false,
+            };
+            if stop {
+                break;
+            }
+            nodes.extend(self.scripted()?);
+        }
+        Ok(nodes)
+    }
+
+    /// An item with its subscript, superscript and primes
+    fn scripted(&mut self) -> Result<Vec<Node>, PptxError> {
+        let base = self.item()?;
+        let (mut sub, mut sup): (Option<Vec<Node>>, Option<Vec<Node>>) = (None, None);
+        let mut explicit_sup = false;
+        loop {
+            let start = self.pos;
+            match self.peek() {
+                Some('_') if sub.is_some() => return Err(self.error(self.pos, "double subscript")),
+                Some('_') => {
+                    self.pos += 1;
+                    sub = Some(self.item()?);
+                }
+                Some('^') if explicit_sup => return Err(self.error(self.pos, "double superscript")),
+                Some('^') => {
+                    self.pos += 1;
+                    let script = self.item()?;
+                    sup.get_or_insert_with(Vec::new).extend(script);
+                    explicit_sup = true;
+                }
+                Some('\'') if !explicit_sup => {
+                    self.pos += 1;
+                    sup.get_or_insert_with(Vec::new).push(Node::Run("′".to_string(), Style::Upright));
+                }
+                _ => {
+                    self.pos = start;
+                    break;
+                }
+            }
+        }
+        if sub.is_none() && sup.is_none() {
+            Ok(base)
+        } else {
+            Ok(vec![Node::Scripts { base, sub, sup }])
+        }
+    }
+
+    /// One character, group or command; scripts with no base get an empty one
+    fn item(&mut self) -> Result<Vec<Node>, PptxError> {
+        let start = self.pos;
+        match self.peek() {
+            None => Err(self.error(self.pos, "missing argument")),
+            Some('{') => {
+                self.pos += 1;
+                let nodes = self.sequence(None)?;
+                if self.peek() != Some('}') {
+                    return Err(self.error(start, "missing }"));
+                }
+                self.pos += 1;
+                Ok(nodes)
+            }
+            Some('}') => Err(self.error(self.pos, "unmatched }")),
+            Some('&') => Err(self.error(self.pos, "unexpected &")),
+            Some('_' | '^') => Ok(Vec::new()),
+            Some('\\') => {
+                self.pos += 1;
+                self.command(start)
+            }
+            Some(c) => {
+                self.pos += 1;
+                Ok(vec![char_node(c)])
+            }
+        }
+    }
+
+    fn command(&mut self, start: usize) -> Result<Vec<Node>, PptxError> {
+        let name = self.command_name();
+        let run = |text: &str, style| Ok(vec![Node::Run(text.to_string(), style)]);
+        match name.as_str() {
+            "," => run("\u{2009}", Style::Upright),
+            ":" | ">" => run("\u{205F}", Style::Upright),
+            ";" => run("\u{2004}", Style::Upright),
+            " " => run(" ", Style::Upright),
+            "quad" => run("\u{2003}", Style::Upright),
+            "qquad" => run("\u{2003}\u{2003}", Style::Upright),
+            "!" | "limits" | "nolimits" | "displaystyle" | "textstyle" | "scriptstyle" => Ok(Vec::new()),
+            "{" | "}" | "%" | "$" | "#" | "&" | "_" => run(&name, Style::Upright),
+            "|" => run("‖", Style::Upright),
+            "frac" | "dfrac" | "tfrac" | "cfrac" => {
+                let num = self.item()?;
+                let den = self.item()?;
+                Ok(vec![Node::Frac { num, den, bar: true }])
+            }
+            "binom" | "dbinom" | "tbinom" => {
+                let num = self.item()?;
+                let den = self.item()?;
+                let body = vec![Node::Frac { num, den, bar: false }];
+                Ok(vec![Node::Delim { open: "(".to_string(), close: ")".to_string(), body }])
+            }
+            "sqrt" => {
+                let degree = if self.peek() == Some('[') {
+                    self.pos += 1;
+                    let degree = self.sequence(Some(']'))?;
+                    self.expect(']')?;
+                    Some(degree)
+                } else {
+                    None
+                };
+                let body = self.item()?;
+                Ok(vec![Node::Root { degree, body }])
+            }
+            "left" => {
+                let open = self.delimiter()?;
+                let body = self.sequence(None)?;
+                if !self.eat_command("right") {
+                    return Err(self.error(start, "\\left without \\right"));
+                }
+                let close = self.delimiter()?;
+                Ok(vec![Node::Delim { open, close, body }])
+            }
+            "right" => Err(self.error(start, "\\right without \\left")),
+            "begin" => self.environment(start),
+            "end" => Err(self.error(start, "\\end without \\begin")),
+            "\\" | "cr" => Err(self.error(start, "unexpected line break")),
+            "text" | "textrm" | "textnormal" | "textit" | "mbox" => run(&self.text_arg()?, Style::Text),
+            "textbf" => run(&self.text_arg()?, Style::Bold),
+            "operatorname" => {
+                let limits = self.peek() == Some('*');
+                if limits {
+                    self.pos += 1;
+                }
+                let name = vec![Node::Run(self.text_arg()?, Style::Upright)];
+                self.function(name, limits)
+            }
+            "mathrm" | "mathsf" | "mathtt" => self.styled(Style::Upright),
+            "mathit" => self.styled(Style::Italic),
+            "mathbf" | "boldsymbol" | "bm" => self.styled(Style::Bold),
+            "mathbb" => self.styled(Style::DoubleStruck),
+            "mathcal" | "mathscr" => self.styled(Style::Script),
+            "mathfrak" => self.styled(Style::Fraktur),
+            "overline" | "underline" => Ok(vec![Node::Bar { top: name == "overline", body: self.item()? }]),
+            "overset" | "stackrel" | "underset" => {
+                let limit = self.item()?;
+                let base = self.item()?;
+                Ok(vec![Node::Limit { base, limit, upper: name != "underset" }])
+            }
+            "middle" | "big" | "Big" | "bigg" | "Bigg" | "bigl" | "bigr" | "Bigl" | "Bigr" | "biggl" | "biggr" | "Biggl" | "Biggr" => {
+                run(&self.delimiter()?, Style::Upright)
+            }
+            name => {
+                if let Some(&(_, op)) = NARY.iter().find(|(n, _)| *n == name) {
+                    self.nary(op)
+                } else if FUNCTIONS.contains(&name) {
+                    self.function(vec![Node::Run(name.to_string(), Style::Upright)], false)
+                } else if let Some((_, text)) = LIMIT_FUNCTIONS.iter().find(|(n, _)| *n == name) {
+                    self.function(vec![Node::Run(text.to_string(), Style::Upright)], true)
+                } else if let Some(&(_, chr)) = ACCENTS.iter().find(|(n, _)| *n == name) {
+                    Ok(vec![Node::Accent { chr, body: self.item()? }])
+                } else if let Some(&(_, c)) = GREEK.iter().find(|(n, _)| *n == name) {
+                    // Lowercase Greek is italic like Latin variables; capitals are upright
+                    run(&c.to_string(), if c.is_lowercase() { Style::Italic } else { Style::Upright })
+                } else if let Some((_, text)) = RELATIONS.iter().chain(SYMBOLS).find(|(n, _)| *n == name) {
+                    run(text, Style::Upright)
+                } else {
+                    Err(self.error(start, &format!("unknown command \\{name}")))
+                }
+            }
+        }
+    }
+
+    /// Argument restyled by `\mathbf` and friends; `\text` keeps its style
+    fn styled(&mut self, style: Style) -> Result<Vec<Node>, PptxError> {
+        let mut nodes = self.item()?;
+        restyle(&mut nodes, style);
+        Ok(nodes)
+    }
+
+    /// Sum, product or integral with its limits and body
+    fn nary(&mut self, op: char) -> Result<Vec<Node>, PptxError> {
+        let (mut sub, mut sup) = (None, None);
+        loop {
+            match self.peek() {
+                Some('_') if sub.is_none() => {
+                    self.pos += 1;
+                    sub = Some(self.item()?);
+                }
+                Some('^') if sup.is_none() => {
+                    self.pos += 1;
+                    sup = Some(self.item()?);
+                }
+                Some('\\') if self.eat_command("limits") || self.eat_command("nolimits") => {}
+                _ => break,
+            }
+        }
+        let body = self.operand()?;
+        Ok(vec![Node::Nary { op, sub, sup, body }])
+    }
+
+    /// Function name with its scripts (below the name for `limits`) and argument
+    fn function(&mut self, mut name: Vec<Node>, limits: bool) -> Result<Vec<Node>, PptxError> {
+        let (mut limit, mut sub, mut sup) = (None, None, None);
+        loop {
+            match self.peek() {
+                Some('_') if limits && limit.is_none() => {
+                    self.pos += 1;
+                    limit = Some(self.item()?);
+                }
+                Some('_') if !limits && sub.is_none() => {
+                    self.pos += 1;
+                    sub = Some(self.item()?);
+                }
+                Some('^') if sup.is_none() => {
+                    self.pos += 1;
+                    sup = Some(self.item()?);
+                }
+                _ => break,
+            }
+        }
+        if let Some(limit) = limit {
+            name = vec![Node::Limit { base: name, limit, upper: false }];
+        }
+        if sub.is_some() || sup.is_some() {
+            name = vec![Node::Scripts { base: name, sub, sup }];
+        }
+        let body = self.operand()?;
+        Ok(if body.is_empty() { name } else { vec![Node::Func { name, body }] })
+    }
+
+    /// `\begin{...}` matrix, cases or aligned environment
+    fn environment(&mut self, start: usize) -> Result<Vec<Node>, PptxError> {
+        let name = self.text_arg()?;
+        let (open, close) = match name.as_str() {
+            "matrix" | "smallmatrix" | "array" | "cases" | "aligned" | "align" | "align*" | "gathered"
+            | "gather" | "gather*" | "split" => ("", ""),
+            "pmatrix" => ("(", ")"),
+            "bmatrix" => ("[", "]"),
+            "Bmatrix" => ("{", "}"),
+            "vmatrix" => ("|", "|"),
+            "Vmatrix" => ("‖", "‖"),
+            _ => return Err(self.error(start, &format!("unknown environment {name}"))),
+        };
+        if name == "array" {
+            self.text_arg()?;
+        }
+        let mut rows = self.rows()?;
+        if !(self.eat_command("end") && self.text_arg().is_ok_and(|end| end == name)) {
+            return Err(self.error(start, &format!("\\begin{{{name}}} without \\end{{{name}}}")));
+        }
+
+        let node = match name.as_str() {
+            "cases" => {
+                let matrix = Node::Matrix { rows, left: true };
+                Node::Delim { open: "{".to_string(), close: String::new(), body: vec![matrix] }
+            }
+            "aligned" | "align" | "align*" | "gathered" | "gather" | "gather*" | "split" => {
+                Node::Lines(rows.iter_mut().map(|row| row.drain(..).flatten().collect()).collect())
+            }
+            _ if open.is_empty() => Node::Matrix { rows, left: false },
+            _ => {
+                let matrix = Node::Matrix { rows, left: false };
+                Node::Delim { open: open.to_string(), close: close.to_string(), body: vec![matrix] }
+            }
+        };
+        Ok(vec![node])
+    }
+
+    /// Braced argument read as plain text, for `\text` and environment names
+    fn text_arg(&mut self) -> Result<String, PptxError> {
+        let start = self.pos;
+        self.expect('{')?;
+        let mut text = String::new();
+        let mut depth = 0;
+        while let Some(&c) = self.chars.get(self.pos) {
+            self.pos += 1;
+            match c {
+                '\\' => match self.chars.get(self.pos) {
+                    Some(&escaped @ ('{' | '}' | '\\' | '$' | '%' | '&' | '#' | '_' | ' ')) => {
+                        self.pos += 1;
+                        text.push(escaped);
+                    }
+                    _ => text.push(c),
+                },
+                '{' => {
+                    depth += 1;
+                    text.push(c);
+                }
+                '}' if depth == 0 => return Ok(text),
+                '}' => {
+                    depth -= 1;
+                    text.push(c);
+                }
+                _ => text.push(c),
+            }
+        }
+        Err(self.error(start, "missing }"))
+    }
+
+    /// Delimiter after `\left`, `\right` or `\big`; `.` is an empty one
+    fn delimiter(&mut self) -> Result<String, PptxError> {
+        let start = self.pos;
+        let Some(c) = self.peek() else { return Err(self.error(start, "missing delimiter")) };
+        self.pos += 1;
+        let delimiter = match c {
+            '.' => "",
+            '(' | ')' | '[' | ']' | '|' | '/' => return Ok(c.to_string()),
+            '<' => "⟨",
+            '>' => "⟩",
+            '\\' => match self.command_name().as_str() {
+                "{" | "lbrace" => "{",
+                "}" | "rbrace" => "}",
+                "|" | "Vert" | "lVert" | "rVert" => "‖",
+                "vert" | "lvert" | "rvert" => "|",
+                "langle" => "⟨",
+                "rangle" => "⟩",
+                "lfloor" => "⌊",
+                "rfloor" => "⌋",
+                "lceil" => "⌈",
+                "rceil" => "⌉",
+                _ => return Err(self.error(start, "unknown delimiter")),
+            },
+            _ => return Err(self.error(start, "missing delimiter")),
+        };
+        Ok(delimiter.to_string())
+    }
+}
+
+fn char_node(c: char) -> Node {
+    let (text, style) = match c {
+        c if c.is_alphabetic() => (c.to_string(), Style::Italic),
+        '-' => ("−".to_string(), Style::Upright),
+        '*' => ("∗".to_string(), Style::Upright),
+        '\'' => ("′".to_string(), Style::Upright),
+        '~' => ("\u{a0}".to_string(), Style::Upright),
+        c => (c.to_string(), Style::Upright),
+    };
+    Node::Run(text, style)
+}
+
+fn restyle(nodes: &mut [Node], style: Style) {
+    for node in nodes {
+        match node {
+            Node::Run(_, Style::Text) => {}
+            Node::Run(_, current) => *current = style,
+            Node::Frac { num, den, .. } => {
+                restyle(num, style);
+                restyle(den, style);
+            }
+            Node::Scripts { base, sub, sup } => {
+                restyle(base, style);
+                sub.iter_mut().chain(sup.iter_mut()).for_each(|script| restyle(script, style));
+            }
+            Node::Root { degree, body } => {
+                degree.iter_mut().for_each(|degree| restyle(degree, style));
+                restyle(body, style);
+            }
+            Node::Nary { body, .. } | Node::Delim { body, .. } | Node::Accent { body, .. } | Node::Bar { body, .. } => {
+                restyle(body, style)
+            }
+            Node::Matrix { rows, .. } => rows.iter_mut().flatten().for_each(|cell| restyle(cell, style)),
+            Node::Lines(lines) => lines.iter_mut().for_each(|line| restyle(line, style)),
+            Node::Func { body, .. } => restyle(body, style),
+            Node::Limit { base, limit, .. } => {
+                restyle(base, style);
+                restyle(limit, style);
+            }
+        }
+    }
+}
+
+/// OMML writer; `size` is in hundredths of a point
+struct Writer {
+    size: u32,
+    xml: String,
+}
+
+impl Writer {
+    /// Text properties of a run or structure, in the math font
+    fn text_props(&self, style: Style) -> String {
+        let italic = u8::from(style == Style::Italic);
+        let bold = u8::from(style == Style::Bold);
+        format!(r#"<a:rPr lang="en-US" sz="{}" i="{italic}" b="{bold}"><a:latin typeface="{MATH_FONT}"/></a:rPr>"#, self.size)
+    }
+
+    /// `m:{tag}Pr` properties element ending with the control properties
+    fn props(&mut self, tag: &str, inner: &str) {
+        let ctrl = self.text_props(Style::Italic);
+        self.xml.push_str(&format!("<m:{tag}Pr>{inner}<m:ctrlPr>{ctrl}</m:ctrlPr></m:{tag}Pr>"));
+    }
+
+    fn arg(&mut self, tag: &str, nodes: &[Node]) {
+        self.xml.push_str(&format!("<m:{tag}>"));
+        self.nodes(nodes);
+        self.xml.push_str(&format!("</m:{tag}>"));
+    }
+
+    fn nodes(&mut self, nodes: &[Node]) {
+        let mut i = 0;
+        while i < nodes.len() {
+            if let Node::Run(_, style) = &nodes[i] {
+                // Neighbouring runs of the same style share one `m:r`
+                let mut text = String::new();
+                while let Some(Node::Run(more, more_style)) = nodes.get(i)
+                    && more_style == style
+                {
+                    text.push_str(more);
+                    i += 1;
+                }
+                self.run(&text, *style);
+            } else {
+                self.node(&nodes[i]);
+                i += 1;
+            }
+        }
+    }
+
+    fn run(&mut self, text: &str, style: Style) {
+        let math_props = match style {
+            Style::Italic => "",
+            Style::Upright => r#"<m:rPr><m:sty m:val="p"/></m:rPr>"#,
+            Style::Text => "<m:rPr><m:nor/></m:rPr>",
+            Style::Bold => r#"<m:rPr><m:sty m:val="b"/></m:rPr>"#,
+            Style::DoubleStruck => r#"<m:rPr><m:scr m:val="double-struck"/><m:sty m:val="p"/></m:rPr>"#,
+            Style::Script => r#"<m:rPr><m:scr m:val="script"/><m:sty m:val="p"/></m:rPr>"#,
+            Style::Fraktur => r#"<m:rPr><m:scr m:val="fraktur"/><m:sty m:val="p"/></m:rPr>"#,
+        };
+        let space = if text.starts_with(' ') || text.ends_with(' ') { r#" xml:space="preserve""# } else { "" };
+        let text_props = self.text_props(style);
+        self.xml.push_str(&format!("<m:r>{math_props}{text_props}<m:t{space}>{}</m:t></m:r>", escape_xml(text)));
+    }
+
+    fn node(&mut self, node: &Node) {
+        match node {
+            Node::Run(text, style) => self.run(text, *style),
+            Node::Frac { num, den, bar } => {
+                self.xml.push_str("<m:f>");
+                self.props("f", if *bar { "" } else { r#"<m:type m:val="noBar"/>"# });
+                self.arg("num", num);
+                self.arg("den", den);
+                self.xml.push_str("</m:f>");
+            }
+            Node::Scripts { base, sub, sup } => {
+                let tag = match (sub, sup) {
+                    (Some(_), Some(_)) => "sSubSup",
+                    (Some(_), None) => "sSub",
+                    _ => "sSup",
+                };
+                self.xml.push_str(&format!("<m:{tag}>"));
+                self.props(tag, "");
+                self.arg("e", base);
+                if let Some(sub) = sub {
+                    self.arg("sub", sub);
+                }
+                if let Some(sup) = sup {
+                    self.arg("sup", sup);
+                }
+                self.xml.push_str(&format!("</m:{tag}>"));
+            }
+            Node::Root { degree, body } => {
+                self.xml.push_str("<m:rad>");
+                self.props("rad", if degree.is_some() { "" } else { r#"<m:degHide m:val="1"/>"# });
+                self.arg("deg", degree.as_deref().unwrap_or_default());
+                self.arg("e", body);
+                self.xml.push_str("</m:rad>");
+            }
+            Node::Nary { op, sub, sup, body } => {
+                // Integrals keep their limits beside the sign, as LaTeX does
+                let location = if matches!(op, '∫' | '∬' | '∭' | '∮') { "subSup" } else { "undOvr" };
+                let mut inner = format!(r#"<m:chr m:val="{op}"/><m:limLoc m:val="{location}"/>"#);
+                if sub.is_none() {
+                    inner.push_str(r#"<m:subHide m:val="1"/>"#);
+                }
+                if sup.is_none() {
+                    inner.push_str(r#"<m:supHide m:val="1"/>"#);
+                }
+                self.xml.push_str("<m:nary>");
+                self.props("nary", &inner);
+                self.arg("sub", sub.as_deref().unwrap_or_default());
+                self.arg("sup", sup.as_deref().unwrap_or_default());
+                self.arg("e", body);
+                self.xml.push_str("</m:nary>");
+            }
+            Node::Delim { open, close, body } => {
+                self.xml.push_str("<m:d>");
+                let inner = format!(r#"<m:begChr m:val="{}"/><m:endChr m:val="{}"/>"#, escape_xml(open), escape_xml(close));
+                self.props("d", &inner);
+                self.arg("e", body);
+                self.xml.push_str("</m:d>");
+            }
+            Node::Matrix { rows, left } => {
+                let columns = rows.iter().map(Vec::len).max().unwrap_or(1);
+                let justify = if *left { "left" } else { "center" };
+                let inner = format!(
+                    r#"<m:mcs><m:mc><m:mcPr><m:count m:val="{columns}"/><m:mcJc m:val="{justify}"/></m:mcPr></m:mc></m:mcs>"#
+                );
+                self.xml.push_str("<m:m>");
+                self.props("m", &inner);
+                for row in rows {
+                    self.xml.push_str("<m:mr>");
+                    for column in 0..columns {
+                        self.arg("e", row.get(column).map(Vec::as_slice).unwrap_or_default());
+                    }
+                    self.xml.push_str("</m:mr>");
+                }
+                self.xml.push_str("</m:m>");
+            }
+            Node::Lines(lines) => {
+                self.xml.push_str("<m:eqArr>");
+                self.props("eqArr", "");
+                for line in lines {
+                    self.arg("e", line);
+                }
+                self.xml.push_str("</m:eqArr>");
+            }
+            Node::Func { name, body } => {
+                self.xml.push_str("<m:func>");
+                self.props("func", "");
+                self.arg("fName", name);
+                self.arg("e", body);
+                self.xml.push_str("</m:func>");
+            }
+            Node::Limit { base, limit, upper } => {
+                let tag = if *upper { "limUpp" } else { "limLow" };
+                self.xml.push_str(&format!("<m:{tag}>"));
+                self.props(tag, "");
+                self.arg("e", base);
+                self.arg("lim", limit);
+                self.xml.push_str(&format!("</m:{tag}>"));
+            }
+            Node::Accent { chr, body } => {
+                self.xml.push_str("<m:acc>");
+                self.props("acc", &format!(r#"<m:chr m:val="{chr}"/>"#));
+                self.arg("e", body);
+                self.xml.push_str("</m:acc>");
+            }
+            Node::Bar { top, body } => {
+                self.xml.push_str("<m:bar>");
+                self.props("bar", &format!(r#"<m:pos m:val="{}"/>"#, if *top { "top" } else { "bot" }));
+                self.arg("e", body);
+                self.xml.push_str("</m:bar>");
+            }
+        }
+    }
+}
+
+fn linear(nodes: &[Node]) -> String {
+    nodes.iter().map(node_linear).collect()
+}
+
+/// Linear form of a script, limit or fraction part, parenthesized unless it
+/// is a single symbol, a number, a scripted symbol or already bracketed
+fn operand(nodes: &[Node]) -> String {
+    let text = linear(nodes);
+    let single = matches!(nodes, [Node::Delim { .. }])
+        || matches!(nodes, [Node::Scripts { base, .. }] if linear(base).chars().count() <= 1);
+    if single || text.chars().count() <= 1 || text.chars().all(|c| c.is_ascii_digit()) {
+        text
+    } else {
+        format!("({text})")
+    }
+}
+
+fn node_linear(node: &Node) -> String {
+    match node {
+        Node::Run(text, Style::DoubleStruck) => text.chars().map(double_struck).collect(),
+        Node::Run(text, _) => text.clone(),
+        Node::Frac { num, den, bar: true } => format!("{}/{}", operand(num), operand(den)),
+        Node::Frac { num, den, bar: false } => format!("{}¦{}", linear(num), linear(den)),
+        Node::Scripts { base, sub, sup } => {
+            let mut text = operand(base);
+            if let Some(sub) = sub {
+                text.push_str(&format!("_{}", operand(sub)));
+            }
+            if let Some(sup) = sup {
+                // Primes need no caret
+                if sup.iter().all(|node| matches!(node, Node::Run(t, _) if t == "′")) {
+                    text.push_str(&linear(sup));
+                } else {
+                    text.push_str(&format!("^{}", operand(sup)));
+                }
+            }
+            text
+        }
+        Node::Root { degree: None, body } => format!("√{}", operand(body)),
+        Node::Root { degree: Some(degree), body } => format!("√({}&{})", linear(degree), linear(body)),
+        Node::Nary { op, sub, sup, body } => {
+            let mut text = op.to_string();
+            if let Some(sub) = sub {
+                text.push_str(&format!("_{}", operand(sub)));
+            }
+            if let Some(sup) = sup {
+                text.push_str(&format!("^{}", operand(sup)));
+            }
+            format!("{text} {}", linear(body))
+        }
+        Node::Delim { open, close, body } => format!("{open}{}{close}", linear(body)),
+        Node::Matrix { rows, .. } => rows.iter()
+            .map(|row| row.iter().map(|cell| linear(cell)).collect::<Vec<_>>().join(", "))
+            .collect::<Vec<_>>()
+            .join("; "),
+        Node::Lines(lines) => lines.iter().map(|line| linear(line)).collect::<Vec<_>>().join("\n"),
+        Node::Func { name, body } => {
+            let body = linear(body);
+            let space = if body.starts_with(char::is_alphanumeric) { " " } else { "" };
+            format!("{}{space}{body}", linear(name))
+        }
+        Node::Limit { base, limit, upper } => {
+            format!("{}{}{}", linear(base), if *upper { "^" } else { "_" }, operand(limit))
+        }
+        Node::Accent { chr, body } => format!("{}{chr}", linear(body)),
+        Node::Bar { top, body } => {
+            let mark = if *top { '\u{305}' } else { '\u{332}' };
+            linear(body).chars().flat_map(|c| [c, mark]).collect()
+        }
+    }
+}
+
+/// Double-struck letter (`\mathbb`)
+fn double_struck(c: char) -> char {
+    match c {
+        'C' => 'ℂ',
+        'H' => 'ℍ',
+        'N' => 'ℕ',
+        'P' => 'ℙ',
+        'Q' => 'ℚ',
+        'R' => 'ℝ',
+        'Z' => 'ℤ',
+        'A'..='Z' => char::from_u32(0x1D538 + (c as u32 - 'A' as u32)).unwrap_or(c),
+        'a'..='z' => char::from_u32(0x1D552 + (c as u32 - 'a' as u32)).unwrap_or(c),
+        _ => c,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn omml(latex: &str) -> String {
+        latex_to_omml(latex, 28).unwrap()
+    }
+
+    fn linear_of(latex: &str) -> String {
+        MathExpr::parse(latex).unwrap().to_linear()
+    }
+
+    #[test]
+    fn test_fractions_scripts_and_roots() {
+        let xml = omml(r"x = \frac{-b \pm \sqrt{b^2 - 4ac}}{2a}");
+        assert!(xml.starts_with("<m:oMath>") && xml.ends_with("</m:oMath>"));
+        assert!(xml.contains("<m:f>") && xml.contains("<m:num>") && xml.contains("<m:den>"));
+        assert!(xml.contains(r#"<m:degHide m:val="1"/>"#));
+        assert!(xml.contains("<m:sSup>") && xml.contains("<m:t>±</m:t>"));
+        assert!(xml.contains(r#"<a:latin typeface="Cambria Math"/>"#));
+        assert_eq!(linear_of(r"x = \frac{-b \pm \sqrt{b^2 - 4ac}}{2a}"), "x=(−b±√(b^2−4ac))/(2a)");
+        assert_eq!(linear_of(r"\sqrt[3]{x} + a_i^2 + f'(x)"), "√(3&x)+a_i^2+f′(x)");
+    }
+
+    #[test]
+    fn test_sums_integrals_and_functions() {
+        let xml = omml(r"\sum_{i=1}^{n} i^2 = \int_0^1 f(x)\,dx");
+        assert!(xml.contains(r#"<m:chr m:val="∑"/><m:limLoc m:val="undOvr"/>"#));
+        assert!(xml.contains(r#"<m:chr m:val="∫"/><m:limLoc m:val="subSup"/>"#));
+        // The sum's body stops at the relation
+        assert_eq!(linear_of(r"\sum_{i=1}^{n} i^2 = \int_0^1 f(x)\,dx"), "∑_(i=1)^n i^2=∫_0^1 f(x)\u{2009}dx");
+
+        let xml = omml(r"\lim_{x \to 0} \frac{\sin x}{x}");
+        assert!(xml.contains("<m:limLow>") && xml.contains("<m:func>"));
+        assert!(xml.contains(r#"<m:rPr><m:sty m:val="p"/></m:rPr>"#));
+        assert_eq!(linear_of(r"\lim_{x \to 0} \frac{\sin x}{x}"), "lim_(x→0)(sin x)/x");
+    }
+
+    #[test]
+    fn test_matrices_and_delimiters() {
+        let xml = omml(r"A = \begin{pmatrix} a & b \\ c & d \end{pmatrix}");
+        assert!(xml.contains(r#"<m:begChr m:val="("/>"#));
+        assert!(xml.contains(r#"<m:count m:val="2"/>"#));
+        assert_eq!(xml.matches("<m:mr>").count(), 2);
+        assert_eq!(linear_of(r"\begin{bmatrix} 1 & 0 \\ 0 & 1 \\ \end{bmatrix}"), "[1, 0; 0, 1]");
+
+        let xml = omml(r"|x| = \begin{cases} x & x \ge 0 \\ -x & \text{otherwise} \end{cases}");
+        assert!(xml.contains(r#"<m:endChr m:val=""/>"#) && xml.contains(r#"<m:mcJc m:val="left"/>"#));
+        assert!(xml.contains("<m:rPr><m:nor/></m:rPr>"));
+        assert_eq!(linear_of(r"\left\langle \phi \middle| \psi \right\rangle"), "⟨ϕ|ψ⟩");
+    }
+
+    #[test]
+    fn test_styles_accents_and_greek() {
+        let xml = omml(r"\hat{\theta} \in \mathbb{R}, \vec{v} \cdot \mathbf{n}");
+        assert!(xml.contains("<m:chr m:val=\"\u{302}\"/>") && xml.contains("<m:t>θ</m:t>"));
+        assert!(xml.contains(r#"<m:scr m:val="double-struck"/>"#));
+        assert!(xml.contains(r#"<m:sty m:val="b"/>"#));
+        assert_eq!(linear_of(r"\mathbb{R}^n \times \Omega"), "ℝ^n×Ω");
+    }
+
+    #[test]
+    fn test_errors() {
+        for (latex, message) in [
+            (r"\frac{a}{b", "missing }"),
+            (r"a}", "unmatched }"),
+            (r"\foo x", "unknown command \\foo at column 1"),
+            (r"\left( x", "\\left without \\right"),
+            (r"\begin{pmatrix} a \end{bmatrix}", "without \\end{pmatrix}"),
+            (r"x_1_2", "double subscript"),
+        ] {
+            let error = MathExpr::parse(latex).unwrap_err().to_string();
+            assert!(error.contains(message), "{latex}: {error}");
+        }
+    }
+}
@@ -27,6 +27,7 @@ pub fn create_content_types_xml(slides: usize) -> String {
 <Override PartName="/ppt/slideLayouts/slideLayout1.xml" ContentType="application/vnd.openxmlformats-officedocument.presentationml.slideLayout+xml"/>
 <Override PartName="/ppt/slideMasters/slideMaster1.xml" ContentType="application/vnd.openxmlformats-officedocument.presentationml.slideMaster+xml"/>
 <Override PartName="/ppt/theme/theme1.xml" ContentType="application/vnd.openxmlformats-officedocument.theme+xml"/>
+<Override PartName="/ppt/tableStyles.xml" ContentType="application/vnd.openxmlformats-officedocument.presentationml.tableStyles+xml"/>
 <Override PartName="/docProps/core.xml" ContentType="application/vnd.openxmlformats-package.core-properties+xml"/>
 <Override PartName="/docProps/app.xml" ContentType="application/vnd.openxmlformats-officedocument.extended-properties+xml"/>
 </Types>"#);
@@ -57,6 +58,11 @@ pub fn create_presentation_rels_xml(slides: usize) -> String {
         ));
     }
 
+    let table_styles_rid = slides + 3;
+    xml.push_str(&format!(
+        "\n    <Relationship Id=\"rId{table_styles_rid}\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/tableStyles\" Target=\"tableStyles.xml\"/>"
+    ));
+
     xml.push_str("\n</Relationships>");
     xml
 }
@@ -84,6 +90,164 @@ pub fn create_presentation_xml(_title: &str, slides: usize) -> String {
     xml
 }
 
+/// Replace the slide size in `ppt/presentation.xml` (`p:sldSz`), in EMU
+pub fn set_slide_size(presentation_xml: &str, width: u32, height: u32) -> String {
+    let Some(start) = presentation_xml.find("<p:sldSz ") else {
+        return presentation_xml.to_string();
+    };
+    let end = presentation_xml[start..].find("/>").map_or(presentation_xml.len(), |end| start + end + 2);
+    let size = match (width, height) {
+        (9_144_000, 6_858_000) => r#"<p:sldSz cx="9144000" cy="6858000" type="screen4x3"/>"#.to_string(),
+        _ => format!(r#"<p:sldSz cx="{width}" cy="{height}"/>"#),
+    };
+    format!("{}{size}{}", &presentation_xml[..start], &presentation_xml[end..])
+}
+
+/// Fit a slide, layout or master laid out for the default 4:3 slide to a
+/// `width` x `height` slide
+///
+/// The 4:3 layout is scaled by the same factor in both directions, as much as
+/// fits, and centred on the slide; shapes inside groups follow their group.
+/// Pictures the full height of the slide fill their part of it up to the
+/// slide edges they touch.
+pub fn fit_to_slide_size(part_xml: &str, width: u32, height: u32) -> String {
+    use super::constants::{SLIDE_HEIGHT, SLIDE_WIDTH};
+    if (width, height) == (SLIDE_WIDTH, SLIDE_HEIGHT) {
+        return part_xml.to_string();
+    }
+    let fit = SlideFit::new(width, height);
+
+    let mut out = String::with_capacity(part_xml.len());
+    let mut open: Vec<&str> = Vec::new();
+    let mut rest = part_xml;
+    while let Some(start) = rest.find('<') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = rest.find('>') else { break };
+        let tag = &rest[..=end];
+        let name = tag[1..].split(|c: char| c.is_whitespace() || c == '/' || c == '>').next().unwrap_or("");
+        // Groups inside the shape tree scale their children along with them;
+        // the shape tree's own transform stays
+        let groups = open.iter().filter(|&&n| n == "p:grpSp").count().saturating_sub(usize::from(open.last() == Some(&"p:grpSpPr")));
+        let tree = open.ends_with(&["p:spTree", "p:grpSpPr"]);
+        if tag.starts_with("</") {
+            open.pop();
+        } else if tag.starts_with("<?") || tag.starts_with("<!") || tag.ends_with("/>") {
+            if name == "a:gridCol" && groups == 0 {
+                out.push_str(&scale_attrs(tag, &[("w", fit.scale)]));
+                rest = &rest[end + 1..];
+                continue;
+            }
+        } else if matches!(name, "a:xfrm" | "p:xfrm") && groups == 0 && !tree {
+            let close = format!("</{name}>");
+            if let Some(xfrm_end) = rest.find(&close) {
+                let picture = open.iter().rev().take(2).any(|&n| n == "p:pic");
+                out.push_str(&fit.xfrm(&rest[..xfrm_end + close.len()], picture));
+                rest = &rest[xfrm_end + close.len()..];
+                continue;
+            }
+        } else {
+            open.push(name);
+        }
+        out.push_str(tag);
+        rest = &rest[end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Uniform scale and centring offset from the 4:3 slide to another size
+struct SlideFit {
+    width: f64,
+    height: f64,
+    scale: f64,
+    offset: (f64, f64),
+}
+
+impl SlideFit {
+    fn new(width: u32, height: u32) -> Self {
+        use super::constants::{SLIDE_HEIGHT, SLIDE_WIDTH};
+        let (width, height) = (width as f64, height as f64);
+        let scale = (width / SLIDE_WIDTH as f64).min(height / SLIDE_HEIGHT as f64);
+        let offset = ((width - SLIDE_WIDTH as f64 * scale) / 2.0, (height - SLIDE_HEIGHT as f64 * scale) / 2.0);
+        SlideFit { width, height, scale, offset }
+    }
+
+    /// Scale the `a:off` and `a:ext` of a transform
+    fn xfrm(&self, xfrm: &str, picture: bool) -> String {
+        use super::constants::{SLIDE_HEIGHT, SLIDE_WIDTH};
+        let value = |tag: &str, attr: &str| -> Option<f64> {
+            let start = xfrm.find(&format!("<{tag} "))?;
+            let element = &xfrm[start..start + xfrm[start..].find('>')?];
+            let at = element.find(&format!(" {attr}=\""))? + attr.len() + 3;
+            element[at..at + element[at..].find('"')?].parse().ok()
+        };
+        let (Some(x), Some(y), Some(cx), Some(cy)) = (value("a:off", "x"), value("a:off", "y"), value("a:ext", "cx"), value("a:ext", "cy")) else {
+            return xfrm.to_string();
+        };
+        let (mut left, mut right) = (self.offset.0 + x * self.scale, self.offset.0 + (x + cx) * self.scale);
+        let (mut top, mut bottom) = (self.offset.1 + y * self.scale, self.offset.1 + (y + cy) * self.scale);
+        if picture && y <= 0.0 && cy >= SLIDE_HEIGHT as f64 {
+            (top, bottom) = (0.0, self.height);
+            if x <= 0.0 {
+                left = 0.0;
+            }
+            if x + cx >= SLIDE_WIDTH as f64 {
+                right = self.width;
+            }
+        }
+        let round = |v: f64| v.round() as i64;
+        xfrm.split_inclusive('>')
+            .map(|tag| match tag.trim_start() {
+                t if t.starts_with("<a:off ") => set_attrs(tag, &[("x", round(left)), ("y", round(top))]),
+                t if t.starts_with("<a:ext ") => set_attrs(tag, &[("cx", round(right) - round(left)), ("cy", round(bottom) - round(top))]),
+                _ => tag.to_string(),
+            })
+            .collect()
+    }
+}
+
+/// Multiply numeric attributes of a tag
+fn scale_attrs(tag: &str, attrs: &[(&str, f64)]) -> String {
+    let values: Vec<(&str, i64)> = attrs.iter()
+        .filter_map(|&(attr, factor)| {
+            let at = tag.find(&format!(" {attr}=\""))? + attr.len() + 3;
+            let value: f64 = tag[at..at + tag[at..].find('"')?].parse().ok()?;
+            Some((attr, (value * factor).round() as i64))
+        })
+        .collect();
+    set_attrs(tag, &values)
+}
+
+/// Set numeric attributes of a tag
+fn set_attrs(tag: &str, attrs: &[(&str, i64)]) -> String {
+    let mut tag = tag.to_string();
+    for (attr, value) in attrs {
+        let pattern = format!(" {attr}=\"");
+        if let Some(at) = tag.find(&pattern).map(|at| at + pattern.len())
+            && let Some(len) = tag[at..].find('"')
+        {
+            tag.replace_range(at..at + len, &value.to_string());
+        }
+    }
+    tag
+}
+
+/// Reference the notes master from `ppt/presentation.xml` (`p:notesMasterIdLst`)
+pub fn add_notes_master_id(presentation_xml: &str, r_id: usize) -> String {
+    if presentation_xml.contains("<p:notesMasterIdLst>") {
+        return presentation_xml.to_string();
+    }
+    let list = format!("\n<p:notesMasterIdLst>\n<p:notesMasterId r:id=\"rId{r_id}\"/>\n</p:notesMasterIdLst>");
+    match presentation_xml.find("</p:sldMasterIdLst>") {
+        Some(pos) => {
+            let end = pos + "</p:sldMasterIdLst>".len();
+            format!("{}{list}{}", &presentation_xml[..end], &presentation_xml[end..])
+        }
+        None => presentation_xml.to_string(),
+    }
+}
+
 /// Create [Content_Types].xml with notes support
 pub fn create_content_types_xml_with_notes(slides: usize, custom_slides: Option<&Vec<super::slide_content::SlideContent>>) -> String {
     let mut xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
@@ -118,6 +282,7 @@ pub fn create_content_types_xml_with_notes(slides: usize, custom_slides: Option<
 <Override PartName="/ppt/slideLayouts/slideLayout1.xml" ContentType="application/vnd.openxmlformats-officedocument.presentationml.slideLayout+xml"/>
 <Override PartName="/ppt/slideMasters/slideMaster1.xml" ContentType="application/vnd.openxmlformats-officedocument.presentationml.slideMaster+xml"/>
 <Override PartName="/ppt/theme/theme1.xml" ContentType="application/vnd.openxmlformats-officedocument.theme+xml"/>
+<Override PartName="/ppt/tableStyles.xml" ContentType="application/vnd.openxmlformats-officedocument.presentationml.tableStyles+xml"/>
 <Override PartName="/docProps/core.xml" ContentType="application/vnd.openxmlformats-package.core-properties+xml"/>
 <Override PartName="/docProps/app.xml" ContentType="application/vnd.openxmlformats-officedocument.extended-properties+xml"/>
 </Types>"#);
@@ -143,6 +308,10 @@ pub fn create_presentation_rels_xml_with_notes(slides: usize) -> String {
     xml.push_str(&format!(
         "\n    <Relationship Id=\"rId{notes_master_rid}\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/notesMaster\" Target=\"notesMasters/notesMaster1.xml\"/>"
     ));
+    let table_styles_rid = slides + 4;
+    xml.push_str(&format!(
+        "\n    <Relationship Id=\"rId{table_styles_rid}\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/tableStyles\" Target=\"tableStyles.xml\"/>"
+    ));
 
     xml.push_str("\n</Relationships>");
     xml
@@ -156,3 +325,37 @@ pub fn create_slide_rels_xml_with_notes(slide_num: usize) -> String {
 <Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/notesSlide" Target="../notesSlides/notesSlide{slide_num}.xml"/>
 </Relationships>"#)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fit_to_slide_size() {
+        let xml = r#"<p:spTree><p:grpSpPr><a:xfrm><a:off x="0" y="0"/><a:ext cx="0" cy="0"/><a:chOff x="0" y="0"/><a:chExt cx="0" cy="0"/></a:xfrm></p:grpSpPr>
+<p:sp><p:spPr><a:xfrm><a:off x="457200" y="100"/><a:ext cx="8230200" cy="200"/></a:xfrm></p:spPr></p:sp>
+<p:pic><p:spPr><a:xfrm><a:off x="0" y="0"/><a:ext cx="3000000" cy="3000000"/></a:xfrm></p:spPr></p:pic>
+<p:pic><p:spPr><a:xfrm><a:off x="4572000" y="0"/><a:ext cx="4572000" cy="6858000"/></a:xfrm></p:spPr></p:pic>
+<p:grpSp><p:grpSpPr><a:xfrm><a:off x="300" y="0"/><a:ext cx="600" cy="10"/><a:chOff x="300" y="0"/><a:chExt cx="600" cy="10"/></a:xfrm></p:grpSpPr>
+<p:sp><p:spPr><a:xfrm><a:off x="300" y="0"/><a:ext cx="600" cy="10"/></a:xfrm></p:spPr></p:sp></p:grpSp>
+<p:graphicFrame><p:xfrm><a:off x="3000" y="0"/><a:ext cx="6000" cy="10"/></p:xfrm><a:tblGrid><a:gridCol w="3000"/><a:gridCol w="3000"/></a:tblGrid></p:graphicFrame></p:spTree>"#;
+        assert_eq!(fit_to_slide_size(xml, 9_144_000, 6_858_000), xml);
+
+        // 16:9 keeps the 4:3 sizes, centred; the shape tree's transform stays
+        let wide = fit_to_slide_size(xml, 12_192_000, 6_858_000);
+        assert!(wide.starts_with(r#"<p:spTree><p:grpSpPr><a:xfrm><a:off x="0" y="0"/><a:ext cx="0" cy="0"/>"#));
+        assert!(wide.contains(r#"<a:off x="1981200" y="100"/><a:ext cx="8230200" cy="200"/>"#));
+        assert!(wide.contains(r#"<a:off x="1524000" y="0"/><a:ext cx="3000000" cy="3000000"/>"#));
+        // Side pictures reach the slide edge they touch
+        assert!(wide.contains(r#"<a:off x="6096000" y="0"/><a:ext cx="6096000" cy="6858000"/>"#));
+        // Groups move, their children follow in the group's own coordinates
+        assert!(wide.contains(r#"<a:off x="1524300" y="0"/><a:ext cx="600" cy="10"/><a:chOff x="300" y="0"/>"#));
+        assert!(wide.contains(r#"<p:spPr><a:xfrm><a:off x="300" y="0"/><a:ext cx="600" cy="10"/>"#));
+        assert!(wide.contains(r#"<p:xfrm><a:off x="1527000" y="0"/><a:ext cx="6000" cy="10"/></p:xfrm><a:tblGrid><a:gridCol w="3000"/><a:gridCol w="3000"/>"#));
+
+        // A bigger 4:3 slide scales both ways alike
+        let big = fit_to_slide_size(xml, 18_288_000, 13_716_000);
+        assert!(big.contains(r#"<a:off x="914400" y="200"/><a:ext cx="16460400" cy="400"/>"#));
+        assert!(big.contains(r#"<a:off x="0" y="0"/><a:ext cx="6000000" cy="6000000"/>"#));
+    }
+}
@@ -1,13 +1,21 @@
 //! Document properties XML generation
 
+use super::package_xml::escape_xml;
+
 /// Create core properties XML (docProps/core.xml)
 pub fn create_core_props_xml(title: &str) -> String {
+    create_core_props_xml_with_author(title, "pptx-rs")
+}
+
+/// Create core properties XML (docProps/core.xml) naming `author` as creator
+pub fn create_core_props_xml_with_author(title: &str, author: &str) -> String {
+    let author = escape_xml(author);
     let now = chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ");
     format!(
         r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
 <cp:coreProperties xmlns:cp="http://schemas.openxmlformats.org/package/2006/metadata/core-properties" xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:dcterms="http://purl.org/dc/terms/" xmlns:dcmitype="http://purl.org/dc/dcmitype/" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">
 <dc:title>{title}</dc:title>
-<dc:creator>pptx-rs</dc:creator>
+<dc:creator>{author}</dc:creator>
 <cp:lastModifiedBy>pptx-rs</cp:lastModifiedBy>
 <cp:revision>1</cp:revision>
 <dcterms:created xsi:type="dcterms:W3CDTF">{now}</dcterms:created>
@@ -2,6 +2,12 @@
 //!
 //! Provides shape types, fills, lines, and builders for creating shapes in slides.
 
+use super::connectors::LineDash;
+use super::effects::{ShapeEffects, Shadow};
+use super::freeform::CustomGeometry;
+use super::hyperlinks::Hyperlink;
+use super::text::TextFrame;
+
 /// Shape types available in PPTX
 #[derive(Clone, Debug, Copy, PartialEq)]
 pub enum ShapeType {
@@ -559,6 +565,7 @@ impl ShapeFill {
 pub struct ShapeLine {
     pub color: String,
     pub width: u32, // in EMU (English Metric Units)
+    pub dash: LineDash,
 }
 
 impl ShapeLine {
@@ -567,8 +574,15 @@ impl ShapeLine {
         ShapeLine {
             color: color.trim_start_matches('#').to_uppercase(),
             width,
+            dash: LineDash::Solid,
         }
     }
+
+    /// Set dash style
+    pub fn with_dash(mut self, dash: LineDash) -> Self {
+        self.dash = dash;
+        self
+    }
 }
 
 /// Shape definition
@@ -583,8 +597,24 @@ pub struct Shape {
     pub gradient: Option<GradientFill>,
     pub line: Option<ShapeLine>,
     pub text: Option<String>,
+    /// Color of `text`; contrasts with the fill when unset
+    pub text_color: Option<String>,
+    /// Rich text; replaces `text` when set
+    pub text_frame: Option<TextFrame>,
     /// Optional fixed shape ID for connector anchoring
     pub id: Option<u32>,
+    /// Shadow, glow, reflection and 3-D effects
+    pub effects: Option<ShapeEffects>,
+    /// Rotation in degrees (clockwise)
+    pub rotation: f64,
+    /// Mirror horizontally
+    pub flip_h: bool,
+    /// Mirror vertically
+    pub flip_v: bool,
+    /// Custom outline; replaces the preset geometry of `shape_type` when set
+    pub geometry: Option<CustomGeometry>,
+    /// Click action of the whole shape
+    pub hyperlink: Option<Hyperlink>,
 }
 
 impl Shape {
@@ -600,10 +630,23 @@ impl Shape {
             gradient: None,
             line: None,
             text: None,
+            text_color: None,
+            text_frame: None,
             id: None,
+            effects: None,
+            rotation: 0.0,
+            flip_h: false,
+            flip_v: false,
+            geometry: None,
+            hyperlink: None,
         }
     }
 
+    /// Create a freeform shape from a custom geometry
+    pub fn freeform(geometry: CustomGeometry, x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self::new(ShapeType::Rectangle, x, y, width, height).with_geometry(geometry)
+    }
+
     /// Set a fixed shape ID (for connector anchoring)
     pub fn with_id(mut self, id: u32) -> Self {
         self.id = Some(id);
@@ -635,6 +678,74 @@ impl Shape {
         self.text = Some(text.to_string());
         self
     }
+
+    /// Set the color of the shape text
+    pub fn with_text_color(mut self, color: &str) -> Self {
+        self.text_color = Some(color.trim_start_matches('#').to_uppercase());
+        self
+    }
+
+    /// Set rich text (paragraphs of runs with mixed formatting and links)
+    pub fn with_text_frame(mut self, text_frame: TextFrame) -> Self {
+        self.text_frame = Some(text_frame);
+        self
+    }
+
+    /// Hyperlinks of the shape and its text runs
+    pub fn hyperlinks(&self) -> Vec<&Hyperlink> {
+        let mut links: Vec<&Hyperlink> = self.hyperlink.iter().collect();
+        links.extend(self.text_frame.iter().flat_map(TextFrame::hyperlinks));
+        links
+    }
+
+    /// Mutable hyperlinks of the shape and its text runs
+    pub fn hyperlinks_mut(&mut self) -> Vec<&mut Hyperlink> {
+        let mut links: Vec<&mut Hyperlink> = self.hyperlink.iter_mut().collect();
+        links.extend(self.text_frame.iter_mut().flat_map(TextFrame::hyperlinks_mut));
+        links
+    }
+
+    /// Set shape effects (shadow, glow, soft edges, reflection, bevel)
+    pub fn with_effects(mut self, effects: ShapeEffects) -> Self {
+        self.effects = Some(effects);
+        self
+    }
+
+    /// Add an outer shadow, keeping any other effects
+    pub fn with_shadow(mut self, shadow: Shadow) -> Self {
+        self.effects.get_or_insert_with(ShapeEffects::default).outer_shadow = Some(shadow);
+        self
+    }
+
+    /// Make the shape clickable
+    pub fn with_hyperlink(mut self, hyperlink: Hyperlink) -> Self {
+        self.hyperlink = Some(hyperlink);
+        self
+    }
+
+    /// Use a custom geometry instead of the preset shape
+    pub fn with_geometry(mut self, geometry: CustomGeometry) -> Self {
+        self.geometry = Some(geometry);
+        self
+    }
+
+    /// Set rotation in degrees (clockwise)
+    pub fn with_rotation(mut self, degrees: f64) -> Self {
+        self.rotation = degrees.rem_euclid(360.0);
+        self
+    }
+
+    /// Mirror the shape horizontally
+    pub fn with_flip_h(mut self) -> Self {
+        self.flip_h = true;
+        self
+    }
+
+    /// Mirror the shape vertically
+    pub fn with_flip_v(mut self) -> Self {
+        self.flip_v = true;
+        self
+    }
 }
 
 /// Convert EMU (English Metric Units) to inches
@@ -684,6 +795,28 @@ mod tests {
         assert_eq!(shape.text, Some("Hello".to_string()));
     }
 
+    #[test]
+    fn test_shape_effects_builder() {
+        let shape = Shape::new(ShapeType::Rectangle, 0, 0, 1000000, 500000)
+            .with_effects(ShapeEffects::new().with_soft_edges(12700))
+            .with_shadow(Shadow::new("000000"));
+
+        let effects = shape.effects.unwrap();
+        assert_eq!(effects.soft_edge, Some(12700));
+        assert!(effects.outer_shadow.is_some());
+    }
+
+    #[test]
+    fn test_shape_rotation_and_flip() {
+        let shape = Shape::new(ShapeType::RightArrow, 0, 0, 1000000, 500000)
+            .with_rotation(-90.0)
+            .with_flip_v();
+
+        assert_eq!(shape.rotation, 270.0);
+        assert!(!shape.flip_h);
+        assert!(shape.flip_v);
+    }
+
     #[test]
     fn test_emu_conversions() {
         let emu = inches_to_emu(1.0);
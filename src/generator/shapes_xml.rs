@@ -3,6 +3,9 @@
 //! Generates XML for shapes embedded in slides.
 
 use super::shapes::{Shape, ShapeFill, ShapeLine, GradientFill};
+use super::connectors::LineDash;
+use super::hyperlinks::generate_cnvpr_xml;
+use super::text::{TextBox, TextMeasurer};
 
 /// Escape XML special characters
 fn escape_xml(s: &str) -> String {
@@ -22,41 +25,64 @@ pub fn generate_shape_xml(shape: &Shape, shape_id: u32) -> String {
         generate_fill_xml(&shape.fill)
     };
     let line_xml = generate_line_xml(&shape.line);
+    let effects_xml = shape.effects.as_ref().map(|e| e.to_xml()).unwrap_or_default();
+    let geometry_xml = match &shape.geometry {
+        Some(geometry) => geometry.to_xml(),
+        None => format!("<a:prstGeom prst=\"{}\">\n<a:avLst/>\n</a:prstGeom>", shape.shape_type.preset_name()),
+    };
     let fill_color = shape.fill.as_ref().map(|f| f.color.as_str());
-    let text_xml = generate_text_xml_with_autofit(&shape.text, shape.width, shape.height, fill_color);
+    let text_xml = match &shape.text_frame {
+        Some(frame) => frame.to_xml(),
+        None => generate_text_xml_with_autofit(&shape.text, shape.width, shape.height, fill_color, shape.text_color.as_deref()),
+    };
     
     format!(
         r#"<p:sp>
 <p:nvSpPr>
-<p:cNvPr id="{}" name="Shape {}"/>
+{}
 <p:cNvSpPr/>
 <p:nvPr/>
 </p:nvSpPr>
 <p:spPr>
-<a:xfrm>
+<a:xfrm{}>
 <a:off x="{}" y="{}"/>
 <a:ext cx="{}" cy="{}"/>
 </a:xfrm>
-<a:prstGeom prst="{}">
-<a:avLst/>
-</a:prstGeom>
-{}{}
+{}
+{}{}{}
 </p:spPr>
 {}
 </p:sp>"#,
-        shape_id,
-        shape_id,
+        generate_cnvpr_xml(shape_id, &format!("Shape {}", shape_id), shape.hyperlink.as_ref()),
+        xfrm_attrs(shape.rotation, shape.flip_h, shape.flip_v),
         shape.x,
         shape.y,
         shape.width,
         shape.height,
-        shape.shape_type.preset_name(),
+        geometry_xml,
         fill_xml,
         line_xml,
+        effects_xml,
         text_xml,
     )
 }
 
+/// Generate `rot`/`flipH`/`flipV` attributes for `a:xfrm`
+pub(crate) fn xfrm_attrs(rotation: f64, flip_h: bool, flip_v: bool) -> String {
+    let mut attrs = String::new();
+    let rot = (rotation * 60000.0).round() as i64;
+    if rot != 0 {
+        attrs.push_str(&format!(r#" rot="{}""#, rot));
+    }
+    if flip_h {
+        attrs.push_str(r#" flipH="1""#);
+    }
+    if flip_v {
+        attrs.push_str(r#" flipV="1""#);
+    }
+    attrs
+}
+
 /// Generate fill XML for solid color
 fn generate_fill_xml(fill: &Option<ShapeFill>) -> String {
     match fill {
@@ -109,53 +135,29 @@ fn generate_gradient_xml(gradient: &GradientFill) -> String {
 fn generate_line_xml(line: &Option<ShapeLine>) -> String {
     match line {
         Some(l) => {
+            let dash = match l.dash {
+                LineDash::Solid => String::new(),
+                dash => format!("\n<a:prstDash val=\"{}\"/>", dash.xml_value()),
+            };
             format!(
                 r#"<a:ln w="{}">
 <a:solidFill>
 <a:srgbClr val="{}"/>
-</a:solidFill>
+</a:solidFill>{}
 </a:ln>"#,
-                l.width, l.color
+                l.width, l.color, dash
             )
         }
         None => String::new(),
     }
 }
 
-/// Calculate optimal font size based on shape dimensions and text content
+/// Calculate optimal font size (hundredths of a point) based on shape dimensions and text content
+///
+/// Wraps the text with font metrics and picks the largest size from 8pt to 44pt that fits.
 fn calculate_font_size(text: &str, width_emu: u32, height_emu: u32) -> u32 {
-    // Convert EMU to approximate character width
-    // 1 inch = 914400 EMU, average char width at 18pt ≈ 0.1 inch
-    let width_inches = width_emu as f64 / 914400.0;
-    let height_inches = height_emu as f64 / 914400.0;
-    
-    // Account for padding (roughly 10% on each side)
-    let usable_width = width_inches * 0.8;
-    let usable_height = height_inches * 0.8;
-    
-    // Get text metrics
-    let lines: Vec<&str> = text.lines().collect();
-    let num_lines = lines.len().max(1);
-    let max_line_len = lines.iter().map(|l| l.chars().count()).max().unwrap_or(1);
-    
-    // Calculate font size based on width constraint
-    // At 18pt (1800 hundredths), average char is ~0.1 inch
-    // So chars_per_inch ≈ 10 at 18pt, scales inversely with font size
-    let chars_that_fit_width = usable_width * 10.0; // at 18pt
-    let width_scale = chars_that_fit_width / max_line_len as f64;
-    let font_from_width = (1800.0 * width_scale).min(4400.0); // max 44pt
-    
-    // Calculate font size based on height constraint
-    // At 18pt, line height ≈ 0.25 inch
-    let lines_that_fit = usable_height / 0.25;
-    let height_scale = lines_that_fit / num_lines as f64;
-    let font_from_height = (1800.0 * height_scale).min(4400.0);
-    
-    // Use the smaller of the two to ensure text fits
-    let optimal_size = font_from_width.min(font_from_height);
-    
-    // Clamp to reasonable range: 800 (8pt) to 4400 (44pt)
-    (optimal_size.max(800.0).min(4400.0)) as u32
+    let text_box = TextBox::new(width_emu, height_emu);
+    TextMeasurer::new().fit_font_size(text, false, &text_box, 8, 44) * 100
 }
 
 /// Calculate if a color is dark (needs white text) or light (needs black text)
@@ -183,7 +185,7 @@ fn get_text_color(fill_color: Option<&str>) -> &'static str {
 }
 
 /// Generate text body XML for shape with auto-fit font sizing
-fn generate_text_xml_with_autofit(text: &Option<String>, width: u32, height: u32, fill_color: Option<&str>) -> String {
+fn generate_text_xml_with_autofit(text: &Option<String>, width: u32, height: u32, fill_color: Option<&str>, text_color: Option<&str>) -> String {
     match text {
         Some(t) => {
             // Check if this is code (starts with [ and contains language tag)
@@ -216,8 +218,8 @@ fn generate_text_xml_with_autofit(text: &Option<String>, width: u32, height: u32
                 // Calculate optimal font size based on shape dimensions
                 let font_size = calculate_font_size(t, width, height);
                 
-                // Get contrasting text color based on fill
-                let text_color = get_text_color(fill_color);
+                // Use the shape's text color, or a contrasting one based on fill
+                let text_color = text_color.unwrap_or_else(|| get_text_color(fill_color));
                 
                 // Use left alignment for multi-line text, center for single line
                 let is_multiline = t.contains('\n');
@@ -313,6 +315,8 @@ pub fn generate_connector_xml(
 mod tests {
     use super::*;
     use super::super::shapes::ShapeType;
+    use super::super::freeform::CustomGeometry;
+    use super::super::effects::{ShapeEffects, Shadow, Glow, Bevel, BevelType};
 
     #[test]
     fn test_generate_shape_xml() {
@@ -327,6 +331,50 @@ mod tests {
         assert!(xml.contains("FF0000"));
     }
 
+    #[test]
+    fn test_generate_shape_with_effects() {
+        let shape = Shape::new(ShapeType::RoundedRectangle, 0, 0, 1000000, 500000)
+            .with_fill(ShapeFill::new("4472C4"))
+            .with_line(ShapeLine::new("000000", 12700))
+            .with_effects(ShapeEffects::new()
+                .with_outer_shadow(Shadow::new("000000"))
+                .with_glow(Glow::new("FFC000", 63500))
+                .with_bevel(Bevel::new(BevelType::Circle)));
+
+        let xml = generate_shape_xml(&shape, 1);
+
+        // Effects follow the line and come before the text body
+        let pos = |tag: &str| xml.find(tag).unwrap();
+        assert!(pos("</a:ln>") < pos("<a:effectLst>"));
+        assert!(pos("<a:effectLst>") < pos("<a:scene3d>"));
+        assert!(pos("<a:sp3d>") < pos("</p:spPr>"));
+        assert!(xml.contains(r#"<a:glow rad="63500">"#));
+    }
+
+    #[test]
+    fn test_generate_shape_with_rotation() {
+        let shape = Shape::new(ShapeType::Rectangle, 0, 0, 1000000, 500000)
+            .with_rotation(45.0)
+            .with_flip_h();
+        let xml = generate_shape_xml(&shape, 1);
+        assert!(xml.contains(r#"<a:xfrm rot="2700000" flipH="1">"#));
+
+        let plain = generate_shape_xml(&Shape::new(ShapeType::Rectangle, 0, 0, 10, 10), 2);
+        assert!(plain.contains("<a:xfrm>"));
+    }
+
+    #[test]
+    fn test_generate_freeform_shape() {
+        let geometry = CustomGeometry::from_svg_path("M0 0 L10 0 L5 10 Z").unwrap();
+        let shape = Shape::freeform(geometry, 0, 0, 914400, 914400)
+            .with_fill(ShapeFill::new("FF0000"));
+        let xml = generate_shape_xml(&shape, 1);
+
+        assert!(xml.contains("<a:custGeom>"));
+        assert!(!xml.contains("prstGeom"));
+        assert!(xml.find("</a:custGeom>").unwrap() < xml.find("<a:solidFill>").unwrap());
+    }
+
     #[test]
     fn test_generate_shape_with_text() {
         let shape = Shape::new(ShapeType::Rectangle, 0, 0, 1000000, 500000)
@@ -443,5 +491,35 @@ mod tests {
             .with_text("Test");
         let xml2 = generate_shape_xml(&shape2, 1);
         assert!(xml2.contains("000000"), "Light fill should have black text");
+
+        // An explicit text color wins over the contrast rule
+        let shape3 = shape2.with_text_color("#c62828");
+        assert!(generate_shape_xml(&shape3, 1).contains(r#"<a:srgbClr val="C62828"/></a:solidFill></a:rPr>"#));
+    }
+
+    #[test]
+    fn test_dashed_shape_line() {
+        let shape = Shape::new(ShapeType::Rectangle, 0, 0, 500000, 500000)
+            .with_line(ShapeLine::new("000000", 12700).with_dash(LineDash::Dash));
+        assert!(generate_shape_xml(&shape, 1).contains(r#"<a:prstDash val="dash"/>"#));
+    }
+
+    #[test]
+    fn test_shape_with_text_frame() {
+        use crate::generator::text::{Paragraph, Run, TextFrame};
+
+        let frame = TextFrame::new().add_paragraph(
+            Paragraph::new()
+                .add_run(Run::new("Run ").bold())
+                .add_run(Run::new("cargo test").code()),
+        );
+        let shape = Shape::new(ShapeType::Rectangle, 0, 0, 1000000, 500000)
+            .with_text("ignored")
+            .with_text_frame(frame);
+        let xml = generate_shape_xml(&shape, 1);
+
+        assert!(xml.contains(r#"<a:latin typeface="Consolas"/></a:rPr><a:t>cargo test</a:t>"#));
+        assert!(xml.contains(r#"b="1""#));
+        assert!(!xml.contains("ignored"));
     }
 }
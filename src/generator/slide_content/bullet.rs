@@ -1,5 +1,8 @@
 //! Bullet point types and formatting
 
+use crate::generator::hyperlinks::Hyperlink;
+use crate::generator::text::Run;
+
 /// Bullet style for lists
 #[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
 pub enum BulletStyle {
@@ -125,6 +128,11 @@ pub struct BulletPoint {
     pub level: u32,
     pub style: BulletStyle,
     pub format: Option<BulletTextFormat>,
+    pub hyperlink: Option<Hyperlink>,
+    /// Rich text runs; rendered instead of `text` when not empty
+    pub runs: Vec<Run>,
+    /// A display equation: its one math run is centered on its own line
+    pub equation: bool,
 }
 
 impl BulletPoint {
@@ -134,8 +142,43 @@ impl BulletPoint {
             level: 0,
             style: BulletStyle::Bullet,
             format: None,
+            hyperlink: None,
+            runs: Vec::new(),
+            equation: false,
         }
     }
+
+    /// Create a display equation from LaTeX, centered without a bullet
+    pub fn equation(latex: &str) -> Self {
+        BulletPoint { style: BulletStyle::None, equation: true, ..Self::rich(vec![Run::math(latex)]) }
+    }
+    
+    /// Create a bullet from rich text runs
+    pub fn rich(runs: Vec<Run>) -> Self {
+        let text: String = runs.iter().map(|r| r.text.as_str()).collect();
+        Self::new(&text).with_runs(runs)
+    }
+    
+    /// Set rich text runs, mixing formatting and links within the line
+    pub fn with_runs(mut self, runs: Vec<Run>) -> Self {
+        self.runs = runs;
+        self
+    }
+    
+    /// Whether the bullet holds Office Math
+    pub fn has_math(&self) -> bool {
+        self.runs.iter().any(|r| r.math)
+    }
+
+    /// Hyperlinks of the bullet and its runs
+    pub fn hyperlinks(&self) -> Vec<&Hyperlink> {
+        self.hyperlink.iter().chain(self.runs.iter().filter_map(|r| r.hyperlink.as_ref())).collect()
+    }
+    
+    /// Mutable hyperlinks of the bullet and its runs
+    pub fn hyperlinks_mut(&mut self) -> Vec<&mut Hyperlink> {
+        self.hyperlink.iter_mut().chain(self.runs.iter_mut().filter_map(|r| r.hyperlink.as_mut())).collect()
+    }
     
     pub fn with_level(mut self, level: u32) -> Self {
         self.level = level;
@@ -152,6 +195,12 @@ impl BulletPoint {
         self
     }
     
+    /// Make the bullet text a hyperlink
+    pub fn with_hyperlink(mut self, hyperlink: Hyperlink) -> Self {
+        self.hyperlink = Some(hyperlink);
+        self
+    }
+    
     pub fn bold(mut self) -> Self {
         self.format = Some(self.format.unwrap_or_default().bold());
         self
@@ -1,5 +1,18 @@
 //! Code block types for syntax highlighting
 
+use crate::cli::syntax::DEFAULT_THEME;
+use crate::generator::constants::CONTENT_WIDTH;
+use crate::generator::text::points_to_emu;
+
+/// Advance of a monospace character, in ems
+const CHAR_WIDTH: f64 = 0.55;
+/// Height of a line of code, in ems
+const LINE_HEIGHT: f64 = 1.2;
+/// Left and right text insets of the code box, in EMU
+const INSET_X: i64 = 91440;
+/// Top and bottom text insets of the code box, in EMU
+const INSET_Y: i64 = 45720;
+
 /// A code block with syntax highlighting info
 #[derive(Clone, Debug)]
 pub struct CodeBlock {
@@ -9,6 +22,18 @@ pub struct CodeBlock {
     pub y: i64,
     pub width: i64,
     pub height: i64,
+    /// Built-in syntect theme name or path of a `.tmTheme` file
+    pub theme: String,
+    pub line_numbers: bool,
+    /// Number of the first line
+    pub first_line: usize,
+    /// Line numbers to highlight, as numbered from `first_line`
+    pub highlight_lines: Vec<usize>,
+    /// Unified diff: `+` and `-` lines are coloured as added and removed
+    pub diff: bool,
+    pub font_family: String,
+    /// Font size in points
+    pub font_size: u32,
 }
 
 impl CodeBlock {
@@ -20,19 +45,161 @@ impl CodeBlock {
             y: 1800000,
             width: 8000000,
             height: 4000000,
+            theme: DEFAULT_THEME.to_string(),
+            line_numbers: false,
+            first_line: 1,
+            highlight_lines: Vec::new(),
+            diff: false,
+            font_family: "Consolas".to_string(),
+            font_size: 14,
         }
     }
-    
+
     pub fn position(mut self, x: i64, y: i64) -> Self {
         self.x = x;
         self.y = y;
         self
     }
-    
+
     pub fn size(mut self, width: i64, height: i64) -> Self {
         self.width = width;
         self.height = height;
         self
     }
-}
 
+    /// Highlighting theme: a built-in name such as "InspiredGitHub", or a `.tmTheme` file
+    pub fn theme(mut self, theme: &str) -> Self {
+        self.theme = theme.to_string();
+        self
+    }
+
+    pub fn line_numbers(mut self) -> Self {
+        self.line_numbers = true;
+        self
+    }
+
+    /// Number the lines from `line` instead of 1
+    pub fn first_line(mut self, line: usize) -> Self {
+        self.first_line = line;
+        self
+    }
+
+    /// Highlight lines by number, e.g. `3..=5`
+    pub fn highlight_lines(mut self, lines: impl IntoIterator<Item = usize>) -> Self {
+        self.highlight_lines.extend(lines);
+        self
+    }
+
+    pub fn diff(mut self) -> Self {
+        self.diff = true;
+        self
+    }
+
+    pub fn font(mut self, family: &str, size: u32) -> Self {
+        self.font_family = family.to_string();
+        self.font_size = size;
+        self
+    }
+
+    /// Fit the box to the code: as wide as its longest line, up to the width
+    /// of the content area, and as tall as its lines
+    pub fn auto_size(mut self) -> Self {
+        let columns = self.code.lines().map(|line| self.columns(line)).max().unwrap_or(0);
+        let width = ((columns + 1) as f64 * self.char_width()) as i64 + 2 * INSET_X;
+        self.width = width.min(CONTENT_WIDTH as i64);
+        self.height = self.text_height();
+        self
+    }
+
+    /// Fence info string that md2ppt reads back: the language (`diff-rust`
+    /// for diffs), highlighted lines counted from the first line, and
+    /// `showLineNumbers`
+    pub fn info_string(&self) -> String {
+        let language = match (self.diff, self.language.as_str()) {
+            (true, "" | "text") => "diff".to_string(),
+            (true, language) => format!("diff-{language}"),
+            (false, "text") => String::new(),
+            (false, language) => language.to_string(),
+        };
+        let mut lines: Vec<usize> = self.highlight_lines.iter()
+            .filter(|&&line| (self.first_line..=self.last_line()).contains(&line))
+            .map(|line| line + 1 - self.first_line)
+            .collect();
+        lines.sort_unstable();
+        lines.dedup();
+        let mut ranges: Vec<(usize, usize)> = Vec::new();
+        for line in lines {
+            match ranges.last_mut() {
+                Some((_, end)) if *end + 1 == line => *end = line,
+                _ => ranges.push((line, line)),
+            }
+        }
+
+        let mut info = vec![language];
+        if !ranges.is_empty() {
+            let ranges: Vec<String> = ranges.iter()
+                .map(|&(start, end)| if start == end { start.to_string() } else { format!("{start}-{end}") })
+                .collect();
+            info.push(format!("{{{}}}", ranges.join(",")));
+        }
+        if self.line_numbers {
+            info.push("showLineNumbers".to_string());
+        }
+        info.join(" ").trim().to_string()
+    }
+
+    /// Number of the last line
+    pub fn last_line(&self) -> usize {
+        self.first_line + self.code.lines().count().saturating_sub(1)
+    }
+
+    /// Height in EMU the code needs at the width of the box, wrapped lines included
+    pub fn text_height(&self) -> i64 {
+        let rows: i64 = self.code.lines().map(|line| self.rows(line)).sum();
+        rows * self.line_height() + 2 * INSET_Y
+    }
+
+    /// How many leading lines fit in `height` EMU; at least one
+    pub fn lines_within(&self, height: i64) -> usize {
+        let mut used = 2 * INSET_Y;
+        let fitting = self.code.lines()
+            .take_while(|line| {
+                used += self.rows(line) * self.line_height();
+                used <= height
+            })
+            .count();
+        fitting.max(1)
+    }
+
+    /// Split before the line at index `at`, both parts sized to their lines;
+    /// the second part keeps numbering from where the first stops
+    pub fn split_at(&self, at: usize) -> (CodeBlock, CodeBlock) {
+        let lines: Vec<&str> = self.code.lines().collect();
+        let at = at.min(lines.len());
+        let mut head = Self { code: lines[..at].join("\n"), ..self.clone() };
+        head.height = head.text_height();
+        let mut tail = Self { code: lines[at..].join("\n"), first_line: self.first_line + at, ..self.clone() };
+        tail.height = tail.text_height();
+        (head, tail)
+    }
+
+    /// Characters of a line as shown, with its line number
+    fn columns(&self, line: &str) -> usize {
+        let gutter = if self.line_numbers { self.last_line().to_string().len() + 1 } else { 0 };
+        gutter + line.chars().count()
+    }
+
+    /// Rows a line takes when wrapped at the width of the box
+    fn rows(&self, line: &str) -> i64 {
+        let per_row = (((self.width - 2 * INSET_X) as f64 / self.char_width()) as usize).max(1);
+        self.columns(line).div_ceil(per_row).max(1) as i64
+    }
+
+    fn char_width(&self) -> f64 {
+        points_to_emu(self.font_size as f64) as f64 * CHAR_WIDTH
+    }
+
+    fn line_height(&self) -> i64 {
+        points_to_emu(self.font_size as f64 * LINE_HEIGHT) as i64
+    }
+}
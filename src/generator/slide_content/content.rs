@@ -1,15 +1,22 @@
 //! SlideContent struct for complex presentations
 
-use crate::generator::tables::Table;
+use crate::generator::table::{Table, TableCell};
 use crate::generator::shapes::Shape;
-use crate::generator::images::Image;
+use crate::generator::groups::ShapeGroup;
+use crate::generator::images::{Image, ImageCrop};
+use crate::generator::constants::{SLIDE_HEIGHT, SLIDE_WIDTH};
 use crate::generator::connectors::Connector;
 use crate::generator::media::{Video, Audio};
 use crate::generator::charts::Chart;
+use crate::generator::hyperlinks::Hyperlink;
+use crate::generator::text::TextFrame;
+use crate::generator::background::Background;
+use crate::parts::animation::SlideTransition;
 
 use super::bullet::{BulletStyle, BulletPoint};
-use super::layout::SlideLayout;
+use super::layout::{ImageSide, SlideLayout};
 use super::code_block::CodeBlock;
+use super::equation::Equation;
 
 /// Slide content for more complex presentations
 #[derive(Clone, Debug)]
@@ -36,9 +43,13 @@ pub struct SlideContent {
     pub layout: SlideLayout,
     pub table: Option<Table>,
     pub shapes: Vec<Shape>,
+    /// Shape groups (rendered as `p:grpSp`)
+    pub groups: Vec<ShapeGroup>,
     pub images: Vec<Image>,
     /// Speaker notes for the slide
     pub notes: Option<String>,
+    /// Formatted speaker notes; `notes` holds their plain text
+    pub rich_notes: Option<TextFrame>,
     /// Connectors between shapes
     pub connectors: Vec<Connector>,
     /// Videos embedded in slide
@@ -49,6 +60,20 @@ pub struct SlideContent {
     pub charts: Vec<Chart>,
     /// Code blocks with syntax highlighting
     pub code_blocks: Vec<CodeBlock>,
+    /// Equations rendered as Office Math
+    pub equations: Vec<Equation>,
+    /// Background fill (None = master background)
+    pub background: Option<Background>,
+    /// Hide the master's background graphics on this slide
+    pub hide_background_graphics: bool,
+    /// Transition into this slide (None = presentation default)
+    pub transition: Option<SlideTransition>,
+    /// Index of the first bullet of the right column (None = split evenly)
+    pub column_break: Option<usize>,
+    /// Headings of the left and right columns in the comparison layout
+    pub column_headings: Option<(String, String)>,
+    /// Half of the slide taken by a side picture; title and text use the other
+    pub image_side: Option<ImageSide>,
 }
 
 impl SlideContent {
@@ -74,13 +99,22 @@ impl SlideContent {
             layout: SlideLayout::TitleAndContent,
             table: None,
             shapes: Vec::new(),
+            groups: Vec::new(),
             images: Vec::new(),
             notes: None,
+            rich_notes: None,
             connectors: Vec::new(),
             videos: Vec::new(),
             audios: Vec::new(),
             charts: Vec::new(),
             code_blocks: Vec::new(),
+            equations: Vec::new(),
+            background: None,
+            hide_background_graphics: false,
+            transition: None,
+            column_break: None,
+            column_headings: None,
+            image_side: None,
         }
     }
 
@@ -91,6 +125,13 @@ impl SlideContent {
         self
     }
     
+    /// Add a bullet point whose text is a hyperlink
+    pub fn add_link_bullet(mut self, text: &str, hyperlink: Hyperlink) -> Self {
+        self.content.push(text.to_string());
+        self.bullets.push(BulletPoint::new(text).with_style(self.bullet_style).with_hyperlink(hyperlink));
+        self
+    }
+    
     /// Add a bullet point with specific style
     pub fn add_styled_bullet(mut self, text: &str, style: BulletStyle) -> Self {
         self.content.push(text.to_string());
@@ -213,6 +254,12 @@ impl SlideContent {
         self
     }
 
+    /// Add a shape group to the slide
+    pub fn add_group(mut self, group: ShapeGroup) -> Self {
+        self.groups.push(group);
+        self
+    }
+
     /// Add an image to the slide
     pub fn add_image(mut self, image: Image) -> Self {
         self.images.push(image);
@@ -220,6 +267,27 @@ impl SlideContent {
         self
     }
 
+    /// Fill one half of the slide with a picture, cropped to fit; the title
+    /// and text of the title and content layout move to the other half
+    pub fn side_image(mut self, image: Image, side: ImageSide) -> Self {
+        let (width, height) = (SLIDE_WIDTH / 2, SLIDE_HEIGHT);
+        let x = if side == ImageSide::Left { 0 } else { SLIDE_WIDTH - width };
+        let mut image = Image { x, y: 0, width, height, ..image };
+        if let Some((pixel_width, pixel_height)) = image.pixel_size().filter(|&(w, h)| w > 0 && h > 0) {
+            // Crop the excess on both sides to keep the picture's proportions
+            let frame = width as f64 / height as f64;
+            let picture = pixel_width as f64 / pixel_height as f64;
+            let excess = |ratio: f64| (1.0 - ratio) * 50.0;
+            image.crop = Some(if picture > frame {
+                ImageCrop::percent(excess(frame / picture), 0.0, excess(frame / picture), 0.0)
+            } else {
+                ImageCrop::percent(0.0, excess(picture / frame), 0.0, excess(picture / frame))
+            });
+        }
+        self.image_side = Some(side);
+        self.add_image(image)
+    }
+
     /// Add multiple images to the slide
     pub fn with_images(mut self, images: Vec<Image>) -> Self {
         self.images.extend(images);
@@ -230,6 +298,49 @@ impl SlideContent {
     /// Add speaker notes to the slide
     pub fn notes(mut self, notes: &str) -> Self {
         self.notes = Some(notes.to_string());
+        self.rich_notes = None;
+        self
+    }
+
+    /// Add formatted speaker notes (bullets, runs, links) to the slide
+    pub fn rich_notes(mut self, notes: TextFrame) -> Self {
+        self.notes = Some(notes.plain_text());
+        self.rich_notes = Some(notes);
+        self
+    }
+
+    /// Speaker notes as a text frame (one paragraph per line for plain notes)
+    pub fn notes_frame(&self) -> Option<TextFrame> {
+        self.rich_notes.clone().or_else(|| self.notes.as_deref().map(TextFrame::from))
+    }
+
+    /// Set the slide background
+    pub fn background(mut self, background: Background) -> Self {
+        self.background = Some(background);
+        self
+    }
+
+    /// Hide the master's background graphics (logos, lines, ...) on this slide
+    pub fn hide_background_graphics(mut self) -> Self {
+        self.hide_background_graphics = true;
+        self
+    }
+
+    /// Set the transition into this slide
+    pub fn transition(mut self, transition: SlideTransition) -> Self {
+        self.transition = Some(transition);
+        self
+    }
+
+    /// Start the right column of a two-column or comparison slide with the next bullet
+    pub fn column_break(mut self) -> Self {
+        self.column_break = Some(self.bullets.len().max(self.content.len()));
+        self
+    }
+
+    /// Set the column headings of a comparison slide
+    pub fn column_headings(mut self, left: &str, right: &str) -> Self {
+        self.column_headings = Some((left.to_string(), right.to_string()));
         self
     }
 
@@ -288,7 +399,43 @@ impl SlideContent {
         self
     }
 
+    /// Add a code block to the slide
+    pub fn add_code_block(mut self, code_block: CodeBlock) -> Self {
+        self.code_blocks.push(code_block);
+        self
+    }
+
+    /// Add an equation to the slide
+    pub fn add_equation(mut self, equation: Equation) -> Self {
+        self.equations.push(equation);
+        self
+    }
+
     /// Check if slide has any media
+    /// Hyperlinks of bullets, shapes, groups, images and table cells, in that order
+    pub fn hyperlinks(&self) -> Vec<&Hyperlink> {
+        let mut links: Vec<&Hyperlink> = self.bullets.iter().flat_map(BulletPoint::hyperlinks).collect();
+        links.extend(self.shapes.iter().flat_map(Shape::hyperlinks));
+        links.extend(self.groups.iter().flat_map(ShapeGroup::hyperlinks));
+        links.extend(self.images.iter().filter_map(|i| i.hyperlink.as_ref()));
+        if let Some(table) = &self.table {
+            links.extend(table.rows.iter().flat_map(|r| &r.cells).flat_map(TableCell::hyperlinks));
+        }
+        links
+    }
+
+    /// Mutable hyperlinks, in the same order as `hyperlinks()`
+    pub fn hyperlinks_mut(&mut self) -> Vec<&mut Hyperlink> {
+        let mut links: Vec<&mut Hyperlink> = self.bullets.iter_mut().flat_map(BulletPoint::hyperlinks_mut).collect();
+        links.extend(self.shapes.iter_mut().flat_map(Shape::hyperlinks_mut));
+        links.extend(self.groups.iter_mut().flat_map(ShapeGroup::hyperlinks_mut));
+        links.extend(self.images.iter_mut().filter_map(|i| i.hyperlink.as_mut()));
+        if let Some(table) = &mut self.table {
+            links.extend(table.rows.iter_mut().flat_map(|r| &mut r.cells).flat_map(TableCell::hyperlinks_mut));
+        }
+        links
+    }
+
     pub fn has_media(&self) -> bool {
         !self.videos.is_empty() || !self.audios.is_empty()
     }
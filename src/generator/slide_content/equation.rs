@@ -0,0 +1,61 @@
+//! Math equation type
+
+use crate::exc::PptxError;
+use crate::generator::constants::{CONTENT_WIDTH, CONTENT_X, CONTENT_Y_START};
+use crate::generator::omml::MathExpr;
+
+/// A LaTeX equation, rendered as editable Office Math
+#[derive(Clone, Debug)]
+pub struct Equation {
+    pub latex: String,
+    /// Centered on its own (`$$...$$`); inline equations are left-aligned
+    pub display: bool,
+    /// Font size in points
+    pub font_size: u32,
+    pub x: i64,
+    pub y: i64,
+    pub width: i64,
+    pub height: i64,
+}
+
+impl Equation {
+    /// Display equation, centered
+    pub fn new(latex: &str) -> Self {
+        Self {
+            latex: latex.to_string(),
+            display: true,
+            font_size: 28,
+            x: CONTENT_X as i64,
+            y: CONTENT_Y_START as i64,
+            width: CONTENT_WIDTH as i64,
+            height: 914400,
+        }
+    }
+
+    /// Inline equation, left-aligned like body text
+    pub fn inline(latex: &str) -> Self {
+        Self { display: false, height: 457200, font_size: 24, ..Self::new(latex) }
+    }
+
+    pub fn position(mut self, x: i64, y: i64) -> Self {
+        self.x = x;
+        self.y = y;
+        self
+    }
+
+    pub fn size(mut self, width: i64, height: i64) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    pub fn font_size(mut self, size: u32) -> Self {
+        self.font_size = size;
+        self
+    }
+
+    /// Check that the LaTeX converts; equations that do not are shown as text
+    pub fn validate(&self) -> Result<(), PptxError> {
+        MathExpr::parse(&self.latex).map(|_| ())
+    }
+}
@@ -15,6 +15,8 @@ pub enum SlideLayout {
     CenteredTitle,
     /// Two columns: title on left, content on right
     TwoColumn,
+    /// Two columns, each under its own heading
+    Comparison,
 }
 
 impl SlideLayout {
@@ -26,7 +28,15 @@ impl SlideLayout {
             SlideLayout::Blank => "blank",
             SlideLayout::CenteredTitle => "centeredTitle",
             SlideLayout::TwoColumn => "twoColumn",
+            SlideLayout::Comparison => "comparison",
         }
     }
 }
 
+/// Half of the slide taken by a side picture
+#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+pub enum ImageSide {
+    Left,
+    Right,
+}
+
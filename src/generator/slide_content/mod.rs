@@ -7,14 +7,20 @@
 //! - `SlideLayout` - Layout types (title only, title and content, etc.)
 //! - `SlideContent` - Complete slide content builder
 //! - `CodeBlock` - Code block with syntax highlighting
+//! - `Equation` - LaTeX equation rendered as Office Math
+//! - `OverflowWarning` - Overflow detection and continuation slides
 
 mod bullet;
 mod layout;
 mod code_block;
+mod equation;
 mod content;
+mod overflow;
 
 pub use bullet::{BulletStyle, BulletPoint, BulletTextFormat};
-pub use layout::SlideLayout;
+pub use layout::{ImageSide, SlideLayout};
 pub use code_block::CodeBlock;
+pub use equation::Equation;
 pub use content::SlideContent;
+pub use overflow::{OverflowElement, OverflowWarning, check_overflow, paginate_slides};
 
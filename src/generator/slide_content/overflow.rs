@@ -0,0 +1,504 @@
+//! Overflow detection, shrink-to-fit and continuation slides
+//!
+//! Body text and tables are measured with a [`TextMeasurer`] against the
+//! placeholder area of the slide layout; code blocks by their line count.
+
+use std::fmt;
+
+use crate::generator::constants::{COLUMN_HEADING_HEIGHT, CONTENT_HEIGHT, CONTENT_HEIGHT_BIG, CONTENT_WIDTH, CONTENT_X, CONTENT_Y_START, SLIDE_WIDTH};
+use crate::generator::shapes::{Shape, ShapeType};
+use crate::generator::table::Table;
+use crate::generator::text::{Paragraph, Run, TextAlign, TextBox, TextFrame, TextMeasurer, emu_to_points, points_to_emu};
+
+use super::bullet::BulletPoint;
+use super::code_block::CodeBlock;
+use super::content::SlideContent;
+use super::layout::SlideLayout;
+
+/// Width of one column in the two-column layout
+const COLUMN_WIDTH: u32 = 4115100;
+
+/// Suffix added to the title of continuation slides
+const CONTINUED_SUFFIX: &str = " (cont.)";
+
+/// Part of a slide that doesn't fit its area
+#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+pub enum OverflowElement {
+    /// Bullets in the body placeholder
+    Body,
+    /// Table rows below the bottom of the content area
+    Table,
+    /// Code lines below the bottom of the content area
+    Code,
+}
+
+impl fmt::Display for OverflowElement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OverflowElement::Body => write!(f, "body text"),
+            OverflowElement::Table => write!(f, "table"),
+            OverflowElement::Code => write!(f, "code block"),
+        }
+    }
+}
+
+/// Warning for content that overflows its placeholder
+#[derive(Clone, Debug, PartialEq)]
+pub struct OverflowWarning {
+    /// Slide number (1-based)
+    pub slide: usize,
+    pub title: String,
+    pub element: OverflowElement,
+    /// Height needed, in EMU
+    pub required: u32,
+    /// Height available, in EMU
+    pub available: u32,
+}
+
+impl fmt::Display for OverflowWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "slide {} \"{}\": {} needs {:.0}pt but only {:.0}pt is available",
+            self.slide,
+            self.title,
+            self.element,
+            emu_to_points(self.required),
+            emu_to_points(self.available)
+        )
+    }
+}
+
+impl SlideContent {
+    /// Placeholder that holds the bullets, and its number of columns
+    fn body_area(&self) -> Option<(TextBox, usize)> {
+        match self.layout {
+            SlideLayout::TitleAndContent if self.table.is_none() => {
+                let width = if self.image_side.is_some() { SLIDE_WIDTH / 2 - 914400 } else { CONTENT_WIDTH };
+                Some((TextBox::new(width, CONTENT_HEIGHT), 1))
+            }
+            SlideLayout::TitleAndBigContent => Some((TextBox::new(CONTENT_WIDTH, CONTENT_HEIGHT_BIG), 1)),
+            SlideLayout::TwoColumn => Some((TextBox::new(COLUMN_WIDTH, CONTENT_HEIGHT_BIG), 2)),
+            SlideLayout::Comparison => {
+                let headings = if self.column_headings.is_some() { COLUMN_HEADING_HEIGHT } else { 0 };
+                Some((TextBox::new(COLUMN_WIDTH, CONTENT_HEIGHT_BIG - headings), 2))
+            }
+            _ => None,
+        }
+    }
+
+    /// Bullets as rendered: styled bullets, or the plain content lines
+    fn body_bullets(&self) -> Vec<BulletPoint> {
+        if !self.bullets.is_empty() {
+            return self.bullets.clone();
+        }
+        self.content
+            .iter()
+            .map(|text| BulletPoint::new(text).with_style(self.bullet_style))
+            .collect()
+    }
+
+    fn default_content_size(&self) -> u32 {
+        let fallback = if matches!(self.layout, SlideLayout::TwoColumn | SlideLayout::Comparison) { 24 } else { 28 };
+        self.content_size.unwrap_or(fallback)
+    }
+
+    /// Height in points of one bullet paragraph in a column of `width` points
+    fn bullet_height(&self, bullet: &BulletPoint, width: f64, measurer: &TextMeasurer) -> f64 {
+        let format = bullet.format.as_ref();
+        let size = bullet
+            .runs
+            .iter()
+            .filter_map(|r| r.format.font_size)
+            .chain(format.and_then(|f| f.font_size))
+            .max()
+            .unwrap_or(self.default_content_size()) as f64;
+        let bold = self.content_bold || format.is_some_and(|f| f.bold);
+        let text = if bullet.runs.is_empty() {
+            bullet.text.clone()
+        } else {
+            bullet.runs.iter().map(|r| r.text.as_str()).collect()
+        };
+        let indent = emu_to_points(457200 * (1 + 2 * bullet.level));
+        measurer.text_height(&text, size, bold, (width - indent).max(size))
+    }
+
+    /// Height in EMU the body text needs, and the height of its placeholder
+    pub fn body_height(&self, measurer: &TextMeasurer) -> Option<(u32, u32)> {
+        let (text_box, columns) = self.body_area()?;
+        let bullets = self.body_bullets();
+        let width = text_box.content_width();
+        let mid = match self.column_break {
+            Some(column_break) if columns == 2 => column_break.min(bullets.len()),
+            _ => bullets.len().div_ceil(columns),
+        };
+        let (first, rest) = bullets.split_at(mid);
+        let text_height = [first, rest]
+            .into_iter()
+            .map(|column| column.iter().map(|b| self.bullet_height(b, width, measurer)).sum::<f64>())
+            .fold(0.0, f64::max);
+        let required = points_to_emu(text_height) + text_box.insets.1 + text_box.insets.3;
+        Some((required, text_box.height))
+    }
+
+    /// Height in EMU available to the table, from its top to the bottom of the content area
+    fn table_space(table: &Table) -> u32 {
+        (CONTENT_Y_START + CONTENT_HEIGHT).saturating_sub(table.y)
+    }
+
+    /// Height in EMU available to the code block, from its top to the bottom of the content area
+    fn code_space(block: &CodeBlock) -> u32 {
+        (CONTENT_Y_START + CONTENT_HEIGHT).saturating_sub(block.y.max(0) as u32)
+    }
+
+    /// Overflow warnings for this slide, numbered as `slide`
+    pub fn check_overflow(&self, slide: usize, measurer: &TextMeasurer) -> Vec<OverflowWarning> {
+        let mut warnings = Vec::new();
+        let mut warn = |element, required, available| {
+            if required > available {
+                warnings.push(OverflowWarning {
+                    slide,
+                    title: self.title.clone(),
+                    element,
+                    required,
+                    available,
+                });
+            }
+        };
+
+        if let Some((required, available)) = self.body_height(measurer) {
+            warn(OverflowElement::Body, required, available);
+        }
+        if let Some(ref table) = self.table
+            && self.layout == SlideLayout::TitleAndContent
+        {
+            warn(OverflowElement::Table, table.measured_height(measurer), Self::table_space(table));
+        }
+        if self.layout == SlideLayout::TitleAndContent {
+            for block in &self.code_blocks {
+                warn(OverflowElement::Code, block.text_height().max(0) as u32, Self::code_space(block));
+            }
+        }
+        warnings
+    }
+
+    /// Whether the body text, table or a code block overflows
+    pub fn overflows(&self, measurer: &TextMeasurer) -> bool {
+        !self.check_overflow(1, measurer).is_empty()
+    }
+
+    /// Reduce the body font size until the text fits, but not below `min_size` points
+    ///
+    /// Explicit bullet and run sizes are scaled in proportion.
+    pub fn shrink_to_fit(self, measurer: &TextMeasurer, min_size: u32) -> Self {
+        let base = self.default_content_size();
+        if self.body_height(measurer).is_none_or(|(required, available)| required <= available) {
+            return self;
+        }
+
+        let mut fitted = self.scaled(base, min_size.min(base));
+        for size in (min_size..base).rev() {
+            let candidate = self.scaled(base, size);
+            if candidate.body_height(measurer).is_some_and(|(required, available)| required <= available) {
+                fitted = candidate;
+                break;
+            }
+        }
+        fitted
+    }
+
+    /// Copy with the body text scaled from `base` to `size` points
+    fn scaled(&self, base: u32, size: u32) -> Self {
+        let scale = |s: u32| (s * size / base).max(1);
+        let mut slide = self.clone();
+        slide.content_size = Some(size);
+        for bullet in &mut slide.bullets {
+            if let Some(ref mut format) = bullet.format {
+                format.font_size = format.font_size.map(scale);
+            }
+            for run in &mut bullet.runs {
+                run.format.font_size = run.format.font_size.map(scale);
+            }
+        }
+        slide
+    }
+
+    /// Split overflowing bullets, table rows or code lines onto continuation slides
+    ///
+    /// Continuation slides are titled "Title (cont.)" and carry only the body
+    /// content; shapes, images and notes stay on the first slide. Tables are
+    /// split as described by [`SlideContent::paginate_table`].
+    pub fn paginate(&self, measurer: &TextMeasurer) -> Vec<SlideContent> {
+        let warnings = self.check_overflow(1, measurer);
+        let overflows = |element| warnings.iter().any(|w| w.element == element);
+        let pages = if overflows(OverflowElement::Table) {
+            self.paginate_table(measurer)
+        } else if overflows(OverflowElement::Body) {
+            self.paginate_bullets(measurer)
+        } else {
+            vec![self.clone()]
+        };
+        pages.into_iter().flat_map(SlideContent::paginate_code).collect()
+    }
+
+    /// Split a code block running past the content area: the slide keeps the
+    /// lines that fit, and continuation slides carry the rest from the top of
+    /// the content area, numbered on
+    fn paginate_code(self) -> Vec<SlideContent> {
+        let overflowing = self.code_blocks.iter().position(|block| {
+            block.code.lines().nth(1).is_some() && block.text_height().max(0) as u32 > Self::code_space(block)
+        });
+        let Some(index) = overflowing.filter(|_| self.layout == SlideLayout::TitleAndContent) else {
+            return vec![self];
+        };
+
+        let block = &self.code_blocks[index];
+        let (head, mut rest) = block.split_at(block.lines_within(Self::code_space(block) as i64));
+        let mut continuations = Vec::new();
+        while !rest.code.is_empty() {
+            rest.y = CONTENT_Y_START as i64;
+            let (part, tail) = rest.split_at(rest.lines_within(CONTENT_HEIGHT as i64));
+            let mut page = self.continuation();
+            page.code_blocks.push(part);
+            continuations.push(page);
+            rest = tail;
+        }
+
+        // Other code blocks on the first slide may overflow too
+        let mut first = self;
+        first.code_blocks[index] = head;
+        let mut pages = first.paginate_code();
+        let others = pages.split_off(1);
+        pages.extend(continuations);
+        pages.extend(others);
+        pages
+    }
+
+    fn paginate_bullets(&self, measurer: &TextMeasurer) -> Vec<SlideContent> {
+        let count = self.body_bullets().len();
+        let fits = |slide: &SlideContent| {
+            slide.body_height(measurer).is_none_or(|(required, available)| required <= available)
+        };
+
+        let mut pages = Vec::new();
+        let mut start = 0;
+        while start < count {
+            let mut end = start + 1;
+            while end < count && fits(&self.with_body_range(start, end + 1)) {
+                end += 1;
+            }
+            pages.push((start, end));
+            start = end;
+        }
+
+        pages
+            .into_iter()
+            .enumerate()
+            .map(|(i, (start, end))| {
+                let page = if i == 0 { self.clone() } else { self.continuation() };
+                page.with_body_range_of(self, start, end)
+            })
+            .collect()
+    }
+
+    /// Split an overflowing table over continuation slides
+    ///
+    /// Uses the table's [`TablePagination`], or a repeated single header row
+    /// without captions when none is set.
+    pub fn paginate_table(&self, measurer: &TextMeasurer) -> Vec<SlideContent> {
+        let table = match self.table {
+            Some(ref table) if self.layout == SlideLayout::TitleAndContent => table,
+            _ => return vec![self.clone()],
+        };
+        let pagination = table.pagination.clone().unwrap_or_default();
+        let parts = table.split(Self::table_space(table), &pagination, measurer);
+        let total = parts.len();
+        if total == 1 {
+            return vec![self.clone()];
+        }
+
+        parts
+            .into_iter()
+            .enumerate()
+            .map(|(i, part)| {
+                let mut page = if i == 0 { self.clone() } else { self.continuation() };
+                page.table = Some(part);
+                if pagination.captions {
+                    page.shapes.push(page_caption(i + 1, total));
+                }
+                page
+            })
+            .collect()
+    }
+
+    /// Copy keeping bullets `start..end` of this slide
+    fn with_body_range(&self, start: usize, end: usize) -> SlideContent {
+        self.clone().with_body_range_of(self, start, end)
+    }
+
+    /// Replace the body of `self` with bullets `start..end` of `source`
+    fn with_body_range_of(mut self, source: &SlideContent, start: usize, end: usize) -> SlideContent {
+        self.column_break = source.column_break.map(|column_break| column_break.clamp(start, end) - start);
+        if source.bullets.is_empty() {
+            self.content = source.content[start..end].to_vec();
+            self.bullets.clear();
+        } else {
+            self.bullets = source.bullets[start..end].to_vec();
+            self.content = if source.content.len() == source.bullets.len() {
+                source.content[start..end].to_vec()
+            } else {
+                self.bullets.iter().map(|b| b.text.clone()).collect()
+            };
+        }
+        self
+    }
+
+    /// Empty continuation of this slide, with the same layout and text styling
+    fn continuation(&self) -> SlideContent {
+        let base_title = self.title.strip_suffix(CONTINUED_SUFFIX).unwrap_or(&self.title);
+        SlideContent {
+            title: format!("{base_title}{CONTINUED_SUFFIX}"),
+            content: Vec::new(),
+            bullets: Vec::new(),
+            table: None,
+            shapes: Vec::new(),
+            groups: Vec::new(),
+            images: Vec::new(),
+            notes: None,
+            rich_notes: None,
+            connectors: Vec::new(),
+            videos: Vec::new(),
+            audios: Vec::new(),
+            charts: Vec::new(),
+            code_blocks: Vec::new(),
+            equations: Vec::new(),
+            ..self.clone()
+        }
+    }
+}
+
+/// "Page X of Y" caption below the content area
+fn page_caption(page: usize, total: usize) -> Shape {
+    let caption = Run::new(&format!("Page {page} of {total}")).size(12).color("7F7F7F");
+    Shape::new(ShapeType::Rectangle, CONTENT_X, CONTENT_Y_START + CONTENT_HEIGHT, CONTENT_WIDTH, 369332)
+        .with_text_frame(TextFrame::new().add_paragraph(Paragraph::new().align(TextAlign::Right).add_run(caption)))
+}
+
+/// Overflow warnings for a whole presentation
+pub fn check_overflow(slides: &[SlideContent], measurer: &TextMeasurer) -> Vec<OverflowWarning> {
+    slides
+        .iter()
+        .enumerate()
+        .flat_map(|(i, slide)| slide.check_overflow(i + 1, measurer))
+        .collect()
+}
+
+/// Paginate every slide, inserting continuation slides where content overflows
+pub fn paginate_slides(slides: Vec<SlideContent>, measurer: &TextMeasurer) -> Vec<SlideContent> {
+    slides.iter().flat_map(|slide| slide.paginate(measurer)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::table::{TableCell, TableRow};
+
+    fn long_list(count: usize) -> SlideContent {
+        (1..=count).fold(SlideContent::new("Agenda"), |slide, i| {
+            slide.add_bullet(&format!("Item number {i} with a fairly long description that wraps"))
+        })
+    }
+
+    #[test]
+    fn test_no_overflow() {
+        let measurer = TextMeasurer::new();
+        let slide = long_list(3);
+        assert!(!slide.overflows(&measurer));
+        assert_eq!(slide.paginate(&measurer).len(), 1);
+        assert!(SlideContent::new("Title only").layout(SlideLayout::TitleOnly).check_overflow(1, &measurer).is_empty());
+    }
+
+    #[test]
+    fn test_body_overflow_warning() {
+        let measurer = TextMeasurer::new();
+        let warnings = check_overflow(&[long_list(2), long_list(20)], &measurer);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].slide, 2);
+        assert_eq!(warnings[0].element, OverflowElement::Body);
+        assert!(warnings[0].required > warnings[0].available);
+        assert!(warnings[0].to_string().starts_with("slide 2 \"Agenda\": body text needs"));
+    }
+
+    #[test]
+    fn test_shrink_to_fit() {
+        let measurer = TextMeasurer::new();
+        let slide = long_list(8).shrink_to_fit(&measurer, 10);
+        assert!(slide.content_size.unwrap() < 28);
+        assert!(!slide.overflows(&measurer));
+
+        let unchanged = long_list(2).shrink_to_fit(&measurer, 10);
+        assert_eq!(unchanged.content_size, Some(28));
+    }
+
+    #[test]
+    fn test_paginate_bullets() {
+        let measurer = TextMeasurer::new();
+        let slide = long_list(20).notes("Speaker notes");
+        let pages = slide.paginate(&measurer);
+
+        assert!(pages.len() > 1);
+        assert_eq!(pages[0].title, "Agenda");
+        assert_eq!(pages[1].title, "Agenda (cont.)");
+        assert!(pages[1].notes.is_none());
+        assert_eq!(pages.iter().map(|p| p.bullets.len()).sum::<usize>(), 20);
+        assert!(pages.iter().all(|p| p.content.len() == p.bullets.len() && !p.overflows(&measurer)));
+        assert_eq!(pages.last().unwrap().bullets.last().unwrap().text, slide.bullets[19].text);
+    }
+
+    #[test]
+    fn test_paginate_table() {
+        let measurer = TextMeasurer::new();
+        let header = TableRow::new(vec![TableCell::new("Name").bold(), TableCell::new("Value").bold()]);
+        let rows = (0..40).map(|i| TableRow::new(vec![TableCell::new(&format!("Row {i}")), TableCell::new("x")]));
+        let table = Table::new(std::iter::once(header).chain(rows).collect(), vec![4000000, 4000000], 457200, 1600200);
+        let slide = SlideContent::new("Data").table(table);
+
+        assert_eq!(slide.check_overflow(1, &measurer)[0].element, OverflowElement::Table);
+        let pages = paginate_slides(vec![slide], &measurer);
+        assert!(pages.len() > 1);
+        for page in &pages {
+            let table = page.table.as_ref().unwrap();
+            assert_eq!(table.rows[0].cells[0].text, "Name");
+            assert!(!page.overflows(&measurer));
+        }
+        let data_rows: usize = pages.iter().map(|p| p.table.as_ref().unwrap().rows.len() - 1).sum();
+        assert_eq!(data_rows, 40);
+    }
+
+    #[test]
+    fn test_paginate_code() {
+        let measurer = TextMeasurer::new();
+        let code: Vec<String> = (1..=60).map(|i| format!("let x{i} = {i};")).collect();
+        let block = CodeBlock::new(&code.join("\n"), "rust").line_numbers().highlight_lines([45]).auto_size();
+        assert!(block.width < CONTENT_WIDTH as i64);
+        let slide = SlideContent::new("Listing").add_code_block(block);
+
+        let warnings = slide.check_overflow(3, &measurer);
+        assert_eq!(warnings[0].element, OverflowElement::Code);
+        assert!(warnings[0].to_string().starts_with("slide 3 \"Listing\": code block needs"));
+
+        let pages = slide.paginate(&measurer);
+        assert!(pages.len() > 1);
+        assert_eq!(pages[1].title, "Listing (cont.)");
+        let blocks: Vec<&CodeBlock> = pages.iter().flat_map(|p| &p.code_blocks).collect();
+        assert_eq!(blocks.len(), pages.len());
+        assert_eq!(blocks.iter().map(|b| b.code.lines().count()).sum::<usize>(), 60);
+        assert_eq!(blocks[1].first_line, blocks[0].code.lines().count() + 1);
+        assert_eq!(blocks[1].y, CONTENT_Y_START as i64);
+        assert!(blocks.iter().all(|b| b.highlight_lines == [45] && b.line_numbers));
+        assert!(pages.iter().all(|p| !p.overflows(&measurer)));
+
+        let short = SlideContent::new("Short").add_code_block(CodeBlock::new("fn main() {}", "rust").auto_size());
+        assert_eq!(short.paginate(&measurer).len(), 1);
+    }
+}
@@ -0,0 +1,280 @@
+//! Per-slide relationship allocation
+//!
+//! Relationship IDs of a generated slide are allocated in a fixed order
+//! (layout, notes, background picture, images, charts, hyperlinks) from the slide content alone, so the
+//! slide XML, its `.rels` part and the media written by the builder always agree.
+
+use std::borrow::Cow;
+
+use super::hyperlinks::Hyperlink;
+use super::package_xml::escape_xml;
+use super::slide_content::SlideContent;
+
+pub const REL_TYPE_SLIDE_LAYOUT: &str = "http://schemas.openxmlformats.org/officeDocument/2006/relationships/slideLayout";
+pub const REL_TYPE_NOTES_SLIDE: &str = "http://schemas.openxmlformats.org/officeDocument/2006/relationships/notesSlide";
+pub const REL_TYPE_IMAGE: &str = "http://schemas.openxmlformats.org/officeDocument/2006/relationships/image";
+pub const REL_TYPE_CHART: &str = "http://schemas.openxmlformats.org/officeDocument/2006/relationships/chart";
+pub const REL_TYPE_HYPERLINK: &str = "http://schemas.openxmlformats.org/officeDocument/2006/relationships/hyperlink";
+pub const REL_TYPE_SLIDE: &str = "http://schemas.openxmlformats.org/officeDocument/2006/relationships/slide";
+
+/// A relationship of a generated slide
+#[derive(Clone, Debug, PartialEq)]
+pub struct SlideRelationship {
+    /// Numeric part of the relationship ID (`rId{n}`)
+    pub id: usize,
+    pub rel_type: &'static str,
+    pub target: String,
+    pub external: bool,
+}
+
+/// A media file referenced by a slide
+#[derive(Clone, Debug)]
+pub struct SlideMedia {
+    /// Package path, e.g. `ppt/media/image1_1.png`
+    pub path: String,
+    pub extension: String,
+    pub data: Vec<u8>,
+}
+
+/// Relationships of one generated slide
+#[derive(Clone, Debug, Default)]
+pub struct SlideRelationships {
+    rels: Vec<SlideRelationship>,
+    /// Relationship ID of each image in `SlideContent::images` (None = not embedded)
+    image_ids: Vec<Option<usize>>,
+    /// Relationship ID of the background picture
+    background_id: Option<usize>,
+    /// Relationship ID of each chart in `SlideContent::charts`
+    chart_ids: Vec<usize>,
+}
+
+impl SlideRelationships {
+    /// Allocate the relationships for a slide
+    pub fn for_slide(slide_num: usize, content: &SlideContent) -> Self {
+        let mut rels = SlideRelationships::default();
+        rels.add(REL_TYPE_SLIDE_LAYOUT, "../slideLayouts/slideLayout1.xml".to_string(), false);
+        if content.notes.is_some() {
+            rels.add(REL_TYPE_NOTES_SLIDE, format!("../notesSlides/notesSlide{slide_num}.xml"), false);
+        }
+        if let Some(image) = content.background.as_ref().and_then(|bg| bg.image()) {
+            let target = format!("../media/{}", background_file_name(slide_num, &image.extension()));
+            rels.background_id = Some(rels.add(REL_TYPE_IMAGE, target, false));
+        }
+        for (i, image) in content.images.iter().enumerate() {
+            let id = image.has_data().then(|| {
+                let target = format!("../media/{}", media_file_name(slide_num, i, &image.extension()));
+                rels.add(REL_TYPE_IMAGE, target, false)
+            });
+            rels.image_ids.push(id);
+        }
+        for i in 0..content.charts.len() {
+            let id = rels.add(REL_TYPE_CHART, format!("../charts/{}", chart_file_name(slide_num, i)), false);
+            rels.chart_ids.push(id);
+        }
+        for hyperlink in content.hyperlinks() {
+            if rels.hyperlink_rel_id(hyperlink).is_none() {
+                rels.add_hyperlink(hyperlink);
+            }
+        }
+        rels
+    }
+
+    fn add_hyperlink(&mut self, hyperlink: &Hyperlink) {
+        if let Some(rel_type) = hyperlink.action.relationship_type() {
+            self.add(rel_type, hyperlink.action.relationship_target(), hyperlink.action.is_external());
+        }
+    }
+
+    fn add(&mut self, rel_type: &'static str, target: String, external: bool) -> usize {
+        let id = self.rels.len() + 1;
+        self.rels.push(SlideRelationship { id, rel_type, target, external });
+        id
+    }
+
+    /// Relationship ID of the image at `index`, if it is embedded
+    pub fn image_rel_id(&self, index: usize) -> Option<usize> {
+        self.image_ids.get(index).copied().flatten()
+    }
+
+    /// Relationship ID of the chart at `index`
+    pub fn chart_rel_id(&self, index: usize) -> Option<usize> {
+        self.chart_ids.get(index).copied()
+    }
+
+    /// Relationship ID of the background picture, if it is embedded
+    pub fn background_rel_id(&self) -> Option<usize> {
+        self.background_id
+    }
+
+    /// Relationship ID of a hyperlink; links with the same target share one relationship
+    pub fn hyperlink_rel_id(&self, hyperlink: &Hyperlink) -> Option<usize> {
+        let rel_type = hyperlink.action.relationship_type()?;
+        let target = hyperlink.action.relationship_target();
+        self.rels
+            .iter()
+            .find(|rel| rel.rel_type == rel_type && rel.target == target)
+            .map(|rel| rel.id)
+    }
+
+    /// Assign relationship IDs to the hyperlinks of a slide's content
+    ///
+    /// Show-jump actions (next slide, end show, ...) get an empty ID, as
+    /// PowerPoint writes them. Content without hyperlinks is not copied.
+    pub fn link_hyperlinks<'a>(&self, content: &'a SlideContent) -> Cow<'a, SlideContent> {
+        if content.hyperlinks().is_empty() {
+            return Cow::Borrowed(content);
+        }
+        let mut linked = content.clone();
+        for hyperlink in linked.hyperlinks_mut() {
+            let r_id = self.hyperlink_rel_id(hyperlink).map(|id| format!("rId{id}")).unwrap_or_default();
+            hyperlink.r_id = Some(r_id);
+        }
+        Cow::Owned(linked)
+    }
+
+    /// All relationships in ID order
+    pub fn relationships(&self) -> &[SlideRelationship] {
+        &self.rels
+    }
+
+    /// Generate the slide `.rels` XML
+    pub fn to_xml(&self) -> String {
+        let mut xml = String::from(r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#);
+        for rel in &self.rels {
+            let mode = if rel.external { r#" TargetMode="External""# } else { "" };
+            xml.push_str(&format!(
+                "\n<Relationship Id=\"rId{}\" Type=\"{}\" Target=\"{}\"{}/>",
+                rel.id, rel.rel_type, escape_xml(&rel.target), mode
+            ));
+        }
+        xml.push_str("\n</Relationships>");
+        xml
+    }
+}
+
+fn media_file_name(slide_num: usize, index: usize, extension: &str) -> String {
+    format!("image{}_{}.{}", slide_num, index + 1, extension)
+}
+
+/// File name of a slide's chart part under `ppt/charts/`
+pub fn chart_file_name(slide_num: usize, index: usize) -> String {
+    format!("chart{}_{}.xml", slide_num, index + 1)
+}
+
+/// File name of a chart's data workbook under `ppt/embeddings/`
+pub fn chart_workbook_file_name(slide_num: usize, index: usize) -> String {
+    format!("Microsoft_Excel_Worksheet{}_{}.xlsx", slide_num, index + 1)
+}
+
+fn background_file_name(slide_num: usize, extension: &str) -> String {
+    format!("background{slide_num}.{extension}")
+}
+
+/// Collect the media files embedded by a slide
+pub fn slide_media(slide_num: usize, content: &SlideContent) -> Vec<SlideMedia> {
+    let rels = SlideRelationships::for_slide(slide_num, content);
+    let background = content.background.as_ref().and_then(|bg| bg.image()).and_then(|image| {
+        let extension = image.extension();
+        Some(SlideMedia {
+            path: format!("ppt/media/{}", background_file_name(slide_num, &extension)),
+            data: image.get_bytes()?,
+            extension,
+        })
+    });
+    let images = content
+        .images
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| rels.image_rel_id(*i).is_some())
+        .filter_map(|(i, image)| {
+            let extension = image.extension();
+            Some(SlideMedia {
+                path: format!("ppt/media/{}", media_file_name(slide_num, i, &extension)),
+                data: image.get_bytes()?,
+                extension,
+            })
+        });
+    background.into_iter().chain(images).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::images::Image;
+    use crate::generator::hyperlinks::HyperlinkAction;
+
+    const PNG_1X1: &str = "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNk+M9QDwADhgGAWjR9awAAAABJRU5ErkJggg==";
+
+    #[test]
+    fn test_relationship_order() {
+        let content = SlideContent::new("Photos")
+            .notes("Speaker notes")
+            .add_image(Image::new("missing.png", 100, 100, "PNG"))
+            .add_image(Image::from_base64(PNG_1X1, 100, 100, "PNG"));
+        let rels = SlideRelationships::for_slide(3, &content);
+
+        assert_eq!(rels.relationships().len(), 3);
+        assert_eq!(rels.image_rel_id(0), None);
+        assert_eq!(rels.image_rel_id(1), Some(3));
+        assert_eq!(rels.relationships()[2].target, "../media/image3_2.png");
+
+        let xml = rels.to_xml();
+        assert!(xml.contains(r#"Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/notesSlide" Target="../notesSlides/notesSlide3.xml""#));
+    }
+
+    #[test]
+    fn test_chart_relationships() {
+        use crate::generator::charts::{ChartBuilder, ChartSeries, ChartType};
+
+        let chart = ChartBuilder::new("Sales", ChartType::Bar)
+            .categories(vec!["Q1"])
+            .add_series(ChartSeries::new("2024", vec![1.0]))
+            .build();
+        let content = SlideContent::new("Charts")
+            .add_image(Image::from_base64(PNG_1X1, 100, 100, "PNG"))
+            .add_chart(chart.clone())
+            .add_chart(chart);
+        let rels = SlideRelationships::for_slide(2, &content);
+
+        assert_eq!(rels.chart_rel_id(0), Some(3));
+        assert_eq!(rels.chart_rel_id(1), Some(4));
+        assert_eq!(rels.chart_rel_id(2), None);
+        assert_eq!(rels.relationships()[3].target, "../charts/chart2_2.xml");
+    }
+
+    #[test]
+    fn test_slide_media() {
+        let content = SlideContent::new("Photos").add_image(Image::from_base64(PNG_1X1, 100, 100, "PNG"));
+        let media = slide_media(1, &content);
+
+        assert_eq!(media.len(), 1);
+        assert_eq!(media[0].path, "ppt/media/image1_1.png");
+        assert_eq!(&media[0].data[1..4], b"PNG");
+    }
+
+    #[test]
+    fn test_hyperlink_relationships() {
+        use crate::generator::hyperlinks::Hyperlink;
+        use crate::generator::shapes::{Shape, ShapeType};
+
+        let content = SlideContent::new("Links")
+            .add_link_bullet("Docs", Hyperlink::url("https://docs.rs"))
+            .add_shape(Shape::new(ShapeType::Rectangle, 0, 0, 100, 100).with_hyperlink(Hyperlink::url("https://docs.rs")))
+            .add_shape(Shape::new(ShapeType::Rectangle, 0, 0, 100, 100).with_hyperlink(Hyperlink::slide(2)))
+            .add_image(Image::from_base64(PNG_1X1, 100, 100, "PNG").with_hyperlink(Hyperlink::new(HyperlinkAction::NextSlide)));
+        let rels = SlideRelationships::for_slide(1, &content);
+
+        // layout, image, one shared URL link, one slide jump
+        assert_eq!(rels.relationships().len(), 4);
+        assert_eq!(rels.hyperlink_rel_id(&Hyperlink::url("https://docs.rs")), Some(3));
+        assert_eq!(rels.hyperlink_rel_id(&Hyperlink::slide(2)), Some(4));
+        let xml = rels.to_xml();
+        assert!(xml.contains(r#"Id="rId3" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/hyperlink" Target="https://docs.rs" TargetMode="External"/>"#));
+        assert!(xml.contains(r#"Id="rId4" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slide" Target="slide2.xml"/>"#));
+
+        let linked = rels.link_hyperlinks(&content);
+        let r_ids: Vec<_> = linked.hyperlinks().iter().map(|h| h.r_id.clone().unwrap()).collect();
+        assert_eq!(r_ids, vec!["rId3", "rId3", "rId4", ""]);
+    }
+}
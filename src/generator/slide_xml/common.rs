@@ -1,5 +1,7 @@
 //! Common XML templates and utilities for slide generation
 
+use crate::generator::omml::MATH_NS;
+
 /// Standard slide header with background
 pub const SLIDE_HEADER: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
 <p:sld xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main">
@@ -33,6 +35,13 @@ pub const SLIDE_FOOTER: &str = r#"
 </p:clrMapOvr>
 </p:sld>"#;
 
+/// Office Math content, with a fallback for readers without math support
+pub fn math_alternate_content(choice: &str, fallback: &str) -> String {
+    format!(
+        r#"<mc:AlternateContent xmlns:mc="http://schemas.openxmlformats.org/markup-compatibility/2006"><mc:Choice xmlns:a14="http://schemas.microsoft.com/office/drawing/2010/main" xmlns:m="{MATH_NS}" Requires="a14">{choice}</mc:Choice><mc:Fallback>{fallback}</mc:Fallback></mc:AlternateContent>"#
+    )
+}
+
 /// Create slide relationships XML
 pub fn create_slide_rels_xml() -> String {
     r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
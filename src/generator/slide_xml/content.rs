@@ -1,10 +1,17 @@
-//! Additional content rendering (shapes, images, code blocks, connectors)
+//! Additional content rendering (shapes, groups, images, code blocks, connectors, equations, charts)
 
-use crate::generator::slide_content::SlideContent;
+use crate::generator::slide_content::{Equation, SlideContent};
 use crate::generator::shapes_xml::generate_shape_xml;
+use crate::generator::groups::generate_group_xml;
+use crate::generator::charts::generate_chart_frame_xml;
+use crate::generator::images_xml::generate_image_xml;
+use crate::generator::slide_rels::SlideRelationships;
+use crate::generator::omml::{MathExpr, MATH_FONT, MATH_NS};
+use crate::generator::package_xml::escape_xml;
+use super::common::math_alternate_content;
 
-/// Render additional content elements (shapes, images, code blocks, connectors)
-pub fn render_additional_content(xml: &mut String, content: &SlideContent) {
+/// Render additional content elements (shapes, groups, images, code blocks, connectors, equations, charts)
+pub fn render_additional_content(xml: &mut String, content: &SlideContent, rels: &SlideRelationships) {
     // Render shapes - use shape's fixed ID if set, otherwise auto-assign
     for (i, shape) in content.shapes.iter().enumerate() {
         xml.push('\n');
@@ -12,11 +19,22 @@ pub fn render_additional_content(xml: &mut String, content: &SlideContent) {
         xml.push_str(&generate_shape_xml(shape, shape_id));
     }
 
-    // Render image placeholders
+    // Render shape groups - IDs are allocated after all other elements
+    let mut group_id = (50 + content.shapes.len() + content.images.len()
+        + content.code_blocks.len() + content.connectors.len() + content.equations.len()) as u32;
+    for group in &content.groups {
+        xml.push('\n');
+        xml.push_str(&generate_group_xml(group, &mut group_id));
+    }
+
+    // Render images - embedded pictures, or placeholders when the data is unavailable
     let image_start_id = 20 + content.shapes.len();
     for (i, image) in content.images.iter().enumerate() {
         xml.push('\n');
-        xml.push_str(&generate_image_placeholder(image_start_id + i, image));
+        match rels.image_rel_id(i) {
+            Some(rel_id) => xml.push_str(&generate_image_xml(image, image_start_id + i, rel_id)),
+            None => xml.push_str(&generate_image_placeholder(image_start_id + i, image)),
+        }
     }
 
     // Render code blocks with syntax highlighting
@@ -33,6 +51,21 @@ pub fn render_additional_content(xml: &mut String, content: &SlideContent) {
         let id = connector_start_id + i;
         xml.push_str(&crate::generator::connectors::generate_connector_xml(connector, id));
     }
+
+    // Render equations after the connectors
+    let equation_start_id = connector_start_id + content.connectors.len();
+    for (i, equation) in content.equations.iter().enumerate() {
+        xml.push('\n');
+        xml.push_str(&generate_equation(equation_start_id + i, equation));
+    }
+
+    // Render chart frames - IDs continue after the groups
+    for (i, chart) in content.charts.iter().enumerate() {
+        let Some(rel_id) = rels.chart_rel_id(i) else { continue };
+        xml.push('\n');
+        xml.push_str(&generate_chart_frame_xml(chart, group_id as usize, rel_id));
+        group_id += 1;
+    }
 }
 
 /// Generate image placeholder XML
@@ -76,7 +109,13 @@ fn generate_image_placeholder(id: usize, image: &crate::generator::images::Image
 
 /// Generate code block XML with syntax highlighting
 fn generate_code_block(id: usize, code_block: &crate::generator::slide_content::CodeBlock) -> String {
-    let highlighted_xml = crate::cli::syntax::generate_highlighted_code_xml(&code_block.code, &code_block.language);
+    let rendered = crate::cli::syntax::render_code_block(code_block);
+    // The fence info string lets ppt2md write the block back as it was
+    let name = match code_block.info_string() {
+        info if info.is_empty() => "Code Block".to_string(),
+        info => escape_xml(&format!("Code Block: {info}")),
+    };
+    let (background, border, paragraphs) = (&rendered.background, &rendered.border, &rendered.paragraphs);
     let x = code_block.x;
     let y = code_block.y;
     let width = code_block.width;
@@ -85,7 +124,7 @@ fn generate_code_block(id: usize, code_block: &crate::generator::slide_content::
     format!(
         r#"<p:sp>
 <p:nvSpPr>
-<p:cNvPr id="{id}" name="Code Block"/>
+<p:cNvPr id="{id}" name="{name}"/>
 <p:cNvSpPr txBox="1"/>
 <p:nvPr/>
 </p:nvSpPr>
@@ -95,13 +134,64 @@ fn generate_code_block(id: usize, code_block: &crate::generator::slide_content::
 <a:ext cx="{width}" cy="{height}"/>
 </a:xfrm>
 <a:prstGeom prst="rect"><a:avLst/></a:prstGeom>
-<a:solidFill><a:srgbClr val="002B36"/></a:solidFill>
-<a:ln w="12700"><a:solidFill><a:srgbClr val="073642"/></a:solidFill></a:ln>
+<a:solidFill><a:srgbClr val="{background}"/></a:solidFill>
+<a:ln w="12700"><a:solidFill><a:srgbClr val="{border}"/></a:solidFill></a:ln>
 </p:spPr>
 <p:txBody>
 <a:bodyPr wrap="square" rtlCol="0" anchor="t" lIns="91440" tIns="45720" rIns="91440" bIns="45720"/>
 <a:lstStyle/>
-{highlighted_xml}</p:txBody>
+{paragraphs}</p:txBody>
 </p:sp>"#
     )
 }
+
+/// Generate equation XML: Office Math, with its linear text as the fallback
+/// for viewers without math support. LaTeX that does not convert is shown as
+/// written.
+fn generate_equation(id: usize, equation: &Equation) -> String {
+    let size = equation.font_size * 100;
+    let align = if equation.display { "ctr" } else { "l" };
+    let text_paragraphs = |text: &str| -> String {
+        text.lines()
+            .map(|line| format!(
+                r#"<a:p><a:pPr algn="{align}"/><a:r><a:rPr lang="en-US" sz="{size}" dirty="0"><a:latin typeface="{MATH_FONT}"/></a:rPr><a:t>{}</a:t></a:r></a:p>"#,
+                escape_xml(line)
+            ))
+            .collect()
+    };
+    // The TeX stays in the alternative text for ppt2md
+    let descr = escape_xml(&equation.latex).replace('\n', "&#10;");
+    let shape = |paragraphs: &str| format!(
+        r#"<p:sp>
+<p:nvSpPr>
+<p:cNvPr id="{id}" name="Equation" descr="{descr}"/>
+<p:cNvSpPr txBox="1"/>
+<p:nvPr/>
+</p:nvSpPr>
+<p:spPr>
+<a:xfrm>
+<a:off x="{}" y="{}"/>
+<a:ext cx="{}" cy="{}"/>
+</a:xfrm>
+<a:prstGeom prst="rect"><a:avLst/></a:prstGeom>
+<a:noFill/>
+</p:spPr>
+<p:txBody>
+<a:bodyPr wrap="square" rtlCol="0" anchor="ctr"/>
+<a:lstStyle/>
+{paragraphs}
+</p:txBody>
+</p:sp>"#,
+        equation.x, equation.y, equation.width, equation.height
+    );
+
+    let Ok(math) = MathExpr::parse(&equation.latex) else {
+        return shape(&text_paragraphs(&equation.latex));
+    };
+    let justify = if equation.display { "centerGroup" } else { "left" };
+    let omml = format!(
+        r#"<a:p><a:pPr algn="{align}"/><a14:m><m:oMathPara xmlns:m="{MATH_NS}"><m:oMathParaPr><m:jc m:val="{justify}"/></m:oMathParaPr>{}</m:oMathPara></a14:m><a:endParaRPr lang="en-US" sz="{size}" dirty="0"/></a:p>"#,
+        math.to_omml(equation.font_size)
+    );
+    math_alternate_content(&shape(&omml), &shape(&text_paragraphs(&math.to_linear())))
+}
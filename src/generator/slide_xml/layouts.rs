@@ -1,19 +1,24 @@
 //! Slide layout implementations
 
-use crate::generator::slide_content::{SlideContent, BulletStyle, BulletPoint, BulletTextFormat};
+use crate::generator::slide_content::{SlideContent, SlideLayout, ImageSide, BulletStyle, BulletPoint, BulletTextFormat};
+use crate::generator::constants::{COLUMN_HEADING_HEIGHT, SLIDE_HEIGHT, SLIDE_WIDTH};
 use crate::generator::package_xml::escape_xml;
 use crate::generator::slide::formatting::generate_text_props;
-use super::common::{SLIDE_HEADER, SLIDE_FOOTER, generate_title_shape};
+use super::common::{SLIDE_HEADER, SLIDE_FOOTER, generate_title_shape, math_alternate_content};
+use crate::generator::omml::{MathExpr, MATH_FONT};
 use crate::generator::layouts::ExtendedTextProps;
 use super::content::render_additional_content;
+use crate::generator::slide_rels::SlideRelationships;
+use crate::generator::hyperlinks::insert_run_hyperlink;
+use crate::generator::text::TextFormat;
 
-/// Generate text properties XML for a bullet, merging slide defaults with bullet-specific format
-fn generate_bullet_text_props(
+/// Merge slide defaults with bullet-specific format
+fn bullet_text_props(
     default_props: &ExtendedTextProps,
     bullet_format: Option<&BulletTextFormat>,
-) -> String {
+) -> ExtendedTextProps {
     if let Some(fmt) = bullet_format {
-        let props = ExtendedTextProps {
+        ExtendedTextProps {
             size: fmt.font_size.map(|s| s * 100).unwrap_or(default_props.size),
             bold: fmt.bold || default_props.bold,
             italic: fmt.italic || default_props.italic,
@@ -24,13 +29,97 @@ fn generate_bullet_text_props(
             color: fmt.color.clone().or_else(|| default_props.color.clone()),
             highlight: fmt.highlight.clone(),
             font_family: fmt.font_family.clone().or_else(|| default_props.font_family.clone()),
-        };
-        props.to_xml()
+        }
     } else {
-        default_props.to_xml()
+        default_props.clone()
+    }
+}
+
+/// Merge bullet properties with the format of a single run
+fn run_text_props(bullet_props: &ExtendedTextProps, fmt: &TextFormat) -> ExtendedTextProps {
+    ExtendedTextProps {
+        size: fmt.font_size.map(|s| s * 100).unwrap_or(bullet_props.size),
+        bold: fmt.bold || bullet_props.bold,
+        italic: fmt.italic || bullet_props.italic,
+        underline: fmt.underline || bullet_props.underline,
+        strikethrough: fmt.strikethrough || bullet_props.strikethrough,
+        subscript: fmt.subscript || (bullet_props.subscript && !fmt.superscript),
+        superscript: fmt.superscript || (bullet_props.superscript && !fmt.subscript),
+        color: fmt.color.clone().or_else(|| bullet_props.color.clone()),
+        highlight: fmt.highlight.clone().or_else(|| bullet_props.highlight.clone()),
+        font_family: fmt.font_family.clone().or_else(|| bullet_props.font_family.clone()),
     }
 }
 
+/// Generate the runs of a bullet: its rich text runs, or a single run of its text.
+/// Math runs are Office Math, or with `office_math` off their TeX between `$`
+fn generate_bullet_runs(bullet: &BulletPoint, default_props: &ExtendedTextProps, office_math: bool) -> String {
+    let props = bullet_text_props(default_props, bullet.format.as_ref());
+    if bullet.runs.is_empty() {
+        let text_props = insert_run_hyperlink(&props.to_xml(), bullet.hyperlink.as_ref());
+        return format!("<a:r>\n{}\n<a:t>{}</a:t>\n</a:r>", text_props, escape_xml(&bullet.text));
+    }
+    bullet
+        .runs
+        .iter()
+        .map(|run| {
+            let props = run_text_props(&props, &run.format);
+            if run.math {
+                return generate_math_run(&run.text, &props, bullet.equation, office_math);
+            }
+            let hyperlink = run.hyperlink.as_ref().or(bullet.hyperlink.as_ref());
+            let text_props = insert_run_hyperlink(&props.to_xml(), hyperlink);
+            format!("<a:r>\n{}\n<a:t>{}</a:t>\n</a:r>", text_props, escape_xml(&run.text))
+        })
+        .collect()
+}
+
+/// Generate an equation of a bullet: inline or, for `display`, a math
+/// paragraph. LaTeX that does not convert is shown as written.
+fn generate_math_run(latex: &str, props: &ExtendedTextProps, display: bool, office_math: bool) -> String {
+    let size = props.size / 100;
+    match MathExpr::parse(latex) {
+        Ok(math) if office_math && display => format!(
+            r#"<a14:m><m:oMathPara><m:oMathParaPr><m:jc m:val="centerGroup"/></m:oMathParaPr>{}</m:oMathPara></a14:m>"#,
+            math.to_omml(size)
+        ),
+        Ok(math) if office_math => format!("<a14:m>{}</a14:m>", math.to_omml(size)),
+        _ => {
+            let delimiter = if display { "$$" } else { "$" };
+            format!(
+                r#"<a:r><a:rPr lang="en-US" sz="{}" dirty="0"><a:latin typeface="{MATH_FONT}"/></a:rPr><a:t>{delimiter}{}{delimiter}</a:t></a:r>"#,
+                props.size,
+                escape_xml(&latex.replace('\n', " "))
+            )
+        }
+    }
+}
+
+/// Generate a text box of bullets, `head` being the shape up to its list
+/// style. Bullets with math are Office Math, and readers without it show the
+/// TeX, which ppt2md reads back.
+fn generate_bullet_body(head: &str, bullets: &[BulletPoint], default_props: &ExtendedTextProps) -> String {
+    let shape = |office_math: bool| {
+        let paragraphs: String = bullets.iter()
+            .map(|bullet| generate_bullet_paragraph_from_point(bullet, default_props, office_math))
+            .collect();
+        format!("{head}{paragraphs}\n</p:txBody>\n</p:sp>")
+    };
+    if bullets.iter().any(BulletPoint::has_math) {
+        format!("\n{}", math_alternate_content(&shape(true), &shape(false)))
+    } else {
+        shape(true)
+    }
+}
+
+/// Bullets of a slide: its styled bullets, or its plain content
+fn slide_bullets(content: &SlideContent) -> Vec<BulletPoint> {
+    if !content.bullets.is_empty() {
+        return content.bullets.clone();
+    }
+    content.content.iter().map(|text| BulletPoint::new(text).with_style(content.bullet_style)).collect()
+}
+
 /// Generate a bullet paragraph with style
 #[allow(dead_code)]
 fn generate_bullet_paragraph(text: &str, level: u32, style: BulletStyle, text_props: &str) -> String {
@@ -57,11 +146,15 @@ fn generate_bullet_paragraph(text: &str, level: u32, style: BulletStyle, text_pr
 fn generate_bullet_paragraph_from_point(
     bullet: &BulletPoint,
     default_props: &ExtendedTextProps,
+    office_math: bool,
 ) -> String {
+    let runs_xml = generate_bullet_runs(bullet, default_props, office_math);
+    if bullet.equation {
+        return format!("\n<a:p>\n<a:pPr marL=\"0\" indent=\"0\" algn=\"ctr\">\n{}\n</a:pPr>\n{}\n</a:p>", BulletStyle::None.to_xml(), runs_xml);
+    }
     let indent = 457200 + (bullet.level * 457200);
     let margin_left = bullet.level * 457200 + indent;
     let bullet_xml = bullet.style.to_xml();
-    let text_props = generate_bullet_text_props(default_props, bullet.format.as_ref());
     
     format!(
         r#"
@@ -69,12 +162,9 @@ fn generate_bullet_paragraph_from_point(
 <a:pPr lvl="{}" marL="{}" indent="-{}">
 {}
 </a:pPr>
-<a:r>
 {}
-<a:t>{}</a:t>
-</a:r>
 </a:p>"#,
-        bullet.level, margin_left, indent, bullet_xml, text_props, escape_xml(&bullet.text)
+        bullet.level, margin_left, indent, bullet_xml, runs_xml
     )
 }
 
@@ -182,8 +272,7 @@ pub fn create_title_and_big_content_slide(content: &SlideContent) -> String {
 
     // Content
     if !content.bullets.is_empty() || !content.content.is_empty() {
-        xml.push_str(
-            r#"
+        let head = r#"
 <p:sp>
 <p:nvSpPr>
 <p:cNvPr id="3" name="Content"/>
@@ -200,8 +289,7 @@ pub fn create_title_and_big_content_slide(content: &SlideContent) -> String {
 </p:spPr>
 <p:txBody>
 <a:bodyPr wrap="square" rtlCol="0"/>
-<a:lstStyle/>"#
-        );
+<a:lstStyle/>"#;
 
         let default_props = ExtendedTextProps::with_basic(
             content_size,
@@ -210,31 +298,14 @@ pub fn create_title_and_big_content_slide(content: &SlideContent) -> String {
             false,
             content.content_color.as_deref(),
         );
-
-        // Use styled bullets if available, otherwise use plain content
-        if !content.bullets.is_empty() {
-            for bullet in &content.bullets {
-                xml.push_str(&generate_bullet_paragraph_from_point(bullet, &default_props));
-            }
-        } else {
-            for bullet in &content.content {
-                let bp = BulletPoint::new(bullet).with_style(content.bullet_style);
-                xml.push_str(&generate_bullet_paragraph_from_point(&bp, &default_props));
-            }
-        }
-
-        xml.push_str(
-            r#"
-</p:txBody>
-</p:sp>"#
-        );
+        xml.push_str(&generate_bullet_body(head, &slide_bullets(content), &default_props));
     }
 
     xml.push_str(SLIDE_FOOTER);
     xml
 }
 
-/// Create a two-column slide
+/// Create a two-column or comparison slide
 pub fn create_two_column_slide(content: &SlideContent) -> String {
     let title_size = content.title_size.unwrap_or(44) * 100;
     let content_size = content.content_size.unwrap_or(24) * 100;
@@ -289,92 +360,81 @@ pub fn create_two_column_slide(content: &SlideContent) -> String {
         content.content_color.as_deref(),
     );
 
-    // Determine which bullets to use
-    let use_styled_bullets = !content.bullets.is_empty();
-    let bullet_count = if use_styled_bullets { content.bullets.len() } else { content.content.len() };
-
-    if bullet_count > 0 {
-        let mid = bullet_count.div_ceil(2);
-
-        // Left column
-        xml.push_str(
-            r#"
+    // Use styled bullets if available, otherwise the plain content
+    let bullets: Vec<BulletPoint> = if content.bullets.is_empty() {
+        content.content.iter().map(|text| BulletPoint::new(text).with_style(content.bullet_style)).collect()
+    } else {
+        content.bullets.clone()
+    };
+    let mid = content.column_break.unwrap_or(bullets.len().div_ceil(2)).min(bullets.len());
+
+    // Comparison slides put a heading above each column
+    let mut top = 1189200;
+    if let (SlideLayout::Comparison, Some((left, right))) = (content.layout, &content.column_headings) {
+        let heading_props = ExtendedTextProps::with_basic(content_size, true, false, false, content.content_color.as_deref());
+        for (id, x, heading) in [(5, 457200, left), (6, 4572300, right)] {
+            xml.push_str(&format!(
+                r#"
 <p:sp>
 <p:nvSpPr>
-<p:cNvPr id="3" name="Left Content"/>
+<p:cNvPr id="{id}" name="Column Heading {}"/>
 <p:cNvSpPr txBox="1"/>
 <p:nvPr/>
 </p:nvSpPr>
 <p:spPr>
 <a:xfrm>
-<a:off x="457200" y="1189200"/>
-<a:ext cx="4115100" cy="5668800"/>
+<a:off x="{x}" y="{top}"/>
+<a:ext cx="4115100" cy="{COLUMN_HEADING_HEIGHT}"/>
 </a:xfrm>
 <a:prstGeom prst="rect"><a:avLst/></a:prstGeom>
 <a:noFill/>
 </p:spPr>
 <p:txBody>
-<a:bodyPr wrap="square" rtlCol="0"/>
-<a:lstStyle/>"#
-        );
-
-        if use_styled_bullets {
-            for bullet in &content.bullets[..mid] {
-                xml.push_str(&generate_bullet_paragraph_from_point(bullet, &default_props));
-            }
-        } else {
-            for bullet in &content.content[..mid] {
-                let bp = BulletPoint::new(bullet).with_style(content.bullet_style);
-                xml.push_str(&generate_bullet_paragraph_from_point(&bp, &default_props));
-            }
+<a:bodyPr wrap="square" rtlCol="0" anchor="b"/>
+<a:lstStyle/>
+<a:p>
+<a:r>
+{}
+<a:t>{}</a:t>
+</a:r>
+</a:p>
+</p:txBody>
+</p:sp>"#,
+                id - 4,
+                heading_props.to_xml(),
+                escape_xml(heading)
+            ));
         }
+        top += COLUMN_HEADING_HEIGHT;
+    }
 
-        xml.push_str(
+    let columns = [(3, "Left Content", 457200, &bullets[..mid]), (4, "Right Content", 4572300, &bullets[mid..])];
+    for (id, name, x, column) in columns {
+        if column.is_empty() {
+            continue;
+        }
+        let head = format!(
             r#"
-</p:txBody>
-</p:sp>"#
-        );
-
-        // Right column
-        if mid < bullet_count {
-            xml.push_str(
-                r#"
 <p:sp>
 <p:nvSpPr>
-<p:cNvPr id="4" name="Right Content"/>
+<p:cNvPr id="{id}" name="{name}"/>
 <p:cNvSpPr txBox="1"/>
 <p:nvPr/>
 </p:nvSpPr>
 <p:spPr>
 <a:xfrm>
-<a:off x="4572300" y="1189200"/>
-<a:ext cx="4115100" cy="5668800"/>
+<a:off x="{x}" y="{top}"/>
+<a:ext cx="4115100" cy="{}"/>
 </a:xfrm>
 <a:prstGeom prst="rect"><a:avLst/></a:prstGeom>
 <a:noFill/>
 </p:spPr>
 <p:txBody>
 <a:bodyPr wrap="square" rtlCol="0"/>
-<a:lstStyle/>"#
-            );
-
-            if use_styled_bullets {
-                for bullet in &content.bullets[mid..] {
-                    xml.push_str(&generate_bullet_paragraph_from_point(bullet, &default_props));
-                }
-            } else {
-                for bullet in &content.content[mid..] {
-                    let bp = BulletPoint::new(bullet).with_style(content.bullet_style);
-                    xml.push_str(&generate_bullet_paragraph_from_point(&bp, &default_props));
-                }
-            }
-
-            xml.push_str(
-                r#"
-</p:txBody>
-</p:sp>"#
-            );
-        }
+<a:lstStyle/>"#,
+            SLIDE_HEIGHT - top
+        );
+        xml.push_str(&generate_bullet_body(&head, column, &default_props));
     }
 
     xml.push_str(SLIDE_FOOTER);
@@ -382,7 +442,7 @@ pub fn create_two_column_slide(content: &SlideContent) -> String {
 }
 
 /// Create a title and content slide (most common layout)
-pub fn create_title_and_content_slide(content: &SlideContent) -> String {
+pub fn create_title_and_content_slide(content: &SlideContent, rels: &SlideRelationships) -> String {
     let title_size = content.title_size.unwrap_or(44) * 100;
     let content_size = content.content_size.unwrap_or(28) * 100;
 
@@ -395,6 +455,13 @@ pub fn create_title_and_content_slide(content: &SlideContent) -> String {
     );
     let title_text = escape_xml(&content.title);
 
+    // Title and text keep clear of a side picture
+    let (text_x, text_width) = match content.image_side {
+        Some(ImageSide::Left) => (SLIDE_WIDTH / 2 + 457200, SLIDE_WIDTH / 2 - 914400),
+        Some(ImageSide::Right) => (457200, SLIDE_WIDTH / 2 - 914400),
+        None => (457200, 8230200),
+    };
+
     let mut xml = String::from(SLIDE_HEADER);
     
     // Title
@@ -408,8 +475,8 @@ pub fn create_title_and_content_slide(content: &SlideContent) -> String {
 </p:nvSpPr>
 <p:spPr>
 <a:xfrm>
-<a:off x="457200" y="274638"/>
-<a:ext cx="8230200" cy="1143000"/>
+<a:off x="{text_x}" y="274638"/>
+<a:ext cx="{text_width}" cy="1143000"/>
 </a:xfrm>
 <a:prstGeom prst="rect"><a:avLst/></a:prstGeom>
 <a:noFill/>
@@ -431,10 +498,10 @@ pub fn create_title_and_content_slide(content: &SlideContent) -> String {
     // Render table if present
     if let Some(ref table) = content.table {
         xml.push('\n');
-        xml.push_str(&crate::generator::tables_xml::generate_table_xml(table, 3));
+        xml.push_str(&crate::generator::table::generate_table_xml(table, 3));
     } else if !content.bullets.is_empty() || !content.content.is_empty() {
         // Render bullets if no table
-        xml.push_str(
+        let head = format!(
             r#"
 <p:sp>
 <p:nvSpPr>
@@ -444,8 +511,8 @@ pub fn create_title_and_content_slide(content: &SlideContent) -> String {
 </p:nvSpPr>
 <p:spPr>
 <a:xfrm>
-<a:off x="457200" y="1600200"/>
-<a:ext cx="8230200" cy="4572000"/>
+<a:off x="{text_x}" y="1600200"/>
+<a:ext cx="{text_width}" cy="4572000"/>
 </a:xfrm>
 <a:prstGeom prst="rect"><a:avLst/></a:prstGeom>
 <a:noFill/>
@@ -462,28 +529,11 @@ pub fn create_title_and_content_slide(content: &SlideContent) -> String {
             false,
             content.content_color.as_deref(),
         );
-
-        // Use styled bullets if available, otherwise use plain content
-        if !content.bullets.is_empty() {
-            for bullet in &content.bullets {
-                xml.push_str(&generate_bullet_paragraph_from_point(bullet, &default_props));
-            }
-        } else {
-            for bullet in &content.content {
-                let bp = BulletPoint::new(bullet).with_style(content.bullet_style);
-                xml.push_str(&generate_bullet_paragraph_from_point(&bp, &default_props));
-            }
-        }
-
-        xml.push_str(
-            r#"
-</p:txBody>
-</p:sp>"#
-        );
+        xml.push_str(&generate_bullet_body(&head, &slide_bullets(content), &default_props));
     }
 
     // Render additional content (shapes, images, code blocks, connectors)
-    render_additional_content(&mut xml, content);
+    render_additional_content(&mut xml, content, rels);
 
     xml.push_str(SLIDE_FOOTER);
     xml
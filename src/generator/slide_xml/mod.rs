@@ -13,6 +13,9 @@ mod layouts;
 mod content;
 
 use super::slide_content::{SlideContent, SlideLayout};
+use super::slide_rels::SlideRelationships;
+use super::background::{hide_master_shapes, replace_background};
+use crate::parts::animation::SlideTransition;
 
 pub use common::create_slide_rels_xml;
 
@@ -75,14 +78,35 @@ pub fn create_slide_xml(slide_num: usize, title: &str) -> String {
 }
 
 /// Create slide XML with content based on layout
-pub fn create_slide_xml_with_content(_slide_num: usize, content: &SlideContent) -> String {
-    match content.layout {
+pub fn create_slide_xml_with_content(slide_num: usize, content: &SlideContent) -> String {
+    let rels = SlideRelationships::for_slide(slide_num, content);
+    let content = rels.link_hyperlinks(content);
+    let content = content.as_ref();
+    let mut xml = match content.layout {
         SlideLayout::Blank => layouts::create_blank_slide(),
         SlideLayout::TitleOnly => layouts::create_title_only_slide(content),
         SlideLayout::CenteredTitle => layouts::create_centered_title_slide(content),
         SlideLayout::TitleAndBigContent => layouts::create_title_and_big_content_slide(content),
-        SlideLayout::TwoColumn => layouts::create_two_column_slide(content),
-        SlideLayout::TitleAndContent => layouts::create_title_and_content_slide(content),
+        SlideLayout::TwoColumn | SlideLayout::Comparison => layouts::create_two_column_slide(content),
+        SlideLayout::TitleAndContent => layouts::create_title_and_content_slide(content, &rels),
+    };
+    if let Some(ref background) = content.background {
+        xml = replace_background(&xml, &background.to_xml(rels.background_rel_id()));
+    }
+    if content.hide_background_graphics {
+        xml = hide_master_shapes(&xml);
+    }
+    if let Some(ref transition) = content.transition {
+        xml = add_transition(&xml, transition);
+    }
+    xml
+}
+
+/// Add a transition after the slide's colour mapping
+pub fn add_transition(xml: &str, transition: &SlideTransition) -> String {
+    match xml.rfind("</p:sld>") {
+        Some(pos) => format!("{}{}\n{}", &xml[..pos], transition.to_xml(), &xml[pos..]),
+        None => xml.to_string(),
     }
 }
 
@@ -133,4 +157,105 @@ mod tests {
         assert!(segments.iter().any(|s| s.bold && s.text == "bold"));
         assert!(segments.iter().any(|s| s.italic && s.text == "italic"));
     }
+
+    #[test]
+    fn test_hyperlinks_in_slide_xml() {
+        use super::create_slide_xml_with_content;
+        use crate::generator::{Hyperlink, HyperlinkAction, Shape, ShapeType, SlideContent, TableBuilder, TableCell, TableRow};
+
+        let table = TableBuilder::new(vec![1000000])
+            .add_row(TableRow::new(vec![TableCell::new("Home").with_hyperlink(Hyperlink::slide(1))]))
+            .build();
+        let content = SlideContent::new("Links")
+            .add_link_bullet("Docs", Hyperlink::url("https://docs.rs").with_tooltip("Open docs"))
+            .add_shape(Shape::new(ShapeType::Rectangle, 0, 0, 100, 100).with_hyperlink(Hyperlink::new(HyperlinkAction::NextSlide)))
+            .table(table);
+        let xml = create_slide_xml_with_content(2, &content);
+
+        // Table replaces the bullets on this layout
+        assert!(xml.contains(r#"<a:hlinkClick r:id="rId3" highlightClick="1" action="ppaction://hlinksldjump"/></a:rPr><a:t>Home</a:t>"#));
+        assert!(xml.contains(r#"<p:cNvPr id="10" name="Shape 10"><a:hlinkClick r:id="" highlightClick="1" action="ppaction://hlinkshowjump?jump=nextslide"/></p:cNvPr>"#));
+
+        let bullets = SlideContent::new("Links").add_link_bullet("Docs", Hyperlink::url("https://docs.rs"));
+        let xml = create_slide_xml_with_content(1, &bullets);
+        assert!(xml.contains(r#"<a:hlinkClick r:id="rId2" highlightClick="1"/></a:rPr>"#));
+    }
+
+    #[test]
+    fn test_rich_bullet_runs() {
+        use super::create_slide_xml_with_content;
+        use crate::generator::{BulletPoint, Hyperlink, Run, SlideContent};
+
+        let mut content = SlideContent::new("Rich");
+        content.bullets.push(BulletPoint::rich(vec![
+            Run::new("Read "),
+            Run::new("the guide").with_hyperlink(Hyperlink::url("https://example.com/guide")),
+            Run::new(" now").color("FF0000"),
+        ]).font_size(20));
+        let xml = create_slide_xml_with_content(1, &content);
+
+        assert_eq!(xml.matches(r#"sz="2000""#).count(), 3);
+        assert!(xml.contains(r#"<a:hlinkClick r:id="rId2" highlightClick="1"/></a:rPr>
+<a:t>the guide</a:t>"#));
+        assert!(xml.contains(r#"<a:srgbClr val="FF0000"/></a:solidFill></a:rPr>
+<a:t> now</a:t>"#));
+    }
+
+    #[test]
+    fn test_comparison_and_side_image() {
+        use super::create_slide_xml_with_content;
+        use crate::generator::{Image, ImageSide, SlideContent, SlideLayout};
+
+        let content = SlideContent::new("Build vs Buy")
+            .layout(SlideLayout::Comparison)
+            .column_headings("Build", "Buy")
+            .add_bullet("Control")
+            .column_break()
+            .add_bullet("Speed");
+        let xml = create_slide_xml_with_content(1, &content);
+        assert!(xml.contains(r#"name="Column Heading 1""#) && xml.contains("<a:t>Buy</a:t>"));
+        assert!(xml.contains(r#"<a:off x="4572300""#));
+
+        let content = SlideContent::new("Team")
+            .side_image(Image::new("team.png", 100, 100, "PNG"), ImageSide::Left)
+            .add_bullet("Ten engineers");
+        let xml = create_slide_xml_with_content(1, &content);
+        assert!(xml.contains(r#"<a:off x="5029200" y="274638"/>"#));
+    }
+
+    #[test]
+    fn test_equations() {
+        use super::create_slide_xml_with_content;
+        use crate::generator::{Equation, SlideContent};
+
+        let content = SlideContent::new("Basel")
+            .add_equation(Equation::new(r"\sum_{n=1}^{\infty} \frac{1}{n^2} = \frac{\pi^2}{6}"))
+            .add_equation(Equation::new(r"\frac{1}{").position(457200, 3000000));
+        let xml = create_slide_xml_with_content(1, &content);
+        assert!(xml.contains(r#"<mc:Choice xmlns:a14="http://schemas.microsoft.com/office/drawing/2010/main" xmlns:m="http://schemas.openxmlformats.org/officeDocument/2006/math" Requires="a14">"#));
+        assert!(xml.contains("<a14:m><m:oMathPara") && xml.contains(r#"<m:jc m:val="centerGroup"/>"#));
+        assert!(xml.contains("<a:t>∑_(n=1)^∞ 1/n^2=π^2/6</a:t>"));
+        // LaTeX that does not convert is shown as written, without Office Math
+        assert_eq!(xml.matches("<mc:AlternateContent").count(), 1);
+        assert!(xml.contains(r#"<a:t>\frac{1}{</a:t>"#));
+    }
+
+    #[test]
+    fn test_bullet_math() {
+        use super::create_slide_xml_with_content;
+        use crate::generator::{BulletPoint, Run, SlideContent};
+
+        let mut content = SlideContent::new("Energy").add_bullet("Before");
+        content.bullets.push(BulletPoint::equation("E = mc^2"));
+        content.bullets.push(BulletPoint::rich(vec![Run::new("Mass "), Run::math("m")]));
+        let xml = create_slide_xml_with_content(1, &content);
+        let (choice, fallback) = xml.split_once("<mc:Fallback>").unwrap();
+        let order: Vec<usize> = ["Before", "<m:oMathPara>", "Mass ", "<a14:m><m:oMath>"].iter()
+            .map(|text| choice.find(text).unwrap())
+            .collect();
+        assert!(order.is_sorted());
+        assert!(choice.contains(r#"<a:pPr marL="0" indent="0" algn="ctr">"#));
+        // Readers without Office Math see the TeX
+        assert!(fallback.contains("<a:t>$$E = mc^2$$</a:t>") && fallback.contains("<a:t>$m$</a:t>"));
+    }
 }
@@ -0,0 +1,209 @@
+//! Cell borders and margins
+
+/// Border line style
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BorderStyle {
+    #[default]
+    Solid,
+    Dashed,
+    /// Round dots (`dot`)
+    Dotted,
+    /// Square dots (`sysDot`)
+    SquareDotted,
+    DashDot,
+    LongDash,
+    /// Two parallel solid lines
+    Double,
+    /// No line (hides the border of the table style)
+    None,
+}
+
+impl BorderStyle {
+    /// Get the OOXML preset dash value
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BorderStyle::Solid | BorderStyle::Double => "solid",
+            BorderStyle::Dashed => "dash",
+            BorderStyle::Dotted => "dot",
+            BorderStyle::SquareDotted => "sysDot",
+            BorderStyle::DashDot => "dashDot",
+            BorderStyle::LongDash => "lgDash",
+            BorderStyle::None => "none",
+        }
+    }
+}
+
+/// Border of one side of a cell
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CellBorder {
+    pub width: i32,        // in EMU (12700 = 1pt)
+    pub color: String,
+    pub style: BorderStyle,
+}
+
+impl CellBorder {
+    /// Create a solid border (width in points, RGB hex color)
+    pub fn new(width_pt: f32, color: impl Into<String>) -> Self {
+        CellBorder {
+            width: (width_pt * 12700.0) as i32,
+            color: color.into(),
+            style: BorderStyle::Solid,
+        }
+    }
+
+    /// Border that hides the line
+    pub fn none() -> Self {
+        CellBorder {
+            width: 0,
+            color: String::new(),
+            style: BorderStyle::None,
+        }
+    }
+
+    /// Set the line style
+    pub fn style(mut self, style: BorderStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Generate the border element (`a:lnL`, `a:lnR`, `a:lnT` or `a:lnB`)
+    pub fn to_xml(&self, tag: &str) -> String {
+        if self.style == BorderStyle::None {
+            return format!(r#"<a:{tag} w="0"><a:noFill/></a:{tag}>"#);
+        }
+        let cmpd = if self.style == BorderStyle::Double { "dbl" } else { "sng" };
+        format!(
+            r#"<a:{tag} w="{}" cap="flat" cmpd="{cmpd}" algn="ctr"><a:solidFill><a:srgbClr val="{}"/></a:solidFill><a:prstDash val="{}"/></a:{tag}>"#,
+            self.width,
+            self.color.trim_start_matches('#'),
+            self.style.as_str(),
+        )
+    }
+}
+
+/// Cell borders (all four sides)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CellBorders {
+    pub left: Option<CellBorder>,
+    pub right: Option<CellBorder>,
+    pub top: Option<CellBorder>,
+    pub bottom: Option<CellBorder>,
+}
+
+impl CellBorders {
+    /// Same border on all sides
+    pub fn all(border: CellBorder) -> Self {
+        CellBorders {
+            left: Some(border.clone()),
+            right: Some(border.clone()),
+            top: Some(border.clone()),
+            bottom: Some(border),
+        }
+    }
+
+    /// Hide all borders
+    pub fn none() -> Self {
+        Self::all(CellBorder::none())
+    }
+
+    /// Set the left border
+    pub fn left(mut self, border: CellBorder) -> Self {
+        self.left = Some(border);
+        self
+    }
+
+    /// Set the right border
+    pub fn right(mut self, border: CellBorder) -> Self {
+        self.right = Some(border);
+        self
+    }
+
+    /// Set the top border
+    pub fn top(mut self, border: CellBorder) -> Self {
+        self.top = Some(border);
+        self
+    }
+
+    /// Set the bottom border
+    pub fn bottom(mut self, border: CellBorder) -> Self {
+        self.bottom = Some(border);
+        self
+    }
+
+    /// Generate the border elements in schema order
+    pub fn to_xml(&self) -> String {
+        let mut xml = String::new();
+        if let Some(ref b) = self.left { xml.push_str(&b.to_xml("lnL")); }
+        if let Some(ref b) = self.right { xml.push_str(&b.to_xml("lnR")); }
+        if let Some(ref b) = self.top { xml.push_str(&b.to_xml("lnT")); }
+        if let Some(ref b) = self.bottom { xml.push_str(&b.to_xml("lnB")); }
+        xml
+    }
+}
+
+/// Cell margins (text insets)
+#[derive(Debug, Clone, PartialEq)]
+pub struct CellMargins {
+    pub left: i32,   // in EMU
+    pub right: i32,
+    pub top: i32,
+    pub bottom: i32,
+}
+
+impl Default for CellMargins {
+    fn default() -> Self {
+        CellMargins {
+            left: 91440,   // 0.1 inch
+            right: 91440,
+            top: 45720,    // 0.05 inch
+            bottom: 45720,
+        }
+    }
+}
+
+impl CellMargins {
+    /// Same margin on all sides
+    pub fn uniform(margin: i32) -> Self {
+        CellMargins { left: margin, right: margin, top: margin, bottom: margin }
+    }
+
+    /// Margin attributes of `a:tcPr`
+    pub fn to_xml_attrs(&self) -> String {
+        format!(
+            r#" marL="{}" marR="{}" marT="{}" marB="{}""#,
+            self.left, self.right, self.top, self.bottom
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_border_xml() {
+        let xml = CellBorder::new(1.5, "#FF0000").style(BorderStyle::Dashed).to_xml("lnT");
+        assert!(xml.starts_with(r#"<a:lnT w="19050" cap="flat" cmpd="sng""#));
+        assert!(xml.contains(r#"<a:srgbClr val="FF0000"/>"#));
+        assert!(xml.contains(r#"<a:prstDash val="dash"/></a:lnT>"#));
+
+        let dotted = CellBorder::new(1.0, "000000").style(BorderStyle::Dotted).to_xml("lnR");
+        assert!(dotted.contains(r#"<a:prstDash val="dot"/>"#));
+        assert_eq!(BorderStyle::SquareDotted.as_str(), "sysDot");
+
+        let double = CellBorder::new(3.0, "000000").style(BorderStyle::Double).to_xml("lnB");
+        assert!(double.contains(r#"cmpd="dbl""#));
+        assert_eq!(CellBorder::none().to_xml("lnL"), r#"<a:lnL w="0"><a:noFill/></a:lnL>"#);
+    }
+
+    #[test]
+    fn test_borders_order() {
+        let xml = CellBorders::default()
+            .bottom(CellBorder::new(1.0, "000000"))
+            .left(CellBorder::new(1.0, "000000"))
+            .to_xml();
+        assert!(xml.find("<a:lnL").unwrap() < xml.find("<a:lnB").unwrap());
+        assert!(!xml.contains("lnR"));
+        assert_eq!(CellBorders::none().to_xml().matches("<a:noFill/>").count(), 4);
+    }
+}
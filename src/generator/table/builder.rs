@@ -1,6 +1,57 @@
 //! Table and TableBuilder for constructing tables
 
+use crate::generator::text::TextMeasurer;
+
+use super::cell::TableCell;
+use super::data::{apply_column_rules, ColorRule, ColumnFormat, NumberFormat};
 use super::row::TableRow;
+use super::style::{TableStyle, TableStyleOptions};
+
+/// How a table that doesn't fit its slide is split over continuation slides
+#[derive(Clone, Debug, PartialEq)]
+pub struct TablePagination {
+    /// Repeat the header rows at the top of every slide
+    pub repeat_header: bool,
+    /// Number of leading rows that form the header
+    pub header_rows: usize,
+    /// Add a "Page X of Y" caption below each part
+    pub captions: bool,
+}
+
+impl Default for TablePagination {
+    fn default() -> Self {
+        TablePagination {
+            repeat_header: true,
+            header_rows: 1,
+            captions: false,
+        }
+    }
+}
+
+impl TablePagination {
+    /// Create pagination settings (repeated single header row, no captions)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enable/disable repeating the header rows
+    pub fn repeat_header(mut self, repeat: bool) -> Self {
+        self.repeat_header = repeat;
+        self
+    }
+
+    /// Set the number of header rows
+    pub fn header_rows(mut self, count: usize) -> Self {
+        self.header_rows = count;
+        self
+    }
+
+    /// Enable/disable "Page X of Y" captions
+    pub fn with_captions(mut self, captions: bool) -> Self {
+        self.captions = captions;
+        self
+    }
+}
 
 /// Table definition with rows and positioning
 #[derive(Clone, Debug)]
@@ -13,28 +64,51 @@ pub struct Table {
     pub x: u32,
     /// Y position in EMU
     pub y: u32,
+    /// Split over slides when too tall
+    pub pagination: Option<TablePagination>,
+    /// Table style id (`a:tableStyleId`), see [`super::builtin`]
+    pub style_id: Option<String>,
+    /// Which parts of the table style apply
+    pub style_options: TableStyleOptions,
+    /// Custom style definition, written to `ppt/tableStyles.xml`
+    pub style: Option<TableStyle>,
 }
 
 impl Table {
-    /// Create a table from raw data (2D string array)
-    pub fn from_data(data: Vec<Vec<&str>>, column_widths: Vec<u32>, x: u32, y: u32) -> Self {
-        use super::cell::TableCell;
-        
-        let rows = data
-            .into_iter()
-            .map(|row_data| {
-                TableRow::new(row_data.into_iter().map(TableCell::new).collect())
-            })
-            .collect();
-
+    /// Create a new table
+    pub fn new(rows: Vec<TableRow>, column_widths: Vec<u32>, x: u32, y: u32) -> Self {
         Table {
             rows,
             column_widths,
             x,
             y,
+            pagination: None,
+            style_id: None,
+            style_options: TableStyleOptions::default(),
+            style: None,
         }
     }
 
+    /// Create a table from raw data (2D string array)
+    pub fn from_data(data: Vec<Vec<&str>>, column_widths: Vec<u32>, x: u32, y: u32) -> Self {
+        let rows = data
+            .into_iter()
+            .map(|row_data| TableRow::new(row_data.into_iter().map(TableCell::new).collect()))
+            .collect();
+
+        Self::new(rows, column_widths, x, y)
+    }
+
+    /// Get the number of columns
+    pub fn column_count(&self) -> usize {
+        self.column_widths.len()
+    }
+
+    /// Get the number of rows
+    pub fn row_count(&self) -> usize {
+        self.rows.len()
+    }
+
     /// Calculate total table width
     pub fn width(&self) -> u32 {
         self.column_widths.iter().sum()
@@ -48,14 +122,99 @@ impl Table {
             .sum()
     }
 
-    /// Get the number of rows
-    pub fn row_count(&self) -> usize {
-        self.rows.len()
+    /// Use a table style by id
+    pub fn with_style(mut self, style_id: &str) -> Self {
+        self.style_id = Some(style_id.to_string());
+        self
     }
 
-    /// Get the number of columns
-    pub fn column_count(&self) -> usize {
-        self.column_widths.len()
+    /// Use a custom table style
+    pub fn with_table_style(mut self, style: TableStyle) -> Self {
+        self.style_id = Some(style.id.clone());
+        self.style = Some(style);
+        self
+    }
+
+    /// Choose which parts of the table style apply
+    pub fn with_style_options(mut self, options: TableStyleOptions) -> Self {
+        self.style_options = options;
+        self
+    }
+
+    /// Merge the `rows` × `cols` block whose top-left cell is at (`row`, `col`)
+    ///
+    /// The top-left cell keeps its content and spans the block; the covered cells
+    /// are marked `hMerge`/`vMerge`. Missing cells are added.
+    pub fn merge(mut self, row: usize, col: usize, rows: usize, cols: usize) -> Self {
+        let (rows, cols) = (rows.max(1), cols.max(1));
+        for r in row..(row + rows).min(self.rows.len()) {
+            let cells = &mut self.rows[r].cells;
+            while cells.len() < col + cols {
+                cells.push(TableCell::new(""));
+            }
+            for (c, cell) in cells.iter_mut().enumerate().skip(col).take(cols) {
+                if r == row && c == col {
+                    cell.row_span = rows as u32;
+                    cell.col_span = cols as u32;
+                    continue;
+                }
+                cell.h_merge = c > col;
+                cell.v_merge = r > row;
+            }
+        }
+        self
+    }
+
+    /// Split the table over slides when it doesn't fit (automatic when generating)
+    pub fn with_pagination(mut self, pagination: TablePagination) -> Self {
+        self.pagination = Some(pagination);
+        self
+    }
+
+    /// Rendered table height in EMU, with rows grown to fit their text
+    pub fn measured_height(&self, measurer: &TextMeasurer) -> u32 {
+        self.rows.iter().map(|r| r.measured_height(&self.column_widths, measurer)).sum()
+    }
+
+    /// Split rows into tables no taller than `available` EMU
+    ///
+    /// Every part gets at least one body row, even when that row alone is too tall.
+    /// Rows are never split inside a vertical merge.
+    pub fn split(&self, available: u32, pagination: &TablePagination, measurer: &TextMeasurer) -> Vec<Table> {
+        let header_count = pagination.header_rows.min(self.rows.len());
+        let (header, body) = self.rows.split_at(header_count);
+        let header_height: u32 = header.iter().map(|r| r.measured_height(&self.column_widths, measurer)).sum();
+
+        let mut parts: Vec<Vec<TableRow>> = Vec::new();
+        let mut used = available;
+        let mut part_rows = 0;
+        for row in body {
+            let height = row.measured_height(&self.column_widths, measurer);
+            let can_break = parts.is_empty() || (part_rows > 0 && !row.continues_merge());
+            if used + height > available && can_break {
+                let repeat = parts.is_empty() || pagination.repeat_header;
+                parts.push(if repeat { header.to_vec() } else { Vec::new() });
+                used = if repeat { header_height } else { 0 };
+                part_rows = 0;
+            }
+            used += height;
+            part_rows += 1;
+            if let Some(part) = parts.last_mut() {
+                part.push(row.clone());
+            }
+        }
+
+        if parts.is_empty() {
+            return vec![self.clone()];
+        }
+        parts
+            .into_iter()
+            .map(|rows| Table {
+                rows,
+                pagination: None,
+                ..self.clone()
+            })
+            .collect()
     }
 }
 
@@ -66,6 +225,13 @@ pub struct TableBuilder {
     rows: Vec<TableRow>,
     x: u32,
     y: u32,
+    pagination: Option<TablePagination>,
+    style_id: Option<String>,
+    style_options: TableStyleOptions,
+    style: Option<TableStyle>,
+    header_rows: usize,
+    formats: Vec<(usize, ColumnFormat)>,
+    color_rules: Vec<(usize, ColorRule)>,
 }
 
 impl TableBuilder {
@@ -76,29 +242,101 @@ impl TableBuilder {
             rows: Vec::new(),
             x: 0,
             y: 0,
+            pagination: None,
+            style_id: None,
+            style_options: TableStyleOptions::default(),
+            style: None,
+            header_rows: 1,
+            formats: Vec::new(),
+            color_rules: Vec::new(),
         }
     }
 
+    /// Set table position
+    pub fn position(mut self, x: u32, y: u32) -> Self {
+        self.x = x;
+        self.y = y;
+        self
+    }
+
+    /// Split the table over slides when it doesn't fit
+    pub fn pagination(mut self, pagination: TablePagination) -> Self {
+        self.pagination = Some(pagination);
+        self
+    }
+
+    /// Use a table style by id
+    pub fn style(mut self, style_id: &str) -> Self {
+        self.style_id = Some(style_id.to_string());
+        self
+    }
+
+    /// Use a custom table style
+    pub fn table_style(mut self, style: TableStyle) -> Self {
+        self.style_id = Some(style.id.clone());
+        self.style = Some(style);
+        self
+    }
+
+    /// Choose which parts of the table style apply
+    pub fn style_options(mut self, options: TableStyleOptions) -> Self {
+        self.style_options = options;
+        self
+    }
+
+    /// Number of leading rows left out of number/date formats and colour rules (default 1)
+    pub fn header_rows(mut self, count: usize) -> Self {
+        self.header_rows = count;
+        self
+    }
+
+    /// Format the numbers of a column's body cells
+    pub fn number_format(mut self, column: usize, format: NumberFormat) -> Self {
+        self.formats.push((column, ColumnFormat::Number(format)));
+        self
+    }
+
+    /// Format the dates of a column's body cells (chrono `strftime` syntax, e.g. `"%d %b %Y"`)
+    pub fn date_format(mut self, column: usize, format: &str) -> Self {
+        self.formats.push((column, ColumnFormat::Date(format.to_string())));
+        self
+    }
+
+    /// Colour a column's body cells by value; later rules win
+    pub fn color_rule(mut self, column: usize, rule: ColorRule) -> Self {
+        self.color_rules.push((column, rule));
+        self
+    }
+
+    /// Index of the column whose header (first row) is `header`
+    pub fn column_index(&self, header: &str) -> Option<usize> {
+        self.rows.first()?.cells.iter().position(|c| c.text == header)
+    }
+
     /// Add a row to the table
     pub fn add_row(mut self, row: TableRow) -> Self {
         self.rows.push(row);
         self
     }
 
-    /// Set table position
-    pub fn position(mut self, x: u32, y: u32) -> Self {
-        self.x = x;
-        self.y = y;
+    /// Add a simple row from strings
+    pub fn add_simple_row(mut self, cells: Vec<&str>) -> Self {
+        self.rows.push(TableRow::new(cells.into_iter().map(TableCell::new).collect()));
         self
     }
 
     /// Build the final table
-    pub fn build(self) -> Table {
+    pub fn build(mut self) -> Table {
+        apply_column_rules(&mut self.rows, self.header_rows, &self.formats, &self.color_rules);
         Table {
             rows: self.rows,
             column_widths: self.column_widths,
             x: self.x,
             y: self.y,
+            pagination: self.pagination,
+            style_id: self.style_id,
+            style_options: self.style_options,
+            style: self.style,
         }
     }
 }
@@ -106,7 +344,6 @@ impl TableBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::generator::table::cell::TableCell;
 
     #[test]
     fn test_table_from_data() {
@@ -138,15 +375,79 @@ mod tests {
                 TableCell::new("Header 1"),
                 TableCell::new("Header 2"),
             ]))
-            .add_row(TableRow::new(vec![
-                TableCell::new("Data 1"),
-                TableCell::new("Data 2"),
-            ]))
+            .add_simple_row(vec!["Data 1", "Data 2"])
             .position(500000, 1000000)
+            .style(super::super::builtin::MEDIUM_STYLE_2_ACCENT_2)
             .build();
 
         assert_eq!(table.row_count(), 2);
         assert_eq!(table.x, 500000);
         assert_eq!(table.y, 1000000);
+        assert_eq!(table.style_id.as_deref(), Some("{21E4AEA4-8DFA-4A89-87EB-49C32662AFE8}"));
+    }
+
+    #[test]
+    fn test_merge() {
+        let table = Table::from_data(
+            vec![vec!["Region", "Q1", "Q2"], vec!["North", "1", "2"], vec!["", "3"]],
+            vec![1000000; 3],
+            0,
+            0,
+        )
+        .merge(0, 1, 1, 2)
+        .merge(1, 0, 2, 1);
+
+        assert_eq!(table.rows[0].cells[1].col_span, 2);
+        assert!(table.rows[0].cells[2].h_merge && !table.rows[0].cells[2].v_merge);
+        assert_eq!(table.rows[1].cells[0].row_span, 2);
+        assert!(table.rows[2].cells[0].v_merge);
+        assert_eq!(table.rows[2].cells.len(), 2);
+    }
+
+    #[test]
+    fn test_measured_height() {
+        let measurer = TextMeasurer::new();
+        let table = TableBuilder::new(vec![1000000, 1000000])
+            .add_simple_row(vec!["Short", "Row"])
+            .add_simple_row(vec!["A much longer cell that has to wrap over several lines", "x"])
+            .build();
+
+        assert_eq!(table.rows[0].measured_height(&table.column_widths, &measurer), 400000);
+        assert!(table.rows[1].measured_height(&table.column_widths, &measurer) > 400000);
+        assert!(table.measured_height(&measurer) > table.height());
+    }
+
+    #[test]
+    fn test_split() {
+        let measurer = TextMeasurer::new();
+        let mut builder = TableBuilder::new(vec![2000000, 2000000])
+            .add_simple_row(vec!["Group", "Total"])
+            .add_simple_row(vec!["Name", "Value"]);
+        for i in 0..20 {
+            builder = builder.add_simple_row(vec![&format!("Row {i}"), "1"]);
+        }
+        let table = builder.build();
+
+        let pagination = TablePagination::new().header_rows(2);
+        let parts = table.split(2000000, &pagination, &measurer);
+        assert!(parts.len() > 1);
+        assert!(parts.iter().all(|p| p.rows[1].cells[0].text == "Name" && p.height() <= 2000000));
+        assert_eq!(parts.iter().map(|p| p.row_count() - 2).sum::<usize>(), 20);
+
+        let parts = table.split(2000000, &pagination.repeat_header(false), &measurer);
+        assert_eq!(parts[0].rows[0].cells[0].text, "Group");
+        assert_eq!(parts[1].rows[0].cells[0].text, "Row 3");
+        assert_eq!(table.split(100, &TablePagination::new(), &measurer)[0].row_count(), 2);
+    }
+
+    #[test]
+    fn test_split_keeps_vertical_merges() {
+        let measurer = TextMeasurer::new();
+        let data: Vec<Vec<&str>> = (0..6).map(|_| vec!["x", "y"]).collect();
+        let table = Table::from_data(data, vec![1000000, 1000000], 0, 0).merge(2, 0, 3, 1);
+
+        let parts = table.split(1200000, &TablePagination::new(), &measurer);
+        let merged_part = parts.iter().find(|p| p.rows.iter().any(|r| r.cells[0].row_span == 3)).unwrap();
+        assert_eq!(merged_part.rows.iter().filter(|r| r.continues_merge()).count(), 2);
     }
 }
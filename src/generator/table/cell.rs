@@ -1,5 +1,10 @@
 //! Table cell definition and formatting
 
+use crate::generator::hyperlinks::Hyperlink;
+use crate::generator::text::{Run, TextBox, TextFormat, TextMeasurer, points_to_emu};
+
+use super::border::{CellBorder, CellBorders, CellMargins};
+
 /// Horizontal text alignment
 #[derive(Clone, Debug, Default, PartialEq)]
 pub enum CellAlign {
@@ -67,6 +72,22 @@ pub struct TableCell {
     pub valign: CellVAlign,
     /// Enable text wrapping
     pub wrap_text: bool,
+    /// Click action of the cell text
+    pub hyperlink: Option<Hyperlink>,
+    /// Rich text runs, rendered instead of `text` when not empty
+    pub runs: Vec<Run>,
+    /// Number of rows this cell spans (`rowSpan`)
+    pub row_span: u32,
+    /// Number of columns this cell spans (`gridSpan`)
+    pub col_span: u32,
+    /// Covered by a cell spanning from the left (`hMerge`)
+    pub h_merge: bool,
+    /// Covered by a cell spanning from above (`vMerge`)
+    pub v_merge: bool,
+    /// Per-side borders
+    pub borders: Option<CellBorders>,
+    /// Text margins
+    pub margins: Option<CellMargins>,
 }
 
 impl TableCell {
@@ -84,9 +105,23 @@ impl TableCell {
             align: CellAlign::Center,
             valign: CellVAlign::Middle,
             wrap_text: true,
+            hyperlink: None,
+            runs: Vec::new(),
+            row_span: 1,
+            col_span: 1,
+            h_merge: false,
+            v_merge: false,
+            borders: None,
+            margins: None,
         }
     }
 
+    /// Create a cell from rich text runs
+    pub fn rich(runs: Vec<Run>) -> Self {
+        let text: String = runs.iter().map(|r| r.text.as_str()).collect();
+        Self::new(&text).with_runs(runs)
+    }
+
     /// Set cell text as bold
     pub fn bold(mut self) -> Self {
         self.bold = true;
@@ -176,6 +211,117 @@ impl TableCell {
         self.wrap_text = wrap;
         self
     }
+
+    /// Make the cell text a hyperlink
+    pub fn with_hyperlink(mut self, hyperlink: Hyperlink) -> Self {
+        self.hyperlink = Some(hyperlink);
+        self
+    }
+
+    /// Set rich text runs; cell formatting applies to runs that don't override it
+    pub fn with_runs(mut self, runs: Vec<Run>) -> Self {
+        self.runs = runs;
+        self
+    }
+
+    /// Span this cell over several rows; the cells below must be [`TableCell::v_merge`]
+    pub fn row_span(mut self, rows: u32) -> Self {
+        self.row_span = rows.max(1);
+        self
+    }
+
+    /// Span this cell over several columns; the cells to the right must be [`TableCell::h_merge`]
+    pub fn col_span(mut self, cols: u32) -> Self {
+        self.col_span = cols.max(1);
+        self
+    }
+
+    /// Mark the cell as covered by a cell spanning from the left
+    pub fn h_merge(mut self) -> Self {
+        self.h_merge = true;
+        self
+    }
+
+    /// Mark the cell as covered by a cell spanning from above
+    pub fn v_merge(mut self) -> Self {
+        self.v_merge = true;
+        self
+    }
+
+    /// Whether the cell is covered by a merge and not rendered on its own
+    pub fn is_merged(&self) -> bool {
+        self.h_merge || self.v_merge
+    }
+
+    /// Set per-side borders
+    pub fn borders(mut self, borders: CellBorders) -> Self {
+        self.borders = Some(borders);
+        self
+    }
+
+    /// Set the same solid border on all sides (width in points)
+    pub fn border(mut self, width_pt: f32, color: &str) -> Self {
+        self.borders = Some(CellBorders::all(CellBorder::new(width_pt, color)));
+        self
+    }
+
+    /// Set text margins
+    pub fn margins(mut self, margins: CellMargins) -> Self {
+        self.margins = Some(margins);
+        self
+    }
+
+    /// Cell-wide formatting, inherited by runs
+    pub fn text_format(&self) -> TextFormat {
+        TextFormat {
+            bold: self.bold,
+            italic: self.italic,
+            underline: self.underline,
+            color: self.text_color.clone(),
+            font_size: self.font_size,
+            font_family: self.font_family.clone(),
+            ..TextFormat::default()
+        }
+    }
+
+    /// Hyperlinks of the cell and its runs
+    pub fn hyperlinks(&self) -> Vec<&Hyperlink> {
+        self.hyperlink.iter().chain(self.runs.iter().filter_map(|r| r.hyperlink.as_ref())).collect()
+    }
+
+    /// Mutable hyperlinks of the cell and its runs
+    pub fn hyperlinks_mut(&mut self) -> Vec<&mut Hyperlink> {
+        self.hyperlink.iter_mut().chain(self.runs.iter_mut().filter_map(|r| r.hyperlink.as_mut())).collect()
+    }
+
+    /// Plain text of the cell (its runs when set)
+    pub fn plain_text(&self) -> String {
+        if self.runs.is_empty() {
+            self.text.clone()
+        } else {
+            self.runs.iter().map(|r| r.text.as_str()).collect()
+        }
+    }
+
+    /// Height in EMU the wrapped cell text needs in a column of `width` EMU, including margins
+    pub fn measured_height(&self, width: u32, measurer: &TextMeasurer) -> u32 {
+        let margins = self.margins.clone().unwrap_or_default();
+        let text_box = TextBox::new(width, 0).with_insets(
+            margins.left.max(0) as u32,
+            margins.top.max(0) as u32,
+            margins.right.max(0) as u32,
+            margins.bottom.max(0) as u32,
+        );
+        let size = self
+            .runs
+            .iter()
+            .filter_map(|r| r.format.font_size)
+            .chain(self.font_size)
+            .max()
+            .unwrap_or(18) as f64;
+        let height = measurer.text_height(&self.plain_text(), size, self.bold, text_box.content_width());
+        points_to_emu(height) + text_box.insets.1 + text_box.insets.3
+    }
 }
 
 #[cfg(test)]
@@ -190,6 +336,8 @@ mod tests {
         assert!(!cell.italic);
         assert_eq!(cell.align, CellAlign::Center);
         assert_eq!(cell.valign, CellVAlign::Middle);
+        assert_eq!((cell.row_span, cell.col_span), (1, 1));
+        assert!(!cell.is_merged());
     }
 
     #[test]
@@ -233,4 +381,12 @@ mod tests {
         assert_eq!(CellVAlign::Middle.as_str(), "ctr");
         assert_eq!(CellVAlign::Bottom.as_str(), "b");
     }
+
+    #[test]
+    fn test_cell_spans_and_borders() {
+        let cell = TableCell::new("Merged").row_span(2).col_span(0).border(1.0, "000000");
+        assert_eq!((cell.row_span, cell.col_span), (2, 1));
+        assert!(cell.borders.as_ref().is_some_and(|b| b.left.is_some() && b.bottom.is_some()));
+        assert!(TableCell::new("").v_merge().is_merged());
+    }
 }
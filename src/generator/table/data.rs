@@ -0,0 +1,587 @@
+//! Tables from data: CSV, JSON and serde-serializable rows
+//!
+//! The first row holds the headers. Numeric columns are right-aligned, and
+//! number/date formats and colour rules are applied to the body cells when the
+//! table is built.
+
+use std::fmt::Write as _;
+use std::io::Read;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::exc::{PptxError, Result};
+use crate::generator::constants::{CONTENT_WIDTH, CONTENT_X, CONTENT_Y_START};
+
+use super::builder::{TableBuilder, TablePagination};
+use super::cell::{CellAlign, TableCell};
+use super::row::TableRow;
+
+/// Number display format
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NumberFormat {
+    /// Fixed number of decimals (shortest form when `None`)
+    pub decimals: Option<usize>,
+    /// Thousands separator
+    pub thousands_separator: Option<char>,
+    /// Text before the number (e.g. currency symbol)
+    pub prefix: String,
+    /// Text after the number (e.g. unit)
+    pub suffix: String,
+    /// Multiply by 100 and append `%`
+    pub percent: bool,
+}
+
+impl NumberFormat {
+    /// Shortest form, no separators
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Percentage with the given decimals (0.125 → "12.5%")
+    pub fn percent(decimals: usize) -> Self {
+        NumberFormat {
+            decimals: Some(decimals),
+            percent: true,
+            ..Self::default()
+        }
+    }
+
+    /// Currency with thousands separators ("$1,234.50")
+    pub fn currency(symbol: &str, decimals: usize) -> Self {
+        NumberFormat {
+            decimals: Some(decimals),
+            thousands_separator: Some(','),
+            prefix: symbol.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Set the number of decimals
+    pub fn decimals(mut self, decimals: usize) -> Self {
+        self.decimals = Some(decimals);
+        self
+    }
+
+    /// Group thousands with a separator
+    pub fn thousands(mut self, separator: char) -> Self {
+        self.thousands_separator = Some(separator);
+        self
+    }
+
+    /// Set the prefix
+    pub fn prefix(mut self, prefix: &str) -> Self {
+        self.prefix = prefix.to_string();
+        self
+    }
+
+    /// Set the suffix
+    pub fn suffix(mut self, suffix: &str) -> Self {
+        self.suffix = suffix.to_string();
+        self
+    }
+
+    /// Format a value
+    pub fn format(&self, value: f64) -> String {
+        let value = if self.percent { value * 100.0 } else { value };
+        let digits = match self.decimals {
+            Some(decimals) => format!("{:.*}", decimals, value.abs()),
+            None => format!("{}", value.abs()),
+        };
+        let (int, frac) = match digits.split_once('.') {
+            Some((int, frac)) => (int, Some(frac)),
+            None => (digits.as_str(), None),
+        };
+
+        let mut number = String::new();
+        for (i, digit) in int.chars().enumerate() {
+            if let Some(sep) = self.thousands_separator
+                && i > 0
+                && (int.len() - i) % 3 == 0
+            {
+                number.push(sep);
+            }
+            number.push(digit);
+        }
+        if let Some(frac) = frac {
+            number.push('.');
+            number.push_str(frac);
+        }
+
+        let negative = value < 0.0 && number.chars().any(|c| c.is_ascii_digit() && c != '0');
+        format!(
+            "{}{}{}{}{}",
+            if negative { "-" } else { "" },
+            self.prefix,
+            number,
+            if self.percent { "%" } else { "" },
+            self.suffix
+        )
+    }
+}
+
+/// When a threshold colour rule applies
+#[derive(Clone, Debug, PartialEq)]
+pub enum Condition {
+    /// Value greater than
+    Above(f64),
+    /// Value less than
+    Below(f64),
+    /// Value within the inclusive range
+    Between(f64, f64),
+    /// Value equal to
+    Equal(f64),
+}
+
+impl Condition {
+    /// Whether a value matches
+    pub fn matches(&self, value: f64) -> bool {
+        match *self {
+            Condition::Above(limit) => value > limit,
+            Condition::Below(limit) => value < limit,
+            Condition::Between(low, high) => value >= low && value <= high,
+            Condition::Equal(target) => value == target,
+        }
+    }
+}
+
+/// Conditional colouring of numeric cells
+#[derive(Clone, Debug, PartialEq)]
+pub enum ColorRule {
+    /// Fixed colours for values matching a condition
+    Threshold {
+        condition: Condition,
+        background: Option<String>,
+        text_color: Option<String>,
+    },
+    /// Background interpolated between colours over the column's value range
+    Scale { colors: Vec<String> },
+}
+
+impl ColorRule {
+    /// Background for values matching a condition
+    pub fn when(condition: Condition, background: &str) -> Self {
+        ColorRule::Threshold {
+            condition,
+            background: Some(normalize_color(background)),
+            text_color: None,
+        }
+    }
+
+    /// Background for values greater than `limit`
+    pub fn above(limit: f64, background: &str) -> Self {
+        Self::when(Condition::Above(limit), background)
+    }
+
+    /// Background for values less than `limit`
+    pub fn below(limit: f64, background: &str) -> Self {
+        Self::when(Condition::Below(limit), background)
+    }
+
+    /// Background for values within `low..=high`
+    pub fn between(low: f64, high: f64, background: &str) -> Self {
+        Self::when(Condition::Between(low, high), background)
+    }
+
+    /// Heat map from the lowest to the highest value
+    pub fn heat_map(low: &str, high: &str) -> Self {
+        ColorRule::Scale {
+            colors: vec![normalize_color(low), normalize_color(high)],
+        }
+    }
+
+    /// Three-colour heat map with a midpoint colour
+    pub fn heat_map_with_mid(low: &str, mid: &str, high: &str) -> Self {
+        ColorRule::Scale {
+            colors: vec![normalize_color(low), normalize_color(mid), normalize_color(high)],
+        }
+    }
+
+    /// Also set the text colour of matching cells (threshold rules only)
+    pub fn text_color(mut self, color: &str) -> Self {
+        if let ColorRule::Threshold { ref mut text_color, .. } = self {
+            *text_color = Some(normalize_color(color));
+        }
+        self
+    }
+
+    /// Colour a column's body cells
+    fn apply(&self, cells: &mut [&mut TableCell]) {
+        let values: Vec<Option<f64>> = cells.iter().map(|c| parse_number(&c.text)).collect();
+        match self {
+            ColorRule::Threshold { condition, background, text_color } => {
+                for (cell, value) in cells.iter_mut().zip(&values) {
+                    if value.is_some_and(|v| condition.matches(v)) {
+                        if background.is_some() {
+                            cell.background_color = background.clone();
+                        }
+                        if text_color.is_some() {
+                            cell.text_color = text_color.clone();
+                        }
+                    }
+                }
+            }
+            ColorRule::Scale { colors } => {
+                let numbers = values.iter().flatten();
+                let min = numbers.clone().copied().fold(f64::INFINITY, f64::min);
+                let max = numbers.copied().fold(f64::NEG_INFINITY, f64::max);
+                for (cell, value) in cells.iter_mut().zip(&values) {
+                    if let Some(value) = value {
+                        let t = if max > min { (value - min) / (max - min) } else { 0.5 };
+                        cell.background_color = Some(scale_color(colors, t));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Per-column format of body cells
+#[derive(Clone, Debug, PartialEq)]
+pub(super) enum ColumnFormat {
+    Number(NumberFormat),
+    Date(String),
+}
+
+impl ColumnFormat {
+    /// Reformat a cell's text, leaving values that don't parse unchanged
+    fn apply(&self, cell: &mut TableCell) {
+        let formatted = match self {
+            ColumnFormat::Number(format) => parse_number(&cell.text).map(|v| format.format(v)),
+            ColumnFormat::Date(format) => parse_date(&cell.text).and_then(|date| {
+                let mut text = String::new();
+                write!(text, "{}", date.format(format)).ok().map(|_| text)
+            }),
+        };
+        if let Some(text) = formatted {
+            cell.text = text;
+        }
+    }
+}
+
+/// Apply colour rules, then formats, to the body rows (colours see the raw values)
+pub(super) fn apply_column_rules(
+    rows: &mut [TableRow],
+    header_rows: usize,
+    formats: &[(usize, ColumnFormat)],
+    rules: &[(usize, ColorRule)],
+) {
+    let body = rows.iter_mut().skip(header_rows);
+    let mut body: Vec<&mut TableRow> = body.collect();
+    for (column, rule) in rules {
+        let mut cells: Vec<&mut TableCell> = body.iter_mut().filter_map(|r| r.cells.get_mut(*column)).collect();
+        rule.apply(&mut cells);
+    }
+    for (column, format) in formats {
+        for row in body.iter_mut() {
+            if let Some(cell) = row.cells.get_mut(*column) {
+                format.apply(cell);
+            }
+        }
+    }
+}
+
+impl TableBuilder {
+    /// Build a table from CSV with a header row
+    ///
+    /// Fields may be quoted (`"a, b"`, `""` for a quote) and span lines.
+    pub fn from_csv<R: Read>(mut reader: R) -> Result<Self> {
+        let mut text = String::new();
+        reader.read_to_string(&mut text)?;
+        let records = parse_csv(text.trim_start_matches('\u{feff}'))?;
+        Self::from_records(records)
+    }
+
+    /// Build a table from a JSON array of objects (keys become headers) or of arrays
+    /// (the first array holds the headers)
+    pub fn from_json(value: &Value) -> Result<Self> {
+        let items = value
+            .as_array()
+            .ok_or_else(|| PptxError::InvalidValue("JSON table data must be an array".to_string()))?;
+
+        if items.iter().all(Value::is_object) {
+            let mut headers: Vec<String> = Vec::new();
+            for key in items.iter().filter_map(Value::as_object).flat_map(|o| o.keys()) {
+                if !headers.contains(key) {
+                    headers.push(key.clone());
+                }
+            }
+            let rows = items.iter().filter_map(Value::as_object).map(|object| {
+                headers.iter().map(|h| object.get(h).map(json_text).unwrap_or_default()).collect()
+            });
+            let records = std::iter::once(headers.clone()).chain(rows).collect();
+            return Self::from_records(records);
+        }
+
+        if items.iter().all(Value::is_array) {
+            let records = items
+                .iter()
+                .filter_map(Value::as_array)
+                .map(|row| row.iter().map(json_text).collect())
+                .collect();
+            return Self::from_records(records);
+        }
+
+        Err(PptxError::InvalidValue(
+            "JSON table rows must all be objects or all be arrays".to_string(),
+        ))
+    }
+
+    /// Build a table from serializable rows (struct fields become headers)
+    pub fn from_serializable<T: Serialize>(rows: &[T]) -> Result<Self> {
+        let value = serde_json::to_value(rows).map_err(|e| PptxError::InvalidValue(e.to_string()))?;
+        Self::from_json(&value)
+    }
+
+    /// Build a table from a header record and body records
+    fn from_records(records: Vec<Vec<String>>) -> Result<Self> {
+        let columns = records.iter().map(Vec::len).max().unwrap_or(0);
+        if records.is_empty() || columns == 0 {
+            return Err(PptxError::InvalidValue("table data has no header row".to_string()));
+        }
+
+        let numeric: Vec<bool> = (0..columns)
+            .map(|col| {
+                let mut values = records.iter().skip(1).filter_map(|r| r.get(col)).filter(|v| !v.trim().is_empty());
+                let mut any = false;
+                values.all(|v| {
+                    any = true;
+                    parse_number(v).is_some()
+                }) && any
+            })
+            .collect();
+
+        let width = CONTENT_WIDTH / columns as u32;
+        let mut builder = TableBuilder::new(vec![width; columns])
+            .position(CONTENT_X, CONTENT_Y_START)
+            .pagination(TablePagination::new());
+
+        for (i, record) in records.iter().enumerate() {
+            let cells = (0..columns)
+                .map(|col| {
+                    let mut cell = TableCell::new(record.get(col).map(String::as_str).unwrap_or(""));
+                    if i == 0 {
+                        cell = cell.bold().background_color("4472C4").text_color("FFFFFF");
+                    }
+                    if numeric[col] {
+                        cell = cell.align(CellAlign::Right);
+                    }
+                    cell
+                })
+                .collect();
+            builder = builder.add_row(TableRow::new(cells));
+        }
+        Ok(builder)
+    }
+}
+
+/// Parse CSV records (RFC 4180)
+fn parse_csv(text: &str) -> Result<Vec<Vec<String>>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => in_quotes = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() => in_quotes = true,
+            ',' => record.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' | '\r' => {
+                record.push(std::mem::take(&mut field));
+                if record.iter().any(|f| !f.is_empty()) {
+                    records.push(std::mem::take(&mut record));
+                }
+                record.clear();
+            }
+            _ => field.push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err(PptxError::InvalidValue("unterminated quoted CSV field".to_string()));
+    }
+    record.push(field);
+    if record.iter().any(|f| !f.is_empty()) {
+        records.push(record);
+    }
+    Ok(records)
+}
+
+/// Cell text of a JSON value
+fn json_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Parse a finite number from cell text
+fn parse_number(text: &str) -> Option<f64> {
+    text.trim().parse::<f64>().ok().filter(|v| v.is_finite())
+}
+
+/// Parse an ISO 8601 / RFC 3339 date or date-time
+fn parse_date(text: &str) -> Option<NaiveDateTime> {
+    let text = text.trim();
+    if let Ok(date) = DateTime::parse_from_rfc3339(text) {
+        return Some(date.naive_local());
+    }
+    ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M"]
+        .iter()
+        .find_map(|f| NaiveDateTime::parse_from_str(text, f).ok())
+        .or_else(|| NaiveDate::parse_from_str(text, "%Y-%m-%d").ok()?.and_hms_opt(0, 0, 0))
+}
+
+/// Strip `#` and upper-case an RGB hex colour
+fn normalize_color(color: &str) -> String {
+    color.trim_start_matches('#').to_uppercase()
+}
+
+/// Colour at position `t` (0..=1) of an evenly spaced colour scale
+fn scale_color(colors: &[String], t: f64) -> String {
+    let rgb = |hex: &str| -> [f64; 3] {
+        let channel = |i: usize| hex.get(i..i + 2).and_then(|c| u8::from_str_radix(c, 16).ok()).unwrap_or(0) as f64;
+        [channel(0), channel(2), channel(4)]
+    };
+    if colors.len() < 2 {
+        return colors.first().cloned().unwrap_or_default();
+    }
+    let position = t.clamp(0.0, 1.0) * (colors.len() - 1) as f64;
+    let index = (position.floor() as usize).min(colors.len() - 2);
+    let local = position - index as f64;
+    let (from, to) = (rgb(&colors[index]), rgb(&colors[index + 1]));
+    (0..3)
+        .map(|i| format!("{:02X}", (from[i] + (to[i] - from[i]) * local).round() as u8))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_number_format() {
+        assert_eq!(NumberFormat::currency("$", 2).format(1234567.891), "$1,234,567.89");
+        assert_eq!(NumberFormat::currency("$", 0).format(-999.6), "-$1,000");
+        assert_eq!(NumberFormat::percent(1).format(0.1234), "12.3%");
+        assert_eq!(NumberFormat::new().thousands(' ').suffix(" kg").format(12500.5), "12 500.5 kg");
+        assert_eq!(NumberFormat::new().decimals(1).format(-0.01), "0.0");
+    }
+
+    #[test]
+    fn test_parse_csv() {
+        let records = parse_csv("name,note\r\n\"Smith, J\",\"said \"\"hi\"\"\nthere\"\n\n").unwrap();
+        assert_eq!(records, vec![
+            vec!["name".to_string(), "note".to_string()],
+            vec!["Smith, J".to_string(), "said \"hi\"\nthere".to_string()],
+        ]);
+        assert!(parse_csv("a\n\"open").is_err());
+    }
+
+    #[test]
+    fn test_from_csv() {
+        let csv = "Region,Revenue,Date\nNorth,1200.5,2024-03-01\nSouth,,2024-03-02\nEast,980,n/a\n";
+        let table = TableBuilder::from_csv(csv.as_bytes())
+            .unwrap()
+            .number_format(1, NumberFormat::currency("$", 2))
+            .date_format(2, "%d %b %Y")
+            .build();
+
+        assert_eq!(table.row_count(), 4);
+        assert_eq!(table.x, CONTENT_X);
+        assert!(table.pagination.is_some());
+        assert!(table.rows[0].cells[0].bold);
+        assert_eq!(table.rows[1].cells[1].align, CellAlign::Right);
+        assert_eq!(table.rows[0].cells[1].align, CellAlign::Right);
+        assert_eq!(table.rows[1].cells[0].align, CellAlign::Center);
+        assert_eq!(table.rows[1].cells[1].text, "$1,200.50");
+        assert_eq!(table.rows[2].cells[1].text, "");
+        assert_eq!(table.rows[1].cells[2].text, "01 Mar 2024");
+        assert_eq!(table.rows[3].cells[2].text, "n/a");
+        assert!(TableBuilder::from_csv("".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_from_json() {
+        let value = serde_json::json!([
+            {"name": "Widget", "price": 9.5, "stock": 3},
+            {"name": "Gadget", "price": 12, "discontinued": true},
+        ]);
+        let table = TableBuilder::from_json(&value).unwrap().build();
+        let headers: Vec<&str> = table.rows[0].cells.iter().map(|c| c.text.as_str()).collect();
+        assert_eq!(headers, vec!["name", "price", "stock", "discontinued"]);
+        assert_eq!(table.rows[2].cells[2].text, "");
+        assert_eq!(table.rows[2].cells[3].text, "true");
+        assert_eq!(table.rows[1].cells[1].align, CellAlign::Right);
+
+        let arrays = serde_json::json!([["a", "b"], [1, null]]);
+        assert_eq!(TableBuilder::from_json(&arrays).unwrap().build().rows[1].cells[0].text, "1");
+        assert!(TableBuilder::from_json(&serde_json::json!({"a": 1})).is_err());
+        assert!(TableBuilder::from_json(&serde_json::json!([1, 2])).is_err());
+    }
+
+    #[test]
+    fn test_from_serializable() {
+        #[derive(Serialize)]
+        struct Sale {
+            region: &'static str,
+            units: u32,
+            share: f64,
+        }
+
+        let sales = [
+            Sale { region: "North", units: 120, share: 0.4 },
+            Sale { region: "South", units: 180, share: 0.6 },
+        ];
+        let table = TableBuilder::from_serializable(&sales)
+            .unwrap()
+            .number_format(2, NumberFormat::percent(0))
+            .build();
+        assert_eq!(table.rows[0].cells[0].text, "region");
+        assert_eq!(table.rows[0].cells[2].text, "share");
+        assert_eq!(table.rows[2].cells[2].text, "60%");
+    }
+
+    #[test]
+    fn test_color_rules() {
+        let csv = "item,score\na,10\nb,55\nc,100\nd,x\n";
+        let table = TableBuilder::from_csv(csv.as_bytes())
+            .unwrap()
+            .color_rule(1, ColorRule::heat_map("#FFFFFF", "FF0000"))
+            .color_rule(1, ColorRule::above(90.0, "00B050").text_color("FFFFFF"))
+            .number_format(1, NumberFormat::new().decimals(1))
+            .build();
+
+        let cells: Vec<&TableCell> = table.rows.iter().skip(1).map(|r| &r.cells[1]).collect();
+        assert_eq!(cells[0].background_color.as_deref(), Some("FFFFFF"));
+        assert_eq!(cells[1].background_color.as_deref(), Some("FF8080"));
+        assert_eq!(cells[2].background_color.as_deref(), Some("00B050"));
+        assert_eq!(cells[2].text_color.as_deref(), Some("FFFFFF"));
+        assert_eq!(cells[2].text, "100.0");
+        assert!(cells[3].background_color.is_none());
+        assert_eq!(table.rows[0].cells[1].background_color.as_deref(), Some("4472C4"));
+    }
+
+    #[test]
+    fn test_scale_color() {
+        let colors = vec!["000000".to_string(), "FFFFFF".to_string(), "FF0000".to_string()];
+        assert_eq!(scale_color(&colors, 0.0), "000000");
+        assert_eq!(scale_color(&colors, 0.5), "FFFFFF");
+        assert_eq!(scale_color(&colors, 1.0), "FF0000");
+    }
+}
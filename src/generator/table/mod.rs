@@ -3,17 +3,26 @@
 //! This module provides comprehensive table support including:
 //! - Cell content and formatting
 //! - Row and column management
+//! - Merged cells (row and column spans)
 //! - Text alignment (horizontal and vertical)
-//! - Cell backgrounds and borders
+//! - Cell backgrounds, borders and margins
+//! - Built-in and custom table styles (`ppt/tableStyles.xml`)
+//! - Tables from CSV, JSON and serde rows with number/date formats and colour rules
 //! - Text wrapping
 //! - XML generation for OOXML
 
+mod border;
 mod cell;
 mod row;
 mod builder;
+mod data;
+mod style;
 mod xml;
 
+pub use border::{BorderStyle, CellBorder, CellBorders, CellMargins};
 pub use cell::{TableCell, CellAlign, CellVAlign};
 pub use row::TableRow;
-pub use builder::{Table, TableBuilder};
-pub use xml::generate_table_xml;
+pub use data::{ColorRule, Condition, NumberFormat};
+pub use builder::{Table, TableBuilder, TablePagination};
+pub use style::{builtin, is_style_id, TableStyle, TableStyleBorders, TableStyleOptions, TableStylePart};
+pub use xml::{generate_table_styles_xml, generate_table_xml};
@@ -1,5 +1,7 @@
 //! Table row definition
 
+use crate::generator::text::TextMeasurer;
+
 use super::cell::TableCell;
 
 /// Table row containing cells
@@ -30,6 +32,27 @@ impl TableRow {
     pub fn cell_count(&self) -> usize {
         self.cells.len()
     }
+
+    /// Whether the row continues a vertical merge from the row above
+    pub fn continues_merge(&self) -> bool {
+        self.cells.iter().any(|c| c.v_merge)
+    }
+
+    /// Rendered height in EMU: the row height, grown to fit the tallest cell
+    ///
+    /// Cells spanning several rows are left out; a column span measures against
+    /// the combined width.
+    pub fn measured_height(&self, column_widths: &[u32], measurer: &TextMeasurer) -> u32 {
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(|(_, cell)| !cell.is_merged() && cell.row_span <= 1)
+            .map(|(i, cell)| {
+                let width = column_widths.iter().skip(i).take(cell.col_span as usize).sum();
+                cell.measured_height(width, measurer)
+            })
+            .fold(self.height.unwrap_or(400000), u32::max)
+    }
 }
 
 #[cfg(test)]
@@ -52,4 +75,16 @@ mod tests {
             .with_height(500000);
         assert_eq!(row.height, Some(500000));
     }
+
+    #[test]
+    fn test_measured_height_with_span() {
+        let measurer = TextMeasurer::new();
+        let text = "A longer heading that wraps in a single narrow column";
+        let single = TableRow::new(vec![TableCell::new(text), TableCell::new("")]);
+        let spanned = TableRow::new(vec![TableCell::new(text).col_span(2), TableCell::new("").h_merge()]);
+        let widths = [1500000, 1500000];
+
+        assert!(single.measured_height(&widths, &measurer) > spanned.measured_height(&widths, &measurer));
+        assert!(!spanned.continues_merge());
+    }
 }
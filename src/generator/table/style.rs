@@ -0,0 +1,543 @@
+//! Table styles
+//!
+//! A table points at a style by id (`a:tableStyleId`); the `a:tblPr` flags
+//! choose which parts of that style apply. Built-in styles are known to
+//! PowerPoint by id, custom styles are written to `ppt/tableStyles.xml`.
+
+use crate::prelude::themes::{self, Theme};
+
+use super::border::CellBorder;
+
+/// Ids of PowerPoint's built-in table styles
+///
+/// Named constants cover a subset of PowerPoint's 74 built-in styles: the plain
+/// styles, the accent 1 variants of the light, themed and medium 1 families,
+/// every Medium Style 2 and Dark Style 1. The ids of the other built-in styles
+/// work as well; presentations only check that a style id is a `{GUID}`.
+pub mod builtin {
+    use crate::exc::PptxError;
+
+    pub const NO_STYLE_NO_GRID: &str = "{2D5ABB26-0587-4C30-8999-92F81FD0307C}";
+    pub const NO_STYLE_TABLE_GRID: &str = "{5940675A-B579-460E-94D1-54222C63F5DA}";
+    pub const THEMED_STYLE_1_ACCENT_1: &str = "{3C2FFA5D-87B4-456A-9821-1D502468CF0F}";
+    pub const LIGHT_STYLE_1_ACCENT_1: &str = "{3B4B98B0-60AC-42C2-AFA5-B58CD77FA1E5}";
+    pub const LIGHT_STYLE_2_ACCENT_1: &str = "{69012ECD-51FC-41F1-AA8D-1B2483CD663E}";
+    pub const MEDIUM_STYLE_1_ACCENT_1: &str = "{B301B821-A1FF-4177-AEE7-76D212191A09}";
+    pub const MEDIUM_STYLE_2: &str = "{073A0DAA-6AF3-43AB-8588-CEC1D06C72B9}";
+    pub const MEDIUM_STYLE_2_ACCENT_1: &str = "{5C22544A-7EE6-4342-B048-85BDC9FD1C3A}";
+    pub const MEDIUM_STYLE_2_ACCENT_2: &str = "{21E4AEA4-8DFA-4A89-87EB-49C32662AFE8}";
+    pub const MEDIUM_STYLE_2_ACCENT_3: &str = "{F5AB1C69-6EDB-4FF4-983F-18BD219EF322}";
+    pub const MEDIUM_STYLE_2_ACCENT_4: &str = "{00A15C55-8517-42AA-B614-E9B94910E393}";
+    pub const MEDIUM_STYLE_2_ACCENT_5: &str = "{7DF18680-E054-41AD-8BC1-D1AEF772440D}";
+    pub const MEDIUM_STYLE_2_ACCENT_6: &str = "{93296810-A885-4BE3-A3E7-6D5BEEA58F35}";
+    pub const DARK_STYLE_1: &str = "{E8034E78-7F5D-4C2E-B375-FC64B27BC917}";
+
+    /// The styles above by their name in PowerPoint's gallery
+    pub const ALL: &[(&str, &str)] = &[
+        ("No Style, No Grid", NO_STYLE_NO_GRID),
+        ("No Style, Table Grid", NO_STYLE_TABLE_GRID),
+        ("Themed Style 1 - Accent 1", THEMED_STYLE_1_ACCENT_1),
+        ("Light Style 1 - Accent 1", LIGHT_STYLE_1_ACCENT_1),
+        ("Light Style 2 - Accent 1", LIGHT_STYLE_2_ACCENT_1),
+        ("Medium Style 1 - Accent 1", MEDIUM_STYLE_1_ACCENT_1),
+        ("Medium Style 2", MEDIUM_STYLE_2),
+        ("Medium Style 2 - Accent 1", MEDIUM_STYLE_2_ACCENT_1),
+        ("Medium Style 2 - Accent 2", MEDIUM_STYLE_2_ACCENT_2),
+        ("Medium Style 2 - Accent 3", MEDIUM_STYLE_2_ACCENT_3),
+        ("Medium Style 2 - Accent 4", MEDIUM_STYLE_2_ACCENT_4),
+        ("Medium Style 2 - Accent 5", MEDIUM_STYLE_2_ACCENT_5),
+        ("Medium Style 2 - Accent 6", MEDIUM_STYLE_2_ACCENT_6),
+        ("Dark Style 1", DARK_STYLE_1),
+    ];
+
+    /// Id of a style by its gallery name, ignoring case
+    pub fn named(name: &str) -> Result<&'static str, PptxError> {
+        ALL.iter()
+            .find(|(style, _)| style.eq_ignore_ascii_case(name.trim()))
+            .map(|&(_, id)| id)
+            .ok_or_else(|| PptxError::InvalidValue(format!("unknown built-in table style `{name}`")))
+    }
+
+    /// Whether `id` is one of the styles above
+    pub fn contains(id: &str) -> bool {
+        ALL.iter().any(|&(_, style)| style.eq_ignore_ascii_case(id))
+    }
+}
+
+/// Whether `id` has the form of a table style id, a `{GUID}`
+pub fn is_style_id(id: &str) -> bool {
+    let Some(guid) = id.strip_prefix('{').and_then(|id| id.strip_suffix('}')) else {
+        return false;
+    };
+    let groups: Vec<&str> = guid.split('-').collect();
+    groups.iter().map(|group| group.len()).eq([8, 4, 4, 4, 12])
+        && groups.iter().all(|group| group.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// Which parts of the table style apply (`a:tblPr` flags)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TableStyleOptions {
+    pub first_row: bool,
+    pub last_row: bool,
+    pub first_column: bool,
+    pub last_column: bool,
+    pub banded_rows: bool,
+    pub banded_columns: bool,
+}
+
+impl Default for TableStyleOptions {
+    fn default() -> Self {
+        TableStyleOptions {
+            first_row: true,
+            last_row: false,
+            first_column: false,
+            last_column: false,
+            banded_rows: true,
+            banded_columns: false,
+        }
+    }
+}
+
+impl TableStyleOptions {
+    /// Header row and banded rows (PowerPoint's default for new tables)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Emphasize the first (header) row
+    pub fn first_row(mut self, on: bool) -> Self {
+        self.first_row = on;
+        self
+    }
+
+    /// Emphasize the last (total) row
+    pub fn last_row(mut self, on: bool) -> Self {
+        self.last_row = on;
+        self
+    }
+
+    /// Emphasize the first column
+    pub fn first_column(mut self, on: bool) -> Self {
+        self.first_column = on;
+        self
+    }
+
+    /// Emphasize the last column
+    pub fn last_column(mut self, on: bool) -> Self {
+        self.last_column = on;
+        self
+    }
+
+    /// Alternate row shading
+    pub fn banded_rows(mut self, on: bool) -> Self {
+        self.banded_rows = on;
+        self
+    }
+
+    /// Alternate column shading
+    pub fn banded_columns(mut self, on: bool) -> Self {
+        self.banded_columns = on;
+        self
+    }
+
+    /// Attributes of `a:tblPr`
+    pub fn to_xml_attrs(&self) -> String {
+        [
+            (self.first_row, "firstRow"),
+            (self.first_column, "firstCol"),
+            (self.last_row, "lastRow"),
+            (self.last_column, "lastCol"),
+            (self.banded_rows, "bandRow"),
+            (self.banded_columns, "bandCol"),
+        ]
+        .iter()
+        .filter(|(on, _)| *on)
+        .map(|(_, name)| format!(r#" {name}="1""#))
+        .collect()
+    }
+}
+
+/// Borders of a table style part, including the lines between cells
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TableStyleBorders {
+    pub left: Option<CellBorder>,
+    pub right: Option<CellBorder>,
+    pub top: Option<CellBorder>,
+    pub bottom: Option<CellBorder>,
+    /// Lines between rows
+    pub inside_horizontal: Option<CellBorder>,
+    /// Lines between columns
+    pub inside_vertical: Option<CellBorder>,
+}
+
+impl TableStyleBorders {
+    /// Same border outside and between all cells
+    pub fn all(border: CellBorder) -> Self {
+        Self::outer(border.clone()).inside(border)
+    }
+
+    /// Same border on the four outer sides
+    pub fn outer(border: CellBorder) -> Self {
+        TableStyleBorders {
+            left: Some(border.clone()),
+            right: Some(border.clone()),
+            top: Some(border.clone()),
+            bottom: Some(border),
+            ..Self::default()
+        }
+    }
+
+    /// Set the lines between rows and columns
+    pub fn inside(mut self, border: CellBorder) -> Self {
+        self.inside_horizontal = Some(border.clone());
+        self.inside_vertical = Some(border);
+        self
+    }
+
+    /// Set the top border
+    pub fn top(mut self, border: CellBorder) -> Self {
+        self.top = Some(border);
+        self
+    }
+
+    /// Set the bottom border
+    pub fn bottom(mut self, border: CellBorder) -> Self {
+        self.bottom = Some(border);
+        self
+    }
+
+    /// Set the lines between rows
+    pub fn inside_horizontal(mut self, border: CellBorder) -> Self {
+        self.inside_horizontal = Some(border);
+        self
+    }
+
+    /// Whether no border is set
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Generate `a:tcBdr`
+    pub fn to_xml(&self) -> String {
+        if self.is_empty() {
+            return String::new();
+        }
+        let sides = [
+            ("left", &self.left),
+            ("right", &self.right),
+            ("top", &self.top),
+            ("bottom", &self.bottom),
+            ("insideH", &self.inside_horizontal),
+            ("insideV", &self.inside_vertical),
+        ];
+        let lines: String = sides
+            .iter()
+            .filter_map(|(tag, border)| border.as_ref().map(|b| format!("<a:{tag}>{}</a:{tag}>", b.to_xml("ln"))))
+            .collect();
+        format!("<a:tcBdr>{lines}</a:tcBdr>")
+    }
+}
+
+/// Formatting of one part of a table style (whole table, header row, band, ...)
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TableStylePart {
+    /// Cell fill (RGB hex)
+    pub fill: Option<String>,
+    /// Text color (RGB hex)
+    pub text_color: Option<String>,
+    pub bold: bool,
+    pub italic: bool,
+    pub borders: TableStyleBorders,
+}
+
+impl TableStylePart {
+    /// Create an empty part
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the cell fill
+    pub fn fill(mut self, color: &str) -> Self {
+        self.fill = Some(color.trim_start_matches('#').to_uppercase());
+        self
+    }
+
+    /// Set the text color
+    pub fn text_color(mut self, color: &str) -> Self {
+        self.text_color = Some(color.trim_start_matches('#').to_uppercase());
+        self
+    }
+
+    /// Bold text
+    pub fn bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+
+    /// Italic text
+    pub fn italic(mut self) -> Self {
+        self.italic = true;
+        self
+    }
+
+    /// Set the borders
+    pub fn borders(mut self, borders: TableStyleBorders) -> Self {
+        self.borders = borders;
+        self
+    }
+
+    /// Generate the part element (`a:wholeTbl`, `a:firstRow`, ...)
+    pub fn to_xml(&self, tag: &str) -> String {
+        let mut xml = format!("<a:{tag}>");
+        if self.bold || self.italic || self.text_color.is_some() {
+            xml.push_str("<a:tcTxStyle");
+            if self.bold {
+                xml.push_str(r#" b="on""#);
+            }
+            if self.italic {
+                xml.push_str(r#" i="on""#);
+            }
+            match &self.text_color {
+                Some(color) => xml.push_str(&format!(r#"><a:srgbClr val="{color}"/></a:tcTxStyle>"#)),
+                None => xml.push_str("/>"),
+            }
+        }
+        let borders = self.borders.to_xml();
+        if !borders.is_empty() || self.fill.is_some() {
+            xml.push_str("<a:tcStyle>");
+            xml.push_str(&borders);
+            if let Some(ref fill) = self.fill {
+                xml.push_str(&format!(r#"<a:fill><a:solidFill><a:srgbClr val="{fill}"/></a:solidFill></a:fill>"#));
+            }
+            xml.push_str("</a:tcStyle>");
+        }
+        xml.push_str(&format!("</a:{tag}>"));
+        xml
+    }
+}
+
+/// A custom table style, written to `ppt/tableStyles.xml`
+#[derive(Clone, Debug, PartialEq)]
+pub struct TableStyle {
+    /// Style id (GUID in braces)
+    pub id: String,
+    /// Display name
+    pub name: String,
+    pub whole_table: Option<TableStylePart>,
+    pub band1_row: Option<TableStylePart>,
+    pub band2_row: Option<TableStylePart>,
+    pub band1_column: Option<TableStylePart>,
+    pub band2_column: Option<TableStylePart>,
+    pub header_row: Option<TableStylePart>,
+    pub total_row: Option<TableStylePart>,
+    pub first_column: Option<TableStylePart>,
+    pub last_column: Option<TableStylePart>,
+}
+
+impl TableStyle {
+    /// Create an empty style; the id is derived from the name
+    pub fn new(name: &str) -> Self {
+        TableStyle {
+            id: style_guid(name),
+            name: name.to_string(),
+            whole_table: None,
+            band1_row: None,
+            band2_row: None,
+            band1_column: None,
+            band2_column: None,
+            header_row: None,
+            total_row: None,
+            first_column: None,
+            last_column: None,
+        }
+    }
+
+    /// Style matching a [`Theme`]: primary header, light/background banded rows
+    pub fn from_theme(theme: &Theme) -> Self {
+        let line = CellBorder::new(1.0, theme.background);
+        Self::new(&format!("{} Table", theme.name))
+            .whole_table(
+                TableStylePart::new()
+                    .text_color(theme.text)
+                    .fill(theme.light)
+                    .borders(TableStyleBorders::all(line)),
+            )
+            .header_row(TableStylePart::new().bold().text_color(contrast_text(theme.primary)).fill(theme.primary))
+            .total_row(
+                TableStylePart::new()
+                    .bold()
+                    .borders(TableStyleBorders::default().top(CellBorder::new(2.0, theme.primary))),
+            )
+            .first_column(TableStylePart::new().bold())
+            .last_column(TableStylePart::new().bold())
+            .banded_rows(TableStylePart::new().fill(theme.light), TableStylePart::new().fill(theme.background))
+            .banded_columns(TableStylePart::new().fill(theme.light), TableStylePart::new().fill(theme.background))
+    }
+
+    /// One style per theme in [`themes::all`]
+    pub fn presets() -> Vec<TableStyle> {
+        themes::all().iter().map(Self::from_theme).collect()
+    }
+
+    /// Set the id (GUID in braces)
+    pub fn with_id(mut self, id: &str) -> Self {
+        self.id = id.to_string();
+        self
+    }
+
+    /// Formatting of every cell
+    pub fn whole_table(mut self, part: TableStylePart) -> Self {
+        self.whole_table = Some(part);
+        self
+    }
+
+    /// Formatting of the first row
+    pub fn header_row(mut self, part: TableStylePart) -> Self {
+        self.header_row = Some(part);
+        self
+    }
+
+    /// Formatting of the last row
+    pub fn total_row(mut self, part: TableStylePart) -> Self {
+        self.total_row = Some(part);
+        self
+    }
+
+    /// Formatting of the first column
+    pub fn first_column(mut self, part: TableStylePart) -> Self {
+        self.first_column = Some(part);
+        self
+    }
+
+    /// Formatting of the last column
+    pub fn last_column(mut self, part: TableStylePart) -> Self {
+        self.last_column = Some(part);
+        self
+    }
+
+    /// Formatting of odd and even rows
+    pub fn banded_rows(mut self, band1: TableStylePart, band2: TableStylePart) -> Self {
+        self.band1_row = Some(band1);
+        self.band2_row = Some(band2);
+        self
+    }
+
+    /// Formatting of odd and even columns
+    pub fn banded_columns(mut self, band1: TableStylePart, band2: TableStylePart) -> Self {
+        self.band1_column = Some(band1);
+        self.band2_column = Some(band2);
+        self
+    }
+
+    /// Generate the `a:tblStyle` element
+    pub fn to_xml(&self) -> String {
+        // Schema order of the parts
+        let parts = [
+            ("wholeTbl", &self.whole_table),
+            ("band1H", &self.band1_row),
+            ("band2H", &self.band2_row),
+            ("band1V", &self.band1_column),
+            ("band2V", &self.band2_column),
+            ("lastCol", &self.last_column),
+            ("firstCol", &self.first_column),
+            ("lastRow", &self.total_row),
+            ("firstRow", &self.header_row),
+        ];
+        let body: String = parts
+            .iter()
+            .filter_map(|(tag, part)| part.as_ref().map(|p| p.to_xml(tag)))
+            .collect();
+        format!(
+            r#"<a:tblStyle styleId="{}" styleName="{}">{body}</a:tblStyle>"#,
+            self.id,
+            crate::core::escape_xml(&self.name)
+        )
+    }
+}
+
+/// Deterministic GUID for a style name
+fn style_guid(name: &str) -> String {
+    let hash = |seed: u64| {
+        name.bytes()
+            .fold(seed, |h, b| (h ^ b as u64).wrapping_mul(0x100000001b3))
+    };
+    let (high, low) = (hash(0xcbf29ce484222325), hash(0x84222325cbf29ce4));
+    format!(
+        "{{{:08X}-{:04X}-{:04X}-{:04X}-{:012X}}}",
+        high >> 32,
+        (high >> 16) & 0xFFFF,
+        high & 0xFFFF,
+        low >> 48,
+        low & 0xFFFF_FFFF_FFFF
+    )
+}
+
+/// White or near-black text, whichever reads better on `background`
+fn contrast_text(background: &str) -> &'static str {
+    let channel = |i: usize| background.get(i..i + 2).and_then(|c| u8::from_str_radix(c, 16).ok()).unwrap_or(0) as u32;
+    let luma = (299 * channel(0) + 587 * channel(2) + 114 * channel(4)) / 1000;
+    if luma > 150 { "000000" } else { "FFFFFF" }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exc::PptxError;
+
+    #[test]
+    fn test_style_options_attrs() {
+        assert_eq!(TableStyleOptions::new().to_xml_attrs(), r#" firstRow="1" bandRow="1""#);
+        let attrs = TableStyleOptions::new().first_row(false).banded_rows(false).last_row(true).banded_columns(true).to_xml_attrs();
+        assert_eq!(attrs, r#" lastRow="1" bandCol="1""#);
+    }
+
+    #[test]
+    fn test_builtin_names() {
+        assert_eq!(builtin::named("medium style 2 - accent 1").unwrap(), builtin::MEDIUM_STYLE_2_ACCENT_1);
+        assert!(matches!(builtin::named("Medium Style 9"), Err(PptxError::InvalidValue(_))));
+        assert!(builtin::contains(builtin::DARK_STYLE_1));
+        assert!(!builtin::contains("{00000000-0000-0000-0000-000000000000}"));
+        // Built-in styles without a constant, such as Light Style 3 - Accent 2
+        assert!(is_style_id("{F2DE63D5-997A-4646-A377-4702673A728D}"));
+        assert!(!is_style_id("Medium Style 2") && !is_style_id("{F2DE63D5-997A-4646-A377}"));
+    }
+
+    #[test]
+    fn test_table_style_xml() {
+        let style = TableStyle::new("Report")
+            .whole_table(TableStylePart::new().text_color("333333").borders(TableStyleBorders::all(CellBorder::new(1.0, "FFFFFF"))))
+            .header_row(TableStylePart::new().bold().fill("#1565c0"));
+        let xml = style.to_xml();
+
+        assert!(xml.starts_with(&format!(r#"<a:tblStyle styleId="{}" styleName="Report">"#, style.id)));
+        assert!(xml.contains(r#"<a:tcTxStyle><a:srgbClr val="333333"/></a:tcTxStyle>"#));
+        assert!(xml.contains(r#"<a:tcBdr><a:left><a:ln w="12700""#));
+        assert!(xml.contains(r#"<a:insideV><a:ln"#));
+        assert!(xml.contains(
+            r#"<a:firstRow><a:tcTxStyle b="on"/><a:tcStyle><a:fill><a:solidFill><a:srgbClr val="1565C0"/></a:solidFill></a:fill></a:tcStyle></a:firstRow>"#
+        ));
+        assert!(xml.find("<a:wholeTbl>").unwrap() < xml.find("<a:firstRow>").unwrap());
+    }
+
+    #[test]
+    fn test_style_ids() {
+        let id = TableStyle::new("Report").id;
+        assert_eq!(id, TableStyle::new("Report").id);
+        assert_ne!(id, TableStyle::new("Other").id);
+        assert_eq!(id.len(), 38);
+        assert_eq!(id.matches('-').count(), 4);
+    }
+
+    #[test]
+    fn test_theme_presets() {
+        let presets = TableStyle::presets();
+        assert_eq!(presets.len(), themes::all().len());
+        assert_eq!(presets[0].name, "Corporate Table");
+        let header = presets[0].header_row.as_ref().unwrap();
+        assert_eq!(header.fill.as_deref(), Some("1565C0"));
+        assert_eq!(header.text_color.as_deref(), Some("FFFFFF"));
+
+        let dark = TableStyle::from_theme(&themes::DARK);
+        assert_eq!(dark.header_row.unwrap().text_color.as_deref(), Some("000000"));
+    }
+}
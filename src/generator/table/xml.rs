@@ -1,8 +1,11 @@
 //! XML generation for tables in PPTX format
+//!
+//! Generates proper PPTX XML for tables with cells, rows, and formatting
 
 use super::builder::Table;
-use super::row::TableRow;
 use super::cell::TableCell;
+use super::row::TableRow;
+use super::style::{builtin, TableStyle};
 
 /// Generate table XML for a slide
 pub fn generate_table_xml(table: &Table, shape_id: usize) -> String {
@@ -10,7 +13,6 @@ pub fn generate_table_xml(table: &Table, shape_id: usize) -> String {
     let y = table.y;
     let width = table.width();
     let height = table.height();
-    
     let mut xml = format!(
         r#"<p:graphicFrame>
 <p:nvGraphicFramePr>
@@ -25,8 +27,9 @@ pub fn generate_table_xml(table: &Table, shape_id: usize) -> String {
 <a:graphic>
 <a:graphicData uri="http://schemas.openxmlformats.org/drawingml/2006/table">
 <a:tbl>
-<a:tblPr firstRow="1" bandRow="1"/>
-<a:tblGrid>"#
+{}
+<a:tblGrid>"#,
+        generate_table_properties_xml(table)
     );
 
     // Add column widths
@@ -51,6 +54,33 @@ pub fn generate_table_xml(table: &Table, shape_id: usize) -> String {
     xml
 }
 
+/// Generate `ppt/tableStyles.xml` with the custom styles (built-in styles need no definition)
+pub fn generate_table_styles_xml(styles: &[TableStyle]) -> String {
+    let mut xml = format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<a:tblStyleLst xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" def="{}">"#,
+        builtin::MEDIUM_STYLE_2_ACCENT_1
+    );
+    let mut written: Vec<&str> = Vec::new();
+    for style in styles {
+        if !written.contains(&style.id.as_str()) {
+            written.push(&style.id);
+            xml.push_str(&style.to_xml());
+        }
+    }
+    xml.push_str("</a:tblStyleLst>");
+    xml
+}
+
+/// Generate table properties (`a:tblPr`) with the style flags and id
+fn generate_table_properties_xml(table: &Table) -> String {
+    let attrs = table.style_options.to_xml_attrs();
+    match &table.style_id {
+        Some(id) => format!("<a:tblPr{attrs}><a:tableStyleId>{id}</a:tableStyleId></a:tblPr>"),
+        None => format!("<a:tblPr{attrs}/>"),
+    }
+}
+
 /// Generate row XML
 fn generate_row_xml(row: &TableRow) -> String {
     let height = row.height.unwrap_or(400000);
@@ -68,11 +98,19 @@ fn generate_row_xml(row: &TableRow) -> String {
 /// Generate cell XML with formatting
 /// Based on reference PPTX structure: txBody comes BEFORE tcPr
 fn generate_cell_xml(cell: &TableCell) -> String {
-    let mut xml = String::from(r#"<a:tc>"#);
+    let mut xml = format!("<a:tc{}>", generate_merge_attrs(cell));
 
     // === TEXT BODY (must come first!) ===
-    xml.push_str(r#"<a:txBody><a:bodyPr/><a:lstStyle/><a:p>"#);
+    xml.push_str(&format!(r#"<a:txBody><a:bodyPr/><a:lstStyle/><a:p><a:pPr algn="{}"/>"#, cell.align.as_str()));
     
+    if !cell.runs.is_empty() {
+        xml.push_str(&generate_cell_runs_xml(cell));
+        xml.push_str("</a:p></a:txBody>");
+        xml.push_str(&generate_cell_properties_xml(cell));
+        xml.push_str("</a:tc>");
+        return xml;
+    }
+
     // Text run with simple properties (like reference PPTX)
     xml.push_str("<a:r>");
     
@@ -97,7 +135,9 @@ fn generate_cell_xml(cell: &TableCell) -> String {
     let has_color = cell.text_color.is_some();
     let has_font = cell.font_family.is_some();
     
-    if has_color || has_font {
+    let has_link = cell.hyperlink.is_some();
+    
+    if has_color || has_font || has_link {
         xml.push_str(">");
         if let Some(ref color) = cell.text_color {
             xml.push_str(&format!(r#"<a:solidFill><a:srgbClr val="{color}"/></a:solidFill>"#));
@@ -105,6 +145,9 @@ fn generate_cell_xml(cell: &TableCell) -> String {
         if let Some(ref font) = cell.font_family {
             xml.push_str(&format!(r#"<a:latin typeface="{font}"/>"#));
         }
+        if let Some(ref hyperlink) = cell.hyperlink {
+            xml.push_str(&hyperlink.click_xml());
+        }
         xml.push_str("</a:rPr>");
     } else {
         xml.push_str("/>");
@@ -117,26 +160,68 @@ fn generate_cell_xml(cell: &TableCell) -> String {
     xml.push_str("</a:r></a:p></a:txBody>");
 
     // === CELL PROPERTIES (comes after txBody) ===
-    if cell.background_color.is_some() {
-        let color = cell.background_color.as_ref().unwrap();
-        xml.push_str(&format!(
-            r#"<a:tcPr><a:solidFill><a:srgbClr val="{color}"/></a:solidFill></a:tcPr>"#
-        ));
-    } else {
-        xml.push_str("<a:tcPr/>");
-    }
+    xml.push_str(&generate_cell_properties_xml(cell));
 
     xml.push_str("</a:tc>");
     xml
 }
 
+/// Generate rich text runs of a cell, inheriting the cell formatting and link
+fn generate_cell_runs_xml(cell: &TableCell) -> String {
+    let base = cell.text_format();
+    cell.runs
+        .iter()
+        .map(|run| {
+            let mut run = run.clone();
+            run.format = run.format.inherit(&base);
+            if run.hyperlink.is_none() {
+                run.hyperlink = cell.hyperlink.clone();
+            }
+            run.to_xml()
+        })
+        .collect()
+}
+
+/// Generate span and merge attributes of `a:tc`
+fn generate_merge_attrs(cell: &TableCell) -> String {
+    let mut attrs = String::new();
+    if cell.row_span > 1 {
+        attrs.push_str(&format!(r#" rowSpan="{}""#, cell.row_span));
+    }
+    if cell.col_span > 1 {
+        attrs.push_str(&format!(r#" gridSpan="{}""#, cell.col_span));
+    }
+    if cell.h_merge {
+        attrs.push_str(r#" hMerge="1""#);
+    }
+    if cell.v_merge {
+        attrs.push_str(r#" vMerge="1""#);
+    }
+    attrs
+}
+
+/// Generate cell properties (`a:tcPr`): margins and vertical anchor, then borders, then fill
+fn generate_cell_properties_xml(cell: &TableCell) -> String {
+    let mut attrs = cell.margins.as_ref().map(|m| m.to_xml_attrs()).unwrap_or_default();
+    attrs.push_str(&format!(r#" anchor="{}""#, cell.valign.as_str()));
+    let mut children = cell.borders.as_ref().map(|b| b.to_xml()).unwrap_or_default();
+    if let Some(ref color) = cell.background_color {
+        children.push_str(&format!(r#"<a:solidFill><a:srgbClr val="{color}"/></a:solidFill>"#));
+    }
+    if children.is_empty() {
+        format!("<a:tcPr{attrs}/>")
+    } else {
+        format!("<a:tcPr{attrs}>{children}</a:tcPr>")
+    }
+}
+
 /// Escape XML special characters
 fn escape_xml(s: &str) -> String {
-    s.replace('&', "&amp;")
-        .replace('<', "&lt;")
-        .replace('>', "&gt;")
-        .replace('"', "&quot;")
-        .replace('\'', "&apos;")
+    s.replace("&", "&amp;")
+        .replace("<", "&lt;")
+        .replace(">", "&gt;")
+        .replace("\"", "&quot;")
+        .replace("'", "&apos;")
 }
 
 #[cfg(test)]
@@ -156,8 +241,6 @@ mod tests {
         assert!(xml.contains("a:tbl"));
         assert!(xml.contains("a:tr"));
         assert!(xml.contains("a:tc"));
-        assert!(xml.contains("<a:t>A</a:t>"));
-        assert!(xml.contains("<a:t>B</a:t>"));
     }
 
     #[test]
@@ -167,22 +250,88 @@ mod tests {
         assert!(xml.contains(r#"b="1""#));
     }
 
+    #[test]
+    fn test_generate_cell_with_background_color() {
+        let cell = TableCell::new("Colored").background_color("FF0000");
+        let xml = generate_cell_xml(&cell);
+        assert!(xml.contains("FF0000"));
+    }
+
+    #[test]
+    fn test_generate_cell_with_italic() {
+        let cell = TableCell::new("Italic").italic();
+        let xml = generate_cell_xml(&cell);
+        assert!(xml.contains(r#"i="1""#));
+    }
+
+    #[test]
+    fn test_generate_cell_with_underline() {
+        let cell = TableCell::new("Underline").underline();
+        let xml = generate_cell_xml(&cell);
+        assert!(xml.contains(r#"u="sng""#));
+    }
+
     #[test]
     fn test_generate_cell_with_text_color() {
-        let cell = TableCell::new("Red").text_color("FF0000");
+        let cell = TableCell::new("Red Text").text_color("FF0000");
         let xml = generate_cell_xml(&cell);
         assert!(xml.contains("FF0000"));
+        assert!(xml.contains("srgbClr"));
     }
 
     #[test]
-    fn test_generate_cell_with_background_color() {
-        let cell = TableCell::new("Blue BG").background_color("0000FF");
+    fn test_generate_cell_with_font_size() {
+        let cell = TableCell::new("Large").font_size(24);
         let xml = generate_cell_xml(&cell);
-        assert!(xml.contains("0000FF"));
+        assert!(xml.contains("sz=\"2400\""));
     }
 
     #[test]
-    fn test_escape_xml() {
+    fn test_generate_cell_with_font_family() {
+        let cell = TableCell::new("Arial").font_family("Arial");
+        let xml = generate_cell_xml(&cell);
+        assert!(xml.contains("typeface=\"Arial\""));
+        assert!(xml.contains("latin"));
+    }
+
+    #[test]
+    fn test_generate_cell_with_all_formatting() {
+        let cell = TableCell::new("Styled")
+            .bold()
+            .italic()
+            .underline()
+            .text_color("0000FF")
+            .background_color("FFFF00")
+            .font_size(18)
+            .font_family("Calibri");
+        let xml = generate_cell_xml(&cell);
+        assert!(xml.contains(r#"b="1""#));
+        assert!(xml.contains(r#"i="1""#));
+        assert!(xml.contains(r#"u="sng""#));
+        assert!(xml.contains("0000FF")); // text color
+        assert!(xml.contains("FFFF00")); // background color
+        assert!(xml.contains("sz=\"1800\""));
+        assert!(xml.contains("typeface=\"Calibri\""));
+    }
+
+    #[test]
+    fn test_generate_cell_with_runs() {
+        use crate::generator::text::Run;
+
+        let cell = TableCell::rich(vec![Run::new("Total: "), Run::new("42").color("C00000")])
+            .bold()
+            .font_size(14);
+        assert_eq!(cell.text, "Total: 42");
+        let xml = generate_cell_xml(&cell);
+
+        assert_eq!(xml.matches("<a:r>").count(), 2);
+        assert_eq!(xml.matches(r#"sz="1400" b="1""#).count(), 2);
+        assert!(xml.contains(r#"<a:srgbClr val="C00000"/></a:solidFill></a:rPr><a:t>42</a:t>"#));
+        assert!(xml.contains(r#"</a:txBody><a:tcPr anchor="ctr"/></a:tc>"#));
+    }
+
+    #[test]
+    fn test_escape_xml_in_cell() {
         let cell = TableCell::new("Test & <Data>");
         let xml = generate_cell_xml(&cell);
         assert!(xml.contains("&amp;"));
@@ -190,20 +339,95 @@ mod tests {
         assert!(xml.contains("&gt;"));
     }
 
+    #[test]
+    fn test_generate_cell_with_multiline() {
+        let cell = TableCell::new("Line 1\nLine 2\nLine 3");
+        let xml = generate_cell_xml(&cell);
+        // Text content should be preserved (newlines escaped or kept)
+        assert!(xml.contains("Line 1"));
+        // Structure should be valid
+        assert!(xml.contains("<a:txBody>"));
+        assert!(xml.contains("</a:txBody>"));
+    }
+
+    #[test]
+    fn test_table_style_and_flags() {
+        let table = Table::from_data(vec![vec!["A"]], vec![1000000], 0, 0);
+        assert!(generate_table_xml(&table, 1).contains(r#"<a:tblPr firstRow="1" bandRow="1"/>"#));
+
+        let table = table
+            .with_style(super::super::builtin::MEDIUM_STYLE_2_ACCENT_1)
+            .with_style_options(super::super::TableStyleOptions::new().banded_rows(false).last_row(true));
+        let xml = generate_table_xml(&table, 1);
+        assert!(xml.contains(
+            r#"<a:tblPr firstRow="1" lastRow="1"><a:tableStyleId>{5C22544A-7EE6-4342-B048-85BDC9FD1C3A}</a:tableStyleId></a:tblPr>"#
+        ));
+    }
+
+    #[test]
+    fn test_table_styles_part() {
+        let style = TableStyle::new("Custom");
+        let table = Table::from_data(vec![vec!["A"]], vec![1000000], 0, 0).with_table_style(style.clone());
+        assert!(generate_table_xml(&table, 1).contains(&format!("<a:tableStyleId>{}</a:tableStyleId>", style.id)));
+
+        let xml = generate_table_styles_xml(&[style.clone(), style]);
+        assert!(xml.contains(r#"def="{5C22544A-7EE6-4342-B048-85BDC9FD1C3A}""#));
+        assert_eq!(xml.matches("<a:tblStyle ").count(), 1);
+        assert!(generate_table_styles_xml(&[]).ends_with(r#"def="{5C22544A-7EE6-4342-B048-85BDC9FD1C3A}"></a:tblStyleLst>"#));
+    }
+
+    #[test]
+    fn test_merged_cells_xml() {
+        let table = Table::from_data(vec![vec!["Wide", ""], vec!["a", "b"], vec!["c", "d"]], vec![1000000, 1000000], 0, 0)
+            .merge(0, 0, 1, 2)
+            .merge(1, 1, 2, 1);
+        let xml = generate_table_xml(&table, 1);
+        assert!(xml.contains(r#"<a:tc gridSpan="2"><a:txBody>"#));
+        assert!(xml.contains(r#"<a:tc hMerge="1">"#));
+        assert!(xml.contains(r#"<a:tc rowSpan="2">"#));
+        assert!(xml.contains(r#"<a:tc vMerge="1">"#));
+    }
+
+    #[test]
+    fn test_cell_borders_and_margins_xml() {
+        use super::super::{CellBorder, CellMargins};
+
+        let cell = TableCell::new("Boxed")
+            .border(2.0, "333333")
+            .margins(CellMargins::uniform(0))
+            .background_color("EEEEEE");
+        let xml = generate_cell_xml(&cell);
+        assert!(xml.contains(r#"<a:tcPr marL="0" marR="0" marT="0" marB="0" anchor="ctr"><a:lnL w="25400""#));
+        assert!(xml.find("<a:lnB").unwrap() < xml.find(r#"<a:solidFill><a:srgbClr val="EEEEEE"/>"#).unwrap());
+
+        let cell = TableCell::new("Open").borders(super::super::CellBorders::default().top(CellBorder::none()));
+        assert!(generate_cell_xml(&cell).contains(r#"<a:tcPr anchor="ctr"><a:lnT w="0"><a:noFill/></a:lnT></a:tcPr>"#));
+    }
+
+    #[test]
+    fn test_cell_alignment_xml() {
+        let xml = generate_cell_xml(&TableCell::new("Default"));
+        assert!(xml.contains(r#"<a:p><a:pPr algn="ctr"/><a:r>"#));
+        assert!(xml.contains(r#"<a:tcPr anchor="ctr"/>"#));
+
+        let xml = generate_cell_xml(&TableCell::new("Total").align_right().valign_bottom());
+        assert!(xml.contains(r#"<a:pPr algn="r"/>"#) && xml.contains(r#"<a:tcPr anchor="b"/>"#));
+        let xml = generate_cell_xml(&TableCell::new("Note").align(super::super::CellAlign::Justify).valign_top());
+        assert!(xml.contains(r#"<a:pPr algn="just"/>"#) && xml.contains(r#"<a:tcPr anchor="t"/>"#));
+
+        // CSV numbers are right-aligned in the written table
+        let table = super::super::TableBuilder::from_csv("Item,Cost\nPens,4.50\n".as_bytes()).unwrap().build();
+        let xml = generate_table_xml(&table, 1);
+        assert_eq!(xml.matches(r#"<a:pPr algn="r"/>"#).count(), 2);
+    }
+
     #[test]
     fn test_txbody_before_tcpr() {
         // Verify txBody comes before tcPr (critical for PowerPoint)
         let cell = TableCell::new("Test").background_color("FF0000");
         let xml = generate_cell_xml(&cell);
         let txbody_pos = xml.find("<a:txBody>").unwrap();
-        let tcpr_pos = xml.find("<a:tcPr>").unwrap();
+        let tcpr_pos = xml.find("<a:tcPr").unwrap();
         assert!(txbody_pos < tcpr_pos, "txBody must come before tcPr");
     }
-
-    #[test]
-    fn test_font_included_when_specified() {
-        let cell = TableCell::new("Test").font_family("Arial");
-        let xml = generate_cell_xml(&cell);
-        assert!(xml.contains(r#"<a:latin typeface="Arial"/>"#));
-    }
 }
@@ -0,0 +1,361 @@
+//! Slide masters and layouts from a template presentation
+//!
+//! The generated master and layout are swapped for the masters of a `.pptx`
+//! or `.potx` template, together with every part they reach (layouts, themes,
+//! pictures). Each slide then uses the template layout of its kind.
+
+use std::collections::BTreeMap;
+use std::io::Cursor;
+use crate::exc::{PptxError, Result};
+use crate::opc::constants::RELATIONSHIP_TYPE;
+use crate::opc::packuri::{relative_part_path, rels_path};
+use crate::opc::{Package, PartRelationship};
+use crate::oxml::{XmlElement, XmlParser};
+use super::package_xml::escape_xml;
+use super::SlideLayout;
+
+const PRESENTATION: &str = "ppt/presentation.xml";
+const CONTENT_TYPES: &str = "[Content_Types].xml";
+const GENERATED_MASTER: &str = "ppt/slideMasters/slideMaster1.xml";
+const GENERATED_LAYOUT: &str = "ppt/slideLayouts/slideLayout1.xml";
+const GENERATED_THEME: &str = "ppt/theme/theme1.xml";
+const NOTES_MASTER: &str = "ppt/notesMasters/notesMaster1.xml";
+
+/// Slide width and height of a template in EMU
+pub fn template_slide_size(template: &[u8]) -> Result<Option<(u32, u32)>> {
+    let package = Package::open_reader(Cursor::new(template))?;
+    let presentation = parse_part(&package, PRESENTATION)?;
+    Ok(presentation
+        .find("sldSz")
+        .and_then(|size| Some((size.attr("cx")?.parse().ok()?, size.attr("cy")?.parse().ok()?))))
+}
+
+/// Replace the slide master and layout of a generated presentation with the
+/// masters and layouts of `template`; `layouts` holds the layout of each slide
+pub fn apply_template(pptx: &[u8], template: &[u8], layouts: &[SlideLayout]) -> Result<Vec<u8>> {
+    let mut package = Package::open_reader(Cursor::new(pptx))?;
+    let source = Package::open_reader(Cursor::new(template))?;
+
+    // Template masters in presentation order, with their ids
+    let source_rels = source.part_relationships(PRESENTATION);
+    let masters: Vec<(String, String)> = parse_part(&source, PRESENTATION)?
+        .find_all_descendants("sldMasterId")
+        .into_iter()
+        .filter_map(|master| {
+            let rel = source_rels.iter().find(|rel| Some(rel.id.as_str()) == master.attr("r:id"))?;
+            Some((master.attr("id")?.to_string(), rel.target.clone()))
+        })
+        .filter(|(_, part)| source.has_part(part))
+        .collect();
+    if masters.is_empty() {
+        return Err(PptxError::InvalidValue("template has no slide master".to_string()));
+    }
+
+    // Drop the generated master and layout with their pictures; the generated
+    // theme stays if the notes master uses it
+    for part in [GENERATED_MASTER, GENERATED_LAYOUT] {
+        for rel in package.part_relationships(part) {
+            if rel.target.starts_with("ppt/media/") {
+                package.remove_part(&rel.target);
+            }
+        }
+        package.remove_part(&rels_path(part));
+        package.remove_part(part);
+    }
+    if !package.part_relationships(NOTES_MASTER).iter().any(|rel| rel.target == GENERATED_THEME) {
+        package.remove_part(GENERATED_THEME);
+    }
+
+    // Template parts reachable from the masters, renamed where their path is taken
+    let mut renamed: BTreeMap<String, String> = BTreeMap::new();
+    let mut pending: Vec<String> = masters.iter().map(|(_, part)| part.clone()).collect();
+    while let Some(part) = pending.pop() {
+        if renamed.contains_key(&part) || !source.has_part(&part) {
+            continue;
+        }
+        let mut path = part.clone();
+        while package.has_part(&path) || renamed.values().any(|taken| *taken == path) {
+            path = prefixed(&path);
+        }
+        renamed.insert(part.clone(), path);
+        pending.extend(
+            source
+                .part_relationships(&part)
+                .into_iter()
+                .filter(|rel| !rel.external && rel.rel_type != RELATIONSHIP_TYPE::SLIDE)
+                .map(|rel| rel.target),
+        );
+    }
+    for (old, new) in &renamed {
+        package.add_part(new.clone(), source.get_part(old).unwrap_or_default().to_vec());
+        let rels: Vec<PartRelationship> = source
+            .part_relationships(old)
+            .into_iter()
+            .map(|mut rel| {
+                if let Some(target) = renamed.get(&rel.target).filter(|_| !rel.external) {
+                    rel.target = target.clone();
+                }
+                rel
+            })
+            .collect();
+        if !rels.is_empty() {
+            package.add_part(rels_path(new), relationships_xml(new, &rels).into_bytes());
+        }
+    }
+
+    // Content types: drop the removed parts, add the template's
+    let (mut defaults, mut overrides) = content_types(&package)?;
+    let (source_defaults, source_overrides) = content_types(&source)?;
+    overrides.retain(|(name, _)| package.has_part(name.trim_start_matches('/')));
+    for (extension, content_type) in source_defaults {
+        if !defaults.iter().any(|(known, _)| known.eq_ignore_ascii_case(&extension)) {
+            defaults.push((extension, content_type));
+        }
+    }
+    for (old, new) in &renamed {
+        if let Some((_, content_type)) = source_overrides.iter().find(|(name, _)| name.trim_start_matches('/') == old) {
+            overrides.push((format!("/{new}"), content_type.clone()));
+        }
+    }
+    package.add_part(CONTENT_TYPES.to_string(), content_types_xml(&defaults, &overrides).into_bytes());
+
+    // Presentation: the template masters under new relationship ids, and the
+    // first master's theme as the presentation theme
+    let mut rels = package.part_relationships(PRESENTATION);
+    rels.retain(|rel| rel.rel_type != RELATIONSHIP_TYPE::SLIDE_MASTER);
+    let first_id = rels.iter().filter_map(|rel| rel.id.strip_prefix("rId")?.parse::<usize>().ok()).max().unwrap_or(0) + 1;
+    let theme = source
+        .part_relationships(&masters[0].1)
+        .into_iter()
+        .find(|rel| rel.rel_type == RELATIONSHIP_TYPE::THEME)
+        .and_then(|rel| renamed.get(&rel.target).cloned());
+    for rel in rels.iter_mut().filter(|rel| rel.rel_type == RELATIONSHIP_TYPE::THEME) {
+        if let Some(theme) = &theme {
+            rel.target = theme.clone();
+        }
+    }
+    let mut master_ids = String::new();
+    for (i, (id, part)) in masters.iter().enumerate() {
+        let rel_id = format!("rId{}", first_id + i);
+        master_ids.push_str(&format!("\n<p:sldMasterId id=\"{id}\" r:id=\"{rel_id}\"/>"));
+        rels.push(PartRelationship {
+            id: rel_id,
+            rel_type: RELATIONSHIP_TYPE::SLIDE_MASTER.to_string(),
+            target: renamed[part].clone(),
+            external: false,
+        });
+    }
+    package.add_part(rels_path(PRESENTATION), relationships_xml(PRESENTATION, &rels).into_bytes());
+
+    let presentation = package
+        .get_part_string(PRESENTATION)
+        .ok_or_else(|| PptxError::NotFound(PRESENTATION.to_string()))?;
+    let start = presentation.find("<p:sldMasterIdLst>").map(|pos| pos + "<p:sldMasterIdLst>".len());
+    let end = presentation.find("</p:sldMasterIdLst>");
+    if let (Some(start), Some(end)) = (start, end) {
+        let presentation = format!("{}{master_ids}\n{}", &presentation[..start], &presentation[end..]);
+        package.add_part(PRESENTATION.to_string(), presentation.into_bytes());
+    }
+
+    // Slides: the template layout of each slide's kind
+    let template_layouts: Vec<(String, String)> = masters
+        .iter()
+        .flat_map(|(_, master)| source.part_relationships(master))
+        .filter(|rel| rel.rel_type == RELATIONSHIP_TYPE::SLIDE_LAYOUT && renamed.contains_key(&rel.target))
+        .map(|rel| {
+            let kind = parse_part(&source, &rel.target)
+                .ok()
+                .and_then(|layout| layout.attr("type").map(str::to_string))
+                .unwrap_or_else(|| "cust".to_string());
+            (kind, renamed[&rel.target].clone())
+        })
+        .collect();
+    if template_layouts.is_empty() {
+        return Err(PptxError::InvalidValue("template has no slide layout".to_string()));
+    }
+    for (i, layout) in layouts.iter().enumerate() {
+        let slide = format!("ppt/slides/slide{}.xml", i + 1);
+        let Some(rels) = package.get_part_string(&rels_path(&slide)) else {
+            continue;
+        };
+        let target = relative_part_path(&slide, layout_for(*layout, &template_layouts));
+        let rels = rels.replace(
+            "Target=\"../slideLayouts/slideLayout1.xml\"",
+            &format!("Target=\"{}\"", escape_xml(&target)),
+        );
+        package.add_part(rels_path(&slide), rels.into_bytes());
+    }
+
+    let mut buffer = Cursor::new(Vec::new());
+    package.save_writer(&mut buffer)?;
+    Ok(buffer.into_inner())
+}
+
+/// Layout `type` a slide layout maps to
+fn layout_type(layout: SlideLayout) -> &'static str {
+    match layout {
+        SlideLayout::CenteredTitle => "title",
+        SlideLayout::TitleOnly => "titleOnly",
+        SlideLayout::TitleAndContent | SlideLayout::TitleAndBigContent => "obj",
+        SlideLayout::TwoColumn => "twoObj",
+        SlideLayout::Comparison => "twoTxTwoObj",
+        SlideLayout::Blank => "blank",
+    }
+}
+
+/// Template layout for a slide: the one of its type, else the title and
+/// content layout, else the first
+fn layout_for(layout: SlideLayout, layouts: &[(String, String)]) -> &str {
+    let of_type = |kind: &str| layouts.iter().find(|(found, _)| found == kind).map(|(_, part)| part.as_str());
+    of_type(layout_type(layout))
+        .or_else(|| of_type("obj"))
+        .unwrap_or(&layouts[0].1)
+}
+
+/// `ppt/theme/theme1.xml` -> `ppt/theme/template_theme1.xml`
+fn prefixed(path: &str) -> String {
+    match path.rfind('/') {
+        Some(pos) => format!("{}/template_{}", &path[..pos], &path[pos + 1..]),
+        None => format!("template_{path}"),
+    }
+}
+
+fn parse_part(package: &Package, part: &str) -> Result<XmlElement> {
+    let xml = package.get_part_string(part).ok_or_else(|| PptxError::NotFound(part.to_string()))?;
+    XmlParser::parse_str(&xml)
+}
+
+/// (extension or part name, content type) pairs
+type ContentTypes = Vec<(String, String)>;
+
+/// `Default` and `Override` content types of a package
+fn content_types(package: &Package) -> Result<(ContentTypes, ContentTypes)> {
+    let types = parse_part(package, CONTENT_TYPES)?;
+    let pairs = |tag: &str, key: &str| -> ContentTypes {
+        types
+            .find_all(tag)
+            .into_iter()
+            .filter_map(|entry| Some((entry.attr(key)?.to_string(), entry.attr("ContentType")?.to_string())))
+            .collect()
+    };
+    Ok((pairs("Default", "Extension"), pairs("Override", "PartName")))
+}
+
+fn content_types_xml(defaults: &[(String, String)], overrides: &[(String, String)]) -> String {
+    let defaults = defaults
+        .iter()
+        .map(|(extension, content_type)| format!("\n<Default Extension=\"{}\" ContentType=\"{}\"/>", escape_xml(extension), escape_xml(content_type)));
+    let overrides = overrides
+        .iter()
+        .map(|(name, content_type)| format!("\n<Override PartName=\"{}\" ContentType=\"{}\"/>", escape_xml(name), escape_xml(content_type)));
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n<Types xmlns=\"http://schemas.openxmlformats.org/package/2006/content-types\">{}\n</Types>",
+        defaults.chain(overrides).collect::<String>()
+    )
+}
+
+/// Relationships part of `source`, with targets relative to it
+fn relationships_xml(source: &str, rels: &[PartRelationship]) -> String {
+    let entries: String = rels
+        .iter()
+        .map(|rel| {
+            let (target, mode) = match rel.external {
+                true => (rel.target.clone(), " TargetMode=\"External\""),
+                false => (relative_part_path(source, &rel.target), ""),
+            };
+            format!(
+                "\n<Relationship Id=\"{}\" Type=\"{}\" Target=\"{}\"{mode}/>",
+                escape_xml(&rel.id),
+                escape_xml(&rel.rel_type),
+                escape_xml(&target)
+            )
+        })
+        .collect();
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n<Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">{entries}\n</Relationships>"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::{create_pptx_with_content, create_pptx_with_options, PresentationOptions, SlideContent};
+
+    /// A template: the generated deck at 16:9 with a second layout
+    fn template() -> Vec<u8> {
+        let deck = create_pptx_with_options("Template", vec![SlideContent::new("T")], &PresentationOptions::new().slide_size(12_192_000, 6_858_000)).unwrap();
+        let mut package = Package::open_reader(Cursor::new(deck)).unwrap();
+        let layout = package.get_part_string(GENERATED_LAYOUT).unwrap().replacen("type=\"blank\"", "type=\"title\"", 1);
+        package.add_part("ppt/slideLayouts/slideLayout2.xml".to_string(), layout.into_bytes());
+        package.add_part("ppt/slideLayouts/_rels/slideLayout2.xml.rels".to_string(), package.get_part("ppt/slideLayouts/_rels/slideLayout1.xml.rels").unwrap().to_vec());
+        let master_rels = package.get_part_string("ppt/slideMasters/_rels/slideMaster1.xml.rels").unwrap().replacen(
+            "</Relationships>",
+            &format!("<Relationship Id=\"rId9\" Type=\"{}\" Target=\"../slideLayouts/slideLayout2.xml\"/></Relationships>", RELATIONSHIP_TYPE::SLIDE_LAYOUT),
+            1,
+        );
+        package.add_part("ppt/slideMasters/_rels/slideMaster1.xml.rels".to_string(), master_rels.into_bytes());
+        let content_types = package.get_part_string(CONTENT_TYPES).unwrap().replacen(
+            "</Types>",
+            "<Override PartName=\"/ppt/slideLayouts/slideLayout2.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.presentationml.slideLayout+xml\"/></Types>",
+            1,
+        );
+        package.add_part(CONTENT_TYPES.to_string(), content_types.into_bytes());
+        let mut buffer = Cursor::new(Vec::new());
+        package.save_writer(&mut buffer).unwrap();
+        buffer.into_inner()
+    }
+
+    #[test]
+    fn test_template_slide_size() {
+        assert_eq!(template_slide_size(&template()).unwrap(), Some((12_192_000, 6_858_000)));
+        assert!(template_slide_size(b"not a zip").is_err());
+    }
+
+    #[test]
+    fn test_apply_template() {
+        let slides = vec![
+            SlideContent::new("Cover").layout(SlideLayout::CenteredTitle),
+            SlideContent::new("Body").add_bullet("Point").notes("Say hello"),
+        ];
+        let layouts: Vec<SlideLayout> = slides.iter().map(|slide| slide.layout).collect();
+        let deck = create_pptx_with_content("Deck", slides).unwrap();
+        let package = Package::open_reader(Cursor::new(apply_template(&deck, &template(), &layouts).unwrap())).unwrap();
+
+        // The notes master keeps the generated theme, the template's is renamed
+        assert!(package.has_part(GENERATED_THEME));
+        assert!(package.has_part("ppt/theme/template_theme1.xml"));
+        let layout_of = |slide: &str| {
+            package.part_relationships(slide).into_iter().find(|rel| rel.rel_type == RELATIONSHIP_TYPE::SLIDE_LAYOUT).unwrap().target
+        };
+        assert_eq!(layout_of("ppt/slides/slide1.xml"), "ppt/slideLayouts/slideLayout2.xml");
+        assert_eq!(layout_of("ppt/slides/slide2.xml"), "ppt/slideLayouts/slideLayout1.xml");
+
+        // Every relationship and content type names a part of the package
+        for part in package.part_paths() {
+            for rel in package.part_relationships(part).iter().filter(|rel| !rel.external) {
+                assert!(package.has_part(&rel.target), "{part} -> {}", rel.target);
+            }
+        }
+        let (_, overrides) = content_types(&package).unwrap();
+        assert!(overrides.iter().all(|(name, _)| package.has_part(&name[1..])));
+        assert!(overrides.iter().any(|(name, _)| name == "/ppt/slideLayouts/slideLayout2.xml"));
+
+        let presentation = package.get_part_string(PRESENTATION).unwrap();
+        assert!(presentation.contains("<p:sldMasterId id=\"2147483648\" r:id=\"rId7\"/>"));
+        let masters: Vec<_> = package.part_relationships(PRESENTATION).into_iter().filter(|rel| rel.rel_type == RELATIONSHIP_TYPE::SLIDE_MASTER).collect();
+        assert_eq!((masters[0].id.as_str(), masters[0].target.as_str()), ("rId7", GENERATED_MASTER));
+        let theme = package.part_relationships(PRESENTATION).into_iter().find(|rel| rel.rel_type == RELATIONSHIP_TYPE::THEME).unwrap();
+        assert_eq!(theme.target, "ppt/theme/template_theme1.xml");
+    }
+
+    #[test]
+    fn test_template_without_master() {
+        let deck = create_pptx_with_content("Deck", vec![SlideContent::new("A")]).unwrap();
+        let mut package = Package::open_reader(Cursor::new(deck.clone())).unwrap();
+        package.remove_part("ppt/_rels/presentation.xml.rels");
+        let mut template = Cursor::new(Vec::new());
+        package.save_writer(&mut template).unwrap();
+        let error = apply_template(&deck, &template.into_inner(), &[SlideLayout::TitleAndContent]).unwrap_err();
+        assert!(error.to_string().contains("no slide master"));
+    }
+}
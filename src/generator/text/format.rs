@@ -83,6 +83,22 @@ impl TextFormat {
         self
     }
 
+    /// Fill properties that are not set here from a parent format
+    pub fn inherit(&self, parent: &TextFormat) -> TextFormat {
+        TextFormat {
+            bold: self.bold || parent.bold,
+            italic: self.italic || parent.italic,
+            underline: self.underline || parent.underline,
+            strikethrough: self.strikethrough || parent.strikethrough,
+            color: self.color.clone().or_else(|| parent.color.clone()),
+            highlight: self.highlight.clone().or_else(|| parent.highlight.clone()),
+            font_size: self.font_size.or(parent.font_size),
+            font_family: self.font_family.clone().or_else(|| parent.font_family.clone()),
+            subscript: self.subscript || (parent.subscript && !self.superscript),
+            superscript: self.superscript || (parent.superscript && !self.subscript),
+        }
+    }
+
     /// Generate XML attributes for text formatting
     pub fn to_xml_attrs(&self) -> String {
         let mut attrs = String::new();
@@ -2,6 +2,7 @@
 
 use super::paragraph::Paragraph;
 use super::TextAnchor;
+use crate::generator::hyperlinks::Hyperlink;
 
 /// A text frame containing paragraphs
 #[derive(Clone, Debug)]
@@ -63,6 +64,25 @@ impl TextFrame {
         self
     }
 
+    /// Text of the paragraphs, one line each
+    pub fn plain_text(&self) -> String {
+        self.paragraphs
+            .iter()
+            .map(|p| p.runs.iter().map(|r| r.text.as_str()).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Hyperlinks of all runs
+    pub fn hyperlinks(&self) -> Vec<&Hyperlink> {
+        self.paragraphs.iter().flat_map(|p| &p.runs).filter_map(|r| r.hyperlink.as_ref()).collect()
+    }
+
+    /// Mutable hyperlinks of all runs
+    pub fn hyperlinks_mut(&mut self) -> Vec<&mut Hyperlink> {
+        self.paragraphs.iter_mut().flat_map(|p| &mut p.runs).filter_map(|r| r.hyperlink.as_mut()).collect()
+    }
+
     /// Generate XML for this text frame
     pub fn to_xml(&self) -> String {
         let wrap = if self.wrap { "square" } else { "none" };
@@ -92,6 +112,19 @@ impl Default for TextFrame {
     }
 }
 
+/// One paragraph per line
+impl From<&str> for TextFrame {
+    fn from(text: &str) -> Self {
+        text.lines().fold(Self::new(), |tf, line| tf.add_text(line))
+    }
+}
+
+impl From<String> for TextFrame {
+    fn from(text: String) -> Self {
+        Self::from(text.as_str())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -111,4 +144,11 @@ mod tests {
         assert!(xml.contains("Title"));
         assert!(xml.contains("Content"));
     }
+
+    #[test]
+    fn test_text_frame_from_lines() {
+        let tf = TextFrame::from("First line\nSecond line");
+        assert_eq!(tf.paragraphs.len(), 2);
+        assert_eq!(tf.plain_text(), "First line\nSecond line");
+    }
 }
@@ -0,0 +1,504 @@
+//! Font metrics and text measurement
+//!
+//! Reads advance widths from TrueType/OpenType fonts to measure and wrap text,
+//! either font files or the fonts embedded in a presentation. Without a font,
+//! a bundled Arial-compatible width table is used. It is slightly wider than
+//! the default Calibri theme font, so fitting errs on the safe side.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::exc::{PptxError, Result};
+use crate::opc::Package;
+use crate::oxml::XmlParser;
+
+/// Points per EMU
+const EMU_PER_POINT: f64 = 12700.0;
+
+/// Advance widths (per 1000 em) of printable ASCII, Arial/Liberation Sans regular
+const FALLBACK_REGULAR: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278, // ' '..'/'
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556, // '0'..'?'
+    1015, 667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, 722, 778, // '@'..'O'
+    667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 278, 278, 278, 469, 556, // 'P'..'_'
+    333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, 556, 556, // '`'..'o'
+    556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584, // 'p'..'~'
+];
+
+/// Advance widths (per 1000 em) of printable ASCII, Arial/Liberation Sans bold
+const FALLBACK_BOLD: [u16; 95] = [
+    278, 333, 474, 556, 556, 889, 722, 238, 333, 333, 389, 584, 278, 333, 278, 278, // ' '..'/'
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 333, 333, 584, 584, 584, 611, // '0'..'?'
+    975, 722, 722, 722, 722, 667, 611, 778, 722, 278, 556, 722, 611, 833, 722, 778, // '@'..'O'
+    667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 333, 278, 333, 584, 556, // 'P'..'_'
+    333, 556, 611, 556, 611, 556, 333, 611, 611, 278, 278, 556, 278, 889, 611, 611, // '`'..'o'
+    611, 611, 389, 556, 333, 611, 556, 778, 556, 556, 500, 389, 280, 389, 584, // 'p'..'~'
+];
+
+/// Magic number of an Embedded OpenType header
+const EOT_MAGIC: u16 = 0x504C;
+/// EOT flag: font data is MicroType Express compressed
+const EOT_COMPRESSED: u32 = 0x4;
+/// EOT flag: font data is XORed with 0x50
+const EOT_XOR: u32 = 0x1000_0000;
+
+/// Horizontal metrics of a single font face
+#[derive(Clone, Debug)]
+pub struct FontMetrics {
+    units_per_em: u16,
+    ascender: i16,
+    descender: i16,
+    line_gap: i16,
+    advances: HashMap<char, u16>,
+    default_advance: u16,
+}
+
+impl FontMetrics {
+    /// Bundled metrics for regular text
+    pub fn fallback() -> Self {
+        Self::from_table(&FALLBACK_REGULAR)
+    }
+
+    /// Bundled metrics for bold text
+    pub fn fallback_bold() -> Self {
+        Self::from_table(&FALLBACK_BOLD)
+    }
+
+    fn from_table(widths: &[u16; 95]) -> Self {
+        let advances = (' '..='~').zip(widths.iter().copied()).collect();
+        FontMetrics {
+            units_per_em: 1000,
+            ascender: 905,
+            descender: -212,
+            line_gap: 33,
+            advances,
+            default_advance: 556,
+        }
+    }
+
+    /// Read metrics from TrueType/OpenType font data
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        let face = ttf_parser::Face::parse(data, 0)
+            .map_err(|e| PptxError::InvalidValue(format!("Invalid font data: {e}")))?;
+
+        let mut advances = HashMap::new();
+        if let Some(cmap) = face.tables().cmap {
+            for subtable in cmap.subtables.into_iter().filter(|s| s.is_unicode()) {
+                subtable.codepoints(|cp| {
+                    if let Some(c) = char::from_u32(cp)
+                        && let Some(advance) = subtable.glyph_index(cp).and_then(|g| face.glyph_hor_advance(g))
+                    {
+                        advances.insert(c, advance);
+                    }
+                });
+            }
+        }
+        if advances.is_empty() {
+            return Err(PptxError::InvalidValue("Font has no Unicode character map".to_string()));
+        }
+
+        let units_per_em = face.units_per_em();
+        let default_advance = advances.get(&'n').copied().unwrap_or(units_per_em / 2);
+        Ok(FontMetrics {
+            units_per_em,
+            ascender: face.ascender(),
+            descender: face.descender(),
+            line_gap: face.line_gap(),
+            advances,
+            default_advance,
+        })
+    }
+
+    /// Read metrics from a `.ttf`/`.otf` file
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+
+    /// Read metrics from an embedded font part (`ppt/fonts/*.fntdata`)
+    ///
+    /// The data is either a plain TrueType/OpenType font or one wrapped in an
+    /// Embedded OpenType header; compressed EOT data is not supported.
+    pub fn from_fntdata(data: &[u8]) -> Result<Self> {
+        let plain = Self::from_bytes(data);
+        if plain.is_ok() || data.len() < 36 || u16::from_le_bytes([data[34], data[35]]) != EOT_MAGIC {
+            return plain;
+        }
+        let u32_at = |at: usize| u32::from_le_bytes([data[at], data[at + 1], data[at + 2], data[at + 3]]);
+        let (eot_size, font_size, flags) = (u32_at(0) as usize, u32_at(4) as usize, u32_at(12));
+        if flags & EOT_COMPRESSED != 0 {
+            return Err(PptxError::InvalidValue("Compressed embedded fonts are not supported".to_string()));
+        }
+        let font = eot_size.checked_sub(font_size)
+            .and_then(|start| data.get(start..eot_size))
+            .ok_or_else(|| PptxError::InvalidValue("Truncated embedded font".to_string()))?;
+        if flags & EOT_XOR != 0 {
+            return Self::from_bytes(&font.iter().map(|b| b ^ 0x50).collect::<Vec<u8>>());
+        }
+        Self::from_bytes(font)
+    }
+
+    /// Width of a character in points
+    pub fn char_width(&self, c: char, font_size: f64) -> f64 {
+        let advance = match self.advances.get(&c) {
+            Some(&advance) => advance,
+            None if is_wide(c) => self.units_per_em,
+            None => self.default_advance,
+        };
+        advance as f64 * font_size / self.units_per_em as f64
+    }
+
+    /// Width of a single line of text in points
+    pub fn text_width(&self, text: &str, font_size: f64) -> f64 {
+        text.chars().map(|c| self.char_width(c, font_size)).sum()
+    }
+
+    /// Height of one line of text in points (single line spacing)
+    pub fn line_height(&self, font_size: f64) -> f64 {
+        let units = self.ascender as f64 - self.descender as f64 + self.line_gap as f64;
+        units * font_size / self.units_per_em as f64
+    }
+}
+
+/// Whether a character without metrics is full width (CJK, Hangul, emoji)
+fn is_wide(c: char) -> bool {
+    matches!(c as u32, 0x1100..=0x115F | 0x2E80..=0xA4CF | 0xAC00..=0xD7A3 | 0xF900..=0xFAFF | 0xFF00..=0xFF60 | 0x1F300..=0x1FAFF)
+}
+
+/// Measures and wraps text using a regular and a bold font face
+#[derive(Clone, Debug)]
+pub struct TextMeasurer {
+    regular: FontMetrics,
+    bold: FontMetrics,
+}
+
+impl Default for TextMeasurer {
+    fn default() -> Self {
+        TextMeasurer {
+            regular: FontMetrics::fallback(),
+            bold: FontMetrics::fallback_bold(),
+        }
+    }
+}
+
+impl TextMeasurer {
+    /// Create a measurer with the bundled metrics
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use the same font file for regular and bold text
+    pub fn from_font_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let metrics = FontMetrics::from_file(path)?;
+        Ok(TextMeasurer {
+            regular: metrics.clone(),
+            bold: metrics,
+        })
+    }
+
+    /// Use the fonts embedded in a presentation
+    ///
+    /// Measures with the theme's body font when it is embedded, otherwise with
+    /// the first embedded font. Faces that are missing or cannot be read keep
+    /// the bundled metrics; a missing bold face uses the regular one.
+    pub fn from_package(package: &Package) -> Self {
+        let measurer = Self::default();
+        let part = "ppt/presentation.xml";
+        let Some(presentation) = package.get_part_string(part).and_then(|xml| XmlParser::parse_str(&xml).ok()) else {
+            return measurer;
+        };
+        let rels = package.part_relationships(part);
+        let fonts = presentation.find_all_descendants("embeddedFont");
+        let body_font = rels.iter()
+            .find(|rel| rel.rel_type.ends_with("/theme"))
+            .and_then(|rel| package.get_part_string(&rel.target))
+            .and_then(|xml| XmlParser::parse_str(&xml).ok())
+            .and_then(|theme| {
+                let typeface = theme.find_descendant("minorFont")?.find("latin")?.attr("typeface")?;
+                Some(typeface.to_string())
+            });
+        let Some(font) = fonts.iter()
+            .find(|font| font.find("font").and_then(|f| f.attr("typeface")) == body_font.as_deref())
+            .or(fonts.first())
+        else {
+            return measurer;
+        };
+
+        let face = |weight: &str| {
+            let id = font.find(weight)?.attr("r:id")?;
+            let rel = rels.iter().find(|rel| rel.id == id)?;
+            FontMetrics::from_fntdata(package.get_part(&rel.target)?).ok()
+        };
+        match (face("regular"), face("bold")) {
+            (Some(regular), bold) => measurer.with_bold(bold.unwrap_or_else(|| regular.clone())).with_regular(regular),
+            (None, Some(bold)) => measurer.with_bold(bold),
+            (None, None) => measurer,
+        }
+    }
+
+    /// Use the fonts embedded in a `.pptx` file; see [`TextMeasurer::from_package`]
+    pub fn from_pptx<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Ok(Self::from_package(&Package::open(path)?))
+    }
+
+    /// Set the metrics for regular text
+    pub fn with_regular(mut self, metrics: FontMetrics) -> Self {
+        self.regular = metrics;
+        self
+    }
+
+    /// Set the metrics for bold text
+    pub fn with_bold(mut self, metrics: FontMetrics) -> Self {
+        self.bold = metrics;
+        self
+    }
+
+    /// Metrics for the given weight
+    pub fn metrics(&self, bold: bool) -> &FontMetrics {
+        if bold { &self.bold } else { &self.regular }
+    }
+
+    /// Width of a single line of text in points
+    pub fn text_width(&self, text: &str, font_size: f64, bold: bool) -> f64 {
+        self.metrics(bold).text_width(text, font_size)
+    }
+
+    /// Height of one line in points
+    pub fn line_height(&self, font_size: f64, bold: bool) -> f64 {
+        self.metrics(bold).line_height(font_size)
+    }
+
+    /// Wrap text into lines no wider than `width` points
+    ///
+    /// Breaks at spaces; words longer than a line are broken between characters.
+    pub fn wrap(&self, text: &str, font_size: f64, bold: bool, width: f64) -> Vec<String> {
+        let metrics = self.metrics(bold);
+        let mut lines = Vec::new();
+
+        for paragraph in text.split('\n') {
+            let mut line = String::new();
+            let mut line_width = 0.0;
+
+            for word in paragraph.split_inclusive(' ') {
+                let word_width = metrics.text_width(word, font_size);
+                let visible_width = metrics.text_width(word.trim_end(), font_size);
+                if line_width + visible_width <= width {
+                    line.push_str(word);
+                    line_width += word_width;
+                    continue;
+                }
+                if !line.is_empty() {
+                    lines.push(line.trim_end().to_string());
+                    line = String::new();
+                    line_width = 0.0;
+                }
+                if visible_width <= width {
+                    line.push_str(word);
+                    line_width = word_width;
+                    continue;
+                }
+                for c in word.chars() {
+                    let char_width = metrics.char_width(c, font_size);
+                    if line_width + char_width > width && !line.is_empty() && c != ' ' {
+                        lines.push(std::mem::take(&mut line));
+                        line_width = 0.0;
+                    }
+                    line.push(c);
+                    line_width += char_width;
+                }
+            }
+            lines.push(line.trim_end().to_string());
+        }
+        lines
+    }
+
+    /// Number of lines the text occupies in `width` points
+    pub fn line_count(&self, text: &str, font_size: f64, bold: bool, width: f64) -> usize {
+        self.wrap(text, font_size, bold, width).len()
+    }
+
+    /// Height in points of the text wrapped to `width` points
+    pub fn text_height(&self, text: &str, font_size: f64, bold: bool, width: f64) -> f64 {
+        self.line_count(text, font_size, bold, width) as f64 * self.line_height(font_size, bold)
+    }
+
+    /// Largest whole point size in `min..=max` at which the text fits the box
+    ///
+    /// Returns `min` when nothing fits.
+    pub fn fit_font_size(&self, text: &str, bold: bool, text_box: &TextBox, min: u32, max: u32) -> u32 {
+        let (width, height) = (text_box.content_width(), text_box.content_height());
+        (min..=max)
+            .rev()
+            .find(|&size| self.text_height(text, size as f64, bold, width) <= height)
+            .unwrap_or(min)
+    }
+}
+
+/// Text area of a shape or placeholder, in EMU
+#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+pub struct TextBox {
+    pub width: u32,
+    pub height: u32,
+    /// Left, top, right and bottom insets
+    pub insets: (u32, u32, u32, u32),
+}
+
+impl TextBox {
+    /// Create a box with PowerPoint's default insets (0.1" sides, 0.05" top/bottom)
+    pub fn new(width: u32, height: u32) -> Self {
+        TextBox {
+            width,
+            height,
+            insets: (91440, 45720, 91440, 45720),
+        }
+    }
+
+    /// Set the insets (left, top, right, bottom)
+    pub fn with_insets(mut self, left: u32, top: u32, right: u32, bottom: u32) -> Self {
+        self.insets = (left, top, right, bottom);
+        self
+    }
+
+    /// Usable width in points
+    pub fn content_width(&self) -> f64 {
+        emu_to_points(self.width.saturating_sub(self.insets.0 + self.insets.2))
+    }
+
+    /// Usable height in points
+    pub fn content_height(&self) -> f64 {
+        emu_to_points(self.height.saturating_sub(self.insets.1 + self.insets.3))
+    }
+}
+
+/// Convert EMU to points
+pub fn emu_to_points(emu: u32) -> f64 {
+    emu as f64 / EMU_PER_POINT
+}
+
+/// Convert points to EMU
+pub fn points_to_emu(points: f64) -> u32 {
+    (points * EMU_PER_POINT).ceil() as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fallback_widths() {
+        let metrics = FontMetrics::fallback();
+        assert!((metrics.text_width("Hi", 10.0) - 9.44).abs() < 0.001);
+        assert!(metrics.text_width("WWW", 18.0) > metrics.text_width("iii", 18.0) * 3.0);
+        assert_eq!(metrics.char_width('中', 20.0), 20.0);
+        assert!((metrics.line_height(20.0) - 23.0).abs() < 0.001);
+        assert!(FontMetrics::fallback_bold().text_width("bold", 10.0) > metrics.text_width("bold", 10.0));
+    }
+
+    #[test]
+    fn test_wrap() {
+        let measurer = TextMeasurer::new();
+        let width = measurer.text_width("The quick brown", 20.0, false) + 1.0;
+        let lines = measurer.wrap("The quick brown fox jumps over\nthe dog", 20.0, false, width);
+        assert_eq!(lines, vec!["The quick brown", "fox jumps over", "the dog"]);
+
+        let lines = measurer.wrap("Supercalifragilistic", 20.0, false, 60.0);
+        assert!(lines.len() > 1);
+        assert_eq!(lines.concat(), "Supercalifragilistic");
+        assert_eq!(measurer.line_count("", 20.0, false, 60.0), 1);
+    }
+
+    #[test]
+    fn test_fit_font_size() {
+        let measurer = TextMeasurer::new();
+        let text_box = TextBox::new(3_000_000, 1_000_000);
+        let short = measurer.fit_font_size("Hi", false, &text_box, 8, 44);
+        let long = measurer.fit_font_size(&"word ".repeat(60), false, &text_box, 8, 44);
+        assert_eq!(short, 44);
+        assert!(long < 20);
+        assert_eq!(measurer.fit_font_size(&"word ".repeat(5000), false, &text_box, 8, 44), 8);
+    }
+
+    #[test]
+    fn test_invalid_font_data() {
+        assert!(FontMetrics::from_bytes(b"not a font").is_err());
+    }
+
+    /// A TrueType font with 1000 units per em whose only glyph, 'A', is 700 units wide
+    fn tiny_font() -> Vec<u8> {
+        let be16 = |values: &[u16]| values.iter().flat_map(|v| v.to_be_bytes()).collect::<Vec<u8>>();
+        let mut head = vec![0u8; 54];
+        head[..4].copy_from_slice(&0x0001_0000u32.to_be_bytes());
+        head[12..16].copy_from_slice(&0x5F0F_3CF5u32.to_be_bytes());
+        head[18..20].copy_from_slice(&1000u16.to_be_bytes());
+        let mut hhea = vec![0u8; 36];
+        hhea[..4].copy_from_slice(&0x0001_0000u32.to_be_bytes());
+        hhea[4..10].copy_from_slice(&be16(&[800, (-200i16) as u16, 0]));
+        hhea[34..36].copy_from_slice(&2u16.to_be_bytes());
+        let maxp = be16(&[0, 0x5000, 2]);
+        let hmtx = be16(&[500, 0, 700, 0]);
+        let cmap = be16(&[
+            0, 1, 3, 1, 0, 12,
+            4, 32, 0, 4, 4, 1, 0, 0x41, 0xFFFF, 0, 0x41, 0xFFFF, 1u16.wrapping_sub(0x41), 1, 0, 0,
+        ]);
+
+        let tables = [(b"cmap", cmap), (b"head", head), (b"hhea", hhea), (b"hmtx", hmtx), (b"maxp", maxp)];
+        let mut font = 0x0001_0000u32.to_be_bytes().to_vec();
+        font.extend(be16(&[tables.len() as u16, 64, 2, 16]));
+        let mut offset = 12 + 16 * tables.len();
+        let mut data = Vec::new();
+        for (tag, table) in &tables {
+            font.extend(tag.iter());
+            font.extend(0u32.to_be_bytes());
+            font.extend((offset as u32).to_be_bytes());
+            font.extend((table.len() as u32).to_be_bytes());
+            data.extend(table);
+            data.resize(data.len().next_multiple_of(4), 0);
+            offset = 12 + 16 * tables.len() + data.len();
+        }
+        font.extend(data);
+        font
+    }
+
+    #[test]
+    fn test_from_fntdata() {
+        let font = tiny_font();
+        let metrics = FontMetrics::from_fntdata(&font).unwrap();
+        assert_eq!(metrics.char_width('A', 10.0), 7.0);
+        assert_eq!(metrics.line_height(10.0), 10.0);
+
+        // Embedded OpenType: the font follows a header, here XORed with 0x50
+        let mut eot = vec![0u8; 82];
+        eot[..4].copy_from_slice(&((82 + font.len()) as u32).to_le_bytes());
+        eot[4..8].copy_from_slice(&(font.len() as u32).to_le_bytes());
+        eot[8..12].copy_from_slice(&0x0002_0001u32.to_le_bytes());
+        eot[12..16].copy_from_slice(&EOT_XOR.to_le_bytes());
+        eot[34..36].copy_from_slice(&EOT_MAGIC.to_le_bytes());
+        eot.extend(font.iter().map(|b| b ^ 0x50));
+        assert_eq!(FontMetrics::from_fntdata(&eot).unwrap().char_width('A', 10.0), 7.0);
+
+        eot[12..16].copy_from_slice(&EOT_COMPRESSED.to_le_bytes());
+        assert!(FontMetrics::from_fntdata(&eot).is_err());
+    }
+
+    #[test]
+    fn test_from_package() {
+        let mut package = Package::new();
+        assert_eq!(TextMeasurer::from_package(&package).text_width("A", 10.0, false), FontMetrics::fallback().text_width("A", 10.0));
+
+        package.add_part("ppt/presentation.xml".to_string(), br#"<p:presentation xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><p:embeddedFontLst>
+<p:embeddedFont><p:font typeface="Other"/><p:regular r:id="rId9"/></p:embeddedFont>
+<p:embeddedFont><p:font typeface="Tiny"/><p:regular r:id="rId8"/></p:embeddedFont>
+</p:embeddedFontLst></p:presentation>"#.to_vec());
+        package.add_part("ppt/_rels/presentation.xml.rels".to_string(), br#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+<Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/theme" Target="theme/theme1.xml"/>
+<Relationship Id="rId8" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/font" Target="fonts/font1.fntdata"/>
+<Relationship Id="rId9" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/font" Target="fonts/font2.fntdata"/>
+</Relationships>"#.to_vec());
+        package.add_part("ppt/theme/theme1.xml".to_string(), br#"<a:theme xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"><a:themeElements><a:fontScheme name="Tiny"><a:majorFont><a:latin typeface="Other"/></a:majorFont><a:minorFont><a:latin typeface="Tiny"/></a:minorFont></a:fontScheme></a:themeElements></a:theme>"#.to_vec());
+        package.add_part("ppt/fonts/font1.fntdata".to_string(), tiny_font());
+        package.add_part("ppt/fonts/font2.fntdata".to_string(), b"not a font".to_vec());
+
+        let measurer = TextMeasurer::from_package(&package);
+        assert_eq!(measurer.text_width("AA", 10.0, false), 14.0);
+        assert_eq!(measurer.text_width("AA", 10.0, true), 14.0);
+    }
+}
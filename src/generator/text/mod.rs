@@ -5,16 +5,19 @@
 //! - `run` - A run of text with consistent formatting
 //! - `paragraph` - A paragraph with alignment and spacing
 //! - `frame` - Container for text content
+//! - `metrics` - Font metrics, wrapping and fitting
 
 mod format;
 mod run;
 mod paragraph;
 mod frame;
+mod metrics;
 
 pub use format::{TextFormat, FormattedText, color_to_xml};
 pub use run::Run;
 pub use paragraph::Paragraph;
 pub use frame::TextFrame;
+pub use metrics::{FontMetrics, TextMeasurer, TextBox, emu_to_points, points_to_emu};
 
 /// Text alignment options
 #[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
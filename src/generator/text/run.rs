@@ -2,12 +2,16 @@
 
 use super::format::TextFormat;
 use super::escape_xml;
+use crate::generator::hyperlinks::Hyperlink;
 
 /// A run of text with consistent formatting
 #[derive(Clone, Debug)]
 pub struct Run {
     pub text: String,
     pub format: TextFormat,
+    pub hyperlink: Option<Hyperlink>,
+    /// The text is LaTeX, rendered as inline Office Math in bullets
+    pub math: bool,
 }
 
 impl Run {
@@ -16,15 +20,28 @@ impl Run {
         Run {
             text: text.to_string(),
             format: TextFormat::default(),
+            hyperlink: None,
+            math: false,
         }
     }
 
+    /// Create an inline equation from LaTeX
+    pub fn math(latex: &str) -> Self {
+        Run { math: true, ..Self::new(latex) }
+    }
+
     /// Apply formatting
     pub fn with_format(mut self, format: TextFormat) -> Self {
         self.format = format;
         self
     }
 
+    /// Make the run a hyperlink
+    pub fn with_hyperlink(mut self, hyperlink: Hyperlink) -> Self {
+        self.hyperlink = Some(hyperlink);
+        self
+    }
+
     /// Set bold
     pub fn bold(mut self) -> Self {
         self.format.bold = true;
@@ -61,12 +78,41 @@ impl Run {
         self
     }
 
+    /// Set strikethrough
+    pub fn strikethrough(mut self) -> Self {
+        self.format.strikethrough = true;
+        self
+    }
+
+    /// Set highlight color
+    pub fn highlight(mut self, hex: &str) -> Self {
+        self.format.highlight = Some(hex.trim_start_matches('#').to_uppercase());
+        self
+    }
+
+    /// Format as inline code (monospace font)
+    pub fn code(mut self) -> Self {
+        self.format.font_family = Some("Consolas".to_string());
+        self
+    }
+
     /// Generate XML for this run
     pub fn to_xml(&self) -> String {
         let size = self.format.font_size.unwrap_or(18) * 100;
         let bold = if self.format.bold { "1" } else { "0" };
         let italic = if self.format.italic { "1" } else { "0" };
-        let underline = if self.format.underline { " u=\"sng\"" } else { "" };
+        let mut extra_attrs = String::new();
+        if self.format.underline {
+            extra_attrs.push_str(r#" u="sng""#);
+        }
+        if self.format.strikethrough {
+            extra_attrs.push_str(r#" strike="sngStrike""#);
+        }
+        if self.format.subscript {
+            extra_attrs.push_str(r#" baseline="-25000""#);
+        } else if self.format.superscript {
+            extra_attrs.push_str(r#" baseline="30000""#);
+        }
         
         let color_xml = self.format.color.as_ref()
             .map(|c| format!(r#"<a:solidFill><a:srgbClr val="{}"/></a:solidFill>"#, c))
@@ -76,9 +122,11 @@ impl Run {
             .map(|f| format!(r#"<a:latin typeface="{}"/>"#, escape_xml(f)))
             .unwrap_or_default();
 
+        let hyperlink_xml = self.hyperlink.as_ref().map(Hyperlink::click_xml).unwrap_or_default();
+
         format!(
-            r#"<a:r><a:rPr lang="en-US" sz="{}" b="{}" i="{}"{} dirty="0">{}{}</a:rPr><a:t>{}</a:t></a:r>"#,
-            size, bold, italic, underline, color_xml, font_xml, escape_xml(&self.text)
+            r#"<a:r><a:rPr lang="en-US" sz="{}" b="{}" i="{}"{} dirty="0">{}{}{}{}</a:rPr><a:t>{}</a:t></a:r>"#,
+            size, bold, italic, extra_attrs, color_xml, self.format.to_highlight_xml(), font_xml, hyperlink_xml, escape_xml(&self.text)
         )
     }
 }
@@ -1,8 +1,10 @@
 //! Theme, master, and layout XML generation
 
+use super::header_footer::{insert_placeholders, layout_placeholders_xml, master_placeholders_xml};
+
 /// Create slide layout XML
 pub fn create_slide_layout_xml() -> String {
-    r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+    let xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
 <p:sldLayout xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" type="blank" preserve="1">
 <p:cSld name="Blank">
 <p:spTree>
@@ -24,7 +26,8 @@ pub fn create_slide_layout_xml() -> String {
 <p:clrMapOvr>
 <a:masterClrMapping/>
 </p:clrMapOvr>
-</p:sldLayout>"#.to_string()
+</p:sldLayout>"#;
+    insert_placeholders(xml, &layout_placeholders_xml())
 }
 
 /// Create layout relationships XML
@@ -37,7 +40,7 @@ pub fn create_layout_rels_xml() -> String {
 
 /// Create slide master XML
 pub fn create_slide_master_xml() -> String {
-    r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+    let xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
 <p:sldMaster xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main">
 <p:cSld>
 <p:bg>
@@ -65,7 +68,8 @@ pub fn create_slide_master_xml() -> String {
 <p:sldLayoutIdLst>
 <p:sldLayoutId id="2147483649" r:id="rId1"/>
 </p:sldLayoutIdLst>
-</p:sldMaster>"#.to_string()
+</p:sldMaster>"#;
+    insert_placeholders(xml, &master_placeholders_xml())
 }
 
 /// Create master relationships XML
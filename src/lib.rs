@@ -72,7 +72,7 @@ pub use generator::{
     create_pptx, create_pptx_with_content, SlideContent, SlideLayout,
     TextFormat, FormattedText,
     Table, TableRow, TableCell, TableBuilder,
-    Shape, ShapeType, ShapeFill, ShapeLine,
+    Shape, ShapeType, ShapeFill, ShapeLine, ShapeGroup,
     Image, ImageBuilder, ImageSource,
     Chart, ChartType, ChartSeries, ChartBuilder,
     // Bullet styles
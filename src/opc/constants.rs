@@ -14,6 +14,7 @@ pub mod CONTENT_TYPE {
     pub const PML_SLIDE_LAYOUT: &str = "application/vnd.openxmlformats-officedocument.presentationml.slideLayout+xml";
     pub const PML_SLIDE_MASTER: &str = "application/vnd.openxmlformats-officedocument.presentationml.slideMaster+xml";
     pub const DML_CHART: &str = "application/vnd.openxmlformats-officedocument.drawingml.chart+xml";
+    pub const SML_SHEET: &str = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet";
     pub const BMP: &str = "image/bmp";
     pub const GIF: &str = "image/gif";
     pub const JPEG: &str = "image/jpeg";
@@ -48,6 +49,7 @@ pub mod RELATIONSHIP_TYPE {
     pub const MEDIA: &str = "http://schemas.openxmlformats.org/officeDocument/2006/relationships/media";
     pub const CHART: &str = "http://schemas.openxmlformats.org/officeDocument/2006/relationships/chart";
     pub const EMBEDDED_PACKAGE: &str = "http://schemas.openxmlformats.org/officeDocument/2006/relationships/oleObject";
+    pub const PACKAGE: &str = "http://schemas.openxmlformats.org/officeDocument/2006/relationships/package";
 }
 
 /// XML namespaces
@@ -5,5 +5,5 @@ pub mod package;
 pub mod packuri;
 pub mod shared;
 
-pub use package::Package;
+pub use package::{Package, PartRelationship};
 pub use packuri::PackUri;
@@ -4,6 +4,17 @@ use std::io::Read;
 use std::path::Path;
 use std::collections::HashMap;
 use crate::exc::Result;
+use super::packuri::{rels_path, resolve_part_path};
+
+/// A relationship read from a part's `.rels` file
+#[derive(Debug, Clone)]
+pub struct PartRelationship {
+    pub id: String,
+    pub rel_type: String,
+    /// Resolved package path, or the raw URL for external targets
+    pub target: String,
+    pub external: bool,
+}
 
 /// Represents an OPC package (ZIP file)
 pub struct Package {
@@ -113,6 +124,30 @@ impl Package {
     pub fn get_part_string(&self, path: &str) -> Option<String> {
         self.parts.get(path).map(|v| String::from_utf8_lossy(v).to_string())
     }
+
+    /// Get the relationships of a part, with targets resolved to package paths
+    pub fn part_relationships(&self, part: &str) -> Vec<PartRelationship> {
+        let Some(xml) = self.get_part_string(&rels_path(part)) else {
+            return Vec::new();
+        };
+        let Ok(root) = crate::oxml::XmlParser::parse_str(&xml) else {
+            return Vec::new();
+        };
+
+        root.find_all("Relationship")
+            .into_iter()
+            .filter_map(|rel| {
+                let external = rel.attr("TargetMode") == Some("External");
+                let target = rel.attr("Target")?;
+                Some(PartRelationship {
+                    id: rel.attr("Id")?.to_string(),
+                    rel_type: rel.attr("Type").unwrap_or("").to_string(),
+                    target: if external { target.to_string() } else { resolve_part_path(part, target) },
+                    external,
+                })
+            })
+            .collect()
+    }
 }
 
 impl Default for Package {
@@ -139,6 +174,25 @@ mod tests {
         assert_eq!(package.get_part("test.txt"), Some(b"content".as_slice()));
     }
 
+    #[test]
+    fn test_part_relationships() {
+        let mut package = Package::new();
+        package.add_part(
+            "ppt/slides/_rels/slide1.xml.rels".to_string(),
+            br#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+<Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/chart" Target="../charts/chart1.xml"/>
+<Relationship Id="rId3" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/hyperlink" Target="https://example.com" TargetMode="External"/>
+</Relationships>"#.to_vec(),
+        );
+
+        let rels = package.part_relationships("ppt/slides/slide1.xml");
+        assert_eq!(rels.len(), 2);
+        assert_eq!(rels[0].target, "ppt/charts/chart1.xml");
+        assert!(!rels[0].external);
+        assert_eq!(rels[1].target, "https://example.com");
+        assert!(rels[1].external);
+    }
+
     #[test]
     fn test_part_paths() {
         let mut package = Package::new();
@@ -68,6 +68,57 @@ impl From<String> for PackUri {
     }
 }
 
+/// Resolve a relationship target against its source part
+///
+/// Both the source and the result are package paths without a leading slash
+/// (e.g. `ppt/slides/slide1.xml` + `../charts/chart1.xml` -> `ppt/charts/chart1.xml`).
+/// Absolute targets (`/ppt/media/image1.png`) are taken from the package root.
+pub fn resolve_part_path(source: &str, target: &str) -> String {
+    let mut segments: Vec<&str> = if target.starts_with('/') {
+        Vec::new()
+    } else {
+        source.split('/').collect()
+    };
+    // Drop the source file name
+    segments.pop();
+
+    for segment in target.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            _ => segments.push(segment),
+        }
+    }
+    segments.join("/")
+}
+
+/// Relationship target of the part `target` as written in the relationships
+/// of `source`, the inverse of [`resolve_part_path`]
+/// (e.g. `ppt/slides/slide1.xml` + `ppt/charts/chart1.xml` -> `../charts/chart1.xml`)
+pub fn relative_part_path(source: &str, target: &str) -> String {
+    let mut directories: Vec<&str> = source.split('/').collect();
+    directories.pop();
+    let segments: Vec<&str> = target.split('/').collect();
+    let common = directories
+        .iter()
+        .zip(&segments[..segments.len() - 1])
+        .take_while(|(a, b)| a == b)
+        .count();
+    let mut path = vec![".."; directories.len() - common];
+    path.extend(&segments[common..]);
+    path.join("/")
+}
+
+/// Relationships part for a part (`ppt/slides/slide1.xml` -> `ppt/slides/_rels/slide1.xml.rels`)
+pub fn rels_path(part: &str) -> String {
+    match part.rfind('/') {
+        Some(pos) => format!("{}/_rels/{}.rels", &part[..pos], &part[pos + 1..]),
+        None => format!("_rels/{part}.rels"),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -97,6 +148,26 @@ mod tests {
         assert_eq!(resolved.as_str(), "/ppt/slides/../theme/theme1.xml");
     }
 
+    #[test]
+    fn test_resolve_part_path() {
+        assert_eq!(resolve_part_path("ppt/slides/slide1.xml", "../charts/chart1.xml"), "ppt/charts/chart1.xml");
+        assert_eq!(resolve_part_path("ppt/presentation.xml", "slides/slide2.xml"), "ppt/slides/slide2.xml");
+        assert_eq!(resolve_part_path("ppt/slides/slide1.xml", "/ppt/media/image1.png"), "ppt/media/image1.png");
+    }
+
+    #[test]
+    fn test_relative_part_path() {
+        assert_eq!(relative_part_path("ppt/slides/slide1.xml", "ppt/slideLayouts/slideLayout2.xml"), "../slideLayouts/slideLayout2.xml");
+        assert_eq!(relative_part_path("ppt/presentation.xml", "ppt/theme/theme1.xml"), "theme/theme1.xml");
+        assert_eq!(relative_part_path("ppt/slideMasters/slideMaster1.xml", "ppt/slideMasters/slideMaster2.xml"), "slideMaster2.xml");
+    }
+
+    #[test]
+    fn test_rels_path() {
+        assert_eq!(rels_path("ppt/slides/slide3.xml"), "ppt/slides/_rels/slide3.xml.rels");
+        assert_eq!(rels_path("[Content_Types].xml"), "_rels/[Content_Types].xml.rels");
+    }
+
     #[test]
     fn test_packuri_from_str() {
         let uri: PackUri = "/ppt/presentation.xml".into();
@@ -1,6 +1,18 @@
 //! Chart XML elements for OOXML
 //!
 //! Provides types for parsing and generating DrawingML chart elements.
+//!
+//! - `parser` - Read chart parts back into `generator::Chart`
+//! - `workbook` - Embedded workbook (xlsx) access for chart data
+//! - `update` - Replace chart data in place, keeping formatting
+
+pub mod parser;
+pub mod update;
+pub mod workbook;
+
+pub use parser::{ChartParser, ChartFrame};
+pub use update::replace_chart_data;
+pub use workbook::EmbeddedWorkbook;
 
 use super::xmlchemy::XmlElement;
 
@@ -33,10 +45,10 @@ impl ChartKind {
 
     pub fn from_element(name: &str) -> Option<Self> {
         match name {
-            "barChart" => Some(ChartKind::Bar),
-            "lineChart" => Some(ChartKind::Line),
-            "pieChart" => Some(ChartKind::Pie),
-            "areaChart" => Some(ChartKind::Area),
+            "barChart" | "bar3DChart" => Some(ChartKind::Bar),
+            "lineChart" | "line3DChart" => Some(ChartKind::Line),
+            "pieChart" | "pie3DChart" | "ofPieChart" => Some(ChartKind::Pie),
+            "areaChart" | "area3DChart" => Some(ChartKind::Area),
             "scatterChart" => Some(ChartKind::Scatter),
             "doughnutChart" => Some(ChartKind::Doughnut),
             "radarChart" => Some(ChartKind::Radar),
@@ -107,6 +119,40 @@ impl NumericData {
         data
     }
 
+    /// Parse from a data container (`c:val`, `c:yVal`, ...) holding a `c:numRef` or `c:numLit`
+    pub fn parse(elem: &XmlElement) -> Option<Self> {
+        let (formula, cache) = if let Some(num_ref) = elem.find("numRef") {
+            let formula = num_ref.find("f").map(|f| f.text_content()).unwrap_or_default();
+            (formula, num_ref.find("numCache"))
+        } else {
+            (String::new(), elem.find("numLit"))
+        };
+
+        let mut data = NumericData::new(formula.trim());
+        if let Some(cache) = cache {
+            for (index, value) in cache_points(cache) {
+                if let Ok(v) = value.trim().parse() {
+                    data.points.push(DataPoint::new(index, v));
+                }
+            }
+        }
+        Some(data)
+    }
+
+    /// Number of points (highest index + 1)
+    pub fn point_count(&self) -> usize {
+        self.points.iter().map(|p| p.index as usize + 1).max().unwrap_or(0)
+    }
+
+    /// Values as a dense vector; missing points are 0.0
+    pub fn values(&self) -> Vec<f64> {
+        let mut values = vec![0.0; self.point_count()];
+        for pt in &self.points {
+            values[pt.index as usize] = pt.value;
+        }
+        values
+    }
+
     pub fn to_xml(&self) -> String {
         let mut xml = format!(
             r#"<c:numRef><c:f>{}</c:f><c:numCache><c:formatCode>General</c:formatCode><c:ptCount val="{}"/>"#,
@@ -144,6 +190,52 @@ impl StringData {
         data
     }
 
+    /// Parse from a data container (`c:cat`, `c:xVal`, `c:tx`, ...)
+    ///
+    /// Accepts string, numeric and multi-level references as well as literals.
+    /// Numeric categories keep their cached text; for multi-level categories
+    /// only the innermost level is kept.
+    pub fn parse(elem: &XmlElement) -> Option<Self> {
+        let reference = [("strRef", "strCache"), ("numRef", "numCache"), ("multiLvlStrRef", "multiLvlStrCache")]
+            .into_iter()
+            .find_map(|(r, c)| elem.find(r).map(|e| (e, c)));
+
+        let (formula, cache) = match reference {
+            Some((r, cache_name)) => {
+                let formula = r.find("f").map(|f| f.text_content()).unwrap_or_default();
+                let cache = r.find(cache_name).map(|c| c.find("lvl").unwrap_or(c));
+                (formula, cache)
+            }
+            None => (String::new(), elem.find("strLit").or_else(|| elem.find("numLit"))),
+        };
+
+        if reference.is_none() && cache.is_none() {
+            // Literal series name (`<c:tx><c:v>Name</c:v></c:tx>`)
+            let v = elem.find("v")?;
+            let mut data = StringData::new("");
+            data.points.push(CategoryPoint::new(0, &v.text_content()));
+            return Some(data);
+        }
+
+        let mut data = StringData::new(formula.trim());
+        if let Some(cache) = cache {
+            for (index, value) in cache_points(cache) {
+                data.points.push(CategoryPoint { index, value });
+            }
+        }
+        Some(data)
+    }
+
+    /// Values as a dense vector; missing points are empty strings
+    pub fn values(&self) -> Vec<String> {
+        let count = self.points.iter().map(|p| p.index as usize + 1).max().unwrap_or(0);
+        let mut values = vec![String::new(); count];
+        for pt in &self.points {
+            values[pt.index as usize] = pt.value.clone();
+        }
+        values
+    }
+
     pub fn to_xml(&self) -> String {
         let mut xml = format!(
             r#"<c:strRef><c:f>{}</c:f><c:strCache><c:ptCount val="{}"/>"#,
@@ -188,18 +280,25 @@ impl ChartSeries {
             .and_then(|v| v.parse().ok())
             .unwrap_or(0);
 
-        let name = elem.find_descendant("t")
-            .map(|t| t.text_content())
+        let name = elem.find("tx")
+            .and_then(StringData::parse)
+            .and_then(|tx| tx.points.first().map(|p| p.value.clone()))
             .unwrap_or_default();
 
-        // Parse values
-        let values = NumericData::new("Sheet1!$B$2");
+        let values = elem.find("val")
+            .or_else(|| elem.find("yVal"))
+            .and_then(NumericData::parse)
+            .unwrap_or_else(|| NumericData::new(""));
+
+        let categories = elem.find("cat")
+            .or_else(|| elem.find("xVal"))
+            .and_then(StringData::parse);
 
         Some(ChartSeries {
             index,
             name,
             values,
-            categories: None,
+            categories,
         })
     }
 
@@ -325,6 +424,18 @@ impl ChartTitle {
     }
 }
 
+/// Collect `(idx, text)` pairs from a `c:numCache`/`c:strCache`/`c:lvl` element
+fn cache_points(cache: &XmlElement) -> Vec<(u32, String)> {
+    cache.find_all("pt")
+        .into_iter()
+        .filter_map(|pt| {
+            let index = pt.attr("idx")?.parse().ok()?;
+            let value = pt.find("v").map(|v| v.text_content()).unwrap_or_default();
+            Some((index, value))
+        })
+        .collect()
+}
+
 fn escape_xml(s: &str) -> String {
     s.replace('&', "&amp;")
         .replace('<', "&lt;")
@@ -369,6 +480,24 @@ mod tests {
         assert!(xml.contains("Sales"));
     }
 
+    #[test]
+    fn test_parse_chart_series() {
+        let xml = r#"<c:ser xmlns:c="http://schemas.openxmlformats.org/drawingml/2006/chart">
+            <c:idx val="1"/><c:order val="1"/>
+            <c:tx><c:strRef><c:f>Sheet1!$C$1</c:f><c:strCache><c:ptCount val="1"/><c:pt idx="0"><c:v>Costs</c:v></c:pt></c:strCache></c:strRef></c:tx>
+            <c:cat><c:strRef><c:f>Sheet1!$A$2:$A$3</c:f><c:strCache><c:ptCount val="2"/><c:pt idx="0"><c:v>Jan</c:v></c:pt><c:pt idx="1"><c:v>Feb</c:v></c:pt></c:strCache></c:strRef></c:cat>
+            <c:val><c:numRef><c:f>Sheet1!$C$2:$C$3</c:f><c:numCache><c:formatCode>General</c:formatCode><c:ptCount val="2"/><c:pt idx="0"><c:v>4.5</c:v></c:pt><c:pt idx="1"><c:v>6</c:v></c:pt></c:numCache></c:numRef></c:val>
+        </c:ser>"#;
+        let elem = crate::oxml::XmlParser::parse_str(xml).unwrap();
+        let series = ChartSeries::parse(&elem).unwrap();
+
+        assert_eq!(series.index, 1);
+        assert_eq!(series.name, "Costs");
+        assert_eq!(series.values.formula, "Sheet1!$C$2:$C$3");
+        assert_eq!(series.values.values(), vec![4.5, 6.0]);
+        assert_eq!(series.categories.unwrap().values(), vec!["Jan", "Feb"]);
+    }
+
     #[test]
     fn test_chart_legend() {
         let legend = ChartLegend::right();
@@ -0,0 +1,318 @@
+//! Chart part parsing
+//!
+//! Reads `ppt/charts/chartN.xml` parts back into `generator::Chart`, using the
+//! embedded workbook as the data source when it resolves the series formulas
+//! and falling back to the cached values (`c:numCache`/`c:strCache`).
+
+use super::workbook::EmbeddedWorkbook;
+use super::{ChartSeries as OxmlChartSeries, NumericData, StringData};
+use crate::exc::PptxError;
+use crate::generator::charts::{Chart, ChartSeries, ChartType};
+use crate::opc::Package;
+use crate::oxml::xmlchemy::{XmlElement, XmlParser};
+
+const CHART_URI: &str = "http://schemas.openxmlformats.org/drawingml/2006/chart";
+
+/// A chart graphic frame on a slide
+#[derive(Debug, Clone)]
+pub struct ChartFrame {
+    /// Shape name from `p:cNvPr`
+    pub name: String,
+    /// Chart part path (e.g. `ppt/charts/chart1.xml`)
+    pub part_path: String,
+    pub x: i64,
+    pub y: i64,
+    pub width: i64,
+    pub height: i64,
+}
+
+/// Chart parser
+pub struct ChartParser;
+
+impl ChartParser {
+    /// Parse chart part XML (`c:chartSpace`)
+    ///
+    /// The returned chart has a zero position and size; use [`ChartParser::read`]
+    /// to get a chart placed like its frame on the slide.
+    pub fn parse(xml: &str, workbook: Option<&EmbeddedWorkbook>) -> Result<Chart, PptxError> {
+        let root = XmlParser::parse_str(xml)?;
+        let chart_elem = root.find("chart")
+            .ok_or_else(|| PptxError::InvalidXml("Missing c:chart element".to_string()))?;
+        let plot_area = chart_elem.find("plotArea")
+            .ok_or_else(|| PptxError::InvalidXml("Missing c:plotArea element".to_string()))?;
+
+        let groups: Vec<&XmlElement> = plot_area.children.iter()
+            .filter(|c| c.local_name.ends_with("Chart"))
+            .collect();
+        let first = groups.first()
+            .ok_or_else(|| PptxError::InvalidXml("Chart has no plot".to_string()))?;
+
+        let mut series = Vec::new();
+        let mut categories: Option<Vec<String>> = None;
+        for group in &groups {
+            for ser in group.find_all("ser") {
+                let Some(parsed) = OxmlChartSeries::parse(ser) else { continue };
+
+                let name = ser.find("tx")
+                    .and_then(StringData::parse)
+                    .map(|tx| resolve_strings(&tx, workbook))
+                    .and_then(|names| names.into_iter().next())
+                    .unwrap_or(parsed.name);
+
+                if categories.is_none() {
+                    categories = parsed.categories.as_ref().map(|c| resolve_strings(c, workbook));
+                }
+
+                series.push(ChartSeries::new(&name, resolve_numbers(&parsed.values, workbook)));
+            }
+        }
+
+        let chart_type = if groups.len() > 1 {
+            ChartType::Combo
+        } else {
+            chart_type_for(first, series.len())
+        };
+
+        let title = chart_elem.find("title")
+            .map(|t| title_text(t, workbook))
+            .unwrap_or_else(|| {
+                // PowerPoint shows the series name as title for single-series charts
+                let auto_deleted = chart_elem.find("autoTitleDeleted")
+                    .and_then(|e| e.attr("val"))
+                    .map(|v| v == "1" || v == "true")
+                    .unwrap_or(false);
+                match (auto_deleted, series.as_slice()) {
+                    (false, [only]) => only.name.clone(),
+                    _ => String::new(),
+                }
+            });
+
+        let mut chart = Chart::new(&title, chart_type, categories.unwrap_or_default(), 0, 0, 0, 0);
+        chart.series = series;
+        Ok(chart)
+    }
+
+    /// Find the chart frames on a slide, in shape tree order
+    pub fn find_frames(package: &Package, slide_path: &str) -> Result<Vec<ChartFrame>, PptxError> {
+        let xml = package.get_part_string(slide_path)
+            .ok_or_else(|| PptxError::NotFound(format!("Slide file not found: {slide_path}")))?;
+        let root = XmlParser::parse_str(&xml)?;
+        let rels = package.part_relationships(slide_path);
+
+        let mut frames = Vec::new();
+        for gf in root.find_all_descendants("graphicFrame") {
+            let Some(graphic_data) = gf.find_descendant("graphicData") else { continue };
+            if graphic_data.attr("uri") != Some(CHART_URI) {
+                continue;
+            }
+            let Some(r_id) = graphic_data.find("chart").and_then(|c| c.attr("r:id")) else { continue };
+            let Some(rel) = rels.iter().find(|r| r.id == r_id) else { continue };
+
+            let mut frame = ChartFrame {
+                name: gf.find_descendant("cNvPr")
+                    .and_then(|e| e.attr("name"))
+                    .unwrap_or("Chart")
+                    .to_string(),
+                part_path: rel.target.clone(),
+                x: 0,
+                y: 0,
+                width: 0,
+                height: 0,
+            };
+            if let Some(xfrm) = gf.find("xfrm") {
+                if let Some(off) = xfrm.find("off") {
+                    frame.x = off.attr("x").and_then(|v| v.parse().ok()).unwrap_or(0);
+                    frame.y = off.attr("y").and_then(|v| v.parse().ok()).unwrap_or(0);
+                }
+                if let Some(ext) = xfrm.find("ext") {
+                    frame.width = ext.attr("cx").and_then(|v| v.parse().ok()).unwrap_or(0);
+                    frame.height = ext.attr("cy").and_then(|v| v.parse().ok()).unwrap_or(0);
+                }
+            }
+            frames.push(frame);
+        }
+        Ok(frames)
+    }
+
+    /// Path of the workbook embedded for a chart part, if any
+    pub fn workbook_path(package: &Package, chart_path: &str) -> Option<String> {
+        package.part_relationships(chart_path)
+            .into_iter()
+            .find(|r| !r.external && (r.rel_type.ends_with("/package") || r.rel_type.ends_with("/oleObject")))
+            .map(|r| r.target)
+    }
+
+    /// Load the workbook embedded for a chart part, if any
+    pub fn load_workbook(package: &Package, chart_path: &str) -> Option<EmbeddedWorkbook> {
+        let path = Self::workbook_path(package, chart_path)?;
+        EmbeddedWorkbook::from_bytes(package.get_part(&path)?).ok()
+    }
+
+    /// Read the chart behind a frame, positioned like the frame
+    pub fn read(package: &Package, frame: &ChartFrame) -> Result<Chart, PptxError> {
+        let xml = package.get_part_string(&frame.part_path)
+            .ok_or_else(|| PptxError::NotFound(format!("Chart part not found: {}", frame.part_path)))?;
+        let workbook = Self::load_workbook(package, &frame.part_path);
+
+        let mut chart = Self::parse(&xml, workbook.as_ref())?;
+        chart.x = u32::try_from(frame.x).unwrap_or(0);
+        chart.y = u32::try_from(frame.y).unwrap_or(0);
+        chart.width = u32::try_from(frame.width).unwrap_or(0);
+        chart.height = u32::try_from(frame.height).unwrap_or(0);
+        Ok(chart)
+    }
+
+    /// Read all charts on a slide
+    pub fn read_slide_charts(package: &Package, slide_path: &str) -> Result<Vec<Chart>, PptxError> {
+        Self::find_frames(package, slide_path)?
+            .iter()
+            .map(|frame| Self::read(package, frame))
+            .collect()
+    }
+}
+
+/// Map a plot group (`c:barChart`, `c:lineChart`, ...) to a chart type
+fn chart_type_for(group: &XmlElement, series_count: usize) -> ChartType {
+    let val = |name: &str| group.find(name).and_then(|e| e.attr("val")).unwrap_or("");
+
+    match group.local_name.as_str() {
+        "barChart" | "bar3DChart" => match (val("barDir"), val("grouping")) {
+            ("bar", "stacked") => ChartType::BarHorizontalStacked,
+            ("bar", _) => ChartType::BarHorizontal,
+            (_, "stacked") => ChartType::BarStacked,
+            (_, "percentStacked") => ChartType::BarStacked100,
+            _ => ChartType::Bar,
+        },
+        "lineChart" | "line3DChart" => {
+            if matches!(val("grouping"), "stacked" | "percentStacked") {
+                ChartType::LineStacked
+            } else if matches!(val("marker"), "1" | "true") {
+                ChartType::LineMarkers
+            } else {
+                ChartType::Line
+            }
+        }
+        "pieChart" | "pie3DChart" | "ofPieChart" => ChartType::Pie,
+        "doughnutChart" => ChartType::Doughnut,
+        "areaChart" | "area3DChart" => match val("grouping") {
+            "stacked" => ChartType::AreaStacked,
+            "percentStacked" => ChartType::AreaStacked100,
+            _ => ChartType::Area,
+        },
+        "scatterChart" => match val("scatterStyle") {
+            "lineMarker" | "line" => ChartType::ScatterLines,
+            "smoothMarker" | "smooth" => ChartType::ScatterSmooth,
+            _ => ChartType::Scatter,
+        },
+        "bubbleChart" => ChartType::Bubble,
+        "radarChart" => match val("radarStyle") {
+            "filled" => ChartType::RadarFilled,
+            _ => ChartType::Radar,
+        },
+        "stockChart" if series_count >= 4 => ChartType::StockOHLC,
+        "stockChart" => ChartType::StockHLC,
+        _ => ChartType::Bar,
+    }
+}
+
+/// Chart title text from rich text or a string reference
+fn title_text(title: &XmlElement, workbook: Option<&EmbeddedWorkbook>) -> String {
+    let Some(tx) = title.find("tx") else {
+        return String::new();
+    };
+    if let Some(rich) = tx.find("rich") {
+        return rich.find_all("p")
+            .into_iter()
+            .map(|p| {
+                p.find_all_descendants("t")
+                    .into_iter()
+                    .map(|t| t.text_content())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+    }
+    StringData::parse(tx)
+        .map(|data| resolve_strings(&data, workbook).join(" "))
+        .unwrap_or_default()
+}
+
+/// Values from the workbook when the formula resolves, else from the cache
+fn resolve_numbers(data: &NumericData, workbook: Option<&EmbeddedWorkbook>) -> Vec<f64> {
+    workbook
+        .and_then(|wb| wb.resolve(&data.formula))
+        .and_then(|cells| cells.iter().map(|c| c.trim().parse().ok()).collect())
+        .unwrap_or_else(|| data.values())
+}
+
+/// Strings from the workbook when the formula resolves, else from the cache
+fn resolve_strings(data: &StringData, workbook: Option<&EmbeddedWorkbook>) -> Vec<String> {
+    workbook
+        .and_then(|wb| wb.resolve(&data.formula))
+        .unwrap_or_else(|| data.values())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::oxml::chart::workbook::tests::sample_workbook;
+
+    pub(crate) const BAR_CHART_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<c:chartSpace xmlns:c="http://schemas.openxmlformats.org/drawingml/2006/chart" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">
+<c:chart>
+<c:title><c:tx><c:rich><a:bodyPr/><a:p><a:r><a:t>Monthly </a:t></a:r><a:r><a:t>Revenue</a:t></a:r></a:p></c:rich></c:tx><c:overlay val="0"/></c:title>
+<c:autoTitleDeleted val="0"/>
+<c:plotArea><c:layout/>
+<c:barChart><c:barDir val="col"/><c:grouping val="stacked"/><c:varyColors val="0"/>
+<c:ser><c:idx val="0"/><c:order val="0"/>
+<c:tx><c:strRef><c:f>Sheet1!$B$1</c:f><c:strCache><c:ptCount val="1"/><c:pt idx="0"><c:v>Revenue</c:v></c:pt></c:strCache></c:strRef></c:tx>
+<c:spPr><a:solidFill><a:srgbClr val="4472C4"/></a:solidFill></c:spPr>
+<c:cat><c:strRef><c:f>Sheet1!$A$2:$A$3</c:f><c:strCache><c:ptCount val="2"/><c:pt idx="0"><c:v>Jan</c:v></c:pt><c:pt idx="1"><c:v>Feb</c:v></c:pt></c:strCache></c:strRef></c:cat>
+<c:val><c:numRef><c:f>Sheet1!$B$2:$B$3</c:f><c:numCache><c:formatCode>General</c:formatCode><c:ptCount val="2"/><c:pt idx="0"><c:v>100</c:v></c:pt><c:pt idx="1"><c:v>110</c:v></c:pt></c:numCache></c:numRef></c:val>
+</c:ser>
+<c:axId val="1"/><c:axId val="2"/>
+</c:barChart>
+<c:catAx><c:axId val="1"/><c:crossAx val="2"/></c:catAx>
+<c:valAx><c:axId val="2"/><c:crossAx val="1"/></c:valAx>
+</c:plotArea>
+</c:chart>
+<c:externalData r:id="rId1"><c:autoUpdate val="0"/></c:externalData>
+</c:chartSpace>"#;
+
+    #[test]
+    fn test_parse_cached_values() {
+        let chart = ChartParser::parse(BAR_CHART_XML, None).unwrap();
+        assert_eq!(chart.title, "Monthly Revenue");
+        assert_eq!(chart.chart_type, ChartType::BarStacked);
+        assert_eq!(chart.categories, vec!["Jan", "Feb"]);
+        assert_eq!(chart.series.len(), 1);
+        assert_eq!(chart.series[0].name, "Revenue");
+        assert_eq!(chart.series[0].values, vec![100.0, 110.0]);
+    }
+
+    #[test]
+    fn test_parse_prefers_workbook() {
+        let workbook = EmbeddedWorkbook::from_bytes(&sample_workbook()).unwrap();
+        let chart = ChartParser::parse(BAR_CHART_XML, Some(&workbook)).unwrap();
+        assert_eq!(chart.series[0].values, vec![120.0, 135.5]);
+        assert_eq!(chart.categories, vec!["Jan", "Feb"]);
+    }
+
+    #[test]
+    fn test_chart_types() {
+        let parse = |plot: &str| {
+            let xml = format!(
+                r#"<c:chartSpace xmlns:c="http://schemas.openxmlformats.org/drawingml/2006/chart"><c:chart><c:plotArea>{plot}</c:plotArea></c:chart></c:chartSpace>"#
+            );
+            ChartParser::parse(&xml, None).unwrap().chart_type
+        };
+
+        assert_eq!(parse(r#"<c:barChart><c:barDir val="bar"/></c:barChart>"#), ChartType::BarHorizontal);
+        assert_eq!(parse(r#"<c:lineChart><c:grouping val="standard"/><c:marker val="1"/></c:lineChart>"#), ChartType::LineMarkers);
+        assert_eq!(parse(r#"<c:pie3DChart/>"#), ChartType::Pie);
+        assert_eq!(parse(r#"<c:areaChart><c:grouping val="percentStacked"/></c:areaChart>"#), ChartType::AreaStacked100);
+        assert_eq!(parse(r#"<c:scatterChart><c:scatterStyle val="smoothMarker"/></c:scatterChart>"#), ChartType::ScatterSmooth);
+        assert_eq!(parse(r#"<c:radarChart><c:radarStyle val="filled"/></c:radarChart>"#), ChartType::RadarFilled);
+        assert_eq!(parse(r#"<c:barChart/><c:lineChart/>"#), ChartType::Combo);
+    }
+}
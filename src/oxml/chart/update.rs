@@ -0,0 +1,297 @@
+//! In-place chart data replacement
+//!
+//! Rewrites the series of an existing chart part with new categories, names and
+//! values. Everything else in the part (series fills, markers, data labels,
+//! axes, layout) is kept byte-for-byte, so a refreshed chart keeps its styling.
+
+use super::workbook::{column_name, CellRange};
+use super::escape_xml;
+use crate::exc::PptxError;
+use crate::generator::charts::Chart;
+use crate::oxml::xmlchemy::XmlParser;
+
+/// Sheet name used by the chart's series formulas (defaults to `Sheet1`)
+pub fn data_sheet_name(xml: &str) -> String {
+    XmlParser::parse_str(xml)
+        .ok()
+        .and_then(|root| {
+            root.find_all_descendants("f")
+                .into_iter()
+                .find_map(|f| CellRange::parse(&f.text_content()).and_then(|r| r.sheet))
+        })
+        .unwrap_or_else(|| "Sheet1".to_string())
+}
+
+/// Replace the data of a chart part, keeping its formatting
+///
+/// Series are matched by position. Extra series are cloned from the last
+/// existing one (so they inherit its formatting); surplus series are removed.
+/// Formulas are rewritten to the standard layout used by
+/// [`EmbeddedWorkbook::write_chart_data`](super::EmbeddedWorkbook::write_chart_data).
+pub fn replace_chart_data(xml: &str, chart: &Chart) -> Result<String, PptxError> {
+    let root = XmlParser::parse_str(xml)?;
+    let prefix = root.tag.split_once(':').map(|(p, _)| format!("{p}:")).unwrap_or_default();
+    let sheet = quote_sheet(&data_sheet_name(xml));
+
+    let open = format!("<{prefix}ser>");
+    let close = format!("</{prefix}ser>");
+    let mut spans = Vec::new();
+    let mut pos = 0;
+    while let Some(found) = xml[pos..].find(&open) {
+        let start = pos + found;
+        let end = xml[start..].find(&close)
+            .map(|e| start + e + close.len())
+            .ok_or_else(|| PptxError::InvalidXml("Unterminated chart series".to_string()))?;
+        spans.push((start, end));
+        pos = end;
+    }
+    let Some(&(_, last_end)) = spans.last() else {
+        return Err(PptxError::InvalidOperation("Chart has no series to update".to_string()));
+    };
+
+    let ctx = SeriesContext { prefix: &prefix, sheet: &sheet, chart };
+    let mut out = String::with_capacity(xml.len());
+    let mut cursor = 0;
+    for (i, &(start, end)) in spans.iter().enumerate() {
+        out.push_str(&xml[cursor..start]);
+        if i < chart.series.len() {
+            out.push_str(&ctx.rewrite(&xml[start..end], i));
+        }
+        cursor = end;
+    }
+    let (template_start, template_end) = spans[spans.len() - 1];
+    for i in spans.len()..chart.series.len() {
+        out.push_str(&ctx.rewrite(&xml[template_start..template_end], i));
+    }
+    out.push_str(&xml[last_end..]);
+    Ok(out)
+}
+
+fn quote_sheet(name: &str) -> String {
+    if name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        name.to_string()
+    } else {
+        format!("'{}'", name.replace('\'', "''"))
+    }
+}
+
+struct SeriesContext<'a> {
+    prefix: &'a str,
+    sheet: &'a str,
+    chart: &'a Chart,
+}
+
+impl SeriesContext<'_> {
+    /// Rewrite one `c:ser` fragment as series `index` of the chart
+    fn rewrite(&self, fragment: &str, index: usize) -> String {
+        let p = self.prefix;
+        let series = &self.chart.series[index];
+        let col = column_name(index as u32 + 2);
+        let rows = series.values.len().max(self.chart.categories.len());
+        let last_row = rows + 1;
+
+        let children = child_spans(fragment);
+        let find = |names: &[&str]| {
+            children.iter().find(|(name, _, _)| names.iter().any(|n| *name == format!("{p}{n}")))
+        };
+
+        let format_code = find(&["val", "yVal"])
+            .and_then(|&(_, s, e)| {
+                let tag = format!("<{p}formatCode>");
+                let frag = &fragment[s..e];
+                let start = frag.find(&tag)? + tag.len();
+                let end = frag[start..].find('<')? + start;
+                Some(frag[start..end].to_string())
+            })
+            .unwrap_or_else(|| "General".to_string());
+
+        let tx = format!(
+            r#"<{p}tx><{p}strRef><{p}f>{}!${col}$1</{p}f><{p}strCache><{p}ptCount val="1"/><{p}pt idx="0"><{p}v>{}</{p}v></{p}pt></{p}strCache></{p}strRef></{p}tx>"#,
+            self.sheet,
+            escape_xml(&series.name)
+        );
+
+        let (cat_name, numeric_cats) = match find(&["cat", "xVal"]) {
+            Some(&(ref name, s, e)) => {
+                let numeric = fragment[s..e].contains(&format!("<{p}numRef>"))
+                    && self.chart.categories.iter().all(|c| c.trim().parse::<f64>().is_ok());
+                (name.trim_start_matches(p).to_string(), numeric)
+            }
+            None => ("cat".to_string(), false),
+        };
+        let cat = self.categories_xml(&cat_name, numeric_cats);
+
+        let val_name = find(&["val", "yVal"])
+            .map(|(name, _, _)| name.trim_start_matches(p).to_string())
+            .unwrap_or_else(|| "val".to_string());
+        let mut val = format!(
+            r#"<{p}{val_name}><{p}numRef><{p}f>{}!${col}$2:${col}${last_row}</{p}f><{p}numCache><{p}formatCode>{format_code}</{p}formatCode><{p}ptCount val="{}"/>"#,
+            self.sheet,
+            series.values.len()
+        );
+        for (i, v) in series.values.iter().enumerate() {
+            val.push_str(&format!(r#"<{p}pt idx="{i}"><{p}v>{v}</{p}v></{p}pt>"#));
+        }
+        val.push_str(&format!("</{p}numCache></{p}numRef></{p}{val_name}>"));
+
+        // Missing tx/cat elements are inserted in schema order
+        let mut out = String::with_capacity(fragment.len());
+        let mut cursor = 0;
+        let has_tx = find(&["tx"]).is_some();
+        let mut wrote_cat = self.chart.categories.is_empty();
+        for (name, start, end) in &children {
+            let local = name.trim_start_matches(p);
+            let replacement = match local {
+                "idx" => Some(format!(r#"<{p}idx val="{index}"/>"#)),
+                "order" => Some(format!(r#"<{p}order val="{index}"/>"#)),
+                "tx" => Some(tx.clone()),
+                "cat" | "xVal" if !self.chart.categories.is_empty() => {
+                    wrote_cat = true;
+                    Some(cat.clone())
+                }
+                "cat" | "xVal" => Some(String::new()),
+                "val" | "yVal" => {
+                    let mut s = String::new();
+                    if !wrote_cat {
+                        s.push_str(&cat);
+                        wrote_cat = true;
+                    }
+                    s.push_str(&val);
+                    Some(s)
+                }
+                _ => None,
+            };
+
+            out.push_str(&fragment[cursor..*start]);
+            match replacement {
+                Some(r) => out.push_str(&r),
+                None => out.push_str(&fragment[*start..*end]),
+            }
+            if local == "order" && !has_tx {
+                out.push_str(&tx);
+            }
+            cursor = *end;
+        }
+        out.push_str(&fragment[cursor..]);
+        out
+    }
+
+    fn categories_xml(&self, element: &str, numeric: bool) -> String {
+        let p = self.prefix;
+        let count = self.chart.categories.len();
+        let (reference, cache) = if numeric { ("numRef", "numCache") } else { ("strRef", "strCache") };
+
+        let mut xml = format!(
+            r#"<{p}{element}><{p}{reference}><{p}f>{}!$A$2:$A${}</{p}f><{p}{cache}>"#,
+            self.sheet,
+            count + 1
+        );
+        if numeric {
+            xml.push_str(&format!("<{p}formatCode>General</{p}formatCode>"));
+        }
+        xml.push_str(&format!(r#"<{p}ptCount val="{count}"/>"#));
+        for (i, cat) in self.chart.categories.iter().enumerate() {
+            xml.push_str(&format!(r#"<{p}pt idx="{i}"><{p}v>{}</{p}v></{p}pt>"#, escape_xml(cat)));
+        }
+        xml.push_str(&format!("</{p}{cache}></{p}{reference}></{p}{element}>"));
+        xml
+    }
+}
+
+/// Direct child elements of the root element of `fragment` as (tag, start, end)
+fn child_spans(fragment: &str) -> Vec<(String, usize, usize)> {
+    let mut children = Vec::new();
+    let mut depth = 0usize;
+    let mut current: Option<(String, usize)> = None;
+    let mut pos = 0;
+
+    while let Some(found) = fragment[pos..].find('<') {
+        let start = pos + found;
+        let Some(close) = fragment[start..].find('>') else { break };
+        let end = start + close + 1;
+        let tag = &fragment[start + 1..end - 1];
+        pos = end;
+
+        if tag.starts_with('?') || tag.starts_with('!') {
+            continue;
+        }
+        if let Some(name) = tag.strip_prefix('/') {
+            depth -= 1;
+            if depth == 1
+                && let Some((open, child_start)) = current.take()
+                && open == name.trim()
+            {
+                children.push((open, child_start, end));
+            }
+            continue;
+        }
+
+        let name = tag.split(|c: char| c.is_whitespace() || c == '/').next().unwrap_or("").to_string();
+        let self_closing = tag.ends_with('/');
+        if depth == 1 {
+            if self_closing {
+                children.push((name, start, end));
+            } else {
+                current = Some((name, start));
+            }
+        }
+        if !self_closing {
+            depth += 1;
+        }
+    }
+    children
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::charts::{ChartBuilder, ChartSeries, ChartType};
+    use crate::oxml::chart::parser::tests::BAR_CHART_XML;
+    use crate::oxml::chart::ChartParser;
+
+    fn kpi_chart() -> Chart {
+        ChartBuilder::new("Monthly Revenue", ChartType::Bar)
+            .categories(vec!["Mar", "Apr", "May"])
+            .add_series(ChartSeries::new("Revenue", vec![130.0, 140.0, 150.0]))
+            .add_series(ChartSeries::new("Target", vec![125.0, 145.0, 145.0]))
+            .build()
+    }
+
+    #[test]
+    fn test_replace_keeps_styling() {
+        let updated = replace_chart_data(BAR_CHART_XML, &kpi_chart()).unwrap();
+
+        // Formatting is kept and cloned onto the new series
+        assert_eq!(updated.matches(r#"<a:srgbClr val="4472C4"/>"#).count(), 2);
+        assert!(updated.contains(r#"<c:grouping val="stacked"/>"#));
+        assert!(updated.contains("Sheet1!$C$2:$C$4"));
+
+        let chart = ChartParser::parse(&updated, None).unwrap();
+        assert_eq!(chart.chart_type, ChartType::BarStacked);
+        assert_eq!(chart.categories, vec!["Mar", "Apr", "May"]);
+        assert_eq!(chart.series.len(), 2);
+        assert_eq!(chart.series[1].name, "Target");
+        assert_eq!(chart.series[1].values, vec![125.0, 145.0, 145.0]);
+    }
+
+    #[test]
+    fn test_replace_removes_surplus_series() {
+        let two = replace_chart_data(BAR_CHART_XML, &kpi_chart()).unwrap();
+        let one = ChartBuilder::new("", ChartType::Bar)
+            .categories(vec!["Q1"])
+            .add_series(ChartSeries::new("Only", vec![1.0]))
+            .build();
+
+        let updated = replace_chart_data(&two, &one).unwrap();
+        let chart = ChartParser::parse(&updated, None).unwrap();
+        assert_eq!(chart.series.len(), 1);
+        assert_eq!(chart.series[0].values, vec![1.0]);
+    }
+
+    #[test]
+    fn test_child_spans() {
+        let spans = child_spans(r#"<c:ser><c:idx val="0"/><c:tx><c:v>A</c:v></c:tx><c:dLbls><c:dLbl><c:tx/></c:dLbl></c:dLbls></c:ser>"#);
+        let names: Vec<&str> = spans.iter().map(|(n, _, _)| n.as_str()).collect();
+        assert_eq!(names, vec!["c:idx", "c:tx", "c:dLbls"]);
+    }
+}
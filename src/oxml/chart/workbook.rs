@@ -0,0 +1,610 @@
+//! Embedded chart workbook
+//!
+//! Charts created by PowerPoint keep their source data in an embedded xlsx
+//! package (`ppt/embeddings/*.xlsx`). Series formulas such as
+//! `Sheet1!$B$2:$B$5` point into this workbook.
+
+use std::collections::{BTreeMap, HashMap};
+use std::io::Cursor;
+
+use crate::exc::PptxError;
+use crate::generator::charts::Chart;
+use crate::opc::Package;
+use crate::oxml::xmlchemy::XmlParser;
+
+/// A parsed cell range reference (1-based, inclusive)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CellRange {
+    pub sheet: Option<String>,
+    pub start: (u32, u32),
+    pub end: (u32, u32),
+}
+
+impl CellRange {
+    /// Parse a formula like `Sheet1!$B$2:$B$5` or `'My Sheet'!$A$1`
+    pub fn parse(formula: &str) -> Option<Self> {
+        let formula = formula.trim().trim_start_matches('=');
+        let (sheet, range) = match formula.rfind('!') {
+            Some(pos) => {
+                let sheet = formula[..pos].trim_matches('\'').replace("''", "'");
+                (Some(sheet), &formula[pos + 1..])
+            }
+            None => (None, formula),
+        };
+
+        let mut parts = range.split(':');
+        let start = parse_cell_ref(parts.next()?)?;
+        let end = match parts.next() {
+            Some(end) => parse_cell_ref(end)?,
+            None => start,
+        };
+
+        Some(CellRange { sheet, start, end })
+    }
+
+    /// Cells in the range, row by row
+    pub fn cells(&self) -> Vec<(u32, u32)> {
+        let mut cells = Vec::new();
+        for row in self.start.1..=self.end.1 {
+            for col in self.start.0..=self.end.0 {
+                cells.push((col, row));
+            }
+        }
+        cells
+    }
+}
+
+/// Parse `$B$2` / `B2` into (column, row), both 1-based
+fn parse_cell_ref(cell: &str) -> Option<(u32, u32)> {
+    let cell = cell.replace('$', "");
+    let split = cell.find(|c: char| c.is_ascii_digit())?;
+    let (letters, digits) = cell.split_at(split);
+    if letters.is_empty() {
+        return None;
+    }
+
+    let mut col = 0u32;
+    for c in letters.chars() {
+        if !c.is_ascii_alphabetic() {
+            return None;
+        }
+        col = col * 26 + (c.to_ascii_uppercase() as u32 - 'A' as u32 + 1);
+    }
+    Some((col, digits.parse().ok()?))
+}
+
+/// Column letters for a 1-based column index
+pub fn column_name(mut col: u32) -> String {
+    let mut name = Vec::new();
+    while col > 0 {
+        let rem = (col - 1) % 26;
+        name.push((b'A' + rem as u8) as char);
+        col = (col - 1) / 26;
+    }
+    name.iter().rev().collect()
+}
+
+/// Embedded xlsx workbook holding chart source data
+pub struct EmbeddedWorkbook {
+    package: Package,
+    /// (sheet name, worksheet part path) in workbook order
+    sheets: Vec<(String, String)>,
+    /// Cell values per sheet, keyed by (column, row)
+    cells: HashMap<String, HashMap<(u32, u32), String>>,
+}
+
+impl EmbeddedWorkbook {
+    /// Open a workbook from xlsx bytes
+    pub fn from_bytes(data: &[u8]) -> Result<Self, PptxError> {
+        let package = Package::open_reader(Cursor::new(data))?;
+        let mut workbook = EmbeddedWorkbook {
+            package,
+            sheets: Vec::new(),
+            cells: HashMap::new(),
+        };
+        workbook.parse_sheets()?;
+        Ok(workbook)
+    }
+
+    /// Create a single-sheet workbook holding the chart's data
+    pub fn for_chart(chart: &Chart) -> Result<Self, PptxError> {
+        let mut package = Package::new();
+        package.add_part("[Content_Types].xml".to_string(), br#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/><Override PartName="/xl/worksheets/sheet1.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/></Types>"#.to_vec());
+        package.add_part("_rels/.rels".to_string(), br#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/></Relationships>"#.to_vec());
+        package.add_part("xl/workbook.xml".to_string(), br#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="Sheet1" sheetId="1" r:id="rId1"/></sheets></workbook>"#.to_vec());
+        package.add_part("xl/_rels/workbook.xml.rels".to_string(), br#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/></Relationships>"#.to_vec());
+        package.add_part("xl/worksheets/sheet1.xml".to_string(), br#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData/></worksheet>"#.to_vec());
+
+        let mut workbook = EmbeddedWorkbook {
+            package,
+            sheets: Vec::new(),
+            cells: HashMap::new(),
+        };
+        workbook.parse_sheets()?;
+        workbook.write_chart_data("Sheet1", chart)?;
+        Ok(workbook)
+    }
+
+    /// Serialize the workbook back to xlsx bytes
+    pub fn to_bytes(&self) -> Result<Vec<u8>, PptxError> {
+        let mut cursor = Cursor::new(Vec::new());
+        self.package.save_writer(&mut cursor)?;
+        Ok(cursor.into_inner())
+    }
+
+    /// Sheet names in workbook order
+    pub fn sheet_names(&self) -> Vec<&str> {
+        self.sheets.iter().map(|(name, _)| name.as_str()).collect()
+    }
+
+    /// Get a cell value as text
+    pub fn cell(&self, sheet: &str, col: u32, row: u32) -> Option<&str> {
+        self.cells.get(sheet)?.get(&(col, row)).map(|s| s.as_str())
+    }
+
+    /// Resolve a series formula to the cell values it references
+    ///
+    /// Returns `None` if the sheet does not exist or any referenced cell is empty.
+    pub fn resolve(&self, formula: &str) -> Option<Vec<String>> {
+        let range = CellRange::parse(formula)?;
+        let sheet = match range.sheet {
+            Some(ref name) => name.as_str(),
+            None => self.sheets.first()?.0.as_str(),
+        };
+        let cells = self.cells.get(sheet)?;
+        range.cells()
+            .into_iter()
+            .map(|cell| cells.get(&cell).cloned())
+            .collect()
+    }
+
+    /// Write the chart's data into a sheet in the standard chart layout
+    ///
+    /// Row 1 holds series names (from column B), column A holds categories
+    /// (from row 2). The cells are updated in place: columns, styles and
+    /// number formats stay, new cells take the style of the cell above, and
+    /// cells of the old data block outside the new one are emptied. Table
+    /// parts attached to the sheet are resized to match.
+    pub fn write_chart_data(&mut self, sheet: &str, chart: &Chart) -> Result<(), PptxError> {
+        let sheet_path = self.sheets.iter()
+            .find(|(name, _)| name == sheet)
+            .map(|(_, path)| path.clone())
+            .ok_or_else(|| PptxError::NotFound(format!("Sheet not found: {sheet}")))?;
+
+        let rows = chart.categories.len()
+            .max(chart.series.iter().map(|s| s.values.len()).max().unwrap_or(0));
+        let last_cell = format!("{}{}", column_name(chart.series.len() as u32 + 1), rows + 1);
+
+        let mut cells = HashMap::new();
+        cells.insert((1, 1), " ".to_string());
+        for (i, series) in chart.series.iter().enumerate() {
+            cells.insert((i as u32 + 2, 1), series.name.clone());
+            for (r, value) in series.values.iter().enumerate() {
+                cells.insert((i as u32 + 2, r as u32 + 2), value.to_string());
+            }
+        }
+        for (r, category) in chart.categories.iter().enumerate() {
+            cells.insert((1, r as u32 + 2), category.clone());
+        }
+
+        let old_xml = self.package.get_part_string(&sheet_path).unwrap_or_default();
+        // The block the old data filled: the table over it, or the used range
+        let old_block = self.table_refs(&sheet_path).into_iter().next()
+            .or_else(|| old_xml.find("<dimension").and_then(|start| tag_attr(&old_xml[start..], "ref").map(str::to_string)))
+            .and_then(|range| CellRange::parse(&range));
+        let (data_start, data_end) = sheet_data_span(&old_xml)
+            .ok_or_else(|| PptxError::InvalidValue(format!("{sheet_path} has no sheetData")))?;
+        let mut sheet_rows = parse_rows(&old_xml[data_start..data_end])
+            .ok_or_else(|| PptxError::InvalidValue(format!("{sheet_path} has malformed rows")))?;
+
+        // Empty the old cells that the new data does not cover
+        let mut values = self.cells.remove(sheet).unwrap_or_default();
+        if let Some(block) = old_block {
+            for pos in block.cells().into_iter().filter(|pos| !cells.contains_key(pos)) {
+                values.remove(&pos);
+                let Some(row) = sheet_rows.get_mut(&pos.1) else { continue };
+                let Some(cell) = row.cells.remove(&pos.0) else { continue };
+                let tag = without_attr(&without_attr(start_tag(&cell), "t"), "cm");
+                if tag_attr(&tag, "s").is_some() {
+                    row.cells.insert(pos.0, format!("{tag}/>"));
+                }
+            }
+        }
+
+        for (&(col, row), value) in &cells {
+            let style = sheet_rows.range(2..row.max(2))
+                .rev()
+                .find_map(|(_, above)| above.cells.get(&col).and_then(|cell| tag_attr(start_tag(cell), "s")))
+                .map(str::to_string);
+            let sheet_row = sheet_rows.entry(row).or_insert_with(|| SheetRow { tag: format!(r#"<row r="{row}""#), cells: BTreeMap::new() });
+            let tag = match sheet_row.cells.get(&col) {
+                Some(cell) => without_attr(&without_attr(start_tag(cell), "t"), "cm"),
+                None => {
+                    let style = style.map(|s| format!(r#" s="{s}""#)).unwrap_or_default();
+                    format!(r#"<c r="{}{row}"{style}"#, column_name(col))
+                }
+            };
+            let cell = if row > 1 && col > 1 {
+                format!("{tag}><v>{value}</v></c>")
+            } else {
+                format!(r#"{tag} t="inlineStr"><is><t xml:space="preserve">{}</t></is></c>"#, escape_xml(value))
+            };
+            sheet_row.cells.insert(col, cell);
+            values.insert((col, row), value.clone());
+        }
+
+        let mut sheet_data = String::from("<sheetData>");
+        for row in sheet_rows.values() {
+            // Cell spans are an optional hint that may no longer hold
+            sheet_data.push_str(&without_attr(&row.tag, "spans"));
+            sheet_data.push('>');
+            sheet_data.extend(row.cells.values().map(String::as_str));
+            sheet_data.push_str("</row>");
+        }
+        sheet_data.push_str("</sheetData>");
+
+        let mut xml = format!("{}{sheet_data}{}", &old_xml[..data_start], &old_xml[data_end..]);
+        let used = sheet_rows.iter()
+            .filter_map(|(&row, sheet_row)| Some((*sheet_row.cells.keys().next_back()?, row)))
+            .fold((1, 1), |(cols, rows), (col, row)| (cols.max(col), rows.max(row)));
+        if let Some(start) = xml.find("<dimension") {
+            let end = start + xml[start..].find('>').map_or(0, |end| end + 1);
+            xml.replace_range(start..end, &format!(r#"<dimension ref="A1:{}{}"/>"#, column_name(used.0), used.1));
+        }
+        self.package.add_part(sheet_path.clone(), xml.into_bytes());
+        self.cells.insert(sheet.to_string(), values);
+
+        let mut header: Vec<String> = Vec::new();
+        for col in 1..=chart.series.len() as u32 + 1 {
+            let mut name = cells.get(&(col, 1)).cloned().unwrap_or_default();
+            if name.is_empty() {
+                name = format!("Column{col}");
+            }
+            // Table column names must be unique
+            let base = name.clone();
+            let mut n = 2;
+            while header.contains(&name) {
+                name = format!("{base}{n}");
+                n += 1;
+            }
+            header.push(name);
+        }
+        self.resize_tables(&sheet_path, &format!("A1:{last_cell}"), &header)
+    }
+
+    /// Ranges of the table parts attached to a sheet
+    fn table_refs(&self, sheet_path: &str) -> Vec<String> {
+        self.package.part_relationships(sheet_path)
+            .into_iter()
+            .filter(|rel| rel.rel_type.ends_with("/table"))
+            .filter_map(|rel| {
+                let xml = self.package.get_part_string(&rel.target)?;
+                let start = xml.find("<table")?;
+                tag_attr(&xml[start..], "ref").map(str::to_string)
+            })
+            .collect()
+    }
+
+    fn parse_sheets(&mut self) -> Result<(), PptxError> {
+        let shared_strings = self.parse_shared_strings();
+
+        let rels: HashMap<String, String> = self.package.part_relationships("xl/workbook.xml")
+            .into_iter()
+            .map(|rel| (rel.id, rel.target))
+            .collect();
+
+        let workbook_xml = self.package.get_part_string("xl/workbook.xml")
+            .ok_or_else(|| PptxError::NotFound("xl/workbook.xml".to_string()))?;
+        let root = XmlParser::parse_str(&workbook_xml)?;
+
+        for sheet in root.find_all_descendants("sheet") {
+            let (Some(name), Some(r_id)) = (sheet.attr("name"), sheet.attr("r:id")) else { continue };
+            if let Some(path) = rels.get(r_id) {
+                self.sheets.push((name.to_string(), path.clone()));
+            }
+        }
+
+        for (name, path) in &self.sheets {
+            let Some(xml) = self.package.get_part_string(path) else { continue };
+            let root = XmlParser::parse_str(&xml)?;
+            let mut cells = HashMap::new();
+            for c in root.find_all_descendants("c") {
+                let Some(pos) = c.attr("r").and_then(parse_cell_ref) else { continue };
+                let value = match c.attr("t") {
+                    Some("s") => c.find("v")
+                        .and_then(|v| v.text_content().trim().parse::<usize>().ok())
+                        .and_then(|i| shared_strings.get(i).cloned()),
+                    Some("inlineStr") => c.find("is").map(|is| is.text_content()),
+                    _ => c.find("v").map(|v| v.text_content()),
+                };
+                if let Some(value) = value {
+                    cells.insert(pos, value);
+                }
+            }
+            self.cells.insert(name.clone(), cells);
+        }
+        Ok(())
+    }
+
+    fn parse_shared_strings(&self) -> Vec<String> {
+        let Some(xml) = self.package.get_part_string("xl/sharedStrings.xml") else {
+            return Vec::new();
+        };
+        let Ok(root) = XmlParser::parse_str(&xml) else {
+            return Vec::new();
+        };
+        root.find_all("si")
+            .into_iter()
+            .map(|si| {
+                // Rich text strings split into runs; phonetic runs are not displayed
+                si.find_all_descendants("t")
+                    .into_iter()
+                    .map(|t| t.text_content())
+                    .collect::<String>()
+            })
+            .collect()
+    }
+
+    fn resize_tables(&mut self, sheet_path: &str, range: &str, header: &[String]) -> Result<(), PptxError> {
+        let table_paths: Vec<String> = self.package.part_relationships(sheet_path)
+            .into_iter()
+            .filter(|rel| rel.rel_type.ends_with("/table"))
+            .map(|rel| rel.target)
+            .collect();
+
+        for path in table_paths {
+            let Some(xml) = self.package.get_part_string(&path) else { continue };
+            let root = XmlParser::parse_str(&xml)?;
+
+            let mut table = format!(
+                r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<table xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" id="{}" name="{}" displayName="{}" ref="{range}" totalsRowShown="0"><tableColumns count="{}">"#,
+                root.attr("id").unwrap_or("1"),
+                escape_xml(root.attr("name").unwrap_or("Table1")),
+                escape_xml(root.attr("displayName").unwrap_or("Table1")),
+                header.len()
+            );
+            for (i, name) in header.iter().enumerate() {
+                table.push_str(&format!(r#"<tableColumn id="{}" name="{}"/>"#, i + 1, escape_xml(name)));
+            }
+            table.push_str("</tableColumns>");
+            if let Some(style) = root.find("tableStyleInfo") {
+                table.push_str("<tableStyleInfo");
+                for attr in ["name", "showFirstColumn", "showLastColumn", "showRowStripes", "showColumnStripes"] {
+                    if let Some(value) = style.attr(attr) {
+                        table.push_str(&format!(r#" {attr}="{}""#, escape_xml(value)));
+                    }
+                }
+                table.push_str("/>");
+            }
+            table.push_str("</table>");
+            self.package.add_part(path, table.into_bytes());
+        }
+        Ok(())
+    }
+}
+
+/// A worksheet row: its start tag without the closing `>`, and its cells'
+/// XML by column
+struct SheetRow {
+    tag: String,
+    cells: BTreeMap<u32, String>,
+}
+
+/// Byte span of the `sheetData` element in a worksheet
+fn sheet_data_span(xml: &str) -> Option<(usize, usize)> {
+    let start = xml.find("<sheetData")?;
+    let tag_end = start + xml[start..].find('>')? + 1;
+    if xml[..tag_end].ends_with("/>") {
+        return Some((start, tag_end));
+    }
+    let end = tag_end + xml[tag_end..].find("</sheetData>")? + "</sheetData>".len();
+    Some((start, end))
+}
+
+/// Rows and cells of a `sheetData` element, numbering those without `r`
+/// after the one before
+fn parse_rows(sheet_data: &str) -> Option<BTreeMap<u32, SheetRow>> {
+    let mut rows = BTreeMap::new();
+    let mut rest = sheet_data;
+    let mut last_row = 0;
+    while let Some(start) = rest.find("<row") {
+        rest = &rest[start..];
+        let tag = rest[..rest.find('>')?].trim_end_matches('/');
+        let (body, len) = match rest[..=tag.len()].ends_with('>') {
+            true => {
+                let close = rest.find("</row>")?;
+                (&rest[tag.len() + 1..close], close + "</row>".len())
+            }
+            false => ("", rest.find('>')? + 1),
+        };
+        last_row = tag_attr(tag, "r").and_then(|r| r.parse().ok()).unwrap_or(last_row + 1);
+
+        let mut row = SheetRow { tag: tag.to_string(), cells: BTreeMap::new() };
+        let mut cells = body;
+        let mut last_col = 0;
+        while let Some(start) = cells.find("<c") {
+            cells = &cells[start..];
+            let tag_end = cells.find('>')? + 1;
+            let len = if cells[..tag_end].ends_with("/>") { tag_end } else { cells.find("</c>")? + "</c>".len() };
+            last_col = tag_attr(start_tag(&cells[..len]), "r")
+                .and_then(parse_cell_ref)
+                .map_or(last_col + 1, |(col, _)| col);
+            row.cells.insert(last_col, cells[..len].to_string());
+            cells = &cells[len..];
+        }
+        if tag_attr(tag, "r").is_none() {
+            row.tag = format!(r#"{tag} r="{last_row}""#);
+        }
+        rows.insert(last_row, row);
+        rest = &rest[len..];
+    }
+    Some(rows)
+}
+
+/// Start tag of an element, without its closing `>` or `/>`
+fn start_tag(xml: &str) -> &str {
+    xml[..xml.find('>').unwrap_or(xml.len())].trim_end_matches('/')
+}
+
+/// Value of an attribute in a start tag
+fn tag_attr<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let needle = format!(r#" {name}=""#);
+    let start = tag[..tag.find('>').unwrap_or(tag.len())].find(&needle)? + needle.len();
+    Some(&tag[start..start + tag[start..].find('"')?])
+}
+
+/// A start tag without the given attribute
+fn without_attr(tag: &str, name: &str) -> String {
+    let needle = format!(r#" {name}=""#);
+    match tag.find(&needle) {
+        Some(start) => {
+            let value = start + needle.len();
+            let end = tag[value..].find('"').map_or(tag.len(), |end| value + end + 1);
+            format!("{}{}", &tag[..start], &tag[end..])
+        }
+        None => tag.to_string(),
+    }
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::generator::charts::{ChartBuilder, ChartSeries, ChartType};
+
+    /// Build a minimal xlsx with one sheet holding a 2x2 chart data block
+    pub(crate) fn sample_workbook() -> Vec<u8> {
+        let mut package = Package::new();
+        package.add_part("[Content_Types].xml".to_string(), br#"<?xml version="1.0" encoding="UTF-8"?><Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"/>"#.to_vec());
+        package.add_part("xl/workbook.xml".to_string(), br#"<?xml version="1.0" encoding="UTF-8"?>
+<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="Sheet1" sheetId="1" r:id="rId1"/></sheets></workbook>"#.to_vec());
+        package.add_part("xl/_rels/workbook.xml.rels".to_string(), br#"<?xml version="1.0" encoding="UTF-8"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/></Relationships>"#.to_vec());
+        package.add_part("xl/sharedStrings.xml".to_string(), br#"<?xml version="1.0" encoding="UTF-8"?>
+<sst xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><si><t>Revenue</t></si><si><t>Jan</t></si><si><r><t>Fe</t></r><r><t>b</t></r></si></sst>"#.to_vec());
+        package.add_part("xl/worksheets/sheet1.xml".to_string(), br#"<?xml version="1.0" encoding="UTF-8"?>
+<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheetData>
+<row r="1"><c r="B1" t="s"><v>0</v></c></row>
+<row r="2"><c r="A2" t="s"><v>1</v></c><c r="B2"><v>120</v></c></row>
+<row r="3"><c r="A3" t="s"><v>2</v></c><c r="B3"><v>135.5</v></c></row>
+</sheetData><tableParts count="1"><tablePart r:id="rId1"/></tableParts></worksheet>"#.to_vec());
+        package.add_part("xl/worksheets/_rels/sheet1.xml.rels".to_string(), br#"<?xml version="1.0" encoding="UTF-8"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/table" Target="../tables/table1.xml"/></Relationships>"#.to_vec());
+        package.add_part("xl/tables/table1.xml".to_string(), br#"<?xml version="1.0" encoding="UTF-8"?>
+<table xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" id="1" name="Table1" displayName="Table1" ref="A1:B3" totalsRowShown="0"><tableColumns count="2"><tableColumn id="1" name=" "/><tableColumn id="2" name="Revenue"/></tableColumns><tableStyleInfo name="TableStyleMedium2" showRowStripes="1"/></table>"#.to_vec());
+
+        let mut cursor = Cursor::new(Vec::new());
+        package.save_writer(&mut cursor).unwrap();
+        cursor.into_inner()
+    }
+
+    #[test]
+    fn test_workbook_for_chart() {
+        let chart = ChartBuilder::new("Pets", ChartType::Pie)
+            .categories(vec!["Dogs", "Cats"])
+            .add_series(ChartSeries::new("Count", vec![45.0, 30.5]))
+            .build();
+        let bytes = EmbeddedWorkbook::for_chart(&chart).unwrap().to_bytes().unwrap();
+        let workbook = EmbeddedWorkbook::from_bytes(&bytes).unwrap();
+        assert_eq!(workbook.sheet_names(), vec!["Sheet1"]);
+        assert_eq!(workbook.resolve("Sheet1!$B$1"), Some(vec!["Count".to_string()]));
+        assert_eq!(workbook.resolve("Sheet1!$A$2:$A$3"), Some(vec!["Dogs".to_string(), "Cats".to_string()]));
+        assert_eq!(workbook.resolve("Sheet1!$B$2:$B$3"), Some(vec!["45".to_string(), "30.5".to_string()]));
+    }
+
+    #[test]
+    fn test_cell_range_parse() {
+        let range = CellRange::parse("'Q1 Data'!$B$2:$C$5").unwrap();
+        assert_eq!(range.sheet.as_deref(), Some("Q1 Data"));
+        assert_eq!(range.start, (2, 2));
+        assert_eq!(range.end, (3, 5));
+        assert_eq!(range.cells().len(), 8);
+
+        let single = CellRange::parse("Sheet1!$AA$1").unwrap();
+        assert_eq!(single.start, (27, 1));
+        assert_eq!(single.end, (27, 1));
+    }
+
+    #[test]
+    fn test_column_name() {
+        assert_eq!(column_name(1), "A");
+        assert_eq!(column_name(26), "Z");
+        assert_eq!(column_name(27), "AA");
+    }
+
+    #[test]
+    fn test_resolve_formula() {
+        let workbook = EmbeddedWorkbook::from_bytes(&sample_workbook()).unwrap();
+        assert_eq!(workbook.sheet_names(), vec!["Sheet1"]);
+        assert_eq!(workbook.resolve("Sheet1!$B$1"), Some(vec!["Revenue".to_string()]));
+        assert_eq!(workbook.resolve("Sheet1!$A$2:$A$3"), Some(vec!["Jan".to_string(), "Feb".to_string()]));
+        assert_eq!(workbook.resolve("Sheet1!$B$2:$B$3"), Some(vec!["120".to_string(), "135.5".to_string()]));
+        assert_eq!(workbook.resolve("Sheet1!$B$2:$B$9"), None);
+        assert_eq!(workbook.resolve("Missing!$A$1"), None);
+    }
+
+    #[test]
+    fn test_write_chart_data() {
+        let mut workbook = EmbeddedWorkbook::from_bytes(&sample_workbook()).unwrap();
+        let chart = ChartBuilder::new("KPIs", ChartType::Bar)
+            .categories(vec!["Jan", "Feb", "Mar"])
+            .add_series(ChartSeries::new("Revenue", vec![1.0, 2.0, 3.0]))
+            .add_series(ChartSeries::new("Costs", vec![0.5, 1.5, 2.5]))
+            .build();
+        workbook.write_chart_data("Sheet1", &chart).unwrap();
+
+        let reopened = EmbeddedWorkbook::from_bytes(&workbook.to_bytes().unwrap()).unwrap();
+        assert_eq!(reopened.resolve("Sheet1!$C$1"), Some(vec!["Costs".to_string()]));
+        assert_eq!(reopened.resolve("Sheet1!$A$4"), Some(vec!["Mar".to_string()]));
+        assert_eq!(reopened.resolve("Sheet1!$C$2:$C$4"), Some(vec!["0.5".to_string(), "1.5".to_string(), "2.5".to_string()]));
+
+        let table = reopened.package.get_part_string("xl/tables/table1.xml").unwrap();
+        assert!(table.contains(r#"ref="A1:C4""#));
+        assert!(table.contains(r#"<tableColumn id="3" name="Costs"/>"#));
+        assert!(table.contains("TableStyleMedium2"));
+    }
+
+    #[test]
+    fn test_write_chart_data_keeps_formatting() {
+        let mut workbook = EmbeddedWorkbook::from_bytes(&sample_workbook()).unwrap();
+        workbook.package.add_part("xl/worksheets/sheet1.xml".to_string(), br#"<?xml version="1.0" encoding="UTF-8"?>
+<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><dimension ref="A1:D3"/><cols><col min="1" max="1" width="18" customWidth="1"/></cols><sheetData>
+<row r="1" spans="1:4"><c r="B1" t="s"><v>0</v></c><c r="D1" t="inlineStr"><is><t>Note</t></is></c></row>
+<row r="2" spans="1:4" ht="20" customHeight="1"><c r="A2" t="s"><v>1</v></c><c r="B2" s="1"><v>120</v></c></row>
+<row r="3" spans="1:4"><c r="A3" t="s"><v>2</v></c><c r="B3" s="1"><v>135.5</v></c></row>
+</sheetData><pageMargins left="0.7" right="0.7" top="0.75" bottom="0.75" header="0.3" footer="0.3"/><tableParts count="1"><tablePart r:id="rId1"/></tableParts></worksheet>"#.to_vec());
+        let chart = ChartBuilder::new("KPIs", ChartType::Bar)
+            .categories(vec!["Jan", "Feb", "Mar"])
+            .add_series(ChartSeries::new("Revenue", vec![1.0, 2.0, 3.0]))
+            .build();
+        workbook.write_chart_data("Sheet1", &chart).unwrap();
+
+        let sheet = workbook.package.get_part_string("xl/worksheets/sheet1.xml").unwrap();
+        assert!(sheet.contains(r#"<cols><col min="1" max="1" width="18" customWidth="1"/></cols>"#));
+        assert!(sheet.contains(r#"<pageMargins left="0.7""#));
+        assert!(sheet.contains(r#"<dimension ref="A1:D4"/>"#));
+        assert!(sheet.contains(r#"<row r="2" ht="20" customHeight="1">"#));
+        assert!(sheet.contains(r#"<c r="B2" s="1"><v>1</v></c>"#));
+        // New rows take the number format of the cell above
+        assert!(sheet.contains(r#"<c r="B4" s="1"><v>3</v></c>"#));
+        // Cells outside the old data block are left alone
+        assert!(sheet.contains(r#"<c r="D1" t="inlineStr"><is><t>Note</t></is></c>"#));
+
+        let reopened = EmbeddedWorkbook::from_bytes(&workbook.to_bytes().unwrap()).unwrap();
+        assert_eq!(reopened.resolve("Sheet1!$B$2:$B$4"), Some(vec!["1".to_string(), "2".to_string(), "3".to_string()]));
+        assert_eq!(reopened.resolve("Sheet1!$A$4"), Some(vec!["Mar".to_string()]));
+        assert_eq!(reopened.resolve("Sheet1!$D$1"), Some(vec!["Note".to_string()]));
+    }
+}
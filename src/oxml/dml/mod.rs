@@ -514,190 +514,236 @@ impl Size {
     }
 }
 
-/// Shadow effect
-#[derive(Debug, Clone)]
+/// Convert a 0-100 transparency percentage to an OOXML alpha value
+fn alpha_from_transparency(percent: u32) -> u32 {
+    (100 - percent.min(100)) * 1000
+}
+
+fn color_xml(color: &str, alpha: u32) -> String {
+    if alpha >= 100000 {
+        format!(r#"<a:srgbClr val="{color}"/>"#)
+    } else {
+        format!(r#"<a:srgbClr val="{color}"><a:alpha val="{alpha}"/></a:srgbClr>"#)
+    }
+}
+
+/// Outer or inner shadow (a:outerShdw / a:innerShdw)
+#[derive(Clone, Debug, PartialEq)]
 pub struct Shadow {
-    pub color: Option<Color>,
-    pub blur_radius: Option<u32>, // in EMU
-    pub distance: Option<u32>,     // in EMU
-    pub angle: Option<i32>,        // in 60000ths of a degree
-    pub offset_x: Option<i64>,     // in EMU
-    pub offset_y: Option<i64>,     // in EMU
+    /// Shadow color (RGB hex)
+    pub color: String,
+    /// Opacity (0-100000, where 100000 = fully opaque)
+    pub alpha: u32,
+    /// Blur radius in EMU
+    pub blur: u32,
+    /// Offset distance in EMU
+    pub distance: u32,
+    /// Direction of the offset in degrees (0 = right, 90 = down)
+    pub angle: u32,
 }
 
 impl Shadow {
-    pub fn new() -> Self {
+    /// Create a shadow with PowerPoint's "offset diagonal bottom right" defaults
+    pub fn new(color: &str) -> Self {
         Shadow {
-            color: None,
-            blur_radius: None,
-            distance: None,
-            angle: None,
-            offset_x: None,
-            offset_y: None,
+            color: color.trim_start_matches('#').to_uppercase(),
+            alpha: 40000,
+            blur: 50800,
+            distance: 38100,
+            angle: 45,
         }
     }
 
-    pub fn with_color(mut self, color: Color) -> Self {
-        self.color = Some(color);
+    /// Set blur radius in EMU
+    pub fn with_blur(mut self, blur: u32) -> Self {
+        self.blur = blur;
         self
     }
 
-    pub fn with_blur(mut self, radius: u32) -> Self {
-        self.blur_radius = Some(radius);
+    /// Set offset distance in EMU
+    pub fn with_distance(mut self, distance: u32) -> Self {
+        self.distance = distance;
         self
     }
 
-    pub fn with_distance(mut self, distance: u32) -> Self {
-        self.distance = Some(distance);
+    /// Set offset direction in degrees
+    pub fn with_angle(mut self, degrees: u32) -> Self {
+        self.angle = degrees % 360;
         self
     }
 
-    pub fn with_angle(mut self, degrees: i32) -> Self {
-        self.angle = Some(degrees * 60000);
+    /// Set transparency (0-100 percent)
+    pub fn with_transparency(mut self, percent: u32) -> Self {
+        self.alpha = alpha_from_transparency(percent);
         self
     }
 
+    /// Set the color; scheme and system colors keep the current color
+    #[deprecated(since = "0.3.0", note = "pass the RGB color to `Shadow::new`")]
+    pub fn with_color(mut self, color: Color) -> Self {
+        if let Color::Rgb(hex) = color {
+            self.color = hex.trim_start_matches('#').to_uppercase();
+        }
+        self
+    }
+
+    /// Set the offset in EMU as x and y
+    #[deprecated(since = "0.3.0", note = "use `with_distance` and `with_angle`")]
     pub fn with_offset(mut self, x: i64, y: i64) -> Self {
-        self.offset_x = Some(x);
-        self.offset_y = Some(y);
+        let (x, y) = (x as f64, y as f64);
+        self.distance = x.hypot(y).round() as u32;
+        self.angle = (y.atan2(x).to_degrees().round() as i32).rem_euclid(360) as u32;
         self
     }
 
+    /// Generate the outer (drop) shadow XML
     pub fn to_xml(&self) -> String {
-        let mut attrs = Vec::new();
-        
-        if let Some(blur) = self.blur_radius {
-            attrs.push(format!(r#"blurRad="{blur}""#));
-        }
-        if let Some(dist) = self.distance {
-            attrs.push(format!(r#"dist="{dist}""#));
-        }
-        if let Some(angle) = self.angle {
-            attrs.push(format!(r#"dir="{angle}""#));
-        }
+        self.element_xml("outerShdw")
+    }
 
-        let attr_str = if attrs.is_empty() {
-            String::new()
-        } else {
-            format!(" {}", attrs.join(" "))
-        };
+    /// Generate the inner shadow XML
+    pub fn to_inner_xml(&self) -> String {
+        self.element_xml("innerShdw")
+    }
 
-        let mut inner = String::new();
-        if let Some(ref color) = self.color {
-            inner.push_str("<a:srgbClr>");
-            inner.push_str(&color.to_xml());
-            inner.push_str("</a:srgbClr>");
-        }
+    fn element_xml(&self, element: &str) -> String {
+        let align = if element == "outerShdw" { r#" algn="tl" rotWithShape="0""# } else { "" };
+        format!(
+            r#"<a:{element} blurRad="{}" dist="{}" dir="{}"{align}>{}</a:{element}>"#,
+            self.blur,
+            self.distance,
+            self.angle * 60000,
+            color_xml(&self.color, self.alpha)
+        )
+    }
+}
 
-        if let (Some(x), Some(y)) = (self.offset_x, self.offset_y) {
-            format!(
-                r#"<a:outerShdw{attr_str}><a:off x="{x}" y="{y}"/>{inner}</a:outerShdw>"#
-            )
-        } else {
-            format!(r#"<a:outerShdw{attr_str}>{inner}</a:outerShdw>"#)
-        }
+impl Default for Shadow {
+    fn default() -> Self {
+        Self::new("000000")
     }
 }
 
-/// Glow effect
-#[derive(Debug, Clone)]
+/// Colored glow around the shape outline (a:glow)
+#[derive(Clone, Debug, PartialEq)]
 pub struct Glow {
-    pub color: Option<Color>,
-    pub radius: Option<u32>, // in EMU
+    /// Glow color (RGB hex)
+    pub color: String,
+    /// Opacity (0-100000, where 100000 = fully opaque)
+    pub alpha: u32,
+    /// Glow radius in EMU
+    pub radius: u32,
 }
 
 impl Glow {
-    pub fn new() -> Self {
+    /// Create a glow with the given color and radius (EMU)
+    pub fn new(color: &str, radius: u32) -> Self {
         Glow {
-            color: None,
-            radius: None,
+            color: color.trim_start_matches('#').to_uppercase(),
+            alpha: 60000,
+            radius,
         }
     }
 
+    /// Set transparency (0-100 percent)
+    pub fn with_transparency(mut self, percent: u32) -> Self {
+        self.alpha = alpha_from_transparency(percent);
+        self
+    }
+
+    /// Set the color; scheme and system colors keep the current color
+    #[deprecated(since = "0.3.0", note = "pass the RGB color to `Glow::new`")]
     pub fn with_color(mut self, color: Color) -> Self {
-        self.color = Some(color);
+        if let Color::Rgb(hex) = color {
+            self.color = hex.trim_start_matches('#').to_uppercase();
+        }
         self
     }
 
+    /// Set the glow radius in EMU
+    #[deprecated(since = "0.3.0", note = "pass the radius to `Glow::new`")]
     pub fn with_radius(mut self, radius: u32) -> Self {
-        self.radius = Some(radius);
+        self.radius = radius;
         self
     }
 
     pub fn to_xml(&self) -> String {
-        let radius_attr = self.radius
-            .map(|r| format!(r#" rad="{r}""#))
-            .unwrap_or_default();
-
-        let mut inner = String::new();
-        if let Some(ref color) = self.color {
-            inner.push_str("<a:srgbClr>");
-            inner.push_str(&color.to_xml());
-            inner.push_str("</a:srgbClr>");
-        }
+        format!(r#"<a:glow rad="{}">{}</a:glow>"#, self.radius, color_xml(&self.color, self.alpha))
+    }
+}
 
-        format!(r#"<a:glow{radius_attr}>{inner}</a:glow>"#)
+impl Default for Glow {
+    fn default() -> Self {
+        Self::new("000000", 0)
     }
 }
 
-/// Reflection effect
-#[derive(Debug, Clone)]
+/// Mirror reflection below the shape (a:reflection)
+#[derive(Clone, Debug, PartialEq)]
 pub struct Reflection {
-    pub blur_radius: Option<u32>, // in EMU
-    pub distance: Option<u32>,    // in EMU
-    pub alpha: Option<u32>,        // 0-100000 (transparency)
+    /// Blur radius in EMU
+    pub blur: u32,
+    /// Gap between the shape and its reflection in EMU
+    pub distance: u32,
+    /// Opacity at the start of the reflection (0-100000)
+    pub start_alpha: u32,
+    /// Reflection size as a fraction of the shape (0-100000)
+    pub size: u32,
 }
 
 impl Reflection {
+    /// Create a "tight reflection, touching" style reflection
     pub fn new() -> Self {
         Reflection {
-            blur_radius: None,
-            distance: None,
-            alpha: None,
+            blur: 6350,
+            distance: 0,
+            start_alpha: 50000,
+            size: 35000,
         }
     }
 
-    pub fn with_blur(mut self, radius: u32) -> Self {
-        self.blur_radius = Some(radius);
+    /// Set blur radius in EMU
+    pub fn with_blur(mut self, blur: u32) -> Self {
+        self.blur = blur;
         self
     }
 
+    /// Set gap between shape and reflection in EMU
     pub fn with_distance(mut self, distance: u32) -> Self {
-        self.distance = Some(distance);
+        self.distance = distance;
         self
     }
 
-    pub fn with_alpha(mut self, alpha: u32) -> Self {
-        self.alpha = Some(alpha.min(100000));
+    /// Set reflection size (0-100 percent of the shape)
+    pub fn with_size(mut self, percent: u32) -> Self {
+        self.size = percent.min(100) * 1000;
         self
     }
 
-    pub fn to_xml(&self) -> String {
-        let mut attrs = Vec::new();
-        
-        if let Some(blur) = self.blur_radius {
-            attrs.push(format!(r#"blurRad="{blur}""#));
-        }
-        if let Some(dist) = self.distance {
-            attrs.push(format!(r#"dist="{dist}""#));
-        }
+    /// Set transparency of the reflection start (0-100 percent)
+    pub fn with_transparency(mut self, percent: u32) -> Self {
+        self.start_alpha = alpha_from_transparency(percent);
+        self
+    }
 
-        let attr_str = if attrs.is_empty() {
-            String::new()
-        } else {
-            format!(" {}", attrs.join(" "))
-        };
+    /// Set the opacity of the reflection start (0-100000)
+    #[deprecated(since = "0.3.0", note = "use `with_transparency`")]
+    pub fn with_alpha(mut self, alpha: u32) -> Self {
+        self.start_alpha = alpha.min(100000);
+        self
+    }
 
-        let mut inner = String::new();
-        if let Some(alpha) = self.alpha {
-            inner.push_str(&format!(r#"<a:alpha val="{alpha}"/>"#));
-        }
+    pub fn to_xml(&self) -> String {
+        format!(
+            r#"<a:reflection blurRad="{}" stA="{}" endA="300" endPos="{}" dist="{}" dir="5400000" sy="-100000" algn="bl" rotWithShape="0"/>"#,
+            self.blur, self.start_alpha, self.size, self.distance
+        )
+    }
+}
 
-        if inner.is_empty() {
-            format!(r#"<a:reflection{attr_str}/>"#)
-        } else {
-            format!(r#"<a:reflection{attr_str}>{inner}</a:reflection>"#)
-        }
+impl Default for Reflection {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -713,6 +759,29 @@ mod tests {
         assert!(xml.contains("FF0000"));
     }
 
+    #[test]
+    #[allow(deprecated)]
+    fn test_deprecated_effect_builders() {
+        let shadow = Shadow::default().with_color(Color::rgb("#ff0000")).with_offset(0, 38100);
+        assert_eq!((shadow.color.as_str(), shadow.distance, shadow.angle), ("FF0000", 38100, 90));
+        assert_eq!(Shadow::default().with_offset(-100, 0).angle, 180);
+        let glow = Glow::default().with_color(Color::rgb("00FF00")).with_radius(50800);
+        assert_eq!(glow, Glow::new("00FF00", 50800));
+        assert_eq!(Reflection::new().with_alpha(250000).start_alpha, 100000);
+    }
+
+    #[test]
+    fn test_effects_xml() {
+        assert_eq!(
+            Glow::new("#ffc000", 63500).to_xml(),
+            r#"<a:glow rad="63500"><a:srgbClr val="FFC000"><a:alpha val="60000"/></a:srgbClr></a:glow>"#
+        );
+        let shadow = Shadow::default().with_transparency(0);
+        assert!(shadow.to_xml().starts_with(r#"<a:outerShdw blurRad="50800" dist="38100" dir="2700000" algn="tl""#));
+        assert!(shadow.to_inner_xml().ends_with(r#"<a:srgbClr val="000000"/></a:innerShdw>"#));
+        assert!(Reflection::new().with_size(50).to_xml().contains(r#"endPos="50000""#));
+    }
+
     #[test]
     fn test_color_scheme() {
         let color = Color::scheme("accent1");
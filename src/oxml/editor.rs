@@ -4,14 +4,40 @@
 //! - Add new slides
 //! - Update slide content
 //! - Remove slides
+//! - Refresh chart data
+//! - Set speaker notes
 //! - Modify presentation properties
 
+use super::chart::{replace_chart_data, ChartParser};
+use super::chart::update::data_sheet_name;
+use super::notes::{NotesParser, ParsedNotes};
 use super::slide::{ParsedSlide, SlideParser};
+use super::xmlchemy::XmlParser;
 use crate::exc::PptxError;
+use crate::generator::charts::Chart;
+use crate::generator::notes_xml::{create_notes_master_rels_xml, create_notes_master_xml, create_rich_notes_rels_xml, create_rich_notes_xml};
+use crate::generator::package_xml::{add_notes_master_id, escape_xml};
 use crate::generator::slide_content::SlideContent;
-use crate::generator::slide_xml::{create_slide_xml_with_content, create_slide_rels_xml};
+use crate::generator::slide_xml::create_slide_xml_with_content;
+use crate::generator::slide_rels::{SlideRelationships, slide_media};
+use crate::generator::images_xml::generate_image_content_type;
+use crate::generator::text::TextFrame;
+use crate::opc::constants::{CONTENT_TYPE, RELATIONSHIP_TYPE};
+use crate::opc::packuri::rels_path;
 use crate::opc::Package;
 
+const NOTES_MASTER_PATH: &str = "ppt/notesMasters/notesMaster1.xml";
+
+/// Overrides always written by `rebuild_content_types`
+const FIXED_OVERRIDES: [&str; 6] = [
+    "/ppt/presentation.xml",
+    "/ppt/slideLayouts/slideLayout1.xml",
+    "/ppt/slideMasters/slideMaster1.xml",
+    "/ppt/theme/theme1.xml",
+    "/docProps/core.xml",
+    "/docProps/app.xml",
+];
+
 /// Presentation editor for modifying PPTX files
 pub struct PresentationEditor {
     package: Package,
@@ -58,23 +84,15 @@ impl PresentationEditor {
     pub fn add_slide(&mut self, content: SlideContent) -> Result<usize, PptxError> {
         let new_index = self.slide_count + 1;
         
-        // Generate slide XML
-        let slide_xml = create_slide_xml_with_content(new_index, &content);
-        let slide_rels_xml = create_slide_rels_xml();
-        
-        // Add slide file
-        let slide_path = format!("ppt/slides/slide{new_index}.xml");
-        self.package.add_part(slide_path, slide_xml.into_bytes());
-        
-        // Add slide relationships
-        let rels_path = format!("ppt/slides/_rels/slide{new_index}.xml.rels");
-        self.package.add_part(rels_path, slide_rels_xml.into_bytes());
+        // Add slide file, relationships and media
+        self.write_slide(new_index, content)?;
         
         // Update presentation.xml to include new slide
-        self.update_presentation_xml(new_index)?;
+        let r_id = self.next_presentation_rel_id();
+        self.update_presentation_xml(new_index, r_id)?;
         
         // Update presentation.xml.rels
-        self.update_presentation_rels(new_index)?;
+        self.update_presentation_rels(new_index, r_id)?;
         
         // Update [Content_Types].xml
         self.update_content_types(new_index)?;
@@ -89,11 +107,170 @@ impl PresentationEditor {
             return Err(PptxError::NotFound(format!("Slide {index} not found")));
         }
         
-        let slide_num = index + 1;
-        let slide_xml = create_slide_xml_with_content(slide_num, &content);
+        self.write_slide(index + 1, content)
+    }
+
+    /// Write a generated slide with its relationships, embedded media and notes
+    fn write_slide(&mut self, slide_num: usize, mut content: SlideContent) -> Result<(), PptxError> {
         let slide_path = format!("ppt/slides/slide{slide_num}.xml");
-        
-        self.package.add_part(slide_path, slide_xml.into_bytes());
+        // Notes are linked after the slide rels are rewritten, replacing any previous notes slide
+        let notes = content.notes_frame();
+        content.notes = None;
+        content.rich_notes = None;
+        self.unlink_notes(&slide_path)?;
+
+        let slide_xml = create_slide_xml_with_content(slide_num, &content);
+        let rels = SlideRelationships::for_slide(slide_num, &content);
+        self.package.add_part(slide_path.clone(), slide_xml.into_bytes());
+        self.package.add_part(rels_path(&slide_path), rels.to_xml().into_bytes());
+
+        for media in slide_media(slide_num, &content) {
+            self.ensure_default_content_type(&media.extension)?;
+            self.package.add_part(media.path, media.data);
+        }
+
+        if let Some(notes) = notes {
+            self.write_notes(slide_num, &slide_path, &notes)?;
+        }
+        Ok(())
+    }
+
+    /// Get the speaker notes of a slide (0-based), if it has a notes slide
+    pub fn get_notes(&self, index: usize) -> Result<Option<ParsedNotes>, PptxError> {
+        NotesParser::read(&self.package, &self.slide_path(index)?)
+    }
+
+    /// Set the speaker notes of a slide (0-based)
+    ///
+    /// Accepts plain text (one paragraph per line) or a formatted `TextFrame`.
+    /// An existing notes slide is replaced; otherwise one is created, adding
+    /// the notes master to the presentation if it has none yet.
+    pub fn set_notes(&mut self, index: usize, notes: impl Into<TextFrame>) -> Result<(), PptxError> {
+        let slide_path = self.slide_path(index)?;
+        self.write_notes(index + 1, &slide_path, &notes.into())
+    }
+
+    /// Remove the speaker notes of a slide (0-based), if any
+    pub fn remove_notes(&mut self, index: usize) -> Result<(), PptxError> {
+        let slide_path = self.slide_path(index)?;
+        self.unlink_notes(&slide_path)
+    }
+
+    /// Write the notes slide of a slide, linking it from the slide if it is new
+    fn write_notes(&mut self, slide_num: usize, slide_path: &str, notes: &TextFrame) -> Result<(), PptxError> {
+        let notes_path = match NotesParser::notes_path(&self.package, slide_path) {
+            Some(path) => path,
+            None => {
+                let mut n = slide_num;
+                while self.package.has_part(&format!("ppt/notesSlides/notesSlide{n}.xml")) {
+                    n += 1;
+                }
+                let slide_rels = rels_path(slide_path);
+                let xml = self.package.get_part_string(&slide_rels)
+                    .ok_or_else(|| PptxError::NotFound(format!("Slide relationships not found: {slide_rels}")))?;
+                let rel = format!(
+                    "<Relationship Id=\"rId{}\" Type=\"{}\" Target=\"../notesSlides/notesSlide{n}.xml\"/>\n",
+                    Self::next_rel_id(&xml),
+                    RELATIONSHIP_TYPE::NOTES_SLIDE,
+                );
+                let updated = xml.replacen("</Relationships>", &format!("{rel}</Relationships>"), 1);
+                self.package.add_part(slide_rels, updated.into_bytes());
+
+                let path = format!("ppt/notesSlides/notesSlide{n}.xml");
+                self.ensure_override(&path, CONTENT_TYPE::PML_NOTES_SLIDE)?;
+                path
+            }
+        };
+
+        self.ensure_notes_master()?;
+        self.package.add_part(rels_path(&notes_path), create_rich_notes_rels_xml(slide_num, notes).into_bytes());
+        self.package.add_part(notes_path, create_rich_notes_xml(slide_num, notes).into_bytes());
+        Ok(())
+    }
+
+    /// Remove the notes slide of a slide and its relationship, if any
+    fn unlink_notes(&mut self, slide_path: &str) -> Result<(), PptxError> {
+        let Some(rel) = self.package.part_relationships(slide_path)
+            .into_iter()
+            .find(|r| !r.external && r.rel_type == RELATIONSHIP_TYPE::NOTES_SLIDE)
+        else {
+            return Ok(());
+        };
+
+        let slide_rels = rels_path(slide_path);
+        if let Some(xml) = self.package.get_part_string(&slide_rels) {
+            let updated = remove_element(&xml, "<Relationship", &format!("Id=\"{}\"", rel.id));
+            self.package.add_part(slide_rels, updated.into_bytes());
+        }
+
+        self.package.remove_part(&rels_path(&rel.target));
+        self.package.remove_part(&rel.target);
+        self.remove_override(&rel.target)
+    }
+
+    /// Add the notes master and reference it from the presentation if it is missing
+    fn ensure_notes_master(&mut self) -> Result<(), PptxError> {
+        if self.package.has_part(NOTES_MASTER_PATH) {
+            return Ok(());
+        }
+        self.package.add_part(NOTES_MASTER_PATH.to_string(), create_notes_master_xml().into_bytes());
+        self.package.add_part(rels_path(NOTES_MASTER_PATH), create_notes_master_rels_xml().into_bytes());
+        self.ensure_override(NOTES_MASTER_PATH, CONTENT_TYPE::PML_NOTES_MASTER)?;
+
+        let r_id = self.next_presentation_rel_id();
+        if let Some(xml) = self.package.get_part_string("ppt/_rels/presentation.xml.rels") {
+            let rel = format!(
+                "    <Relationship Id=\"rId{r_id}\" Type=\"{}\" Target=\"notesMasters/notesMaster1.xml\"/>\n",
+                RELATIONSHIP_TYPE::NOTES_MASTER,
+            );
+            let updated = xml.replacen("</Relationships>", &format!("{rel}</Relationships>"), 1);
+            self.package.add_part("ppt/_rels/presentation.xml.rels".to_string(), updated.into_bytes());
+        }
+        if let Some(xml) = self.package.get_part_string("ppt/presentation.xml") {
+            self.package.add_part("ppt/presentation.xml".to_string(), add_notes_master_id(&xml, r_id).into_bytes());
+        }
+        Ok(())
+    }
+
+    /// Add an `Override` content type for a part if it is missing
+    fn ensure_override(&mut self, part: &str, content_type: &str) -> Result<(), PptxError> {
+        let Some(xml) = self.package.get_part_string("[Content_Types].xml") else {
+            return Ok(());
+        };
+        let part_name = format!("/{part}");
+        let root = XmlParser::parse_str(&xml)?;
+        if root.find_all("Override").iter().any(|o| o.attr("PartName") == Some(part_name.as_str())) {
+            return Ok(());
+        }
+        let entry = format!("<Override PartName=\"{part_name}\" ContentType=\"{content_type}\"/>\n");
+        let updated = xml.replacen("</Types>", &format!("{entry}</Types>"), 1);
+        self.package.add_part("[Content_Types].xml".to_string(), updated.into_bytes());
+        Ok(())
+    }
+
+    /// Remove the `Override` content type of a part
+    fn remove_override(&mut self, part: &str) -> Result<(), PptxError> {
+        let Some(xml) = self.package.get_part_string("[Content_Types].xml") else {
+            return Ok(());
+        };
+        let updated = remove_element(&xml, "<Override", &format!("PartName=\"/{part}\""));
+        self.package.add_part("[Content_Types].xml".to_string(), updated.into_bytes());
+        Ok(())
+    }
+
+    /// Add a `Default` content type for a file extension if it is missing
+    fn ensure_default_content_type(&mut self, extension: &str) -> Result<(), PptxError> {
+        let Some(xml) = self.package.get_part_string("[Content_Types].xml") else {
+            return Ok(());
+        };
+        let root = XmlParser::parse_str(&xml)?;
+        let exists = root.find_all("Default").iter()
+            .any(|d| d.attr("Extension").is_some_and(|e| e.eq_ignore_ascii_case(extension)));
+        if !exists && let Some(pos) = xml.find("<Override") {
+            let mut updated = xml.clone();
+            updated.insert_str(pos, &format!("{}\n", generate_image_content_type(extension)));
+            self.package.add_part("[Content_Types].xml".to_string(), updated.into_bytes());
+        }
         Ok(())
     }
 
@@ -105,8 +282,9 @@ impl PresentationEditor {
         
         let slide_num = index + 1;
         
-        // Remove slide file
+        // Remove slide file (and its notes)
         let slide_path = format!("ppt/slides/slide{slide_num}.xml");
+        self.unlink_notes(&slide_path)?;
         self.package.remove_part(&slide_path);
         
         // Remove slide relationships
@@ -128,6 +306,39 @@ impl PresentationEditor {
         Ok(())
     }
 
+    /// Get the charts on a slide (0-based), in shape tree order
+    pub fn get_slide_charts(&self, slide_index: usize) -> Result<Vec<Chart>, PptxError> {
+        ChartParser::read_slide_charts(&self.package, &self.slide_path(slide_index)?)
+    }
+
+    /// Replace the data of an existing chart, keeping its formatting
+    ///
+    /// `chart_index` counts charts on the slide in shape tree order. Only the
+    /// categories, series names and values of `chart` are used; the chart's
+    /// type, title, position and styling are left as they are. The embedded
+    /// workbook, if any, is rewritten so editing the data in PowerPoint shows
+    /// the new values.
+    pub fn update_chart_data(&mut self, slide_index: usize, chart_index: usize, chart: &Chart) -> Result<(), PptxError> {
+        let slide_path = self.slide_path(slide_index)?;
+        let frames = ChartParser::find_frames(&self.package, &slide_path)?;
+        let frame = frames.get(chart_index)
+            .ok_or_else(|| PptxError::NotFound(format!("Chart {chart_index} not found on slide {slide_index}")))?;
+
+        let xml = self.package.get_part_string(&frame.part_path)
+            .ok_or_else(|| PptxError::NotFound(format!("Chart part not found: {}", frame.part_path)))?;
+        let updated = replace_chart_data(&xml, chart)?;
+
+        if let Some(workbook_path) = ChartParser::workbook_path(&self.package, &frame.part_path)
+            && let Some(mut workbook) = ChartParser::load_workbook(&self.package, &frame.part_path)
+        {
+            workbook.write_chart_data(&data_sheet_name(&xml), chart)?;
+            self.package.add_part(workbook_path, workbook.to_bytes()?);
+        }
+
+        self.package.add_part(frame.part_path.clone(), updated.into_bytes());
+        Ok(())
+    }
+
     /// Save the modified presentation
     pub fn save(&self, path: &str) -> Result<(), PptxError> {
         self.package.save(path)?;
@@ -146,6 +357,14 @@ impl PresentationEditor {
 
     // Helper methods
 
+    fn slide_path(&self, index: usize) -> Result<String, PptxError> {
+        let path = format!("ppt/slides/slide{}.xml", index + 1);
+        if index >= self.slide_count || !self.package.has_part(&path) {
+            return Err(PptxError::NotFound(format!("Slide {index} not found")));
+        }
+        Ok(path)
+    }
+
     fn count_slides(package: &Package) -> usize {
         package.part_paths()
             .iter()
@@ -153,19 +372,40 @@ impl PresentationEditor {
             .count()
     }
 
-    fn update_presentation_xml(&mut self, new_slide_count: usize) -> Result<(), PptxError> {
+    /// First relationship id not used by `presentation.xml.rels`
+    fn next_presentation_rel_id(&self) -> usize {
+        match self.package.get_part_string("ppt/_rels/presentation.xml.rels") {
+            Some(xml) => Self::next_rel_id(&xml),
+            None => self.slide_count + 3,
+        }
+    }
+
+    /// First `rIdN` number not used by a relationships part
+    fn next_rel_id(rels_xml: &str) -> usize {
+        XmlParser::parse_str(rels_xml)
+            .map(|root| {
+                root.find_all("Relationship")
+                    .iter()
+                    .filter_map(|r| r.attr("Id")?.strip_prefix("rId")?.parse::<usize>().ok())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .unwrap_or(0)
+            + 1
+    }
+
+    fn update_presentation_xml(&mut self, new_slide_count: usize, r_id: usize) -> Result<(), PptxError> {
         if let Some(xml) = self.package.get_part_string("ppt/presentation.xml") {
             // Parse and update the presentation XML
-            let updated = self.add_slide_to_presentation_xml(&xml, new_slide_count)?;
+            let updated = self.add_slide_to_presentation_xml(&xml, new_slide_count, r_id)?;
             self.package.add_part("ppt/presentation.xml".to_string(), updated.into_bytes());
         }
         Ok(())
     }
 
-    fn add_slide_to_presentation_xml(&self, xml: &str, slide_num: usize) -> Result<String, PptxError> {
+    fn add_slide_to_presentation_xml(&self, xml: &str, slide_num: usize, r_id: usize) -> Result<String, PptxError> {
         // Find </p:sldIdLst> and insert new slide reference before it
         let slide_id = 256 + slide_num;
-        let r_id = slide_num + 2; // rId1=master, rId2=theme, rId3+=slides
         
         let new_slide_ref = format!(
             "\n<p:sldId id=\"{slide_id}\" r:id=\"rId{r_id}\"/>"
@@ -181,16 +421,15 @@ impl PresentationEditor {
         }
     }
 
-    fn update_presentation_rels(&mut self, new_slide_count: usize) -> Result<(), PptxError> {
+    fn update_presentation_rels(&mut self, new_slide_count: usize, r_id: usize) -> Result<(), PptxError> {
         if let Some(xml) = self.package.get_part_string("ppt/_rels/presentation.xml.rels") {
-            let updated = self.add_slide_to_presentation_rels(&xml, new_slide_count)?;
+            let updated = self.add_slide_to_presentation_rels(&xml, new_slide_count, r_id)?;
             self.package.add_part("ppt/_rels/presentation.xml.rels".to_string(), updated.into_bytes());
         }
         Ok(())
     }
 
-    fn add_slide_to_presentation_rels(&self, xml: &str, slide_num: usize) -> Result<String, PptxError> {
-        let r_id = slide_num + 2;
+    fn add_slide_to_presentation_rels(&self, xml: &str, slide_num: usize, r_id: usize) -> Result<String, PptxError> {
         let new_rel = format!(
             "\n    <Relationship Id=\"rId{r_id}\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/slide\" Target=\"slides/slide{slide_num}.xml\"/>"
         );
@@ -242,11 +481,30 @@ impl PresentationEditor {
         if let Some(content) = self.package.remove_part(&old_rels) {
             self.package.add_part(new_rels, content);
         }
+
+        // Point the notes slide back at the renumbered slide
+        if let Some(notes_path) = NotesParser::notes_path(&self.package, &format!("ppt/slides/slide{new_num}.xml"))
+            && let Some(xml) = self.package.get_part_string(&rels_path(&notes_path))
+        {
+            let updated = xml.replace(
+                &format!("Target=\"../slides/slide{old_num}.xml\""),
+                &format!("Target=\"../slides/slide{new_num}.xml\""),
+            );
+            self.package.add_part(rels_path(&notes_path), updated.into_bytes());
+        }
         
         Ok(())
     }
 
     fn rebuild_presentation_xml(&mut self) -> Result<(), PptxError> {
+        // Keep the notes master reference
+        let notes_master_r_id = match self.package.get_part_string("ppt/presentation.xml") {
+            Some(existing) => XmlParser::parse_str(&existing)?
+                .find_descendant("notesMasterId")
+                .and_then(|e| e.attr("r:id")?.strip_prefix("rId")?.parse::<usize>().ok()),
+            None => None,
+        };
+
         let mut slide_refs = String::new();
         for i in 1..=self.slide_count {
             let slide_id = 256 + i;
@@ -268,6 +526,10 @@ impl PresentationEditor {
 <p:notesSz cx="6858000" cy="9144000"/>
 </p:presentation>"#
         );
+        let xml = match notes_master_r_id {
+            Some(r_id) => add_notes_master_id(&xml, r_id),
+            None => xml,
+        };
         
         self.package.add_part("ppt/presentation.xml".to_string(), xml.into_bytes());
         Ok(())
@@ -281,6 +543,26 @@ impl PresentationEditor {
                 "\n    <Relationship Id=\"rId{r_id}\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/slide\" Target=\"slides/slide{i}.xml\"/>"
             ));
         }
+
+        // Keep the other relationships (notes master, table styles, ...) whose ids are still free
+        if let Some(existing) = self.package.get_part_string("ppt/_rels/presentation.xml.rels") {
+            let root = XmlParser::parse_str(&existing)?;
+            for rel in root.find_all("Relationship") {
+                let (Some(id), Some(rel_type), Some(target)) = (rel.attr("Id"), rel.attr("Type"), rel.attr("Target")) else {
+                    continue;
+                };
+                let fixed = [RELATIONSHIP_TYPE::SLIDE, RELATIONSHIP_TYPE::SLIDE_MASTER, RELATIONSHIP_TYPE::THEME].contains(&rel_type);
+                let taken = id.strip_prefix("rId")
+                    .and_then(|n| n.parse::<usize>().ok())
+                    .is_none_or(|n| n <= self.slide_count + 2);
+                if !fixed && !taken {
+                    slide_rels.push_str(&format!(
+                        "\n    <Relationship Id=\"{id}\" Type=\"{rel_type}\" Target=\"{}\"/>",
+                        escape_xml(target),
+                    ));
+                }
+            }
+        }
         
         let xml = format!(
             r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
@@ -295,18 +577,42 @@ impl PresentationEditor {
     }
 
     fn rebuild_content_types(&mut self) -> Result<(), PptxError> {
+        // Keep media defaults (png, jpeg, ...) of the existing part
+        // and the overrides of parts other than slides (notes, table styles, ...)
+        let mut media_defaults = String::new();
+        let mut other_overrides = String::new();
+        if let Some(existing) = self.package.get_part_string("[Content_Types].xml") {
+            let root = XmlParser::parse_str(&existing)?;
+            for default in root.find_all("Default") {
+                if let (Some(ext), Some(ct)) = (default.attr("Extension"), default.attr("ContentType"))
+                    && !matches!(ext, "rels" | "xml")
+                {
+                    media_defaults.push_str(&format!("\n<Default Extension=\"{ext}\" ContentType=\"{ct}\"/>"));
+                }
+            }
+            for part in root.find_all("Override") {
+                if let (Some(name), Some(ct)) = (part.attr("PartName"), part.attr("ContentType"))
+                    && !name.starts_with("/ppt/slides/")
+                    && !FIXED_OVERRIDES.contains(&name)
+                {
+                    other_overrides.push_str(&format!("\n<Override PartName=\"{name}\" ContentType=\"{ct}\"/>"));
+                }
+            }
+        }
+
         let mut slide_overrides = String::new();
         for i in 1..=self.slide_count {
             slide_overrides.push_str(&format!(
                 "\n<Override PartName=\"/ppt/slides/slide{i}.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.presentationml.slide+xml\"/>"
             ));
         }
+        slide_overrides.push_str(&other_overrides);
         
         let xml = format!(
             r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
 <Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
 <Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>
-<Default Extension="xml" ContentType="application/xml"/>
+<Default Extension="xml" ContentType="application/xml"/>{media_defaults}
 <Override PartName="/ppt/presentation.xml" ContentType="application/vnd.openxmlformats-officedocument.presentationml.presentation.main+xml"/>{slide_overrides}
 <Override PartName="/ppt/slideLayouts/slideLayout1.xml" ContentType="application/vnd.openxmlformats-officedocument.presentationml.slideLayout+xml"/>
 <Override PartName="/ppt/slideMasters/slideMaster1.xml" ContentType="application/vnd.openxmlformats-officedocument.presentationml.slideMaster+xml"/>
@@ -321,6 +627,21 @@ impl PresentationEditor {
     }
 }
 
+/// Remove the self-closing element (`<Tag .../>`) holding an attribute, with its line break
+fn remove_element(xml: &str, open_tag: &str, attr: &str) -> String {
+    let Some(attr_pos) = xml.find(attr) else {
+        return xml.to_string();
+    };
+    let (Some(start), Some(len)) = (xml[..attr_pos].rfind(open_tag), xml[attr_pos..].find("/>")) else {
+        return xml.to_string();
+    };
+    let mut end = attr_pos + len + 2;
+    if xml[end..].starts_with('\n') {
+        end += 1;
+    }
+    format!("{}{}", &xml[..start], &xml[end..])
+}
+
 impl Default for PresentationEditor {
     fn default() -> Self {
         Self::new()
@@ -353,6 +674,9 @@ mod tests {
         editor.add_slide(new_slide).unwrap();
         
         assert_eq!(editor.slide_count(), 2);
+        let rels = editor.package().get_part_string("ppt/_rels/presentation.xml.rels").unwrap();
+        assert_eq!(rels.matches(r#"Id="rId4""#).count(), 1);
+        assert!(rels.contains(r#"Id="rId5" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slide""#));
         
         // Save and verify
         editor.save("test_edit_modified.pptx").unwrap();
@@ -389,4 +713,157 @@ mod tests {
         fs::remove_file("test_update.pptx").ok();
         fs::remove_file("test_update_modified.pptx").ok();
     }
+
+    #[test]
+    fn test_add_slide_with_picture() {
+        use crate::generator::images::Image;
+
+        let pptx_data = create_pptx_with_content("Test", vec![SlideContent::new("Intro")]).unwrap();
+        fs::write("test_edit_picture.pptx", &pptx_data).unwrap();
+
+        let mut editor = PresentationEditor::open("test_edit_picture.pptx").unwrap();
+        let png = "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNk+M9QDwADhgGAWjR9awAAAABJRU5ErkJggg==";
+        let slide = SlideContent::new("Photo").add_image(Image::from_base64(png, 914400, 914400, "PNG"));
+        editor.add_slide(slide).unwrap();
+        editor.remove_slide(0).unwrap();
+
+        let package = editor.package_mut();
+        assert!(package.has_part("ppt/media/image2_1.png"));
+        let rels = package.get_part_string("ppt/slides/_rels/slide1.xml.rels").unwrap();
+        assert!(rels.contains(r#"Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/image" Target="../media/image2_1.png""#));
+        let content_types = package.get_part_string("[Content_Types].xml").unwrap();
+        assert!(content_types.contains(r#"<Default Extension="png" ContentType="image/png"/>"#));
+
+        fs::remove_file("test_edit_picture.pptx").ok();
+    }
+
+    /// Attach a PowerPoint-style chart part (with embedded workbook) to slide 1
+    fn add_chart_to_first_slide(editor: &mut PresentationEditor) {
+        use crate::oxml::chart::parser::tests::BAR_CHART_XML;
+        use crate::oxml::chart::workbook::tests::sample_workbook;
+
+        let package = editor.package_mut();
+        let slide = package.get_part_string("ppt/slides/slide1.xml").unwrap();
+        let frame = r#"<p:graphicFrame><p:nvGraphicFramePr><p:cNvPr id="10" name="KPI Chart"/><p:cNvGraphicFramePr/><p:nvPr/></p:nvGraphicFramePr><p:xfrm><a:off x="914400" y="1828800"/><a:ext cx="5486400" cy="3200400"/></p:xfrm><a:graphic><a:graphicData uri="http://schemas.openxmlformats.org/drawingml/2006/chart"><c:chart xmlns:c="http://schemas.openxmlformats.org/drawingml/2006/chart" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" r:id="rId9"/></a:graphicData></a:graphic></p:graphicFrame>"#;
+        let slide = slide.replacen("</p:spTree>", &format!("{frame}</p:spTree>"), 1);
+        package.add_part("ppt/slides/slide1.xml".to_string(), slide.into_bytes());
+
+        let rels = package.get_part_string("ppt/slides/_rels/slide1.xml.rels").unwrap();
+        let rels = rels.replace("</Relationships>", r#"<Relationship Id="rId9" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/chart" Target="../charts/chart1.xml"/></Relationships>"#);
+        package.add_part("ppt/slides/_rels/slide1.xml.rels".to_string(), rels.into_bytes());
+
+        package.add_part("ppt/charts/chart1.xml".to_string(), BAR_CHART_XML.as_bytes().to_vec());
+        package.add_part("ppt/charts/_rels/chart1.xml.rels".to_string(), br#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/package" Target="../embeddings/Microsoft_Excel_Worksheet.xlsx"/></Relationships>"#.to_vec());
+        package.add_part("ppt/embeddings/Microsoft_Excel_Worksheet.xlsx".to_string(), sample_workbook());
+    }
+
+    #[test]
+    fn test_read_and_update_chart_data() {
+        use crate::generator::charts::{ChartBuilder, ChartSeries, ChartType};
+
+        let slides = vec![SlideContent::new("KPIs")];
+        let pptx_data = create_pptx_with_content("Test", slides).unwrap();
+        fs::write("test_chart_edit.pptx", &pptx_data).unwrap();
+
+        let mut editor = PresentationEditor::open("test_chart_edit.pptx").unwrap();
+        add_chart_to_first_slide(&mut editor);
+
+        let charts = editor.get_slide_charts(0).unwrap();
+        assert_eq!(charts.len(), 1);
+        assert_eq!(charts[0].series[0].values, vec![120.0, 135.5]);
+
+        let refreshed = ChartBuilder::new("", ChartType::Bar)
+            .categories(vec!["Jan", "Feb", "Mar"])
+            .add_series(ChartSeries::new("Revenue", vec![120.0, 135.5, 142.0]))
+            .add_series(ChartSeries::new("Target", vec![125.0, 130.0, 140.0]))
+            .build();
+        editor.update_chart_data(0, 0, &refreshed).unwrap();
+        assert!(editor.update_chart_data(0, 1, &refreshed).is_err());
+        editor.save("test_chart_edit_modified.pptx").unwrap();
+
+        let reader = PresentationReader::open("test_chart_edit_modified.pptx").unwrap();
+        let charts = reader.get_slide_charts(0).unwrap();
+        assert_eq!(charts.len(), 1);
+        let chart = &charts[0];
+        assert_eq!(chart.title, "Monthly Revenue");
+        assert_eq!(chart.chart_type, ChartType::BarStacked);
+        assert_eq!((chart.x, chart.y, chart.width, chart.height), (914400, 1828800, 5486400, 3200400));
+        assert_eq!(chart.categories, vec!["Jan", "Feb", "Mar"]);
+        assert_eq!(chart.series[1].name, "Target");
+        assert_eq!(chart.series[1].values, vec![125.0, 130.0, 140.0]);
+
+        fs::remove_file("test_chart_edit.pptx").ok();
+        fs::remove_file("test_chart_edit_modified.pptx").ok();
+    }
+
+    #[test]
+    fn test_set_notes() {
+        use crate::generator::{Paragraph, Run};
+
+        let slides = vec![SlideContent::new("One"), SlideContent::new("Two")];
+        let pptx_data = create_pptx_with_content("Test", slides).unwrap();
+        fs::write("test_edit_notes.pptx", &pptx_data).unwrap();
+
+        let mut editor = PresentationEditor::open("test_edit_notes.pptx").unwrap();
+        assert!(editor.get_notes(1).unwrap().is_none());
+        let notes = TextFrame::new()
+            .add_paragraph(Paragraph::new().add_run(Run::new("Key point").bold()))
+            .add_paragraph(Paragraph::new().bullet().add_text("Detail"));
+        editor.set_notes(1, notes).unwrap();
+        editor.set_notes(1, "Replaced\nSecond line").unwrap();
+        assert!(editor.set_notes(2, "Missing").is_err());
+
+        let package = editor.package();
+        assert!(package.has_part("ppt/notesSlides/notesSlide2.xml"));
+        assert!(package.has_part(NOTES_MASTER_PATH));
+        let slide_rels = package.get_part_string("ppt/slides/_rels/slide2.xml.rels").unwrap();
+        assert_eq!(slide_rels.matches("relationships/notesSlide\"").count(), 1);
+        let presentation = package.get_part_string("ppt/presentation.xml").unwrap();
+        assert!(presentation.contains(r#"<p:notesMasterId r:id="rId6"/>"#));
+        let pres_rels = package.get_part_string("ppt/_rels/presentation.xml.rels").unwrap();
+        assert!(pres_rels.contains(r#"Id="rId6" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/notesMaster""#));
+        let content_types = package.get_part_string("[Content_Types].xml").unwrap();
+        assert!(content_types.contains(r#"PartName="/ppt/notesSlides/notesSlide2.xml""#));
+
+        // Removing the first slide keeps the notes linked to the renumbered slide
+        editor.remove_slide(0).unwrap();
+        editor.save("test_edit_notes_modified.pptx").unwrap();
+
+        let reader = PresentationReader::open("test_edit_notes_modified.pptx").unwrap();
+        let notes = reader.notes(0).unwrap().unwrap();
+        assert_eq!(notes.text(), "Replaced\nSecond line");
+        let notes_rels = editor.package().get_part_string("ppt/notesSlides/_rels/notesSlide2.xml.rels").unwrap();
+        assert!(notes_rels.contains(r#"Target="../slides/slide1.xml""#));
+        let presentation = editor.package().get_part_string("ppt/presentation.xml").unwrap();
+        assert!(presentation.contains("<p:notesMasterIdLst>"));
+
+        editor.remove_notes(0).unwrap();
+        assert!(editor.get_notes(0).unwrap().is_none());
+        assert!(!editor.package().has_part("ppt/notesSlides/notesSlide2.xml"));
+        let content_types = editor.package().get_part_string("[Content_Types].xml").unwrap();
+        assert!(!content_types.contains("notesSlide2.xml"));
+
+        fs::remove_file("test_edit_notes.pptx").ok();
+        fs::remove_file("test_edit_notes_modified.pptx").ok();
+    }
+
+    #[test]
+    fn test_update_slide_keeps_notes() {
+        let slides = vec![SlideContent::new("Intro").notes("Old notes")];
+        let pptx_data = create_pptx_with_content("Test", slides).unwrap();
+        fs::write("test_update_notes.pptx", &pptx_data).unwrap();
+
+        let mut editor = PresentationEditor::open("test_update_notes.pptx").unwrap();
+        assert_eq!(editor.get_notes(0).unwrap().unwrap().text(), "Old notes");
+        editor.update_slide(0, SlideContent::new("Intro").notes("New notes")).unwrap();
+        assert_eq!(editor.get_notes(0).unwrap().unwrap().text(), "New notes");
+        editor.add_slide(SlideContent::new("Added").notes("Added notes")).unwrap();
+        assert_eq!(editor.get_notes(1).unwrap().unwrap().text(), "Added notes");
+
+        let presentation = editor.package().get_part_string("ppt/presentation.xml").unwrap();
+        assert_eq!(presentation.matches("<p:notesMasterId ").count(), 1);
+
+        fs::remove_file("test_update_notes.pptx").ok();
+    }
 }
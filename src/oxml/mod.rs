@@ -7,6 +7,7 @@ pub mod chart;
 pub mod coreprops;
 pub mod dml;
 pub mod editor;
+pub mod notes;
 pub mod ns;
 pub mod presentation;
 pub mod repair;
@@ -22,7 +23,10 @@ pub mod xmlchemy;
 pub use xmlchemy::{XmlElement, XmlParser, BaseOxmlElement};
 
 // Slide parsing
-pub use slide::{SlideParser, ParsedSlide, ParsedShape, ParsedTable, ParsedTableCell, Paragraph, TextRun};
+pub use slide::{SlideParser, ParsedSlide, ParsedShape, ParsedPicture, ParsedTable, ParsedTableCell, ParsedEquation, Paragraph, TextRun, ShapeRole};
+
+// Notes parsing
+pub use notes::{NotesParser, ParsedNotes};
 
 // Presentation reading
 pub use presentation::{PresentationReader, PresentationInfo};
@@ -51,6 +55,7 @@ pub use dml::{
 
 // Chart elements
 pub use chart::{ChartKind, ChartSeries as OxmlChartSeries, ChartAxis, ChartLegend, ChartTitle, NumericData, StringData, DataPoint, CategoryPoint};
+pub use chart::{ChartParser, ChartFrame, EmbeddedWorkbook};
 
 // Repair functionality
 pub use repair::{PptxRepair, RepairIssue, RepairResult};
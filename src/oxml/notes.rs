@@ -0,0 +1,94 @@
+//! Notes slide parsing
+//!
+//! Extracts the speaker notes from a notes slide (`ppt/notesSlides/notesSlideN.xml`).
+
+use super::slide::{Paragraph, SlideParser};
+use super::xmlchemy::XmlParser;
+use crate::exc::PptxError;
+use crate::opc::Package;
+
+/// Relationship type suffix linking a slide to its notes slide
+pub(crate) const NOTES_SLIDE_REL_SUFFIX: &str = "/notesSlide";
+
+/// Parsed speaker notes
+#[derive(Debug, Clone, Default)]
+pub struct ParsedNotes {
+    /// Paragraphs of the notes body, with formatting and hyperlinks
+    pub paragraphs: Vec<Paragraph>,
+}
+
+impl ParsedNotes {
+    /// Plain text of the notes, one line per paragraph
+    pub fn text(&self) -> String {
+        self.paragraphs.iter().map(|p| p.text()).collect::<Vec<_>>().join("\n")
+    }
+
+    /// Check if the notes have no text
+    pub fn is_empty(&self) -> bool {
+        self.paragraphs.iter().all(|p| p.text().is_empty())
+    }
+}
+
+/// Notes slide parser
+pub struct NotesParser;
+
+impl NotesParser {
+    /// Parse notes slide XML
+    ///
+    /// Run hyperlinks hold relationship ids; use [`NotesParser::read`] to resolve them.
+    pub fn parse(xml: &str) -> Result<ParsedNotes, PptxError> {
+        let root = XmlParser::parse_str(xml)?;
+        let body = root.find_all_descendants("sp").into_iter().find(|sp| {
+            sp.find_descendant("ph").and_then(|ph| ph.attr("type")) == Some("body")
+        });
+
+        let paragraphs = body
+            .and_then(|sp| sp.find("txBody"))
+            .map(SlideParser::parse_text_body)
+            .unwrap_or_default();
+        Ok(ParsedNotes { paragraphs })
+    }
+
+    /// Path of the notes slide linked to a slide, if any
+    pub fn notes_path(package: &Package, slide_path: &str) -> Option<String> {
+        package.part_relationships(slide_path)
+            .into_iter()
+            .find(|r| !r.external && r.rel_type.ends_with(NOTES_SLIDE_REL_SUFFIX))
+            .map(|r| r.target)
+    }
+
+    /// Read the notes of a slide, with hyperlinks resolved to their targets
+    pub fn read(package: &Package, slide_path: &str) -> Result<Option<ParsedNotes>, PptxError> {
+        let Some(path) = Self::notes_path(package, slide_path) else {
+            return Ok(None);
+        };
+        let xml = package.get_part_string(&path)
+            .ok_or_else(|| PptxError::NotFound(format!("Notes file not found: {path}")))?;
+
+        let mut notes = Self::parse(&xml)?;
+        SlideParser::resolve_hyperlinks(&mut notes.paragraphs, &package.part_relationships(&path));
+        Ok(Some(notes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::{create_rich_notes_xml, Hyperlink, Run, TextFrame, Paragraph as TextParagraph};
+
+    #[test]
+    fn test_parse_rich_notes() {
+        let frame = TextFrame::new()
+            .add_paragraph(TextParagraph::new().add_run(Run::new("Intro").bold()))
+            .add_paragraph(TextParagraph::new()
+                .bullet()
+                .add_run(Run::new("Docs").with_hyperlink(Hyperlink::url("https://example.com"))));
+        let notes = NotesParser::parse(&create_rich_notes_xml(1, &frame)).unwrap();
+
+        assert_eq!(notes.paragraphs.len(), 2);
+        assert!(notes.paragraphs[0].runs[0].bold);
+        assert!(notes.paragraphs[1].bullet);
+        assert_eq!(notes.paragraphs[1].runs[0].hyperlink.as_deref(), Some("rId3"));
+        assert_eq!(notes.text(), "Intro\nDocs");
+    }
+}
@@ -2,9 +2,12 @@
 //!
 //! Parses presentation.xml and provides high-level access to presentation content.
 
+use super::chart::ChartParser;
+use super::notes::{NotesParser, ParsedNotes};
 use super::slide::{ParsedSlide, SlideParser};
 use super::xmlchemy::XmlParser;
 use crate::exc::PptxError;
+use crate::generator::charts::Chart;
 use crate::opc::Package;
 
 /// Parsed presentation metadata
@@ -17,6 +20,8 @@ pub struct PresentationInfo {
     pub modified: Option<String>,
     pub revision: Option<u32>,
     pub slide_count: usize,
+    /// Slide width and height in EMU, from `p:sldSz`
+    pub slide_size: Option<(u32, u32)>,
 }
 
 impl PresentationInfo {
@@ -29,6 +34,7 @@ impl PresentationInfo {
             modified: None,
             revision: None,
             slide_count: 0,
+            slide_size: None,
         }
     }
 }
@@ -69,7 +75,8 @@ impl PresentationReader {
         self.slide_paths.len()
     }
 
-    /// Get slide by index (0-based)
+    /// Get slide by index (0-based), with hyperlinks and pictures resolved
+    /// to their targets
     pub fn get_slide(&self, index: usize) -> Result<ParsedSlide, PptxError> {
         let path = self.slide_paths.get(index)
             .ok_or_else(|| PptxError::NotFound(format!("Slide {index} not found")))?;
@@ -78,7 +85,25 @@ impl PresentationReader {
             .ok_or_else(|| PptxError::NotFound(format!("Slide file not found: {path}")))?;
         
         let xml_str = String::from_utf8_lossy(xml);
-        SlideParser::parse(&xml_str)
+        let mut slide = SlideParser::parse(&xml_str)?;
+        let rels = self.package.part_relationships(path);
+        for shape in &mut slide.shapes {
+            SlideParser::resolve_hyperlinks(&mut shape.paragraphs, &rels);
+        }
+        for cell in slide.tables.iter_mut().flat_map(|table| table.rows.iter_mut().flatten()) {
+            SlideParser::resolve_hyperlinks(&mut cell.paragraphs, &rels);
+        }
+        for picture in &mut slide.pictures {
+            if let Some(ref r_id) = picture.target {
+                picture.target = rels.iter().find(|r| r.id == *r_id).map(|r| r.target.clone());
+            }
+        }
+        Ok(slide)
+    }
+
+    /// Get the bytes of a package part, such as a picture's media file
+    pub fn part_data(&self, path: &str) -> Option<&[u8]> {
+        self.package.get_part(path)
     }
 
     /// Get all slides
@@ -90,6 +115,30 @@ impl PresentationReader {
         Ok(slides)
     }
 
+    /// Get the charts on a slide (0-based), in shape tree order
+    ///
+    /// Series data comes from the embedded workbook when it is present and
+    /// resolves the series formulas, otherwise from the values cached in the chart part.
+    pub fn get_slide_charts(&self, index: usize) -> Result<Vec<Chart>, PptxError> {
+        let path = self.slide_paths.get(index)
+            .ok_or_else(|| PptxError::NotFound(format!("Slide {index} not found")))?;
+        ChartParser::read_slide_charts(&self.package, path)
+    }
+
+    /// Get the speaker notes of a slide (0-based), if it has a notes slide
+    pub fn notes(&self, index: usize) -> Result<Option<ParsedNotes>, PptxError> {
+        let path = self.slide_paths.get(index)
+            .ok_or_else(|| PptxError::NotFound(format!("Slide {index} not found")))?;
+        NotesParser::read(&self.package, path)
+    }
+
+    /// Get the charts of all slides, one entry per slide
+    pub fn get_all_charts(&self) -> Result<Vec<Vec<Chart>>, PptxError> {
+        (0..self.slide_paths.len())
+            .map(|i| self.get_slide_charts(i))
+            .collect()
+    }
+
     /// Get all text from presentation
     pub fn extract_all_text(&self) -> Result<Vec<String>, PptxError> {
         let mut all_text = Vec::new();
@@ -185,6 +234,12 @@ impl PresentationReader {
         }
         
         self.info.slide_count = self.slide_paths.len();
+        if let Some(xml) = self.package.get_part_string("ppt/presentation.xml")
+            && let Ok(root) = XmlParser::parse_str(&xml)
+        {
+            self.info.slide_size = root.find("sldSz")
+                .and_then(|size| Some((size.attr("cx")?.parse().ok()?, size.attr("cy")?.parse().ok()?)));
+        }
         Ok(())
     }
 }
@@ -215,6 +270,7 @@ mod tests {
         
         assert_eq!(reader.slide_count(), 2);
         assert!(reader.info().title.is_some());
+        assert_eq!(reader.info().slide_size, Some((9_144_000, 6_858_000)));
         
         let slide1 = reader.get_slide(0).unwrap();
         assert!(slide1.title.is_some());
@@ -244,4 +300,32 @@ mod tests {
         
         fs::remove_file("test_extract.pptx").ok();
     }
+
+    #[test]
+    fn test_read_notes() {
+        use crate::generator::{Hyperlink, Paragraph, Run, TextFrame};
+
+        let notes = TextFrame::new()
+            .add_paragraph(Paragraph::new().add_run(Run::new("Say hello").bold()))
+            .add_paragraph(Paragraph::new().bullet()
+                .add_run(Run::new("Link").with_hyperlink(Hyperlink::url("https://example.com"))));
+        let slides = vec![
+            SlideContent::new("With notes").rich_notes(notes),
+            SlideContent::new("Without notes"),
+        ];
+
+        let pptx_data = create_pptx_with_content("Notes Test", slides).unwrap();
+        fs::write("test_read_notes.pptx", &pptx_data).unwrap();
+
+        let reader = PresentationReader::open("test_read_notes.pptx").unwrap();
+        let parsed = reader.notes(0).unwrap().unwrap();
+        assert_eq!(parsed.text(), "Say hello\nLink");
+        assert!(parsed.paragraphs[0].runs[0].bold);
+        assert!(parsed.paragraphs[1].bullet);
+        assert_eq!(parsed.paragraphs[1].runs[0].hyperlink.as_deref(), Some("https://example.com"));
+        assert!(reader.notes(1).unwrap().is_none());
+        assert!(reader.notes(5).is_err());
+
+        fs::remove_file("test_read_notes.pptx").ok();
+    }
 }
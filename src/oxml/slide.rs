@@ -4,6 +4,7 @@
 
 use super::xmlchemy::{XmlElement, XmlParser};
 use crate::exc::PptxError;
+use crate::opc::package::PartRelationship;
 
 /// Parsed text run with formatting
 #[derive(Debug, Clone)]
@@ -14,6 +15,12 @@ pub struct TextRun {
     pub underline: bool,
     pub font_size: Option<u32>,
     pub color: Option<String>,
+    /// Latin typeface (`a:latin`)
+    pub font: Option<String>,
+    /// Click hyperlink target (URL or part path)
+    pub hyperlink: Option<String>,
+    /// Excluded from proofing (`noProof`), as the line numbers of code blocks are
+    pub no_proof: bool,
 }
 
 impl TextRun {
@@ -25,6 +32,9 @@ impl TextRun {
             underline: false,
             font_size: None,
             color: None,
+            font: None,
+            hyperlink: None,
+            no_proof: false,
         }
     }
 }
@@ -34,6 +44,8 @@ impl TextRun {
 pub struct Paragraph {
     pub runs: Vec<TextRun>,
     pub level: u32,
+    /// Has a bullet character or number
+    pub bullet: bool,
 }
 
 impl Paragraph {
@@ -41,6 +53,7 @@ impl Paragraph {
         Paragraph {
             runs: Vec::new(),
             level: 0,
+            bullet: false,
         }
     }
 
@@ -56,11 +69,25 @@ impl Default for Paragraph {
     }
 }
 
+/// What a shape holds on its slide
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShapeRole {
+    /// Slide title
+    Title,
+    /// Body text (bullets)
+    Body,
+    /// Any other shape
+    Other,
+}
+
 /// Parsed shape from slide
 #[derive(Debug, Clone)]
 pub struct ParsedShape {
     pub name: String,
     pub shape_type: Option<String>,
+    pub role: ShapeRole,
+    /// Plain text box (`txBox="1"`)
+    pub text_box: bool,
     pub paragraphs: Vec<Paragraph>,
     pub x: i64,
     pub y: i64,
@@ -73,6 +100,8 @@ impl ParsedShape {
         ParsedShape {
             name: name.to_string(),
             shape_type: None,
+            role: ShapeRole::Other,
+            text_box: false,
             paragraphs: Vec::new(),
             x: 0,
             y: 0,
@@ -90,12 +119,31 @@ impl ParsedShape {
     }
 }
 
+/// Parsed picture from slide
+#[derive(Debug, Clone)]
+pub struct ParsedPicture {
+    pub name: String,
+    /// Alternative text (`descr`)
+    pub description: Option<String>,
+    /// Image relationship id, resolved to the media part path by
+    /// [`PresentationReader::get_slide`](super::PresentationReader::get_slide)
+    pub target: Option<String>,
+    pub x: i64,
+    pub y: i64,
+    pub width: i64,
+    pub height: i64,
+}
+
 /// Parsed table cell
 #[derive(Debug, Clone)]
 pub struct ParsedTableCell {
     pub text: String,
+    /// Formatted paragraphs of the cell
+    pub paragraphs: Vec<Paragraph>,
     pub row_span: u32,
     pub col_span: u32,
+    /// Horizontal alignment (`algn`) of the first paragraph
+    pub align: Option<String>,
 }
 
 /// Parsed table
@@ -124,11 +172,26 @@ impl Default for ParsedTable {
     }
 }
 
+/// Parsed equation (an Office Math shape)
+#[derive(Debug, Clone)]
+pub struct ParsedEquation {
+    /// LaTeX source, kept in the alternative text (`descr`) by md2ppt
+    pub latex: Option<String>,
+    /// Linear text of the fallback shape
+    pub text: String,
+    /// On its own line, not justified left
+    pub display: bool,
+    pub x: i64,
+    pub y: i64,
+}
+
 /// Parsed slide content
 #[derive(Debug, Clone)]
 pub struct ParsedSlide {
     pub shapes: Vec<ParsedShape>,
+    pub pictures: Vec<ParsedPicture>,
     pub tables: Vec<ParsedTable>,
+    pub equations: Vec<ParsedEquation>,
     pub title: Option<String>,
     pub body_text: Vec<String>,
 }
@@ -137,7 +200,9 @@ impl ParsedSlide {
     pub fn new() -> Self {
         ParsedSlide {
             shapes: Vec::new(),
+            pictures: Vec::new(),
             tables: Vec::new(),
+            equations: Vec::new(),
             title: None,
             body_text: Vec::new(),
         }
@@ -179,20 +244,11 @@ impl SlideParser {
         if let Some(sp_tree) = root.find_descendant("spTree") {
             // Parse shapes
             for sp in sp_tree.find_all("sp") {
-                if let Some(shape) = Self::parse_shape(sp) {
-                    // Check if this is title or body
-                    if Self::is_title_shape(sp) {
-                        slide.title = Some(shape.text());
-                    } else if Self::is_body_shape(sp) {
-                        for para in &shape.paragraphs {
-                            let text = para.text();
-                            if !text.is_empty() {
-                                slide.body_text.push(text);
-                            }
-                        }
-                    }
-                    slide.shapes.push(shape);
-                }
+                Self::push_shape(&mut slide, sp);
+            }
+
+            for pic in sp_tree.find_all("pic") {
+                slide.pictures.push(Self::parse_picture(pic));
             }
 
             // Parse graphic frames (tables, charts)
@@ -201,11 +257,45 @@ impl SlideParser {
                     slide.tables.push(table);
                 }
             }
+
+            // Equations, and text with math, are shapes with a text fallback
+            // for older readers; the fallback of text shows the TeX
+            for content in sp_tree.find_all("AlternateContent") {
+                let name = content.find("Choice")
+                    .and_then(|choice| choice.find("sp"))
+                    .and_then(|sp| sp.find_descendant("cNvPr"))
+                    .and_then(|c_nv_pr| c_nv_pr.attr("name"));
+                if name == Some("Equation") {
+                    slide.equations.extend(Self::parse_equation(content));
+                } else if let Some(sp) = content.find("Fallback").and_then(|fallback| fallback.find("sp")) {
+                    Self::push_shape(&mut slide, sp);
+                }
+            }
         }
 
         Ok(slide)
     }
 
+    /// Add a shape, as the title or body when it is one
+    fn push_shape(slide: &mut ParsedSlide, sp: &XmlElement) {
+        let Some(mut shape) = Self::parse_shape(sp) else {
+            return;
+        };
+        if Self::is_title_shape(sp) {
+            shape.role = ShapeRole::Title;
+            slide.title = Some(shape.text());
+        } else if Self::is_body_shape(sp) {
+            shape.role = ShapeRole::Body;
+            for para in &shape.paragraphs {
+                let text = para.text();
+                if !text.is_empty() {
+                    slide.body_text.push(text);
+                }
+            }
+        }
+        slide.shapes.push(shape);
+    }
+
     fn parse_shape(sp: &XmlElement) -> Option<ParsedShape> {
         // Get shape name from nvSpPr/cNvPr
         let name = sp.find_descendant("cNvPr")
@@ -213,18 +303,8 @@ impl SlideParser {
             .unwrap_or("Shape");
 
         let mut shape = ParsedShape::new(name);
-
-        // Get position and size from spPr/xfrm
-        if let Some(xfrm) = sp.find_descendant("xfrm") {
-            if let Some(off) = xfrm.find("off") {
-                shape.x = off.attr("x").and_then(|v| v.parse().ok()).unwrap_or(0);
-                shape.y = off.attr("y").and_then(|v| v.parse().ok()).unwrap_or(0);
-            }
-            if let Some(ext) = xfrm.find("ext") {
-                shape.width = ext.attr("cx").and_then(|v| v.parse().ok()).unwrap_or(0);
-                shape.height = ext.attr("cy").and_then(|v| v.parse().ok()).unwrap_or(0);
-            }
-        }
+        shape.text_box = sp.find_descendant("cNvSpPr").and_then(|e| e.attr("txBox")) == Some("1");
+        (shape.x, shape.y, shape.width, shape.height) = Self::parse_xfrm(sp);
 
         // Get shape type from prstGeom
         if let Some(prst_geom) = sp.find_descendant("prstGeom") {
@@ -239,7 +319,54 @@ impl SlideParser {
         Some(shape)
     }
 
-    fn parse_text_body(tx_body: &XmlElement) -> Vec<Paragraph> {
+    fn parse_picture(pic: &XmlElement) -> ParsedPicture {
+        let c_nv_pr = pic.find_descendant("cNvPr");
+        let (x, y, width, height) = Self::parse_xfrm(pic);
+        ParsedPicture {
+            name: c_nv_pr.and_then(|e| e.attr("name")).unwrap_or("Picture").to_string(),
+            description: c_nv_pr.and_then(|e| e.attr("descr")).filter(|d| !d.is_empty()).map(|d| d.to_string()),
+            target: pic.find_descendant("blip").and_then(|b| b.attr("r:embed")).map(|id| id.to_string()),
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    fn parse_equation(content: &XmlElement) -> Option<ParsedEquation> {
+        let sp = content.find("Choice")?.find("sp")?;
+        let math = sp.find_descendant("oMathPara")?;
+        let text = content.find("Fallback")
+            .and_then(|fallback| fallback.find("sp"))
+            .and_then(|sp| sp.find_descendant("txBody"))
+            .map(|tx_body| Self::parse_text_body(tx_body).iter().map(Paragraph::text).collect::<Vec<_>>().join("\n"))
+            .unwrap_or_default();
+        let justify = math.find("oMathParaPr").and_then(|pr| pr.find("jc")).and_then(|jc| jc.attr("m:val"));
+        let (x, y, _, _) = Self::parse_xfrm(sp);
+        Some(ParsedEquation {
+            latex: sp.find_descendant("cNvPr").and_then(|e| e.attr("descr")).filter(|d| !d.is_empty()).map(|d| d.to_string()),
+            text,
+            display: justify != Some("left"),
+            x,
+            y,
+        })
+    }
+
+    /// Position and size from the first `xfrm`, zero when missing
+    fn parse_xfrm(element: &XmlElement) -> (i64, i64, i64, i64) {
+        let Some(xfrm) = element.find_descendant("xfrm") else {
+            return (0, 0, 0, 0);
+        };
+        let value = |child: &str, name: &str| {
+            xfrm.find(child).and_then(|e| e.attr(name)).and_then(|v| v.parse().ok()).unwrap_or(0)
+        };
+        (value("off", "x"), value("off", "y"), value("ext", "cx"), value("ext", "cy"))
+    }
+
+    /// Parse the paragraphs of a text body (`p:txBody`)
+    ///
+    /// Run hyperlinks hold the relationship id; see [`SlideParser::resolve_hyperlinks`].
+    pub(crate) fn parse_text_body(tx_body: &XmlElement) -> Vec<Paragraph> {
         let mut paragraphs = Vec::new();
 
         for p in tx_body.find_all("p") {
@@ -248,6 +375,7 @@ impl SlideParser {
             // Get paragraph level
             if let Some(ppr) = p.find("pPr") {
                 para.level = ppr.attr("lvl").and_then(|v| v.parse().ok()).unwrap_or(0);
+                para.bullet = ppr.find("buChar").is_some() || ppr.find("buAutoNum").is_some();
             }
 
             // Parse text runs
@@ -264,6 +392,7 @@ impl SlideParser {
                     run.bold = rpr.attr("b").map(|v| v == "1" || v == "true").unwrap_or(false);
                     run.italic = rpr.attr("i").map(|v| v == "1" || v == "true").unwrap_or(false);
                     run.underline = rpr.attr("u").is_some();
+                    run.no_proof = rpr.attr("noProof").is_some_and(|v| v == "1" || v == "true");
                     run.font_size = rpr.attr("sz").and_then(|v| v.parse().ok());
 
                     // Get color from solidFill/srgbClr
@@ -272,6 +401,13 @@ impl SlideParser {
                             run.color = srgb.attr("val").map(|s| s.to_string());
                         }
                     }
+                    run.font = rpr.find("latin")
+                        .and_then(|latin| latin.attr("typeface"))
+                        .map(|s| s.to_string());
+                    run.hyperlink = rpr.find("hlinkClick")
+                        .and_then(|h| h.attr("r:id"))
+                        .filter(|id| !id.is_empty())
+                        .map(|id| id.to_string());
                 }
 
                 para.runs.push(run);
@@ -285,6 +421,15 @@ impl SlideParser {
         paragraphs
     }
 
+    /// Replace run hyperlink relationship ids with their targets
+    pub(crate) fn resolve_hyperlinks(paragraphs: &mut [Paragraph], rels: &[PartRelationship]) {
+        for run in paragraphs.iter_mut().flat_map(|p| p.runs.iter_mut()) {
+            if let Some(ref r_id) = run.hyperlink {
+                run.hyperlink = rels.iter().find(|r| r.id == *r_id).map(|r| r.target.clone());
+            }
+        }
+    }
+
     fn is_title_shape(sp: &XmlElement) -> bool {
         // Check placeholder type first
         if let Some(nv_pr) = sp.find_descendant("nvPr") {
@@ -337,17 +482,27 @@ impl SlideParser {
         for tr in tbl.find_all("tr") {
             let mut row = Vec::new();
             for tc in tr.find_all("tc") {
-                let text = tc.find_descendant("t")
-                    .map(|t| t.text_content())
-                    .unwrap_or_default();
+                // Paragraphs of the cell, one per line
+                let text = tc.find_all_descendants("p")
+                    .into_iter()
+                    .map(|p| p.find_all_descendants("t").into_iter().map(|t| t.text_content()).collect::<String>())
+                    .collect::<Vec<_>>()
+                    .join("\n");
                 
                 let row_span = tc.attr("rowSpan").and_then(|v| v.parse().ok()).unwrap_or(1);
                 let col_span = tc.attr("gridSpan").and_then(|v| v.parse().ok()).unwrap_or(1);
 
+                let align = tc.find_descendant("p")
+                    .and_then(|p| p.find("pPr"))
+                    .and_then(|ppr| ppr.attr("algn"))
+                    .map(str::to_string);
+
                 row.push(ParsedTableCell {
                     text,
+                    paragraphs: tc.find("txBody").map(Self::parse_text_body).unwrap_or_default(),
                     row_span,
                     col_span,
+                    align,
                 });
             }
             if !row.is_empty() {
@@ -444,4 +599,39 @@ mod tests {
         assert!(run.italic);
         assert_eq!(run.font_size, Some(4400));
     }
+
+    #[test]
+    fn test_parse_pictures_and_code_runs() {
+        let xml = r#"<p:sld xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"
+               xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"
+               xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">
+            <p:cSld><p:spTree>
+                <p:sp>
+                    <p:nvSpPr><p:cNvPr id="2" name="Code Block"/><p:cNvSpPr txBox="1"/><p:nvPr/></p:nvSpPr>
+                    <p:txBody><a:p>
+                        <a:r><a:rPr><a:latin typeface="Consolas"/></a:rPr><a:t>fn</a:t></a:r>
+                        <a:r><a:rPr><a:latin typeface="Consolas"/></a:rPr><a:t> </a:t></a:r>
+                        <a:r><a:rPr><a:latin typeface="Consolas"/></a:rPr><a:t>main</a:t></a:r>
+                    </a:p></p:txBody>
+                </p:sp>
+                <p:pic>
+                    <p:nvPicPr><p:cNvPr id="3" name="photo.png" descr="Team photo"/><p:cNvPicPr/><p:nvPr/></p:nvPicPr>
+                    <p:blipFill><a:blip r:embed="rId2"/></p:blipFill>
+                    <p:spPr><a:xfrm><a:off x="10" y="20"/><a:ext cx="300" cy="400"/></a:xfrm></p:spPr>
+                </p:pic>
+            </p:spTree></p:cSld>
+        </p:sld>"#;
+
+        let slide = SlideParser::parse(xml).unwrap();
+        let shape = &slide.shapes[0];
+        assert!(shape.text_box);
+        assert_eq!(shape.role, ShapeRole::Other);
+        assert_eq!(shape.text(), "fn main");
+        assert_eq!(shape.paragraphs[0].runs[0].font.as_deref(), Some("Consolas"));
+
+        let picture = &slide.pictures[0];
+        assert_eq!(picture.description.as_deref(), Some("Team photo"));
+        assert_eq!(picture.target.as_deref(), Some("rId2"));
+        assert_eq!((picture.x, picture.y, picture.width, picture.height), (10, 20, 300, 400));
+    }
 }
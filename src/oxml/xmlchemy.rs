@@ -179,6 +179,12 @@ impl XmlParser {
                         current.text.push_str(&text);
                     }
                 }
+                // Whitespace-only text is content only in text runs (`a:t`)
+                Ok(XmlEvent::Whitespace(text)) => {
+                    if let Some(current) = stack.last_mut().filter(|e| e.local_name == "t") {
+                        current.text.push_str(&text);
+                    }
+                }
                 Err(e) => {
                     return Err(PptxError::XmlParse(e.to_string()));
                 }
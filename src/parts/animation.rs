@@ -394,16 +394,70 @@ impl SlideTransition {
         let auto_advance = self.advance_after_ms
             .map(|ms| format!(r#" advTm="{}""#, ms))
             .unwrap_or_default();
+        let speed = match self.duration_ms {
+            0..=500 => "fast",
+            501..=750 => "med",
+            _ => "slow",
+        };
+        let transition = |effect: &str, extra: &str| {
+            format!(r#"<p:transition spd="{speed}"{extra}{advance_attr}{auto_advance}>{effect}</p:transition>"#)
+        };
+
+        match self.p14_effect() {
+            // PowerPoint 2010 effects, with a fade for older readers
+            Some(effect) => format!(
+                r#"<mc:AlternateContent xmlns:mc="http://schemas.openxmlformats.org/markup-compatibility/2006"><mc:Choice xmlns:p14="http://schemas.microsoft.com/office/powerpoint/2010/main" Requires="p14">{}</mc:Choice><mc:Fallback>{}</mc:Fallback></mc:AlternateContent>"#,
+                transition(&effect, &format!(r#" p14:dur="{}""#, self.duration_ms)),
+                transition("<p:fade/>", "")
+            ),
+            None => transition(&self.effect_xml(), ""),
+        }
+    }
 
-        format!(
-            r#"<p:transition spd="med"{}{}>
-  <p:{} dir="{}"/>
-</p:transition>"#,
-            advance_attr,
-            auto_advance,
-            self.effect.as_str(),
-            self.direction.as_str()
-        )
+    /// Element of a PresentationML 2006 effect, with the direction it accepts
+    fn effect_xml(&self) -> String {
+        use AnimationDirection as D;
+        let side = match self.direction {
+            D::Up => Some("u"),
+            D::Down => Some("d"),
+            D::Left => Some("l"),
+            D::Right => Some("r"),
+            _ => None,
+        };
+        let in_out = matches!(self.direction, D::In | D::Out).then(|| self.direction.as_str());
+        let orientation = matches!(self.direction, D::Left | D::Right).then_some("vert");
+        let corner = match self.direction {
+            D::UpLeft => Some("lu"),
+            D::UpRight => Some("ru"),
+            D::DownLeft => Some("ld"),
+            D::DownRight => Some("rd"),
+            _ => None,
+        };
+        let dir = match self.effect {
+            TransitionEffect::Push | TransitionEffect::Wipe | TransitionEffect::Cover | TransitionEffect::Uncover => side,
+            TransitionEffect::Split | TransitionEffect::Zoom => in_out,
+            TransitionEffect::RandomBars | TransitionEffect::Blinds => orientation,
+            TransitionEffect::Strips => corner,
+            _ => None,
+        };
+        match dir {
+            Some(dir) => format!(r#"<p:{} dir="{dir}"/>"#, self.effect.as_str()),
+            None => format!("<p:{}/>", self.effect.as_str()),
+        }
+    }
+
+    /// Element of an effect added in PowerPoint 2010, if it is one
+    fn p14_effect(&self) -> Option<String> {
+        let element = match self.effect {
+            TransitionEffect::Cube => "prism",
+            TransitionEffect::Box => r#"prism isInverted="1""#,
+            TransitionEffect::Reveal | TransitionEffect::Flash | TransitionEffect::Ripple
+            | TransitionEffect::Honeycomb | TransitionEffect::Glitter | TransitionEffect::Vortex
+            | TransitionEffect::Shred | TransitionEffect::Switch | TransitionEffect::Flip
+            | TransitionEffect::Gallery | TransitionEffect::Doors => self.effect.as_str(),
+            _ => return None,
+        };
+        Some(format!("<p14:{element}/>"))
     }
 }
 
@@ -509,8 +563,21 @@ mod tests {
             .direction(AnimationDirection::Left)
             .duration(1000);
         let xml = trans.to_xml();
-        assert!(xml.contains("p:transition"));
-        assert!(xml.contains("p:wipe"));
+        assert!(xml.contains(r#"<p:transition spd="slow">"#));
+        assert!(xml.contains(r#"<p:wipe dir="l"/>"#));
+
+        // Effects without a direction get none
+        let fade = SlideTransition::new(TransitionEffect::Fade).to_xml();
+        assert!(fade.contains("<p:fade/>"));
+        assert!(SlideTransition::new(TransitionEffect::None).to_xml().is_empty());
+    }
+
+    #[test]
+    fn test_transition_p14_fallback() {
+        let xml = SlideTransition::new(TransitionEffect::Honeycomb).advance_after(3000).to_xml();
+        assert!(xml.contains(r#"Requires="p14""#));
+        assert!(xml.contains(r#"p14:dur="500" advTm="3000"><p14:honeycomb/>"#));
+        assert!(xml.contains(r#"<mc:Fallback><p:transition spd="fast" advTm="3000"><p:fade/>"#));
     }
 
     #[test]
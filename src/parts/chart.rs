@@ -4,7 +4,7 @@
 
 use super::base::{Part, PartType, ContentType};
 use crate::exc::PptxError;
-use crate::generator::charts::{Chart, generate_chart_xml};
+use crate::generator::charts::{Chart, generate_chart_part_xml};
 
 /// Chart part (ppt/charts/chartN.xml)
 #[derive(Debug, Clone)]
@@ -77,7 +77,7 @@ impl Part for ChartPart {
         }
 
         if let Some(ref chart) = self.chart {
-            return Ok(generate_chart_xml(chart, self.chart_number));
+            return Ok(generate_chart_part_xml(chart, None));
         }
 
         // Return minimal chart XML
@@ -55,133 +55,7 @@ impl VerticalAlign {
     }
 }
 
-/// Border style
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
-pub enum BorderStyle {
-    #[default]
-    Solid,
-    Dashed,
-    Dotted,
-    Double,
-    None,
-}
-
-impl BorderStyle {
-    pub fn as_str(&self) -> &'static str {
-        match self {
-            BorderStyle::Solid => "solid",
-            BorderStyle::Dashed => "dash",
-            BorderStyle::Dotted => "dot",
-            BorderStyle::Double => "dbl",
-            BorderStyle::None => "none",
-        }
-    }
-}
-
-/// Cell border
-#[derive(Debug, Clone, Default)]
-pub struct CellBorder {
-    pub width: i32,        // in EMU (12700 = 1pt)
-    pub color: String,
-    pub style: BorderStyle,
-}
-
-impl CellBorder {
-    pub fn new(width_pt: f32, color: impl Into<String>) -> Self {
-        CellBorder {
-            width: (width_pt * 12700.0) as i32,
-            color: color.into(),
-            style: BorderStyle::Solid,
-        }
-    }
-
-    pub fn style(mut self, style: BorderStyle) -> Self {
-        self.style = style;
-        self
-    }
-
-    pub fn to_xml(&self, tag: &str) -> String {
-        if self.style == BorderStyle::None {
-            return format!("<a:{}/>\n", tag);
-        }
-        format!(
-            r#"<a:{} w="{}" cap="flat" cmpd="sng" algn="ctr">
-              <a:solidFill><a:srgbClr val="{}"/></a:solidFill>
-              <a:prstDash val="{}"/>
-            </a:{}>"#,
-            tag,
-            self.width,
-            self.color.trim_start_matches('#'),
-            self.style.as_str(),
-            tag
-        )
-    }
-}
-
-/// Cell borders (all four sides)
-#[derive(Debug, Clone, Default)]
-pub struct CellBorders {
-    pub left: Option<CellBorder>,
-    pub right: Option<CellBorder>,
-    pub top: Option<CellBorder>,
-    pub bottom: Option<CellBorder>,
-}
-
-impl CellBorders {
-    pub fn all(border: CellBorder) -> Self {
-        CellBorders {
-            left: Some(border.clone()),
-            right: Some(border.clone()),
-            top: Some(border.clone()),
-            bottom: Some(border),
-        }
-    }
-
-    pub fn none() -> Self {
-        let no_border = CellBorder { width: 0, color: String::new(), style: BorderStyle::None };
-        CellBorders {
-            left: Some(no_border.clone()),
-            right: Some(no_border.clone()),
-            top: Some(no_border.clone()),
-            bottom: Some(no_border),
-        }
-    }
-
-    pub fn to_xml(&self) -> String {
-        let mut xml = String::new();
-        if let Some(ref b) = self.left { xml.push_str(&b.to_xml("lnL")); }
-        if let Some(ref b) = self.right { xml.push_str(&b.to_xml("lnR")); }
-        if let Some(ref b) = self.top { xml.push_str(&b.to_xml("lnT")); }
-        if let Some(ref b) = self.bottom { xml.push_str(&b.to_xml("lnB")); }
-        xml
-    }
-}
-
-/// Cell margins
-#[derive(Debug, Clone)]
-pub struct CellMargins {
-    pub left: i32,   // in EMU
-    pub right: i32,
-    pub top: i32,
-    pub bottom: i32,
-}
-
-impl Default for CellMargins {
-    fn default() -> Self {
-        CellMargins {
-            left: 91440,   // 0.1 inch
-            right: 91440,
-            top: 45720,    // 0.05 inch
-            bottom: 45720,
-        }
-    }
-}
-
-impl CellMargins {
-    pub fn uniform(margin: i32) -> Self {
-        CellMargins { left: margin, right: margin, top: margin, bottom: margin }
-    }
-}
+pub use crate::generator::table::{BorderStyle, CellBorder, CellBorders, CellMargins};
 
 /// Table cell with advanced formatting
 #[derive(Debug, Clone)]
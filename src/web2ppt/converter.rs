@@ -1,7 +1,10 @@
 //! Converter from web content to PowerPoint
 
 use super::{Web2PptError, Result, Web2PptConfig, WebContent, ContentType};
+use super::parser::ContentBlock;
 use crate::{create_pptx_with_content, SlideContent, SlideLayout};
+use crate::generator::{Hyperlink, TableBuilder, TableCell, TablePagination, TableRow};
+use crate::generator::constants::{CONTENT_WIDTH, CONTENT_X, CONTENT_Y_START};
 
 /// Safely truncate text at char boundary
 fn truncate_text(text: &str, max_len: usize) -> String {
@@ -25,6 +28,39 @@ fn truncate_text(text: &str, max_len: usize) -> String {
     format!("{}...", &text[..end].trim_end())
 }
 
+/// Add a bullet, clickable when the block carries a link
+fn add_block_bullet(slide: SlideContent, text: &str, block: &ContentBlock) -> SlideContent {
+    match &block.href {
+        Some(href) => slide.add_link_bullet(text, Hyperlink::url(href)),
+        None => slide.add_bullet(text),
+    }
+}
+
+/// Slide with a table of the rows, split over continuation slides when too tall
+fn table_slide(title: &str, rows: &[Vec<String>]) -> SlideContent {
+    let col_count = rows.iter().map(|r| r.len()).max().unwrap_or(1).max(1);
+    let col_width = CONTENT_WIDTH / col_count as u32;
+
+    let mut builder = TableBuilder::new(vec![col_width; col_count])
+        .position(CONTENT_X, CONTENT_Y_START)
+        .pagination(TablePagination::new().with_captions(true));
+    for (i, row) in rows.iter().enumerate() {
+        let cells = (0..col_count)
+            .map(|c| {
+                let cell = TableCell::new(&truncate_text(row.get(c).map(String::as_str).unwrap_or(""), 100)).font_size(14);
+                if i == 0 {
+                    cell.bold().background_color("4472C4").text_color("FFFFFF")
+                } else {
+                    cell
+                }
+            })
+            .collect();
+        builder = builder.add_row(TableRow::new(cells));
+    }
+
+    SlideContent::new(title).layout(SlideLayout::TitleAndContent).table(builder.build())
+}
+
 /// Options for conversion
 #[derive(Clone, Debug)]
 pub struct ConversionOptions {
@@ -175,12 +211,17 @@ impl Web2Ppt {
                     ContentType::Paragraph => {
                         // Truncate long paragraphs - use char_indices for safe slicing
                         let text = truncate_text(&block.text, 200);
-                        slide = slide.add_bullet(&text);
+                        slide = add_block_bullet(slide, &text, block);
                         bullet_count += 1;
                     }
                     ContentType::ListItem => {
                         let text = truncate_text(&block.text, 180);
-                        slide = slide.add_bullet(&format!("• {}", text));
+                        slide = add_block_bullet(slide, &format!("• {}", text), block);
+                        bullet_count += 1;
+                    }
+                    ContentType::Link { text, href } => {
+                        let text = truncate_text(text, 180);
+                        slide = slide.add_link_bullet(&text, Hyperlink::url(href));
                         bullet_count += 1;
                     }
                     ContentType::Quote => {
@@ -197,12 +238,14 @@ impl Web2Ppt {
                     }
                     ContentType::Table(rows) => {
                         if self.config.include_tables && !rows.is_empty() {
-                            let summary = format!("[Table: {} rows × {} cols]", 
-                                rows.len(), 
-                                rows.first().map(|r| r.len()).unwrap_or(0)
-                            );
-                            slide = slide.add_bullet(&summary);
-                            bullet_count += 1;
+                            // Tables get their own slide, after the bullets so far
+                            if bullet_count > 0 {
+                                slides.push(slide);
+                                slide = SlideContent::new(&format!("{} (cont.)", heading.text))
+                                    .layout(SlideLayout::TitleAndContent);
+                                bullet_count = 0;
+                            }
+                            slides.push(table_slide(&heading.text, rows));
                         }
                     }
                     ContentType::Image { alt, .. } => {
@@ -273,7 +316,7 @@ impl Web2Ppt {
                     if let Some(ref mut slide) = current_slide {
                         if bullet_count < self.config.max_bullets_per_slide {
                             let text = truncate_text(&block.text, 200);
-                            *slide = slide.clone().add_bullet(&text);
+                            *slide = add_block_bullet(slide.clone(), &text, block);
                             bullet_count += 1;
                         } else {
                             // Start new continuation slide
@@ -282,7 +325,7 @@ impl Web2Ppt {
                             *slide = SlideContent::new(&format!("{} (cont.)", title))
                                 .layout(SlideLayout::TitleAndContent);
                             let text = truncate_text(&block.text, 200);
-                            *slide = slide.clone().add_bullet(&text);
+                            *slide = add_block_bullet(slide.clone(), &text, block);
                             bullet_count = 1;
                         }
                     }
@@ -298,11 +341,20 @@ impl Web2Ppt {
                     if let Some(ref mut slide) = current_slide {
                         if bullet_count < self.config.max_bullets_per_slide {
                             let text = truncate_text(&block.text, 180);
-                            *slide = slide.clone().add_bullet(&format!("• {}", text));
+                            *slide = add_block_bullet(slide.clone(), &format!("• {}", text), block);
                             bullet_count += 1;
                         }
                     }
                 }
+                ContentType::Link { text, href } => {
+                    if bullet_count < self.config.max_bullets_per_slide
+                        && let Some(ref mut slide) = current_slide
+                    {
+                        let text = truncate_text(text, 180);
+                        *slide = slide.clone().add_link_bullet(&text, Hyperlink::url(href));
+                        bullet_count += 1;
+                    }
+                }
                 ContentType::Quote => {
                     if let Some(ref mut slide) = current_slide {
                         if bullet_count < self.config.max_bullets_per_slide {
@@ -312,6 +364,16 @@ impl Web2Ppt {
                         }
                     }
                 }
+                ContentType::Table(rows) if self.config.include_tables && !rows.is_empty() => {
+                    let title = current_slide.as_ref().map(|s| s.title.clone()).unwrap_or_else(|| "Data".to_string());
+                    if let Some(slide) = current_slide.take()
+                        && bullet_count > 0
+                    {
+                        slides.push(slide);
+                    }
+                    slides.push(table_slide(&title, rows));
+                    bullet_count = 0;
+                }
                 _ => {}
             }
         }
@@ -441,4 +503,46 @@ mod tests {
         assert_eq!(converter.config().max_slides, 5);
         assert_eq!(converter.config().max_bullets_per_slide, 3);
     }
+
+    #[test]
+    fn test_links_become_hyperlinks() {
+        let html = r#"
+            <html><body>
+                <h1>Main Title</h1>
+                <h2>Resources</h2>
+                <ul>
+                    <li><a href="https://docs.rs">Documentation</a> for the crate</li>
+                    <li>No link here</li>
+                </ul>
+                <div><a href="https://crates.io/crates/ppt-rs">Crate page on crates.io</a></div>
+            </body></html>
+        "#;
+        let content = super::super::WebParser::new().parse(html, "https://example.com").unwrap();
+        let slides = Web2Ppt::new().build_slides(&content, &ConversionOptions::new()).unwrap();
+        let slide = slides.iter().find(|s| s.title == "Resources").unwrap();
+
+        let links: Vec<_> = slide.hyperlinks().iter().map(|h| h.action.relationship_target()).collect();
+        assert_eq!(links, vec!["https://docs.rs", "https://crates.io/crates/ppt-rs"]);
+    }
+
+    #[test]
+    fn test_tables_become_paginated_slides() {
+        let rows: String = (0..60).map(|i| format!("<tr><td>Item {i}</td><td>{i}</td></tr>")).collect();
+        let html = format!(
+            r#"<html><body>
+                <h1>Main Title</h1>
+                <h2>Inventory</h2>
+                <p>The full inventory of items is listed in the following table.</p>
+                <table><tr><th>Name</th><th>Count</th></tr>{rows}</table>
+            </body></html>"#
+        );
+        let content = super::super::WebParser::new().parse(&html, "https://example.com").unwrap();
+        let slides = Web2Ppt::new().build_slides(&content, &ConversionOptions::new()).unwrap();
+
+        let slide = slides.iter().find(|s| s.table.is_some()).unwrap();
+        let table = slide.table.as_ref().unwrap();
+        assert_eq!(table.row_count(), 61);
+        assert!(table.pagination.as_ref().is_some_and(|p| p.captions));
+        assert!(slide.paginate_table(&crate::generator::TextMeasurer::new()).len() > 1);
+    }
 }
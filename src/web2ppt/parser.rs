@@ -35,6 +35,8 @@ pub struct ContentBlock {
     pub text: String,
     /// Nesting level (for lists)
     pub level: u8,
+    /// Target of the first link in the block, if any
+    pub href: Option<String>,
 }
 
 impl ContentBlock {
@@ -44,6 +46,7 @@ impl ContentBlock {
             content_type,
             text: text.trim().to_string(),
             level: 0,
+            href: None,
         }
     }
 
@@ -53,6 +56,12 @@ impl ContentBlock {
         self
     }
 
+    /// Set the link target
+    pub fn with_href(mut self, href: Option<String>) -> Self {
+        self.href = href;
+        self
+    }
+
     /// Check if this is a heading
     pub fn is_heading(&self) -> bool {
         matches!(self.content_type, ContentType::Title | ContentType::Heading(_))
@@ -314,13 +323,17 @@ impl WebParser {
                 let text = self.clean_text(element);
                 // Accept paragraphs with at least 10 chars
                 if text.len() >= 10 {
-                    content.blocks.push(ContentBlock::new(ContentType::Paragraph, &text));
+                    content.blocks.push(ContentBlock::new(ContentType::Paragraph, &text).with_href(self.first_link(element)));
                 }
             }
             "li" => {
                 let text = self.clean_text(element);
                 if !text.is_empty() && text.len() < 500 {
-                    content.blocks.push(ContentBlock::new(ContentType::ListItem, &text).with_level(depth));
+                    content.blocks.push(
+                        ContentBlock::new(ContentType::ListItem, &text)
+                            .with_level(depth)
+                            .with_href(self.first_link(element)),
+                    );
                 }
             }
             "blockquote" => {
@@ -360,14 +373,13 @@ impl WebParser {
                 return; // Don't recurse into tables
             }
             "a" => {
-                // Extract important links
-                if self.config.extract_links {
-                    if let Some(href) = element.value().attr("href") {
-                        let text = self.clean_text(element);
-                        if !text.is_empty() && text.len() > 5 && href.starts_with("http") {
-                            // Only add standalone links, not inline ones
-                            // This is handled by not recursing for links with substantial text
-                        }
+                // Standalone links; links inside paragraphs and list items are
+                // attached to those blocks instead
+                if let Some(href) = self.first_link(element) {
+                    let text = self.clean_text(element);
+                    if text.len() > 5 {
+                        content.blocks.push(ContentBlock::new(ContentType::Link { text: text.clone(), href }, &text));
+                        return;
                     }
                 }
             }
@@ -385,6 +397,23 @@ impl WebParser {
         }
     }
 
+    /// Find the first absolute link in (or on) an element, if link extraction is enabled
+    fn first_link(&self, element: &ElementRef) -> Option<String> {
+        if !self.config.extract_links {
+            return None;
+        }
+        let is_absolute = |href: &&str| href.starts_with("http://") || href.starts_with("https://") || href.starts_with("mailto:");
+        if let Some(href) = element.value().attr("href").filter(is_absolute) {
+            return Some(href.to_string());
+        }
+        let selector = Selector::parse("a[href]").ok()?;
+        element
+            .select(&selector)
+            .filter_map(|a| a.value().attr("href"))
+            .find(is_absolute)
+            .map(str::to_string)
+    }
+
     /// Clean and normalize text
     fn clean_text(&self, element: &ElementRef) -> String {
         let text: String = element.text().collect();
@@ -414,7 +443,7 @@ impl WebParser {
             }
         }
 
-        if !rows.is_empty() && rows.len() <= 30 {
+        if !rows.is_empty() {
             content.blocks.push(ContentBlock::new(
                 ContentType::Table(rows),
                 ""
@@ -7,7 +7,7 @@
 //! - Alignment with standards
 
 use ppt_rs::generator::{
-    SlideContent, create_pptx_with_content, create_pptx,
+    SlideContent, TableBuilder, TablePagination, create_pptx_with_content, create_pptx,
 };
 use ppt_rs::generator::constants::{
     SLIDE_WIDTH, SLIDE_HEIGHT, TITLE_X, TITLE_Y, TITLE_WIDTH, TITLE_HEIGHT,
@@ -234,6 +234,478 @@ fn test_create_and_validate_multiple_slides() {
     }
 }
 
+#[test]
+fn test_paginated_table_spreads_over_slides() {
+    let mut builder = TableBuilder::new(vec![4000000, 4000000])
+        .position(CONTENT_X, CONTENT_Y_START)
+        .pagination(TablePagination::new().with_captions(true))
+        .add_simple_row(vec!["Name", "Value"]);
+    for i in 0..60 {
+        builder = builder.add_simple_row(vec![&format!("Row {i}"), "x"]);
+    }
+    let slides = vec![SlideContent::new("Big Table").table(builder.build())];
+
+    let pptx_data = create_pptx_with_content("Pagination Test", slides).unwrap();
+    let mut archive = ZipArchive::new(Cursor::new(&pptx_data)).unwrap();
+    let slide_count = (1..)
+        .take_while(|i| archive.by_name(&format!("ppt/slides/slide{i}.xml")).is_ok())
+        .count();
+    assert!(slide_count > 1);
+
+    let mut last = String::new();
+    archive
+        .by_name(&format!("ppt/slides/slide{slide_count}.xml"))
+        .unwrap()
+        .read_to_string(&mut last)
+        .unwrap();
+    assert!(last.contains("Big Table (cont.)"));
+    assert!(last.contains("<a:t>Name</a:t>"));
+    assert!(last.contains(&format!("Page {slide_count} of {slide_count}")));
+    assert!(last.contains("<a:t>Row 59</a:t>"));
+}
+
+#[test]
+fn test_custom_table_style_is_written() {
+    use ppt_rs::generator::table::TableStyle;
+    use ppt_rs::prelude::themes;
+
+    let style = TableStyle::from_theme(&themes::NATURE);
+    let table = TableBuilder::new(vec![4000000, 4000000])
+        .add_simple_row(vec!["Name", "Value"])
+        .table_style(style.clone())
+        .build();
+    let slides = vec![SlideContent::new("Styled").table(table)];
+
+    let pptx_data = create_pptx_with_content("Style Test", slides).unwrap();
+    assert!(validate_pptx_structure(&pptx_data).is_ok());
+    let mut archive = ZipArchive::new(Cursor::new(&pptx_data)).unwrap();
+    let read = |archive: &mut ZipArchive<Cursor<&Vec<u8>>>, name: &str| {
+        let mut xml = String::new();
+        archive.by_name(name).unwrap().read_to_string(&mut xml).unwrap();
+        xml
+    };
+
+    assert!(read(&mut archive, "ppt/tableStyles.xml").contains(&format!(r#"styleId="{}" styleName="Nature Table""#, style.id)));
+    assert!(read(&mut archive, "ppt/slides/slide1.xml").contains(&format!("<a:tableStyleId>{}</a:tableStyleId>", style.id)));
+    assert!(read(&mut archive, "ppt/_rels/presentation.xml.rels").contains(r#"Target="tableStyles.xml""#));
+    assert!(read(&mut archive, "[Content_Types].xml").contains("/ppt/tableStyles.xml"));
+
+    // Any built-in id is accepted, and ids that are not GUIDs are rejected
+    let table = TableBuilder::new(vec![4000000]).add_simple_row(vec!["A"]).style("{F2DE63D5-997A-4646-A377-4702673A728D}").build();
+    assert!(create_pptx_with_content("Style Test", vec![SlideContent::new("Light 3").table(table)]).is_ok());
+    let table = TableBuilder::new(vec![4000000]).add_simple_row(vec!["A"]).style("Medium Style 2").build();
+    let error = create_pptx_with_content("Style Test", vec![SlideContent::new("Unknown").table(table)]).unwrap_err();
+    assert!(error.to_string().contains("is not a {GUID}"));
+}
+
+#[test]
+fn test_slide_and_master_backgrounds() {
+    use ppt_rs::generator::{create_pptx_with_options, Background, GradientFill, Image, PatternType, PresentationOptions};
+
+    let png = "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNk+M9QDwADhgGAWjR9awAAAABJRU5ErkJggg==";
+    let slides = vec![
+        SlideContent::new("Photo").background(Background::picture(Image::from_base64(png, 100, 100, "PNG"))),
+        SlideContent::new("Gradient")
+            .background(Background::gradient(GradientFill::two_color("1F4E79", "2E75B6")))
+            .hide_background_graphics(),
+        SlideContent::new("Master"),
+    ];
+    let options = PresentationOptions::new()
+        .background(Background::pattern(PatternType::SmallGrid, "D9D9D9", "FFFFFF"));
+
+    let pptx_data = create_pptx_with_options("Background Test", slides, &options).unwrap();
+    assert!(validate_pptx_structure(&pptx_data).is_ok());
+    let mut archive = ZipArchive::new(Cursor::new(&pptx_data)).unwrap();
+    let read = |archive: &mut ZipArchive<Cursor<&Vec<u8>>>, name: &str| {
+        let mut xml = String::new();
+        archive.by_name(name).unwrap().read_to_string(&mut xml).unwrap();
+        xml
+    };
+
+    let slide1 = read(&mut archive, "ppt/slides/slide1.xml");
+    assert!(slide1.contains(r#"<p:bgPr><a:blipFill dpi="0" rotWithShape="1"><a:blip r:embed="rId2"/>"#));
+    assert!(read(&mut archive, "ppt/slides/_rels/slide1.xml.rels").contains(r#"Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/image" Target="../media/background1.png""#));
+    assert!(archive.by_name("ppt/media/background1.png").is_ok());
+    assert!(read(&mut archive, "[Content_Types].xml").contains(r#"<Default Extension="png" ContentType="image/png"/>"#));
+
+    let slide2 = read(&mut archive, "ppt/slides/slide2.xml");
+    assert!(slide2.starts_with(r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<p:sld showMasterSp="0" "#));
+    assert!(slide2.contains("<p:bg>\n<p:bgPr><a:gradFill"));
+
+    // Slides without a background inherit the master's
+    assert!(read(&mut archive, "ppt/slides/slide3.xml").contains(r#"<p:bgRef idx="1001">"#));
+    assert!(read(&mut archive, "ppt/slideMasters/slideMaster1.xml").contains(r#"<a:pattFill prst="smGrid">"#));
+}
+
+#[test]
+fn test_header_footer_placeholders() {
+    use ppt_rs::generator::{create_pptx_with_options, DateFormat, HeaderFooter, PresentationOptions, SlideLayout};
+
+    let slides = vec![
+        SlideContent::new("Welcome").layout(SlideLayout::CenteredTitle),
+        SlideContent::new("Agenda").add_bullet("Intro"),
+    ];
+    let options = PresentationOptions::new().header_footer(
+        HeaderFooter::new().footer("Confidential").slide_numbers().auto_date(DateFormat::Short).hide_on_title_slide(),
+    );
+
+    let pptx_data = create_pptx_with_options("Footer Test", slides, &options).unwrap();
+    assert!(validate_pptx_structure(&pptx_data).is_ok());
+    let mut archive = ZipArchive::new(Cursor::new(&pptx_data)).unwrap();
+    let read = |archive: &mut ZipArchive<Cursor<&Vec<u8>>>, name: &str| {
+        let mut xml = String::new();
+        archive.by_name(name).unwrap().read_to_string(&mut xml).unwrap();
+        xml
+    };
+
+    assert!(!read(&mut archive, "ppt/slides/slide1.xml").contains("<p:ph type=\"ftr\""));
+    let slide2 = read(&mut archive, "ppt/slides/slide2.xml");
+    assert!(slide2.contains(r#"<p:ph type="ftr" sz="quarter" idx="11"/>"#));
+    assert!(slide2.contains("<a:t>Confidential</a:t>"));
+    assert!(slide2.contains(r#"type="slidenum"><a:rPr lang="en-US"/><a:t>2</a:t></a:fld>"#));
+    assert!(slide2.contains(r#"type="datetime1""#));
+
+    let master = read(&mut archive, "ppt/slideMasters/slideMaster1.xml");
+    assert!(master.contains(r#"<p:ph type="dt" sz="half" idx="2"/>"#));
+    assert!(master.contains(r#"<p:hf hdr="0" ftr="1" dt="1" sldNum="1"/>"#));
+    assert!(read(&mut archive, "ppt/slideLayouts/slideLayout1.xml").contains(r#"<p:ph type="sldNum" sz="quarter" idx="12"/>"#));
+}
+
+#[test]
+fn test_mermaid_charts_are_embedded() {
+    use ppt_rs::cli::markdown::parse;
+    use ppt_rs::generator::ChartType;
+    use ppt_rs::oxml::PresentationReader;
+
+    let markdown = "# Pets\n\n```mermaid\npie title Pets adopted\n    \"Dogs\" : 386\n    \"Cats\" : 85\n```\n\n\
+# Plan\n\n```mermaid\ngantt\n    dateFormat YYYY-MM-DD\n    section Build\n    Code : code, 2024-01-01, 10d\n    Test : after code, 3d\n```\n";
+    let slides = parse(markdown).unwrap();
+    let pptx_data = create_pptx_with_content("Charts", slides).unwrap();
+    assert!(validate_pptx_structure(&pptx_data).is_ok());
+
+    let mut archive = ZipArchive::new(Cursor::new(&pptx_data)).unwrap();
+    let mut content_types = String::new();
+    archive.by_name("[Content_Types].xml").unwrap().read_to_string(&mut content_types).unwrap();
+    assert!(content_types.contains(r#"<Override PartName="/ppt/charts/chart1_1.xml""#));
+    assert!(content_types.contains(r#"<Default Extension="xlsx""#));
+    assert!(archive.by_name("ppt/embeddings/Microsoft_Excel_Worksheet2_1.xlsx").is_ok());
+
+    let path = std::env::temp_dir().join("ppt_rs_mermaid_charts.pptx");
+    fs::write(&path, &pptx_data).unwrap();
+    let reader = PresentationReader::open(path.to_str().unwrap()).unwrap();
+
+    let pie = &reader.get_slide_charts(0).unwrap()[0];
+    assert_eq!(pie.chart_type, ChartType::Pie);
+    assert_eq!(pie.categories, vec!["Dogs", "Cats"]);
+    assert_eq!(pie.series[0].values, vec![386.0, 85.0]);
+
+    let timeline = &reader.get_slide_charts(1).unwrap()[0];
+    assert_eq!(timeline.chart_type, ChartType::BarHorizontalStacked);
+    assert_eq!(timeline.categories, vec!["Code", "Test"]);
+    assert_eq!(timeline.series[0].values, vec![45292.0, 45302.0]);
+    assert_eq!(timeline.series[1].values, vec![10.0, 3.0]);
+    fs::remove_file(path).ok();
+}
+
+#[test]
+fn test_mermaid_extra_diagram_types() {
+    use ppt_rs::cli::markdown::parse_with_diagnostics;
+    use ppt_rs::generator::ChartType;
+    use ppt_rs::oxml::PresentationReader;
+
+    let markdown = "# Sales\n\n```mermaid\nxychart-beta\n    x-axis [q1, q2, q3]\n    y-axis 0 --> 100\n    bar [40, 60, 75]\n    line [35, 55, 80]\n```\n\n\
+# Context\n\n```mermaid\nC4Context\n    Person(user, \"User\")\n    System_Boundary(b, \"Shop\") {\n        System(web, \"Web shop\")\n    }\n    Rel(user, web, \"Buys\")\n```\n\n\
+# Energy\n\n```mermaid\nsankey-beta\nCoal,Power,40\nGas,Power,20\nPower,Homes,45\n```\n\n\
+# Blocks\n\n```mermaid\nblock-beta\n    columns 2\n    a b\n    a --> b\n```\n\n\
+# Requirements\n\n```mermaid\nrequirementDiagram\n    requirement r {\n        id: 1\n    }\n    element e {\n        type: code\n    }\n    e - satisfies -> r\n```\n";
+    let (slides, diagnostics) = parse_with_diagnostics(markdown).unwrap();
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+    for slide in &slides[1..] {
+        assert!(!slide.shapes.is_empty() && !slide.connectors.is_empty(), "{}", slide.title);
+    }
+
+    let pptx_data = create_pptx_with_content("Diagrams", slides).unwrap();
+    assert!(validate_pptx_structure(&pptx_data).is_ok());
+    let path = std::env::temp_dir().join("ppt_rs_mermaid_extra.pptx");
+    fs::write(&path, &pptx_data).unwrap();
+    let reader = PresentationReader::open(path.to_str().unwrap()).unwrap();
+    let chart = &reader.get_slide_charts(0).unwrap()[0];
+    assert_eq!(chart.chart_type, ChartType::Combo);
+    assert_eq!(chart.categories, vec!["q1", "q2", "q3"]);
+    assert_eq!(chart.series[1].values, vec![35.0, 55.0, 80.0]);
+    fs::remove_file(path).ok();
+}
+
+#[test]
+fn test_md2ppt_strict_mermaid_diagnostics() {
+    use ppt_rs::cli::FromMarkdownCommand;
+
+    let dir = std::env::temp_dir();
+    let input = dir.join("ppt_rs_mermaid_strict.md");
+    let output = dir.join("ppt_rs_mermaid_strict.pptx");
+    fs::write(&input, "# Flow\n\n```mermaid\nflowchart LR\n    A --> B\n    A -- maybe --> C\n```\n").unwrap();
+    fs::remove_file(&output).ok();
+    let (input_path, output_path) = (input.to_str().unwrap(), output.to_str().unwrap());
+
+    let error = FromMarkdownCommand::execute(input_path, output_path, None, true).unwrap_err();
+    assert!(error.contains(&format!("{input_path}:6:5: invalid node `A -- maybe`")), "{error}");
+    assert!(!output.exists());
+
+    // Without --strict the problems are only warnings
+    FromMarkdownCommand::execute(input_path, output_path, None, false).unwrap();
+    assert!(validate_pptx_structure(&fs::read(&output).unwrap()).is_ok());
+    fs::remove_file(input).ok();
+    fs::remove_file(output).ok();
+}
+
+#[test]
+fn test_md2ppt_front_matter() {
+    use ppt_rs::cli::FromMarkdownCommand;
+
+    let dir = std::env::temp_dir();
+    let input = dir.join("ppt_rs_front_matter.md");
+    let output = dir.join("ppt_rs_front_matter.pptx");
+    fs::write(&input, "---\ntitle: Roadmap\nauthor: Product Team\naspect: 16:9\ntransition: push\nslide_numbers: true\n---\n\
+# Now\n- Shipping\n\n# Next\n<!-- transition: none -->\n<!-- background: #1E1E1E -->\n- Planning\n").unwrap();
+    let (input_path, output_path) = (input.to_str().unwrap(), output.to_str().unwrap());
+    FromMarkdownCommand::execute(input_path, output_path, None, false).unwrap();
+
+    let pptx_data = fs::read(&output).unwrap();
+    assert!(validate_pptx_structure(&pptx_data).is_ok());
+    let mut archive = ZipArchive::new(Cursor::new(&pptx_data)).unwrap();
+    let read = |archive: &mut ZipArchive<Cursor<&Vec<u8>>>, name: &str| {
+        let mut xml = String::new();
+        archive.by_name(name).unwrap().read_to_string(&mut xml).unwrap();
+        xml
+    };
+
+    assert!(read(&mut archive, "ppt/presentation.xml").contains(r#"<p:sldSz cx="12192000" cy="6858000"/>"#));
+    let core = read(&mut archive, "docProps/core.xml");
+    assert!(core.contains("<dc:title>Roadmap</dc:title>"));
+    assert!(core.contains("<dc:creator>Product Team</dc:creator>"));
+
+    let slide1 = read(&mut archive, "ppt/slides/slide1.xml");
+    assert!(slide1.contains(r#"<p:transition spd="fast"><p:push/></p:transition>"#));
+    // The 4:3 layout is centred on the 16:9 slide without stretching
+    assert!(slide1.contains(r#"<a:off x="1981200" y="274638"/>"#));
+    assert!(slide1.contains(r#"<a:ext cx="8230200" cy="1143000"/>"#));
+    assert!(!read(&mut archive, "ppt/slideMasters/slideMaster1.xml").contains(r#"<a:off x="457200" "#));
+    assert!(slide1.contains(r#"type="slidenum""#));
+    let slide2 = read(&mut archive, "ppt/slides/slide2.xml");
+    assert!(!slide2.contains("<p:transition"));
+    assert!(slide2.contains(r#"<a:srgbClr val="1E1E1E"/>"#));
+    fs::remove_file(input).ok();
+    fs::remove_file(output).ok();
+}
+
+#[test]
+fn test_md2ppt_template() {
+    use ppt_rs::cli::FromMarkdownCommand;
+    use ppt_rs::generator::{create_pptx_with_options, Background, PresentationOptions};
+
+    // A 16:10 template whose master has a dark background
+    let dir = std::env::temp_dir();
+    let template = dir.join("ppt_rs_corporate_template.pptx");
+    let options = PresentationOptions::new().slide_size(12_192_000, 7_620_000).background(Background::solid("102030"));
+    fs::write(&template, create_pptx_with_options("Corporate", vec![SlideContent::new("Cover")], &options).unwrap()).unwrap();
+
+    let input = dir.join("ppt_rs_template.md");
+    let output = dir.join("ppt_rs_template.pptx");
+    fs::write(&input, "---\ntemplate: ppt_rs_corporate_template.pptx\n---\n# Agenda\n- Results\n").unwrap();
+    FromMarkdownCommand::execute(input.to_str().unwrap(), output.to_str().unwrap(), None, false).unwrap();
+
+    let pptx_data = fs::read(&output).unwrap();
+    assert!(validate_pptx_structure(&pptx_data).is_ok());
+    let mut archive = ZipArchive::new(Cursor::new(&pptx_data)).unwrap();
+    let mut read = |name: &str| {
+        let mut xml = String::new();
+        archive.by_name(name).unwrap().read_to_string(&mut xml).unwrap();
+        xml
+    };
+    assert!(read("ppt/presentation.xml").contains(r#"<p:sldSz cx="12192000" cy="7620000"/>"#));
+    assert!(read("ppt/slideMasters/slideMaster1.xml").contains(r#"<a:srgbClr val="102030"/>"#));
+    assert!(read("ppt/slides/_rels/slide1.xml.rels").contains("../slideLayouts/slideLayout1.xml"));
+    fs::remove_file(template).ok();
+    fs::remove_file(input).ok();
+    fs::remove_file(output).ok();
+}
+
+#[test]
+fn test_md2ppt_columns_and_side_image() {
+    use ppt_rs::cli::FromMarkdownCommand;
+
+    let dir = std::env::temp_dir().join("ppt_rs_columns");
+    fs::create_dir_all(&dir).unwrap();
+    let (input, output) = (dir.join("deck.md"), dir.join("deck.pptx"));
+    // A wide picture, found next to the Markdown file
+    image::RgbImage::new(40, 10).save(dir.join("wide.png")).unwrap();
+    fs::write(&input, "# Build vs Buy\n:::columns\n:::column\n## Build\n- Control\n:::\n:::column\n## Buy\n- Speed\n:::\n:::\n\n\
+# Team\n![bg right](wide.png)\n- Ten engineers\n").unwrap();
+    FromMarkdownCommand::execute(input.to_str().unwrap(), output.to_str().unwrap(), None, false).unwrap();
+
+    let pptx_data = fs::read(&output).unwrap();
+    assert!(validate_pptx_structure(&pptx_data).is_ok());
+    let mut archive = ZipArchive::new(Cursor::new(&pptx_data)).unwrap();
+    let mut slide1 = String::new();
+    archive.by_name("ppt/slides/slide1.xml").unwrap().read_to_string(&mut slide1).unwrap();
+    assert!(slide1.contains("Column Heading 2") && slide1.contains("<a:t>Buy</a:t>"));
+    assert!(!slide1.contains("**Buy**"));
+
+    let mut slide2 = String::new();
+    archive.by_name("ppt/slides/slide2.xml").unwrap().read_to_string(&mut slide2).unwrap();
+    assert!(slide2.contains("<a:srcRect"));
+    assert!(archive.file_names().any(|name| name.starts_with("ppt/media/")));
+    fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn test_ppt2md_round_trip() {
+    use ppt_rs::cli::{parse_markdown, FromMarkdownCommand, ToMarkdownCommand};
+
+    let dir = std::env::temp_dir().join("ppt_rs_ppt2md");
+    fs::create_dir_all(&dir).unwrap();
+    let (input, pptx, output) = (dir.join("deck.md"), dir.join("deck.pptx"), dir.join("export/deck.md"));
+    image::RgbImage::new(10, 20).save(dir.join("tall.png")).unwrap();
+    // On a 16:9 slide, so side pictures are found at the real slide size
+    let markdown = "---\naspect: 16:9\n---\n# Intro\n- Use **bold**, *italic* and `code`\n- See [docs](https://docs.rs)\n\n> Say hello\n\n\
+# Plan\n:::columns\n:::column\n## Now\n- Ship\n:::\n:::column\n## Next\n- Plan\n:::\n:::\n\n\
+# Data\n| Name | Value |\n|---|---|\n| **A** | [1](https://docs.rs) |\n\n# Code\n```rust showLineNumbers\nfn main() {}\n```\n\n# Team\n![bg left](tall.png)\n- Ten engineers\n";
+    fs::write(&input, markdown).unwrap();
+    FromMarkdownCommand::execute(input.to_str().unwrap(), pptx.to_str().unwrap(), None, false).unwrap();
+
+    let slides = ToMarkdownCommand::execute(pptx.to_str().unwrap(), output.to_str().unwrap(), None).unwrap();
+    assert_eq!(slides, 5);
+    let exported = fs::read_to_string(&output).unwrap();
+    assert!(exported.contains("- Use **bold**, *italic* and `code`\n- See [docs](https://docs.rs)\n\n> Say hello"));
+    assert!(exported.contains(":::columns\n:::column\n## Now\n\n- Ship\n:::\n:::column\n## Next"));
+    assert!(exported.contains("| Name | Value |\n| :---: | :---: |\n| **A** | [1](https://docs.rs) |"));
+    assert!(exported.contains("```rust showLineNumbers\nfn main() {}\n```"));
+    let link = exported.split("![bg left](").nth(1).and_then(|rest| rest.split(')').next()).unwrap();
+    assert!(link.starts_with("deck_images/") && dir.join("export").join(link).is_file());
+
+    // Reading the export back gives the same slides
+    let original = parse_markdown(markdown).unwrap();
+    let round_trip = parse_markdown(&exported).unwrap();
+    assert_eq!(round_trip.len(), original.len());
+    for (a, b) in original.iter().zip(&round_trip) {
+        assert_eq!((&a.title, &a.content, a.layout), (&b.title, &b.content, b.layout));
+        assert_eq!(a.notes, b.notes);
+    }
+    fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn test_ppt2md_nested_lists_and_aligned_tables() {
+    use ppt_rs::cli::{FromMarkdownCommand, ToMarkdownCommand};
+    use ppt_rs::generator::{CellAlign, TableCell, TableRow};
+    use ppt_rs::oxml::PresentationReader;
+
+    let dir = std::env::temp_dir().join("ppt_rs_ppt2md_nested");
+    fs::create_dir_all(&dir).unwrap();
+    let (original, markdown, round_trip) = (dir.join("original.pptx"), dir.join("deck.md"), dir.join("round_trip.pptx"));
+    let row = |cells: [&str; 3]| TableRow::new(
+        cells.iter().zip([CellAlign::Left, CellAlign::Center, CellAlign::Right])
+            .map(|(text, align)| TableCell::new(text).align(align))
+            .collect(),
+    );
+    let table = TableBuilder::new(vec![2000000; 3]).add_row(row(["Name", "Count", "Total"])).add_row(row(["A", "1", "10"])).build();
+    let slides = vec![
+        SlideContent::new("Plan").add_bullet("Goals").add_sub_bullet("Ship").add_bullet("Risks").add_sub_bullet("Scope"),
+        SlideContent::new("Data").table(table),
+    ];
+    fs::write(&original, create_pptx_with_content("Round Trip", slides).unwrap()).unwrap();
+
+    ToMarkdownCommand::execute(original.to_str().unwrap(), markdown.to_str().unwrap(), None).unwrap();
+    let exported = fs::read_to_string(&markdown).unwrap();
+    assert!(exported.contains("- Goals\n  - Ship\n- Risks\n  - Scope"));
+    assert!(exported.contains("| Name | Count | Total |\n| :--- | :---: | ---: |\n| A | 1 | 10 |"));
+    FromMarkdownCommand::execute(markdown.to_str().unwrap(), round_trip.to_str().unwrap(), None, false).unwrap();
+
+    // Bullet levels and cell alignments survive pptx -> md -> pptx
+    let read = |path: &std::path::Path| {
+        let reader = PresentationReader::open(path.to_str().unwrap()).unwrap();
+        let plan = reader.get_slide(0).unwrap();
+        let bullets: Vec<(String, u32)> = plan.shapes.iter()
+            .flat_map(|shape| &shape.paragraphs)
+            .filter(|p| p.bullet)
+            .map(|p| (p.runs.iter().map(|run| run.text.as_str()).collect(), p.level))
+            .collect();
+        let data = reader.get_slide(1).unwrap();
+        let cells: Vec<(String, Option<String>)> = data.tables[0].rows.iter().flatten()
+            .map(|cell| (cell.text.clone(), cell.align.clone()))
+            .collect();
+        (bullets, cells)
+    };
+    let (bullets, cells) = read(&original);
+    assert_eq!(bullets.iter().map(|(_, level)| *level).collect::<Vec<_>>(), [0, 1, 0, 1]);
+    assert_eq!(read(&round_trip), (bullets, cells));
+    fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn test_md2ppt_equations() {
+    use ppt_rs::cli::{FromMarkdownCommand, ToMarkdownCommand};
+
+    let dir = std::env::temp_dir().join("ppt_rs_equations");
+    fs::create_dir_all(&dir).unwrap();
+    let (input, output, exported) = (dir.join("deck.md"), dir.join("deck.pptx"), dir.join("export/deck.md"));
+    let markdown = "# Calculus\n- Area under $f$ from $a$ to $b$\n\n$$\n\\int_a^b f(x)\\,dx = F(b) - F(a)\n$$\n\n\
+$$\\sum_{k=0}^{n} \\binom{n}{k} = 2^n$$\n\n- Then simplify\n";
+    fs::write(&input, markdown).unwrap();
+    FromMarkdownCommand::execute(input.to_str().unwrap(), output.to_str().unwrap(), None, false).unwrap();
+
+    let pptx_data = fs::read(&output).unwrap();
+    assert!(validate_pptx_structure(&pptx_data).is_ok());
+    let mut archive = ZipArchive::new(Cursor::new(&pptx_data)).unwrap();
+    let mut slide1 = String::new();
+    archive.by_name("ppt/slides/slide1.xml").unwrap().read_to_string(&mut slide1).unwrap();
+    assert_eq!(slide1.matches("<a14:m>").count(), 5);
+    assert!(slide1.contains("<m:f>") && slide1.contains("<m:nary>") && slide1.contains(r#"<m:type m:val="noBar"/>"#));
+    assert!(slide1.contains("<mc:Fallback>"));
+
+    // Inline math sits in its bullet, and display math between the bullets
+    let choice = &slide1[..slide1.find("<mc:Fallback>").unwrap()];
+    let order: Vec<usize> = ["Area under", "<m:oMath>", " from ", "<m:oMathPara>", "<m:nary>", "Then simplify"].iter()
+        .map(|text| choice.find(text).unwrap())
+        .collect();
+    assert!(order.is_sorted());
+
+    // ppt2md reads the TeX back in place
+    ToMarkdownCommand::execute(output.to_str().unwrap(), exported.to_str().unwrap(), None).unwrap();
+    let exported = fs::read_to_string(&exported).unwrap();
+    assert!(exported.contains("- Area under $f$ from $a$ to $b$\n\n$$\n\\int_a^b f(x)\\,dx = F(b) - F(a)\n$$\n\n\
+$$\n\\sum_{k=0}^{n} \\binom{n}{k} = 2^n\n$$\n\n- Then simplify"), "{exported}");
+    fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn test_md2ppt_code_blocks() {
+    use ppt_rs::cli::FromMarkdownCommand;
+
+    let dir = std::env::temp_dir().join("ppt_rs_code_blocks");
+    fs::create_dir_all(&dir).unwrap();
+    let (input, output) = (dir.join("deck.md"), dir.join("deck.pptx"));
+    let listing: Vec<String> = (1..=40).map(|i| format!("let x{i} = {i};")).collect();
+    fs::write(&input, format!("---\ncode_theme: InspiredGitHub\n---\n# Listing\n\n```rust {{39}} showLineNumbers\n{}\n```\n", listing.join("\n"))).unwrap();
+    FromMarkdownCommand::execute(input.to_str().unwrap(), output.to_str().unwrap(), None, false).unwrap();
+
+    let pptx_data = fs::read(&output).unwrap();
+    assert!(validate_pptx_structure(&pptx_data).is_ok());
+    let mut archive = ZipArchive::new(Cursor::new(&pptx_data)).unwrap();
+    let mut slides = Vec::new();
+    for name in ["ppt/slides/slide1.xml", "ppt/slides/slide2.xml"] {
+        let mut xml = String::new();
+        archive.by_name(name).unwrap().read_to_string(&mut xml).unwrap();
+        slides.push(xml);
+    }
+    assert!(slides[0].contains(r#"<a:srgbClr val="FFFFFF"/></a:solidFill>"#));
+    assert!(slides[0].contains("<a:t> 1 </a:t>") && !slides[0].contains("<a:t>40 </a:t>"));
+    assert!(slides[1].contains("Listing (cont.)") && slides[1].contains("<a:t>40 </a:t>"));
+    assert!(slides[1].contains("<a:highlight>"));
+    fs::remove_dir_all(dir).ok();
+}
+
 #[test]
 fn test_create_empty_presentation() {
     let result = create_pptx("Empty Presentation", 0);